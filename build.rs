@@ -3,7 +3,35 @@ use std::{
     io::{self, Write},
 };
 
+/// Every custom cfg that `collect_uds_features` might define, declared upfront so that the compiler doesn't flag them as unexpected.
+static UDS_CFGS: &[&str] = &[
+    "uds_supported",
+    "uds_sun_len",
+    "uds_scm_rights",
+    "uds_scm_credentials",
+    "uds_scm_security",
+    "uds_scm_timestamp",
+    "uds_scm_timestampns",
+    "uds_ucred",
+    "uds_sockcred",
+    "uds_peerucred",
+    "uds_getpeerucred",
+    "uds_unpcbid",
+    "uds_xucred",
+    "uds_peereid",
+    "uds_linux_namespace",
+    "uds_msg_nosignal",
+    "uds_so_nosigpipe",
+    "uds_msghdr_iovlen_c_int",
+    "uds_msghdr_iovlen_size_t",
+    "uds_msghdr_controllen_socklen_t",
+    "uds_msghdr_controllen_size_t",
+    "uds_cmsghdr_len_socklen_t",
+    "uds_cmsghdr_len_size_t",
+];
+
 fn main() {
+    declare_check_cfgs(UDS_CFGS);
     if is_unix() {
         let target = TargetTriplet::fetch();
         collect_uds_features(&target);
@@ -20,6 +48,9 @@ fn is_unix() -> bool {
 /// - Ancillary data support:
 ///     - `uds_scm_rights` ("passfd")
 ///     - `uds_scm_credentials` ("passcred")
+///     - `uds_scm_security` (`SO_PASSSEC`), Linux-only
+///     - `uds_scm_timestamp` (`SO_TIMESTAMP`)
+///     - `uds_scm_timestampns` (`SO_TIMESTAMPNS`), Linux-only
 /// - Credential ancillary message structure flavor:
 ///     - `uds_ucred`
 ///     - `uds_sockcred`
@@ -37,6 +68,9 @@ fn is_unix() -> bool {
 /// - `cmsghdr`'s `cmsg_len` type:
 ///     - `uds_cmsghdr_len_socklen_t`
 ///     - `uds_cmsghdr_len_size_t`, on Linux with GNU, AIX, Android, uClibc MIPS64, and uClibc x86-64
+/// - Suppression of `SIGPIPE` when sending to a disconnected peer:
+///     - `uds_msg_nosignal`, the `MSG_NOSIGNAL` flag for `send`-family calls
+///     - `uds_so_nosigpipe`, the `SO_NOSIGPIPE` socket option, on Apple platforms
 #[rustfmt::skip]
 fn collect_uds_features(target: &TargetTriplet) {
     let (mut uds, mut scm_rights, mut size_t_madness) = (false, true, false);
@@ -54,7 +88,13 @@ fn collect_uds_features(target: &TargetTriplet) {
         }
         if target.os_any(&["linux", "android"]) {
             // Only actual Linux has that... I think? lmao
-            define("uds_linux_namespace");
+            ldefine(&["uds_linux_namespace", "uds_scm_security", "uds_scm_timestamp"]);
+        }
+        if target.os("linux") {
+            define("uds_scm_timestampns");
+        }
+        if !target.os("redox") {
+            define("uds_msg_nosignal");
         }
     } else if target.os_any(&["aix", "nto"]) || (target.env("newlib") && target.arch("xtensa")) {
         uds = true;
@@ -69,6 +109,7 @@ fn collect_uds_features(target: &TargetTriplet) {
         ldefine(&[
             "uds_peereid",
             "uds_sun_len",
+            "uds_scm_timestamp",
         ]);
         // FIXME sockcred platforms are really fucked, like actually messed up in the head. They make my brain hurt
         // in one of the worst ways imaginable. They disgust me beyond human belief. Just read the fucking FreeBSD
@@ -80,6 +121,11 @@ fn collect_uds_features(target: &TargetTriplet) {
         // if !target.os_any(&["macos", "ios"]) {
         //     define("uds_sockcred");
         // }
+        if target.os_any(&["macos", "ios"]) {
+            define("uds_so_nosigpipe");
+        } else {
+            define("uds_msg_nosignal");
+        }
         if target.os("netbsd") {
             define("uds_unpcbid");
         } else {
@@ -87,7 +133,7 @@ fn collect_uds_features(target: &TargetTriplet) {
         }
     } else if target.os_any(&["solaris", "illumos"]) {
         uds = true;
-        define("uds_getpeerucred");
+        ldefine(&["uds_getpeerucred", "uds_scm_timestamp", "uds_msg_nosignal"]);
     } else if target.os("haiku") {
         uds = true;
         ldefine(&["uds_ucred", "uds_peerucred"]);
@@ -110,6 +156,15 @@ fn collect_uds_features(target: &TargetTriplet) {
     }
 }
 
+fn declare_check_cfgs(cfgs: &[&str]) {
+    let stdout_ = io::stdout();
+    let mut stdout = stdout_.lock();
+    for i in cfgs {
+        stdout.write_all(b"cargo:rustc-check-cfg=cfg(").unwrap();
+        stdout.write_all(i.as_ref()).unwrap();
+        stdout.write_all(b")\n").unwrap();
+    }
+}
 fn define(cfg: &str) {
     ldefine(&[cfg]);
 }
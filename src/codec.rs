@@ -0,0 +1,74 @@
+//! Message framing over the Tokio byte streams, built on the length-delimited codec from `tokio-util`.
+//!
+//! Byte streams don't preserve message boundaries, which is why every protocol on top of them has to delimit its
+//! messages somehow. The [`LengthDelimitedExt`] trait turns any of the crate's Tokio byte streams into a
+//! [`Framed`] stream and sink of messages, each prefixed with its length as a big-endian 32-bit integer, in one line:
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use bytes::Bytes;
+//! use futures::{SinkExt, StreamExt};
+//! use interprocess::{codec::LengthDelimitedExt, local_socket::tokio::LocalSocketStream};
+//!
+//! let mut conn = LocalSocketStream::connect("/tmp/example.sock").await?.length_delimited();
+//! conn.send(Bytes::from_static(b"Hello from client!")).await?;
+//! if let Some(msg) = conn.next().await {
+//!     println!("Server answered: {}", String::from_utf8_lossy(&msg?));
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! Both ends of the connection need to use the same framing, which is guaranteed if both use this module. The other
+//! end doesn't need to use `interprocess`, as long as it follows the same format – it's the default configuration
+//! of [`LengthDelimitedCodec`].
+
+use crate::Sealed;
+pub use tokio_util::codec::{Framed, LengthDelimitedCodec};
+
+/// The maximum size of a message accepted by [`.length_delimited()`](LengthDelimitedExt::length_delimited), equal to
+/// the default of [`LengthDelimitedCodec`]: 8 MiB.
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// Extends the Tokio byte streams of the crate with constructors for [`Framed`] message streams which use
+/// [`LengthDelimitedCodec`].
+///
+/// See the [module-level documentation](self) for an example.
+pub trait LengthDelimitedExt: Sized + Sealed {
+    /// Wraps the stream in a [`Framed`] with a [`LengthDelimitedCodec`] that accepts messages of up to
+    /// [`DEFAULT_MAX_FRAME_LENGTH`] bytes.
+    #[inline]
+    fn length_delimited(self) -> Framed<Self, LengthDelimitedCodec> {
+        self.length_delimited_with_max_frame_length(DEFAULT_MAX_FRAME_LENGTH)
+    }
+    /// Wraps the stream in a [`Framed`] with a [`LengthDelimitedCodec`] that accepts messages of up to the given
+    /// size in bytes.
+    ///
+    /// Receiving a bigger message fails with an [`InvalidData`](std::io::ErrorKind::InvalidData) error, and so does
+    /// sending one.
+    fn length_delimited_with_max_frame_length(self, max_frame_length: usize) -> Framed<Self, LengthDelimitedCodec>;
+}
+
+macro_rules! impl_length_delimited {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl Sealed for $ty {}
+        impl LengthDelimitedExt for $ty {
+            #[inline]
+            fn length_delimited_with_max_frame_length(
+                self,
+                max_frame_length: usize,
+            ) -> Framed<Self, LengthDelimitedCodec> {
+                LengthDelimitedCodec::builder()
+                    .max_frame_length(max_frame_length)
+                    .new_framed(self)
+            }
+        }
+    )+};
+}
+
+impl_length_delimited!(crate::local_socket::tokio::LocalSocketStream);
+#[cfg(unix)]
+impl_length_delimited!(crate::os::unix::udsocket::tokio::UdStream);
+#[cfg(windows)]
+impl_length_delimited!(
+    crate::os::windows::named_pipe::tokio::DuplexPipeStream<crate::os::windows::named_pipe::pipe_mode::Bytes>
+);
@@ -0,0 +1,76 @@
+//! Control over what the server side of a connection does with it when the stream is dropped.
+//!
+//! On Windows, the server end of a named pipe stays connected to the client until it is explicitly disconnected, and
+//! disconnecting discards whatever the client hasn't read yet. Dropping a server-side stream therefore has to choose
+//! between losing data, waiting for the client and leaving the disconnection to the client – a [`DropPolicy`] makes
+//! that choice explicit. The policy is set process-wide with [`set_default_drop_policy()`] and can be overridden for
+//! individual named pipe streams with `PipeStream::set_drop_policy()`. It applies to all server-side named pipe
+//! streams, including the ones behind [local sockets](crate::local_socket), blocking and asynchronous alike, and to
+//! their split halves once the last of them is dropped. Dropping a stream never panics, whatever the policy, and
+//! errors which occur while carrying it out are ignored.
+//!
+//! On Unix, closing a socket never discards data which the peer hasn't received yet, and there is no way to disconnect
+//! the peer other than closing, so the policy has no effect there – every drop behaves like
+//! [`FlushThenDisconnect`](DropPolicy::FlushThenDisconnect) without blocking.
+
+use std::sync::atomic::{AtomicU8, Ordering::Relaxed};
+
+/// What a server-side stream does with the connection when it is dropped.
+///
+/// See the [module-level documentation](self) for where this applies.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DropPolicy {
+    /// Waits for the client to read everything that has been sent, then disconnects it.
+    ///
+    /// The wait happens on a single background thread shared by the whole process, so that dropping never blocks.
+    /// Streams dropped this way are flushed one after another, meaning that a client which never reads holds up the
+    /// disconnection of all those dropped after it. If the process exits before the client has read everything, the
+    /// data is lost all the same – flush the stream explicitly before dropping it when this matters.
+    FlushThenDisconnect = 1,
+    /// Disconnects the client right away, discarding whatever it hasn't read yet. This is the default.
+    #[default]
+    Disconnect = 2,
+    /// Closes the stream without disconnecting the client, which can still read what has been sent and sees the end of
+    /// the stream afterwards. The pipe instance lives on until the client closes its end.
+    Detach = 3,
+}
+impl DropPolicy {
+    fn from_u8(val: u8) -> Option<Self> {
+        match val {
+            1 => Some(Self::FlushThenDisconnect),
+            2 => Some(Self::Disconnect),
+            3 => Some(Self::Detach),
+            _ => None,
+        }
+    }
+}
+
+static DEFAULT_DROP_POLICY: AtomicU8 = AtomicU8::new(DropPolicy::Disconnect as u8);
+
+/// Sets the policy applied by server-side streams which don't have one of their own when they are dropped.
+///
+/// The setting applies to the whole process and takes effect for streams dropped afterwards, including ones which
+/// already exist.
+pub fn set_default_drop_policy(policy: DropPolicy) {
+    DEFAULT_DROP_POLICY.store(policy as u8, Relaxed);
+}
+/// Returns the policy applied by server-side streams which don't have one of their own when they are dropped, as set
+/// by [`set_default_drop_policy()`].
+pub fn default_drop_policy() -> DropPolicy {
+    DropPolicy::from_u8(DEFAULT_DROP_POLICY.load(Relaxed)).unwrap_or_default()
+}
+
+/// The policy of an individual stream, which follows the process-wide default until set.
+#[cfg(windows)]
+#[derive(Debug, Default)]
+pub(crate) struct DropPolicyCell(AtomicU8);
+#[cfg(windows)]
+impl DropPolicyCell {
+    pub fn set(&self, policy: DropPolicy) {
+        self.0.store(policy as u8, Relaxed);
+    }
+    pub fn get(&self) -> DropPolicy {
+        DropPolicy::from_u8(self.0.load(Relaxed)).unwrap_or_else(default_drop_policy)
+    }
+}
@@ -0,0 +1,234 @@
+//! A portable error type with semantic error kinds.
+//!
+//! Most of the crate reports failures with [`io::Error`], whose [`kind()`](io::Error::kind) is often too coarse to tell apart the conditions that matter to IPC code – a name being taken by another server, there being no server to connect to, or the peer going away in the middle of a conversation – and whose raw OS error codes differ from platform to platform. The [`Error`] type of this module wraps an [`io::Error`] together with an [`ErrorKind`] which names those conditions the same way on every platform:
+//! ```no_run
+//! use interprocess::{
+//!     local_socket::LocalSocketStream,
+//!     Error, ErrorKind,
+//! };
+//!
+//! match LocalSocketStream::connect("example.sock").map_err(Error::from) {
+//!     Ok(_conn) => { /* ... */ }
+//!     Err(e) if e.kind() == ErrorKind::ServerNotFound => eprintln!("the server isn't running"),
+//!     Err(e) => eprintln!("connection failed: {e}"),
+//! }
+//! ```
+//! Converting an [`Error`] back into an [`io::Error`] preserves its kind, so the two can be used interchangeably in code that propagates errors with `?`.
+
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+/// The semantic category of an [`Error`].
+///
+/// New kinds may be added in the future, and errors which currently fall into [`Other`](Self::Other) may then start falling into the new kinds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The name requested for a server is already in use by another server.
+    NameInUse,
+    /// There is no server listening under the name a connection was attempted to.
+    ///
+    /// A name which doesn't exist at all only falls into this kind when reported by an operation which connects to a
+    /// server, since a missing file means something else elsewhere.
+    ServerNotFound,
+    /// The other side of the connection has disconnected or closed its end of it.
+    PeerDisconnected,
+    /// A message is too large to be sent or received.
+    MessageTooLarge,
+    /// A socket path or name is longer than the platform allows. On Unix, see
+    /// `os::unix::udsocket::set_long_path_workaround()` for a way to use longer socket paths.
+    NameTooLong,
+    /// A file descriptor or handle doesn't refer to the kind of object the operation expects.
+    InvalidHandle,
+    /// The listener has been closed while waiting for incoming connections.
+    ListenerClosed,
+    /// The operation is not supported on the current platform or by the object it was performed on.
+    Unsupported,
+    /// Any other error.
+    Other,
+}
+impl ErrorKind {
+    /// Determines the kind of the given I/O error.
+    ///
+    /// Errors produced by this crate carry their kind along with them, and errors reported by the operating system are classified by their raw error code, falling back to their [`io::ErrorKind`].
+    pub fn of(err: &io::Error) -> Self {
+        if let Some(inner) = err.get_ref() {
+            if let Some(e) = inner.downcast_ref::<Error>() {
+                return e.kind;
+            }
+            if inner.is::<crate::local_socket::ListenerClosedError>() {
+                return Self::ListenerClosed;
+            }
+        }
+        if let Some(kind) = err.raw_os_error().and_then(kind_from_os_error) {
+            return kind;
+        }
+        match err.kind() {
+            io::ErrorKind::AddrInUse => Self::NameInUse,
+            io::ErrorKind::ConnectionRefused => Self::ServerNotFound,
+            io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected => Self::PeerDisconnected,
+            io::ErrorKind::Unsupported => Self::Unsupported,
+            _ => Self::Other,
+        }
+    }
+    /// The [`io::ErrorKind`] given to errors of this kind created by the crate itself.
+    fn io_kind(self) -> io::ErrorKind {
+        match self {
+            Self::NameInUse => io::ErrorKind::AddrInUse,
+            Self::ServerNotFound => io::ErrorKind::NotFound,
+            Self::PeerDisconnected => io::ErrorKind::BrokenPipe,
+            Self::MessageTooLarge | Self::NameTooLong | Self::InvalidHandle => io::ErrorKind::InvalidInput,
+            Self::Unsupported => io::ErrorKind::Unsupported,
+            Self::ListenerClosed | Self::Other => io::ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn kind_from_os_error(code: i32) -> Option<ErrorKind> {
+    use libc::*;
+    Some(match code {
+        EADDRINUSE => ErrorKind::NameInUse,
+        ECONNREFUSED => ErrorKind::ServerNotFound,
+        EPIPE | ECONNRESET | ECONNABORTED | ENOTCONN | ESHUTDOWN => ErrorKind::PeerDisconnected,
+        EMSGSIZE => ErrorKind::MessageTooLarge,
+        ENAMETOOLONG => ErrorKind::NameTooLong,
+        ENOTSOCK | EBADF => ErrorKind::InvalidHandle,
+        ENOSYS | EOPNOTSUPP | EPROTONOSUPPORT | EAFNOSUPPORT => ErrorKind::Unsupported,
+        _ => return None,
+    })
+}
+#[cfg(windows)]
+fn kind_from_os_error(code: i32) -> Option<ErrorKind> {
+    use winapi::shared::winerror::*;
+    Some(match code as u32 {
+        ERROR_ALREADY_EXISTS => ErrorKind::NameInUse,
+        ERROR_BROKEN_PIPE | ERROR_NO_DATA | ERROR_PIPE_NOT_CONNECTED => ErrorKind::PeerDisconnected,
+        ERROR_MORE_DATA => ErrorKind::MessageTooLarge,
+        ERROR_FILENAME_EXCED_RANGE => ErrorKind::NameTooLong,
+        ERROR_INVALID_HANDLE => ErrorKind::InvalidHandle,
+        ERROR_NOT_SUPPORTED | ERROR_CALL_NOT_IMPLEMENTED => ErrorKind::Unsupported,
+        _ => return None,
+    })
+}
+
+/// Attaches [`ServerNotFound`](ErrorKind::ServerNotFound) to an error from connecting to a server if the name doesn't
+/// exist. Elsewhere, a missing file is just a missing file, so this isn't done by [`ErrorKind::of`].
+pub(crate) fn connect_error(err: io::Error) -> io::Error {
+    if err.kind() == io::ErrorKind::NotFound && ErrorKind::of(&err) == ErrorKind::Other {
+        Error {
+            kind: ErrorKind::ServerNotFound,
+            inner: err,
+        }
+        .into()
+    } else {
+        err
+    }
+}
+/// Attaches [`NameInUse`](ErrorKind::NameInUse) to an error from creating a named pipe instance if another server holds
+/// the name: creating the first instance of a pipe which already exists fails with `ERROR_ACCESS_DENIED`, and going over
+/// the instance limit of someone else's pipe with `ERROR_PIPE_BUSY`. Those mean something else in other places.
+#[cfg(windows)]
+pub(crate) fn create_pipe_error(err: io::Error) -> io::Error {
+    use winapi::shared::winerror::{ERROR_ACCESS_DENIED, ERROR_PIPE_BUSY};
+    match err.raw_os_error().map(|code| code as u32) {
+        Some(ERROR_ACCESS_DENIED | ERROR_PIPE_BUSY) => Error {
+            kind: ErrorKind::NameInUse,
+            inner: err,
+        }
+        .into(),
+        _ => err,
+    }
+}
+
+/// An I/O error with a portable [`ErrorKind`] attached to it.
+///
+/// Can be created from any [`io::Error`], in which case the kind is determined by [`ErrorKind::of`], and converted back into one without losing the kind. See the [module-level documentation](self) for an example.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    inner: io::Error,
+}
+impl Error {
+    /// Creates an error of the given kind with the given payload, which is usually a message.
+    pub fn new(kind: ErrorKind, payload: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        Self::with_io_kind(kind, kind.io_kind(), payload)
+    }
+    /// Like `new()`, but with the [`io::ErrorKind`] of the I/O error it turns into specified explicitly.
+    pub(crate) fn with_io_kind(
+        kind: ErrorKind,
+        io_kind: io::ErrorKind,
+        payload: impl Into<Box<dyn StdError + Send + Sync>>,
+    ) -> Self {
+        Self {
+            kind,
+            inner: io::Error::new(io_kind, payload),
+        }
+    }
+    /// Returns the portable kind of the error.
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+    /// Returns the I/O error that the error wraps.
+    #[inline]
+    pub fn as_io_error(&self) -> &io::Error {
+        &self.inner
+    }
+    /// Returns the raw OS error code, if the error was reported by the operating system.
+    #[inline]
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.inner.raw_os_error()
+    }
+}
+
+/// Turns a message-mode send operation which sent only `sent` bytes of a `len`-byte message into a
+/// [`MessageTooLarge`](ErrorKind::MessageTooLarge) error, so that callers don't have to inspect the byte count.
+pub(crate) fn check_msg_sent(sent: usize, len: usize) -> Result<(), Error> {
+    if sent == len {
+        return Ok(());
+    }
+    Err(Error::new(
+        ErrorKind::MessageTooLarge,
+        format!("only {sent} out of {len} bytes of the message were sent"),
+    ))
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.inner, f)
+    }
+}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner.source()
+    }
+}
+impl From<io::Error> for Error {
+    fn from(inner: io::Error) -> Self {
+        if inner.get_ref().map_or(false, |e| e.is::<Self>()) {
+            // The unwraps can't fail because of the check above.
+            return *inner.into_inner().unwrap().downcast::<Self>().unwrap();
+        }
+        Self {
+            kind: ErrorKind::of(&inner),
+            inner,
+        }
+    }
+}
+impl From<Error> for io::Error {
+    /// Unwraps the I/O error if its kind can be determined from it alone, or wraps the whole error into a new I/O error of
+    /// the same [`io::ErrorKind`] otherwise.
+    fn from(e: Error) -> Self {
+        if ErrorKind::of(&e.inner) == e.kind {
+            e.inner
+        } else {
+            io::Error::new(e.inner.kind(), e)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! The error type of fallible conversions from owned handles and file descriptors.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+#[cfg(unix)]
+type OwnedHandle = std::os::unix::io::OwnedFd;
+#[cfg(windows)]
+type OwnedHandle = std::os::windows::io::OwnedHandle;
+
+/// Error produced by converting an [`OwnedFd`] on Unix or an [`OwnedHandle`] on Windows into an IPC primitive of this crate
+/// when the file descriptor or handle refers to a different kind of object than the one the primitive expects, such as a
+/// datagram socket being converted into a stream or the writing end of a pipe being converted into a reader.
+///
+/// Ownership of the file descriptor or handle is handed back along with the reason for which it was rejected, so that it
+/// doesn't get closed when the conversion fails.
+///
+/// [`OwnedFd`]: https://doc.rust-lang.org/std/os/unix/io/struct.OwnedFd.html " "
+/// [`OwnedHandle`]: https://doc.rust-lang.org/std/os/windows/io/struct.OwnedHandle.html " "
+#[derive(Debug)]
+pub struct FromHandleError {
+    /// The file descriptor or handle that was rejected.
+    pub handle: OwnedHandle,
+    /// The reason for which it was rejected.
+    pub cause: io::Error,
+}
+impl FromHandleError {
+    pub(crate) fn new(handle: OwnedHandle, cause: io::Error) -> Self {
+        Self { handle, cause }
+    }
+    /// Checks the handle with the given function, handing it back in the error if the check fails.
+    pub(crate) fn check(
+        handle: OwnedHandle,
+        f: impl FnOnce(&OwnedHandle) -> io::Result<()>,
+    ) -> Result<OwnedHandle, Self> {
+        match f(&handle) {
+            Ok(()) => Ok(handle),
+            Err(cause) => Err(Self::new(handle, cause)),
+        }
+    }
+}
+impl Display for FromHandleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "conversion from handle failed: {}", self.cause)
+    }
+}
+impl Error for FromHandleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+impl From<FromHandleError> for io::Error {
+    /// Drops the handle, keeping only the cause.
+    fn from(e: FromHandleError) -> Self {
+        e.cause
+    }
+}
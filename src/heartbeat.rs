@@ -0,0 +1,414 @@
+//! A keepalive wrapper for message-mode connections, which detects a peer that has stopped responding without closing
+//! the connection.
+//!
+//! A connection only ends once the other side closes it or its process exits. A peer which is deadlocked, stopped by a
+//! debugger, swapped out or otherwise wedged keeps the connection open, and waiting for a message from it blocks
+//! forever. Some connections can't even report the exit of the peer promptly, such as ones whose other end was
+//! inherited by a child process which is still running. A [`Heartbeat`] wraps a message-mode connection and sends the
+//! peer a ping at a regular [interval](HeartbeatOptions::interval), which the peer answers with a pong. If nothing at
+//! all arrives from the peer within the [timeout](HeartbeatOptions::timeout), the peer is considered dead, and
+//! receiving and sending fail with [`TimedOut`](io::ErrorKind::TimedOut).
+//!
+//! Any connection which is both a [`Stream`] of received messages and a [`Sink`] of messages to send can be wrapped,
+//! which includes [Tokio message-mode local sockets](crate::local_socket::tokio::LocalSocketMsgStream) as well as byte
+//! streams, such as pipes, with message framing added by the `codec` feature. Both sides of the
+//! connection have to use a `Heartbeat`, since pings and pongs are interleaved with the messages of the application,
+//! each of which is prefixed with a byte that tells them apart.
+//!
+//! The connection is driven by a background task, which is why a `Heartbeat` has to be created within a Tokio runtime.
+//! The task keeps answering pings regardless of whether the application is receiving messages, which means that
+//! messages are buffered without limit for as long as the application doesn't receive them. Once the `Heartbeat` is
+//! dropped, the task sends the remaining messages and closes the connection.
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! use interprocess::{heartbeat::HeartbeatOptions, local_socket::tokio::LocalSocketMsgStream};
+//! use std::time::Duration;
+//!
+//! let conn = LocalSocketMsgStream::connect("@example_heartbeat.sock").await?;
+//! let conn = HeartbeatOptions::new()
+//!     .interval(Duration::from_millis(500))
+//!     .timeout(Duration::from_secs(2))
+//!     .build(conn)?;
+//! conn.send(b"Hello from client!").await?;
+//! // Fails within two seconds if the server stops responding.
+//! match conn.recv().await? {
+//!     Some(reply) => println!("Server answered: {}", String::from_utf8_lossy(&reply)),
+//!     None => println!("Server hung up"),
+//! }
+//! # Ok(()) }
+//! ```
+
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    io,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender},
+    time::{self, Instant, Interval, MissedTickBehavior, Sleep},
+};
+
+/// How often a ping is sent unless configured otherwise: once per second.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the peer may stay silent unless configured otherwise before it's considered dead: five seconds.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many messages of the application can be waiting to be sent before sending waits for the connection.
+const OUTGOING_CAPACITY: usize = 16;
+
+// Frame types, which make up the first byte of every message on the connection.
+const DATA: u8 = 0;
+const PING: u8 = 1;
+const PONG: u8 = 2;
+
+/// Builder for [`Heartbeat`], allowing its timing to be configured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HeartbeatOptions {
+    interval: Duration,
+    timeout: Duration,
+}
+impl HeartbeatOptions {
+    /// Creates a builder with the default timing.
+    pub fn new() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+    /// Sets how often a ping is sent to the peer. Defaults to [`DEFAULT_INTERVAL`].
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Sets how long the peer may go without sending anything before it's considered dead. Defaults to
+    /// [`DEFAULT_TIMEOUT`].
+    ///
+    /// The timeout has to be longer than the interval of the peer plus the time it takes the connection to deliver a
+    /// message, or else a healthy peer can be considered dead. A few times the interval is a good choice.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Wraps the given connection with the configured timing. Has to be called within a Tokio runtime.
+    ///
+    /// The connection is a [`Stream`] of received messages and a [`Sink`] of messages to send, each of which is one
+    /// whole message – the item types are generic so that both `Vec<u8>` and the `bytes` types used by the `codec`
+    /// feature fit. The stream ending is taken to mean that the peer has closed the connection.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the interval is zero or the timeout is not longer
+    /// than the interval.
+    pub fn build<S, I, M>(&self, conn: S) -> io::Result<Heartbeat>
+    where
+        S: Stream<Item = io::Result<I>> + Sink<M, Error = io::Error> + Send + 'static,
+        I: AsRef<[u8]>,
+        M: From<Vec<u8>> + 'static,
+    {
+        if self.interval.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the heartbeat interval cannot be zero",
+            ));
+        }
+        if self.timeout <= self.interval {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the heartbeat timeout must be longer than the interval",
+            ));
+        }
+        let shared = Arc::new(Shared::default());
+        let (outgoing, outgoing_rx) = mpsc::channel(OUTGOING_CAPACITY);
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        let mut interval = time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tokio::spawn(Driver {
+            conn: Box::pin(conn),
+            outgoing: outgoing_rx,
+            incoming: incoming_tx,
+            queue: VecDeque::new(),
+            needs_flush: false,
+            outgoing_done: false,
+            interval,
+            deadline: Box::pin(time::sleep(self.timeout)),
+            timeout: self.timeout,
+            next_ping: 0,
+            ping_sent: None,
+            shared: Arc::clone(&shared),
+            _phantom: PhantomData,
+        });
+        Ok(Heartbeat {
+            outgoing,
+            incoming: tokio::sync::Mutex::new(incoming),
+            shared,
+        })
+    }
+}
+impl Default for HeartbeatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A message-mode connection which checks that the peer is still responding.
+///
+/// See the [module-level documentation](self) for an overview and an example.
+pub struct Heartbeat {
+    outgoing: Sender<Vec<u8>>,
+    incoming: tokio::sync::Mutex<UnboundedReceiver<Vec<u8>>>,
+    shared: Arc<Shared>,
+}
+impl Heartbeat {
+    /// Wraps the given connection with the default timing. Has to be called within a Tokio runtime.
+    ///
+    /// See [`HeartbeatOptions::build()`] for the requirements on the connection.
+    pub fn new<S, I, M>(conn: S) -> Self
+    where
+        S: Stream<Item = io::Result<I>> + Sink<M, Error = io::Error> + Send + 'static,
+        I: AsRef<[u8]>,
+        M: From<Vec<u8>> + 'static,
+    {
+        HeartbeatOptions::new()
+            .build(conn)
+            .expect("the default options are valid")
+    }
+    /// Sends one message, waiting only if many messages are already waiting to be sent.
+    ///
+    /// Fails with [`TimedOut`](io::ErrorKind::TimedOut) if the peer has stopped responding, and with the error which
+    /// ended the connection if it's closed.
+    pub async fn send(&self, msg: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(msg.len() + 1);
+        frame.push(DATA);
+        frame.extend_from_slice(msg);
+        self.outgoing
+            .send(frame)
+            .await
+            .map_err(|_| self.shared.lock().closed_error())
+    }
+    /// Receives one message, returning `None` once the peer has closed the connection.
+    ///
+    /// Fails with [`TimedOut`](io::ErrorKind::TimedOut) if the peer has stopped responding, or with the error which
+    /// ended the connection, once all messages received before that are received.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: if the future is dropped, no message is lost.
+    pub async fn recv(&self) -> io::Result<Option<Vec<u8>>> {
+        match self.incoming.lock().await.recv().await {
+            Some(msg) => Ok(Some(msg)),
+            None => {
+                let state = self.shared.lock();
+                match state.error {
+                    Some(_) => Err(state.closed_error()),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+    /// Returns how long the latest ping took to be answered, or `None` if no ping has been answered yet.
+    pub fn round_trip_time(&self) -> Option<Duration> {
+        self.shared.lock().round_trip_time
+    }
+    /// Returns `true` if the connection has ended, either because the peer closed it, failed to respond in time or an
+    /// error occurred.
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().closed
+    }
+}
+impl Debug for Heartbeat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.shared.lock();
+        f.debug_struct("Heartbeat")
+            .field("round_trip_time", &state.round_trip_time)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+}
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is never left inconsistent by a panic, so poisoning can be ignored.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    round_trip_time: Option<Duration>,
+    closed: bool,
+    /// The reason the connection ended, if that was due to an error rather than the peer closing it.
+    error: Option<(io::ErrorKind, String)>,
+}
+impl State {
+    /// The error returned by operations once the connection has ended.
+    fn closed_error(&self) -> io::Error {
+        match &self.error {
+            Some((kind, msg)) => io::Error::new(*kind, msg.clone()),
+            None => io::Error::new(io::ErrorKind::ConnectionAborted, "the connection was closed"),
+        }
+    }
+}
+
+/// The background task which owns the connection.
+struct Driver<S, M> {
+    conn: Pin<Box<S>>,
+    outgoing: Receiver<Vec<u8>>,
+    incoming: UnboundedSender<Vec<u8>>,
+    /// Frames waiting to be handed to the connection.
+    queue: VecDeque<Vec<u8>>,
+    needs_flush: bool,
+    /// Set once the `Heartbeat` is dropped, after which the connection is closed once the queue is empty.
+    outgoing_done: bool,
+    interval: Interval,
+    /// When the peer is considered dead, pushed back whenever anything arrives from it.
+    deadline: Pin<Box<Sleep>>,
+    timeout: Duration,
+    next_ping: u64,
+    /// The sequence number of the ping which was last sent and not answered yet, along with when it was sent.
+    ping_sent: Option<(u64, Instant)>,
+    shared: Arc<Shared>,
+    _phantom: PhantomData<fn(M)>,
+}
+impl<S, I, M> Driver<S, M>
+where
+    S: Stream<Item = io::Result<I>> + Sink<M, Error = io::Error>,
+    I: AsRef<[u8]>,
+    M: From<Vec<u8>>,
+{
+    /// Drives the connection as far as possible, returning `Ready` once it has ended.
+    fn drive(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.interval.poll_tick(cx).is_ready() {
+            let seq = self.next_ping;
+            self.next_ping += 1;
+            self.queue.push_back(control_frame(PING, seq));
+            // Only the latest ping is timed, so that the round trip time reflects the current state of the peer.
+            self.ping_sent = Some((seq, Instant::now()));
+        }
+
+        // Writing frames can make room for more messages of the application, so everything is repeated until nothing
+        // more can be done.
+        loop {
+            let mut progress = false;
+            loop {
+                match self.conn.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(Ok(frame))) => self.handle_frame(frame.as_ref())?,
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+                    Poll::Ready(None) => return Poll::Ready(Ok(())),
+                    Poll::Pending => break,
+                }
+            }
+            while !self.outgoing_done && self.queue.len() < OUTGOING_CAPACITY {
+                match self.outgoing.poll_recv(cx) {
+                    Poll::Ready(Some(frame)) => self.queue.push_back(frame),
+                    Poll::Ready(None) => self.outgoing_done = true,
+                    Poll::Pending => break,
+                }
+            }
+            while !self.queue.is_empty() {
+                match self.conn.as_mut().poll_ready(cx) {
+                    Poll::Ready(Ok(())) => {
+                        let frame = self.queue.pop_front().expect("queue is not empty");
+                        self.conn.as_mut().start_send(frame.into())?;
+                        self.needs_flush = true;
+                        progress = true;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => break,
+                }
+            }
+            if self.needs_flush {
+                match self.conn.as_mut().poll_flush(cx) {
+                    Poll::Ready(Ok(())) => self.needs_flush = false,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => {}
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+
+        // Checked after reading, so that frames which arrived while the task wasn't running still count.
+        if self.deadline.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the peer did not respond within the heartbeat timeout",
+            )));
+        }
+        if self.outgoing_done && self.queue.is_empty() && !self.needs_flush {
+            return self.conn.as_mut().poll_close(cx);
+        }
+        Poll::Pending
+    }
+    fn handle_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        let now = Instant::now();
+        self.deadline.as_mut().reset(now + self.timeout);
+        match frame.split_first() {
+            Some((&DATA, msg)) => {
+                // If the `Heartbeat` is gone, nobody is interested in the message anymore.
+                let _ = self.incoming.send(msg.to_vec());
+            }
+            Some((&PING, seq)) => {
+                let mut pong = Vec::with_capacity(frame.len());
+                pong.push(PONG);
+                pong.extend_from_slice(seq);
+                self.queue.push_back(pong);
+            }
+            Some((&PONG, seq)) => {
+                if let Some((sent_seq, sent_at)) = self.ping_sent {
+                    if seq == sent_seq.to_le_bytes() {
+                        self.ping_sent = None;
+                        self.shared.lock().round_trip_time = Some(now - sent_at);
+                    }
+                }
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "received a message which is not a heartbeat frame",
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+impl<S, I, M> Future for Driver<S, M>
+where
+    S: Stream<Item = io::Result<I>> + Sink<M, Error = io::Error>,
+    I: AsRef<[u8]>,
+    M: From<Vec<u8>>,
+{
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let slf = self.get_mut();
+        let result = match slf.drive(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+        let mut state = slf.shared.lock();
+        state.closed = true;
+        state.error = result.err().map(|e| (e.kind(), e.to_string()));
+        Poll::Ready(())
+    }
+}
+
+fn control_frame(kind: u8, seq: u64) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(9);
+    frame.push(kind);
+    frame.extend_from_slice(&seq.to_le_bytes());
+    frame
+}
@@ -0,0 +1,134 @@
+//! Integration with Hyper and Tower, for serving and consuming HTTP-based APIs over local sockets.
+//!
+//! Many daemons, such as the Docker engine, expose an HTTP API on a local socket instead of a TCP port, which keeps it
+//! off the network and lets the filesystem permissions of the socket decide who may use it. This module provides the
+//! two pieces needed to use Hyper for that:
+//! - [`LocalSocketConnector`], a Tower [`Service`] which connects to a local socket server and can be plugged into
+//!   `hyper::Client` or any other client which accepts a custom connector, such as Tonic's gRPC channels;
+//! - an implementation of Hyper's [`Accept`] trait for the [Tokio local socket
+//!   listener](crate::local_socket::tokio::LocalSocketListener), which allows it to be passed to
+//!   `hyper::Server::builder()` in place of a TCP listener.
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use hyper::{
+//!     service::{make_service_fn, service_fn},
+//!     Body, Client, Response, Server,
+//! };
+//! use interprocess::{hyper::LocalSocketConnector, local_socket::tokio::LocalSocketListener};
+//! use std::convert::Infallible;
+//!
+//! // Server side:
+//! let listener = LocalSocketListener::bind("/tmp/example.sock")?;
+//! let make_service = make_service_fn(|_| async {
+//!     Ok::<_, Infallible>(service_fn(|_req| async { Ok::<_, Infallible>(Response::new(Body::from("Hello!"))) }))
+//! });
+//! tokio::spawn(Server::builder(listener).serve(make_service));
+//!
+//! // Client side:
+//! let client = Client::builder().build::<_, Body>(LocalSocketConnector::new("/tmp/example.sock")?);
+//! // The authority of the URI is ignored, but is still sent in the `Host` header.
+//! let response = client.get("http://localhost/greeting".parse()?).await?;
+//! let body = hyper::body::to_bytes(response.into_body()).await?;
+//! println!("Server answered: {}", String::from_utf8_lossy(&body));
+//! # Ok(()) }
+//! ```
+
+use crate::local_socket::{
+    tokio::{LocalSocketListener, LocalSocketStream},
+    LocalSocketName, ToLocalSocketName,
+};
+use ::hyper::{
+    client::connect::{Connected, Connection},
+    server::accept::Accept,
+    Uri,
+};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+pub use tower_service::Service;
+
+/// A Tower [`Service`] which connects to a local socket server, for use as the connector of an HTTP client.
+///
+/// Every connection goes to the same local socket, regardless of the URI of the request. Since the URI is still used
+/// by the client to fill in the `Host` header and to pool connections, it's best to stick to one authority, such as
+/// `localhost`, for all requests made through the same connector.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Clone, Debug)]
+pub struct LocalSocketConnector {
+    name: LocalSocketName<'static>,
+}
+impl LocalSocketConnector {
+    /// Creates a connector which connects to the local socket server with the given name.
+    pub fn new<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let namespaced = name.is_namespaced();
+        Ok(Self {
+            name: LocalSocketName::from_raw_parts(Cow::Owned(name.into_inner()), namespaced),
+        })
+    }
+    /// Returns the name of the local socket server the connector connects to.
+    #[inline]
+    pub fn name(&self) -> &LocalSocketName<'static> {
+        &self.name
+    }
+}
+impl Service<Uri> for LocalSocketConnector {
+    type Response = LocalSocketStream;
+    type Error = io::Error;
+    type Future = ConnectFuture;
+
+    /// Always ready – connecting doesn't require any preparation.
+    #[inline]
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn call(&mut self, _: Uri) -> Self::Future {
+        let name = self.name.clone();
+        ConnectFuture(Box::pin(async move { LocalSocketStream::connect(name).await }))
+    }
+}
+
+/// The future returned by [`LocalSocketConnector`], which resolves to the established connection.
+pub struct ConnectFuture(Pin<Box<dyn Future<Output = io::Result<LocalSocketStream>> + Send>>);
+impl Future for ConnectFuture {
+    type Output = io::Result<LocalSocketStream>;
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.as_mut().poll(cx)
+    }
+}
+impl Debug for ConnectFuture {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectFuture").finish_non_exhaustive()
+    }
+}
+
+impl Connection for LocalSocketStream {
+    /// Local socket connections carry no metadata of interest to Hyper, such as the result of ALPN negotiation.
+    #[inline]
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+/// Makes the listener usable as the source of incoming connections of a Hyper server.
+///
+/// Since the server takes ownership of the listener, it cannot be [closed](LocalSocketListener::close) – use
+/// Hyper's graceful shutdown facilities to stop the server instead.
+impl Accept for LocalSocketListener {
+    type Conn = LocalSocketStream;
+    type Error = io::Error;
+    #[inline]
+    fn poll_accept(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<io::Result<LocalSocketStream>>> {
+        LocalSocketListener::poll_accept(&self, cx).map(Some)
+    }
+}
@@ -8,9 +8,16 @@
 //!
 //! ### Cross-platform IPC APIs
 //! - **Local sockets** – similar to TCP sockets, but use filesystem or namespaced paths instead of ports on `localhost`, depending on the OS, bypassing the network stack entirely; implemented using named pipes on Windows and Unix domain sockets on Unix
+//! - **Signals** – a small set of portable signals (interrupt, terminate and one user-defined signal) which can be sent to other processes by ID and received by any number of subscribers within a process; implemented using POSIX signals on Unix and emulated with named semaphores and console control events on Windows
+//! - **Publish/subscribe** – a message broker which relays messages published on named topics to all of the processes subscribed to them; built on local sockets
+//! - **Child process spawning** – a helper which spawns a child process with local socket streams and unnamed pipes already connected to the parent, passed down by inheritance or over a bootstrap connection
 //!
 //! ### Platform-specific, but present on both Unix-like systems and Windows
 //! - **Unnamed pipes** – anonymous file-like objects for communicating privately in one direction, most commonly used to communicate between a child process and its parent
+//! - **Shared memory** – named regions of memory which can be mapped into the address spaces of several processes at once, the fastest but least structured form of IPC, along with single-producer single-consumer ring buffer channels built on top of them; implemented using POSIX shared memory on Unix (except Android) and file mappings on Windows, with nameless sealable shared memory that is passed around by file descriptor additionally available on Linux
+//! - **Named semaphores** – counters shared between processes by name, used to signal events such as data having been written into shared memory; implemented using POSIX named semaphores on Unix (except Android) and semaphore objects on Windows
+//! - **Named mutexes** – locks shared between processes by name, which report when their previous owner died while holding them; implemented using robust process-shared mutexes on Linux, file locks on other Unix-like systems (except Android) and mutex objects on Windows
+//! - **Named events** – condition-variable-like primitives which let processes sleep until notified by another process, used to wait for changes to shared memory without busy-polling; implemented using futexes on Linux and emulated with named semaphores elsewhere (except Android)
 //!
 //! ### Unix-only
 //! - **FIFO files** – special type of file which is similar to unnamed pipes but exists on the filesystem, often referred to as "named pipes" but completely different from Windows named pipes
@@ -20,10 +27,22 @@
 //! - **Named pipes** – closely resembles Unix domain sockets, uses a separate namespace instead of on-drive paths
 //!
 //! ## Asynchronous I/O
-//! Currently, only Tokio for local sockets, Unix domain sockets and Windows named pipes is supported. Support for `async-std` is planned.
+//! Tokio is supported for local sockets, Unix domain sockets, Windows named pipes, FIFO files, signals, publish/subscribe clients, keepalive checks for message-mode connections and the multiplexing of many channels over one connection. Local sockets and Unix domain sockets can also be used with async-std and other runtimes built on `async-io`, such as `smol`, as well as with any other runtime which implements the `Reactor` trait.
 //!
 //! # Feature gates
 //! - **`tokio`**, *off* by default – enables support for Tokio-powered efficient asynchronous IPC.
+//! - **`async`**, *off* by default – enables runtime-agnostic asynchronous local sockets, which can be driven by any runtime that implements the `Reactor` trait.
+//! - **`async-io`**, *off* by default – enables the `async` feature and adds Unix domain sockets driven by the reactor of `async-io`, for use with async-std, `smol` and any other executor.
+//! - **`async-std`**, *off* by default – enables the `async-io` feature and provides a `Reactor` implementation built on `async-io`, for use with async-std and similar runtimes.
+//! - **`codec`**, *off* by default – enables the `tokio` feature and adds constructors for length-delimited message framing over the Tokio byte streams, using `tokio-util`.
+//! - **`hyper`**, *off* by default – enables the `tokio` feature and adds a Hyper-compatible connector and listener adapter, for HTTP-based APIs served over local sockets.
+//! - **`io-uring`**, *off* by default – adds batched sends and receives for Unix domain sockets and multishot accepting for their listeners, submitted through a minimal `io_uring` ring of the crate's own; has no effect on platforms other than Linux.
+//! - **`serde`**, *off* by default – implements `Serialize` and `Deserialize` for local socket names.
+//! - **`typed`**, *off* by default – enables the `serde` feature and adds typed channels, which send and receive Serde-serializable values over local sockets using `bincode`, including ones whose ends and attached file descriptors or handles can be sent between processes.
+//! - **`typed-json`**, *off* by default – enables the `typed` feature and adds JSON as a codec for typed channels, for exchanging values with peers written in other languages.
+//! - **`typed-cbor`**, *off* by default – enables the `typed` feature and adds CBOR as a codec for typed channels.
+//! - **`rpc`**, *off* by default – enables the `tokio` and `typed` features and adds a request/response layer, with a client that multiplexes concurrent calls over one local socket connection and a server that dispatches them to a handler.
+//! - **`bytemuck`**, *off* by default – adds views of shared memory mappings as values and slices of plain old data types, checked for size and alignment with the help of `bytemuck`, along with a versioned header that guards against mismatched layouts and an allocator for boxes and vectors inside of shared memory.
 //!
 //! # License
 //! This crate, along with all community contributions made to it, is dual-licensed under the terms of either the [MIT license] or the [Apache 2.0 license].
@@ -31,7 +50,6 @@
 //! [MIT license]: https://choosealicense.com/licenses/mit/ " "
 //! [Apache 2.0 license]: https://choosealicense.com/licenses/apache-2.0/ " "
 // TODO mailslots
-// TODO shared memory
 // TODO use standard library raw+owned FDs and handles
 // TODO the Intra Doc Link Sweep
 // - **Mailslots** – Windows-specific interprocess communication primitive for short messages, potentially even across the network
@@ -82,9 +100,23 @@ compile_error!("Platforms with exotic pointer widths (neither 32-bit nor 64-bit)
 #[macro_use]
 mod macros;
 
+pub mod drop_policy;
+pub mod error;
+pub use error::{Error, ErrorKind};
 pub mod local_socket;
+pub mod platform;
+pub mod pubsub;
+pub mod select;
+#[cfg(not(target_os = "android"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(not(target_os = "android"))))]
+pub mod shared_memory;
+pub mod signal;
+pub mod spawn;
+#[cfg(not(target_os = "android"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(not(target_os = "android"))))]
+pub mod sync;
+pub mod timeout;
 pub mod unnamed_pipe;
-//pub mod shared_memory;
 
 pub mod os;
 
@@ -92,3 +124,38 @@ mod sealed;
 pub(crate) use sealed::Sealed;
 
 pub mod reliable_recv_msg;
+
+#[cfg(feature = "async")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "async")))]
+pub mod reactor;
+
+#[cfg(feature = "codec")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "codec")))]
+pub mod codec;
+
+#[cfg(feature = "hyper")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "hyper")))]
+pub mod hyper;
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod heartbeat;
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod mux;
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod msg_adapters;
+
+#[cfg(feature = "typed")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "typed")))]
+pub mod typed;
+
+#[cfg(feature = "rpc")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rpc")))]
+pub mod rpc;
+
+mod handle_conversion;
+mod listener_close;
@@ -0,0 +1,112 @@
+//! Machinery for closing listeners while other tasks are waiting for incoming connections.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+};
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering::*},
+        Mutex, PoisonError,
+    },
+    task::{Context, Waker},
+};
+#[cfg(feature = "tokio")]
+use {
+    std::{
+        future::{poll_fn, Future},
+        task::Poll,
+    },
+    tokio::sync::Notify,
+};
+
+/// Error produced by `accept()` on a listener which has been closed via its `close()` method.
+///
+/// The error is delivered wrapped in an [`io::Error`] of kind [`Other`](io::ErrorKind::Other) – use [`ListenerClosedError::is_in`] to check for it without downcasting manually, or check for [`ErrorKind::ListenerClosed`](crate::ErrorKind::ListenerClosed) on the crate's [`Error`](crate::Error) type.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ListenerClosedError;
+impl ListenerClosedError {
+    /// Returns `true` if the given I/O error was produced because the listener has been closed, `false` otherwise.
+    pub fn is_in(err: &io::Error) -> bool {
+        err.get_ref().map_or(false, |e| e.is::<Self>())
+    }
+}
+impl Display for ListenerClosedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the listener has been closed")
+    }
+}
+impl Error for ListenerClosedError {}
+impl From<ListenerClosedError> for io::Error {
+    fn from(e: ListenerClosedError) -> Self {
+        io::Error::new(io::ErrorKind::Other, e)
+    }
+}
+
+/// A flag which, once raised, wakes up every task waiting on it via [`race`](Self::race) or
+/// [`poll_closed`](Self::poll_closed).
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+#[derive(Debug, Default)]
+pub(crate) struct AsyncCloseSignal {
+    closed: AtomicBool,
+    #[cfg(feature = "tokio")]
+    notify: Notify,
+    /// Tasks which are polling the listener by hand rather than through a future.
+    wakers: Mutex<Vec<Waker>>,
+}
+#[cfg(any(feature = "tokio", feature = "async-io"))]
+impl AsyncCloseSignal {
+    pub fn close(&self) {
+        self.closed.store(true, Release);
+        #[cfg(feature = "tokio")]
+        self.notify.notify_waiters();
+        let wakers = std::mem::take(&mut *self.wakers.lock().unwrap_or_else(PoisonError::into_inner));
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+    /// Registers the task to be woken up once the signal is raised, returning whether it already has been. For use
+    /// by `poll_*` methods, which should call [`forget`](Self::forget) once they return `Poll::Ready`.
+    pub fn poll_closed(&self, cx: &mut Context<'_>) -> bool {
+        {
+            // Registering before checking the flag ensures that a `close()` in between the two can't slip by unnoticed.
+            let mut wakers = self.wakers.lock().unwrap_or_else(PoisonError::into_inner);
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+        }
+        self.is_closed()
+    }
+    /// Unregisters a task registered by [`poll_closed`](Self::poll_closed), so that wakers of tasks which are done
+    /// accepting don't pile up.
+    pub fn forget(&self, cx: &Context<'_>) {
+        self.wakers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|w| !w.will_wake(cx.waker()));
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Acquire)
+    }
+    /// Drives the given future to completion unless the signal is raised first, in which case the future is dropped and [`ListenerClosedError`] is returned.
+    #[cfg(feature = "tokio")]
+    pub async fn race<T>(&self, fut: impl Future<Output = io::Result<T>>) -> io::Result<T> {
+        let notified = self.notify.notified();
+        tokio::pin!(notified, fut);
+        // Register interest before checking the flag so that a `close()` in between the two
+        // can't slip by unnoticed.
+        notified.as_mut().enable();
+        if self.is_closed() {
+            return Err(ListenerClosedError.into());
+        }
+        poll_fn(|cx| {
+            if notified.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(ListenerClosedError.into()));
+            }
+            fut.as_mut().poll(cx)
+        })
+        .await
+    }
+}
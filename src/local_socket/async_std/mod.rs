@@ -0,0 +1,86 @@
+//! Asynchronous local sockets which work with async-std and other runtimes built on `async-io`, such as `smol`.
+//!
+//! Unlike the [Tokio integration](super::tokio), this one doesn't depend on any particular runtime being active: on
+//! Unix, the sockets are registered with the global reactor of `async-io`, which async-std itself uses; on Windows,
+//! where `async-io` cannot drive named pipes, the I/O is performed on the thread pool of the `blocking` crate. As a
+//! result, the types from this module can be used from any executor without embedding a second runtime.
+//!
+//! The types are aliases of the [generic asynchronous local sockets](super::generic) with
+//! [`AsyncIo`] as the reactor.
+//!
+//! # Platform-specific behavior
+//! ## Windows
+//! Since the named pipes are not opened for overlapped I/O, a read that's waiting for data blocks writes to the same
+//! connection until it completes, including writes to its other half. Dropping a pending `accept()` or read doesn't
+//! cancel the operation on the thread pool – a client accepted that way is dropped once the operation completes.
+
+use {super::generic, crate::reactor::AsyncIo};
+
+/// An async-std-compatible local socket server, listening for connections.
+///
+/// # Examples
+///
+/// ## Basic server
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+/// use interprocess::local_socket::{async_std::LocalSocketListener, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example.sock",
+///         OnlyNamespaced | Both => "@example.sock",
+///     }
+/// };
+/// let listener = LocalSocketListener::bind(name)?;
+///
+/// loop {
+///     let conn = listener.accept().await?;
+///     let (reader, mut writer) = conn.into_split();
+///     let mut buffer = String::with_capacity(128);
+///     BufReader::new(reader).read_line(&mut buffer).await?;
+///     writer.write_all(b"Hello from server!\n").await?;
+///     println!("Client answered: {}", buffer.trim());
+/// }
+/// # std::io::Result::<()>::Ok(()) }).unwrap();
+/// ```
+pub type LocalSocketListener = generic::LocalSocketListener<AsyncIo>;
+
+/// An async-std-compatible local socket byte stream, obtained either from [`LocalSocketListener`] or by connecting to
+/// an existing local socket.
+///
+/// # Examples
+///
+/// ## Basic client
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+/// use interprocess::local_socket::{async_std::LocalSocketStream, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example.sock",
+///         OnlyNamespaced | Both => "@example.sock",
+///     }
+/// };
+/// let conn = LocalSocketStream::connect(name).await?;
+/// let (reader, mut writer) = conn.into_split();
+///
+/// writer.write_all(b"Hello from client!\n").await?;
+/// let mut buffer = String::with_capacity(128);
+/// BufReader::new(reader).read_line(&mut buffer).await?;
+/// println!("Server answered: {}", buffer.trim());
+/// # std::io::Result::<()>::Ok(()) }).unwrap();
+/// ```
+pub type LocalSocketStream = generic::LocalSocketStream<AsyncIo>;
+
+/// An owned read half of an async-std-compatible local socket stream, obtained by splitting a [`LocalSocketStream`].
+pub type OwnedReadHalf = generic::OwnedReadHalf<AsyncIo>;
+
+/// An owned write half of an async-std-compatible local socket stream, obtained by splitting a [`LocalSocketStream`].
+pub type OwnedWriteHalf = generic::OwnedWriteHalf<AsyncIo>;
+
+/// Error type for `.reunite()` on owned read and write halves of an async-std-compatible local socket stream.
+pub type ReuniteError = generic::ReuniteError<AsyncIo>;
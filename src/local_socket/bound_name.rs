@@ -0,0 +1,65 @@
+use {
+    super::LocalSocketName,
+    std::{
+        borrow::Cow,
+        fmt::{self, Debug, Formatter},
+        fs, io,
+        mem::ManuallyDrop,
+        path::Path,
+    },
+};
+
+/// The name a local socket listener has been bound to, which removes the socket file when dropped – including during
+/// unwinding from a panic.
+///
+/// Obtained from [`LocalSocketListener::bind_guarded()`](super::LocalSocketListener::bind_guarded) and
+/// [`LocalSocketListenerOptions::bind_guarded()`](super::LocalSocketListenerOptions::bind_guarded). Socket files are
+/// not removed by the OS when the listener goes away, and a leftover one makes binding to the same name fail, so
+/// servers which use file paths need to clean up after themselves; this type does so in one place. Namespaced names
+/// don't leave anything behind, which makes the guard a no-op for them – code that uses it works the same regardless of
+/// the name type.
+///
+/// The guard is independent from the listener: if it's dropped first, the file is removed while the listener keeps
+/// accepting connections from clients that already had it open, but new clients won't be able to find the server.
+pub struct BoundName {
+    name: LocalSocketName<'static>,
+}
+impl BoundName {
+    pub(super) fn new(name: LocalSocketName<'_>) -> Self {
+        let namespaced = name.is_namespaced();
+        Self {
+            name: LocalSocketName::from_raw_parts(Cow::Owned(name.into_inner()), namespaced),
+        }
+    }
+    /// Returns the name the listener has been bound to.
+    pub fn name(&self) -> &LocalSocketName<'static> {
+        &self.name
+    }
+    /// Disarms the guard, leaving the socket file in place, and returns the name.
+    pub fn keep(self) -> LocalSocketName<'static> {
+        let slf = ManuallyDrop::new(self);
+        // SAFETY: the guard is never used again, and its destructor is suppressed
+        unsafe { std::ptr::read(&slf.name) }
+    }
+    /// Removes the socket file right away, returning an error if that fails rather than ignoring it like dropping the
+    /// guard does. Succeeds without doing anything for namespaced names.
+    pub fn remove(self) -> io::Result<()> {
+        let name = self.keep();
+        match name.is_path() {
+            true => fs::remove_file(Path::new(name.inner())),
+            false => Ok(()),
+        }
+    }
+}
+impl Drop for BoundName {
+    fn drop(&mut self) {
+        if self.name.is_path() {
+            let _ = fs::remove_file(Path::new(self.name.inner()));
+        }
+    }
+}
+impl Debug for BoundName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BoundName").field(&self.name).finish()
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(windows)]
+use std::ffi::OsString;
+use {
+    super::{is_reportable, ConnectionEvent, LocalSocketHooks, LocalSocketName, LocalSocketStream, ToLocalSocketName},
+    crate::Sealed,
+    std::{
+        io,
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// A builder for connecting to local socket servers which may not be up yet, retrying failed attempts with exponential
+/// backoff.
+///
+/// This covers the window during which a server is starting up or restarting: until it has bound its name, connecting
+/// fails with [`NotFound`](io::ErrorKind::NotFound) (the socket file or the named pipe doesn't exist) or
+/// [`ConnectionRefused`](io::ErrorKind::ConnectionRefused) (a stale socket file is left behind). Only those errors are
+/// retried – any other one is returned immediately. Waiting for a busy named pipe server to dispatch an instance is
+/// already handled by [`LocalSocketStream::connect()`] and doesn't count as a retry.
+///
+/// By default, there are no retries, which makes [`.connect()`](Self::connect) equivalent to
+/// [`LocalSocketStream::connect()`].
+///
+/// On Windows, the computer on which the server is to be found can be set through
+/// `os::windows::LocalSocketConnectOptionsExt`.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::LocalSocketConnectOptions;
+/// use std::time::Duration;
+///
+/// let conn = LocalSocketConnectOptions::new()
+///     .retries(u32::MAX)
+///     .deadline(Duration::from_secs(5))
+///     .connect("@example.sock")?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalSocketConnectOptions {
+    retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    deadline: Option<Duration>,
+    hooks: Option<Arc<dyn LocalSocketHooks>>,
+    #[cfg(windows)]
+    pub(crate) hostname: Option<OsString>,
+}
+impl LocalSocketConnectOptions {
+    /// Creates a new builder with default options: no retries, a backoff starting at 10 milliseconds and capped at one
+    /// second, and no deadline.
+    pub fn new() -> Self {
+        Self {
+            retries: 0,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            deadline: None,
+            hooks: None,
+            #[cfg(windows)]
+            hostname: None,
+        }
+    }
+    /// Sets how many times a failed attempt to connect is retried. By default, it is 0.
+    ///
+    /// Use `u32::MAX` together with a [deadline](Self::deadline) to keep retrying until the deadline passes.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+    /// Sets how long to wait before the first retry and the upper limit of the wait, which doubles after every retry.
+    /// By default, the wait starts at 10 milliseconds and is capped at one second.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+    /// Sets the total amount of time after which no more attempts are made, counting from the call to
+    /// [`.connect()`](Self::connect). By default, there is no deadline, and only the number of retries limits the
+    /// attempts.
+    ///
+    /// The last wait is shortened so as not to overshoot the deadline, and the error of the last attempt is returned
+    /// once it passes.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+    /// Sets the [hooks](LocalSocketHooks) which are called once the connection is established or the last attempt
+    /// fails. By default, there are none.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn hooks(mut self, hooks: Arc<dyn LocalSocketHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+    /// Connects to a local socket server with the specified name, retrying according to the options of the builder.
+    pub fn connect<'a>(&self, name: impl ToLocalSocketName<'a>) -> io::Result<LocalSocketStream> {
+        let name = name.to_local_socket_name()?;
+        let result = self.connect_with_retries(&name);
+        if let Some(hooks) = &self.hooks {
+            match &result {
+                Ok(conn) => hooks.on_connect(&ConnectionEvent {
+                    name: &name,
+                    peer_pid: conn.peer_pid().ok(),
+                }),
+                Err(e) if is_reportable(e) => hooks.on_error(
+                    &ConnectionEvent {
+                        name: &name,
+                        peer_pid: None,
+                    },
+                    e,
+                ),
+                Err(..) => {}
+            }
+        }
+        result
+    }
+    fn connect_with_retries(&self, name: &LocalSocketName<'_>) -> io::Result<LocalSocketStream> {
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+        let mut backoff = self.initial_backoff;
+        let mut retries_left = self.retries;
+        loop {
+            let e = match self.connect_once(name) {
+                Err(e) if is_retryable(&e) && retries_left > 0 => e,
+                els => return els,
+            };
+            let wait = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(e);
+                    }
+                    backoff.min(remaining)
+                }
+                None => backoff,
+            };
+            thread::sleep(wait);
+            retries_left -= 1;
+            backoff = backoff.saturating_mul(2).min(self.max_backoff);
+        }
+    }
+    fn connect_once(&self, name: &LocalSocketName<'_>) -> io::Result<LocalSocketStream> {
+        #[cfg(windows)]
+        if let Some(hostname) = &self.hostname {
+            return LocalSocketStream::connect_to_remote(name.clone(), hostname);
+        }
+        LocalSocketStream::connect(name.clone())
+    }
+}
+impl Default for LocalSocketConnectOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Sealed for LocalSocketConnectOptions {}
+
+/// Whether the error means that the server is not there (yet).
+fn is_retryable(e: &io::Error) -> bool {
+    matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused)
+}
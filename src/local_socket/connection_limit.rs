@@ -0,0 +1,116 @@
+use {
+    super::ListenerClosedError,
+    std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering::*},
+            Arc, Condvar, Mutex,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// Error produced by `accept()` on a nonblocking listener which already has as many live connections as allowed by
+/// [`max_concurrent_connections`](super::LocalSocketListenerOptions::max_concurrent_connections).
+///
+/// The error is delivered wrapped in an [`io::Error`] of kind [`WouldBlock`](io::ErrorKind::WouldBlock), so that
+/// nonblocking accept loops treat it like the absence of a client – use [`ConnectionLimitError::is_in`] to tell the two
+/// apart.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConnectionLimitError;
+impl ConnectionLimitError {
+    /// Returns `true` if the given I/O error was produced because the connection limit has been reached, `false`
+    /// otherwise.
+    pub fn is_in(err: &io::Error) -> bool {
+        err.get_ref().map_or(false, |e| e.is::<Self>())
+    }
+}
+impl Display for ConnectionLimitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the maximum number of concurrent connections has been reached")
+    }
+}
+impl Error for ConnectionLimitError {}
+impl From<ConnectionLimitError> for io::Error {
+    fn from(e: ConnectionLimitError) -> Self {
+        io::Error::new(io::ErrorKind::WouldBlock, e)
+    }
+}
+
+/// Counts the live connections produced by a listener, making `accept()` wait while there are too many of them.
+#[derive(Debug)]
+pub(crate) struct ConnectionLimit {
+    max: usize,
+    live: Mutex<usize>,
+    freed: Condvar,
+    /// Mirrors the nonblocking mode of the listener, which decides between waiting for a slot and failing right away.
+    nonblocking: AtomicBool,
+}
+impl ConnectionLimit {
+    pub fn new(max: usize, nonblocking: bool) -> Arc<Self> {
+        Arc::new(Self {
+            max,
+            live: Mutex::new(0),
+            freed: Condvar::new(),
+            nonblocking: AtomicBool::new(nonblocking),
+        })
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Relaxed);
+    }
+    /// Reserves a slot for a connection which is about to be accepted, waiting for one to free up unless the listener is
+    /// in nonblocking mode. No timeout means waiting indefinitely.
+    pub fn acquire(
+        self: &Arc<Self>,
+        timeout: Option<Duration>,
+        is_closed: impl Fn() -> bool,
+    ) -> io::Result<ConnectionPermit> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut live = self.live.lock().expect("unexpected lock poison");
+        while *live >= self.max {
+            if is_closed() {
+                return Err(ListenerClosedError.into());
+            }
+            if self.nonblocking.load(Relaxed) {
+                return Err(ConnectionLimitError.into());
+            }
+            live = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining == Duration::ZERO {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "no connection slot became free before the timeout expired",
+                        ));
+                    }
+                    self.freed
+                        .wait_timeout(live, remaining)
+                        .expect("unexpected lock poison")
+                        .0
+                }
+                None => self.freed.wait(live).expect("unexpected lock poison"),
+            };
+        }
+        *live += 1;
+        Ok(ConnectionPermit(Arc::clone(self)))
+    }
+    /// Wakes up every thread waiting for a slot, so that they can notice that the listener has been closed.
+    pub fn wake_all(&self) {
+        // Taking the lock ensures that a waiter which has just checked the flag is already waiting on the condvar.
+        let _live = self.live.lock().expect("unexpected lock poison");
+        self.freed.notify_all();
+    }
+}
+
+/// A slot taken up by a live connection, freed when dropped.
+#[derive(Debug)]
+pub(crate) struct ConnectionPermit(Arc<ConnectionLimit>);
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let mut live = self.0.live.lock().expect("unexpected lock poison");
+        *live -= 1;
+        self.0.freed.notify_one();
+    }
+}
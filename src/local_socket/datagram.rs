@@ -0,0 +1,113 @@
+use {
+    super::{LocalSocketName, ToLocalSocketName},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+    },
+};
+
+impmod! {local_socket,
+    LocalSocketDatagram as LocalSocketDatagramImpl
+}
+
+/// A local socket for sending and receiving individual datagrams, without establishing a connection.
+///
+/// On Unix, this is a `SOCK_DGRAM` Ud-socket. On Windows, a [mailslot](https://learn.microsoft.com/en-us/windows/win32/ipc/mailslots) is used – mailslots live in their own namespace, separate from that of named pipes, which means that a `LocalSocketDatagram` and a [`LocalSocketListener`](super::LocalSocketListener) can be bound to the same name without conflicting.
+///
+/// # Platform-specific behavior
+/// ## Windows
+/// Mailslots don't keep track of who sent a datagram, so [`recv_from()`](Self::recv_from) never reports the sender. If the buffer passed to a receive method is too small for the datagram, an error is returned and the datagram stays in the mailslot, whereas on Unix the excess bytes of the datagram are discarded.
+///
+/// # Examples
+///
+/// ## Receiving end
+/// ```no_run
+/// use interprocess::local_socket::{LocalSocketDatagram, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_dgram.sock",
+///         OnlyNamespaced | Both => "@example_dgram.sock",
+///     }
+/// };
+/// let socket = LocalSocketDatagram::bind(name)?;
+///
+/// let mut buffer = [0; 128];
+/// let size = socket.recv(&mut buffer)?;
+/// println!("Got a datagram: {}", String::from_utf8_lossy(&buffer[..size]));
+/// # std::io::Result::<()>::Ok(())
+/// ```
+///
+/// ## Sending end
+/// ```no_run
+/// use interprocess::local_socket::{LocalSocketDatagram, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_dgram.sock",
+///         OnlyNamespaced | Both => "@example_dgram.sock",
+///     }
+/// };
+/// let socket = LocalSocketDatagram::unbound()?;
+/// socket.send_to(b"Hello from sender!", name)?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct LocalSocketDatagram {
+    inner: LocalSocketDatagramImpl,
+}
+impl LocalSocketDatagram {
+    /// Creates a socket which receives datagrams sent to the given name.
+    ///
+    /// On Unix, the socket file, if one is created, is deleted once the socket is dropped.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketDatagramImpl::bind(name)?,
+        })
+    }
+    /// Creates a socket which isn't bound to any name. Such a socket can only be used to send datagrams, and its datagrams cannot be replied to.
+    pub fn unbound() -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketDatagramImpl::unbound()?,
+        })
+    }
+    /// Sets the default destination for [`send()`](Self::send).
+    ///
+    /// On Windows, this opens the destination mailslot, meaning that it must already exist.
+    pub fn connect<'a>(&mut self, name: impl ToLocalSocketName<'a>) -> io::Result<()> {
+        self.inner.connect(name)
+    }
+    /// Sends a datagram to the destination previously set via [`connect()`](Self::connect), returning how many bytes were sent.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+    /// Sends a datagram to the given name, returning how many bytes were sent.
+    pub fn send_to<'a>(&self, buf: &[u8], name: impl ToLocalSocketName<'a>) -> io::Result<usize> {
+        self.inner.send_to(buf, name)
+    }
+    /// Receives a single datagram, returning its size.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.recv(buf)
+    }
+    /// Receives a single datagram, returning its size and the name of the sender, if it has one and the platform reports it.
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, Option<LocalSocketName<'static>>)> {
+        self.inner.recv_from(buf)
+    }
+    /// Enables or disables the nonblocking mode for the socket. By default, it is disabled.
+    ///
+    /// In nonblocking mode, receiving will immediately return with the [`WouldBlock`](io::ErrorKind::WouldBlock) error if there are no datagrams available.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LocalSocketDatagram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl_handle_manip_unix!(LocalSocketDatagram);
+// Unbound datagram sockets have no handle on Windows, so only the raw handle traits are implemented there.
+impl_as_raw_handle_windows!(LocalSocketDatagram);
+impl_into_raw_handle_windows!(LocalSocketDatagram);
+impl_from_raw_handle_windows!(LocalSocketDatagram);
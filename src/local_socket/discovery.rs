@@ -0,0 +1,44 @@
+use {
+    super::LocalSocketName,
+    std::{ffi::OsStr, io},
+};
+
+impmod! {local_socket,
+    enumerate_servers as enumerate_servers_impl,
+}
+
+/// Lists the local sockets that servers are currently listening on and whose names start with the given prefix, for
+/// finding the available endpoints at runtime.
+///
+/// File paths are matched by their file name rather than the whole path, so the prefix is the same for both name types,
+/// such as the one set with [`LocalSocketNameMapper::prefix()`](super::LocalSocketNameMapper::prefix). An empty prefix
+/// lists every server that can be found. The result is only a snapshot: servers may go away, or new ones appear, right
+/// after it is taken.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{enumerate_servers, LocalSocketStream};
+///
+/// for name in enumerate_servers("my-app-")? {
+///     if let Ok(_conn) = LocalSocketStream::connect(name.clone()) {
+///         println!("found a server at {:?}", name);
+///     }
+/// }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+///
+/// # Platform-specific behavior
+/// ## Linux and Android
+/// Listening stream sockets are read from `/proc/net/unix`, which includes namespaced sockets and sockets in any
+/// directory, but only the ones in the network namespace of the calling process. Listeners whose socket file has been
+/// removed are skipped.
+/// ## Other Unix platforms
+/// There is no way to list listening sockets, so the socket files in the directories where local sockets are
+/// conventionally placed are listed instead: the per-user runtime directory (`XDG_RUNTIME_DIR`), the temporary directory
+/// and `/tmp`. Socket files left behind by servers which have exited are included.
+/// ## Windows
+/// The `\\.\pipe\` directory is enumerated, which lists every named pipe on the system, not just those created through
+/// local sockets. Names longer than `MAX_PATH` are truncated by the OS.
+pub fn enumerate_servers(prefix: impl AsRef<OsStr>) -> io::Result<Vec<LocalSocketName<'static>>> {
+    enumerate_servers_impl(prefix.as_ref())
+}
@@ -0,0 +1,46 @@
+use {
+    super::{super::ToLocalSocketName, LocalSocketStream},
+    crate::reactor::Reactor,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+    },
+};
+
+impmod! {local_socket::generic,
+    LocalSocketListener as LocalSocketListenerImpl
+}
+
+/// A runtime-agnostic local socket server, listening for connections, driven by the reactor `R`.
+///
+/// See [`async_std::LocalSocketListener`](super::super::async_std::LocalSocketListener) for an example.
+pub struct LocalSocketListener<R: Reactor> {
+    inner: LocalSocketListenerImpl<R>,
+}
+impl<R: Reactor> LocalSocketListener<R> {
+    /// Creates a socket server with the specified local socket name.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketListenerImpl::bind(name)?,
+        })
+    }
+    /// Listens for incoming connections to the socket, asynchronously waiting until a client is connected.
+    pub async fn accept(&self) -> io::Result<LocalSocketStream<R>> {
+        Ok(LocalSocketStream {
+            inner: self.inner.accept().await?,
+        })
+    }
+}
+impl<R: Reactor> Debug for LocalSocketListener<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+#[cfg(unix)]
+impl<R: Reactor> std::os::unix::io::AsRawFd for LocalSocketListener<R> {
+    #[inline]
+    fn as_raw_fd(&self) -> libc::c_int {
+        self.inner.as_raw_fd()
+    }
+}
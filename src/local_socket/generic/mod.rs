@@ -0,0 +1,21 @@
+//! Asynchronous local sockets which work with any runtime, as long as it implements [`Reactor`].
+//!
+//! The types in this module are generic over the reactor that drives them, and thus adding support for a new runtime
+//! doesn't require duplicating them – implementing [`Reactor`] for a marker type is enough. The [`async_std`]
+//! module, for instance, consists solely of type aliases which plug in [`AsyncIo`](crate::reactor::AsyncIo).
+//!
+//! # Platform-specific behavior
+//! ## Windows
+//! Since the named pipes are not opened for overlapped I/O, all operations are performed on the thread pool provided
+//! by the reactor. A read that's waiting for data blocks writes to the same connection until it completes, including
+//! writes to its other half. Dropping a pending `accept()` or read doesn't cancel the operation on the thread pool – a
+//! client accepted that way is dropped once the operation completes.
+//!
+//! [`Reactor`]: crate::reactor::Reactor
+//! [`async_std`]: super::async_std
+
+mod listener;
+pub use listener::*;
+
+mod stream;
+pub use stream::*;
@@ -0,0 +1,163 @@
+mod read_half;
+pub use read_half::*;
+
+mod write_half;
+pub use write_half::*;
+
+use {
+    super::super::{LocalSocketPeerCredentials, ToLocalSocketName},
+    crate::reactor::Reactor,
+    futures_io::{AsyncRead, AsyncWrite},
+    std::{
+        error::Error,
+        fmt::{self, Debug, Display, Formatter},
+        io::{self, IoSlice, IoSliceMut},
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+impmod! {local_socket::generic,
+    LocalSocketStream as LocalSocketStreamImpl
+}
+
+/// A runtime-agnostic local socket byte stream, driven by the reactor `R` and obtained either from
+/// [`LocalSocketListener`](super::LocalSocketListener) or by connecting to an existing local socket.
+///
+/// See [`async_std::LocalSocketStream`](super::super::async_std::LocalSocketStream) for an example.
+pub struct LocalSocketStream<R: Reactor> {
+    pub(super) inner: LocalSocketStreamImpl<R>,
+}
+impl<R: Reactor> LocalSocketStream<R> {
+    /// Connects to a remote local socket server.
+    #[inline]
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketStreamImpl::connect(name).await.map(Self::from)
+    }
+    /// Splits a stream into a read half and a write half, which can be used to read and write the stream concurrently.
+    ///
+    /// The halves can be put back together with [`OwnedReadHalf::reunite()`] or [`OwnedWriteHalf::reunite()`].
+    #[inline]
+    pub fn into_split(self) -> (OwnedReadHalf<R>, OwnedWriteHalf<R>) {
+        let (r, w) = self.inner.into_split();
+        (OwnedReadHalf { inner: r }, OwnedWriteHalf { inner: w })
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`](crate::local_socket::LocalSocketStream::peer_pid) for the
+    /// platform-specific behavior.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.inner.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`](crate::local_socket::LocalSocketStream::peer_credentials) for the
+    /// details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    #[inline]
+    fn pinproj(&mut self) -> Pin<&mut LocalSocketStreamImpl<R>> {
+        Pin::new(&mut self.inner)
+    }
+}
+impl<R: Reactor> From<LocalSocketStreamImpl<R>> for LocalSocketStream<R> {
+    #[inline]
+    fn from(inner: LocalSocketStreamImpl<R>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R: Reactor> AsyncRead for LocalSocketStream<R> {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_read(cx, buf)
+    }
+    #[inline]
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_read_vectored(cx, bufs)
+    }
+}
+impl<R: Reactor> AsyncWrite for LocalSocketStream<R> {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_write(cx, buf)
+    }
+    #[inline]
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_write_vectored(cx, bufs)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.pinproj().poll_flush(cx)
+    }
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.pinproj().poll_close(cx)
+    }
+}
+
+impl<R: Reactor> Debug for LocalSocketStream<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+
+#[cfg(unix)]
+impl<R: Reactor> std::os::unix::io::AsRawFd for LocalSocketStream<R> {
+    #[inline]
+    fn as_raw_fd(&self) -> libc::c_int {
+        self.inner.as_raw_fd()
+    }
+}
+#[cfg(windows)]
+impl<R: Reactor> std::os::windows::io::AsRawHandle for LocalSocketStream<R> {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut std::ffi::c_void {
+        self.inner.as_raw_handle()
+    }
+}
+
+fn reunite<R: Reactor>(rh: OwnedReadHalf<R>, wh: OwnedWriteHalf<R>) -> Result<LocalSocketStream<R>, ReuniteError<R>> {
+    LocalSocketStreamImpl::reunite(rh.inner, wh.inner)
+        .map(LocalSocketStream::from)
+        .map_err(|(r, w)| ReuniteError {
+            read_half: OwnedReadHalf { inner: r },
+            write_half: OwnedWriteHalf { inner: w },
+        })
+}
+
+/// Error type for `.reunite()` on owned read and write halves of a runtime-agnostic local socket stream.
+///
+/// The error indicates that the halves belong to different streams and allows to recover both of them.
+pub struct ReuniteError<R: Reactor> {
+    /// The read half that didn't go anywhere, in case you still need it.
+    pub read_half: OwnedReadHalf<R>,
+    /// The write half that didn't go anywhere, in case you still need it.
+    pub write_half: OwnedWriteHalf<R>,
+}
+impl<R: Reactor> Debug for ReuniteError<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReuniteError")
+            .field("read_half", &self.read_half)
+            .field("write_half", &self.write_half)
+            .finish()
+    }
+}
+impl<R: Reactor> Display for ReuniteError<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the read and write halves belong to different local socket streams")
+    }
+}
+impl<R: Reactor> Error for ReuniteError<R> {}
@@ -0,0 +1,68 @@
+use {
+    super::{LocalSocketStream, OwnedWriteHalf, ReuniteError},
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncRead,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, IoSliceMut},
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+impmod! {local_socket::generic,
+    OwnedReadHalf as OwnedReadHalfImpl
+}
+
+/// An owned read half of a runtime-agnostic local socket stream, obtained by splitting a [`LocalSocketStream`].
+pub struct OwnedReadHalf<R: Reactor> {
+    pub(super) inner: OwnedReadHalfImpl<R>,
+}
+impl<R: Reactor> OwnedReadHalf<R> {
+    /// Attempts to reunite this read half with the given write half to yield the original stream back, returning both
+    /// halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedWriteHalf<R>) -> Result<LocalSocketStream<R>, ReuniteError<R>> {
+        super::reunite(self, other)
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.inner.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    #[inline]
+    fn pinproj(&mut self) -> Pin<&mut OwnedReadHalfImpl<R>> {
+        Pin::new(&mut self.inner)
+    }
+}
+
+impl<R: Reactor> AsyncRead for OwnedReadHalf<R> {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_read(cx, buf)
+    }
+    #[inline]
+    fn poll_read_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_read_vectored(cx, bufs)
+    }
+}
+
+impl<R: Reactor> Debug for OwnedReadHalf<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
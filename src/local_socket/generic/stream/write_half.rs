@@ -0,0 +1,76 @@
+use {
+    super::{LocalSocketStream, OwnedReadHalf, ReuniteError},
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncWrite,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, IoSlice},
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+impmod! {local_socket::generic,
+    OwnedWriteHalf as OwnedWriteHalfImpl
+}
+
+/// An owned write half of a runtime-agnostic local socket stream, obtained by splitting a [`LocalSocketStream`].
+pub struct OwnedWriteHalf<R: Reactor> {
+    pub(super) inner: OwnedWriteHalfImpl<R>,
+}
+impl<R: Reactor> OwnedWriteHalf<R> {
+    /// Attempts to reunite this write half with the given read half to yield the original stream back, returning both
+    /// halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedReadHalf<R>) -> Result<LocalSocketStream<R>, ReuniteError<R>> {
+        super::reunite(other, self)
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.inner.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    #[inline]
+    fn pinproj(&mut self) -> Pin<&mut OwnedWriteHalfImpl<R>> {
+        Pin::new(&mut self.inner)
+    }
+}
+
+impl<R: Reactor> AsyncWrite for OwnedWriteHalf<R> {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_write(cx, buf)
+    }
+    #[inline]
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.pinproj().poll_write_vectored(cx, bufs)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.pinproj().poll_flush(cx)
+    }
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.pinproj().poll_close(cx)
+    }
+}
+
+impl<R: Reactor> Debug for OwnedWriteHalf<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
@@ -0,0 +1,130 @@
+use {
+    super::{ConnectionLimitError, ListenerClosedError, LocalSocketName, RateLimitError},
+    std::{
+        borrow::Cow,
+        fmt::{self, Debug, Formatter},
+        io,
+        sync::Arc,
+    },
+};
+
+/// Callbacks invoked by local socket listeners and [connect builders](super::LocalSocketConnectOptions) as connections
+/// are established, turned away or fail, for audit logging and metrics without wrapping every call site.
+///
+/// Every method does nothing by default, so implementors only need to override the ones they're interested in. The
+/// hooks are called synchronously on the thread that accepts or connects, so they should be quick – anything slow is
+/// better handed off to another thread.
+///
+/// Registered with [`LocalSocketListenerOptions::hooks()`](super::LocalSocketListenerOptions::hooks) and
+/// [`LocalSocketConnectOptions::hooks()`](super::LocalSocketConnectOptions::hooks). Both take an `Arc`, which allows
+/// one set of hooks to observe several listeners and clients.
+///
+/// # Example
+/// ```no_run
+/// use {
+///     interprocess::local_socket::{ConnectionEvent, LocalSocketHooks, LocalSocketListenerOptions},
+///     std::sync::{
+///         atomic::{AtomicUsize, Ordering},
+///         Arc,
+///     },
+/// };
+///
+/// #[derive(Default)]
+/// struct Metrics {
+///     accepted: AtomicUsize,
+/// }
+/// impl LocalSocketHooks for Metrics {
+///     fn on_accept(&self, event: &ConnectionEvent<'_>) {
+///         self.accepted.fetch_add(1, Ordering::Relaxed);
+///         eprintln!("accepted a client with PID {:?} on {:?}", event.peer_pid, event.name);
+///     }
+/// }
+///
+/// let metrics = Arc::new(Metrics::default());
+/// let listener = LocalSocketListenerOptions::new()
+///     .hooks(metrics.clone())
+///     .bind("@example.sock")?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub trait LocalSocketHooks: Send + Sync {
+    /// Called when a listener accepts a client.
+    fn on_accept(&self, event: &ConnectionEvent<'_>) {
+        let _ = event;
+    }
+    /// Called when a listener turns a client away because of its policy, such as
+    /// [`same_user_only`](super::LocalSocketListenerOptions::same_user_only) or shedding clients beyond the
+    /// [accept rate limit](super::AcceptRateLimit::shed).
+    fn on_reject(&self, event: &ConnectionEvent<'_>) {
+        let _ = event;
+    }
+    /// Called when a client connects to a server.
+    fn on_connect(&self, event: &ConnectionEvent<'_>) {
+        let _ = event;
+    }
+    /// Called when accepting or connecting fails. The process ID of the peer in the event is always `None`.
+    ///
+    /// Errors which are part of the normal flow of a program are not reported: [`WouldBlock`](io::ErrorKind::WouldBlock)
+    /// in nonblocking mode (unless caused by the [connection limit](super::ConnectionLimitError) or the
+    /// [accept rate limit](super::RateLimitError)),
+    /// [`TimedOut`](io::ErrorKind::TimedOut) from `accept_timeout()` and [closure](super::ListenerClosedError) of the
+    /// listener.
+    fn on_error(&self, event: &ConnectionEvent<'_>, error: &io::Error) {
+        let _ = (event, error);
+    }
+}
+impl Debug for dyn LocalSocketHooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("LocalSocketHooks")
+    }
+}
+
+/// Information about a connection passed to [`LocalSocketHooks`].
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct ConnectionEvent<'a> {
+    /// The name the listener is bound to, or the name the client is connecting to.
+    pub name: &'a LocalSocketName<'a>,
+    /// The process ID of the peer, or `None` if it is unknown or not reported by the platform.
+    pub peer_pid: Option<u32>,
+}
+
+/// The hooks of a listener together with its name, which they are called with.
+#[derive(Clone, Debug)]
+pub(crate) struct ListenerHooks {
+    hooks: Arc<dyn LocalSocketHooks>,
+    name: LocalSocketName<'static>,
+}
+impl ListenerHooks {
+    pub fn new(hooks: Option<&Arc<dyn LocalSocketHooks>>, name: &LocalSocketName<'_>) -> Option<Self> {
+        hooks.map(|hooks| Self {
+            hooks: Arc::clone(hooks),
+            name: LocalSocketName::from_raw_parts(Cow::Owned(name.inner().to_owned()), name.is_namespaced()),
+        })
+    }
+    fn event(&self, peer_pid: Option<u32>) -> ConnectionEvent<'_> {
+        ConnectionEvent {
+            name: &self.name,
+            peer_pid,
+        }
+    }
+    pub fn accepted(&self, peer_pid: io::Result<u32>) {
+        self.hooks.on_accept(&self.event(peer_pid.ok()));
+    }
+    pub fn rejected(&self, peer_pid: io::Result<u32>) {
+        self.hooks.on_reject(&self.event(peer_pid.ok()));
+    }
+    pub fn failed(&self, error: &io::Error) {
+        if is_reportable(error) {
+            self.hooks.on_error(&self.event(None), error);
+        }
+    }
+}
+
+/// Whether the error is a failure rather than part of the normal flow of a program.
+pub(crate) fn is_reportable(error: &io::Error) -> bool {
+    match error.kind() {
+        io::ErrorKind::WouldBlock => ConnectionLimitError::is_in(error) || RateLimitError::is_in(error),
+        io::ErrorKind::TimedOut => false,
+        _ => !ListenerClosedError::is_in(error),
+    }
+}
@@ -1,9 +1,15 @@
 use {
-    super::{LocalSocketStream, ToLocalSocketName},
+    super::{
+        BoundName, ConnectionLimit, ConnectionPermit, LocalSocketNameGen, LocalSocketStream, RateLimiter,
+        ToLocalSocketName,
+    },
+    crate::timeout::AcceptTimeout,
     std::{
         fmt::{self, Debug, Formatter},
         io,
         iter::FusedIterator,
+        sync::Arc,
+        time::{Duration, Instant},
     },
 };
 
@@ -31,7 +37,7 @@ impmod! {local_socket,
 ///         }
 ///     }
 /// }
-//// // Pick a name. There isn't a helper function for this, mostly because it's largely unnecessary:
+/// // Pick a name. There isn't a helper function for this, mostly because it's largely unnecessary:
 /// // in Rust, `match` is your concise, readable and expressive decision making construct.
 /// let name = {
 ///     // This scoping trick allows us to nicely contain the import inside the `match`, so that if
@@ -105,24 +111,189 @@ impmod! {local_socket,
 /// # io::Result::<()>::Ok(())
 /// ```
 pub struct LocalSocketListener {
-    inner: LocalSocketListenerImpl,
+    pub(super) inner: LocalSocketListenerImpl,
+    /// Set if the amount of live connections is limited.
+    pub(super) limit: Option<Arc<ConnectionLimit>>,
+    /// Set if the rate at which clients are accepted is limited.
+    pub(super) rate_limit: Option<RateLimiter>,
 }
 impl LocalSocketListener {
     /// Creates a socket server with the specified local socket name.
+    ///
+    /// Use [`LocalSocketListenerOptions`](super::LocalSocketListenerOptions) to set up the listener in a more detailed way.
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         Ok(Self {
             inner: LocalSocketListenerImpl::bind(name)?,
+            limit: None,
+            rate_limit: None,
         })
     }
+    /// Creates a socket server with the specified local socket name, returning a [`BoundName`](super::BoundName) guard
+    /// which removes the socket file when dropped along with it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use interprocess::local_socket::LocalSocketListener;
+    ///
+    /// let (listener, _guard) = LocalSocketListener::bind_guarded("/tmp/example.sock")?;
+    /// // The socket file is removed when the scope is left, even if this panics.
+    /// for conn in listener.incoming() {
+    ///     let _conn = conn?;
+    /// #   break;
+    /// }
+    /// # std::io::Result::<()>::Ok(())
+    /// ```
+    pub fn bind_guarded<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<(Self, BoundName)> {
+        let name = name.to_local_socket_name()?;
+        let listener = Self::bind(name.clone())?;
+        Ok((listener, BoundName::new(name)))
+    }
+    /// Binds a guarded listener to the first free per-user name produced by a [`LocalSocketNameGen`] with the given
+    /// prefix, for connections whose name is handed to the other side out of band.
+    pub(crate) fn bind_generated(prefix: &str) -> io::Result<(Self, BoundName)> {
+        LocalSocketNameGen::new(prefix)
+            .per_user(true)
+            .find_map(|name| match Self::bind_guarded(name) {
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+                els => Some(els),
+            })
+            .expect("the name generator is infinite")
+    }
     /// Listens for incoming connections to the socket, blocking until a client is connected.
     ///
+    /// If the listener is [closed](Self::close) while this is blocked or before it is called, an error wrapping
+    /// [`ListenerClosedError`](super::ListenerClosedError) is returned.
+    ///
+    /// If the listener has a [connection limit](super::LocalSocketListenerOptions::max_concurrent_connections) which
+    /// has been reached, this first waits for one of the live connections to be closed, or fails with an error wrapping
+    /// [`ConnectionLimitError`](super::ConnectionLimitError) in nonblocking mode. Likewise, if the listener has an
+    /// [accept rate limit](super::AcceptRateLimit) which has been reached, this waits until it allows another client,
+    /// fails with an error wrapping [`RateLimitError`](super::RateLimitError) in nonblocking mode, or sheds the clients
+    /// which arrive until then, depending on its configuration.
+    ///
     /// See [`incoming`] for a convenient way to create a main loop for a server.
     ///
     /// [`incoming`]: #method.incoming " "
     pub fn accept(&self) -> io::Result<LocalSocketStream> {
-        Ok(LocalSocketStream {
-            inner: self.inner.accept()?,
-        })
+        self.report((|| {
+            let permit = self.acquire_permit(None)?;
+            self.accept_rate_limited(None, permit)
+        })())
+    }
+    /// Listens for incoming connections to the socket, blocking until a client is connected or the specified amount of time passes, in which case a [`TimedOut`] error is returned.
+    ///
+    /// This allows a single-threaded server to periodically get control back to do other work without switching the listener to nonblocking mode. A zero timeout checks for a pending client without waiting at all.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// Since named pipe listeners don't support waiting with a timeout directly, the pipe is polled in nonblocking mode at short intervals, which may delay the acceptance by a few milliseconds.
+    ///
+    /// [`TimedOut`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.TimedOut " "
+    ///
+    /// Time spent waiting for a slot in the [connection limit](super::LocalSocketListenerOptions::max_concurrent_connections)
+    /// or for the [accept rate limit](super::AcceptRateLimit) to allow another client counts towards the timeout.
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<LocalSocketStream> {
+        self.report((|| {
+            let deadline = Instant::now() + timeout;
+            let permit = self.acquire_permit(Some(timeout))?;
+            self.accept_rate_limited(Some(deadline), permit)
+        })())
+    }
+    /// Accepts a client, applying the accept rate limit if there is one. No deadline means waiting indefinitely.
+    fn accept_rate_limited(
+        &self,
+        deadline: Option<Instant>,
+        permit: Option<Arc<ConnectionPermit>>,
+    ) -> io::Result<LocalSocketStream> {
+        let accept = |permit| {
+            let inner = match deadline {
+                Some(deadline) => self
+                    .inner
+                    .accept_timeout(deadline.saturating_duration_since(Instant::now()))?,
+                None => self.inner.accept()?,
+            };
+            Ok(LocalSocketStream { inner, permit })
+        };
+        let Some(rate_limit) = &self.rate_limit else {
+            return accept(permit);
+        };
+        if !rate_limit.sheds() {
+            rate_limit.take(deadline, || self.inner.is_closed())?;
+            return accept(permit).map_err(|e| {
+                rate_limit.refund();
+                e
+            });
+        }
+        loop {
+            let conn = accept(permit.clone())?;
+            if rate_limit.try_take() {
+                return Ok(conn);
+            }
+            if let Some(hooks) = self.inner.hooks() {
+                hooks.rejected(conn.peer_pid());
+            }
+        }
+    }
+    /// Passes the outcome of an accept operation to the hooks, if there are any.
+    fn report(&self, result: io::Result<LocalSocketStream>) -> io::Result<LocalSocketStream> {
+        if let Some(hooks) = self.inner.hooks() {
+            match &result {
+                Ok(conn) => hooks.accepted(conn.peer_pid()),
+                Err(e) => hooks.failed(e),
+            }
+        }
+        result
+    }
+    /// Reserves a slot for the connection that is about to be accepted, if the amount of them is limited.
+    fn acquire_permit(&self, timeout: Option<Duration>) -> io::Result<Option<Arc<ConnectionPermit>>> {
+        self.limit
+            .as_ref()
+            .map(|limit| limit.acquire(timeout, || self.inner.is_closed()).map(Arc::new))
+            .transpose()
+    }
+    /// Waits until a client is ready to be accepted without accepting it, returning `false` if the timeout expires
+    /// first. No timeout means waiting indefinitely.
+    ///
+    /// Intended for listeners in [nonblocking mode](Self::set_nonblocking): a successful wait means that the next call to
+    /// [`.accept()`](Self::accept) is unlikely to fail with [`WouldBlock`](io::ErrorKind::WouldBlock), though another
+    /// thread might accept the client first. If the listener is [closed](Self::close), an error wrapping
+    /// [`ListenerClosedError`](super::ListenerClosedError) is returned.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// The pending pipe instance is polled at short intervals, which may delay the wakeup by a few milliseconds.
+    pub fn wait_accept(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_accept(timeout)
+    }
+    /// Closes the listener, waking up all threads blocked in [`.accept()`](Self::accept) or
+    /// [`.accept_timeout()`](Self::accept_timeout) with an error wrapping [`ListenerClosedError`](super::ListenerClosedError).
+    /// All subsequent calls to those methods fail with the same error.
+    ///
+    /// This is meant to be called from a thread other than the one running the server loop, which is why it takes the
+    /// listener by shared reference. The socket itself is only closed when the listener is dropped, and the socket file,
+    /// if there is one, is not removed.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// Threads blocked in `.accept()` are woken up by cancelling their pending I/O via `CancelSynchronousIo`. Threads
+    /// in `.accept_timeout()` notice the closure within about 10 milliseconds.
+    pub fn close(&self) {
+        self.inner.close();
+        if let Some(limit) = &self.limit {
+            limit.wake_all();
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            rate_limit.wake_all();
+        }
+    }
+    /// Returns `true` if the listener has been [closed](Self::close), `false` otherwise.
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+    /// The file descriptor which becomes readable once the listener is closed, for waiting on it together with others.
+    #[cfg(unix)]
+    pub(crate) fn close_signal_fd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        self.inner.close_signal_fd()
     }
     /// Creates an infinite iterator which calls `accept()` with each iteration. Used together with `for` loops to conveniently create a main loop for a socket server.
     pub fn incoming(&self) -> Incoming<'_> {
@@ -140,7 +311,23 @@ impl LocalSocketListener {
     /// [`accept`]: #method.accept " "
     /// [`incoming`]: #method.incoming " "
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        self.inner.set_nonblocking(nonblocking)
+        self.inner.set_nonblocking(nonblocking)?;
+        if let Some(limit) = &self.limit {
+            limit.set_nonblocking(nonblocking);
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            rate_limit.set_nonblocking(nonblocking);
+        }
+        Ok(())
+    }
+}
+impl AcceptTimeout for LocalSocketListener {
+    type Stream = LocalSocketStream;
+    fn accept(&self) -> io::Result<LocalSocketStream> {
+        self.accept()
+    }
+    fn accept_timeout(&self, timeout: Duration) -> io::Result<LocalSocketStream> {
+        self.accept_timeout(timeout)
     }
 }
 impl Debug for LocalSocketListener {
@@ -148,7 +335,31 @@ impl Debug for LocalSocketListener {
         Debug::fmt(&self.inner, f)
     }
 }
-impl_handle_manip_unix!(LocalSocketListener);
+impl_as_raw_handle_unix!(LocalSocketListener);
+impl_into_raw_handle_unix!(LocalSocketListener);
+impl_as_handle_unix!(LocalSocketListener);
+impl_into_handle_unix!(LocalSocketListener);
+#[cfg(unix)]
+impl TryFrom<std::os::unix::io::OwnedFd> for LocalSocketListener {
+    type Error = crate::handle_conversion::FromHandleError;
+    fn try_from(fd: std::os::unix::io::OwnedFd) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: TryFrom::try_from(fd)?,
+            limit: None,
+            rate_limit: None,
+        })
+    }
+}
+#[cfg(unix)]
+impl std::os::unix::io::FromRawFd for LocalSocketListener {
+    unsafe fn from_raw_fd(fd: libc::c_int) -> Self {
+        Self {
+            inner: unsafe { std::os::unix::io::FromRawFd::from_raw_fd(fd) },
+            limit: None,
+            rate_limit: None,
+        }
+    }
+}
 
 /// An infinite iterator over incoming client connections of a [`LocalSocketListener`].
 ///
@@ -0,0 +1,250 @@
+#[cfg(windows)]
+use std::{ffi::OsString, num::NonZeroU8};
+use {
+    super::{
+        AcceptRateLimit, BoundName, ConnectionLimit, LocalSocketHooks, LocalSocketListener, RateLimiter,
+        ToLocalSocketName,
+    },
+    crate::Sealed,
+    std::{io, sync::Arc},
+};
+
+impmod! {local_socket,
+    LocalSocketListener as LocalSocketListenerImpl
+}
+#[cfg(feature = "tokio")]
+impmod! {local_socket::tokio,
+    LocalSocketListener as TokioLocalSocketListenerImpl
+}
+
+/// A builder for [`LocalSocketListener`]s, providing finer control over their creation than
+/// [`LocalSocketListener::bind()`] does.
+///
+/// Only the options that have a meaning on all platforms are set through the methods of this type. Platform-specific
+/// ones are set through extension traits:
+/// - `os::unix::LocalSocketListenerOptionsExt` – permissions and ownership of the socket file and whether it is
+///   deleted when the listener is dropped;
+/// - `os::windows::LocalSocketListenerOptionsExt` – security descriptor, instance limit and acceptance of remote
+///   clients.
+///
+/// With the `tokio` feature enabled, [`.bind_tokio()`](Self::bind_tokio) creates a Tokio listener from the same
+/// options.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::LocalSocketListenerOptions;
+///
+/// let listener = LocalSocketListenerOptions::new()
+///     .nonblocking(true)
+///     .backlog(16)
+///     .bind("@example.sock")?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalSocketListenerOptions {
+    pub(crate) nonblocking: bool,
+    pub(crate) backlog: Option<u32>,
+    #[cfg_attr(windows, allow(dead_code))]
+    pub(crate) reclaim_stale_name: bool,
+    pub(crate) same_user_only: bool,
+    pub(crate) max_connections: Option<usize>,
+    pub(crate) rate_limit: Option<AcceptRateLimit>,
+    pub(crate) hooks: Option<Arc<dyn LocalSocketHooks>>,
+    #[cfg(unix)]
+    pub(crate) mode: Option<u32>,
+    #[cfg(unix)]
+    pub(crate) owner: Option<u32>,
+    #[cfg(unix)]
+    pub(crate) group: Option<u32>,
+    #[cfg(unix)]
+    pub(crate) unlink_on_drop: bool,
+    #[cfg(windows)]
+    pub(crate) security_descriptor: Option<OsString>,
+    #[cfg(windows)]
+    pub(crate) instance_limit: Option<NonZeroU8>,
+    #[cfg(windows)]
+    pub(crate) accept_remote: bool,
+}
+impl LocalSocketListenerOptions {
+    /// Creates a new builder with default options, which are the same as the ones used by
+    /// [`LocalSocketListener::bind()`].
+    pub fn new() -> Self {
+        Self {
+            nonblocking: false,
+            backlog: None,
+            reclaim_stale_name: false,
+            same_user_only: false,
+            max_connections: None,
+            rate_limit: None,
+            hooks: None,
+            #[cfg(unix)]
+            mode: None,
+            #[cfg(unix)]
+            owner: None,
+            #[cfg(unix)]
+            group: None,
+            #[cfg(unix)]
+            unlink_on_drop: false,
+            #[cfg(windows)]
+            security_descriptor: None,
+            #[cfg(windows)]
+            instance_limit: None,
+            #[cfg(windows)]
+            accept_remote: false,
+        }
+    }
+    /// Sets whether the listener will be created in nonblocking mode. By default, it is disabled.
+    ///
+    /// See [`LocalSocketListener::set_nonblocking()`] for the effects of this mode, including the platform-specific
+    /// ones.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+    /// Sets how many clients may wait to be accepted before further attempts to connect are refused. By default, 128 is
+    /// used, and the OS may silently clamp the value to a limit of its own.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// Ignored, since named pipes have no connection queue to speak of – use the instance limit to restrict the number
+    /// of clients instead.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn backlog(mut self, backlog: u32) -> Self {
+        self.backlog = Some(backlog);
+        self
+    }
+    /// Sets whether a name that is taken by a socket nobody is listening on will be reclaimed. By default, it is
+    /// disabled, and binding fails with an [`AddrInUse`](io::ErrorKind::AddrInUse) error in such a case.
+    ///
+    /// Socket files outlive their server if it doesn't delete them on exit, which is bound to happen if it crashes. With
+    /// this option, if the name is found to be in use, the listener connects to the socket to find out whether a server
+    /// is listening on it, and if the connection gets refused, deletes the file and retries. The error is returned as
+    /// usual if the server is alive, or if the file at the path is not a socket, which is never deleted.
+    ///
+    /// The check and the deletion are not atomic: if another server binds the same path in between, its socket file is
+    /// deleted. Servers which may be started concurrently should serialize their startup, for example with a lock file.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// Ignored, since named pipes cease to exist together with the server.
+    ///
+    /// ## Linux
+    /// Ignored for names in the abstract namespace, which are also freed together with the server.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn reclaim_stale_name(mut self, reclaim_stale_name: bool) -> Self {
+        self.reclaim_stale_name = reclaim_stale_name;
+        self
+    }
+    /// Sets whether connections from processes running as a different user will be rejected. By default, they are
+    /// accepted.
+    ///
+    /// With this option, the listener checks the credentials of every client as it arrives, and silently closes the
+    /// connection if the client runs as another user or its credentials cannot be retrieved. Rejected clients never
+    /// reach the caller of `accept()` – it keeps waiting for the next one, and `accept_timeout()` keeps waiting for the
+    /// remainder of the timeout.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// The effective user ID of the client is compared to the one of the current process. Not supported on platforms
+    /// which don't report peer credentials, where every connection is rejected.
+    /// ## Windows
+    /// The user SID of the client process is compared to the one of the current process.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn same_user_only(mut self, same_user_only: bool) -> Self {
+        self.same_user_only = same_user_only;
+        self
+    }
+    /// Sets the maximum amount of connections accepted by the listener that may be open at the same time. By default,
+    /// there is no limit.
+    ///
+    /// Once the limit is reached, `accept()` waits for one of the streams it produced to be dropped before accepting
+    /// the next client, which stays in the queue of pending connections until then – this provides backpressure
+    /// instead of letting a flood of clients exhaust the server. In nonblocking mode, `accept()` fails with an error
+    /// wrapping [`ConnectionLimitError`](super::ConnectionLimitError) instead of waiting. A connection counts as open
+    /// until the stream and all of its [clones](super::LocalSocketStream::try_clone) are dropped.
+    ///
+    /// Binding fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the limit is zero.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn max_concurrent_connections(mut self, max_concurrent_connections: usize) -> Self {
+        self.max_connections = Some(max_concurrent_connections);
+        self
+    }
+    /// Sets the rate at which the listener accepts clients. By default, there is no limit.
+    ///
+    /// This protects a server from connect storms caused by misbehaving clients: see [`AcceptRateLimit`] for how the
+    /// limit works and what happens to clients beyond it.
+    ///
+    /// Binding fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the limit allows no connections, its
+    /// interval is zero or its burst size is zero.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn accept_rate_limit(mut self, accept_rate_limit: AcceptRateLimit) -> Self {
+        self.rate_limit = Some(accept_rate_limit);
+        self
+    }
+    /// Sets the [hooks](LocalSocketHooks) which the listener calls as it accepts clients, rejects them or fails to
+    /// accept. By default, there are none.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn hooks(mut self, hooks: Arc<dyn LocalSocketHooks>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+    /// Creates a socket server with the specified local socket name and the options of the builder.
+    pub fn bind<'a>(&self, name: impl ToLocalSocketName<'a>) -> io::Result<LocalSocketListener> {
+        let limit = match self.max_connections {
+            Some(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "the maximum amount of concurrent connections must not be zero",
+                ))
+            }
+            Some(max) => Some(ConnectionLimit::new(max, self.nonblocking)),
+            None => None,
+        };
+        let rate_limit = self
+            .rate_limit
+            .map(|rate_limit| RateLimiter::new(rate_limit, self.nonblocking))
+            .transpose()?;
+        Ok(LocalSocketListener {
+            inner: LocalSocketListenerImpl::bind_with_options(name, self)?,
+            limit,
+            rate_limit,
+        })
+    }
+    /// Like [`bind()`](Self::bind), but also returns a [`BoundName`] guard which removes the socket file when dropped.
+    pub fn bind_guarded<'a>(&self, name: impl ToLocalSocketName<'a>) -> io::Result<(LocalSocketListener, BoundName)> {
+        let name = name.to_local_socket_name()?;
+        let listener = self.bind(name.clone())?;
+        Ok((listener, BoundName::new(name)))
+    }
+    /// Creates a [Tokio-based socket server](super::tokio::LocalSocketListener) with the specified local socket name
+    /// and the options of the builder. Has to be called within a Tokio runtime.
+    ///
+    /// The options which concern the socket itself, i.e. the backlog, reclaiming stale names and all of the
+    /// platform-specific ones, are applied in the same way as by [`bind()`](Self::bind). The nonblocking mode is
+    /// ignored, since asynchronous listeners don't block either way.
+    ///
+    /// The options which concern the accepting of clients – [same-user-only mode](Self::same_user_only), the
+    /// [connection limit](Self::max_concurrent_connections), the [accept rate limit](Self::accept_rate_limit) and
+    /// [hooks](Self::hooks) – are not supported by the Tokio
+    /// listener. Rather than silently ignoring any of them, which might let unwanted clients in, binding fails with an
+    /// [`Unsupported`](io::ErrorKind::Unsupported) error if they are set.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    pub fn bind_tokio<'a>(&self, name: impl ToLocalSocketName<'a>) -> io::Result<super::tokio::LocalSocketListener> {
+        if self.same_user_only || self.max_connections.is_some() || self.rate_limit.is_some() || self.hooks.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "same-user-only mode, connection limits, accept rate limits and hooks are not supported by Tokio local \
+                socket listeners",
+            ));
+        }
+        TokioLocalSocketListenerImpl::bind_with_options(name, self).map(From::from)
+    }
+}
+impl Default for LocalSocketListenerOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Sealed for LocalSocketListenerOptions {}
@@ -0,0 +1,177 @@
+use {
+    super::{lookup, to_key, End, ListenerCore, Registered},
+    crate::{
+        local_socket::ToLocalSocketName,
+        timeout::{AcceptTimeout, WaitReady},
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, prelude::*},
+        iter::FusedIterator,
+        process,
+        sync::atomic::Ordering::Relaxed,
+        time::Duration,
+    },
+};
+
+/// An in-process counterpart of [`LocalSocketListener`](crate::local_socket::LocalSocketListener).
+///
+/// See the [module-level documentation](super) for more.
+pub struct LoopbackListener {
+    core: ListenerCore<u8>,
+}
+impl LoopbackListener {
+    /// Creates a listener with the specified name, failing with [`AddrInUse`](io::ErrorKind::AddrInUse) if another
+    /// loopback listener in the process already has it. The name is freed once the listener is dropped.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            core: ListenerCore::bind(to_key(name)?, Registered::Bytes)?,
+        })
+    }
+    /// Blocks until a client connects, or fails with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is none in
+    /// nonblocking mode.
+    pub fn accept(&self) -> io::Result<LoopbackStream> {
+        self.core.accept().map(|end| LoopbackStream { end })
+    }
+    /// Blocks until a client connects or the specified amount of time passes, in which case a
+    /// [`TimedOut`](io::ErrorKind::TimedOut) error is returned.
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<LoopbackStream> {
+        self.core.accept_timeout(timeout).map(|end| LoopbackStream { end })
+    }
+    /// Creates an infinite iterator which calls `accept()` with each iteration.
+    pub fn incoming(&self) -> LoopbackIncoming<'_> {
+        LoopbackIncoming { listener: self }
+    }
+    /// Enables or disables the nonblocking mode for the listener. By default, it is disabled.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.core.nonblocking.store(nonblocking, Relaxed);
+        Ok(())
+    }
+}
+impl AcceptTimeout for LoopbackListener {
+    type Stream = LoopbackStream;
+    fn accept(&self) -> io::Result<LoopbackStream> {
+        self.accept()
+    }
+    fn accept_timeout(&self, timeout: Duration) -> io::Result<LoopbackStream> {
+        self.accept_timeout(timeout)
+    }
+}
+impl Debug for LoopbackListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoopbackListener")
+            .field("name", &self.core.key.0)
+            .field("namespaced", &self.core.key.1)
+            .finish()
+    }
+}
+
+/// An infinite iterator over incoming client connections of a [`LoopbackListener`].
+///
+/// Created by the [`.incoming()`](LoopbackListener::incoming) method.
+#[derive(Debug)]
+pub struct LoopbackIncoming<'a> {
+    listener: &'a LoopbackListener,
+}
+impl Iterator for LoopbackIncoming<'_> {
+    type Item = io::Result<LoopbackStream>;
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.listener.accept())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+impl FusedIterator for LoopbackIncoming<'_> {}
+
+/// An in-process counterpart of [`LocalSocketStream`](crate::local_socket::LocalSocketStream).
+///
+/// Reading returns end of file once the other side has been dropped and everything it has sent has been read, and
+/// writing fails with [`BrokenPipe`](io::ErrorKind::BrokenPipe) once the other side has been dropped.
+pub struct LoopbackStream {
+    end: End<u8>,
+}
+impl LoopbackStream {
+    /// Connects to the loopback listener with the given name, failing with [`NotFound`](io::ErrorKind::NotFound) if
+    /// there is none.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let backlog = lookup(&to_key(name)?, |e| match e {
+            Registered::Bytes(b) => Some(b),
+            Registered::Msgs(..) => None,
+        })?;
+        Ok(Self { end: backlog.connect() })
+    }
+    /// Returns the identifier of the current process, since that's where the other side of the connection is.
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        Ok(process::id())
+    }
+    /// Waits until reading from the stream would not block, returning `false` if the timeout expires first. No timeout
+    /// means waiting indefinitely.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        Ok(self.end.wait_rx(timeout).is_some())
+    }
+    /// Always returns `true` right away, since writing to a loopback stream never blocks.
+    pub fn wait_writable(&self, _timeout: Option<Duration>) -> io::Result<bool> {
+        Ok(true)
+    }
+    /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
+    ///
+    /// In nonblocking mode, reading fails with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no data to read.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.end.nonblocking.store(nonblocking, Relaxed);
+        Ok(())
+    }
+}
+impl Read for &LoopbackStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut state = self.end.lock_rx()?;
+        let size = buf.len().min(state.queue.len());
+        for (dst, src) in buf.iter_mut().zip(state.queue.drain(..size)) {
+            *dst = src;
+        }
+        Ok(size)
+    }
+}
+impl Read for LoopbackStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+impl Write for &LoopbackStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.end.send_with(|queue| queue.extend(buf))?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+impl Write for LoopbackStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+impl WaitReady for LoopbackStream {
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_readable(timeout)
+    }
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_writable(timeout)
+    }
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LoopbackStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoopbackStream")
+            .field("nonblocking", &self.end.nonblocking.load(Relaxed))
+            .finish()
+    }
+}
@@ -0,0 +1,296 @@
+//! In-process local sockets, which connect threads of the same process without involving the OS.
+//!
+//! The types in this module mirror the interface of [`LocalSocketListener`](super::LocalSocketListener),
+//! [`LocalSocketStream`](super::LocalSocketStream) and their [message-mode](super::LocalSocketMsgStream) counterparts,
+//! but the connections they make never leave the process: data is passed through in-memory buffers, and names are
+//! looked up in a process-wide registry instead of the filesystem or the namespace of the OS. This makes them useful for
+//! unit tests of code built around local sockets, which then don't depend on the environment they run in, and for
+//! configurations in which the client and the server of an application happen to end up in the same process.
+//!
+//! Loopback names are separate from the names of real local sockets – a [`LoopbackListener`] and a
+//! [`LocalSocketListener`](super::LocalSocketListener) can be bound to the same name without conflicting, and a
+//! loopback connection can only be made to a loopback listener. Byte-stream and message-mode listeners share the same
+//! registry, just like they share names on the OS level, and connecting to a listener of the other mode fails with
+//! [`ConnectionRefused`](io::ErrorKind::ConnectionRefused).
+//!
+//! # Differences from OS-backed local sockets
+//! - Sent data is buffered without limit, which means that sending never blocks.
+//! - The peer process is always the current one.
+//! - There are no file descriptors or handles to convert to or from.
+//!
+//! # Example
+//! ```
+//! use interprocess::local_socket::loopback::{LoopbackListener, LoopbackStream};
+//! use std::{io::{prelude::*, BufReader}, thread};
+//!
+//! let listener = LoopbackListener::bind("@example_loopback.sock")?;
+//! let client = thread::spawn(|| -> std::io::Result<()> {
+//!     let mut conn = LoopbackStream::connect("@example_loopback.sock")?;
+//!     conn.write_all(b"Hello from client!\n")?;
+//!     Ok(())
+//! });
+//! let mut conn = BufReader::new(listener.accept()?);
+//! let mut line = String::new();
+//! conn.read_line(&mut line)?;
+//! assert_eq!(line, "Hello from client!\n");
+//! client.join().unwrap()?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+mod bytes;
+pub use bytes::*;
+
+mod msg;
+pub use msg::*;
+
+use {
+    super::ToLocalSocketName,
+    std::{
+        collections::{BTreeMap, VecDeque},
+        ffi::OsString,
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering::Relaxed},
+            Arc, Condvar, Mutex, MutexGuard, PoisonError, Weak,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// Registry key: the name and whether it's namespaced, so that a path and a namespaced name don't clash.
+type Key = (OsString, bool);
+
+/// Listeners currently bound in the process, by name.
+static REGISTRY: Mutex<BTreeMap<Key, Registered>> = Mutex::new(BTreeMap::new());
+
+enum Registered {
+    Bytes(Weak<Backlog<u8>>),
+    Msgs(Weak<Backlog<Vec<u8>>>),
+}
+impl Registered {
+    fn is_alive(&self) -> bool {
+        match self {
+            Self::Bytes(b) => b.strong_count() != 0,
+            Self::Msgs(b) => b.strong_count() != 0,
+        }
+    }
+    fn as_ptr(&self) -> *const () {
+        match self {
+            Self::Bytes(b) => b.as_ptr().cast(),
+            Self::Msgs(b) => b.as_ptr().cast(),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn to_key<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Key> {
+    let name = name.to_local_socket_name()?;
+    let namespaced = name.is_namespaced();
+    Ok((name.into_inner(), namespaced))
+}
+
+/// Registers a listener under the given name, failing with `AddrInUse` if a live listener already has it.
+fn register(key: Key, entry: Registered) -> io::Result<()> {
+    let mut registry = lock(&REGISTRY);
+    if registry.get(&key).map_or(false, Registered::is_alive) {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "a loopback listener is already bound to this name",
+        ));
+    }
+    registry.insert(key, entry);
+    Ok(())
+}
+/// Removes the registry entry of a listener which is being dropped, identified by the address of its backlog.
+fn unregister(key: &Key, backlog: *const ()) {
+    let mut registry = lock(&REGISTRY);
+    if registry.get(key).map_or(false, |e| e.as_ptr() == backlog) {
+        registry.remove(key);
+    }
+}
+/// Looks up the listener to connect to, using `select` to pick the one of the right mode.
+fn lookup<T>(key: &Key, select: impl FnOnce(&Registered) -> Option<&Weak<Backlog<T>>>) -> io::Result<Arc<Backlog<T>>> {
+    let registry = lock(&REGISTRY);
+    let entry = registry
+        .get(key)
+        .filter(|e| e.is_alive())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no loopback listener is bound to this name"))?;
+    select(entry).and_then(Weak::upgrade).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            "the loopback listener bound to this name uses a different mode",
+        )
+    })
+}
+
+/// One direction of a connection, carrying either bytes or messages.
+struct Pipe<T> {
+    state: Mutex<PipeState<T>>,
+    cond: Condvar,
+}
+struct PipeState<T> {
+    queue: VecDeque<T>,
+    writer_gone: bool,
+    reader_gone: bool,
+}
+impl<T> Pipe<T> {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(PipeState {
+                queue: VecDeque::new(),
+                writer_gone: false,
+                reader_gone: false,
+            }),
+            cond: Condvar::new(),
+        })
+    }
+}
+
+/// One end of a connection: the receiving side of one pipe and the sending side of the other.
+struct End<T> {
+    rx: Arc<Pipe<T>>,
+    tx: Arc<Pipe<T>>,
+    nonblocking: AtomicBool,
+}
+impl<T> End<T> {
+    fn pair() -> (Self, Self) {
+        let (a, b) = (Pipe::new(), Pipe::new());
+        let end = |rx, tx| Self {
+            rx,
+            tx,
+            nonblocking: AtomicBool::new(false),
+        };
+        (end(Arc::clone(&a), Arc::clone(&b)), end(b, a))
+    }
+    /// Waits until there is something to receive or the other end is gone, returning `None` if the timeout expires
+    /// first.
+    fn wait_rx(&self, timeout: Option<Duration>) -> Option<MutexGuard<'_, PipeState<T>>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = lock(&self.rx.state);
+        while state.queue.is_empty() && !state.writer_gone {
+            state = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return None;
+                    }
+                    self.rx
+                        .cond
+                        .wait_timeout(state, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self.rx.cond.wait(state).unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+        Some(state)
+    }
+    /// Locks the receiving side once there is something to receive or the other end is gone, failing with
+    /// `WouldBlock` instead of waiting in nonblocking mode.
+    fn lock_rx(&self) -> io::Result<MutexGuard<'_, PipeState<T>>> {
+        let timeout = self.nonblocking.load(Relaxed).then_some(Duration::ZERO);
+        self.wait_rx(timeout)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))
+    }
+    /// Passes the sending side to `f` and wakes up the receiver, failing with `BrokenPipe` if the other end is gone.
+    fn send_with(&self, f: impl FnOnce(&mut VecDeque<T>)) -> io::Result<()> {
+        let mut state = lock(&self.tx.state);
+        if state.reader_gone {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        }
+        f(&mut state.queue);
+        drop(state);
+        self.tx.cond.notify_all();
+        Ok(())
+    }
+}
+impl<T> Drop for End<T> {
+    fn drop(&mut self) {
+        lock(&self.rx.state).reader_gone = true;
+        lock(&self.tx.state).writer_gone = true;
+        self.tx.cond.notify_all();
+    }
+}
+
+/// The queue of connections waiting to be accepted by a listener.
+struct Backlog<T> {
+    pending: Mutex<VecDeque<End<T>>>,
+    cond: Condvar,
+}
+impl<T> Backlog<T> {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            pending: Mutex::new(VecDeque::new()),
+            cond: Condvar::new(),
+        })
+    }
+    fn connect(&self) -> End<T> {
+        let (client, server) = End::pair();
+        lock(&self.pending).push_back(server);
+        self.cond.notify_one();
+        client
+    }
+    /// Waits for a connection to accept, returning `None` if the timeout expires first.
+    fn accept(&self, timeout: Option<Duration>) -> Option<End<T>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut pending = lock(&self.pending);
+        loop {
+            if let Some(end) = pending.pop_front() {
+                return Some(end);
+            }
+            pending = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return None;
+                    }
+                    self.cond
+                        .wait_timeout(pending, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self.cond.wait(pending).unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+    }
+}
+
+/// The listener half shared by both modes: the backlog, the name it's registered under and the nonblocking flag.
+struct ListenerCore<T> {
+    backlog: Arc<Backlog<T>>,
+    key: Key,
+    nonblocking: AtomicBool,
+}
+impl<T> ListenerCore<T> {
+    fn bind(key: Key, wrap: fn(Weak<Backlog<T>>) -> Registered) -> io::Result<Self> {
+        let backlog = Backlog::new();
+        register(key.clone(), wrap(Arc::downgrade(&backlog)))?;
+        Ok(Self {
+            backlog,
+            key,
+            nonblocking: AtomicBool::new(false),
+        })
+    }
+    fn accept(&self) -> io::Result<End<T>> {
+        let timeout = self.nonblocking.load(Relaxed).then_some(Duration::ZERO);
+        self.backlog
+            .accept(timeout)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::WouldBlock))
+    }
+    fn accept_timeout(&self, timeout: Duration) -> io::Result<End<T>> {
+        self.backlog.accept(Some(timeout)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no client connected before the timeout expired",
+            )
+        })
+    }
+}
+impl<T> Drop for ListenerCore<T> {
+    fn drop(&mut self) {
+        unregister(&self.key, Arc::as_ptr(&self.backlog).cast());
+    }
+}
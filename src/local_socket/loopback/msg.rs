@@ -0,0 +1,158 @@
+use {
+    super::{lookup, to_key, End, ListenerCore, Registered},
+    crate::{
+        local_socket::ToLocalSocketName,
+        reliable_recv_msg::{RecvBufPool, RecvResult, ReliableRecvMsg, TryRecvResult},
+        timeout::WaitReady,
+        Error,
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        io, process,
+        sync::atomic::Ordering::Relaxed,
+        time::Duration,
+    },
+};
+
+/// An in-process counterpart of [`LocalSocketMsgListener`](crate::local_socket::LocalSocketMsgListener).
+///
+/// See the [module-level documentation](super) for more.
+pub struct LoopbackMsgListener {
+    core: ListenerCore<Vec<u8>>,
+}
+impl LoopbackMsgListener {
+    /// Creates a message-mode listener with the specified name, failing with [`AddrInUse`](io::ErrorKind::AddrInUse)
+    /// if another loopback listener in the process already has it. The name is freed once the listener is dropped.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            core: ListenerCore::bind(to_key(name)?, Registered::Msgs)?,
+        })
+    }
+    /// Blocks until a client connects, or fails with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is none in
+    /// nonblocking mode.
+    pub fn accept(&self) -> io::Result<LoopbackMsgStream> {
+        self.core.accept().map(|end| LoopbackMsgStream { end })
+    }
+    /// Blocks until a client connects or the specified amount of time passes, in which case a
+    /// [`TimedOut`](io::ErrorKind::TimedOut) error is returned.
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<LoopbackMsgStream> {
+        self.core.accept_timeout(timeout).map(|end| LoopbackMsgStream { end })
+    }
+    /// Enables or disables the nonblocking mode for the listener. By default, it is disabled.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.core.nonblocking.store(nonblocking, Relaxed);
+        Ok(())
+    }
+}
+impl Debug for LoopbackMsgListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoopbackMsgListener")
+            .field("name", &self.core.key.0)
+            .field("namespaced", &self.core.key.1)
+            .finish()
+    }
+}
+
+/// An in-process counterpart of [`LocalSocketMsgStream`](crate::local_socket::LocalSocketMsgStream).
+///
+/// Messages are received via the [`ReliableRecvMsg`] trait. Once the other side has been dropped and all of its
+/// messages have been received, receiving returns an empty message, and sending fails with
+/// [`BrokenPipe`](io::ErrorKind::BrokenPipe).
+pub struct LoopbackMsgStream {
+    end: End<Vec<u8>>,
+}
+impl LoopbackMsgStream {
+    /// Connects to the message-mode loopback listener with the given name, failing with
+    /// [`NotFound`](io::ErrorKind::NotFound) if there is none.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let backlog = lookup(&to_key(name)?, |e| match e {
+            Registered::Msgs(b) => Some(b),
+            Registered::Bytes(..) => None,
+        })?;
+        Ok(Self { end: backlog.connect() })
+    }
+    /// Sends one message, returning how many bytes were sent, which is always the size of the whole message.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.end.send_with(|queue| queue.push_back(buf.to_vec()))?;
+        Ok(buf.len())
+    }
+    /// Sends one message. Provided for parity with
+    /// [`LocalSocketMsgStream::send_all()`](crate::local_socket::LocalSocketMsgStream::send_all), since loopback
+    /// streams always send whole messages.
+    pub fn send_all(&self, buf: &[u8]) -> Result<(), Error> {
+        self.send(buf)?;
+        Ok(())
+    }
+    /// Returns the identifier of the current process, since that's where the other side of the connection is.
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        Ok(process::id())
+    }
+    /// Waits until receiving from the stream would not block, returning `false` if the timeout expires first. No
+    /// timeout means waiting indefinitely.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        Ok(self.end.wait_rx(timeout).is_some())
+    }
+    /// Always returns `true` right away, since sending into a loopback stream never blocks.
+    pub fn wait_writable(&self, _timeout: Option<Duration>) -> io::Result<bool> {
+        Ok(true)
+    }
+    /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
+    ///
+    /// In nonblocking mode, receiving fails with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no message to
+    /// receive.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.end.nonblocking.store(nonblocking, Relaxed);
+        Ok(())
+    }
+}
+impl ReliableRecvMsg for &LoopbackMsgStream {
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        let mut state = self.end.lock_rx()?;
+        let size = state.queue.front().map_or(0, Vec::len);
+        let fit = buf.len() >= size;
+        if fit {
+            if let Some(msg) = state.queue.pop_front() {
+                buf[..size].copy_from_slice(&msg);
+            }
+        }
+        Ok(TryRecvResult { size, fit })
+    }
+    fn recv_pooled(&mut self, buf: &mut [u8], _pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        // Messages which don't fit are handed over in the buffer they were sent in, so the pool is never needed.
+        let mut state = self.end.lock_rx()?;
+        Ok(match state.queue.pop_front() {
+            Some(msg) if msg.len() <= buf.len() => {
+                buf[..msg.len()].copy_from_slice(&msg);
+                RecvResult::Fit(msg.len())
+            }
+            Some(msg) => RecvResult::Alloc(msg),
+            None => RecvResult::Fit(0),
+        })
+    }
+}
+impl ReliableRecvMsg for LoopbackMsgStream {
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        (&*self).try_recv(buf)
+    }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (&*self).recv_pooled(buf, pool)
+    }
+}
+impl WaitReady for LoopbackMsgStream {
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_readable(timeout)
+    }
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_writable(timeout)
+    }
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LoopbackMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoopbackMsgStream")
+            .field("nonblocking", &self.end.nonblocking.load(Relaxed))
+            .finish()
+    }
+}
@@ -15,24 +15,94 @@
 //!
 //! ## Differences from regular sockets
 //! A few missing features, primarily on Windows, require local sockets to omit some important functionality, because code relying on it wouldn't be portable. Some notable differences are:
+//! - Message boundaries are not preserved by [`LocalSocketStream`]; use [`LocalSocketMsgStream`] and [`LocalSocketMsgListener`] if you need them.
 //! - No `.shutdown()` – your communication protocol must manually negotiate end of transmission. Notably, `.read_to_string()` and `.read_all()` will always block indefinitely at some point.
-//! - No datagram sockets – the difference in semantics between connectionless datagram Ud-sockets and connection-based named message pipes on Windows does not allow bridging those two into a common API. You can emulate datagrams on top of streams anyway, so no big deal, right?
+//! - Datagrams are sent through [`LocalSocketDatagram`], which is backed by mailslots on Windows rather than named pipes, since the latter are connection-based. As a result, the receiver of a datagram cannot portably find out who sent it.
 
 #[cfg(feature = "tokio")]
 #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
+#[cfg(feature = "async")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "async")))]
+pub mod generic;
+
+#[cfg(feature = "async-std")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "async-std")))]
+pub mod async_std;
+
+pub mod loopback;
+
+mod bound_name;
+pub use bound_name::*;
+
+mod datagram;
+pub use datagram::*;
+
+mod discovery;
+pub use discovery::*;
+
+mod hooks;
+pub(crate) use hooks::{is_reportable, ListenerHooks};
+pub use hooks::{ConnectionEvent, LocalSocketHooks};
+
 mod listener;
 pub use listener::*;
 
+pub use crate::{handle_conversion::FromHandleError, listener_close::ListenerClosedError};
+
+mod listener_options;
+pub use listener_options::*;
+
 mod stream;
 pub use stream::*;
 
+mod connection_limit;
+pub use connection_limit::ConnectionLimitError;
+pub(crate) use connection_limit::{ConnectionLimit, ConnectionPermit};
+
+mod rate_limit;
+pub(crate) use rate_limit::RateLimiter;
+pub use rate_limit::{AcceptRateLimit, RateLimitError};
+
+mod connect_options;
+pub use connect_options::*;
+
+mod split;
+pub use split::*;
+
+mod msg_listener;
+pub use msg_listener::*;
+
+mod msg_stream;
+pub use msg_stream::*;
+
 mod name;
 pub use name::*;
 
+mod name_gen;
+pub use name_gen::*;
+
+mod name_mapper;
+pub use name_mapper::*;
+
+#[cfg(feature = "serde")]
+mod name_serde;
+
+mod peer_credentials;
+pub use peer_credentials::*;
+
+mod peer_identity;
+pub use peer_identity::*;
+
+mod server;
+pub use server::*;
+
 mod name_type_support;
 pub use name_type_support::*;
 
 mod to_name;
 pub use to_name::*;
+
+mod transferable;
+pub use transferable::*;
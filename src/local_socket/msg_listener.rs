@@ -0,0 +1,81 @@
+use {
+    super::{LocalSocketMsgStream, ToLocalSocketName},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+    },
+};
+
+impmod! {local_socket,
+    LocalSocketMsgListener as LocalSocketMsgListenerImpl
+}
+
+/// A local socket server for message-mode connections, the message-boundary-preserving counterpart of [`LocalSocketListener`](super::LocalSocketListener).
+///
+/// On Unix, this is a `SOCK_SEQPACKET` Ud-socket, which is not supported on macOS and iOS. On Windows, named pipes in message mode (`PipeMode::Messages`) are used.
+///
+/// # Examples
+///
+/// ## Basic server
+/// ```no_run
+/// use interprocess::{
+///     local_socket::{LocalSocketMsgListener, NameTypeSupport},
+///     reliable_recv_msg::{RecvResult, ReliableRecvMsg},
+/// };
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_msg.sock",
+///         OnlyNamespaced | Both => "@example_msg.sock",
+///     }
+/// };
+/// let listener = LocalSocketMsgListener::bind(name)?;
+///
+/// let mut buffer = vec![0; 128];
+/// loop {
+///     let conn = listener.accept()?;
+///     // Every message arrives whole, no matter how it was fragmented on its way.
+///     let msg = match (&conn).recv(&mut buffer)? {
+///         RecvResult::Fit(size) => &buffer[..size],
+///         RecvResult::Alloc(bigger) => {
+///             buffer = bigger;
+///             &buffer[..]
+///         }
+///     };
+///     println!("Client sent: {}", String::from_utf8_lossy(msg));
+///     conn.send(b"Hello from server!")?;
+/// }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct LocalSocketMsgListener {
+    inner: LocalSocketMsgListenerImpl,
+}
+impl LocalSocketMsgListener {
+    /// Creates a message-mode socket server with the specified local socket name.
+    ///
+    /// On Unix, the socket file, if one is created, is deleted once the listener is dropped.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketMsgListenerImpl::bind(name)?,
+        })
+    }
+    /// Listens for incoming connections to the socket, blocking until a client is connected.
+    pub fn accept(&self) -> io::Result<LocalSocketMsgStream> {
+        Ok(LocalSocketMsgStream {
+            inner: self.inner.accept()?,
+        })
+    }
+    /// Enables or disables the nonblocking mode for the listener. By default, it is disabled.
+    ///
+    /// See [`LocalSocketListener::set_nonblocking()`](super::LocalSocketListener::set_nonblocking) for the details.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LocalSocketMsgListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl_handle_manip_unix!(LocalSocketMsgListener);
@@ -0,0 +1,143 @@
+use {
+    super::{LocalSocketPeerCredentials, ToLocalSocketName},
+    crate::{
+        error::{self, Error},
+        reliable_recv_msg::{RecvBufPool, RecvResult, ReliableRecvMsg, TryRecvResult},
+        timeout::WaitReady,
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        time::Duration,
+    },
+};
+
+impmod! {local_socket,
+    LocalSocketMsgStream as LocalSocketMsgStreamImpl
+}
+
+/// A message-mode local socket connection, obtained either from [`LocalSocketMsgListener`](super::LocalSocketMsgListener) or by connecting to an existing message-mode local socket.
+///
+/// Unlike with [`LocalSocketStream`](super::LocalSocketStream), every [`send()`](Self::send) corresponds to exactly one message on the receiving end. Messages are received via the [`ReliableRecvMsg`] trait, which is implemented both for the stream itself and for shared references to it, and never get truncated.
+///
+/// # Examples
+///
+/// ## Basic client
+/// ```no_run
+/// use interprocess::{
+///     local_socket::{LocalSocketMsgStream, NameTypeSupport},
+///     reliable_recv_msg::ReliableRecvMsg,
+/// };
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_msg.sock",
+///         OnlyNamespaced | Both => "@example_msg.sock",
+///     }
+/// };
+/// let mut conn = LocalSocketMsgStream::connect(name)?;
+/// conn.send(b"Hello from client!")?;
+///
+/// let mut buffer = [0; 128];
+/// let reply = conn.recv(&mut buffer)?;
+/// println!("Server answered: {:?}", reply);
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct LocalSocketMsgStream {
+    pub(super) inner: LocalSocketMsgStreamImpl,
+}
+impl LocalSocketMsgStream {
+    /// Connects to a remote message-mode local socket server.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketMsgStreamImpl::connect(name)?,
+        })
+    }
+    /// Sends one message, returning how many bytes were sent (normally the size of the whole message).
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+    /// Sends one message, failing with [`MessageTooLarge`](crate::ErrorKind::MessageTooLarge) if only part of it
+    /// could be sent instead of returning how many bytes were sent.
+    ///
+    /// The error carries a portable [`ErrorKind`](crate::ErrorKind), so failures such as the other side having hung up can be told apart
+    /// without inspecting the raw OS error.
+    pub fn send_all(&self, buf: &[u8]) -> Result<(), Error> {
+        let sent = self.send(buf)?;
+        error::check_msg_sent(sent, buf.len())
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`](super::LocalSocketStream::peer_pid) for the platform-specific behavior.
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.inner.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`](super::LocalSocketStream::peer_credentials) for the details.
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
+    ///
+    /// In nonblocking mode, sending and receiving will immediately return with the [`WouldBlock`](io::ErrorKind::WouldBlock) error in situations when they would normally block.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+    /// Waits until receiving from the stream would not block, returning `false` if the timeout expires first. No timeout
+    /// means waiting indefinitely.
+    ///
+    /// See [`LocalSocketStream::wait_readable()`](super::LocalSocketStream::wait_readable) for the platform-specific
+    /// behavior.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    /// Waits until sending into the stream would not block, returning `false` if the timeout expires first. No timeout
+    /// means waiting indefinitely.
+    ///
+    /// See [`LocalSocketStream::wait_writable()`](super::LocalSocketStream::wait_writable) for the platform-specific
+    /// behavior.
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_writable(timeout)
+    }
+}
+impl ReliableRecvMsg for &LocalSocketMsgStream {
+    fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
+        (&self.inner).recv(buf)
+    }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (&self.inner).recv_pooled(buf, pool)
+    }
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        (&self.inner).try_recv(buf)
+    }
+}
+impl ReliableRecvMsg for LocalSocketMsgStream {
+    fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
+        (&*self).recv(buf)
+    }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (&*self).recv_pooled(buf, pool)
+    }
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        (&*self).try_recv(buf)
+    }
+}
+impl WaitReady for LocalSocketMsgStream {
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_readable(timeout)
+    }
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_writable(timeout)
+    }
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LocalSocketMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl_handle_manip!(LocalSocketMsgStream);
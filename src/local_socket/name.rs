@@ -1,10 +1,12 @@
 // TODO LocalSocketNameBuf
 
 use {
-    super::NameTypeSupport,
+    super::{LocalSocketNameMapper, NameTypeSupport},
     std::{
         borrow::Cow,
         ffi::{OsStr, OsString},
+        io,
+        path::Path,
     },
 };
 
@@ -13,17 +15,69 @@ use {
 /// Due to vast differences between platforms in terms of how local sockets are named, there needs to be a way to store and process those in a unified way while also retaining platform-specific pecularities. `LocalSocketName` aims to bridge the gap between portability and platform-specific correctness.
 ///
 /// # Creation
-/// A separate trait is used to create names from basic strings: [`ToLocalSocketName`](super::ToLocalSocketName). Aside from being conveniently implemented on every single string type in the standard library, it also provides some special processing. Please read its documentation if you haven't already – the rest of this page assumes you did.
+/// Names of a known type are created with [`namespaced()`](Self::namespaced) and [`path()`](Self::path), which take
+/// the string as is. When the type doesn't matter, [`from_base_name()`](Self::from_base_name) picks the best one the
+/// platform supports.
+///
+/// Strings can also be used as names directly, through the [`ToLocalSocketName`](super::ToLocalSocketName) trait. Aside from being conveniently implemented on every single string type in the standard library, it also provides some special processing, the `@` syntax, for compatibility with older versions. Please read its documentation if you haven't already – the rest of this page assumes you did.
 ///
 /// # Validity
 /// As mentioned in the [module-level documentation](super), not all platforms support all types of local socket names. A name pointing to a filesystem location is only supported on Unix-like systems, and names pointing to an abstract namespace reserved specifically for local sockets are only available on Linux and Windows. Due to the diversity of those differences, `LocalSocketName` does not provide any forced validation by itself – the [`is_supported`] and [`is_always_supported`] checks are not enforced to succeed. Instead, they are intended as helpers for the process of user input validation, if any local socket names are ever read from environment variables, configuration files or other methods of user input.
 ///
 /// If an invalid local socket name is used to create a local socket or connect to it, the creation/connection method will fail.
+///
+/// # Serialization
+/// With the `serde` feature enabled, names implement `Serialize` and `Deserialize`, represented as an enum with the
+/// `Path` and `Namespaced` newtype variants holding the name as a string, which preserves the type of the name. Names
+/// which aren't valid Unicode cannot be serialized.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct LocalSocketName<'a> {
     inner: Cow<'a, OsStr>,
     namespaced: bool,
 }
 impl<'a> LocalSocketName<'a> {
+    /// Creates a namespaced name from the given string, which is used as is – a leading `@` is not stripped, unlike
+    /// with the `@` syntax of [`ToLocalSocketName`](super::ToLocalSocketName).
+    ///
+    /// The name borrows the string; use [`.into_owned()`](Self::into_owned) to detach it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use interprocess::local_socket::{LocalSocketListener, LocalSocketName};
+    ///
+    /// let listener = LocalSocketListener::bind(LocalSocketName::namespaced("example.sock"))?;
+    /// # std::io::Result::<()>::Ok(())
+    /// ```
+    pub fn namespaced<S: AsRef<OsStr> + ?Sized>(name: &'a S) -> Self {
+        Self::from_raw_parts(Cow::Borrowed(name.as_ref()), true)
+    }
+    /// Creates a filesystem path name from the given path, which is used as is.
+    ///
+    /// The name borrows the path; use [`.into_owned()`](Self::into_owned) to detach it.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use interprocess::local_socket::{LocalSocketListener, LocalSocketName};
+    ///
+    /// let listener = LocalSocketListener::bind(LocalSocketName::path("/run/example.sock"))?;
+    /// # std::io::Result::<()>::Ok(())
+    /// ```
+    pub fn path<P: AsRef<Path> + ?Sized>(path: &'a P) -> Self {
+        Self::from_raw_parts(Cow::Borrowed(path.as_ref().as_os_str()), false)
+    }
+    /// Creates a name of the best type supported by the platform from the given base name, such as the name of the
+    /// application: the base name itself as a namespaced name where those are supported, and a file in the temporary
+    /// directory named after it with `.sock` appended otherwise.
+    ///
+    /// This is the mapping done by a [`LocalSocketNameMapper`] with default settings, which can be used instead to
+    /// customize it.
+    pub fn from_base_name(base_name: impl AsRef<OsStr>) -> LocalSocketName<'static> {
+        LocalSocketNameMapper::new().map(base_name)
+    }
+    /// Converts the name into one which owns its string, cloning it if it is borrowed.
+    pub fn into_owned(self) -> LocalSocketName<'static> {
+        LocalSocketName::from_raw_parts(Cow::Owned(self.inner.into_owned()), self.namespaced)
+    }
     /// Returns `true` if the type of the name is supported by the OS, `false` otherwise.
     ///
     /// The check is performed at runtime. For a conservative compile-time check, see [`.is_always_supported`](Self::is_always_supported).
@@ -77,4 +131,19 @@ impl<'a> LocalSocketName<'a> {
     pub(crate) const fn from_raw_parts(inner: Cow<'a, OsStr>, namespaced: bool) -> Self {
         Self { inner, namespaced }
     }
+    /// Converts the name into a string which [`ToLocalSocketName`](super::ToLocalSocketName) turns back into the same
+    /// name on this platform, prefixing namespaced names with `@` where that's needed to tell them apart from paths.
+    /// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the name is not valid Unicode.
+    pub(crate) fn to_portable_string(&self) -> io::Result<String> {
+        let mut string = String::new();
+        if self.namespaced && NameTypeSupport::query() == NameTypeSupport::Both {
+            string.push('@');
+        }
+        string.push_str(
+            self.inner.to_str().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "the local socket name is not valid Unicode")
+            })?,
+        );
+        Ok(string)
+    }
 }
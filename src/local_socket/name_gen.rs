@@ -0,0 +1,129 @@
+use {
+    super::{LocalSocketName, NameTypeSupport},
+    std::{
+        borrow::Cow,
+        collections::hash_map::RandomState,
+        ffi::OsString,
+        hash::{BuildHasher, Hash, Hasher},
+        iter::FusedIterator,
+        process,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+        time::SystemTime,
+    },
+};
+
+impmod! {local_socket,
+    name_gen_user_tag,
+    name_gen_directory,
+}
+
+/// Shared between all generators so that two of them created at the same moment still don't produce the same names.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// An infinite iterator of collision-resistant local socket names, for when the name only has to be communicated to
+/// the other side (typically a child process) rather than be known in advance.
+///
+/// The names are namespaced on platforms which support that, and are paths to files in the temporary directory
+/// otherwise. Each one consists of the chosen prefix, optionally an identifier of the current user and a random-looking
+/// 64-bit suffix, which is derived from the process ID, the current time, a process-wide counter and randomly keyed
+/// hashing. The suffix makes collisions very unlikely rather than impossible, and the name of an existing socket can
+/// still be picked deliberately by another process – simply try the next name if binding fails with
+/// [`AddrInUse`](std::io::ErrorKind::AddrInUse), as in the example below.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{LocalSocketListener, LocalSocketNameGen};
+/// use std::io;
+///
+/// let listener = LocalSocketNameGen::new("my-helper")
+///     .per_user(true)
+///     .find_map(|name| match LocalSocketListener::bind(name) {
+///         Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+///         els => Some(els),
+///     })
+///     .unwrap()?;
+/// // ...pass the name to the child process and accept its connection...
+/// # io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalSocketNameGen {
+    prefix: String,
+    namespaced: bool,
+    per_user: bool,
+    hasher: RandomState,
+}
+impl LocalSocketNameGen {
+    /// Creates a generator of names which start with the given prefix, such as the name of the application. The names
+    /// are namespaced if the platform supports that, and not scoped to the current user.
+    ///
+    /// Keep the prefix short if file paths are to be generated: many platforms limit the length of Ud-socket paths to
+    /// about a hundred bytes, and the path of the temporary directory takes up a part of that.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            namespaced: NameTypeSupport::query().namespace_supported(),
+            per_user: false,
+            hasher: RandomState::new(),
+        }
+    }
+    /// Sets whether namespaced names are to be generated if the platform supports both kinds. By default, they are.
+    ///
+    /// Only file paths are ever generated on platforms which don't support namespaced names, and only namespaced names
+    /// on platforms which don't support file paths, regardless of this setting.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn prefer_namespaced(mut self, prefer_namespaced: bool) -> Self {
+        self.namespaced = match NameTypeSupport::query() {
+            NameTypeSupport::OnlyPaths => false,
+            NameTypeSupport::OnlyNamespaced => true,
+            NameTypeSupport::Both => prefer_namespaced,
+        };
+        self
+    }
+    /// Sets whether the names are to be scoped to the current user, so that the same prefix used by different users
+    /// never leads to them competing for a name. By default, they are not.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// The effective user ID is made part of the name. File paths are placed in the per-user runtime directory
+    /// specified by `XDG_RUNTIME_DIR` if it is set, which also keeps other users from accessing the socket, and in the
+    /// temporary directory otherwise.
+    /// ## Windows
+    /// The name of the user, as specified by the `USERNAME` environment variable, is made part of the name.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn per_user(mut self, per_user: bool) -> Self {
+        self.per_user = per_user;
+        self
+    }
+    fn suffix(&self) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        COUNTER.fetch_add(1, Relaxed).hash(&mut hasher);
+        process::id().hash(&mut hasher);
+        // A clock that's set before the epoch is no reason to fail, the other components suffice.
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+}
+impl Iterator for LocalSocketNameGen {
+    type Item = LocalSocketName<'static>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut filename = OsString::from(&self.prefix);
+        if self.per_user {
+            filename.push("-");
+            filename.push(name_gen_user_tag());
+        }
+        filename.push(format!("-{:016x}.sock", self.suffix()));
+        let inner = if self.namespaced {
+            filename
+        } else {
+            name_gen_directory(self.per_user).join(filename).into_os_string()
+        };
+        Some(LocalSocketName::from_raw_parts(Cow::Owned(inner), self.namespaced))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+impl FusedIterator for LocalSocketNameGen {}
@@ -0,0 +1,130 @@
+use {
+    super::{LocalSocketName, NameTypeSupport},
+    std::{
+        borrow::Cow,
+        ffi::{OsStr, OsString},
+        path::PathBuf,
+    },
+};
+
+impmod! {local_socket,
+    name_gen_user_tag,
+    name_gen_directory,
+}
+
+/// A policy for turning the short, logical names an application uses for its sockets into concrete local socket names,
+/// so that the conventions for where sockets live and what they are called are set up in one place instead of at every
+/// call to `bind()` and `connect()`.
+///
+/// A logical name is mapped to a namespaced name on platforms which support that, consisting of the configured prefix,
+/// the logical name and optionally an identifier of the current user. On platforms with only file paths, or if
+/// namespaced names are not preferred, the same string with the configured extension appended is used as the name of a
+/// file in the configured directory instead.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{LocalSocketListener, LocalSocketNameMapper, LocalSocketStream};
+///
+/// // Yields `\\.\pipe\MyApp\control-<user>` on Windows, `@MyApp\control-<uid>` on Linux and
+/// // `$XDG_RUNTIME_DIR/MyApp\control-<uid>.sock` or `/tmp/MyApp\control-<uid>.sock` elsewhere.
+/// let mapper = LocalSocketNameMapper::new().prefix("MyApp\\").per_user(true);
+///
+/// let listener = LocalSocketListener::bind(mapper.map("control"))?;
+/// // ...and in another process, with the same mapper:
+/// let conn = LocalSocketStream::connect(mapper.map("control"))?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LocalSocketNameMapper {
+    prefix: OsString,
+    extension: OsString,
+    directory: Option<PathBuf>,
+    namespaced: bool,
+    per_user: bool,
+}
+impl LocalSocketNameMapper {
+    /// Creates a mapper with no prefix, the `.sock` extension for file paths, the default directory, namespaced names
+    /// preferred and no scoping to the current user – that is, one which maps names the same way the `@` syntax of
+    /// [`ToLocalSocketName`](super::ToLocalSocketName) would if namespaced names are supported.
+    pub fn new() -> Self {
+        Self {
+            prefix: OsString::new(),
+            extension: OsString::from(".sock"),
+            directory: None,
+            namespaced: NameTypeSupport::query().namespace_supported(),
+            per_user: false,
+        }
+    }
+    /// Sets the string to put in front of every name, such as the name of the application followed by a separator. By
+    /// default, there is none.
+    ///
+    /// The prefix is used verbatim. On Windows, a prefix which ends with a backslash groups the pipes of the
+    /// application under a "directory" of its own in `\\.\pipe\`.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn prefix(mut self, prefix: impl Into<OsString>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+    /// Sets the string to append to the names of socket files, including the dot. By default, `.sock` is used. Not
+    /// used for namespaced names.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn extension(mut self, extension: impl Into<OsString>) -> Self {
+        self.extension = extension.into();
+        self
+    }
+    /// Sets the directory to put socket files in. By default, the per-user runtime directory is used if the names are
+    /// scoped to the current user and the platform has one, and the temporary directory otherwise. Not used for
+    /// namespaced names.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+    /// Sets whether namespaced names are to be used if the platform supports both kinds. By default, they are.
+    ///
+    /// Only file paths are produced on platforms which don't support namespaced names, and only namespaced names on
+    /// platforms which don't support file paths, regardless of this setting.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn prefer_namespaced(mut self, prefer_namespaced: bool) -> Self {
+        self.namespaced = match NameTypeSupport::query() {
+            NameTypeSupport::OnlyPaths => false,
+            NameTypeSupport::OnlyNamespaced => true,
+            NameTypeSupport::Both => prefer_namespaced,
+        };
+        self
+    }
+    /// Sets whether the names are to be scoped to the current user, so that several users running the same application
+    /// don't compete for the same names. By default, they are not.
+    ///
+    /// The current user is identified the same way as by
+    /// [`LocalSocketNameGen::per_user()`](super::LocalSocketNameGen::per_user), which also describes where the socket
+    /// files end up by default.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn per_user(mut self, per_user: bool) -> Self {
+        self.per_user = per_user;
+        self
+    }
+    /// Maps the given logical name to a local socket name according to the policy.
+    pub fn map(&self, name: impl AsRef<OsStr>) -> LocalSocketName<'static> {
+        let mut filename = self.prefix.clone();
+        filename.push(name);
+        if self.per_user {
+            filename.push("-");
+            filename.push(name_gen_user_tag());
+        }
+        if self.namespaced {
+            return LocalSocketName::from_raw_parts(Cow::Owned(filename), true);
+        }
+        filename.push(&self.extension);
+        let directory = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => name_gen_directory(self.per_user),
+        };
+        LocalSocketName::from_raw_parts(Cow::Owned(directory.join(filename).into_os_string()), false)
+    }
+}
+impl Default for LocalSocketNameMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+//! Serde support for [`LocalSocketName`].
+//!
+//! A name is represented as an enum with two newtype variants, `Path` and `Namespaced`, each holding the name as a
+//! string. This keeps the type of the name intact without resorting to the `@` syntax, which would make paths that start
+//! with `@` impossible to represent, and looks natural in self-describing formats – `{"Namespaced":"example.sock"}` in
+//! JSON, for example.
+
+use {
+    super::LocalSocketName,
+    serde::{
+        de::{self, Deserialize, Deserializer, EnumAccess, VariantAccess, Visitor},
+        ser::{self, Serialize, Serializer},
+    },
+    std::{borrow::Cow, ffi::OsString, fmt},
+};
+
+const NAME: &str = "LocalSocketName";
+const VARIANTS: &[&str] = &["Path", "Namespaced"];
+
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "serde")))]
+impl Serialize for LocalSocketName<'_> {
+    /// Serializes the name as a newtype variant of an enum, failing if the name is not valid Unicode.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (index, variant) = match self.is_namespaced() {
+            false => (0, VARIANTS[0]),
+            true => (1, VARIANTS[1]),
+        };
+        let name = self
+            .inner()
+            .to_str()
+            .ok_or_else(|| ser::Error::custom("local socket name is not valid Unicode"))?;
+        serializer.serialize_newtype_variant(NAME, index, variant, name)
+    }
+}
+
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "serde")))]
+impl<'de> Deserialize<'de> for LocalSocketName<'_> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum(NAME, VARIANTS, NameVisitor)
+    }
+}
+
+/// Which of the two variants is being deserialized.
+struct Namespaced(bool);
+impl<'de> Deserialize<'de> for Namespaced {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(VariantVisitor)
+    }
+}
+
+struct VariantVisitor;
+impl Visitor<'_> for VariantVisitor {
+    type Value = Namespaced;
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`Path` or `Namespaced`")
+    }
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        match v {
+            0 => Ok(Namespaced(false)),
+            1 => Ok(Namespaced(true)),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &"variant index 0 or 1")),
+        }
+    }
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            "Path" => Ok(Namespaced(false)),
+            "Namespaced" => Ok(Namespaced(true)),
+            _ => Err(E::unknown_variant(v, VARIANTS)),
+        }
+    }
+}
+
+struct NameVisitor;
+impl<'de> Visitor<'de> for NameVisitor {
+    type Value = LocalSocketName<'static>;
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a local socket name")
+    }
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (Namespaced(namespaced), variant) = data.variant()?;
+        let name = variant.newtype_variant::<String>()?;
+        Ok(LocalSocketName::from_raw_parts(
+            Cow::Owned(OsString::from(name)),
+            namespaced,
+        ))
+    }
+}
@@ -0,0 +1,37 @@
+/// Identity of the process on the other end of a local socket connection, as reported by the OS.
+///
+/// Returned by the `.peer_credentials()` methods on local socket streams. Since Unix and Windows identify users in
+/// fundamentally different ways, the credentials are tagged with the kind of platform they come from – matching on this
+/// enum lets access-control code handle both without resorting to conditional compilation, while [`.pid()`](Self::pid)
+/// covers the one piece of information that's available everywhere.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LocalSocketPeerCredentials {
+    /// Credentials of a peer connected via a Ud-socket.
+    Unix {
+        /// The process ID of the peer, or `None` if the platform doesn't report it (this is the case on macOS and iOS).
+        pid: Option<u32>,
+        /// The effective user ID of the peer at the time it connected.
+        uid: u32,
+        /// The effective group ID of the peer at the time it connected.
+        gid: u32,
+    },
+    /// Credentials of a peer connected via a named pipe.
+    Windows {
+        /// The process ID of the peer.
+        pid: u32,
+        /// The Remote Desktop Services session the peer process belongs to.
+        session_id: u32,
+        /// The security identifier of the user the peer process runs as, in its string form (`S-1-5-…`).
+        sid: String,
+    },
+}
+impl LocalSocketPeerCredentials {
+    /// Returns the process ID of the peer, or `None` if the platform doesn't report it.
+    pub fn pid(&self) -> Option<u32> {
+        match self {
+            Self::Unix { pid, .. } => *pid,
+            Self::Windows { pid, .. } => Some(*pid),
+        }
+    }
+}
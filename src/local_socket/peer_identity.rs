@@ -0,0 +1,69 @@
+use std::io;
+
+impmod! {local_socket,
+    current_process_identity,
+}
+
+/// The user a process on either end of a local socket connection runs as, in a form meant for access control decisions.
+///
+/// Returned by [`LocalSocketStream::peer_identity()`](super::LocalSocketStream::peer_identity). Unlike
+/// [`LocalSocketPeerCredentials`](super::LocalSocketPeerCredentials), which reports what the OS knows about the peer
+/// process, this only contains what identifies its user and its privileges, and comes with comparison helpers – the
+/// checks which are easy to get subtly wrong by hand, such as comparing real instead of effective user IDs or SID strings
+/// obtained in different ways, are done here once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LocalSocketPeerIdentity {
+    /// Identity of a process on a Unix platform.
+    Unix {
+        /// The effective user ID.
+        uid: u32,
+        /// The effective group ID.
+        gid: u32,
+    },
+    /// Identity of a process on Windows, taken from its access token.
+    Windows {
+        /// The security identifier of the user, in its string form (`S-1-5-…`).
+        sid: String,
+        /// Whether the token is elevated, i.e. the process was started with "Run as administrator" or by an elevated
+        /// process.
+        elevated: bool,
+        /// The relative identifier of the mandatory integrity label, such as `0x2000` for medium and `0x3000` for high
+        /// integrity.
+        integrity_level: u32,
+    },
+}
+impl LocalSocketPeerIdentity {
+    /// The integrity level of processes which run with administrative rights on Windows, `SECURITY_MANDATORY_HIGH_RID`.
+    pub const HIGH_INTEGRITY_LEVEL: u32 = 0x3000;
+
+    /// Retrieves the identity of the current process, to compare the identities of peers against.
+    pub fn of_current_process() -> io::Result<Self> {
+        current_process_identity()
+    }
+    /// Returns `true` if both identities belong to the same user, regardless of group or privileges.
+    pub fn is_same_user_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unix { uid, .. }, Self::Unix { uid: other_uid, .. }) => uid == other_uid,
+            (Self::Windows { sid, .. }, Self::Windows { sid: other_sid, .. }) => sid.eq_ignore_ascii_case(other_sid),
+            _ => false,
+        }
+    }
+    /// Returns `true` if the identity belongs to the same user as the current process, which is the most common access
+    /// control policy for local IPC.
+    pub fn is_same_user_as_current_process(&self) -> io::Result<bool> {
+        Ok(self.is_same_user_as(&Self::of_current_process()?))
+    }
+    /// Returns `true` if the process has administrative rights: the superuser on Unix, an elevated or high-integrity
+    /// process on Windows.
+    pub fn is_privileged(&self) -> bool {
+        match self {
+            Self::Unix { uid, .. } => *uid == 0,
+            Self::Windows {
+                elevated,
+                integrity_level,
+                ..
+            } => *elevated || *integrity_level >= Self::HIGH_INTEGRITY_LEVEL,
+        }
+    }
+}
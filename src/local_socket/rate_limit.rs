@@ -0,0 +1,210 @@
+use {
+    super::ListenerClosedError,
+    std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering::*},
+            Condvar, Mutex,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// The rate at which a listener accepts clients, set with
+/// [`accept_rate_limit`](super::LocalSocketListenerOptions::accept_rate_limit).
+///
+/// The limit is a token bucket: the listener starts out with [`burst`](Self::burst) tokens, spends one on every accepted
+/// client and regains them at the configured rate, never holding more than the burst size. Clients which arrive while
+/// the bucket is empty are either delayed until a token is regained, which is the default, or
+/// [shed](Self::shed).
+///
+/// # Example
+/// ```no_run
+/// use interprocess::local_socket::{AcceptRateLimit, LocalSocketListenerOptions};
+/// use std::time::Duration;
+///
+/// // At most 100 clients per second on average, with bursts of up to 20 on top of that.
+/// let limit = AcceptRateLimit::new(100, Duration::from_secs(1)).burst(20);
+/// let listener = LocalSocketListenerOptions::new()
+///     .accept_rate_limit(limit)
+///     .bind("@example.sock")?;
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AcceptRateLimit {
+    connections: u32,
+    per: Duration,
+    burst: u32,
+    shed: bool,
+}
+impl AcceptRateLimit {
+    /// Creates a limit of the specified amount of connections per the specified interval, with a burst size equal to
+    /// that amount.
+    pub fn new(connections: u32, per: Duration) -> Self {
+        Self {
+            connections,
+            per,
+            burst: connections,
+            shed: false,
+        }
+    }
+    /// Sets how many clients may be accepted back to back after a quiet period, i.e. the capacity of the bucket.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+    /// Sets whether clients beyond the rate are shed instead of delayed. By default, they are delayed.
+    ///
+    /// Delayed clients stay in the queue of pending connections while `accept()` waits for a token, or fails with an
+    /// error wrapping [`RateLimitError`] in nonblocking mode. Shed clients are accepted and immediately disconnected
+    /// without reaching the caller of `accept()`, which keeps waiting for the next client – this keeps the queue from
+    /// filling up with clients which will time out anyway, at the cost of turning them away. Shed clients are reported
+    /// to the [`on_reject`](super::LocalSocketHooks::on_reject) hook.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn shed(mut self, shed: bool) -> Self {
+        self.shed = shed;
+        self
+    }
+    /// The time it takes to regain one token.
+    fn interval(&self) -> Duration {
+        self.per / self.connections
+    }
+}
+
+/// Error produced by `accept()` on a nonblocking listener whose [accept rate limit](AcceptRateLimit) has been reached.
+///
+/// The error is delivered wrapped in an [`io::Error`] of kind [`WouldBlock`](io::ErrorKind::WouldBlock), so that
+/// nonblocking accept loops treat it like the absence of a client – use [`RateLimitError::is_in`] to tell the two
+/// apart.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RateLimitError;
+impl RateLimitError {
+    /// Returns `true` if the given I/O error was produced because the accept rate limit has been reached, `false`
+    /// otherwise.
+    pub fn is_in(err: &io::Error) -> bool {
+        err.get_ref().map_or(false, |e| e.is::<Self>())
+    }
+}
+impl Display for RateLimitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the accept rate limit has been reached")
+    }
+}
+impl Error for RateLimitError {}
+impl From<RateLimitError> for io::Error {
+    fn from(e: RateLimitError) -> Self {
+        io::Error::new(io::ErrorKind::WouldBlock, e)
+    }
+}
+
+/// The token bucket of a listener with an accept rate limit.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    burst: u32,
+    shed: bool,
+    bucket: Mutex<Bucket>,
+    /// Only signalled when the listener is closed, since waiting for a token has a known deadline.
+    closed: Condvar,
+    /// Mirrors the nonblocking mode of the listener, which decides between waiting for a token and failing right away.
+    nonblocking: AtomicBool,
+}
+#[derive(Debug)]
+struct Bucket {
+    tokens: u32,
+    /// When the most recent token was regained, or when the bucket was last full.
+    refilled: Instant,
+}
+impl RateLimiter {
+    pub fn new(limit: AcceptRateLimit, nonblocking: bool) -> io::Result<Self> {
+        if limit.connections == 0 || limit.per.is_zero() || limit.burst == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the accept rate limit must allow at least one connection per nonzero interval and burst",
+            ));
+        }
+        Ok(Self {
+            interval: limit.interval(),
+            burst: limit.burst,
+            shed: limit.shed,
+            bucket: Mutex::new(Bucket {
+                tokens: limit.burst,
+                refilled: Instant::now(),
+            }),
+            closed: Condvar::new(),
+            nonblocking: AtomicBool::new(nonblocking),
+        })
+    }
+    pub fn sheds(&self) -> bool {
+        self.shed
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) {
+        self.nonblocking.store(nonblocking, Relaxed);
+    }
+    /// Adds the tokens regained since the last refill, returning when the next one will be regained if the bucket is
+    /// empty.
+    fn refill(&self, bucket: &mut Bucket, now: Instant) -> Option<Instant> {
+        let regained = now.saturating_duration_since(bucket.refilled).as_nanos() / self.interval.as_nanos().max(1);
+        let regained = u32::try_from(regained).unwrap_or(u32::MAX);
+        if bucket.tokens.saturating_add(regained) >= self.burst {
+            bucket.tokens = self.burst;
+            bucket.refilled = now;
+        } else if regained > 0 {
+            bucket.tokens += regained;
+            bucket.refilled += self.interval * regained;
+        }
+        (bucket.tokens == 0).then(|| bucket.refilled + self.interval)
+    }
+    /// Takes a token if there is one, without waiting.
+    pub fn try_take(&self) -> bool {
+        let mut bucket = self.bucket.lock().expect("unexpected lock poison");
+        if self.refill(&mut bucket, Instant::now()).is_some() {
+            return false;
+        }
+        bucket.tokens -= 1;
+        true
+    }
+    /// Takes a token, waiting for one to be regained unless the listener is in nonblocking mode. No deadline means
+    /// waiting indefinitely.
+    pub fn take(&self, deadline: Option<Instant>, is_closed: impl Fn() -> bool) -> io::Result<()> {
+        let mut bucket = self.bucket.lock().expect("unexpected lock poison");
+        loop {
+            if is_closed() {
+                return Err(ListenerClosedError.into());
+            }
+            let now = Instant::now();
+            let Some(next) = self.refill(&mut bucket, now) else {
+                bucket.tokens -= 1;
+                return Ok(());
+            };
+            if self.nonblocking.load(Relaxed) {
+                return Err(RateLimitError.into());
+            }
+            if deadline.map_or(false, |deadline| deadline < next) {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the accept rate limit did not allow a client before the timeout expired",
+                ));
+            }
+            bucket = self
+                .closed
+                .wait_timeout(bucket, next - now)
+                .expect("unexpected lock poison")
+                .0;
+        }
+    }
+    /// Returns a token taken for a client which then failed to be accepted.
+    pub fn refund(&self) {
+        let mut bucket = self.bucket.lock().expect("unexpected lock poison");
+        bucket.tokens = (bucket.tokens + 1).min(self.burst);
+    }
+    /// Wakes up every thread waiting for a token, so that they can notice that the listener has been closed.
+    pub fn wake_all(&self) {
+        // Taking the lock ensures that a waiter which has just checked the flag is already waiting on the condvar.
+        let _bucket = self.bucket.lock().expect("unexpected lock poison");
+        self.closed.notify_all();
+    }
+}
@@ -0,0 +1,165 @@
+use {
+    super::{ListenerClosedError, LocalSocketListener, LocalSocketStream},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        panic::{self, AssertUnwindSafe},
+        sync::Arc,
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+};
+
+/// How long a worker waits before retrying after `accept()` fails, so that a persistent error such as running out of
+/// file descriptors doesn't turn into a busy loop.
+const ERROR_BACKOFF: Duration = Duration::from_millis(10);
+
+/// A pool of threads serving connections to a [`LocalSocketListener`] with a user-provided handler.
+///
+/// Each of the worker threads runs its own accept loop and calls the handler on the connections it receives, so up to
+/// as many clients as there are workers are served at the same time. Errors from `accept()` only concern the client
+/// which was being accepted and are therefore skipped, and a panic in the handler only drops the connection it was
+/// called with, leaving the worker running.
+///
+/// The pool runs until it's [shut down](Self::shutdown), either directly or via a [`ServerShutdownHandle`] from
+/// another thread. Dropping it shuts it down as well.
+///
+/// # Example
+/// ```no_run
+/// use {
+///     interprocess::local_socket::{LocalSocketListener, LocalSocketServer},
+///     std::io::{prelude::*, BufReader},
+/// };
+///
+/// let listener = LocalSocketListener::bind("/tmp/example.sock")?;
+/// let server = LocalSocketServer::spawn(listener, 4, |conn| {
+///     let mut conn = BufReader::new(conn);
+///     let mut line = String::new();
+///     if conn.read_line(&mut line).is_ok() {
+///         let _ = conn.get_mut().write_all(line.as_bytes());
+///     }
+/// })?;
+/// // Hand this to whatever decides when the server should stop.
+/// let shutdown = server.shutdown_handle();
+/// # shutdown.shutdown();
+/// server.join();
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct LocalSocketServer {
+    listener: Arc<LocalSocketListener>,
+    workers: Vec<JoinHandle<()>>,
+}
+impl LocalSocketServer {
+    /// Starts serving connections to the given listener with the specified amount of worker threads, each of which
+    /// calls `handler` on the connections it accepts.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if `threads` is zero, or with the error from the OS if
+    /// a thread could not be spawned, in which case the already started workers are stopped.
+    pub fn spawn<F>(listener: LocalSocketListener, threads: usize, handler: F) -> io::Result<Self>
+    where
+        F: Fn(LocalSocketStream) + Send + Sync + 'static,
+    {
+        if threads == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "server needs at least one worker thread",
+            ));
+        }
+        let handler = Arc::new(handler);
+        let mut slf = Self {
+            listener: Arc::new(listener),
+            workers: Vec::with_capacity(threads),
+        };
+        for i in 0..threads {
+            let listener = Arc::clone(&slf.listener);
+            let handler = Arc::clone(&handler);
+            // On failure, the drop of slf stops and joins the workers spawned so far.
+            let worker = thread::Builder::new()
+                .name(format!("local-socket-server-{}", i))
+                .spawn(move || serve(&listener, &*handler))?;
+            slf.workers.push(worker);
+        }
+        Ok(slf)
+    }
+    /// Returns a handle which can be used to shut the server down from another thread.
+    pub fn shutdown_handle(&self) -> ServerShutdownHandle {
+        ServerShutdownHandle {
+            listener: Arc::clone(&self.listener),
+        }
+    }
+    /// Stops accepting new connections and waits for the workers to finish handling the ones they are currently
+    /// serving.
+    pub fn shutdown(self) {
+        self.listener.close();
+        self.join();
+    }
+    /// Waits until the server is shut down via a [`ServerShutdownHandle`] and the workers finish handling the
+    /// connections they are currently serving.
+    pub fn join(mut self) {
+        self.join_workers();
+    }
+    /// Returns `true` if the server has been shut down, `false` otherwise. Workers may still be finishing up their
+    /// connections at that point.
+    pub fn is_shut_down(&self) -> bool {
+        self.listener.is_closed()
+    }
+    fn join_workers(&mut self) {
+        for worker in self.workers.drain(..) {
+            // Panics in the handler are caught by the workers themselves.
+            let _ = worker.join();
+        }
+    }
+}
+impl Drop for LocalSocketServer {
+    fn drop(&mut self) {
+        if !self.workers.is_empty() {
+            self.listener.close();
+            self.join_workers();
+        }
+    }
+}
+impl Debug for LocalSocketServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketServer")
+            .field("listener", &self.listener)
+            .field("workers", &self.workers.len())
+            .finish()
+    }
+}
+
+/// A handle for shutting down a [`LocalSocketServer`] from another thread, obtained via
+/// [`.shutdown_handle()`](LocalSocketServer::shutdown_handle).
+#[derive(Clone)]
+pub struct ServerShutdownHandle {
+    listener: Arc<LocalSocketListener>,
+}
+impl ServerShutdownHandle {
+    /// Makes the server stop accepting new connections. Does not wait for the workers to finish, which is what
+    /// [`LocalSocketServer::join()`] is for.
+    pub fn shutdown(&self) {
+        self.listener.close();
+    }
+    /// Returns `true` if the server has been shut down, `false` otherwise.
+    pub fn is_shut_down(&self) -> bool {
+        self.listener.is_closed()
+    }
+}
+impl Debug for ServerShutdownHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerShutdownHandle")
+            .field("shut_down", &self.is_shut_down())
+            .finish()
+    }
+}
+
+fn serve(listener: &LocalSocketListener, handler: &(dyn Fn(LocalSocketStream) + Sync)) {
+    loop {
+        match listener.accept() {
+            Ok(conn) => {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| handler(conn)));
+            }
+            Err(e) if ListenerClosedError::is_in(&e) => return,
+            Err(_) => thread::sleep(ERROR_BACKOFF),
+        }
+    }
+}
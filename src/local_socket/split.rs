@@ -0,0 +1,215 @@
+use {
+    super::{LocalSocketPeerCredentials, LocalSocketStream},
+    std::{
+        error::Error,
+        fmt::{self, Debug, Display, Formatter},
+        io::{self, prelude::*, IoSlice, IoSliceMut},
+        sync::Arc,
+    },
+};
+
+/// A borrowed read half of a [`LocalSocketStream`], obtained via [`.split()`](LocalSocketStream::split).
+///
+/// Reading from this half and writing to the corresponding [`WriteHalf`] can happen concurrently, from different
+/// threads.
+pub struct ReadHalf<'a> {
+    pub(super) stream: &'a LocalSocketStream,
+}
+impl ReadHalf<'_> {
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.stream.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.stream.peer_credentials()
+    }
+}
+impl Read for ReadHalf<'_> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.stream.read_vectored(bufs)
+    }
+}
+impl Debug for ReadHalf<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReadHalf").field(self.stream).finish()
+    }
+}
+
+/// A borrowed write half of a [`LocalSocketStream`], obtained via [`.split()`](LocalSocketStream::split).
+///
+/// Writing to this half and reading from the corresponding [`ReadHalf`] can happen concurrently, from different
+/// threads.
+pub struct WriteHalf<'a> {
+    pub(super) stream: &'a LocalSocketStream,
+}
+impl WriteHalf<'_> {
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.stream.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.stream.peer_credentials()
+    }
+}
+impl Write for WriteHalf<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.stream.write_vectored(bufs)
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+impl Debug for WriteHalf<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WriteHalf").field(self.stream).finish()
+    }
+}
+
+fn reunite(rh: OwnedReadHalf, wh: OwnedWriteHalf) -> Result<LocalSocketStream, ReuniteError> {
+    if !Arc::ptr_eq(&rh.stream, &wh.stream) {
+        return Err(ReuniteError {
+            read_half: rh,
+            write_half: wh,
+        });
+    }
+    drop(wh);
+    Ok(Arc::try_unwrap(rh.stream).unwrap_or_else(|_| {
+        unreachable!("reference counter unwrap failed, even though the other half has just been dropped")
+    }))
+}
+
+/// An owned read half of a [`LocalSocketStream`], obtained via [`.into_split()`](LocalSocketStream::into_split).
+///
+/// Unlike [`ReadHalf`], this half can be moved to another thread without scoping, and can be put back together with
+/// its [`OwnedWriteHalf`] via [`.reunite()`](Self::reunite).
+pub struct OwnedReadHalf {
+    pub(super) stream: Arc<LocalSocketStream>,
+}
+impl OwnedReadHalf {
+    /// Attempts to reunite this read half with the given write half to yield the original stream back, returning both
+    /// halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedWriteHalf) -> Result<LocalSocketStream, ReuniteError> {
+        reunite(self, other)
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.stream.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.stream.peer_credentials()
+    }
+}
+impl Read for OwnedReadHalf {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self.stream).read(buf)
+    }
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&*self.stream).read_vectored(bufs)
+    }
+}
+impl Debug for OwnedReadHalf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedReadHalf").field(&*self.stream).finish()
+    }
+}
+
+/// An owned write half of a [`LocalSocketStream`], obtained via [`.into_split()`](LocalSocketStream::into_split).
+///
+/// Unlike [`WriteHalf`], this half can be moved to another thread without scoping, and can be put back together with
+/// its [`OwnedReadHalf`] via [`.reunite()`](Self::reunite).
+pub struct OwnedWriteHalf {
+    pub(super) stream: Arc<LocalSocketStream>,
+}
+impl OwnedWriteHalf {
+    /// Attempts to reunite this write half with the given read half to yield the original stream back, returning both
+    /// halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedReadHalf) -> Result<LocalSocketStream, ReuniteError> {
+        reunite(other, self)
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`] for the details.
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.stream.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`] for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.stream.peer_credentials()
+    }
+}
+impl Write for OwnedWriteHalf {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self.stream).write(buf)
+    }
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&*self.stream).write_vectored(bufs)
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self.stream).flush()
+    }
+}
+impl Debug for OwnedWriteHalf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedWriteHalf").field(&*self.stream).finish()
+    }
+}
+
+/// Error type for `.reunite()` on owned read and write halves.
+///
+/// The error indicates that the halves belong to different streams and allows to recover both of them.
+#[derive(Debug)]
+pub struct ReuniteError {
+    /// The read half that didn't go anywhere, in case you still need it.
+    pub read_half: OwnedReadHalf,
+    /// The write half that didn't go anywhere, in case you still need it.
+    pub write_half: OwnedWriteHalf,
+}
+impl Display for ReuniteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the read and write halves belong to different local socket streams")
+    }
+}
+impl Error for ReuniteError {}
@@ -1,11 +1,23 @@
 use {
-    super::ToLocalSocketName,
+    super::{
+        BorrowedTransferable, ConnectionPermit, LocalSocketPeerCredentials, LocalSocketPeerIdentity, OwnedReadHalf,
+        OwnedTransferable, OwnedWriteHalf, ReadHalf, ToLocalSocketName, WriteHalf,
+    },
+    crate::timeout::WaitReady,
     std::{
         fmt::{self, Debug, Formatter},
         io::{self, prelude::*, IoSlice, IoSliceMut},
+        sync::Arc,
+        time::Duration,
     },
 };
 
+#[cfg(feature = "bytes")]
+use {
+    crate::reliable_recv_msg::BYTES_MUT_MIN_SPARE,
+    bytes::{BufMut, BytesMut},
+};
+
 impmod! {local_socket,
     LocalSocketStream as LocalSocketStreamImpl
 }
@@ -60,22 +72,198 @@ impmod! {local_socket,
 /// ```
 pub struct LocalSocketStream {
     pub(super) inner: LocalSocketStreamImpl,
+    /// The slot taken up in the connection limit of the listener that accepted this stream, shared with its clones.
+    pub(super) permit: Option<Arc<ConnectionPermit>>,
 }
 impl LocalSocketStream {
     /// Connects to a remote local socket server.
+    ///
+    /// Use [`LocalSocketConnectOptions`](super::LocalSocketConnectOptions) to retry if the server is not up yet.
     pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         Ok(Self {
             inner: LocalSocketStreamImpl::connect(name)?,
+            permit: None,
+        })
+    }
+    /// Connects to a server on another computer, as set up by the Windows-specific connect options.
+    #[cfg(windows)]
+    pub(super) fn connect_to_remote<'a>(
+        name: impl ToLocalSocketName<'a>,
+        hostname: &std::ffi::OsStr,
+    ) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketStreamImpl::connect_to_remote(name, hostname)?,
+            permit: None,
         })
     }
     /// Retrieves the identifier of the process on the opposite end of the local socket connection.
     ///
     /// # Platform-specific behavior
+    /// ## Windows
+    /// Uses `GetNamedPipeClientProcessId` on the server side of the connection and `GetNamedPipeServerProcessId` on the
+    /// client side.
     /// ## macOS and iOS
     /// Not supported by the OS, will always generate an error at runtime.
     pub fn peer_pid(&self) -> io::Result<u32> {
         self.inner.peer_pid()
     }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection: its process ID, user and
+    /// group on Unix, or its process ID, session and user SID on Windows.
+    ///
+    /// # Platform-specific behavior
+    /// ## macOS and iOS
+    /// The process ID is not reported by the OS and will be `None`.
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    /// Retrieves the identity of the user the process on the opposite end of the local socket connection runs as, which
+    /// can be compared with the one of the current process via
+    /// [`.is_same_user_as_current_process()`](LocalSocketPeerIdentity::is_same_user_as_current_process).
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// The access token of the peer process is opened, which requires the `PROCESS_QUERY_LIMITED_INFORMATION` access
+    /// right to it.
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        self.inner.peer_identity()
+    }
+    /// Creates a new handle to the same connection, which can be used independently of the original one – for example,
+    /// by a reader thread and a writer thread, without splitting the stream. The connection is closed once all handles
+    /// are dropped.
+    ///
+    /// The nonblocking mode is shared between the clones.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// The file descriptor is duplicated with `fcntl(F_DUPFD_CLOEXEC)`.
+    /// ## Windows
+    /// The handle is duplicated with `DuplicateHandle`.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            inner: self.inner.try_clone()?,
+            permit: self.permit.clone(),
+        })
+    }
+    /// Splits the stream into a read half and a write half borrowing it, which can be used for reading and writing
+    /// concurrently – for example, from two [scoped threads](std::thread::scope).
+    ///
+    /// Both halves can also be obtained by simply taking two shared references to the stream, since `Read` and `Write`
+    /// are implemented for `&LocalSocketStream`; the halves make the intent clear and prevent mixing up the directions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use interprocess::local_socket::LocalSocketStream;
+    /// use std::{io::{prelude::*, BufReader}, thread};
+    ///
+    /// let conn = LocalSocketStream::connect("@example.sock")?;
+    /// let (reader, mut writer) = conn.split();
+    /// thread::scope(|s| {
+    ///     let sender = s.spawn(move || writer.write_all(b"Hello from client!\n"));
+    ///     let mut line = String::new();
+    ///     BufReader::new(reader).read_line(&mut line)?;
+    ///     print!("Server answered: {line}");
+    ///     sender.join().unwrap()
+    /// })?;
+    /// # std::io::Result::<()>::Ok(())
+    /// ```
+    pub fn split(&self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        (ReadHalf { stream: self }, WriteHalf { stream: self })
+    }
+    /// Splits the stream into a read half and a write half which own it, and can thus be moved to different threads
+    /// freely. The halves can be put back together via `.reunite()`.
+    ///
+    /// This is the synchronous counterpart of the method of the same name on the Tokio-based stream; see
+    /// [`.split()`](Self::split) for a variant which doesn't need to allocate.
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        let stream = Arc::new(self);
+        (
+            OwnedReadHalf {
+                stream: Arc::clone(&stream),
+            },
+            OwnedWriteHalf { stream },
+        )
+    }
+    /// Sends bytes together with the given file descriptors or handles, which the other side receives with
+    /// [`.recv_handles()`](Self::recv_handles). The resources are duplicated, so the ones passed to this method remain
+    /// owned by the caller.
+    ///
+    /// Returns the amount of bytes sent. The data must not be empty, since the resources can only travel together with
+    /// at least one byte, and the stream must be in blocking mode.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// The file descriptors are sent as `SCM_RIGHTS` ancillary data, as with
+    /// [`UdStream::send_fds()`](crate::os::unix::udsocket::UdStream::send_fds).
+    /// ## Windows
+    /// Named pipes cannot carry handles, so the handles are duplicated within this process and their values are sent in
+    /// a small header in front of the data. The receiving side then moves them over to its own process with
+    /// `DuplicateHandle`, for which it needs the `PROCESS_DUP_HANDLE` right on this process – this is normally the case
+    /// when both processes run as the same user. If the other side never receives the handles, they stay open in this
+    /// process until it exits.
+    pub fn send_handles(&self, buf: &[u8], handles: &[BorrowedTransferable<'_>]) -> io::Result<usize> {
+        self.inner.send_handles(buf, handles)
+    }
+    /// Receives bytes and file descriptors or handles sent with [`.send_handles()`](Self::send_handles).
+    ///
+    /// Returns the amount of bytes received and the resources, which are now owned by this process. If the buffer is
+    /// too small to fit all of the data, the rest remains in the stream and can be read normally. The stream must be in
+    /// blocking mode.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// Works like [`UdStream::recv_fds()`](crate::os::unix::udsocket::UdStream::recv_fds), discarding any control
+    /// messages other than file descriptors.
+    /// ## Windows
+    /// Fails if the header in front of the data is malformed or if the handles cannot be duplicated from the other
+    /// process, which requires the `PROCESS_DUP_HANDLE` access right to it.
+    pub fn recv_handles(&self, buf: &mut [u8]) -> io::Result<(usize, Vec<OwnedTransferable>)> {
+        self.inner.recv_handles(buf)
+    }
+    /// Receives data from the stream without removing it, so that the next read returns it again. Returns how many bytes
+    /// were copied into the buffer, which is zero if the other side has closed the connection.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// Blocks until data arrives unless the stream is in nonblocking mode, like reading would.
+    /// ## Windows
+    /// Never blocks: if no data is available, a [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned even if the
+    /// stream is in blocking mode.
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.peek(buf)
+    }
+    /// Returns how many bytes can be read from the stream without blocking, which is useful for sizing buffers. Zero
+    /// means that either nothing has arrived yet or the other side has closed the connection.
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        self.inner.bytes_available()
+    }
+    /// Waits until reading from the stream would not block, returning `false` if the timeout expires first. No timeout
+    /// means waiting indefinitely.
+    ///
+    /// This is what makes [nonblocking mode](Self::set_nonblocking) usable without busy-looping on
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) errors. The stream also counts as readable once the other side has
+    /// closed the connection, since reading then returns end of file right away.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// Uses `poll`.
+    /// ## Windows
+    /// Named pipes can only be waited on with overlapped I/O, which local sockets don't use, so the pipe is polled with
+    /// `PeekNamedPipe` at short intervals, which may delay the wakeup by a few milliseconds.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    /// Waits until writing to the stream would not block, returning `false` if the timeout expires first. No timeout
+    /// means waiting indefinitely.
+    ///
+    /// # Platform-specific behavior
+    /// ## Unix
+    /// Uses `poll`.
+    /// ## Windows
+    /// Always returns `true` right away: in nonblocking mode, writing to a named pipe writes as much as fits into its
+    /// buffer instead of failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_writable(timeout)
+    }
     /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
     ///
     /// In nonblocking mode, reading and writing will immediately return with the [`WouldBlock`] error in situations when they would normally block for an uncontrolled amount of time. The specific situations are:
@@ -86,25 +274,96 @@ impl LocalSocketStream {
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         self.inner.set_nonblocking(nonblocking)
     }
+    /// Reads bytes from the stream into the spare capacity of the given buffer, appending to its contents without
+    /// zeroing the memory first, and returns how many bytes were read.
+    ///
+    /// If the buffer has no spare capacity, some is reserved, so that zero is only ever returned at end of file. This
+    /// is the counterpart of Tokio's `AsyncReadExt::read_buf()`, which the [Tokio-based
+    /// stream](super::tokio::LocalSocketStream) supports out of the box, and makes it possible to hand received data to
+    /// codecs and network stacks working with [`BytesMut`] without copying it.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+    pub fn read_buf(&self, buf: &mut BytesMut) -> io::Result<usize> {
+        if buf.capacity() == buf.len() {
+            buf.reserve(BYTES_MUT_MIN_SPARE);
+        }
+        let size = self.inner.read_to_uninit(unsafe {
+            // SAFETY: the read never de-initializes the memory
+            buf.chunk_mut().as_uninit_slice_mut()
+        })?;
+        unsafe {
+            // SAFETY: the read initialized this many bytes of the spare capacity
+            buf.advance_mut(size);
+        }
+        Ok(size)
+    }
+    /// Converts the stream into a [Tokio-based one](super::tokio::LocalSocketStream), switching it to nonblocking
+    /// mode and registering it with the Tokio runtime this function is called in. Calling it outside a runtime will
+    /// result in an error. The connection is handed over as-is, without any data being lost.
+    ///
+    /// If the stream was accepted from a listener with a [connection
+    /// limit](super::LocalSocketListenerOptions::max_concurrent_connections), it no longer counts towards that limit once
+    /// converted, unless it has clones which are still alive.
+    ///
+    /// The reverse operation is [`.into_sync()`](super::tokio::LocalSocketStream::into_sync).
+    ///
+    /// # Platform-specific behavior
+    /// This is only available on Unix. On Windows, Tokio requires named pipes to be opened for overlapped I/O, which
+    /// can't be enabled or disabled on an existing pipe, and the synchronous local sockets are not opened that way.
+    #[cfg(all(unix, feature = "tokio"))]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(all(unix, feature = "tokio"))))]
+    pub fn into_tokio(self) -> io::Result<super::tokio::LocalSocketStream> {
+        crate::os::unix::local_socket::tokio::LocalSocketStream::from_sync(self.inner).map(From::from)
+    }
 }
 // TODO vectored I/O on Unix
+impl Read for &LocalSocketStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&self.inner).read_vectored(bufs)
+    }
+}
+impl Write for &LocalSocketStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&self.inner).write_vectored(bufs)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.inner).flush()
+    }
+}
 impl Read for LocalSocketStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+        (&*self).read(buf)
     }
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.read_vectored(bufs)
+        (&*self).read_vectored(bufs)
     }
 }
 impl Write for LocalSocketStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        (&*self).write(buf)
     }
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.write_vectored(bufs)
+        (&*self).write_vectored(bufs)
     }
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.flush()
+        (&*self).flush()
+    }
+}
+impl WaitReady for LocalSocketStream {
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_readable(timeout)
+    }
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_writable(timeout)
+    }
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.set_nonblocking(nonblocking)
     }
 }
 impl Debug for LocalSocketStream {
@@ -112,4 +371,45 @@ impl Debug for LocalSocketStream {
         Debug::fmt(&self.inner, f)
     }
 }
-impl_handle_manip!(LocalSocketStream);
+impl_as_raw_handle!(LocalSocketStream);
+impl_into_raw_handle!(LocalSocketStream);
+impl_as_handle!(LocalSocketStream);
+impl_into_handle!(LocalSocketStream);
+#[cfg(windows)]
+impl TryFrom<std::os::windows::io::OwnedHandle> for LocalSocketStream {
+    type Error = crate::handle_conversion::FromHandleError;
+    fn try_from(handle: std::os::windows::io::OwnedHandle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: TryFrom::try_from(handle)?,
+            permit: None,
+        })
+    }
+}
+#[cfg(unix)]
+impl TryFrom<std::os::unix::io::OwnedFd> for LocalSocketStream {
+    type Error = crate::handle_conversion::FromHandleError;
+    fn try_from(fd: std::os::unix::io::OwnedFd) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: TryFrom::try_from(fd)?,
+            permit: None,
+        })
+    }
+}
+#[cfg(windows)]
+impl std::os::windows::io::FromRawHandle for LocalSocketStream {
+    unsafe fn from_raw_handle(handle: *mut std::ffi::c_void) -> Self {
+        Self {
+            inner: unsafe { std::os::windows::io::FromRawHandle::from_raw_handle(handle) },
+            permit: None,
+        }
+    }
+}
+#[cfg(unix)]
+impl std::os::unix::io::FromRawFd for LocalSocketStream {
+    unsafe fn from_raw_fd(fd: libc::c_int) -> Self {
+        Self {
+            inner: unsafe { std::os::unix::io::FromRawFd::from_raw_fd(fd) },
+            permit: None,
+        }
+    }
+}
@@ -23,7 +23,7 @@ impmod! {local_socket,
 ///
 /// The feature, in its core, is extremely simple: if the first character in a string is the @ character, the value of the string is interpreted and stored as a namespaced name (otherwise, it's treated as a filesystem path); the @ character is then removed from the string (by taking a subslice which dosen't include it if a string slice is being used; for owned strings, it's simply removed from the string by shifting the entire string towards the beginning). **[`Path`] and [`PathBuf`] are not affected at all – those have explicit path semantics and therefore cannot logically represent namespaced names.**
 ///
-/// This feature is extremely useful both when using hardcoded literals and accepting user input for the path, but sometimes you might want to prevent this behavior. In such a case, you have the following possible approaches:
+/// This feature is extremely useful both when using hardcoded literals and accepting user input for the path, but sometimes you might want to prevent this behavior. The simplest way to do so is to construct the name with [`LocalSocketName::namespaced()`] or [`LocalSocketName::path()`], which leave the string untouched and make the type of the name explicit. Alternatively, you have the following possible approaches:
 /// - If the string is a [`OsStr`]/[`OsString`], it can be cheaply converted to a [`Path`]/[`PathBuf`], which do not support the @ syntax
 /// - If the string is a [`str`]/[`String`], it can be cheaply converted to [`OsStr`]/[`OsString`]; then the above method can be applied
 /// - If the string is a [`CStr`]/[`CString`], it can be converted to [`str`]/[`String`] using the following code:
@@ -59,6 +59,12 @@ pub trait ToLocalSocketName<'a> {
     fn to_local_socket_name(self) -> io::Result<LocalSocketName<'a>>;
 }
 
+/// Passes a [`LocalSocketName`] through as is, so that names which have already been converted, such as the ones produced by [`LocalSocketNameGen`](super::LocalSocketNameGen), can be used directly.
+impl<'a> ToLocalSocketName<'a> for LocalSocketName<'a> {
+    fn to_local_socket_name(self) -> io::Result<LocalSocketName<'a>> {
+        Ok(self)
+    }
+}
 /// Converts a borrowed [`Path`] to a borrowed file-type [`LocalSocketName`] with the same lifetime.
 impl<'a> ToLocalSocketName<'a> for &'a Path {
     fn to_local_socket_name(self) -> io::Result<LocalSocketName<'a>> {
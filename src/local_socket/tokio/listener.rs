@@ -3,6 +3,7 @@ use {
     std::{
         fmt::{self, Debug, Formatter},
         io,
+        task::{Context, Poll},
     },
 };
 
@@ -105,17 +106,56 @@ pub struct LocalSocketListener {
 }
 impl LocalSocketListener {
     /// Creates a socket server with the specified local socket name.
+    ///
+    /// Use [`LocalSocketListenerOptions::bind_tokio()`](super::super::LocalSocketListenerOptions::bind_tokio) to
+    /// control the permissions of the socket, the backlog and other options.
     #[inline]
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         LocalSocketListenerImpl::bind(name).map(Self::from)
     }
     /// Listens for incoming connections to the socket, asynchronously waiting until a client is connected.
+    ///
+    /// If the listener is [closed](Self::close) while this is pending or before it is called, an error wrapping [`ListenerClosedError`](super::ListenerClosedError) is returned.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe on all platforms: if the future is dropped before it completes, such as when another branch of `tokio::select!` finishes first, no client is lost – a client that has already connected is returned by the next call. Together with [`.close()`](Self::close), this allows a server loop to be shut down either from inside via `select!` or from another task.
     #[inline]
     pub async fn accept(&self) -> io::Result<LocalSocketStream> {
         Ok(LocalSocketStream {
             inner: self.inner.accept().await?,
         })
     }
+    /// Raw polling interface for accepting incoming connections, for use in hand-written futures and streams. You
+    /// probably want [`.accept()`](Self::accept) instead.
+    ///
+    /// If there is no client to accept, `Poll::Pending` is returned and the task is woken up once one connects. Only
+    /// the waker from the most recent call is scheduled to receive the wakeup, so this method should only be polled by
+    /// one task at a time.
+    ///
+    /// Returns an error wrapping [`ListenerClosedError`](super::ListenerClosedError) if the listener has been
+    /// [closed](Self::close). Closing the listener also wakes up the task waiting for this method to return
+    /// `Poll::Ready`, which then receives that error.
+    #[inline]
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<LocalSocketStream>> {
+        self.inner.poll_accept(cx).map_ok(|inner| LocalSocketStream { inner })
+    }
+    /// Closes the listener, waking up all pending [`.accept()`](Self::accept) calls with an error wrapping [`ListenerClosedError`](super::ListenerClosedError). All subsequent calls to `.accept()` will fail with the same error.
+    ///
+    /// This does not remove the socket file on platforms where local sockets reside on the filesystem – use [`.close_and_unlink()`](Self::close_and_unlink) for that.
+    #[inline]
+    pub fn close(&self) {
+        self.inner.close()
+    }
+    /// Same as [`.close()`](Self::close), but also removes the socket file if the listener is bound to a filesystem path. On Windows, as well as for namespaced names, there is no file to remove and this is equivalent to `.close()`.
+    #[inline]
+    pub fn close_and_unlink(&self) -> io::Result<()> {
+        self.inner.close_and_unlink()
+    }
+    /// Returns `true` if the listener has been [closed](Self::close), `false` otherwise.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
     /// Creates a Tokio-based async object from a given raw file descriptor. This will also attach the object to the Tokio runtime this function is called in, so calling it outside a runtime will result in an error (which is why the `FromRawFd` trait can't be implemented instead).
     ///
     /// # Safety
@@ -148,4 +188,5 @@ impl Debug for LocalSocketListener {
     }
 }
 impl_as_raw_handle_unix!(LocalSocketListener);
+impl_as_handle_unix!(LocalSocketListener);
 // TODO: incoming
@@ -2,10 +2,20 @@
 //!
 //! The Tokio integration allows the local socket streams and listeners to be notified by the OS kernel whenever they're ready to be read from of written to, instead of spawning threads just to put them in a wait state of blocking on the I/O.
 //!
-//! Types from this module will *not* work with other async runtimes, such as `async-std` or `smol`, since the Tokio types' methods will panic whenever they're called outside of a Tokio runtime context. Open an issue if you'd like to see other runtimes supported as well.
+//! Types from this module will *not* work with other async runtimes, such as `async-std` or `smol`, since the Tokio types' methods will panic whenever they're called outside of a Tokio runtime context. See the [`generic`](super::generic) module for local sockets which can be used with other runtimes.
+//!
+//! The byte stream types and their halves implement both Tokio's `AsyncRead` and `AsyncWrite` and their counterparts from the `futures` crate, so they can be used with `tokio::io::copy()`, `tokio_util::codec::Framed` and the like without a compatibility adapter. Since the two sets of traits have methods with the same names, the extension traits `tokio::io::AsyncReadExt` and `futures::io::AsyncReadExt` (and the same for writing) should not be imported into the same scope.
 
 mod listener;
 pub use listener::*;
 
+mod msg_listener;
+pub use msg_listener::*;
+
+mod msg_stream;
+pub use msg_stream::*;
+
 mod stream;
 pub use stream::*;
+
+pub use crate::listener_close::ListenerClosedError;
@@ -0,0 +1,68 @@
+use {
+    super::{super::ToLocalSocketName, LocalSocketMsgStream},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+    },
+};
+
+impmod! {local_socket::tokio,
+    LocalSocketMsgListener as LocalSocketMsgListenerImpl
+}
+
+/// A Tokio-based local socket server for message-mode connections, the asynchronous counterpart of
+/// [`LocalSocketMsgListener`](crate::local_socket::LocalSocketMsgListener).
+///
+/// On Unix, this is a `SOCK_SEQPACKET` Ud-socket, which is not supported on macOS and iOS. On Windows, named pipes in
+/// message mode (`PipeMode::Messages`) are used.
+///
+/// # Examples
+///
+/// ## Basic server
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use interprocess::local_socket::{tokio::LocalSocketMsgListener, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_msg.sock",
+///         OnlyNamespaced | Both => "@example_msg.sock",
+///     }
+/// };
+/// let listener = LocalSocketMsgListener::bind(name)?;
+///
+/// loop {
+///     let conn = listener.accept().await?;
+///     // Every message arrives whole, no matter how it was fragmented on its way.
+///     let msg = conn.recv().await?;
+///     println!("Client sent: {}", String::from_utf8_lossy(&msg));
+///     conn.send(b"Hello from server!").await?;
+/// }
+/// # }
+/// ```
+pub struct LocalSocketMsgListener {
+    inner: LocalSocketMsgListenerImpl,
+}
+impl LocalSocketMsgListener {
+    /// Creates a message-mode socket server with the specified local socket name.
+    ///
+    /// On Unix, the socket file, if one is created, is deleted once the listener is dropped.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Ok(Self {
+            inner: LocalSocketMsgListenerImpl::bind(name)?,
+        })
+    }
+    /// Listens for incoming connections to the socket, asynchronously waiting until a client is connected.
+    pub async fn accept(&self) -> io::Result<LocalSocketMsgStream> {
+        Ok(LocalSocketMsgStream::new(self.inner.accept().await?))
+    }
+}
+impl Debug for LocalSocketMsgListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl_as_raw_handle_unix!(LocalSocketMsgListener);
+impl_as_handle_unix!(LocalSocketMsgListener);
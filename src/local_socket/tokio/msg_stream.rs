@@ -0,0 +1,262 @@
+use {
+    super::super::{LocalSocketPeerCredentials, ToLocalSocketName},
+    crate::{
+        error::{self, Error},
+        msg_adapters::AsyncSendMsg,
+        reliable_recv_msg::{AsyncReliableRecvMsg, TryRecvResult},
+    },
+    futures_core::{ready, Stream},
+    futures_sink::Sink,
+    std::{
+        fmt::{self, Debug, Formatter},
+        future, io,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+#[cfg(feature = "bytes")]
+use bytes::{Bytes, BytesMut};
+
+impmod! {local_socket::tokio,
+    LocalSocketMsgStream as LocalSocketMsgStreamImpl
+}
+
+/// Size of the buffer on the stack that messages are first received into, before being copied into a `Vec` of the
+/// exact size. Bigger messages are received into a `Vec` directly.
+const STACK_RECV_BUF_SIZE: usize = 1024;
+
+/// A Tokio-based message-mode local socket connection, obtained either from
+/// [`LocalSocketMsgListener`](super::LocalSocketMsgListener) or by connecting to an existing message-mode local socket.
+///
+/// Every [`send()`](Self::send) corresponds to exactly one [`recv()`](Self::recv) on the receiving end. Received
+/// messages are never truncated.
+///
+/// The stream also implements [`Stream`] over received messages and [`Sink`] for messages to send, so that it can be
+/// used with the combinators from the `futures` crate. The stream of messages ends once the other side hangs up. Note
+/// that the inherent [`send()`](Self::send) takes precedence over `SinkExt::send()` in method call syntax. With the
+/// `bytes` feature enabled, [`.bytes_sink()`](Self::bytes_sink) provides a sink for [`Bytes`](bytes::Bytes) as well.
+///
+/// # Examples
+///
+/// ## Basic client
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use interprocess::local_socket::{tokio::LocalSocketMsgStream, NameTypeSupport};
+///
+/// let name = {
+///     use NameTypeSupport::*;
+///     match NameTypeSupport::query() {
+///         OnlyPaths => "/tmp/example_msg.sock",
+///         OnlyNamespaced | Both => "@example_msg.sock",
+///     }
+/// };
+/// let conn = LocalSocketMsgStream::connect(name).await?;
+/// conn.send(b"Hello from client!").await?;
+///
+/// let reply = conn.recv().await?;
+/// println!("Server answered: {}", String::from_utf8_lossy(&reply));
+/// # Ok(()) }
+/// ```
+pub struct LocalSocketMsgStream {
+    inner: LocalSocketMsgStreamImpl,
+    /// The message given to the `Sink` implementation which hasn't been sent yet.
+    pending: Option<PendingMsg>,
+}
+impl LocalSocketMsgStream {
+    pub(super) fn new(inner: LocalSocketMsgStreamImpl) -> Self {
+        Self { inner, pending: None }
+    }
+    /// Connects to a remote message-mode local socket server.
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketMsgStreamImpl::connect(name).await.map(Self::new)
+    }
+    /// Sends one message, returning how many bytes were sent (normally the size of the whole message).
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        future::poll_fn(|cx| self.inner.poll_send(cx, buf)).await
+    }
+    /// Sends one message, failing with [`MessageTooLarge`](crate::ErrorKind::MessageTooLarge) if only part of it
+    /// could be sent instead of returning how many bytes were sent.
+    ///
+    /// The error carries a portable [`ErrorKind`](crate::ErrorKind), so failures such as the other side having hung up can be told apart
+    /// without inspecting the raw OS error.
+    pub async fn send_all(&self, buf: &[u8]) -> Result<(), Error> {
+        let sent = self.send(buf).await?;
+        error::check_msg_sent(sent, buf.len())
+    }
+    /// Receives one message, allocating a buffer of its exact size.
+    ///
+    /// An empty message is returned once the other side hangs up. On Unix, this cannot be told apart from the other side
+    /// sending an empty message.
+    pub async fn recv(&self) -> io::Result<Vec<u8>> {
+        future::poll_fn(|cx| self.poll_recv_vec(cx)).await
+    }
+    /// Receives one message and appends it to the given buffer, growing it if the message doesn't fit into its spare
+    /// capacity, and returns the size of the message.
+    ///
+    /// Received messages can then be split off the buffer and frozen into [`Bytes`](bytes::Bytes) without copying,
+    /// which is how codecs from `tokio-util` and network stacks built on the `bytes` crate expect them. A size of zero
+    /// means that the other side has hung up, like with [`.recv()`](Self::recv).
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+    pub async fn recv_buf(&self, buf: &mut BytesMut) -> io::Result<usize> {
+        let mut slf = self;
+        future::poll_fn(|cx| Pin::new(&mut slf).poll_recv_buf(cx, buf)).await
+    }
+    /// Borrows the stream as a [`Sink`] for [`Bytes`], which sends them without copying them into a `Vec` first, as the
+    /// `Sink<Vec<u8>>` implementation of the stream itself would require.
+    ///
+    /// This is a separate type rather than a second `Sink` implementation on the stream so that code which is generic
+    /// over the message type of the sink keeps compiling when the `bytes` feature is enabled.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+    pub fn bytes_sink(&mut self) -> BytesSink<'_> {
+        BytesSink(self)
+    }
+    /// Retrieves the identifier of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_pid()`](super::LocalSocketStream::peer_pid) for the platform-specific behavior.
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        self.inner.peer_pid()
+    }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`](super::LocalSocketStream::peer_credentials) for the details.
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+
+    fn poll_recv_vec(&self, cx: &mut Context<'_>) -> Poll<io::Result<Vec<u8>>> {
+        let mut buf = [0; STACK_RECV_BUF_SIZE];
+        let TryRecvResult { size, fit } = ready!(self.inner.poll_try_recv(cx, &mut buf))?;
+        if fit {
+            return Poll::Ready(Ok(buf[..size].to_vec()));
+        }
+        // The message is still in the queue, so this won't wait for anything.
+        let mut msg = vec![0; size];
+        let TryRecvResult { size, fit } = ready!(self.inner.poll_try_recv(cx, &mut msg))?;
+        assert!(
+            fit,
+            "try_recv() returned fit = false for a buffer of a size that it reported was sufficient"
+        );
+        msg.truncate(size);
+        Poll::Ready(Ok(msg))
+    }
+    fn start_send_pending(&mut self, msg: PendingMsg) -> io::Result<()> {
+        debug_assert!(self.pending.is_none(), "start_send() called without poll_ready()");
+        self.pending = Some(msg);
+        Ok(())
+    }
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(msg) = &self.pending {
+            let msg = msg.as_slice();
+            let sent = ready!(self.inner.poll_send(cx, msg))?;
+            let result = error::check_msg_sent(sent, msg.len());
+            self.pending = None;
+            result?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncReliableRecvMsg for &LocalSocketMsgStream {
+    #[inline]
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        self.inner.poll_try_recv(cx, buf)
+    }
+}
+impl AsyncReliableRecvMsg for LocalSocketMsgStream {
+    #[inline]
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        self.inner.poll_try_recv(cx, buf)
+    }
+}
+impl AsyncSendMsg for &LocalSocketMsgStream {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_send(cx, buf)
+    }
+}
+impl AsyncSendMsg for LocalSocketMsgStream {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_send(cx, buf)
+    }
+}
+
+impl Stream for LocalSocketMsgStream {
+    type Item = io::Result<Vec<u8>>;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match ready!(self.poll_recv_vec(cx)) {
+            Ok(msg) if msg.is_empty() => Poll::Ready(None),
+            els => Poll::Ready(Some(els)),
+        }
+    }
+}
+impl Sink<Vec<u8>> for LocalSocketMsgStream {
+    type Error = io::Error;
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+    fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> io::Result<()> {
+        self.get_mut().start_send_pending(PendingMsg::Vec(item))
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+}
+
+/// A [`Sink`] for [`Bytes`] borrowing a [`LocalSocketMsgStream`], obtained with
+/// [`.bytes_sink()`](LocalSocketMsgStream::bytes_sink).
+///
+/// Every item is sent as one message. A message given to the sink and not yet flushed is still sent by the next
+/// operation on the sink of the stream itself, since they share the slot for it.
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+#[derive(Debug)]
+pub struct BytesSink<'a>(&'a mut LocalSocketMsgStream);
+#[cfg(feature = "bytes")]
+impl Sink<Bytes> for BytesSink<'_> {
+    type Error = io::Error;
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_send_pending(cx)
+    }
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> io::Result<()> {
+        self.get_mut().0.start_send_pending(PendingMsg::Bytes(item))
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_send_pending(cx)
+    }
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().0.poll_send_pending(cx)
+    }
+}
+
+/// A message handed to one of the `Sink` implementations, kept in the form it was given in.
+enum PendingMsg {
+    Vec(Vec<u8>),
+    #[cfg(feature = "bytes")]
+    Bytes(Bytes),
+}
+impl PendingMsg {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Self::Vec(msg) => msg,
+            #[cfg(feature = "bytes")]
+            Self::Bytes(msg) => msg,
+        }
+    }
+}
+
+impl Debug for LocalSocketMsgStream {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl_as_raw_handle!(LocalSocketMsgStream);
+impl_as_handle!(LocalSocketMsgStream);
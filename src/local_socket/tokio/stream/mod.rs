@@ -5,14 +5,17 @@ mod write_half;
 pub use write_half::*;
 
 use {
-    super::super::ToLocalSocketName,
+    super::super::{LocalSocketPeerCredentials, LocalSocketPeerIdentity, ToLocalSocketName},
     futures_io::{AsyncRead, AsyncWrite},
     std::{
-        fmt::{self, Debug, Formatter},
+        error::Error,
+        fmt::{self, Debug, Display, Formatter},
         io::{self, IoSlice, IoSliceMut},
         pin::Pin,
         task::{Context, Poll},
+        time::Duration,
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
 };
 
 impmod! {local_socket::tokio,
@@ -82,7 +85,25 @@ impl LocalSocketStream {
     pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         LocalSocketStreamImpl::connect(name).await.map(Self::from)
     }
+    /// Connects to a remote local socket server, giving up with a [`TimedOut`](io::ErrorKind::TimedOut) error if the
+    /// connection isn't established within the given timeout.
+    ///
+    /// Unlike wrapping [`connect()`](Self::connect) in a timeout future, this also bounds the waiting that the
+    /// platform does behind the scenes, which would otherwise keep running after the outer future is dropped.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// If all instances of the named pipe are busy, the time spent waiting for the server to dispatch a new one counts
+    /// towards the timeout.
+    #[inline]
+    pub async fn connect_with_timeout<'a>(name: impl ToLocalSocketName<'a>, timeout: Duration) -> io::Result<Self> {
+        LocalSocketStreamImpl::connect_with_timeout(name, timeout)
+            .await
+            .map(Self::from)
+    }
     /// Splits a stream into a read half and a write half, which can be used to read and write the stream concurrently.
+    ///
+    /// The halves can be put back together with [`OwnedReadHalf::reunite()`] or [`OwnedWriteHalf::reunite()`].
     #[inline]
     pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
         let (r, w) = self.inner.into_split();
@@ -91,12 +112,73 @@ impl LocalSocketStream {
     /// Retrieves the identifier of the process on the opposite end of the local socket connection.
     ///
     /// # Platform-specific behavior
+    /// ## Windows
+    /// Uses `GetNamedPipeClientProcessId` on the server side of the connection and `GetNamedPipeServerProcessId` on the
+    /// client side.
     /// ## macOS and iOS
     /// Not supported by the OS, will always generate an error at runtime.
     #[inline]
     pub fn peer_pid(&self) -> io::Result<u32> {
         self.inner.peer_pid()
     }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection: its process ID, user and
+    /// group on Unix, or its process ID, session and user SID on Windows.
+    ///
+    /// # Platform-specific behavior
+    /// ## macOS and iOS
+    /// The process ID is not reported by the OS and will be `None`.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    /// Retrieves the identity of the user the process on the opposite end of the local socket connection runs as, which
+    /// can be compared with the one of the current process via
+    /// [`.is_same_user_as_current_process()`](LocalSocketPeerIdentity::is_same_user_as_current_process).
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// The access token of the peer process is opened, which requires the `PROCESS_QUERY_LIMITED_INFORMATION` access
+    /// right to it.
+    #[inline]
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        self.inner.peer_identity()
+    }
+    /// Waits until the stream becomes readable, which usually means that [`.try_read()`](Self::try_read) can be called
+    /// without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_read()` fails with `WouldBlock` and this
+    /// method needs to be called again.
+    #[inline]
+    pub async fn readable(&self) -> io::Result<()> {
+        self.inner.readable().await
+    }
+    /// Waits until the stream becomes writable, which usually means that [`.try_write()`](Self::try_write) can be
+    /// called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_write()` fails with `WouldBlock` and this
+    /// method needs to be called again.
+    #[inline]
+    pub async fn writable(&self) -> io::Result<()> {
+        self.inner.writable().await
+    }
+    /// Reads bytes from the stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if none are
+    /// available.
+    ///
+    /// Together with [`.readable()`](Self::readable), this allows reading into a buffer that's reused across reads
+    /// without going through `AsyncRead`, and only requires a shared reference.
+    #[inline]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.try_read(buf)
+    }
+    /// Writes bytes to the stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no
+    /// buffer space available.
+    ///
+    /// Together with [`.writable()`](Self::writable), this allows writing without going through `AsyncWrite`, and only
+    /// requires a shared reference.
+    #[inline]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.try_write(buf)
+    }
     /// Creates a Tokio-based async object from a given raw file descriptor. This will also attach the object to the Tokio runtime this function is called in, so calling it outside a runtime will result in an error (which is why the `FromRawFd` trait can't be implemented instead).
     ///
     /// # Safety
@@ -114,6 +196,23 @@ impl LocalSocketStream {
     pub fn into_raw_fd(self) -> io::Result<libc::c_int> {
         self.inner.into_raw_fd()
     }
+    /// Detaches the stream from the Tokio runtime (therefore has to be called within the runtime) and converts it into
+    /// a [synchronous one](super::super::LocalSocketStream), restoring blocking mode. The connection is handed over
+    /// as-is, without any data being lost.
+    ///
+    /// The reverse operation is [`.into_tokio()`](super::super::LocalSocketStream::into_tokio).
+    ///
+    /// # Platform-specific behavior
+    /// This is only available on Unix. On Windows, Tokio requires named pipes to be opened for overlapped I/O, which
+    /// can't be enabled or disabled on an existing pipe, and the synchronous local sockets are not opened that way.
+    #[cfg(unix)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+    pub fn into_sync(self) -> io::Result<super::super::LocalSocketStream> {
+        Ok(super::super::LocalSocketStream {
+            inner: self.inner.into_sync()?,
+            permit: None,
+        })
+    }
     #[inline]
     fn pinproj(&mut self) -> Pin<&mut LocalSocketStreamImpl> {
         Pin::new(&mut self.inner)
@@ -130,7 +229,7 @@ impl From<LocalSocketStreamImpl> for LocalSocketStream {
 impl AsyncRead for LocalSocketStream {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_read_vectored(
@@ -144,7 +243,7 @@ impl AsyncRead for LocalSocketStream {
 impl AsyncWrite for LocalSocketStream {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_write_vectored(
@@ -152,12 +251,12 @@ impl AsyncWrite for LocalSocketStream {
         cx: &mut Context<'_>,
         bufs: &[IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write_vectored(cx, bufs)
+        AsyncWrite::poll_write_vectored(self.pinproj(), cx, bufs)
     }
     // Those don't do anything
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -165,6 +264,27 @@ impl AsyncWrite for LocalSocketStream {
     }
 }
 
+impl TokioAsyncRead for LocalSocketStream {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
+impl TokioAsyncWrite for LocalSocketStream {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_shutdown(self.pinproj(), cx)
+    }
+}
+
 impl Debug for LocalSocketStream {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -173,3 +293,30 @@ impl Debug for LocalSocketStream {
 }
 
 impl_as_raw_handle!(LocalSocketStream);
+impl_as_handle!(LocalSocketStream);
+
+fn reunite(rh: OwnedReadHalf, wh: OwnedWriteHalf) -> Result<LocalSocketStream, ReuniteError> {
+    LocalSocketStreamImpl::reunite(rh.inner, wh.inner)
+        .map(LocalSocketStream::from)
+        .map_err(|(r, w)| ReuniteError {
+            read_half: OwnedReadHalf { inner: r },
+            write_half: OwnedWriteHalf { inner: w },
+        })
+}
+
+/// Error type for `.reunite()` on owned read and write halves of a Tokio-based local socket stream.
+///
+/// The error indicates that the halves belong to different streams and allows to recover both of them.
+#[derive(Debug)]
+pub struct ReuniteError {
+    /// The read half that didn't go anywhere, in case you still need it.
+    pub read_half: OwnedReadHalf,
+    /// The write half that didn't go anywhere, in case you still need it.
+    pub write_half: OwnedWriteHalf,
+}
+impl Display for ReuniteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("the read and write halves belong to different local socket streams")
+    }
+}
+impl Error for ReuniteError {}
@@ -1,4 +1,6 @@
 use {
+    super::{LocalSocketStream, OwnedWriteHalf, ReuniteError},
+    crate::local_socket::LocalSocketPeerCredentials,
     futures_io::AsyncRead,
     std::{
         fmt::{self, Debug, Formatter},
@@ -6,6 +8,7 @@ use {
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, ReadBuf as TokioReadBuf},
 };
 
 impmod! {local_socket::tokio,
@@ -22,6 +25,12 @@ pub struct OwnedReadHalf {
     pub(super) inner: OwnedReadHalfImpl,
 }
 impl OwnedReadHalf {
+    /// Attempts to reunite this read half with the given write half to yield the original stream back,
+    /// returning both halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedWriteHalf) -> Result<LocalSocketStream, ReuniteError> {
+        super::reunite(self, other)
+    }
     /// Retrieves the identifier of the process on the opposite end of the local socket connection.
     ///
     /// # Platform-specific behavior
@@ -31,6 +40,13 @@ impl OwnedReadHalf {
     pub fn peer_pid(&self) -> io::Result<u32> {
         self.inner.peer_pid()
     }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`](super::LocalSocketStream::peer_credentials) for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
     #[inline]
     fn pinproj(&mut self) -> Pin<&mut OwnedReadHalfImpl> {
         Pin::new(&mut self.inner)
@@ -40,7 +56,7 @@ impl OwnedReadHalf {
 impl AsyncRead for OwnedReadHalf {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_read_vectored(
@@ -52,6 +68,13 @@ impl AsyncRead for OwnedReadHalf {
     }
 }
 
+impl TokioAsyncRead for OwnedReadHalf {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
+
 impl Debug for OwnedReadHalf {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
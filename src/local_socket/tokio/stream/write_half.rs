@@ -1,11 +1,15 @@
 use {
+    super::{LocalSocketStream, OwnedReadHalf, ReuniteError},
+    crate::local_socket::LocalSocketPeerCredentials,
     futures_io::AsyncWrite,
     std::{
         fmt::{self, Debug, Formatter},
+        future::poll_fn,
         io::{self, IoSlice},
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::AsyncWrite as TokioAsyncWrite,
 };
 
 impmod! {local_socket::tokio,
@@ -14,6 +18,20 @@ impmod! {local_socket::tokio,
 
 /// An owned write half of a Tokio-based local socket stream, obtained by splitting a [`LocalSocketStream`].
 ///
+/// # Shutdown and dropping
+/// The write half can be [shut down](Self::shutdown) to tell the peer that nothing more is going to be sent, which
+/// makes reads on the other end return end-of-file once everything sent before that has been received. The read half
+/// keeps working, so that the peer can still reply. Dropping the write half without shutting it down has the same
+/// effect.
+///
+/// ## Platform-specific behavior
+/// ### Windows
+/// Named pipes cannot be shut down in one direction. Shutting the write half down waits until the peer has received
+/// everything that was sent and makes further writes fail with [`BrokenPipe`](io::ErrorKind::BrokenPipe), but the
+/// peer only observes end-of-file once both halves have been dropped. What happens to data the client hasn't received
+/// by then on the server side is decided by the [drop policy](crate::drop_policy), which by default delays the
+/// disconnect until the client has received everything, instead of discarding the unread data.
+///
 /// # Examples
 /// - [Basic client](https://github.com/kotauskas/interprocess/blob/main/examples/tokio_local_socket/client.rs)
 ///
@@ -22,6 +40,12 @@ pub struct OwnedWriteHalf {
     pub(super) inner: OwnedWriteHalfImpl,
 }
 impl OwnedWriteHalf {
+    /// Attempts to reunite this write half with the given read half to yield the original stream back,
+    /// returning both halves as an error if they belong to different streams.
+    #[inline]
+    pub fn reunite(self, other: OwnedReadHalf) -> Result<LocalSocketStream, ReuniteError> {
+        super::reunite(other, self)
+    }
     /// Retrieves the identifier of the process on the opposite end of the local socket connection.
     ///
     /// # Platform-specific behavior
@@ -31,6 +55,21 @@ impl OwnedWriteHalf {
     pub fn peer_pid(&self) -> io::Result<u32> {
         self.inner.peer_pid()
     }
+    /// Retrieves the credentials of the process on the opposite end of the local socket connection.
+    ///
+    /// See [`LocalSocketStream::peer_credentials()`](super::LocalSocketStream::peer_credentials) for the details.
+    #[inline]
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        self.inner.peer_credentials()
+    }
+    /// Shuts down the write half, signalling end-of-file to the peer.
+    ///
+    /// This is the same as the [`poll_shutdown()`](TokioAsyncWrite::poll_shutdown) and
+    /// [`poll_close()`](AsyncWrite::poll_close) methods of the `AsyncWrite` traits; see the [type-level
+    /// documentation](Self#shutdown-and-dropping) for the semantics on each platform.
+    pub async fn shutdown(&mut self) -> io::Result<()> {
+        poll_fn(|cx| TokioAsyncWrite::poll_shutdown(Pin::new(&mut *self), cx)).await
+    }
     #[inline]
     fn pinproj(&mut self) -> Pin<&mut OwnedWriteHalfImpl> {
         Pin::new(&mut self.inner)
@@ -40,7 +79,7 @@ impl OwnedWriteHalf {
 impl AsyncWrite for OwnedWriteHalf {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_write_vectored(
@@ -48,12 +87,12 @@ impl AsyncWrite for OwnedWriteHalf {
         cx: &mut Context<'_>,
         bufs: &[IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write_vectored(cx, bufs)
+        AsyncWrite::poll_write_vectored(self.pinproj(), cx, bufs)
     }
     // Those don't do anything
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
@@ -61,6 +100,21 @@ impl AsyncWrite for OwnedWriteHalf {
     }
 }
 
+impl TokioAsyncWrite for OwnedWriteHalf {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_shutdown(self.pinproj(), cx)
+    }
+}
+
 impl Debug for OwnedWriteHalf {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,28 @@
+#[cfg(unix)]
+use std::os::fd::{BorrowedFd, OwnedFd};
+#[cfg(windows)]
+use std::os::windows::io::{BorrowedHandle, OwnedHandle};
+
+/// A borrowed OS resource that can be sent to the other side of a local socket connection via
+/// [`.send_handles()`](super::LocalSocketStream::send_handles) – a file descriptor on Unix, a handle on Windows.
+#[cfg(unix)]
+pub type BorrowedTransferable<'a> = BorrowedFd<'a>;
+/// A borrowed OS resource that can be sent to the other side of a local socket connection via
+/// [`.send_handles()`](super::LocalSocketStream::send_handles) – a file descriptor on Unix, a handle on Windows.
+#[cfg(windows)]
+pub type BorrowedTransferable<'a> = BorrowedHandle<'a>;
+
+/// An owned OS resource received from the other side of a local socket connection via
+/// [`.recv_handles()`](super::LocalSocketStream::recv_handles) – a file descriptor on Unix, a handle on Windows.
+///
+/// Both `OwnedFd` and `OwnedHandle` can be converted into [`File`](std::fs::File) and other standard library types via
+/// `From`, which allows code that receives those types to be written without conditional compilation.
+#[cfg(unix)]
+pub type OwnedTransferable = OwnedFd;
+/// An owned OS resource received from the other side of a local socket connection via
+/// [`.recv_handles()`](super::LocalSocketStream::recv_handles) – a file descriptor on Unix, a handle on Windows.
+///
+/// Both `OwnedFd` and `OwnedHandle` can be converted into [`File`](std::fs::File) and other standard library types via
+/// `From`, which allows code that receives those types to be written without conditional compilation.
+#[cfg(windows)]
+pub type OwnedTransferable = OwnedHandle;
@@ -85,11 +85,101 @@ macro_rules! impl_from_raw_handle {
     };
 }
 
+macro_rules! impl_as_handle_windows {
+    ($ty:ident) => {
+        #[cfg(windows)]
+        impl ::std::os::windows::io::AsHandle for $ty {
+            fn as_handle(&self) -> ::std::os::windows::io::BorrowedHandle<'_> {
+                ::std::os::windows::io::AsHandle::as_handle(&self.inner)
+            }
+        }
+    };
+}
+macro_rules! impl_as_handle_unix {
+    ($ty:ident) => {
+        #[cfg(unix)]
+        impl ::std::os::unix::io::AsFd for $ty {
+            fn as_fd(&self) -> ::std::os::unix::io::BorrowedFd<'_> {
+                ::std::os::unix::io::AsFd::as_fd(&self.inner)
+            }
+        }
+    };
+}
+macro_rules! impl_as_handle {
+    ($ty:ident) => {
+        impl_as_handle_windows!($ty);
+        impl_as_handle_unix!($ty);
+    };
+}
+
+macro_rules! impl_into_handle_windows {
+    ($ty:ident) => {
+        #[cfg(windows)]
+        impl ::std::convert::From<$ty> for ::std::os::windows::io::OwnedHandle {
+            fn from(x: $ty) -> Self {
+                ::std::convert::From::from(x.inner)
+            }
+        }
+    };
+}
+macro_rules! impl_into_handle_unix {
+    ($ty:ident) => {
+        #[cfg(unix)]
+        impl ::std::convert::From<$ty> for ::std::os::unix::io::OwnedFd {
+            fn from(x: $ty) -> Self {
+                ::std::convert::From::from(x.inner)
+            }
+        }
+    };
+}
+macro_rules! impl_into_handle {
+    ($ty:ident) => {
+        impl_into_handle_windows!($ty);
+        impl_into_handle_unix!($ty);
+    };
+}
+
+macro_rules! impl_try_from_handle_windows {
+    ($ty:ident) => {
+        #[cfg(windows)]
+        impl ::std::convert::TryFrom<::std::os::windows::io::OwnedHandle> for $ty {
+            type Error = $crate::handle_conversion::FromHandleError;
+            fn try_from(handle: ::std::os::windows::io::OwnedHandle) -> Result<Self, Self::Error> {
+                Ok(Self {
+                    inner: ::std::convert::TryFrom::try_from(handle)?,
+                })
+            }
+        }
+    };
+}
+macro_rules! impl_try_from_handle_unix {
+    ($ty:ident) => {
+        #[cfg(unix)]
+        impl ::std::convert::TryFrom<::std::os::unix::io::OwnedFd> for $ty {
+            type Error = $crate::handle_conversion::FromHandleError;
+            fn try_from(fd: ::std::os::unix::io::OwnedFd) -> Result<Self, Self::Error> {
+                Ok(Self {
+                    inner: ::std::convert::TryFrom::try_from(fd)?,
+                })
+            }
+        }
+    };
+}
+macro_rules! impl_try_from_handle {
+    ($ty:ident) => {
+        impl_try_from_handle_windows!($ty);
+        impl_try_from_handle_unix!($ty);
+    };
+}
+
 macro_rules! impl_handle_manip_unix {
     ($ty:ident) => {
         impl_as_raw_handle_unix!($ty);
         impl_into_raw_handle_unix!($ty);
         impl_from_raw_handle_unix!($ty);
+        impl_as_handle_unix!($ty);
+        impl_into_handle_unix!($ty);
+        impl_try_from_handle_unix!($ty);
     };
 }
 macro_rules! impl_handle_manip_windows {
@@ -97,6 +187,9 @@ macro_rules! impl_handle_manip_windows {
         impl_as_raw_handle_windows!($ty);
         impl_into_raw_handle_windows!($ty);
         impl_from_raw_handle_windows!($ty);
+        impl_as_handle_windows!($ty);
+        impl_into_handle_windows!($ty);
+        impl_try_from_handle_windows!($ty);
     };
 }
 macro_rules! impl_handle_manip {
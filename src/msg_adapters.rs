@@ -0,0 +1,179 @@
+//! [`Stream`] and [`Sink`] adapters shared by all message-oriented types of the crate.
+//!
+//! Message-mode named pipes, message-mode local sockets and Ud-socket datagrams each have their own receiving and
+//! sending methods, but all of them receive via [`AsyncReliableRecvMsg`] or send via [`AsyncSendMsg`]. The
+//! [`MsgStreamExt`] and [`MsgSinkExt`] traits build on those to turn any of them into a stream of received messages or
+//! a sink for messages to send, so that code using the combinators from the `futures` crate doesn't need to care about
+//! which kind of IPC primitive it's dealing with:
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use futures::{SinkExt, StreamExt};
+//! use interprocess::{
+//!     local_socket::tokio::LocalSocketMsgStream,
+//!     msg_adapters::{MsgSinkExt, MsgStreamExt},
+//! };
+//!
+//! let conn = LocalSocketMsgStream::connect("@example_msg.sock").await?;
+//! // Shared references are enough, so the stream and the sink can be used concurrently.
+//! let mut sink = (&conn).msg_sink();
+//! let mut stream = (&conn).msg_stream();
+//! sink.send(b"Hello from client!".to_vec()).await?;
+//! if let Some(reply) = stream.next().await {
+//!     println!("Server answered: {}", String::from_utf8_lossy(&reply?));
+//! }
+//! # Ok(()) }
+//! ```
+
+use {
+    crate::{
+        error,
+        reliable_recv_msg::{AsyncReliableRecvMsg, RecvResult},
+    },
+    futures_core::{ready, Stream},
+    futures_sink::Sink,
+    std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+/// Size of the buffer on the stack that [`MsgStream`] first receives messages into, before copying them into a `Vec`
+/// of the exact size. Bigger messages are received into a `Vec` directly.
+const STACK_RECV_BUF_SIZE: usize = 1024;
+
+/// Implementation of asynchronously sending messages which preserve their boundaries, the sending counterpart of
+/// [`AsyncReliableRecvMsg`].
+pub trait AsyncSendMsg {
+    /// Polls a future that sends one message, returning how many bytes were sent (normally the size of the whole
+    /// message).
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>>;
+}
+
+/// Conversion of message receivers into a [`Stream`] of messages.
+///
+/// See the [module-level documentation](self) for an example.
+pub trait MsgStreamExt: AsyncReliableRecvMsg + Sized {
+    /// Wraps the receiver into a [`MsgStream`].
+    #[inline]
+    fn msg_stream(self) -> MsgStream<Self> {
+        MsgStream { inner: self }
+    }
+}
+impl<T: AsyncReliableRecvMsg> MsgStreamExt for T {}
+
+/// Conversion of message senders into a [`Sink`] of messages.
+///
+/// See the [module-level documentation](self) for an example.
+pub trait MsgSinkExt: AsyncSendMsg + Sized {
+    /// Wraps the sender into a [`MsgSink`].
+    #[inline]
+    fn msg_sink(self) -> MsgSink<Self> {
+        MsgSink {
+            inner: self,
+            pending: None,
+        }
+    }
+}
+impl<T: AsyncSendMsg> MsgSinkExt for T {}
+
+/// A [`Stream`] of the messages received from the wrapped receiver, obtained with
+/// [`.msg_stream()`](MsgStreamExt::msg_stream).
+///
+/// Each message is received into a `Vec` of its exact size. The stream ends once an empty message is received, which
+/// is what the other side hanging up looks like on Unix, where it cannot be told apart from an actual empty message.
+#[derive(Debug)]
+pub struct MsgStream<T> {
+    inner: T,
+}
+impl<T> MsgStream<T> {
+    /// Borrows the wrapped receiver.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrows the wrapped receiver.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Unwraps the receiver.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+impl<T: AsyncReliableRecvMsg + Unpin> Stream for MsgStream<T> {
+    type Item = io::Result<Vec<u8>>;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut buf = [0; STACK_RECV_BUF_SIZE];
+        let msg = match ready!(Pin::new(&mut self.get_mut().inner).poll_recv(cx, &mut buf)) {
+            Ok(RecvResult::Fit(size)) => buf[..size].to_vec(),
+            Ok(RecvResult::Alloc(msg)) => msg,
+            Err(e) => return Poll::Ready(Some(Err(e))),
+        };
+        if msg.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(Ok(msg)))
+        }
+    }
+}
+
+/// A [`Sink`] which sends every item as one message through the wrapped sender, obtained with
+/// [`.msg_sink()`](MsgSinkExt::msg_sink).
+///
+/// A message which was sent only partially fails with [`MessageTooLarge`](crate::ErrorKind::MessageTooLarge).
+#[derive(Debug)]
+pub struct MsgSink<T> {
+    inner: T,
+    /// The message given to the sink which hasn't been sent yet.
+    pending: Option<Vec<u8>>,
+}
+impl<T> MsgSink<T> {
+    /// Borrows the wrapped sender.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Mutably borrows the wrapped sender.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Unwraps the sender. A message given to the sink which hasn't been flushed yet is discarded.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+impl<T: AsyncSendMsg + Unpin> MsgSink<T> {
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(msg) = &self.pending {
+            let sent = ready!(Pin::new(&mut self.inner).poll_send_msg(cx, msg))?;
+            let result = error::check_msg_sent(sent, msg.len());
+            self.pending = None;
+            result?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+impl<T: AsyncSendMsg + Unpin> Sink<Vec<u8>> for MsgSink<T> {
+    type Error = io::Error;
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+    fn start_send(self: Pin<&mut Self>, item: Vec<u8>) -> io::Result<()> {
+        let slf = self.get_mut();
+        debug_assert!(slf.pending.is_none(), "start_send() called without poll_ready()");
+        slf.pending = Some(item);
+        Ok(())
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+}
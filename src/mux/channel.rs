@@ -0,0 +1,175 @@
+use super::{
+    conn::{data_frame, frame, Handle},
+    FIN, MAX_DATA_LEN, RST, WINDOW_UPDATE,
+};
+use futures_io::{AsyncRead as FuturesAsyncRead, AsyncWrite as FuturesAsyncWrite};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf};
+
+/// One logical channel of a [`Mux`](super::Mux), which is a bidirectional byte stream of its own.
+///
+/// Both Tokio's and the `futures` crate's `AsyncRead` and `AsyncWrite` traits are implemented. Writes are handed over
+/// to the background task of the connection, and only wait for the peer to grant more credit when the channel's flow
+/// control window is exhausted – flushing completes immediately.
+///
+/// Shutting down the writing direction sends the peer an end of stream once it has read everything sent before that.
+/// Dropping the channel without reading everything up to the peer's end of stream resets it instead, failing the
+/// peer's further reads and writes: to close a channel gracefully, shut it down and read it to the end before dropping
+/// it, or make sure the protocol spoken over it knows when the peer is done.
+pub struct MuxChannel {
+    pub(super) handle: Arc<Handle>,
+    pub(super) id: u32,
+}
+impl MuxChannel {
+    /// Returns the ID of the channel, which is the same on both sides of the connection.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn read_into(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let shared = &self.handle.shared;
+        let mut state = shared.lock();
+        let closed_error = state.closed.then(|| state.closed_error());
+        let channel = state.channels.get_mut(&self.id).expect("channel state missing");
+        if channel.recv_buf.is_empty() {
+            return if channel.remote_closed {
+                Poll::Ready(Ok(0))
+            } else if channel.reset {
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::ConnectionReset,
+                    "the channel was reset by the peer",
+                )))
+            } else if let Some(e) = closed_error {
+                Poll::Ready(Err(e))
+            } else {
+                channel.read_waker = Some(cx.waker().clone());
+                Poll::Pending
+            };
+        }
+
+        let n = buf.len().min(channel.recv_buf.len());
+        for (dst, src) in buf.iter_mut().zip(channel.recv_buf.drain(..n)) {
+            *dst = src;
+        }
+        channel.unannounced += n as u32;
+        // Credit is granted in batches to keep the number of window updates down.
+        if channel.unannounced >= shared.receive_window / 2 && !channel.remote_closed {
+            let credit = std::mem::take(&mut channel.unannounced);
+            channel.recv_window += credit;
+            state.send(frame(WINDOW_UPDATE, 0, self.id, credit));
+        }
+        Poll::Ready(Ok(n))
+    }
+    fn write_from(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let mut state = self.handle.shared.lock();
+        let closed_error = state.closed.then(|| state.closed_error());
+        let channel = state.channels.get_mut(&self.id).expect("channel state missing");
+        if channel.local_closed {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the channel was shut down for writing",
+            )));
+        }
+        if channel.reset {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the channel was reset by the peer",
+            )));
+        }
+        if let Some(e) = closed_error {
+            return Poll::Ready(Err(e));
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if channel.send_window == 0 {
+            channel.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let n = buf.len().min(channel.send_window as usize).min(MAX_DATA_LEN);
+        channel.send_window -= n as u32;
+        state.send(data_frame(self.id, &buf[..n]));
+        Poll::Ready(Ok(n))
+    }
+    fn shut_down(&self) -> Poll<io::Result<()>> {
+        let mut state = self.handle.shared.lock();
+        let channel = state.channels.get_mut(&self.id).expect("channel state missing");
+        if !channel.local_closed {
+            channel.local_closed = true;
+            state.send(frame(WINDOW_UPDATE, FIN, self.id, 0));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+impl Drop for MuxChannel {
+    fn drop(&mut self) {
+        let mut state = self.handle.shared.lock();
+        let channel = match state.channels.remove(&self.id) {
+            Some(channel) => channel,
+            None => return,
+        };
+        if channel.reset {
+            return;
+        }
+        if !channel.remote_closed {
+            // Anything the peer sends from now on would go unread, and it would eventually run out of credit.
+            state.send(frame(WINDOW_UPDATE, RST, self.id, 0));
+        } else if !channel.local_closed {
+            state.send(frame(WINDOW_UPDATE, FIN, self.id, 0));
+        }
+    }
+}
+impl Debug for MuxChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MuxChannel").field("id", &self.id).finish()
+    }
+}
+
+impl TokioAsyncRead for MuxChannel {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let n = match self.read_into(cx, buf.initialize_unfilled()) {
+            Poll::Ready(Ok(n)) => n,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+impl TokioAsyncWrite for MuxChannel {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.write_from(cx, buf)
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.shut_down()
+    }
+}
+impl FuturesAsyncRead for MuxChannel {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.read_into(cx, buf)
+    }
+}
+impl FuturesAsyncWrite for MuxChannel {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.write_from(cx, buf)
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.shut_down()
+    }
+}
@@ -0,0 +1,279 @@
+//! The state of a multiplexed connection, shared between the handles and the background tasks which read and write
+//! frames.
+
+use super::{Side, ACK, DATA, FIN, HEADER_LEN, INITIAL_WINDOW, MAX_DATA_LEN, RST, SYN, WINDOW_UPDATE};
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::Waker,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    task::JoinHandle,
+};
+
+pub(super) struct Shared {
+    pub state: Mutex<State>,
+    /// The receive window of every channel, as configured on this side.
+    pub receive_window: u32,
+    pub max_channels: usize,
+    pub side: Side,
+}
+impl Shared {
+    pub fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is never left inconsistent by a panic, so poisoning can be ignored.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+pub(super) struct State {
+    pub channels: HashMap<u32, Channel>,
+    /// The ID of the next channel opened by this side.
+    pub next_id: Option<u32>,
+    /// The queue of frames to be written, or `None` once all handles are dropped.
+    pub outgoing: Option<UnboundedSender<Vec<u8>>>,
+    pub closed: bool,
+    /// The reason the connection ended, if that was due to an error rather than the peer closing it.
+    pub error: Option<(io::ErrorKind, String)>,
+}
+impl State {
+    pub fn send(&self, frame: Vec<u8>) {
+        if let Some(outgoing) = &self.outgoing {
+            // If the writer has stopped, the connection is closed and the frame has nowhere to go anyway.
+            let _ = outgoing.send(frame);
+        }
+    }
+    /// Marks the connection as ended, waking everything that waits for it.
+    fn close(&mut self, error: Option<io::Error>) {
+        if self.closed {
+            return;
+        }
+        self.closed = true;
+        self.error = error.map(|e| (e.kind(), e.to_string()));
+        for channel in self.channels.values_mut() {
+            channel.opening = None;
+            channel.wake();
+        }
+    }
+    /// The error returned by operations on channels once the connection has ended.
+    pub fn closed_error(&self) -> io::Error {
+        match &self.error {
+            Some((kind, msg)) => io::Error::new(*kind, format!("the multiplexed connection failed: {msg}")),
+            None => io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "the multiplexed connection was closed",
+            ),
+        }
+    }
+}
+
+/// The state of one channel.
+pub(super) struct Channel {
+    /// Data received but not yet read.
+    pub recv_buf: VecDeque<u8>,
+    /// How many more bytes the peer may send before it's granted more credit.
+    pub recv_window: u32,
+    /// How many bytes were read since the peer was last granted credit.
+    pub unannounced: u32,
+    /// How many more bytes may be sent to the peer.
+    pub send_window: u32,
+    pub read_waker: Option<Waker>,
+    pub write_waker: Option<Waker>,
+    /// Whether the peer has finished sending.
+    pub remote_closed: bool,
+    /// Whether this side has finished sending.
+    pub local_closed: bool,
+    /// Whether the peer has reset the channel.
+    pub reset: bool,
+    /// Notified of the peer accepting or refusing the channel, for channels opened by this side.
+    pub opening: Option<oneshot::Sender<bool>>,
+}
+impl Channel {
+    pub fn new(opening: Option<oneshot::Sender<bool>>) -> Self {
+        Self {
+            recv_buf: VecDeque::new(),
+            recv_window: INITIAL_WINDOW,
+            unannounced: 0,
+            send_window: INITIAL_WINDOW,
+            read_waker: None,
+            write_waker: None,
+            remote_closed: false,
+            local_closed: false,
+            reset: false,
+            opening,
+        }
+    }
+    fn wake_reader(&mut self) {
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+    }
+    fn wake_writer(&mut self) {
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+    fn wake(&mut self) {
+        self.wake_reader();
+        self.wake_writer();
+    }
+}
+
+pub(super) fn frame(kind: u8, flags: u8, id: u32, len: u32) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(HEADER_LEN);
+    frame.push(kind);
+    frame.push(flags);
+    frame.extend_from_slice(&id.to_le_bytes());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame
+}
+pub(super) fn data_frame(id: u32, data: &[u8]) -> Vec<u8> {
+    let mut frame = frame(DATA, 0, id, data.len() as u32);
+    frame.extend_from_slice(data);
+    frame
+}
+
+/// Owns the background tasks of a connection, stopping them once the last handle to it is dropped.
+pub(super) struct Handle {
+    pub shared: Arc<Shared>,
+    reader: JoinHandle<()>,
+}
+impl Handle {
+    pub fn spawn<S: AsyncRead + AsyncWrite + Send + 'static>(
+        io: S,
+        shared: Arc<Shared>,
+        outgoing: UnboundedReceiver<Vec<u8>>,
+        incoming: UnboundedSender<u32>,
+    ) -> Self {
+        let (reader, writer) = tokio::io::split(io);
+        tokio::spawn(write_frames(writer, outgoing, Arc::clone(&shared)));
+        let reader = tokio::spawn(read_frames(reader, Arc::clone(&shared), incoming));
+        Self { shared, reader }
+    }
+}
+impl Drop for Handle {
+    fn drop(&mut self) {
+        // The writer finishes on its own once it has written the frames queued by the handles, such as the resets of
+        // channels dropped along with them, but the reader would wait for the peer to close the connection, which it
+        // only does once this side closes it.
+        self.reader.abort();
+        self.shared.lock().outgoing = None;
+    }
+}
+
+async fn write_frames<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    mut outgoing: UnboundedReceiver<Vec<u8>>,
+    shared: Arc<Shared>,
+) {
+    while let Some(frame) = outgoing.recv().await {
+        if let Err(e) = writer.write_all(&frame).await {
+            shared.lock().close(Some(e));
+            return;
+        }
+    }
+    let _ = writer.shutdown().await;
+}
+
+async fn read_frames<R: AsyncRead + Unpin>(mut reader: R, shared: Arc<Shared>, incoming: UnboundedSender<u32>) {
+    let result = async {
+        let mut payload = Vec::new();
+        loop {
+            let mut header = [0; HEADER_LEN];
+            let mut filled = 0;
+            while filled < HEADER_LEN {
+                match reader.read(&mut header[filled..]).await? {
+                    0 if filled == 0 => return Ok(()),
+                    0 => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                    n => filled += n,
+                }
+            }
+            let [kind, flags, i0, i1, i2, i3, l0, l1, l2, l3] = header;
+            let id = u32::from_le_bytes([i0, i1, i2, i3]);
+            let len = u32::from_le_bytes([l0, l1, l2, l3]);
+            payload.clear();
+            if kind == DATA {
+                if len as usize > MAX_DATA_LEN {
+                    return Err(protocol_error("received an oversized data frame"));
+                }
+                payload.resize(len as usize, 0);
+                reader.read_exact(&mut payload).await?;
+            }
+            handle_frame(&shared, &incoming, kind, flags, id, len, &payload)?;
+        }
+    }
+    .await;
+    shared.lock().close(result.err());
+}
+
+fn handle_frame(
+    shared: &Shared,
+    incoming: &UnboundedSender<u32>,
+    kind: u8,
+    flags: u8,
+    id: u32,
+    len: u32,
+    payload: &[u8],
+) -> io::Result<()> {
+    if kind != DATA && kind != WINDOW_UPDATE {
+        return Err(protocol_error("received a frame of an unknown type"));
+    }
+    let mut state = shared.lock();
+    if flags & SYN != 0 {
+        if shared.side.opens(id) || state.channels.contains_key(&id) {
+            return Err(protocol_error("the peer opened a channel with an invalid ID"));
+        }
+        if state.channels.len() >= shared.max_channels {
+            state.send(frame(WINDOW_UPDATE, RST, id, 0));
+            return Ok(());
+        }
+        state.channels.insert(id, Channel::new(None));
+        state.send(frame(WINDOW_UPDATE, ACK, id, shared.receive_window - INITIAL_WINDOW));
+        let _ = incoming.send(id);
+    }
+
+    // Frames for channels which were dropped on this side are discarded.
+    let channel = match state.channels.get_mut(&id) {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+    if flags & ACK != 0 {
+        if let Some(opening) = channel.opening.take() {
+            let _ = opening.send(true);
+        }
+    }
+    if kind == DATA && !payload.is_empty() {
+        if payload.len() > channel.recv_window as usize {
+            return Err(protocol_error(
+                "the peer sent more data than the flow control window allows",
+            ));
+        }
+        channel.recv_window -= payload.len() as u32;
+        channel.recv_buf.extend(payload);
+        channel.wake_reader();
+    } else if kind == WINDOW_UPDATE && len != 0 {
+        channel.send_window = channel.send_window.saturating_add(len);
+        channel.wake_writer();
+    }
+    if flags & FIN != 0 {
+        channel.remote_closed = true;
+        channel.wake_reader();
+    }
+    if flags & RST != 0 {
+        channel.reset = true;
+        if let Some(opening) = channel.opening.take() {
+            let _ = opening.send(false);
+        }
+        channel.wake();
+    }
+    Ok(())
+}
+
+fn protocol_error(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,278 @@
+//! A multiplexer which carries many independent logical channels over one connection, such as a local socket or a named
+//! pipe, with flow control for every channel.
+//!
+//! Applications which have many logical flows of data between two processes – a control channel, a log stream, a bulk
+//! transfer or two – either open an OS-level connection for each of them or invent a framing scheme of their own. A
+//! [`Mux`] wraps a single connection instead and lets either side [open](Mux::open_channel) channels which the other
+//! side [accepts](Mux::accept_channel), much like connections to a listener. Every [`MuxChannel`] is a byte stream
+//! which implements `AsyncRead` and `AsyncWrite`, so message framing can be added on top with the `codec` feature or
+//! any other means.
+//!
+//! Each channel has its own flow control window: a side can only send as much data as the other side has buffer space
+//! for, and writing to a channel whose reader is falling behind waits for it to catch up, without affecting the other
+//! channels. The connection is read and written by background tasks, which is why a `Mux` has to be created within a
+//! Tokio runtime. It's closed once the `Mux` and all of its channels are dropped.
+//!
+//! The design and the wire format are modeled after [yamux](https://github.com/hashicorp/yamux/blob/master/spec.md),
+//! but the two are not compatible.
+//!
+//! # Example
+//! ```
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! use interprocess::mux::{Mux, Side};
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//!
+//! // Any connection works – a local socket stream, a named pipe or, in this case, an in-memory pipe.
+//! let (a, b) = tokio::io::duplex(4096);
+//! let client = Mux::new(a, Side::Client);
+//! let server = Mux::new(b, Side::Server);
+//!
+//! let mut logs = client.open_channel().await?;
+//! let mut control = client.open_channel().await?;
+//! logs.write_all(b"starting up").await?;
+//! control.write_all(b"ping").await?;
+//!
+//! let mut accepted = Vec::new();
+//! for _ in 0..2 {
+//!     accepted.push(server.accept_channel().await?.unwrap());
+//! }
+//! let mut buf = [0; 4];
+//! accepted[1].read_exact(&mut buf).await?;
+//! assert_eq!(&buf, b"ping");
+//! accepted[1].write_all(b"pong").await?;
+//! control.read_exact(&mut buf).await?;
+//! assert_eq!(&buf, b"pong");
+//! # Ok(()) }
+//! ```
+//!
+//! # Wire format
+//! The connection carries frames, each of which consists of a one-byte frame type, a one-byte set of flags, the channel
+//! ID and a length, both as little-endian 32-bit integers, followed by the payload in the case of data frames. The
+//! frame types are data frames, whose length is that of the payload, and window updates, whose length is the amount of
+//! additional credit granted to the peer. The flags open a channel, acknowledge that a channel was accepted, end the
+//! sending direction of a channel and reset a channel. Channels opened by the client have odd IDs, and ones opened by
+//! the server have even IDs. Every channel starts out with a window of 256 KiB in each direction.
+
+mod channel;
+mod conn;
+pub use channel::*;
+
+use conn::{frame, Channel, Handle, Shared, State};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    io,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::{
+        mpsc::{self, UnboundedReceiver},
+        oneshot,
+    },
+};
+
+/// The receive window of every channel unless configured otherwise, in bytes: 256 KiB, which is also the minimum.
+pub const DEFAULT_RECEIVE_WINDOW: u32 = INITIAL_WINDOW;
+/// The maximum number of channels which can be open at once unless configured otherwise.
+pub const DEFAULT_MAX_CHANNELS: usize = 1024;
+
+/// The window every channel starts out with, before any window updates.
+const INITIAL_WINDOW: u32 = 256 * 1024;
+/// The maximum size of the payload of one data frame.
+const MAX_DATA_LEN: usize = 64 * 1024;
+const HEADER_LEN: usize = 10;
+
+// Frame types.
+const DATA: u8 = 0;
+const WINDOW_UPDATE: u8 = 1;
+// Flags.
+const SYN: u8 = 1;
+const ACK: u8 = 2;
+const FIN: u8 = 4;
+const RST: u8 = 8;
+
+/// Which side of the connection a [`Mux`] is on, which determines the IDs of the channels it opens. The two sides of a
+/// connection must be different.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The side which opens channels with odd IDs, typically the one which initiated the connection.
+    Client,
+    /// The side which opens channels with even IDs, typically the one which accepted the connection.
+    Server,
+}
+impl Side {
+    /// Returns `true` if the channel with the given ID is one this side opens.
+    fn opens(self, id: u32) -> bool {
+        (id % 2 == 1) == (self == Self::Client)
+    }
+}
+
+/// Builder for [`Mux`], allowing its limits to be configured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MuxOptions {
+    receive_window: u32,
+    max_channels: usize,
+}
+impl MuxOptions {
+    /// Creates a builder with the default limits.
+    pub fn new() -> Self {
+        Self {
+            receive_window: DEFAULT_RECEIVE_WINDOW,
+            max_channels: DEFAULT_MAX_CHANNELS,
+        }
+    }
+    /// Sets how much data the peer may send on a channel before this side reads it, in bytes. Defaults to
+    /// [`DEFAULT_RECEIVE_WINDOW`], which is also the minimum.
+    ///
+    /// Larger windows let a single channel transfer data faster, at the cost of up to that much memory for every
+    /// channel whose reader falls behind.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn receive_window(mut self, receive_window: u32) -> Self {
+        self.receive_window = receive_window;
+        self
+    }
+    /// Sets the maximum number of channels which can be open at once, counting the ones opened by either side. Defaults
+    /// to [`DEFAULT_MAX_CHANNELS`].
+    ///
+    /// Channels opened by the peer while the limit is reached are refused.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn max_channels(mut self, max_channels: usize) -> Self {
+        self.max_channels = max_channels;
+        self
+    }
+    /// Creates a multiplexer on the given connection with the configured limits. Has to be called within a Tokio
+    /// runtime.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the receive window is below the minimum or the
+    /// maximum number of channels is zero.
+    pub fn build<S: AsyncRead + AsyncWrite + Send + 'static>(&self, io: S, side: Side) -> io::Result<Mux> {
+        if self.receive_window < INITIAL_WINDOW {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the receive window cannot be smaller than 256 KiB",
+            ));
+        }
+        if self.max_channels == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the maximum number of channels cannot be zero",
+            ));
+        }
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                channels: HashMap::new(),
+                next_id: Some(match side {
+                    Side::Client => 1,
+                    Side::Server => 2,
+                }),
+                outgoing: Some(outgoing),
+                closed: false,
+                error: None,
+            }),
+            receive_window: self.receive_window,
+            max_channels: self.max_channels,
+            side,
+        });
+        Ok(Mux {
+            handle: Arc::new(Handle::spawn(io, shared, outgoing_rx, incoming_tx)),
+            incoming: tokio::sync::Mutex::new(incoming),
+        })
+    }
+}
+impl Default for MuxOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A multiplexed connection, over which channels can be opened and accepted.
+///
+/// See the [module-level documentation](self) for an overview and an example.
+pub struct Mux {
+    handle: Arc<Handle>,
+    incoming: tokio::sync::Mutex<UnboundedReceiver<u32>>,
+}
+impl Mux {
+    /// Creates a multiplexer on the given connection with the default limits. Has to be called within a Tokio runtime.
+    pub fn new<S: AsyncRead + AsyncWrite + Send + 'static>(io: S, side: Side) -> Self {
+        MuxOptions::new()
+            .build(io, side)
+            .expect("the default options are valid")
+    }
+    /// Opens a channel and waits for the peer to accept it.
+    ///
+    /// Fails with [`ConnectionRefused`](io::ErrorKind::ConnectionRefused) if the peer has reached its
+    /// [limit](MuxOptions::max_channels) on open channels, and with the error which ended the connection if it's
+    /// closed. The peer accepts channels on its own as they arrive, which means that this doesn't wait for it to call
+    /// [`.accept_channel()`](Self::accept_channel).
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: if the future is dropped, the channel is reset.
+    pub async fn open_channel(&self) -> io::Result<MuxChannel> {
+        let shared = &self.handle.shared;
+        let (opened, opened_rx) = oneshot::channel();
+        let id = {
+            let mut state = shared.lock();
+            if state.closed {
+                return Err(state.closed_error());
+            }
+            let id = state.next_id.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::Other,
+                    "all channel IDs of the connection have been used up",
+                )
+            })?;
+            state.next_id = id.checked_add(2);
+            state.channels.insert(id, Channel::new(Some(opened)));
+            state.send(frame(WINDOW_UPDATE, SYN, id, shared.receive_window - INITIAL_WINDOW));
+            id
+        };
+        let channel = MuxChannel {
+            handle: Arc::clone(&self.handle),
+            id,
+        };
+        match opened_rx.await {
+            Ok(true) => Ok(channel),
+            Ok(false) => Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "the peer refused to accept the channel",
+            )),
+            Err(_) => Err(shared.lock().closed_error()),
+        }
+    }
+    /// Waits for the peer to open a channel, returning `None` once the peer has closed the connection.
+    ///
+    /// If the connection fails instead, the error is returned once all channels opened before that are accepted.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: if the future is dropped, no channel is lost.
+    pub async fn accept_channel(&self) -> io::Result<Option<MuxChannel>> {
+        match self.incoming.lock().await.recv().await {
+            Some(id) => Ok(Some(MuxChannel {
+                handle: Arc::clone(&self.handle),
+                id,
+            })),
+            None => {
+                let state = self.handle.shared.lock();
+                match state.error {
+                    Some(_) => Err(state.closed_error()),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+}
+impl Debug for Mux {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let state = self.handle.shared.lock();
+        f.debug_struct("Mux")
+            .field("side", &self.handle.shared.side)
+            .field("channels", &state.channels.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
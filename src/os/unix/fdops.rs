@@ -1,18 +1,22 @@
 use super::unixprelude::*;
+use crate::{Error, ErrorKind};
 use std::{
     io::{self, IoSlice, IoSliceMut},
-    marker::PhantomData,
-    mem::ManuallyDrop,
+    mem::{zeroed, MaybeUninit},
 };
 use to_method::To;
 
+/// Newtype wrapper which defines file I/O operations on an owned file descriptor.
 #[repr(transparent)]
-pub(super) struct FdOps(pub(super) c_int, PhantomData<*mut ()>);
+pub(super) struct FdOps(pub(super) OwnedFd);
 impl FdOps {
-    pub fn new(fd: c_int) -> Self {
-        Self(fd, PhantomData)
-    }
     pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.read_to_uninit(unsafe {
+            // SAFETY: the types are layout-compatible, and read() never de-initializes the buffer
+            &mut *(buf as *mut [u8] as *mut [MaybeUninit<u8>])
+        })
+    }
+    pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
         let (success, bytes_read) = unsafe {
             let length_to_read = buf.len();
             let size_or_err = libc::read(self.as_raw_fd(), buf.as_mut_ptr() as *mut _, length_to_read);
@@ -44,66 +48,78 @@ impl FdOps {
         };
         ok_or_ret_errno!(success => bytes_written)
     }
+    /// Duplicates the file descriptor with the close-on-exec flag set.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let fd = unsafe { libc::fcntl(self.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 0) };
+        ok_or_ret_errno!(fd != -1 => unsafe {
+            // SAFETY: fcntl() just created the descriptor
+            Self::from_raw_fd(fd)
+        })
+    }
     pub fn flush(&self) -> io::Result<()> {
         let success = unsafe { libc::fsync(self.as_raw_fd()) >= 0 };
         ok_or_ret_errno!(success => ())
     }
 }
-impl AsRef<c_int> for FdOps {
-    fn as_ref(&self) -> &c_int {
-        &self.0
-    }
-}
-impl AsRef<FdOps> for c_int {
-    fn as_ref(&self) -> &FdOps {
-        unsafe {
-            // SAFETY: #[repr(transparent)] guarantees layout compatibility
-            &*(self as *const _ as *const FdOps)
-        }
+impl AsFd for FdOps {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
     }
 }
 impl AsRawFd for FdOps {
     fn as_raw_fd(&self) -> c_int {
-        self.0
+        self.0.as_raw_fd()
+    }
+}
+impl From<FdOps> for OwnedFd {
+    fn from(op: FdOps) -> Self {
+        op.0
+    }
+}
+impl From<OwnedFd> for FdOps {
+    fn from(fd: OwnedFd) -> Self {
+        Self(fd)
     }
 }
 impl IntoRawFd for FdOps {
     fn into_raw_fd(self) -> c_int {
-        let self_ = ManuallyDrop::new(self);
-        self_.as_raw_fd()
+        self.0.into_raw_fd()
     }
 }
 impl FromRawFd for FdOps {
     unsafe fn from_raw_fd(fd: c_int) -> Self {
-        Self::new(fd)
+        Self(unsafe { OwnedFd::from_raw_fd(fd) })
     }
 }
-impl Drop for FdOps {
-    fn drop(&mut self) {
-        unsafe { close_fd(self.0) };
+
+/// Sets or clears the `O_NONBLOCK` flag of the file description.
+pub(super) fn set_nonblocking(fd: c_int, nonblocking: bool) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags == -1 {
+        return Err(io::Error::last_os_error());
     }
+    let new_flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+    if new_flags == flags {
+        return Ok(());
+    }
+    let success = unsafe { libc::fcntl(fd, libc::F_SETFL, new_flags) != -1 };
+    ok_or_ret_errno!(success => ())
 }
-unsafe impl Send for FdOps {}
-unsafe impl Sync for FdOps {}
 
-pub(super) unsafe fn close_fd(fd: i32) {
-    let error = unsafe {
-        let mut error = None;
-        // If the close() call fails, the loop starts and keeps retrying until either the error
-        // value isn't Interrupted (in which case the assertion fails) or the close operation
-        // properly fails with a non-Interrupted error type. Why does Unix even have this
-        // idiotic error type?
-        while libc::close(fd) != 0 {
-            let current_error = io::Error::last_os_error();
-            if current_error.kind() != io::ErrorKind::Interrupted {
-                // An actual close error happened – return early now
-                error = Some(current_error);
-                break;
-            }
-        }
-        error
-    };
-    if let Some(e) = error {
-        panic!("failed to close file descriptor: {e}");
+/// Checks that the file descriptor refers to a file of the given type, one of the `S_IF*` constants, failing with an
+/// [`InvalidHandle`](ErrorKind::InvalidHandle) error with the given message otherwise.
+pub(super) fn check_file_type(fd: BorrowedFd<'_>, ty: mode_t, msg: &'static str) -> io::Result<()> {
+    let mut stat = unsafe { zeroed::<libc::stat>() };
+    let success = unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != -1;
+    if !success {
+        return Err(io::Error::last_os_error());
+    }
+    if stat.st_mode & libc::S_IFMT != ty {
+        return Err(Error::new(ErrorKind::InvalidHandle, msg).into());
     }
+    Ok(())
 }
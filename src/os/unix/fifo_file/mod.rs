@@ -0,0 +1,256 @@
+//! Creating and using FIFO files, which are also known as "named pipes" but totally different from named pipes on Windows.
+//!
+//! On Windows, named pipes can be compared to Unix domain sockets: they can have multiple duplex connections on a single path, and the data can be chosen to either preserve or erase the message boundaries, resulting in a reliable performant implementation of TCP and UDP working in the bounds of a single machine. Those Unix domain sockets are also implemented by `interprocess` – see the [`udsocket`] module for that.
+//!
+//! On Linux, named pipes, referred to as "FIFO files" in this crate, are just files which can have a writer and a reader communicating with each other in one direction without message boundaries. If further readers try to open the file, they will simply read nothing at all; if further writers are connected, the data mixes in an unpredictable way, making it unusable. Therefore, FIFOs are to be used specifically to conveniently connect two applications through a known path which works like a pipe and nothing else.
+//!
+//! ## Usage
+//! The [`create_fifo`] function serves for a FIFO file creation. The easiest way to open FIFO files is [`FifoOptions`], which can also create the file if it doesn't exist and deals with the peculiarities of opening FIFOs, producing a [`FifoReader`] or a [`FifoWriter`]. Opening FIFO files via the standard [`File`]s, opened either only for writing or only for reading, works as well. Deleting works the same way as with any regular file, via the [`remove_file`] function.
+//!
+//! With the `tokio` feature enabled, the [`tokio`](self::tokio) submodule provides asynchronous versions of the reader and writer, which never block the runtime when opening the FIFO.
+//!
+//! ## Opening semantics
+//! Opening a FIFO normally blocks until the other end is opened as well: readers wait for a writer and vice versa. This can be disabled with [`FifoOptions::wait_for_peer`], in which case a reader is opened right away and sees end-of-file until a writer connects, while opening a writer fails with [`NotConnected`](io::ErrorKind::NotConnected) if there is no reader.
+//!
+//! # Example
+//! ```
+//! use interprocess::os::unix::fifo_file::FifoOptions;
+//! use std::{io::prelude::*, thread};
+//!
+//! let path = std::env::temp_dir().join(format!("interprocess-doctest-fifo-{}", std::process::id()));
+//! let options = FifoOptions::new().create(true);
+//! let writer = thread::spawn({
+//!     let path = path.clone();
+//!     move || options.open_writer(path)?.write_all(b"Hello from FIFO!")
+//! });
+//!
+//! let mut message = String::new();
+//! options.open_reader(&path)?.read_to_string(&mut message)?;
+//! writer.join().unwrap()?;
+//! assert_eq!(message, "Hello from FIFO!");
+//! std::fs::remove_file(path)?;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+//!
+//! [`udsocket`]: ../udsocket/index.html " "
+//! [`File`]: https://doc.rust-lang.org/stable/std/fs/struct.File.html " "
+//! [`remove_file`]: https://doc.rust-lang.org/stable/std/fs/fn.remove_file.html " "
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use super::{check_file_type, set_nonblocking, unixprelude::*, FdOps};
+use std::{
+    ffi::CString,
+    fmt::{self, Debug, Formatter},
+    io::{self, IoSlice, IoSliceMut, Read, Write},
+    path::Path,
+    process::Stdio,
+};
+
+/// Creates a FIFO file at the specified path with the specified permissions.
+///
+/// Since the `mode` parameter is masked with the [`umask`], it's best to leave it at `0o777` unless a different value is desired.
+///
+/// ## System calls
+/// - [`mkfifo`]
+///
+/// [`mkfifo`]: https://pubs.opengroup.org/onlinepubs/9699919799/utilities/mkfifo.html " "
+/// [`umask`]: https://en.wikipedia.org/wiki/Umask " "
+pub fn create_fifo<P: AsRef<Path>>(path: P, mode: mode_t) -> io::Result<()> {
+    _create_fifo(path.as_ref(), mode)
+}
+fn _create_fifo(path: &Path, mode: mode_t) -> io::Result<()> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let success = unsafe { libc::mkfifo(path.as_bytes_with_nul().as_ptr() as *const _, mode) == 0 };
+    ok_or_ret_errno!(success => ())
+}
+
+/// A builder for opening FIFO files, optionally creating them first.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Copy, Clone, Debug)]
+pub struct FifoOptions {
+    mode: mode_t,
+    create: bool,
+    wait_for_peer: bool,
+    nonblocking: bool,
+}
+impl FifoOptions {
+    /// Creates a new builder with default options, which open an existing FIFO in blocking mode, waiting for the other end to be opened.
+    pub fn new() -> Self {
+        Self {
+            mode: 0o666,
+            create: false,
+            wait_for_peer: true,
+            nonblocking: false,
+        }
+    }
+    /// Sets the permissions with which the FIFO is created if [`create`](Self::create) is enabled. Masked with the [`umask`], and `0o666` by default.
+    ///
+    /// [`umask`]: https://en.wikipedia.org/wiki/Umask " "
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn mode(mut self, mode: mode_t) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Sets whether the FIFO is created if it doesn't exist yet. By default, it isn't, and opening a nonexistent FIFO fails with [`NotFound`](io::ErrorKind::NotFound).
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+    /// Sets whether opening the FIFO waits for the other end to be opened. By default, it does.
+    ///
+    /// If disabled, opening a reader succeeds right away, with reads returning end-of-file until a writer connects, and opening a writer fails with [`NotConnected`](io::ErrorKind::NotConnected) if there are no readers. Either way, the resulting reader or writer is in blocking mode, unless [`nonblocking`](Self::nonblocking) is enabled.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn wait_for_peer(mut self, wait_for_peer: bool) -> Self {
+        self.wait_for_peer = wait_for_peer;
+        self
+    }
+    /// Sets whether the reader or writer is put in nonblocking mode once opened. By default, it isn't.
+    ///
+    /// Enabling this also disables [`wait_for_peer`](Self::wait_for_peer), since the system opens FIFOs in nonblocking mode without waiting.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+    /// Opens the reading end of the FIFO at the specified path.
+    pub fn open_reader(&self, path: impl AsRef<Path>) -> io::Result<FifoReader> {
+        self.open(path.as_ref(), false).map(FifoReader)
+    }
+    /// Opens the writing end of the FIFO at the specified path.
+    pub fn open_writer(&self, path: impl AsRef<Path>) -> io::Result<FifoWriter> {
+        self.open(path.as_ref(), true).map(FifoWriter)
+    }
+    fn open(&self, path: &Path, write: bool) -> io::Result<FdOps> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        if self.create {
+            let success = unsafe { libc::mkfifo(path.as_ptr(), self.mode) == 0 };
+            if !success {
+                let e = io::Error::last_os_error();
+                if e.kind() != io::ErrorKind::AlreadyExists {
+                    return Err(e);
+                }
+            }
+        }
+
+        // Opening in nonblocking mode is the only way to not wait for the other end.
+        let open_nonblocking = self.nonblocking || !self.wait_for_peer;
+        let mut flags = if write { libc::O_WRONLY } else { libc::O_RDONLY } | libc::O_CLOEXEC;
+        if open_nonblocking {
+            flags |= libc::O_NONBLOCK;
+        }
+        let fd = loop {
+            let fd = unsafe { libc::open(path.as_ptr(), flags) };
+            if fd != -1 {
+                break unsafe {
+                    // SAFETY: open() just created the descriptor
+                    FdOps::from_raw_fd(fd)
+                };
+            }
+            let e = io::Error::last_os_error();
+            match e.raw_os_error() {
+                Some(libc::EINTR) => continue,
+                Some(libc::ENXIO) if write => {
+                    return Err(io::Error::new(io::ErrorKind::NotConnected, "the FIFO has no readers"))
+                }
+                _ => return Err(e),
+            }
+        };
+
+        check_file_type(fd.as_fd(), libc::S_IFIFO, "the file is not a FIFO")?;
+        if open_nonblocking && !self.nonblocking {
+            set_nonblocking(fd.as_raw_fd(), false)?;
+        }
+        Ok(fd)
+    }
+}
+impl Default for FifoOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! fifo_end_impls {
+    ($ty:ident) => {
+        impl $ty {
+            /// Enables or disables nonblocking mode, in which operations that would block fail with a [`WouldBlock`](io::ErrorKind::WouldBlock) error instead.
+            pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+                set_nonblocking(self.0.as_raw_fd(), nonblocking)
+            }
+        }
+        impl Debug for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($ty)).field(&self.0.as_raw_fd()).finish()
+            }
+        }
+        impl AsFd for $ty {
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
+        impl AsRawFd for $ty {
+            fn as_raw_fd(&self) -> c_int {
+                self.0.as_raw_fd()
+            }
+        }
+        impl IntoRawFd for $ty {
+            fn into_raw_fd(self) -> c_int {
+                self.0.into_raw_fd()
+            }
+        }
+        impl FromRawFd for $ty {
+            unsafe fn from_raw_fd(fd: c_int) -> Self {
+                Self(unsafe { FdOps::from_raw_fd(fd) })
+            }
+        }
+        impl From<$ty> for OwnedFd {
+            fn from(x: $ty) -> Self {
+                x.0.into()
+            }
+        }
+        impl From<OwnedFd> for $ty {
+            fn from(fd: OwnedFd) -> Self {
+                Self(fd.into())
+            }
+        }
+        impl From<$ty> for Stdio {
+            fn from(x: $ty) -> Self {
+                OwnedFd::from(x).into()
+            }
+        }
+    };
+}
+
+/// The reading end of a FIFO file, opened with [`FifoOptions::open_reader`].
+pub struct FifoReader(FdOps);
+impl Read for FifoReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+}
+fifo_end_impls!(FifoReader);
+
+/// The writing end of a FIFO file, opened with [`FifoOptions::open_writer`].
+///
+/// Writes of at most `PIPE_BUF` bytes, which is at least 512 and 4096 on Linux, are atomic, meaning that they don't get interleaved with writes made by other writers of the same FIFO.
+pub struct FifoWriter(FdOps);
+impl Write for FifoWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        // Data written into a FIFO is immediately available to the reader.
+        Ok(())
+    }
+}
+fifo_end_impls!(FifoWriter);
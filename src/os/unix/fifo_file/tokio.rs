@@ -0,0 +1,160 @@
+//! Tokio-based asynchronous FIFO readers and writers.
+//!
+//! Unlike their synchronous counterparts, these never block the runtime when opening the FIFO: the reader is opened right away, and the writer periodically retries opening until a reader shows up. To create the FIFO or set its permissions, use [`create_fifo`](super::create_fifo) or [`FifoOptions::create`] first.
+
+use super::{FifoOptions, FifoReader as SyncFifoReader, FifoWriter as SyncFifoWriter};
+use futures_core::ready;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, IoSlice},
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{unix::AsyncFd, AsyncRead, AsyncWrite, ReadBuf};
+
+/// The asynchronous reading end of a FIFO file.
+///
+/// Reads return end-of-file once all writers close the FIFO, and may also do so if no writer has opened it yet.
+pub struct FifoReader {
+    inner: AsyncFd<SyncFifoReader>,
+}
+impl FifoReader {
+    /// Opens the reading end of an existing FIFO at the specified path without waiting for a writer.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let options = FifoOptions::new().nonblocking(true);
+        Self::from_sync(options.open_reader(path)?)
+    }
+    /// Wraps a synchronous reader, switching it to nonblocking mode.
+    pub fn from_sync(inner: SyncFifoReader) -> io::Result<Self> {
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner: AsyncFd::new(inner)?,
+        })
+    }
+}
+impl AsyncRead for FifoReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+            let rslt = guard.try_io(|inner| {
+                // SAFETY: read_to_uninit never de-initializes the buffer
+                let unfilled = unsafe { buf.unfilled_mut() };
+                inner.get_ref().0.read_to_uninit(unfilled)
+            });
+            if let Ok(rslt) = rslt {
+                return Poll::Ready(rslt.map(|bytes_read| {
+                    // SAFETY: the system call has just initialized this many bytes
+                    unsafe { buf.assume_init(bytes_read) };
+                    buf.advance(bytes_read);
+                }));
+            }
+        }
+    }
+}
+impl Debug for FifoReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FifoReader")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for FifoReader {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.get_ref().as_fd()
+    }
+}
+impl AsRawFd for FifoReader {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+/// The asynchronous writing end of a FIFO file.
+pub struct FifoWriter {
+    inner: AsyncFd<SyncFifoWriter>,
+}
+impl FifoWriter {
+    /// The longest delay between two attempts at opening a FIFO which has no readers.
+    const MAX_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+    /// Opens the writing end of an existing FIFO at the specified path, waiting for a reader to open it if there are
+    /// none.
+    ///
+    /// The system provides no way to be notified of a reader showing up, so opening is retried with exponential
+    /// backoff, up to every 100 milliseconds.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let options = FifoOptions::new().nonblocking(true);
+        let mut delay = Duration::from_millis(1);
+        loop {
+            match options.open_writer(path) {
+                Ok(writer) => return Self::from_sync(writer),
+                Err(e) if e.kind() == io::ErrorKind::NotConnected => {
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(Self::MAX_RETRY_DELAY);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    /// Wraps a synchronous writer, switching it to nonblocking mode.
+    pub fn from_sync(inner: SyncFifoWriter) -> io::Result<Self> {
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner: AsyncFd::new(inner)?,
+        })
+    }
+}
+impl AsyncWrite for FifoWriter {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_write_ready(cx))?;
+            if let Ok(rslt) = guard.try_io(|inner| inner.get_ref().0.write(buf)) {
+                return Poll::Ready(rslt);
+            }
+        }
+    }
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_write_ready(cx))?;
+            if let Ok(rslt) = guard.try_io(|inner| inner.get_ref().0.write_vectored(bufs)) {
+                return Poll::Ready(rslt);
+            }
+        }
+    }
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Data written into a FIFO is immediately available to the reader.
+        Poll::Ready(Ok(()))
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // The reader only sees end-of-file once the writer is dropped, which can't be done early.
+        Poll::Ready(Ok(()))
+    }
+}
+impl Debug for FifoWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FifoWriter")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for FifoWriter {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.get_ref().as_fd()
+    }
+}
+impl AsRawFd for FifoWriter {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
@@ -0,0 +1,92 @@
+use {
+    super::{local_socket_name_to_ud_socket_path, ud_socket_path_to_local_socket_name},
+    crate::{
+        handle_conversion::FromHandleError,
+        local_socket::{LocalSocketName, ToLocalSocketName},
+        os::unix::udsocket::{UdSocket, UdSocketPath},
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
+    },
+};
+
+pub struct LocalSocketDatagram {
+    inner: UdSocket,
+}
+impl LocalSocketDatagram {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let inner = UdSocket::bind_with_drop_guard(path)?;
+        Ok(Self { inner })
+    }
+    pub fn unbound() -> io::Result<Self> {
+        let inner = UdSocket::unbound()?;
+        Ok(Self { inner })
+    }
+    pub fn connect<'a>(&mut self, name: impl ToLocalSocketName<'a>) -> io::Result<()> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        self.inner.set_destination(path)
+    }
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+    pub fn send_to<'a>(&self, buf: &[u8], name: impl ToLocalSocketName<'a>) -> io::Result<usize> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        self.inner.send_to(buf, path)
+    }
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.recv(buf)
+    }
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, Option<LocalSocketName<'static>>)> {
+        let mut addr_buf = UdSocketPath::buffer();
+        let size = self.inner.recv_from(buf, &mut addr_buf)?;
+        Ok((size, ud_socket_path_to_local_socket_name(addr_buf)))
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LocalSocketDatagram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketDatagram")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for LocalSocketDatagram {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+impl From<LocalSocketDatagram> for OwnedFd {
+    fn from(x: LocalSocketDatagram) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedFd> for LocalSocketDatagram {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: UdSocket::try_from(fd)?,
+        })
+    }
+}
+impl AsRawFd for LocalSocketDatagram {
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
+impl IntoRawFd for LocalSocketDatagram {
+    fn into_raw_fd(self) -> i32 {
+        self.inner.into_raw_fd()
+    }
+}
+impl FromRawFd for LocalSocketDatagram {
+    unsafe fn from_raw_fd(fd: i32) -> Self {
+        Self {
+            inner: unsafe { UdSocket::from_raw_fd(fd) },
+        }
+    }
+}
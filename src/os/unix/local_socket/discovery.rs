@@ -0,0 +1,108 @@
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+use {
+    super::name_gen_directory,
+    std::{fs, os::unix::fs::FileTypeExt, path::PathBuf},
+};
+use {
+    crate::local_socket::LocalSocketName,
+    std::{
+        borrow::Cow,
+        ffi::{OsStr, OsString},
+        io,
+        os::unix::ffi::{OsStrExt, OsStringExt},
+        path::Path,
+    },
+};
+
+/// Lists the listening stream sockets from `/proc/net/unix`, which covers both socket files and namespaced sockets.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn enumerate_servers(prefix: &OsStr) -> io::Result<Vec<LocalSocketName<'static>>> {
+    /// Set in the flags column for sockets which are listening (`__SO_ACCEPTCON`).
+    const ACCEPTCON: u32 = 0x10000;
+
+    let table = std::fs::read("/proc/net/unix")?;
+    let mut servers = Vec::new();
+    // The first line is the header.
+    for line in table.split(|&b| b == b'\n').skip(1) {
+        // Num RefCount Protocol Flags Type St Inode Path – every column but the path is free of spaces, while the path
+        // may contain them and is absent for unnamed sockets.
+        let mut rest = line;
+        let mut fields = [&[][..]; 7];
+        for field in &mut fields {
+            let start = rest.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(rest.len());
+            rest = &rest[start..];
+            let end = rest.iter().position(u8::is_ascii_whitespace).unwrap_or(rest.len());
+            *field = &rest[..end];
+            rest = &rest[end..];
+        }
+        let path = match rest.strip_prefix(b" ") {
+            Some(path) if !path.is_empty() => path,
+            _ => continue,
+        };
+        let flags = std::str::from_utf8(fields[3])
+            .ok()
+            .and_then(|flags| u32::from_str_radix(flags, 16).ok());
+        let is_stream = fields[4] == b"0001";
+        if flags.map_or(true, |flags| flags & ACCEPTCON == 0) || !is_stream {
+            continue;
+        }
+
+        let name = match path.strip_prefix(b"@") {
+            Some(name) => {
+                // Nul bytes are displayed as @ too, and namespaced addresses bound with the whole `sun_path` buffer
+                // are padded with them.
+                let len = name.iter().rposition(|&b| b != b'@').map_or(0, |i| i + 1);
+                let name = OsString::from_vec(name[..len].to_vec());
+                LocalSocketName::from_raw_parts(Cow::Owned(name), true)
+            }
+            None => {
+                let path = Path::new(OsStr::from_bytes(path));
+                // A listener whose socket file has been removed can't be connected to anymore.
+                if !path.exists() {
+                    continue;
+                }
+                LocalSocketName::from_raw_parts(Cow::Owned(path.as_os_str().to_owned()), false)
+            }
+        };
+        if matches_prefix(&name, prefix) && !servers.contains(&name) {
+            servers.push(name);
+        }
+    }
+    Ok(servers)
+}
+
+/// Lists the socket files in the directories where local sockets are conventionally placed, since there is no portable
+/// way to enumerate listening sockets.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn enumerate_servers(prefix: &OsStr) -> io::Result<Vec<LocalSocketName<'static>>> {
+    let mut dirs: Vec<PathBuf> = vec![name_gen_directory(true), name_gen_directory(false), "/tmp".into()];
+    dirs.dedup();
+    let mut servers = Vec::new();
+    for dir in dirs {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_socket() {
+                continue;
+            }
+            let name = LocalSocketName::from_raw_parts(Cow::Owned(entry.path().into_os_string()), false);
+            if matches_prefix(&name, prefix) && !servers.contains(&name) {
+                servers.push(name);
+            }
+        }
+    }
+    Ok(servers)
+}
+
+/// Paths are matched by their file name, so that the prefix doesn't have to include the directory.
+fn matches_prefix(name: &LocalSocketName<'_>, prefix: &OsStr) -> bool {
+    let name = match name.is_path() {
+        true => Path::new(name.inner()).file_name().unwrap_or_default(),
+        false => name.inner(),
+    };
+    name.as_bytes().starts_with(prefix.as_bytes())
+}
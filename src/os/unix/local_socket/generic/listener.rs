@@ -0,0 +1,40 @@
+use {
+    super::{super::local_socket_name_to_ud_socket_path, LocalSocketStream, Registered},
+    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::UdStreamListener, reactor::Reactor},
+    std::{
+        fmt::{self, Debug, Formatter},
+        future, io,
+        os::unix::io::AsRawFd,
+    },
+};
+
+pub struct LocalSocketListener<R: Reactor> {
+    inner: Registered<R, UdStreamListener>,
+}
+impl<R: Reactor> LocalSocketListener<R> {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let listener = UdStreamListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Registered::new(listener)?,
+        })
+    }
+    pub async fn accept(&self) -> io::Result<LocalSocketStream<R>> {
+        let inner = future::poll_fn(|cx| self.inner.poll_read_with(cx, UdStreamListener::accept)).await?;
+        LocalSocketStream::from_sync(inner)
+    }
+}
+impl<R: Reactor> Debug for LocalSocketListener<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketListener")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl<R: Reactor> AsRawFd for LocalSocketListener<R> {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
@@ -0,0 +1,10 @@
+//! Adapter module, implements generic asynchronous local sockets under Unix.
+
+mod registered;
+use registered::*;
+
+mod listener;
+pub use listener::*;
+
+mod stream;
+pub use stream::*;
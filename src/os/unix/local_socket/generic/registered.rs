@@ -0,0 +1,65 @@
+use {
+    crate::reactor::Reactor,
+    futures_core::ready,
+    std::{
+        io,
+        os::unix::io::AsRawFd,
+        task::{Context, Poll},
+    },
+};
+
+/// A nonblocking I/O object registered with the reactor `R`.
+pub(super) struct Registered<R: Reactor, T> {
+    // Declared before the I/O object so that the registration is undone before the file descriptor is closed.
+    registration: R::Registration,
+    io: T,
+}
+impl<R: Reactor, T: AsRawFd> Registered<R, T> {
+    /// Registers the given I/O object, which must already be in nonblocking mode.
+    pub fn new(io: T) -> io::Result<Self> {
+        let registration = R::register(io.as_raw_fd())?;
+        Ok(Self { registration, io })
+    }
+}
+impl<R: Reactor, T> Registered<R, T> {
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.io
+    }
+    /// Performs a read-like operation, waiting for readability every time it would block.
+    pub fn poll_read_with<U>(
+        &self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&T) -> io::Result<U>,
+    ) -> Poll<io::Result<U>> {
+        loop {
+            match op(&self.io) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    ready!(R::poll_readable(&self.registration, cx))?;
+                }
+                els => return Poll::Ready(els),
+            }
+        }
+    }
+    /// Performs a write-like operation, waiting for writability every time it would block.
+    pub fn poll_write_with<U>(
+        &self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&T) -> io::Result<U>,
+    ) -> Poll<io::Result<U>> {
+        loop {
+            match op(&self.io) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    ready!(R::poll_writable(&self.registration, cx))?;
+                }
+                els => return Poll::Ready(els),
+            }
+        }
+    }
+}
+impl<R: Reactor, T: AsRawFd> AsRawFd for Registered<R, T> {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.io.as_raw_fd()
+    }
+}
@@ -0,0 +1,112 @@
+mod read_half;
+pub use read_half::*;
+
+mod write_half;
+pub use write_half::*;
+
+use {
+    super::{
+        super::{local_socket_credentials, local_socket_name_to_ud_socket_path, peer_pid_from_credentials},
+        Registered,
+    },
+    crate::{
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::unix::udsocket::UdStream,
+        reactor::Reactor,
+    },
+    futures_io::{AsyncRead, AsyncWrite},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, IoSlice, IoSliceMut},
+        os::unix::io::AsRawFd,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+    },
+};
+
+/// The socket is shared via `Arc` so that it could be split into halves and reunited, like the sync streams.
+type StreamImpl<R> = Arc<Registered<R, UdStream>>;
+
+pub struct LocalSocketStream<R: Reactor> {
+    inner: StreamImpl<R>,
+}
+impl<R: Reactor> LocalSocketStream<R> {
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        // Connecting to a Ud-socket never waits for the server to accept, so this doesn't block the executor.
+        Self::from_sync(UdStream::connect(path)?)
+    }
+    pub(super) fn from_sync(inner: UdStream) -> io::Result<Self> {
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner: Arc::new(Registered::new(inner)?),
+        })
+    }
+    pub fn into_split(self) -> (OwnedReadHalf<R>, OwnedWriteHalf<R>) {
+        let inner = Arc::clone(&self.inner);
+        (OwnedReadHalf { inner }, OwnedWriteHalf { inner: self.inner })
+    }
+    pub fn reunite(rh: OwnedReadHalf<R>, wh: OwnedWriteHalf<R>) -> Result<Self, (OwnedReadHalf<R>, OwnedWriteHalf<R>)> {
+        if !Arc::ptr_eq(&rh.inner, &wh.inner) {
+            return Err((rh, wh));
+        }
+        drop(wh);
+        Ok(Self { inner: rh.inner })
+    }
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+}
+impl<R: Reactor> AsyncRead for LocalSocketStream<R> {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_read_with(cx, |s| s.recv(buf))
+    }
+    #[inline]
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_read_with(cx, |s| s.recv_vectored(bufs))
+    }
+}
+impl<R: Reactor> AsyncWrite for LocalSocketStream<R> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_with(cx, |s| s.send(buf))
+    }
+    #[inline]
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_with(cx, |s| s.send_vectored(bufs))
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+impl<R: Reactor> Debug for LocalSocketStream<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketStream")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl<R: Reactor> AsRawFd for LocalSocketStream<R> {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
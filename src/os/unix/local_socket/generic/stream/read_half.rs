@@ -0,0 +1,48 @@
+use {
+    super::{
+        super::super::{local_socket_credentials, peer_pid_from_credentials},
+        StreamImpl,
+    },
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncRead,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, IoSliceMut},
+        os::unix::io::AsRawFd,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+pub struct OwnedReadHalf<R: Reactor> {
+    pub(super) inner: StreamImpl<R>,
+}
+impl<R: Reactor> OwnedReadHalf<R> {
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+}
+impl<R: Reactor> AsyncRead for OwnedReadHalf<R> {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_read_with(cx, |s| s.recv(buf))
+    }
+    #[inline]
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_read_with(cx, |s| s.recv_vectored(bufs))
+    }
+}
+impl<R: Reactor> Debug for OwnedReadHalf<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("local_socket::OwnedReadHalf")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
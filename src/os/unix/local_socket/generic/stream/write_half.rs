@@ -0,0 +1,56 @@
+use {
+    super::{
+        super::super::{local_socket_credentials, peer_pid_from_credentials},
+        StreamImpl,
+    },
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncWrite,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io::{self, IoSlice},
+        os::unix::io::AsRawFd,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+pub struct OwnedWriteHalf<R: Reactor> {
+    pub(super) inner: StreamImpl<R>,
+}
+impl<R: Reactor> OwnedWriteHalf<R> {
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+}
+impl<R: Reactor> AsyncWrite for OwnedWriteHalf<R> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_with(cx, |s| s.send(buf))
+    }
+    #[inline]
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_with(cx, |s| s.send_vectored(bufs))
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+impl<R: Reactor> Debug for OwnedWriteHalf<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("local_socket::OwnedWriteHalf")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
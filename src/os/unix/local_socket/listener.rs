@@ -1,30 +1,313 @@
 use {
-    super::{local_socket_name_to_ud_socket_path, LocalSocketStream},
-    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::UdStreamListener},
+    super::{local_socket_name_to_ud_socket_path, peer_pid_from_credentials, LocalSocketStream},
+    crate::{
+        handle_conversion::FromHandleError,
+        listener_close::ListenerClosedError,
+        local_socket::{ListenerHooks, LocalSocketListenerOptions, ToLocalSocketName},
+        os::unix::udsocket::{check_ud_socket, SocketFileOptions, UdSocketPath, UdStream, UdStreamListener},
+    },
+    libc::{c_int, mode_t, SOCK_STREAM},
     std::{
+        ffi::OsStr,
         fmt::{self, Debug, Formatter},
-        io,
-        os::unix::io::{AsRawFd, FromRawFd, IntoRawFd},
+        fs,
+        io::{self, prelude::*},
+        os::unix::{
+            ffi::OsStrExt,
+            fs::FileTypeExt,
+            io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
+            net::UnixStream,
+        },
+        path::Path,
+        sync::{
+            atomic::{AtomicBool, Ordering::*},
+            Mutex, PoisonError,
+        },
+        time::{Duration, Instant},
     },
 };
 
 pub struct LocalSocketListener {
+    /// Always in nonblocking mode, so that a thread which loses the race for a client to another one after waking up
+    /// from `poll` goes back to waiting instead of blocking in `accept` where closing the listener can't reach it.
     inner: UdStreamListener,
+    /// The nonblocking mode as seen by the user.
+    nonblocking: AtomicBool,
+    same_user_only: bool,
+    close_signal: CloseSignal,
+    hooks: Option<ListenerHooks>,
 }
 impl LocalSocketListener {
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
         let inner = UdStreamListener::bind(path)?;
-        Ok(Self { inner })
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner,
+            nonblocking: AtomicBool::new(false),
+            same_user_only: false,
+            close_signal: CloseSignal::new()?,
+            hooks: None,
+        })
+    }
+    pub fn bind_with_options<'a>(
+        name: impl ToLocalSocketName<'a>,
+        options: &LocalSocketListenerOptions,
+    ) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let hooks = ListenerHooks::new(options.hooks.as_ref(), &name);
+        let path = local_socket_name_to_ud_socket_path(name)?;
+        let inner = bind_ud_listener(path, options, true)?;
+        Ok(Self {
+            inner,
+            nonblocking: AtomicBool::new(options.nonblocking),
+            same_user_only: options.same_user_only,
+            close_signal: CloseSignal::new()?,
+            hooks,
+        })
     }
     pub fn accept(&self) -> io::Result<LocalSocketStream> {
-        let inner = self.inner.accept()?;
-        Ok(LocalSocketStream { inner })
+        loop {
+            let nonblocking = self.nonblocking.load(Relaxed);
+            if nonblocking {
+                self.close_signal.check()?;
+            } else {
+                self.close_signal.wait(&self.inner, None)?;
+            }
+            let inner = match self.inner.accept() {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock && !nonblocking => continue,
+                els => els?,
+            };
+            if let Some(stream) = self.admit(inner, nonblocking)? {
+                return Ok(stream);
+            }
+        }
+    }
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<LocalSocketStream> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if !self.close_signal.wait(&self.inner, Some(remaining))? {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no client connected before the timeout expired",
+                ));
+            }
+            let inner = match self.inner.accept() {
+                // Another thread got to the client first.
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                els => els?,
+            };
+            if let Some(stream) = self.admit(inner, self.nonblocking.load(Relaxed))? {
+                return Ok(stream);
+            }
+        }
+    }
+    pub fn wait_accept(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.close_signal.wait(&self.inner, timeout)
+    }
+    pub fn close(&self) {
+        self.close_signal.close()
+    }
+    pub fn is_closed(&self) -> bool {
+        self.close_signal.closed.load(Acquire)
+    }
+    pub fn close_signal_fd(&self) -> io::Result<RawFd> {
+        self.close_signal.receiver_fd()
+    }
+    /// Applies the same-user-only policy to a freshly accepted connection, returning `None` if it's rejected.
+    fn admit(&self, conn: UdStream, nonblocking: bool) -> io::Result<Option<LocalSocketStream>> {
+        if !self.admits(&conn) {
+            self.report_rejection(&conn);
+            return Ok(None);
+        }
+        // Accepted sockets inherit the nonblocking mode of the listener everywhere but on Linux, and the one the
+        // listener is kept in internally isn't the one the user asked for.
+        if cfg!(not(any(target_os = "linux", target_os = "android"))) && !nonblocking {
+            conn.set_nonblocking(false)?;
+        }
+        Ok(Some(LocalSocketStream { inner: conn }))
+    }
+    fn admits(&self, conn: &UdStream) -> bool {
+        if !self.same_user_only {
+            return true;
+        }
+        let euid = unsafe { libc::geteuid() };
+        matches!(conn.get_peer_credentials().map(|c| c.uid()), Ok(Some(uid)) if uid == euid)
+    }
+    fn report_rejection(&self, conn: &UdStream) {
+        if let Some(hooks) = &self.hooks {
+            hooks.rejected(peer_pid_from_credentials(conn.get_peer_credentials()));
+        }
+    }
+    pub(crate) fn hooks(&self) -> Option<&ListenerHooks> {
+        self.hooks.as_ref()
     }
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        self.inner.set_nonblocking(nonblocking)
+        self.nonblocking.store(nonblocking, Relaxed);
+        Ok(())
+    }
+    /// Hands out the file descriptor with the nonblocking mode the user asked for.
+    fn into_inner(self) -> UdStreamListener {
+        // The descriptor is valid, so this can only fail in ways which would have made the listener unusable anyway.
+        let _ = self.inner.set_nonblocking(self.nonblocking.load(Relaxed));
+        self.inner
+    }
+}
+/// Lets `close()` wake up threads blocked in `accept()`: instead of blocking in the `accept` system call directly, they
+/// wait for either the listener or one end of this socket pair to become readable, and closing writes a byte into the
+/// other end.
+#[derive(Debug)]
+struct CloseSignal {
+    closed: AtomicBool,
+    /// The sending and receiving ends. Listeners created with `FromRawFd` have no way of reporting an error, so they
+    /// create the pair on first use instead; once created, it's never replaced.
+    pair: Mutex<Option<(UnixStream, UnixStream)>>,
+}
+impl CloseSignal {
+    fn new() -> io::Result<Self> {
+        Ok(Self {
+            closed: AtomicBool::new(false),
+            pair: Mutex::new(Some(UnixStream::pair()?)),
+        })
+    }
+    fn new_lazy() -> Self {
+        Self {
+            closed: AtomicBool::new(false),
+            pair: Mutex::new(None),
+        }
+    }
+    fn close(&self) {
+        // The flag is set before looking at the pair, so a waiter which creates the pair after this sees the flag.
+        if !self.closed.swap(true, AcqRel) {
+            if let Some((sender, _)) = &*self.pair.lock().unwrap_or_else(PoisonError::into_inner) {
+                // The byte is never read, so that every waiter sees it. Failing to send it is only possible if the
+                // buffer is full, which in turn means that a byte has already been sent.
+                let _ = (&*sender).write(&[0]);
+            }
+        }
+    }
+    fn receiver_fd(&self) -> io::Result<RawFd> {
+        let mut pair = self.pair.lock().unwrap_or_else(PoisonError::into_inner);
+        if pair.is_none() {
+            *pair = Some(UnixStream::pair()?);
+        }
+        Ok(pair.as_ref().map_or(-1, |(_, receiver)| receiver.as_raw_fd()))
+    }
+    fn check(&self) -> io::Result<()> {
+        match self.closed.load(Acquire) {
+            true => Err(ListenerClosedError.into()),
+            false => Ok(()),
+        }
+    }
+    /// Waits for the listener to have a connection to accept, returning `false` if the timeout expires first and an
+    /// error if the listener gets closed.
+    fn wait(&self, listener: &UdStreamListener, timeout: Option<Duration>) -> io::Result<bool> {
+        let receiver = self.receiver_fd()?;
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            self.check()?;
+            let millis = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    // Rounded up so that a sub-millisecond remainder doesn't turn into a busy loop.
+                    let millis = (remaining.as_nanos() + 999_999) / 1_000_000;
+                    millis.min(c_int::MAX as u128) as c_int
+                }
+                None => -1,
+            };
+            let mut pfds = [listener.as_raw_fd(), receiver].map(|fd| libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            });
+            let result = unsafe { libc::poll(pfds.as_mut_ptr(), pfds.len() as _, millis) };
+            match result {
+                -1 => {
+                    let e = io::Error::last_os_error();
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+                0 if deadline.map_or(false, |deadline| Instant::now() >= deadline) => return Ok(false),
+                0 => {}
+                _ if pfds[1].revents != 0 => return Err(ListenerClosedError.into()),
+                _ => return Ok(true),
+            }
+        }
     }
 }
+
+/// Creates the socket of a listener, applying the options that concern the socket itself rather than the accepting of
+/// clients. The nonblocking mode is specified separately, since the Tokio listener needs it regardless of the options.
+pub(super) fn bind_ud_listener(
+    path: UdSocketPath<'_>,
+    options: &LocalSocketListenerOptions,
+    nonblocking: bool,
+) -> io::Result<UdStreamListener> {
+    let backlog = options.backlog.map_or(128, |b| b.min(c_int::MAX as u32) as c_int);
+    let bind = |path: UdSocketPath<'_>| {
+        UdStreamListener::_bind_with_options(
+            path,
+            SOCK_STREAM,
+            options.unlink_on_drop,
+            nonblocking,
+            backlog,
+            SocketFileOptions {
+                mode: options.mode.map(|m| m as mode_t),
+                owner: options.owner,
+                group: options.group,
+            },
+        )
+    };
+    match bind(path.borrow()) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse && options.reclaim_stale_name => {
+            if !reclaim_if_stale(&path)? {
+                return Err(e);
+            }
+            bind(path)
+        }
+        els => els,
+    }
+}
+
+/// Checks whether a server is listening on the socket file by connecting to it, deleting the file if there isn't one.
+/// Returns whether the name is free now.
+///
+/// Only socket files are ever deleted: connecting to a file of any other type also fails with `ECONNREFUSED` on
+/// Linux, and such a file is left alone for the bind to fail with `AddrInUse`. This doesn't rule out a race with
+/// another process which binds the same path between the check and the deletion, in which case the newly bound socket
+/// file is deleted out from under it – servers which may be started concurrently should serialize their startup, for
+/// example with a lock file.
+fn reclaim_if_stale(path: &UdSocketPath<'_>) -> io::Result<bool> {
+    let file = match path {
+        UdSocketPath::File(file) => Path::new(OsStr::from_bytes(file.to_bytes())),
+        // Namespaced sockets disappear together with their server, so an occupied name is never stale.
+        _ => return Ok(false),
+    };
+    match UdStream::connect(path.borrow()) {
+        // Someone's home.
+        Ok(..) => Ok(false),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+            match fs::symlink_metadata(file) {
+                Ok(meta) if meta.file_type().is_socket() => {}
+                Ok(..) => return Ok(false),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+                Err(e) => return Err(e),
+            }
+            match fs::remove_file(file) {
+                Ok(()) => Ok(true),
+                // Another process got to it first, which is fine.
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(true),
+                Err(e) => Err(e),
+            }
+        }
+        // The server has already removed the file by itself.
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e),
+    }
+}
+
 impl Debug for LocalSocketListener {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("LocalSocketListener")
@@ -32,6 +315,38 @@ impl Debug for LocalSocketListener {
             .finish()
     }
 }
+impl AsFd for LocalSocketListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+impl From<LocalSocketListener> for OwnedFd {
+    fn from(x: LocalSocketListener) -> Self {
+        x.into_inner().into()
+    }
+}
+impl TryFrom<OwnedFd> for LocalSocketListener {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_ud_socket(fd.as_fd(), &[SOCK_STREAM], true))?;
+        let close_signal = match CloseSignal::new() {
+            Ok(s) => s,
+            Err(e) => return Err(FromHandleError::new(fd, e)),
+        };
+        let inner = UdStreamListener::try_from(fd)?;
+        let nonblocking = match make_nonblocking(&inner) {
+            Ok(nonblocking) => nonblocking,
+            Err(e) => return Err(FromHandleError::new(inner.into(), e)),
+        };
+        Ok(Self {
+            inner,
+            nonblocking: AtomicBool::new(nonblocking),
+            same_user_only: false,
+            close_signal,
+            hooks: None,
+        })
+    }
+}
 impl AsRawFd for LocalSocketListener {
     fn as_raw_fd(&self) -> i32 {
         self.inner.as_raw_fd()
@@ -39,13 +354,30 @@ impl AsRawFd for LocalSocketListener {
 }
 impl IntoRawFd for LocalSocketListener {
     fn into_raw_fd(self) -> i32 {
-        self.inner.into_raw_fd()
+        self.into_inner().into_raw_fd()
     }
 }
 impl FromRawFd for LocalSocketListener {
     unsafe fn from_raw_fd(fd: i32) -> Self {
+        let inner = unsafe { UdStreamListener::from_raw_fd(fd) };
+        // There's no way to report the error, and the descriptor is the caller's responsibility anyway. Should it
+        // somehow fail, accepting blocks in the system call like it would without the close signal.
+        let nonblocking = make_nonblocking(&inner).unwrap_or(false);
         Self {
-            inner: unsafe { UdStreamListener::from_raw_fd(fd) },
+            inner,
+            nonblocking: AtomicBool::new(nonblocking),
+            same_user_only: false,
+            close_signal: CloseSignal::new_lazy(),
+            hooks: None,
         }
     }
 }
+
+/// Puts an adopted listener socket into nonblocking mode, returning the mode it was in before.
+fn make_nonblocking(listener: &UdStreamListener) -> io::Result<bool> {
+    let nonblocking = listener.is_nonblocking()?;
+    if !nonblocking {
+        listener.set_nonblocking(true)?;
+    }
+    Ok(nonblocking)
+}
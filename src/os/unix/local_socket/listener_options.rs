@@ -0,0 +1,56 @@
+use crate::{local_socket::LocalSocketListenerOptions, Sealed};
+
+/// Unix-specific options for [`LocalSocketListenerOptions`].
+pub trait LocalSocketListenerOptionsExt: Sealed {
+    /// Sets the permissions of the socket file, in the same format as the `mode` argument of `chmod` (the file type
+    /// bits are ignored). By default, the permissions are determined by the umask of the process.
+    ///
+    /// The permissions are changed before the listener starts accepting connections, leaving no window for a client to
+    /// connect with the default ones. Sockets in the Linux abstract namespace have no file and thus no permissions, so
+    /// this option is ignored for them.
+    ///
+    /// Connecting to a Ud-socket requires write permission for the socket file. Note that some older platforms ignore
+    /// the permissions of socket files altogether; restricting access to the containing directory works everywhere.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn mode(self, mode: u32) -> Self;
+    /// Sets the user ID to which the ownership of the socket file is given. By default, the file is owned by the
+    /// effective user ID of the process.
+    ///
+    /// Like the [permissions](Self::mode), the owner is changed with `chown` right after the file is created and
+    /// before the listener starts accepting connections, so that a daemon started as root can hand its socket over
+    /// to the user it serves without a window in which the file belongs to root. If changing the owner fails, which
+    /// is the case for unprivileged processes on most systems, the file is deleted and binding fails. Ignored for
+    /// sockets in the Linux abstract namespace.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn owner(self, uid: u32) -> Self;
+    /// Sets the group ID to which the socket file is assigned. By default, the group is chosen by the OS, usually
+    /// being the effective group ID of the process or the group of the containing directory.
+    ///
+    /// Applied in the same way as the [owner](Self::owner). Unprivileged processes may only assign the file to groups
+    /// they are a member of. Together with a [mode](Self::mode) of `0o660`, this grants access to the socket to the
+    /// members of a service group.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn group(self, gid: u32) -> Self;
+    /// Sets whether the socket file is to be deleted when the listener is dropped. By default, it is left over, as with
+    /// [`UdStreamListener::bind()`](crate::os::unix::udsocket::UdStreamListener::bind).
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn unlink_on_drop(self, unlink_on_drop: bool) -> Self;
+}
+impl LocalSocketListenerOptionsExt for LocalSocketListenerOptions {
+    fn mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+    fn owner(mut self, uid: u32) -> Self {
+        self.owner = Some(uid);
+        self
+    }
+    fn group(mut self, gid: u32) -> Self {
+        self.group = Some(gid);
+        self
+    }
+    fn unlink_on_drop(mut self, unlink_on_drop: bool) -> Self {
+        self.unlink_on_drop = unlink_on_drop;
+        self
+    }
+}
@@ -3,22 +3,42 @@
 #[cfg(feature = "tokio")]
 pub mod tokio;
 
+#[cfg(feature = "async")]
+pub mod generic;
+
+mod datagram;
+pub use datagram::*;
+
+mod discovery;
+pub use discovery::*;
+
 mod listener;
 pub use listener::*;
 
+mod listener_options;
+pub use listener_options::*;
+
 mod stream;
 pub use stream::*;
 
+mod msg_listener;
+pub use msg_listener::*;
+
+mod msg_stream;
+pub use msg_stream::*;
+
 use {
     crate::{
-        local_socket::{LocalSocketName, NameTypeSupport},
-        os::unix::udsocket::UdSocketPath,
+        local_socket::{LocalSocketName, LocalSocketPeerCredentials, LocalSocketPeerIdentity, NameTypeSupport},
+        os::unix::udsocket::{PeerCredentials, UdSocketPath},
     },
     std::{
         borrow::Cow,
+        env,
         ffi::{CStr, CString, OsStr, OsString},
         io,
         os::unix::ffi::{OsStrExt, OsStringExt},
+        path::PathBuf,
     },
 };
 
@@ -46,6 +66,65 @@ fn local_socket_name_to_ud_socket_path(name: LocalSocketName<'_>) -> io::Result<
     Ok(UdSocketPath::File(cow_osstr_to_cstr(name.into_inner_cow())?))
 }
 
+/// Converts a source address received from a datagram socket to a local socket name, or `None` if the sender is unnamed.
+fn ud_socket_path_to_local_socket_name(path: UdSocketPath<'_>) -> Option<LocalSocketName<'static>> {
+    let (cstr, namespaced) = match path {
+        UdSocketPath::Unnamed => return None,
+        UdSocketPath::File(cstr) => (cstr, false),
+        #[cfg(uds_linux_namespace)]
+        UdSocketPath::Namespaced(cstr) => (cstr, true),
+    };
+    if cstr.to_bytes().is_empty() {
+        return None;
+    }
+    let osstring = OsString::from_vec(cstr.into_owned().into_bytes());
+    Some(LocalSocketName::from_raw_parts(Cow::Owned(osstring), namespaced))
+}
+
+/// Extracts the process ID from the result of `get_peer_credentials()`, failing if the platform doesn't report it.
+fn peer_pid_from_credentials(creds: io::Result<PeerCredentials>) -> io::Result<u32> {
+    creds?.pid().map(|pid| pid as u32).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the process ID of the peer is not reported on this platform",
+        )
+    })
+}
+
+/// Converts the result of `get_peer_credentials()` to the platform-independent form, failing if the platform doesn't
+/// report the user or group of the peer.
+fn local_socket_credentials(creds: io::Result<PeerCredentials>) -> io::Result<LocalSocketPeerCredentials> {
+    let creds = creds?;
+    match (creds.uid(), creds.gid()) {
+        (Some(uid), Some(gid)) => Ok(LocalSocketPeerCredentials::Unix {
+            pid: creds.pid().map(|pid| pid as u32),
+            uid,
+            gid,
+        }),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the user and group of the peer are not reported on this platform",
+        )),
+    }
+}
+
+pub fn current_process_identity() -> io::Result<LocalSocketPeerIdentity> {
+    Ok(LocalSocketPeerIdentity::Unix {
+        uid: unsafe { libc::geteuid() },
+        gid: unsafe { libc::getegid() },
+    })
+}
+
+pub fn name_gen_user_tag() -> String {
+    unsafe { libc::geteuid() }.to_string()
+}
+pub fn name_gen_directory(per_user: bool) -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if per_user && !dir.is_empty() => dir.into(),
+        _ => env::temp_dir(),
+    }
+}
+
 pub fn name_type_support_query() -> NameTypeSupport {
     NAME_TYPE_ALWAYS_SUPPORTED
 }
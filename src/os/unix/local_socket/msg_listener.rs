@@ -0,0 +1,75 @@
+use {
+    super::{local_socket_name_to_ud_socket_path, LocalSocketMsgStream},
+    crate::{
+        handle_conversion::FromHandleError,
+        local_socket::ToLocalSocketName,
+        os::unix::udsocket::{check_ud_socket, UdStreamListener},
+    },
+    libc::SOCK_SEQPACKET,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
+    },
+};
+
+pub struct LocalSocketMsgListener {
+    inner: UdStreamListener,
+}
+impl LocalSocketMsgListener {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let inner = UdStreamListener::bind_seqpacket(path)?;
+        Ok(Self { inner })
+    }
+    pub fn accept(&self) -> io::Result<LocalSocketMsgStream> {
+        let inner = self.inner.accept()?;
+        Ok(LocalSocketMsgStream { inner })
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+impl Debug for LocalSocketMsgListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgListener")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for LocalSocketMsgListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+impl From<LocalSocketMsgListener> for OwnedFd {
+    fn from(x: LocalSocketMsgListener) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedFd> for LocalSocketMsgListener {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_ud_socket(fd.as_fd(), &[SOCK_SEQPACKET], true))?;
+        Ok(Self {
+            inner: UdStreamListener::try_from(fd)?,
+        })
+    }
+}
+impl AsRawFd for LocalSocketMsgListener {
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
+impl IntoRawFd for LocalSocketMsgListener {
+    fn into_raw_fd(self) -> i32 {
+        self.inner.into_raw_fd()
+    }
+}
+impl FromRawFd for LocalSocketMsgListener {
+    unsafe fn from_raw_fd(fd: i32) -> Self {
+        Self {
+            inner: unsafe { UdStreamListener::from_raw_fd(fd) },
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use {
+    super::{local_socket_credentials, local_socket_name_to_ud_socket_path, peer_pid_from_credentials},
+    crate::{
+        handle_conversion::FromHandleError,
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::unix::udsocket::{check_ud_socket, UdStream},
+        reliable_recv_msg::{ReliableRecvMsg, TryRecvResult},
+    },
+    libc::SOCK_SEQPACKET,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
+        time::Duration,
+    },
+};
+
+pub struct LocalSocketMsgStream {
+    pub(super) inner: UdStream,
+}
+impl LocalSocketMsgStream {
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let inner = UdStream::connect_seqpacket(path)?;
+        Ok(Self { inner })
+    }
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_writable(timeout)
+    }
+}
+impl ReliableRecvMsg for &LocalSocketMsgStream {
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        self.inner.try_recv_msg(buf)
+    }
+}
+impl Debug for LocalSocketMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgStream")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for LocalSocketMsgStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+impl From<LocalSocketMsgStream> for OwnedFd {
+    fn from(x: LocalSocketMsgStream) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedFd> for LocalSocketMsgStream {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_ud_socket(fd.as_fd(), &[SOCK_SEQPACKET], false))?;
+        Ok(Self {
+            inner: UdStream::try_from(fd)?,
+        })
+    }
+}
+impl AsRawFd for LocalSocketMsgStream {
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
+impl IntoRawFd for LocalSocketMsgStream {
+    fn into_raw_fd(self) -> i32 {
+        self.inner.into_raw_fd()
+    }
+}
+impl FromRawFd for LocalSocketMsgStream {
+    unsafe fn from_raw_fd(fd: i32) -> Self {
+        Self {
+            inner: unsafe { UdStream::from_raw_fd(fd) },
+        }
+    }
+}
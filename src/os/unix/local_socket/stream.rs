@@ -1,10 +1,19 @@
 use {
-    super::local_socket_name_to_ud_socket_path,
-    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::UdStream},
+    super::{local_socket_credentials, local_socket_name_to_ud_socket_path, peer_pid_from_credentials},
+    crate::{
+        handle_conversion::FromHandleError,
+        local_socket::{LocalSocketPeerCredentials, LocalSocketPeerIdentity, ToLocalSocketName},
+        os::unix::udsocket::{check_ud_socket, UdStream},
+    },
+    libc::SOCK_STREAM,
     std::{
         fmt::{self, Debug, Formatter},
         io::{self, prelude::*, IoSlice, IoSliceMut},
-        os::unix::io::{AsRawFd, FromRawFd, IntoRawFd},
+        os::{
+            fd::{AsFd, BorrowedFd, OwnedFd},
+            unix::io::{AsRawFd, FromRawFd, IntoRawFd},
+        },
+        time::Duration,
     },
 };
 
@@ -18,36 +27,85 @@ impl LocalSocketStream {
         Ok(Self { inner })
     }
     pub fn peer_pid(&self) -> io::Result<u32> {
-        #[cfg(uds_peerucred)]
-        {
-            self.inner.get_peer_credentials().map(|ucred| ucred.pid as u32)
-        }
-        #[cfg(not(uds_peerucred))]
-        {
-            Err(io::Error::new(io::ErrorKind::Other, "not supported"))
+        peer_pid_from_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        match self.peer_credentials()? {
+            LocalSocketPeerCredentials::Unix { uid, gid, .. } => Ok(LocalSocketPeerIdentity::Unix { uid, gid }),
+            _ => unreachable!(),
         }
     }
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            inner: self.inner.try_clone()?,
+        })
+    }
+    pub fn send_handles(&self, buf: &[u8], handles: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        self.inner.send_fds(buf, handles)
+    }
+    pub fn recv_handles(&self, buf: &mut [u8]) -> io::Result<(usize, Vec<OwnedFd>)> {
+        let (size, fds) = self.inner.recv_fds(buf)?;
+        Ok((size, fds.into_inner()))
+    }
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.peek(buf)
+    }
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        self.inner.bytes_available()
+    }
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_writable(timeout)
+    }
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         self.inner.set_nonblocking(nonblocking)
     }
+    #[cfg(feature = "bytes")]
+    pub fn read_to_uninit(&self, buf: &mut [std::mem::MaybeUninit<u8>]) -> io::Result<usize> {
+        self.inner.recv_to_uninit(buf)
+    }
+}
+impl Read for &LocalSocketStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&self.inner).read_vectored(bufs)
+    }
+}
+impl Write for &LocalSocketStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&self.inner).write_vectored(bufs)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.inner).flush()
+    }
 }
 impl Read for LocalSocketStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+        (&*self).read(buf)
     }
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.read_vectored(bufs)
+        (&*self).read_vectored(bufs)
     }
 }
 impl Write for LocalSocketStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        (&*self).write(buf)
     }
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.write_vectored(bufs)
+        (&*self).write_vectored(bufs)
     }
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.flush()
+        (&*self).flush()
     }
 }
 impl Debug for LocalSocketStream {
@@ -57,6 +115,25 @@ impl Debug for LocalSocketStream {
             .finish()
     }
 }
+impl AsFd for LocalSocketStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+impl From<LocalSocketStream> for OwnedFd {
+    fn from(x: LocalSocketStream) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedFd> for LocalSocketStream {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_ud_socket(fd.as_fd(), &[SOCK_STREAM], false))?;
+        Ok(Self {
+            inner: UdStream::try_from(fd)?,
+        })
+    }
+}
 impl AsRawFd for LocalSocketStream {
     fn as_raw_fd(&self) -> i32 {
         self.inner.as_raw_fd()
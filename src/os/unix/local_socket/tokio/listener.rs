@@ -1,27 +1,65 @@
 use {
-    super::{super::local_socket_name_to_ud_socket_path, LocalSocketStream},
-    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::tokio::UdStreamListener},
+    super::{
+        super::{bind_ud_listener, local_socket_name_to_ud_socket_path},
+        LocalSocketStream,
+    },
+    crate::{
+        local_socket::{LocalSocketListenerOptions, ToLocalSocketName},
+        os::unix::udsocket::{tokio::UdStreamListener, PathDropGuard},
+    },
     std::{
         fmt::{self, Debug, Formatter},
         io,
-        os::unix::io::AsRawFd,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd},
+        task::{Context, Poll},
     },
 };
 
 pub struct LocalSocketListener {
     inner: UdStreamListener,
+    /// Deletes the socket file when the listener is dropped, if that was requested in the options. The Tokio
+    /// Ud-socket listener doesn't have a drop guard of its own, so it's moved here from the synchronous one.
+    _drop_guard: PathDropGuard<'static>,
 }
 impl LocalSocketListener {
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
         let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
         let inner = UdStreamListener::bind(path)?;
-        Ok(Self { inner })
+        Ok(Self::from(inner))
+    }
+    pub fn bind_with_options<'a>(
+        name: impl ToLocalSocketName<'a>,
+        options: &LocalSocketListenerOptions,
+    ) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let mut sync = bind_ud_listener(path, options, true)?;
+        let drop_guard = sync.take_drop_guard();
+        Ok(Self {
+            inner: UdStreamListener::from_sync(sync)?,
+            _drop_guard: drop_guard,
+        })
     }
     pub async fn accept(&self) -> io::Result<LocalSocketStream> {
         let inner = self.inner.accept().await?;
         Ok(LocalSocketStream { inner })
     }
     #[inline]
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<LocalSocketStream>> {
+        self.inner.poll_accept(cx).map_ok(|inner| LocalSocketStream { inner })
+    }
+    #[inline]
+    pub fn close(&self) {
+        self.inner.close()
+    }
+    #[inline]
+    pub fn close_and_unlink(&self) -> io::Result<()> {
+        self.inner.close_and_unlink()
+    }
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+    #[inline]
     pub unsafe fn from_raw_fd(fd: libc::c_int) -> io::Result<Self> {
         unsafe { UdStreamListener::from_raw_fd(fd) }.map(Self::from)
     }
@@ -33,7 +71,10 @@ impl LocalSocketListener {
 impl From<UdStreamListener> for LocalSocketListener {
     #[inline]
     fn from(inner: UdStreamListener) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            _drop_guard: PathDropGuard::dummy(),
+        }
     }
 }
 impl Debug for LocalSocketListener {
@@ -43,6 +84,11 @@ impl Debug for LocalSocketListener {
             .finish()
     }
 }
+impl AsFd for LocalSocketListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
 impl AsRawFd for LocalSocketListener {
     fn as_raw_fd(&self) -> i32 {
         self.inner.as_raw_fd()
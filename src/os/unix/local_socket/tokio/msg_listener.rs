@@ -0,0 +1,51 @@
+use {
+    super::{super::local_socket_name_to_ud_socket_path, LocalSocketMsgStream},
+    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::UdStreamListener},
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd},
+    },
+    tokio::io::unix::AsyncFd,
+};
+
+pub struct LocalSocketMsgListener {
+    inner: AsyncFd<UdStreamListener>,
+}
+impl LocalSocketMsgListener {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        let inner = UdStreamListener::bind_seqpacket(path)?;
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner: AsyncFd::new(inner)?,
+        })
+    }
+    pub async fn accept(&self) -> io::Result<LocalSocketMsgStream> {
+        loop {
+            let mut guard = self.inner.readable().await?;
+            if let Ok(rslt) = guard.try_io(|inner| inner.get_ref().accept()) {
+                return LocalSocketMsgStream::from_sync(rslt?);
+            }
+        }
+    }
+}
+impl Debug for LocalSocketMsgListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgListener")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for LocalSocketMsgListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.get_ref().as_fd()
+    }
+}
+impl AsRawFd for LocalSocketMsgListener {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
@@ -0,0 +1,74 @@
+use {
+    super::super::{local_socket_credentials, local_socket_name_to_ud_socket_path, peer_pid_from_credentials},
+    crate::{
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::unix::udsocket::UdStream,
+        reliable_recv_msg::TryRecvResult,
+    },
+    futures_core::ready,
+    std::{
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd},
+        task::{Context, Poll},
+    },
+    tokio::io::unix::AsyncFd,
+};
+
+pub struct LocalSocketMsgStream {
+    inner: AsyncFd<UdStream>,
+}
+impl LocalSocketMsgStream {
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        // Connecting to a Ud-socket never waits for the server to accept, so this doesn't block the runtime.
+        Self::from_sync(UdStream::connect_seqpacket(path)?)
+    }
+    pub(super) fn from_sync(inner: UdStream) -> io::Result<Self> {
+        inner.set_nonblocking(true)?;
+        Ok(Self {
+            inner: AsyncFd::new(inner)?,
+        })
+    }
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_write_ready(cx))?;
+            if let Ok(rslt) = guard.try_io(|inner| inner.get_ref().send(buf)) {
+                return Poll::Ready(rslt);
+            }
+        }
+    }
+    pub fn poll_try_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+            if let Ok(rslt) = guard.try_io(|inner| inner.get_ref().try_recv_msg(buf)) {
+                return Poll::Ready(rslt);
+            }
+        }
+    }
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_ref().get_peer_credentials())
+    }
+}
+impl Debug for LocalSocketMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgStream")
+            .field("fd", &self.inner.as_raw_fd())
+            .finish()
+    }
+}
+impl AsFd for LocalSocketMsgStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.get_ref().as_fd()
+    }
+}
+impl AsRawFd for LocalSocketMsgStream {
+    #[inline]
+    fn as_raw_fd(&self) -> i32 {
+        self.inner.as_raw_fd()
+    }
+}
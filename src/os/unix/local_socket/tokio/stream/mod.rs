@@ -5,16 +5,24 @@ mod write_half;
 pub use write_half::*;
 
 use {
-    super::super::local_socket_name_to_ud_socket_path,
-    crate::{local_socket::ToLocalSocketName, os::unix::udsocket::tokio::UdStream},
+    super::super::{
+        local_socket_credentials, local_socket_name_to_ud_socket_path, peer_pid_from_credentials,
+        LocalSocketStream as SyncLocalSocketStream,
+    },
+    crate::{
+        local_socket::{LocalSocketPeerCredentials, LocalSocketPeerIdentity, ToLocalSocketName},
+        os::unix::udsocket::tokio::{ReuniteError, UdStream},
+    },
     futures_io::{AsyncRead, AsyncWrite},
     std::{
         fmt::{self, Debug, Formatter},
         io::{self, IoSlice, IoSliceMut},
-        os::unix::io::AsRawFd,
+        os::unix::io::{AsFd, AsRawFd, BorrowedFd},
         pin::Pin,
         task::{Context, Poll},
+        time::Duration,
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
 };
 
 pub struct LocalSocketStream {
@@ -25,21 +33,64 @@ impl LocalSocketStream {
         let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
         UdStream::connect(path).await.map(Self::from)
     }
+    pub async fn connect_with_timeout<'a>(name: impl ToLocalSocketName<'a>, timeout: Duration) -> io::Result<Self> {
+        let path = local_socket_name_to_ud_socket_path(name.to_local_socket_name()?)?;
+        match tokio::time::timeout(timeout, UdStream::connect(path)).await {
+            Ok(rslt) => rslt.map(Self::from),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the connection was not established before the timeout expired",
+            )),
+        }
+    }
     pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
         let (r, w) = self.inner.into_split();
         (OwnedReadHalf { inner: r }, OwnedWriteHalf { inner: w })
     }
-    pub fn peer_pid(&self) -> io::Result<u32> {
-        #[cfg(uds_peerucred)]
-        {
-            self.inner.get_peer_credentials().map(|ucred| ucred.pid as u32)
+    pub fn reunite(rh: OwnedReadHalf, wh: OwnedWriteHalf) -> Result<Self, (OwnedReadHalf, OwnedWriteHalf)> {
+        match UdStream::reunite(rh.inner, wh.inner) {
+            Ok(inner) => Ok(Self { inner }),
+            Err(ReuniteError(r, w)) => Err((OwnedReadHalf { inner: r }, OwnedWriteHalf { inner: w })),
         }
-        #[cfg(not(uds_peerucred))]
-        {
-            Err(io::Error::new(io::ErrorKind::Other, "not supported"))
+    }
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        peer_pid_from_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        match self.peer_credentials()? {
+            LocalSocketPeerCredentials::Unix { uid, gid, .. } => Ok(LocalSocketPeerIdentity::Unix { uid, gid }),
+            _ => unreachable!(),
         }
     }
     #[inline]
+    pub async fn readable(&self) -> io::Result<()> {
+        self.inner.readable().await
+    }
+    #[inline]
+    pub async fn writable(&self) -> io::Result<()> {
+        self.inner.writable().await
+    }
+    #[inline]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.try_recv(buf)
+    }
+    #[inline]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.try_send(buf)
+    }
+    pub fn from_sync(sync: SyncLocalSocketStream) -> io::Result<Self> {
+        sync.inner.set_nonblocking(true)?;
+        UdStream::from_sync(sync.inner).map(Self::from)
+    }
+    pub fn into_sync(self) -> io::Result<SyncLocalSocketStream> {
+        let inner = self.inner.into_sync()?;
+        inner.set_nonblocking(false)?;
+        Ok(SyncLocalSocketStream { inner })
+    }
+    #[inline]
     pub unsafe fn from_raw_fd(fd: i32) -> io::Result<Self> {
         unsafe { UdStream::from_raw_fd(fd) }.map(Self::from)
     }
@@ -60,7 +111,7 @@ impl From<UdStream> for LocalSocketStream {
 impl AsyncRead for LocalSocketStream {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> std::task::Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_read_vectored(
@@ -74,7 +125,7 @@ impl AsyncRead for LocalSocketStream {
 impl AsyncWrite for LocalSocketStream {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_write_vectored(
@@ -82,18 +133,38 @@ impl AsyncWrite for LocalSocketStream {
         cx: &mut Context<'_>,
         bufs: &[IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write_vectored(cx, bufs)
+        AsyncWrite::poll_write_vectored(self.pinproj(), cx, bufs)
     }
 
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         self.pinproj().poll_close(cx)
     }
 }
+impl TokioAsyncRead for LocalSocketStream {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
+impl TokioAsyncWrite for LocalSocketStream {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_shutdown(self.pinproj(), cx)
+    }
+}
 impl Debug for LocalSocketStream {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("LocalSocketStream")
@@ -101,6 +172,12 @@ impl Debug for LocalSocketStream {
             .finish()
     }
 }
+impl AsFd for LocalSocketStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
 impl AsRawFd for LocalSocketStream {
     #[inline]
     fn as_raw_fd(&self) -> i32 {
@@ -1,5 +1,6 @@
 use {
-    crate::os::unix::udsocket::tokio::OwnedReadHalf as OwnedReadHalfImpl,
+    super::super::super::{local_socket_credentials, peer_pid_from_credentials},
+    crate::{local_socket::LocalSocketPeerCredentials, os::unix::udsocket::tokio::OwnedReadHalf as OwnedReadHalfImpl},
     futures_io::AsyncRead,
     std::{
         fmt::{self, Debug, Formatter},
@@ -7,6 +8,7 @@ use {
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, ReadBuf as TokioReadBuf},
 };
 
 pub struct OwnedReadHalf {
@@ -14,14 +16,10 @@ pub struct OwnedReadHalf {
 }
 impl OwnedReadHalf {
     pub fn peer_pid(&self) -> io::Result<u32> {
-        #[cfg(uds_peerucred)]
-        {
-            self.inner.get_peer_credentials().map(|ucred| ucred.pid as u32)
-        }
-        #[cfg(not(uds_peerucred))]
-        {
-            Err(io::Error::new(io::ErrorKind::Other, "not supported"))
-        }
+        peer_pid_from_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_peer_credentials())
     }
     #[inline]
     fn pinproj(&mut self) -> Pin<&mut OwnedReadHalfImpl> {
@@ -31,7 +29,7 @@ impl OwnedReadHalf {
 impl AsyncRead for OwnedReadHalf {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> std::task::Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_read_vectored(
@@ -42,6 +40,12 @@ impl AsyncRead for OwnedReadHalf {
         self.pinproj().poll_read_vectored(cx, bufs)
     }
 }
+impl TokioAsyncRead for OwnedReadHalf {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
 impl Debug for OwnedReadHalf {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_tuple("local_socket::OwnedReadHalf").field(&self.inner).finish()
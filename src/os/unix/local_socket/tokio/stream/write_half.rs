@@ -1,5 +1,8 @@
 use {
-    crate::os::unix::udsocket::tokio::OwnedWriteHalf as OwnedWriteHalfImpl,
+    super::super::super::{local_socket_credentials, peer_pid_from_credentials},
+    crate::{
+        local_socket::LocalSocketPeerCredentials, os::unix::udsocket::tokio::OwnedWriteHalf as OwnedWriteHalfImpl,
+    },
     futures_io::AsyncWrite,
     std::{
         fmt::{self, Debug, Formatter},
@@ -7,6 +10,7 @@ use {
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::AsyncWrite as TokioAsyncWrite,
 };
 
 pub struct OwnedWriteHalf {
@@ -14,14 +18,10 @@ pub struct OwnedWriteHalf {
 }
 impl OwnedWriteHalf {
     pub fn peer_pid(&self) -> io::Result<u32> {
-        #[cfg(uds_peerucred)]
-        {
-            self.inner.get_peer_credentials().map(|ucred| ucred.pid as u32)
-        }
-        #[cfg(not(uds_peerucred))]
-        {
-            Err(io::Error::new(io::ErrorKind::Other, "not supported"))
-        }
+        peer_pid_from_credentials(self.inner.get_peer_credentials())
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        local_socket_credentials(self.inner.get_peer_credentials())
     }
     #[inline]
     fn pinproj(&mut self) -> Pin<&mut OwnedWriteHalfImpl> {
@@ -31,7 +31,7 @@ impl OwnedWriteHalf {
 impl AsyncWrite for OwnedWriteHalf {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_write_vectored(
@@ -39,17 +39,31 @@ impl AsyncWrite for OwnedWriteHalf {
         cx: &mut Context<'_>,
         bufs: &[IoSlice<'_>],
     ) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write_vectored(cx, bufs)
+        AsyncWrite::poll_write_vectored(self.pinproj(), cx, bufs)
     }
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         self.pinproj().poll_close(cx)
     }
 }
+impl TokioAsyncWrite for OwnedWriteHalf {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_shutdown(self.pinproj(), cx)
+    }
+}
 impl Debug for OwnedWriteHalf {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_tuple("local_socket::OwnedWriteHalf")
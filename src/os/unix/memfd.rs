@@ -0,0 +1,278 @@
+//! Anonymous shared memory backed by `memfd_create`, which can be shared with other processes by passing its file
+//! descriptor.
+//!
+//! Unlike [named shared memory](crate::shared_memory), an [`AnonymousSharedMemory`] object has no name which could
+//! collide with others or be left behind: it's reachable only through its file descriptor, and is freed as soon as the
+//! last descriptor and the last mapping of it are gone, even if the processes using it crash. The descriptor reaches
+//! other processes by being inherited, for example with the [spawn helper](crate::spawn), or by being sent over a Unix
+//! domain socket – the [`.send_over()`](AnonymousSharedMemory::send_over) and
+//! [`.recv_from()`](AnonymousSharedMemory::recv_from) methods and their local socket counterparts do the latter in a
+//! single call.
+//!
+//! A process that receives shared memory from one it doesn't fully trust can't rely on its size and contents staying
+//! the same, since the sender keeps its own descriptor: if the object is shrunk while mapped, accessing the part past
+//! its new end kills the process with `SIGBUS`. This is what [seals](Seals) are for. Once the sender has sealed the
+//! object against shrinking, or against all writes, the seal can never be removed, and the receiver can check for it
+//! with [`.seals()`](AnonymousSharedMemory::seals) before mapping the memory.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::{
+//!     local_socket::LocalSocketStream,
+//!     os::unix::memfd::{AnonymousSharedMemory, Seals},
+//! };
+//!
+//! // In the process that creates the memory:
+//! let shm = AnonymousSharedMemory::create(4096)?;
+//! let mapping = shm.map()?;
+//! unsafe {
+//!     // SAFETY: nobody else has the memory yet
+//!     mapping.as_mut_slice()[..6].copy_from_slice(b"Hello!");
+//! }
+//! drop(mapping);
+//! // Writable mappings have to be gone before writes can be sealed.
+//! shm.add_seals(Seals::SHRINK | Seals::GROW | Seals::WRITE | Seals::SEAL)?;
+//! let conn = LocalSocketStream::connect("/tmp/example.sock")?;
+//! shm.send_over_local_socket(&conn)?;
+//!
+//! // In the process that receives it:
+//! # let conn = LocalSocketStream::connect("/tmp/example.sock")?;
+//! let shm = AnonymousSharedMemory::recv_from_local_socket(&conn)?;
+//! assert!(shm.seals()?.contains(Seals::SHRINK | Seals::WRITE));
+//! let mapping = shm.map_read_only()?;
+//! // SAFETY: the memory is sealed against writes
+//! assert_eq!(unsafe { &mapping.as_slice()[..6] }, b"Hello!");
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{shared_memory::map_fd, udsocket::UdStream};
+use crate::{local_socket::LocalSocketStream, shared_memory::SharedMemoryMapping};
+use libc::c_int;
+use std::{
+    io,
+    ops::{BitOr, BitOrAssign},
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+};
+
+/// The byte sent along with the file descriptor, which lets the receiving side tell shared memory apart from other
+/// messages.
+const MARKER: u8 = b'M';
+
+/// An anonymous shared memory object created with `memfd_create`.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct AnonymousSharedMemory {
+    fd: OwnedFd,
+    len: usize,
+}
+impl AnonymousSharedMemory {
+    /// Creates a new anonymous shared memory object of the specified size in bytes which allows sealing.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the size is zero. The contents of the memory are
+    /// initialized with zeroes.
+    pub fn create(size: usize) -> io::Result<Self> {
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shared memory objects cannot be empty",
+            ));
+        }
+        let len = libc::off_t::try_from(size).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shared memory size does not fit into off_t",
+            )
+        })?;
+        // The name only shows up in /proc/<pid>/fd and /proc/<pid>/maps, and doesn't have to be unique.
+        let fd = unsafe {
+            libc::memfd_create(
+                b"interprocess\0".as_ptr().cast(),
+                libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING,
+            )
+        };
+        if fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let fd = unsafe {
+            // SAFETY: memfd_create() just returned a new descriptor
+            OwnedFd::from_raw_fd(fd)
+        };
+        let success = unsafe { libc::ftruncate(fd.as_raw_fd(), len) } != -1;
+        ok_or_ret_errno!(success => Self { fd, len: size })
+    }
+    /// Returns the size of the shared memory object in bytes, as it was when the object was created or received.
+    #[allow(clippy::len_without_is_empty)] // Shared memory objects are never empty.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns the seals which have been placed on the object so far.
+    pub fn seals(&self) -> io::Result<Seals> {
+        get_seals(self.fd.as_raw_fd())
+    }
+    /// Places the given seals on the object, in addition to the ones it already has. Seals can never be removed.
+    ///
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the object is sealed with [`Seals::SEAL`],
+    /// and with `EBUSY` if [`Seals::WRITE`] is added while the object has writable mappings, including ones made by
+    /// other processes.
+    pub fn add_seals(&self, seals: Seals) -> io::Result<()> {
+        let success = unsafe { libc::fcntl(self.fd.as_raw_fd(), libc::F_ADD_SEALS, seals.0) } != -1;
+        ok_or_ret_errno!(success => ())
+    }
+    /// Maps the whole shared memory object into the address space of the current process for reading and writing.
+    ///
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the object is sealed against writes.
+    pub fn map(&self) -> io::Result<SharedMemoryMapping> {
+        map_fd(self.fd.as_raw_fd(), self.len, false)
+            .map(|ptr| SharedMemoryMapping::from_raw_parts(ptr, self.len, false))
+    }
+    /// Maps the whole shared memory object into the address space of the current process for reading only.
+    pub fn map_read_only(&self) -> io::Result<SharedMemoryMapping> {
+        map_fd(self.fd.as_raw_fd(), self.len, true).map(|ptr| SharedMemoryMapping::from_raw_parts(ptr, self.len, true))
+    }
+
+    /// Sends the file descriptor of the object over a Unix domain socket stream, to be received with
+    /// [`.recv_from()`](Self::recv_from). The object stays usable in this process.
+    ///
+    /// One byte of data is sent together with the descriptor, which means that the peer must not be in the middle of
+    /// reading anything else from the stream.
+    pub fn send_over(&self, stream: &UdStream) -> io::Result<()> {
+        stream.send_fds(&[MARKER], &[self.fd.as_fd()]).map(drop)
+    }
+    /// Receives an object sent with [`.send_over()`](Self::send_over) from a Unix domain socket stream. The size of the
+    /// object is whatever it is at the time it's received.
+    ///
+    /// Fails with [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the peer has closed the connection, and with
+    /// [`InvalidData`](io::ErrorKind::InvalidData) if what was received is not an anonymous shared memory object.
+    pub fn recv_from(stream: &UdStream) -> io::Result<Self> {
+        let mut buf = [0];
+        let (received, fds) = stream.recv_fds(&mut buf)?;
+        Self::from_received(received, buf[0], fds.into_inner())
+    }
+    /// Sends the file descriptor of the object over a local socket stream, to be received with
+    /// [`.recv_from_local_socket()`](Self::recv_from_local_socket). Works the same as
+    /// [`.send_over()`](Self::send_over), and requires the stream to be in blocking mode.
+    pub fn send_over_local_socket(&self, stream: &LocalSocketStream) -> io::Result<()> {
+        stream.send_handles(&[MARKER], &[self.fd.as_fd()]).map(drop)
+    }
+    /// Receives an object sent with [`.send_over_local_socket()`](Self::send_over_local_socket) from a local socket
+    /// stream. Works the same as [`.recv_from()`](Self::recv_from), and requires the stream to be in blocking mode.
+    pub fn recv_from_local_socket(stream: &LocalSocketStream) -> io::Result<Self> {
+        let mut buf = [0];
+        let (received, fds) = stream.recv_handles(&mut buf)?;
+        Self::from_received(received, buf[0], fds)
+    }
+    fn from_received(received: usize, marker: u8, fds: Vec<OwnedFd>) -> io::Result<Self> {
+        if received == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the peer closed the connection instead of sending shared memory",
+            ));
+        }
+        let mut fds = fds.into_iter();
+        match (marker, fds.next(), fds.next()) {
+            (MARKER, Some(fd), None) => Self::try_from(fd),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the peer sent something other than shared memory",
+            )),
+        }
+    }
+}
+/// Takes ownership of a file descriptor received by other means, such as inheritance.
+///
+/// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the descriptor doesn't refer to a non-empty object which
+/// supports sealing, such as one made by `memfd_create`.
+impl TryFrom<OwnedFd> for AnonymousSharedMemory {
+    type Error = io::Error;
+    fn try_from(fd: OwnedFd) -> io::Result<Self> {
+        // Only memfd objects and other shared memory files support seals, so this is how they're told apart.
+        get_seals(fd.as_raw_fd()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file descriptor does not refer to anonymous shared memory",
+            )
+        })?;
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        let success = unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != -1;
+        if !success {
+            return Err(io::Error::last_os_error());
+        }
+        match usize::try_from(stat.st_size) {
+            Ok(0) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the shared memory object is empty",
+            )),
+            Ok(len) => Ok(Self { fd, len }),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "shared memory object is too big to be mapped",
+            )),
+        }
+    }
+}
+impl From<AnonymousSharedMemory> for OwnedFd {
+    fn from(shm: AnonymousSharedMemory) -> Self {
+        shm.fd
+    }
+}
+impl AsFd for AnonymousSharedMemory {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+impl AsRawFd for AnonymousSharedMemory {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+fn get_seals(fd: RawFd) -> io::Result<Seals> {
+    let seals = unsafe { libc::fcntl(fd, libc::F_GET_SEALS) };
+    ok_or_ret_errno!(seals != -1 => Seals(seals))
+}
+
+/// A set of seals, which restrict what can be done with an [`AnonymousSharedMemory`] object by anyone who has it.
+///
+/// Sets are combined with the `|` operator.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Seals(c_int);
+impl Seals {
+    /// Prevents further seals from being added.
+    pub const SEAL: Self = Self(libc::F_SEAL_SEAL);
+    /// Prevents the object from being made smaller, which makes it safe to map for anyone who checked its size.
+    pub const SHRINK: Self = Self(libc::F_SEAL_SHRINK);
+    /// Prevents the object from being made larger.
+    pub const GROW: Self = Self(libc::F_SEAL_GROW);
+    /// Prevents the contents of the object from being modified by anyone, including through existing mappings, which
+    /// is why it cannot be added while writable mappings exist.
+    pub const WRITE: Self = Self(libc::F_SEAL_WRITE);
+    /// Prevents new writable mappings and writes through the file descriptor, while existing writable mappings stay
+    /// usable. This is how the creator of an object can keep writing to it while everyone else only gets to read it.
+    /// Requires Linux 5.1.
+    pub const FUTURE_WRITE: Self = Self(libc::F_SEAL_FUTURE_WRITE);
+
+    /// Returns an empty set of seals.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+    /// Returns `true` if the set has no seals in it.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Returns `true` if all seals in `other` are also in this set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for Seals {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOrAssign for Seals {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
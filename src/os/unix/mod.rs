@@ -13,10 +13,8 @@
 pub(crate) mod imports;
 
 mod fdops;
-// pub(self) is just a fancy way of saying priv (i.e. no access modifier), but
-// we want to make it clear that we're exporting to child modules here rather
-// than importing for use within this module.
-pub(self) use fdops::*;
+// Exported to child modules rather than imported for use within this module.
+use fdops::*;
 
 pub mod fifo_file;
 
@@ -24,9 +22,23 @@ pub mod fifo_file;
 pub mod udsocket;
 
 pub(crate) mod local_socket;
-pub(crate) mod unnamed_pipe;
+pub use local_socket::LocalSocketListenerOptionsExt;
+#[cfg(target_os = "linux")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "linux")))]
+pub mod memfd;
+pub(crate) mod platform;
+pub(crate) mod select;
+#[cfg(not(target_os = "android"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(not(target_os = "android"))))]
+pub mod shared_memory;
+pub(crate) mod signal;
+pub(crate) mod spawn;
+#[cfg(not(target_os = "android"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(not(target_os = "android"))))]
+pub mod sync;
+pub mod unnamed_pipe;
 
 mod unixprelude {
-    pub use libc::{c_int, gid_t, mode_t, pid_t, size_t, uid_t};
+    pub use libc::{c_int, mode_t, size_t};
     pub use std::os::unix::prelude::*;
 }
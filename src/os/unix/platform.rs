@@ -0,0 +1,94 @@
+//! Unix implementation of capability probing.
+
+use crate::{local_socket::NameTypeSupport, platform::Capabilities};
+use libc::c_int;
+use std::{
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub(crate) fn probe() -> Capabilities {
+    Capabilities {
+        name_types: NameTypeSupport::query(),
+        abstract_namespace: cfg!(uds_linux_namespace),
+        unix_domain_sockets: cfg!(uds_supported),
+        seqpacket: cfg!(uds_supported) && socket_type_supported(libc::SOCK_SEQPACKET),
+        ancillary_credentials: cfg!(uds_scm_credentials),
+        handle_passing: cfg!(uds_scm_rights),
+        shared_memory: shared_memory_supported(),
+        message_queues: message_queues_supported(),
+    }
+}
+
+/// Generates a name for a probe object which no other probe uses at the same time.
+fn probe_name() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("/interprocess-probe-{}-{n}", process::id())
+}
+
+fn socket_type_supported(ty: c_int) -> bool {
+    let fd = unsafe { libc::socket(libc::AF_UNIX, ty, 0) };
+    if fd == -1 {
+        return false;
+    }
+    unsafe { libc::close(fd) };
+    true
+}
+
+#[cfg(not(target_os = "android"))]
+fn shared_memory_supported() -> bool {
+    use crate::{os::unix::shared_memory::SharedMemoryExt, shared_memory::SharedMemory};
+    match SharedMemory::create(probe_name(), 1) {
+        Ok(shm) => {
+            let _ = shm.unlink();
+            true
+        }
+        Err(e) => e.kind() == std::io::ErrorKind::AlreadyExists,
+    }
+}
+#[cfg(target_os = "android")]
+fn shared_memory_supported() -> bool {
+    false
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+))]
+fn message_queues_supported() -> bool {
+    use std::{ffi::CString, io, ptr};
+    let name = CString::new(probe_name()).expect("probe names contain no nul bytes");
+    let mqd = unsafe {
+        libc::mq_open(
+            name.as_ptr(),
+            libc::O_CREAT | libc::O_EXCL | libc::O_RDWR,
+            0o600 as libc::c_uint,
+            ptr::null_mut::<libc::mq_attr>(),
+        )
+    };
+    // mqd_t is an integer on some platforms and a pointer on others, but always -1 on failure.
+    if mqd as isize == -1 {
+        return io::Error::last_os_error().kind() == io::ErrorKind::AlreadyExists;
+    }
+    unsafe {
+        libc::mq_close(mqd);
+        libc::mq_unlink(name.as_ptr());
+    }
+    true
+}
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+)))]
+fn message_queues_supported() -> bool {
+    false
+}
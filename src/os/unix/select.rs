@@ -0,0 +1,82 @@
+//! Unix implementation of [`Select`](crate::select::Select): every listener and stream in the set is waited for with a
+//! single `poll` call.
+
+use {
+    crate::select::Entry,
+    libc::{c_int, c_short},
+    std::{
+        io,
+        os::unix::io::{AsRawFd, RawFd},
+        time::{Duration, Instant},
+    },
+};
+
+fn pollfd(fd: RawFd, events: c_short) -> libc::pollfd {
+    libc::pollfd { fd, events, revents: 0 }
+}
+
+/// Waits for any of the listeners and streams in the set to become ready, adding their keys to `ready`, or until the
+/// timeout expires. Events are skipped, since they're checked by the caller.
+pub(crate) fn wait_sockets(entries: &[Entry<'_>], timeout: Option<Duration>, ready: &mut Vec<usize>) -> io::Result<()> {
+    // The key of the entry each file descriptor belongs to, since listeners take up two.
+    let mut keys = Vec::with_capacity(entries.len());
+    let mut pfds = Vec::with_capacity(entries.len());
+    for (key, entry) in entries.iter().enumerate() {
+        match entry {
+            Entry::Accept(listener) => {
+                if listener.is_closed() {
+                    ready.push(key);
+                    continue;
+                }
+                keys.extend([key, key]);
+                pfds.push(pollfd(listener.as_raw_fd(), libc::POLLIN));
+                pfds.push(pollfd(listener.close_signal_fd()?, libc::POLLIN));
+            }
+            Entry::Readable(stream) => {
+                keys.push(key);
+                pfds.push(pollfd(stream.as_raw_fd(), libc::POLLIN));
+            }
+            Entry::Writable(stream) => {
+                keys.push(key);
+                pfds.push(pollfd(stream.as_raw_fd(), libc::POLLOUT));
+            }
+            #[cfg(not(target_os = "android"))]
+            Entry::Event { .. } => {}
+        }
+    }
+    // Closed listeners are ready already, but the others are still checked so that they're reported together.
+    let timeout = if ready.is_empty() {
+        timeout
+    } else {
+        Some(Duration::ZERO)
+    };
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let millis = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                // Rounded up so that a sub-millisecond remainder doesn't turn into a busy loop.
+                let millis = (remaining.as_nanos() + 999_999) / 1_000_000;
+                millis.min(c_int::MAX as u128) as c_int
+            }
+            None => -1,
+        };
+        let result = unsafe { libc::poll(pfds.as_mut_ptr(), pfds.len() as _, millis) };
+        match result {
+            -1 => {
+                let e = io::Error::last_os_error();
+                if e.kind() != io::ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            0 if deadline.map_or(false, |deadline| Instant::now() >= deadline) => return Ok(()),
+            0 => {}
+            _ => {
+                // Errors and hangups count as readiness too, since the next operation reports them right away.
+                let ready_keys = keys.iter().zip(&pfds).filter(|(_, pfd)| pfd.revents != 0);
+                ready.extend(ready_keys.map(|(&key, _)| key));
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Unix-specific functionality for shared memory.
+//!
+//! POSIX shared memory objects persist until they are explicitly unlinked, which is done through the
+//! [`SharedMemoryExt`] trait or the [`unlink`] function, and the same goes for [ring buffer](crate::shared_memory::ring)
+//! channels, which are unlinked through the [`RingBufferExt`] trait or the [`unlink_ring_buffer`] function.
+
+use super::FdOps;
+use crate::{
+    shared_memory::{
+        ring::{self, RingReceiver, RingSender},
+        SharedMemory as PubSharedMemory,
+    },
+    Sealed,
+};
+use libc::{c_int, c_void};
+use std::{
+    ffi::{CString, OsStr},
+    fmt::{self, Debug, Formatter},
+    io,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    },
+    ptr,
+};
+
+/// Unix-specific extensions for [shared memory objects](PubSharedMemory).
+pub trait SharedMemoryExt: Sealed {
+    /// Removes the name of the shared memory object, so that it can no longer be opened and the memory is freed once
+    /// all processes which have it opened or mapped are done with it. A new object with the same name can be created
+    /// right away.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if the object has been unlinked already.
+    fn unlink(&self) -> io::Result<()>;
+}
+impl SharedMemoryExt for PubSharedMemory {
+    fn unlink(&self) -> io::Result<()> {
+        unlink_cstr(&self.inner.name)
+    }
+}
+
+/// Removes the name of the shared memory object with the specified name, with the same effect as
+/// [`SharedMemoryExt::unlink`]. Useful for cleaning up objects left over by processes that did not unlink them.
+pub fn unlink(name: impl AsRef<OsStr>) -> io::Result<()> {
+    unlink_cstr(&to_shm_name(name.as_ref())?)
+}
+
+/// Unix-specific extensions for both sides of [ring buffer](crate::shared_memory::ring) channels.
+pub trait RingBufferExt: Sealed {
+    /// Removes the names of the shared memory object and the events that make up the channel, so that it can no longer
+    /// be opened and is destroyed once both sides are dropped. A new channel with the same name can be created right
+    /// away.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if the channel has been unlinked already.
+    fn unlink(&self) -> io::Result<()>;
+}
+impl RingBufferExt for RingSender {
+    fn unlink(&self) -> io::Result<()> {
+        ring::unlink(&self.ring.name)
+    }
+}
+impl RingBufferExt for RingReceiver {
+    fn unlink(&self) -> io::Result<()> {
+        ring::unlink(&self.ring.name)
+    }
+}
+impl Sealed for RingSender {}
+impl Sealed for RingReceiver {}
+
+/// Removes the names of the objects that make up the ring buffer channel with the specified name, with the same effect
+/// as [`RingBufferExt::unlink`]. Useful for cleaning up channels left over by processes that did not unlink them.
+pub fn unlink_ring_buffer(name: impl AsRef<OsStr>) -> io::Result<()> {
+    ring::unlink(name.as_ref())
+}
+
+fn unlink_cstr(name: &CString) -> io::Result<()> {
+    let success = unsafe { libc::shm_unlink(name.as_ptr()) } != -1;
+    ok_or_ret_errno!(success => ())
+}
+
+/// Turns the name into the form required by `shm_open` and `sem_open`, prepending the leading slash if it's missing.
+pub(crate) fn to_shm_name(name: &OsStr) -> io::Result<CString> {
+    let name = name.as_bytes();
+    let mut buf = Vec::with_capacity(name.len() + 2);
+    if name.first() != Some(&b'/') {
+        buf.push(b'/');
+    }
+    buf.extend_from_slice(name);
+    CString::new(buf).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "object names cannot contain interior nul bytes",
+        )
+    })
+}
+
+fn shm_open(name: &CString, flags: c_int) -> io::Result<FdOps> {
+    // The close-on-exec flag is always set by shm_open(). The mode is passed through varargs on Apple platforms,
+    // where it gets promoted to an unsigned int.
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    let mode = 0o600 as libc::c_uint;
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    let mode = 0o600 as libc::mode_t;
+    let fd = unsafe { libc::shm_open(name.as_ptr(), flags, mode) };
+    ok_or_ret_errno!(fd != -1 => unsafe {
+        // SAFETY: shm_open() just created the descriptor
+        FdOps::from_raw_fd(fd)
+    })
+}
+
+pub(crate) struct SharedMemory {
+    fd: FdOps,
+    name: CString,
+    len: usize,
+    read_only: bool,
+}
+impl SharedMemory {
+    pub fn create(name: &OsStr, size: usize) -> io::Result<Self> {
+        let name = to_shm_name(name)?;
+        let fd = shm_open(&name, libc::O_CREAT | libc::O_EXCL | libc::O_RDWR)?;
+        let truncated = libc::off_t::try_from(size)
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "shared memory size does not fit into off_t",
+                )
+            })
+            .and_then(|size| {
+                let success = unsafe { libc::ftruncate(fd.as_raw_fd(), size) } != -1;
+                ok_or_ret_errno!(success => ())
+            });
+        if let Err(e) = truncated {
+            // Don't leave behind an object of the wrong size for others to open.
+            let _ = unlink_cstr(&name);
+            return Err(e);
+        }
+        Ok(Self {
+            fd,
+            name,
+            len: size,
+            read_only: false,
+        })
+    }
+    pub fn open(name: &OsStr, read_only: bool) -> io::Result<Self> {
+        let name = to_shm_name(name)?;
+        let fd = shm_open(&name, if read_only { libc::O_RDONLY } else { libc::O_RDWR })?;
+        let mut stat = unsafe { std::mem::zeroed::<libc::stat>() };
+        let success = unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } != -1;
+        if !success {
+            return Err(io::Error::last_os_error());
+        }
+        let len = usize::try_from(stat.st_size).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "shared memory object is too big to be mapped",
+            )
+        })?;
+        Ok(Self {
+            fd,
+            name,
+            len,
+            read_only,
+        })
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    pub fn map(&self, read_only: bool) -> io::Result<*mut u8> {
+        if self.len == 0 {
+            // mmap() refuses empty mappings, which is what a freshly created object looks like to others before its
+            // creator gets to set the size.
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the shared memory object has no size yet",
+            ));
+        }
+        map_fd(self.fd.as_raw_fd(), self.len, read_only)
+    }
+}
+impl Debug for SharedMemory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedMemory")
+            .field("fd", &self.fd.as_raw_fd())
+            .field("name", &self.name)
+            .field("len", &self.len)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+/// Maps the first `len` bytes of the file behind the descriptor as shared memory.
+pub(crate) fn map_fd(fd: RawFd, len: usize, read_only: bool) -> io::Result<*mut u8> {
+    let prot = if read_only {
+        libc::PROT_READ
+    } else {
+        libc::PROT_READ | libc::PROT_WRITE
+    };
+    let ptr = unsafe { libc::mmap(ptr::null_mut(), len, prot, libc::MAP_SHARED, fd, 0) };
+    ok_or_ret_errno!(ptr != libc::MAP_FAILED => ptr as *mut u8)
+}
+
+pub(crate) fn unmap(ptr: *mut u8, len: usize) {
+    unsafe {
+        libc::munmap(ptr as *mut c_void, len);
+    }
+}
+
+impl AsRawFd for PubSharedMemory {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.fd.as_raw_fd()
+    }
+}
+impl AsFd for PubSharedMemory {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.fd.as_fd()
+    }
+}
+impl From<PubSharedMemory> for OwnedFd {
+    fn from(shm: PubSharedMemory) -> Self {
+        shm.inner.fd.into()
+    }
+}
@@ -0,0 +1,146 @@
+//! Unix implementation of signals: a `sigaction()` handler writes the signals it catches into a pipe, which is read by
+//! a background thread that dispatches them to the receivers outside of the signal handler.
+
+use super::set_nonblocking;
+use crate::{
+    signal::{dispatch, Signal},
+    unnamed_pipe::pipe,
+};
+use libc::{c_int, c_void, pid_t};
+use std::{
+    io::{self, Read},
+    mem::zeroed,
+    os::unix::io::IntoRawFd,
+    ptr,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Mutex, PoisonError,
+    },
+    thread,
+};
+
+/// The writing end of the pipe to the dispatcher thread, or -1 if it hasn't been started yet.
+static PIPE_WRITER: AtomicI32 = AtomicI32::new(-1);
+/// Bitmask of the signals for which the handler has been installed.
+static INSTALLED: Mutex<u8> = Mutex::new(0);
+
+fn signal_number(signal: Signal) -> c_int {
+    match signal {
+        Signal::Interrupt => libc::SIGINT,
+        Signal::Terminate => libc::SIGTERM,
+        Signal::User1 => libc::SIGUSR1,
+    }
+}
+
+pub(crate) fn send(pid: u32, signal: Signal) -> io::Result<()> {
+    let pid = pid_t::try_from(pid)
+        .ok()
+        .filter(|&pid| pid > 0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid process ID"))?;
+    if unsafe { libc::kill(pid, signal_number(signal)) } != -1 {
+        return Ok(());
+    }
+    let e = io::Error::last_os_error();
+    Err(if e.raw_os_error() == Some(libc::ESRCH) {
+        io::Error::new(io::ErrorKind::NotFound, "no process with the specified ID exists")
+    } else {
+        e
+    })
+}
+
+pub(crate) fn install(signal: Signal) -> io::Result<()> {
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    if *installed & (1 << signal.index()) != 0 {
+        return Ok(());
+    }
+    if PIPE_WRITER.load(Ordering::Acquire) == -1 {
+        start_dispatcher()?;
+    }
+
+    let mut action = unsafe { zeroed::<libc::sigaction>() };
+    action.sa_sigaction = handler as extern "C" fn(c_int) as libc::sighandler_t;
+    // Restarting interrupted system calls keeps the signal from surfacing as an error in unrelated code.
+    action.sa_flags = libc::SA_RESTART;
+    unsafe { libc::sigemptyset(&mut action.sa_mask) };
+    let success = unsafe { libc::sigaction(signal_number(signal), &action, ptr::null_mut()) } != -1;
+    if !success {
+        return Err(io::Error::last_os_error());
+    }
+    *installed |= 1 << signal.index();
+    Ok(())
+}
+
+fn start_dispatcher() -> io::Result<()> {
+    let (writer, mut reader) = pipe()?;
+    // The handler must never block, even if the dispatcher thread falls behind.
+    let writer = writer.into_raw_fd();
+    if let Err(e) = set_nonblocking(writer, true) {
+        unsafe { libc::close(writer) };
+        return Err(e);
+    }
+    let spawned = thread::Builder::new()
+        .name("interprocess signal dispatcher".to_owned())
+        .spawn(move || {
+            let mut buf = [0; 64];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        for signal in buf[..n].iter().filter_map(|&index| Signal::from_index(index)) {
+                            dispatch(signal);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        unsafe { libc::close(writer) };
+        return Err(e);
+    }
+    // The writing end is never closed from now on, so the handler can't write into a reused descriptor.
+    PIPE_WRITER.store(writer, Ordering::Release);
+    Ok(())
+}
+
+extern "C" fn handler(signum: c_int) {
+    let Some(signal) = Signal::ALL.into_iter().find(|&s| signal_number(s) == signum) else {
+        return;
+    };
+    let fd = PIPE_WRITER.load(Ordering::Acquire);
+    if fd == -1 {
+        return;
+    }
+    let errno = errno::get();
+    let index = signal.index();
+    // If the pipe is full, the signal is dropped, like a signal that arrives while the same one is already pending.
+    unsafe { libc::write(fd, &index as *const u8 as *const c_void, 1) };
+    errno::set(errno);
+}
+
+/// Saving and restoring `errno`, which the signal handler must leave untouched for the code it interrupted.
+mod errno {
+    use libc::c_int;
+
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "emscripten", target_os = "redox", target_os = "dragonfly"))] {
+            use libc::__errno_location as location;
+        } else if #[cfg(any(target_os = "android", target_os = "netbsd", target_os = "openbsd"))] {
+            use libc::__errno as location;
+        } else if #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))] {
+            use libc::__error as location;
+        } else if #[cfg(any(target_os = "solaris", target_os = "illumos"))] {
+            use libc::___errno as location;
+        } else if #[cfg(target_os = "haiku")] {
+            use libc::_errnop as location;
+        }
+    }
+
+    pub fn get() -> c_int {
+        unsafe { *location() }
+    }
+    pub fn set(errno: c_int) {
+        unsafe { *location() = errno };
+    }
+}
@@ -0,0 +1,59 @@
+//! Unix implementation of endpoint inheritance for the spawn helper: the close-on-exec flag is cleared on the child's
+//! ends in the forked process, right before the program is executed, so that the parent never has them inheritable.
+
+use crate::local_socket::{BorrowedTransferable, OwnedTransferable};
+use libc::{c_int, FD_CLOEXEC, F_GETFD, F_SETFD};
+use std::{
+    io,
+    os::unix::{
+        io::{AsRawFd, FromRawFd},
+        process::CommandExt,
+    },
+    process::Command,
+};
+
+fn set_cloexec(fd: c_int, cloexec: bool) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, F_GETFD) };
+    if flags == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let flags = if cloexec {
+        flags | FD_CLOEXEC
+    } else {
+        flags & !FD_CLOEXEC
+    };
+    let success = unsafe { libc::fcntl(fd, F_SETFD, flags) } != -1;
+    ok_or_ret_errno!(success => ())
+}
+
+/// Arranges for the given resources to be inherited by the process spawned by the command. They must stay open until
+/// it's spawned.
+pub(crate) fn inherit(command: &mut Command, resources: &[BorrowedTransferable<'_>]) -> io::Result<()> {
+    let fds = resources.iter().map(AsRawFd::as_raw_fd).collect::<Vec<_>>();
+    unsafe {
+        // SAFETY: fcntl() is async-signal-safe, and the closure neither allocates nor takes locks.
+        command.pre_exec(move || {
+            for &fd in &fds {
+                set_cloexec(fd, false)?;
+            }
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+/// The value by which the child process refers to an inherited resource.
+pub(crate) fn raw_value(resource: BorrowedTransferable<'_>) -> u64 {
+    resource.as_raw_fd() as u64
+}
+
+/// Takes ownership of an inherited resource, making sure that it's open and stops being inherited by further children.
+///
+/// # Safety
+/// The resource must have been inherited from the parent process and not be owned by anything else in this process.
+pub(crate) unsafe fn take_inherited(value: u64) -> io::Result<OwnedTransferable> {
+    let fd = c_int::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid inherited file descriptor"))?;
+    set_cloexec(fd, true)?;
+    Ok(unsafe { OwnedTransferable::from_raw_fd(fd) })
+}
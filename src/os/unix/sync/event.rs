@@ -0,0 +1,130 @@
+use crate::{
+    sync::{event::NamedEventImpl, NamedEvent as PubNamedEvent},
+    Sealed,
+};
+use std::{ffi::OsStr, io};
+
+#[cfg(target_os = "linux")]
+pub(crate) use futex::NamedEvent;
+
+/// Unix-specific extensions for [named events](PubNamedEvent).
+pub trait NamedEventExt: Sealed {
+    /// Removes the name of the event, so that it can no longer be opened and is destroyed once all processes which have
+    /// it opened close it. A new event with the same name can be created right away.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if the event has been unlinked already.
+    fn unlink(&self) -> io::Result<()>;
+}
+impl NamedEventExt for PubNamedEvent {
+    fn unlink(&self) -> io::Result<()> {
+        NamedEventImpl::unlink(&self.inner.name)
+    }
+}
+
+/// Removes the name of the event with the specified name, with the same effect as [`NamedEventExt::unlink`]. Useful
+/// for cleaning up events left over by processes that did not unlink them.
+pub fn unlink_event(name: impl AsRef<OsStr>) -> io::Result<()> {
+    NamedEventImpl::unlink(name.as_ref())
+}
+
+/// A futex in shared memory, holding the number of notifications sent so far.
+#[cfg(target_os = "linux")]
+mod futex {
+    use crate::{
+        shared_memory::{SharedMemory, SharedMemoryMapping},
+        sync::open_when_ready,
+    };
+    use libc::{c_long, timespec};
+    use std::{
+        ffi::{OsStr, OsString},
+        fmt::{self, Debug, Formatter},
+        io,
+        mem::{size_of, zeroed},
+        ptr,
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    pub(crate) struct NamedEvent {
+        mapping: SharedMemoryMapping,
+        pub(crate) name: OsString,
+    }
+    impl NamedEvent {
+        pub fn create(name: &OsStr) -> io::Result<Self> {
+            // A zero futex is a valid initial state, so there's nothing to initialize.
+            let shm = SharedMemory::create(name, size_of::<AtomicU32>())?;
+            let mapping = shm.map().map_err(|e| {
+                let _ = Self::unlink(name);
+                e
+            })?;
+            Ok(Self {
+                mapping,
+                name: name.to_owned(),
+            })
+        }
+        pub fn open(name: &OsStr) -> io::Result<Self> {
+            open_when_ready(|| {
+                // The creator might not have gotten to setting the size of the shared memory yet.
+                let shm = SharedMemory::open(name)?;
+                if shm.len() < size_of::<AtomicU32>() {
+                    return Ok(None);
+                }
+                Ok(Some(Self {
+                    mapping: shm.map()?,
+                    name: name.to_owned(),
+                }))
+            })
+        }
+        fn futex(&self) -> &AtomicU32 {
+            unsafe {
+                // SAFETY: the mapping is big enough and page-aligned, and the futex is only accessed atomically
+                &*(self.mapping.as_ptr() as *const AtomicU32)
+            }
+        }
+        /// Performs a non-private futex operation, which is required for the futex to work across processes.
+        fn futex_op(&self, op: i32, val: u32, timeout: *const timespec) -> c_long {
+            unsafe { libc::syscall(libc::SYS_futex, self.futex() as *const AtomicU32, op, val, timeout) }
+        }
+        pub fn seq(&self) -> u32 {
+            self.futex().load(Ordering::SeqCst)
+        }
+        pub fn wait_for_change(&self, seq: u32, timeout: Option<Duration>) -> io::Result<bool> {
+            let timeout = timeout.map(|timeout| {
+                // Some platforms have padding fields in timespec, so it can't be constructed with a struct literal.
+                let mut ts = unsafe { zeroed::<timespec>() };
+                ts.tv_sec = timeout.as_secs().min(libc::time_t::MAX as u64) as libc::time_t;
+                ts.tv_nsec = timeout.subsec_nanos() as _;
+                ts
+            });
+            let timeout_ptr = timeout.as_ref().map_or(ptr::null(), |ts| ts as *const _);
+            if self.futex_op(libc::FUTEX_WAIT, seq, timeout_ptr) != -1 {
+                return Ok(true);
+            }
+            let e = io::Error::last_os_error();
+            match e.raw_os_error() {
+                // EAGAIN means that the event was notified before the wait began. Interruptions by signals are
+                // reported as spurious wakeups.
+                Some(libc::EAGAIN) | Some(libc::EINTR) => Ok(true),
+                Some(libc::ETIMEDOUT) => Ok(false),
+                _ => Err(e),
+            }
+        }
+        pub fn notify(&self, all: bool) -> io::Result<()> {
+            self.futex().fetch_add(1, Ordering::SeqCst);
+            let count = if all { i32::MAX as u32 } else { 1 };
+            let success = self.futex_op(libc::FUTEX_WAKE, count, ptr::null()) != -1;
+            ok_or_ret_errno!(success => ())
+        }
+        pub fn unlink(name: &OsStr) -> io::Result<()> {
+            crate::os::unix::shared_memory::unlink(name)
+        }
+    }
+    impl Debug for NamedEvent {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NamedEvent")
+                .field("mapping", &self.mapping)
+                .field("name", &self.name)
+                .finish()
+        }
+    }
+}
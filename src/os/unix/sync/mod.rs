@@ -0,0 +1,36 @@
+//! Unix-specific functionality for named synchronization primitives.
+//!
+//! Like shared memory objects, the named primitives of the [`sync`](crate::sync) module persist until they are
+//! explicitly unlinked, which is done through the extension traits of this module or the `unlink_*` functions.
+
+pub(crate) mod event;
+pub(crate) mod mutex;
+pub(crate) mod semaphore;
+pub use {
+    event::{unlink_event, NamedEventExt},
+    mutex::{unlink_mutex, NamedMutexExt},
+    semaphore::{unlink_semaphore, NamedSemaphoreExt},
+};
+
+use libc::timespec;
+use std::{io, mem::zeroed, time::Duration};
+
+/// Computes the absolute `CLOCK_REALTIME` deadline which lies the given amount of time in the future, for use with the
+/// timed waiting functions of POSIX.
+#[cfg_attr(any(target_os = "macos", target_os = "ios"), allow(dead_code))]
+fn realtime_deadline(timeout: Duration) -> io::Result<timespec> {
+    let mut now = unsafe { zeroed::<timespec>() };
+    let success = unsafe { libc::clock_gettime(libc::CLOCK_REALTIME, &mut now) } != -1;
+    if !success {
+        return Err(io::Error::last_os_error());
+    }
+    let nanos = now.tv_nsec as u64 + u64::from(timeout.subsec_nanos());
+    let secs = (now.tv_sec as u64)
+        .saturating_add(timeout.as_secs())
+        .saturating_add(nanos / 1_000_000_000);
+    // Some platforms have padding fields in timespec, so it can't be constructed with a struct literal.
+    let mut deadline = unsafe { zeroed::<timespec>() };
+    deadline.tv_sec = secs.min(libc::time_t::MAX as u64) as libc::time_t;
+    deadline.tv_nsec = (nanos % 1_000_000_000) as _;
+    Ok(deadline)
+}
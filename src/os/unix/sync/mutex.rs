@@ -0,0 +1,314 @@
+use crate::{sync::NamedMutex as PubNamedMutex, Sealed};
+use std::{ffi::OsStr, io};
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) use file_lock::{unlink, NamedMutex, NamedMutexGuard};
+#[cfg(target_os = "linux")]
+pub(crate) use robust::{unlink, NamedMutex, NamedMutexGuard};
+
+/// Unix-specific extensions for [named mutexes](PubNamedMutex).
+pub trait NamedMutexExt: Sealed {
+    /// Removes the name of the mutex, so that it can no longer be opened and is destroyed once all processes which have
+    /// it opened close it. A new mutex with the same name can be created right away.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if the mutex has been unlinked already.
+    fn unlink(&self) -> io::Result<()>;
+}
+impl NamedMutexExt for PubNamedMutex {
+    fn unlink(&self) -> io::Result<()> {
+        unlink(&self.inner.name)
+    }
+}
+
+/// Removes the name of the mutex with the specified name, with the same effect as [`NamedMutexExt::unlink`]. Useful
+/// for cleaning up mutexes left over by processes that did not unlink them.
+pub fn unlink_mutex(name: impl AsRef<OsStr>) -> io::Result<()> {
+    unlink(name.as_ref())
+}
+
+/// A robust process-shared pthread mutex in shared memory, which the system unlocks if its owner dies.
+#[cfg(target_os = "linux")]
+mod robust {
+    use crate::{
+        shared_memory::{SharedMemory, SharedMemoryMapping},
+        sync::open_when_ready,
+    };
+    use libc::pthread_mutex_t;
+    use std::{
+        ffi::{OsStr, OsString},
+        fmt::{self, Debug, Formatter},
+        io,
+        mem::{size_of, MaybeUninit},
+        ptr::{addr_of, addr_of_mut},
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    /// The layout of the shared memory object.
+    #[repr(C)]
+    struct SharedState {
+        /// Set to a nonzero value by the creator once the mutex is initialized.
+        initialized: AtomicU32,
+        mutex: pthread_mutex_t,
+    }
+
+    pub(crate) struct NamedMutex {
+        mapping: SharedMemoryMapping,
+        pub(super) name: OsString,
+    }
+    impl NamedMutex {
+        pub fn create(name: &OsStr) -> io::Result<Self> {
+            let shm = SharedMemory::create(name, size_of::<SharedState>())?;
+            let slf = shm.map().map(|mapping| Self {
+                mapping,
+                name: name.to_owned(),
+            });
+            let slf = slf.and_then(|slf| {
+                slf.init_mutex()?;
+                Ok(slf)
+            });
+            if slf.is_err() {
+                // Don't leave behind a mutex that can never be locked.
+                let _ = unlink(name);
+            }
+            slf
+        }
+        fn init_mutex(&self) -> io::Result<()> {
+            let mut attr = MaybeUninit::<libc::pthread_mutexattr_t>::uninit();
+            let result = unsafe { libc::pthread_mutexattr_init(attr.as_mut_ptr()) };
+            if result != 0 {
+                return Err(io::Error::from_raw_os_error(result));
+            }
+            let result = unsafe {
+                let attr = attr.as_mut_ptr();
+                let mut result = libc::pthread_mutexattr_setpshared(attr, libc::PTHREAD_PROCESS_SHARED);
+                if result == 0 {
+                    result = libc::pthread_mutexattr_setrobust(attr, libc::PTHREAD_MUTEX_ROBUST);
+                }
+                if result == 0 {
+                    result = libc::pthread_mutexattr_settype(attr, libc::PTHREAD_MUTEX_ERRORCHECK);
+                }
+                if result == 0 {
+                    result = libc::pthread_mutex_init(self.mutex(), attr);
+                }
+                libc::pthread_mutexattr_destroy(attr);
+                result
+            };
+            if result != 0 {
+                return Err(io::Error::from_raw_os_error(result));
+            }
+            self.initialized().store(1, Ordering::Release);
+            Ok(())
+        }
+        pub fn open(name: &OsStr) -> io::Result<Self> {
+            open_when_ready(|| {
+                // The creator might not have gotten to setting the size of the shared memory or initializing the
+                // mutex yet.
+                let shm = SharedMemory::open(name)?;
+                if shm.len() < size_of::<SharedState>() {
+                    return Ok(None);
+                }
+                let slf = Self {
+                    mapping: shm.map()?,
+                    name: name.to_owned(),
+                };
+                Ok((slf.initialized().load(Ordering::Acquire) != 0).then_some(slf))
+            })
+        }
+        fn state(&self) -> *mut SharedState {
+            // The mapping is big enough and page-aligned.
+            self.mapping.as_mut_ptr() as *mut SharedState
+        }
+        fn initialized(&self) -> &AtomicU32 {
+            // SAFETY: the flag is only ever accessed atomically
+            unsafe { &*addr_of!((*self.state()).initialized) }
+        }
+        fn mutex(&self) -> *mut pthread_mutex_t {
+            // No references to the mutex are ever made, since it's modified by other processes.
+            unsafe { addr_of_mut!((*self.state()).mutex) }
+        }
+        /// Turns the result of a locking function into whether the previous owner died.
+        fn lock_result(&self, result: i32) -> io::Result<(NamedMutexGuard<'_>, bool)> {
+            match result {
+                0 => Ok((NamedMutexGuard(self), false)),
+                libc::EOWNERDEAD => {
+                    // The mutex is held at this point, but has to be marked as consistent so as to not become
+                    // unusable once unlocked.
+                    let guard = NamedMutexGuard(self);
+                    let result = unsafe { libc::pthread_mutex_consistent(self.mutex()) };
+                    if result != 0 {
+                        return Err(io::Error::from_raw_os_error(result));
+                    }
+                    Ok((guard, true))
+                }
+                e => Err(io::Error::from_raw_os_error(e)),
+            }
+        }
+        pub fn lock(&self) -> io::Result<(NamedMutexGuard<'_>, bool)> {
+            let result = unsafe { libc::pthread_mutex_lock(self.mutex()) };
+            self.lock_result(result)
+        }
+        pub fn try_lock(&self) -> io::Result<Option<(NamedMutexGuard<'_>, bool)>> {
+            match unsafe { libc::pthread_mutex_trylock(self.mutex()) } {
+                libc::EBUSY => Ok(None),
+                result => self.lock_result(result).map(Some),
+            }
+        }
+    }
+    impl Debug for NamedMutex {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NamedMutex")
+                .field("mutex", &self.mutex())
+                .field("name", &self.name)
+                .finish()
+        }
+    }
+
+    pub(crate) struct NamedMutexGuard<'a>(&'a NamedMutex);
+    impl Debug for NamedMutexGuard<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("NamedMutexGuard").field(&self.0.mutex()).finish()
+        }
+    }
+    impl Drop for NamedMutexGuard<'_> {
+        fn drop(&mut self) {
+            unsafe { libc::pthread_mutex_unlock(self.0.mutex()) };
+        }
+    }
+
+    pub(crate) fn unlink(name: &OsStr) -> io::Result<()> {
+        crate::os::unix::shared_memory::unlink(name)
+    }
+}
+
+/// An advisory lock on a file, with a byte in the file recording whether the lock is held so that the death of the
+/// owner can be detected.
+#[cfg(not(target_os = "linux"))]
+mod file_lock {
+    use super::super::super::FdOps;
+    use libc::c_int;
+    use std::{
+        ffi::{CString, OsStr, OsString},
+        fmt::{self, Debug, Formatter},
+        io,
+        os::unix::{
+            ffi::OsStrExt,
+            io::{AsRawFd, FromRawFd},
+        },
+    };
+
+    /// Builds the path to the lock file of the mutex with the specified name.
+    fn lock_file_path(name: &OsStr) -> io::Result<CString> {
+        let name = name.as_bytes();
+        let name = name.strip_prefix(b"/").unwrap_or(name);
+        let mut path = b"/tmp/".to_vec();
+        path.extend_from_slice(name);
+        path.extend_from_slice(b".interprocess-mutex");
+        CString::new(path).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "object names cannot contain interior nul bytes",
+            )
+        })
+    }
+    fn open_file(path: &CString, flags: c_int) -> io::Result<FdOps> {
+        let fd = unsafe {
+            libc::open(
+                path.as_ptr(),
+                flags | libc::O_RDWR | libc::O_CLOEXEC,
+                0o600 as libc::c_uint,
+            )
+        };
+        ok_or_ret_errno!(fd != -1 => unsafe {
+            // SAFETY: open() just created the descriptor
+            FdOps::from_raw_fd(fd)
+        })
+    }
+
+    pub(crate) struct NamedMutex {
+        path: CString,
+        pub(super) name: OsString,
+    }
+    impl NamedMutex {
+        pub fn create(name: &OsStr) -> io::Result<Self> {
+            let path = lock_file_path(name)?;
+            open_file(&path, libc::O_CREAT | libc::O_EXCL)?;
+            Ok(Self {
+                path,
+                name: name.to_owned(),
+            })
+        }
+        pub fn open(name: &OsStr) -> io::Result<Self> {
+            let path = lock_file_path(name)?;
+            open_file(&path, 0)?;
+            Ok(Self {
+                path,
+                name: name.to_owned(),
+            })
+        }
+        /// Locks the file through a new open file description, which makes `flock()` exclude other threads of the
+        /// same process as well.
+        fn lock_impl(&self, nonblocking: bool) -> io::Result<Option<(NamedMutexGuard<'_>, bool)>> {
+            let fd = open_file(&self.path, 0)?;
+            let op = if nonblocking {
+                libc::LOCK_EX | libc::LOCK_NB
+            } else {
+                libc::LOCK_EX
+            };
+            loop {
+                if unsafe { libc::flock(fd.as_raw_fd(), op) } != -1 {
+                    break;
+                }
+                let e = io::Error::last_os_error();
+                match e.raw_os_error() {
+                    Some(libc::EINTR) => continue,
+                    Some(libc::EWOULDBLOCK) if nonblocking => return Ok(None),
+                    _ => return Err(e),
+                }
+            }
+            let mut held = [0];
+            let abandoned = unsafe { libc::pread(fd.as_raw_fd(), held.as_mut_ptr().cast(), 1, 0) } == 1 && held[0] != 0;
+            let success = unsafe { libc::pwrite(fd.as_raw_fd(), [1u8].as_ptr().cast(), 1, 0) } == 1;
+            if !success {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Some((NamedMutexGuard { fd, _mutex: self }, abandoned)))
+        }
+        pub fn lock(&self) -> io::Result<(NamedMutexGuard<'_>, bool)> {
+            self.lock_impl(false)
+                .map(|g| g.expect("blocking lock returned without locking"))
+        }
+        pub fn try_lock(&self) -> io::Result<Option<(NamedMutexGuard<'_>, bool)>> {
+            self.lock_impl(true)
+        }
+    }
+    impl Debug for NamedMutex {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("NamedMutex")
+                .field("path", &self.path)
+                .field("name", &self.name)
+                .finish()
+        }
+    }
+
+    pub(crate) struct NamedMutexGuard<'a> {
+        fd: FdOps,
+        _mutex: &'a NamedMutex,
+    }
+    impl Debug for NamedMutexGuard<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("NamedMutexGuard").field(&self.fd.as_raw_fd()).finish()
+        }
+    }
+    impl Drop for NamedMutexGuard<'_> {
+        fn drop(&mut self) {
+            // Closing the descriptor releases the lock afterwards.
+            unsafe { libc::pwrite(self.fd.as_raw_fd(), [0u8].as_ptr().cast(), 1, 0) };
+        }
+    }
+
+    pub(crate) fn unlink(name: &OsStr) -> io::Result<()> {
+        let path = lock_file_path(name)?;
+        let success = unsafe { libc::unlink(path.as_ptr()) } != -1;
+        ok_or_ret_errno!(success => ())
+    }
+}
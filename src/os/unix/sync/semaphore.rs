@@ -0,0 +1,127 @@
+use crate::{os::unix::shared_memory::to_shm_name, sync::NamedSemaphore as PubNamedSemaphore, Sealed};
+use libc::{c_int, c_uint, sem_t};
+use std::{
+    ffi::{CString, OsStr},
+    fmt::{self, Debug, Formatter},
+    io,
+    time::Duration,
+};
+
+/// Unix-specific extensions for [named semaphores](PubNamedSemaphore).
+pub trait NamedSemaphoreExt: Sealed {
+    /// Removes the name of the semaphore, so that it can no longer be opened and is destroyed once all processes which
+    /// have it opened close it. A new semaphore with the same name can be created right away.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if the semaphore has been unlinked already.
+    fn unlink(&self) -> io::Result<()>;
+}
+impl NamedSemaphoreExt for PubNamedSemaphore {
+    fn unlink(&self) -> io::Result<()> {
+        unlink_cstr(&self.inner.name)
+    }
+}
+
+/// Removes the name of the semaphore with the specified name, with the same effect as [`NamedSemaphoreExt::unlink`].
+/// Useful for cleaning up semaphores left over by processes that did not unlink them.
+pub fn unlink_semaphore(name: impl AsRef<OsStr>) -> io::Result<()> {
+    unlink_cstr(&to_shm_name(name.as_ref())?)
+}
+fn unlink_cstr(name: &CString) -> io::Result<()> {
+    let success = unsafe { libc::sem_unlink(name.as_ptr()) } != -1;
+    ok_or_ret_errno!(success => ())
+}
+
+pub(crate) struct NamedSemaphore {
+    sem: *mut sem_t,
+    name: CString,
+}
+// SAFETY: POSIX semaphores are meant to be used from multiple threads and processes at once
+unsafe impl Send for NamedSemaphore {}
+unsafe impl Sync for NamedSemaphore {}
+impl NamedSemaphore {
+    pub fn create(name: &OsStr, initial_count: u32) -> io::Result<Self> {
+        Self::sem_open(name, libc::O_CREAT | libc::O_EXCL, initial_count)
+    }
+    pub fn open(name: &OsStr) -> io::Result<Self> {
+        Self::sem_open(name, 0, 0)
+    }
+    fn sem_open(name: &OsStr, flags: c_int, initial_count: u32) -> io::Result<Self> {
+        let name = to_shm_name(name)?;
+        // Both the mode and the initial value are passed through varargs, and are thus promoted to unsigned ints.
+        let sem = unsafe { libc::sem_open(name.as_ptr(), flags, 0o600 as c_uint, initial_count as c_uint) };
+        ok_or_ret_errno!(sem != libc::SEM_FAILED => Self { sem, name })
+    }
+    pub fn post(&self) -> io::Result<()> {
+        let success = unsafe { libc::sem_post(self.sem) } != -1;
+        ok_or_ret_errno!(success => ())
+    }
+    pub fn wait(&self) -> io::Result<()> {
+        loop {
+            if unsafe { libc::sem_wait(self.sem) } != -1 {
+                return Ok(());
+            }
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::Interrupted {
+                return Err(e);
+            }
+        }
+    }
+    pub fn try_wait(&self) -> io::Result<bool> {
+        loop {
+            if unsafe { libc::sem_trywait(self.sem) } != -1 {
+                return Ok(true);
+            }
+            let e = io::Error::last_os_error();
+            match e.raw_os_error() {
+                Some(libc::EAGAIN) => return Ok(false),
+                Some(libc::EINTR) => continue,
+                _ => return Err(e),
+            }
+        }
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        let deadline = super::realtime_deadline(timeout)?;
+        loop {
+            if unsafe { libc::sem_timedwait(self.sem, &deadline) } != -1 {
+                return Ok(true);
+            }
+            let e = io::Error::last_os_error();
+            match e.raw_os_error() {
+                Some(libc::ETIMEDOUT) => return Ok(false),
+                Some(libc::EINTR) => continue,
+                _ => return Err(e),
+            }
+        }
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        use std::{thread, time::Instant};
+        let deadline = Instant::now() + timeout;
+        let mut backoff = Duration::from_micros(50);
+        loop {
+            if self.try_wait()? {
+                return Ok(true);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            thread::sleep(backoff.min(deadline - now));
+            backoff = (backoff * 2).min(Duration::from_millis(5));
+        }
+    }
+}
+impl Debug for NamedSemaphore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamedSemaphore")
+            .field("sem", &self.sem)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+impl Drop for NamedSemaphore {
+    fn drop(&mut self) {
+        unsafe { libc::sem_close(self.sem) };
+    }
+}
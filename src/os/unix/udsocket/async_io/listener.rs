@@ -0,0 +1,120 @@
+use super::{poll_read_with, UdStream};
+use crate::{
+    listener_close::{AsyncCloseSignal, ListenerClosedError},
+    os::unix::udsocket::{ToUdSocketPath, UdSocketPath, UdStreamListener as SyncUdStreamListener},
+};
+use async_io::Async;
+use futures_core::ready;
+use std::{
+    convert::TryFrom,
+    fs,
+    future::poll_fn,
+    io,
+    os::unix::{io::AsFd, net::UnixListener as StdUdStreamListener},
+    task::{Context, Poll},
+};
+
+/// A Unix domain byte stream socket server driven by `async-io`, listening for connections.
+///
+/// All such sockets have the `SOCK_STREAM` socket type; in other words, this is the Unix domain version of a TCP server.
+///
+/// # Examples
+///
+/// ## Basic server
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::io::{AsyncReadExt, AsyncWriteExt};
+/// use interprocess::os::unix::udsocket::async_io::UdStreamListener;
+///
+/// let listener = UdStreamListener::bind("/tmp/example.sock")?;
+/// loop {
+///     let conn = listener.accept().await?;
+///     let mut buffer = String::with_capacity(128);
+///     (&conn).read_to_string(&mut buffer).await?;
+///     (&conn).write_all(b"Hello from server!\n").await?;
+///     println!("Client answered: {}", buffer.trim());
+/// }
+/// # std::io::Result::<()>::Ok(()) }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct UdStreamListener(Async<SyncUdStreamListener>, AsyncCloseSignal);
+impl UdStreamListener {
+    /// Creates a new listener socket at the specified address.
+    ///
+    /// If the socket path exceeds the [maximum socket path length] (which includes the first 0 byte when using the [socket namespace]), an error is returned. Errors can also be produced for different reasons, i.e. errors should always be handled regardless of whether the path is known to be short enough or not.
+    ///
+    /// # Example
+    /// See [`ToUdSocketPath`].
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `bind`
+    ///
+    /// [maximum socket path length]: super::super::MAX_UDSOCKET_PATH_LEN
+    /// [socket namespace]: super::super::UdSocketPath::Namespaced
+    pub fn bind<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        Self::_bind(path.to_socket_path()?)
+    }
+    fn _bind(path: UdSocketPath<'_>) -> io::Result<Self> {
+        let listener = SyncUdStreamListener::_bind(path, false, true)?;
+        Self::from_sync(listener)
+    }
+    /// Listens for incoming connections to the socket, asynchronously waiting a client is connected.
+    ///
+    /// If the listener is [closed](Self::close) while this is pending or before it is called, an error wrapping [`ListenerClosedError`] is returned.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: a client is only taken from the queue of pending connections once it can be returned.
+    pub async fn accept(&self) -> io::Result<UdStream> {
+        poll_fn(|cx| self.poll_accept(cx)).await
+    }
+    /// Raw polling interface for accepting incoming connections. You probably want `.accept()` instead.
+    ///
+    /// Returns an error wrapping [`ListenerClosedError`] if the listener has been [closed](Self::close), including when it is closed while the task is waiting for this method to return `Poll::Ready`.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<UdStream>> {
+        if self.1.poll_closed(cx) {
+            return Poll::Ready(Err(ListenerClosedError.into()));
+        }
+        let rslt = ready!(poll_read_with(&self.0, cx, SyncUdStreamListener::accept));
+        self.1.forget(cx);
+        Poll::Ready(rslt.and_then(UdStream::from_sync))
+    }
+    /// Closes the listener, waking up all pending [`.accept()`](Self::accept) calls with an error wrapping [`ListenerClosedError`]. All subsequent calls to `.accept()` will fail with the same error.
+    ///
+    /// The socket file is left intact – use [`.close_and_unlink()`](Self::close_and_unlink) to remove it as well. The file descriptor of the listener is only closed when it's dropped.
+    pub fn close(&self) {
+        self.1.close()
+    }
+    /// Same as [`.close()`](Self::close), but also deletes the socket file if the listener is bound to a filesystem path. Listeners in the [socket namespace] don't have files and thus are simply closed.
+    ///
+    /// The socket path is retrieved via `getsockname`, meaning that a listener bound to a relative path resolves it relative to the *current* working directory, which may have changed since the listener was created.
+    ///
+    /// # System calls
+    /// - `dup`
+    /// - `getsockname`
+    /// - `unlink`
+    ///
+    /// [socket namespace]: super::super::UdSocketPath::Namespaced
+    pub fn close_and_unlink(&self) -> io::Result<()> {
+        self.close();
+        let listener = StdUdStreamListener::from(self.as_fd().try_clone_to_owned()?);
+        if let Some(path) = listener.local_addr()?.as_pathname() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+    /// Returns `true` if the listener has been [closed](Self::close), `false` otherwise.
+    pub fn is_closed(&self) -> bool {
+        self.1.is_closed()
+    }
+
+    /// Unwraps into the `async-io` wrapper of the blocking listener. This is a zero-cost operation.
+    pub fn into_async_io(self) -> Async<SyncUdStreamListener> {
+        self.0
+    }
+    /// Wraps an `async-io` wrapper of a blocking listener. This is a zero-cost operation.
+    pub fn from_async_io(async_io: Async<SyncUdStreamListener>) -> Self {
+        Self(async_io, AsyncCloseSignal::default())
+    }
+}
+async_io_wrapper_impls!(for UdStreamListener, sync SyncUdStreamListener, std StdUdStreamListener);
@@ -0,0 +1,50 @@
+//! Asynchronous Ud-sockets which work with async-std, `smol` and other runtimes built on `async-io`.
+//!
+//! Unlike the Tokio integration, the types from this module don't need a runtime context to be created or used: the sockets are registered with the global reactor of `async-io`, which drives them from a thread of its own if nothing else does, so they can be awaited on any executor. Ancillary data, including the passing of file descriptors, is supported the same way as with the blocking and Tokio-based types.
+//!
+//! Every type wraps `async-io`'s [`Async`] around its blocking counterpart, and can be converted to and from it.
+
+// contains macros, has to go before the other modules
+#[macro_use]
+mod util;
+
+mod listener;
+mod socket;
+mod stream;
+pub use {listener::*, socket::*, stream::*};
+
+pub use crate::listener_close::ListenerClosedError;
+
+use async_io::Async;
+use futures_core::ready;
+use std::{
+    io,
+    task::{Context, Poll},
+};
+
+/// Performs the nonblocking operation, waiting for the socket to become readable and retrying whenever it fails with [`WouldBlock`](io::ErrorKind::WouldBlock).
+fn poll_read_with<T, R>(
+    io: &Async<T>,
+    cx: &mut Context<'_>,
+    mut f: impl FnMut(&T) -> io::Result<R>,
+) -> Poll<io::Result<R>> {
+    loop {
+        match f(io.get_ref()) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => ready!(io.poll_readable(cx))?,
+            els => return Poll::Ready(els),
+        }
+    }
+}
+/// Same as [`poll_read_with()`], but waits for the socket to become writable instead.
+fn poll_write_with<T, R>(
+    io: &Async<T>,
+    cx: &mut Context<'_>,
+    mut f: impl FnMut(&T) -> io::Result<R>,
+) -> Poll<io::Result<R>> {
+    loop {
+        match f(io.get_ref()) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => ready!(io.poll_writable(cx))?,
+            els => return Poll::Ready(els),
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use super::{poll_read_with, poll_write_with};
+use crate::{
+    os::unix::{
+        udsocket::{
+            c_wrappers,
+            cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+            fd_chunking, PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocket as SyncUdSocket, UdSocketPath,
+        },
+        unixprelude::*,
+    },
+    reliable_recv_msg::{AsyncReliableRecvMsg, TryRecvResult},
+};
+use async_io::Async;
+use std::{
+    convert::TryFrom,
+    future::poll_fn,
+    io::{self, IoSlice, IoSliceMut},
+    net::Shutdown,
+    os::unix::net::UnixDatagram as StdUdSocket,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A Unix domain datagram socket driven by `async-io`, obtained either by binding it to a path or by creating an unnamed one.
+///
+/// # Examples
+///
+/// ## Basic packet exchange
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::try_join;
+/// use interprocess::os::unix::udsocket::async_io::UdSocket;
+///
+/// let socket = UdSocket::bind("/tmp/example_side_a.sock")?;
+/// socket.set_destination("/tmp/example_side_b.sock")?;
+///
+/// let mut buffer = [0; 128];
+/// let send = socket.send(b"Hello from side A!");
+/// let recv = socket.recv(&mut buffer);
+/// let (_, received) = try_join!(send, recv)?;
+///
+/// println!("Other side answered: {}", String::from_utf8_lossy(&buffer[..received]));
+/// # std::io::Result::<()>::Ok(()) }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct UdSocket(Async<SyncUdSocket>);
+impl UdSocket {
+    /// Creates an unnamed datagram socket.
+    pub fn unbound() -> io::Result<Self> {
+        Self::from_sync(SyncUdSocket::unbound()?)
+    }
+    /// Creates a named datagram socket assigned to the specified path. This will be the "home" of this socket. Then, packets from somewhere else directed to this socket with [`.send_to()`](Self::send_to) or [`.set_destination()`](Self::set_destination) will go here.
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    pub fn bind<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        Self::from_sync(SyncUdSocket::bind(path)?)
+    }
+    /// Selects the Unix domain socket to send packets to. You can also just use [`.send_to()`](Self::send_to) instead, but supplying the address to the kernel once is more efficient.
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    pub fn set_destination<'a>(&self, path: impl ToUdSocketPath<'a>) -> io::Result<()> {
+        self.0.get_ref().set_destination(path)
+    }
+    /// Shuts down the read, write, or both halves of the socket. See [`Shutdown`].
+    ///
+    /// Attempting to call this method with the same `how` argument multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        c_wrappers::shutdown(self.as_fd(), how)
+    }
+
+    /// Receives a single datagram from the socket, returning the amount of bytes received.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_recv(cx, buf)).await
+    }
+    /// Raw polling interface for receiving datagrams. You probably want `.recv()` instead.
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        poll_read_with(&self.0, cx, |s| s.recv(buf))
+    }
+    /// Asynchronously waits until readable data arrives to the socket.
+    ///
+    /// May finish spuriously – *do not* perform a blocking read when this future finishes and *do* handle a [`WouldBlock`](io::ErrorKind::WouldBlock) or [`Poll::Pending`].
+    pub async fn recv_ready(&self) -> io::Result<()> {
+        self.0.readable().await
+    }
+    /// Sends a single datagram into the socket, returning how many bytes were actually sent.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_send(cx, buf)).await
+    }
+    /// Raw polling interface for sending datagrams. You probably want `.send()` instead.
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        poll_write_with(&self.0, cx, |s| s.send(buf))
+    }
+    /// Sends a single datagram to the given address, returning how many bytes were actually sent.
+    pub async fn send_to(&self, buf: &[u8], path: impl ToUdSocketPath<'_>) -> io::Result<usize> {
+        let path = path.to_socket_path()?;
+        poll_fn(|cx| self._poll_send_to(cx, buf, &path)).await
+    }
+    /// Raw polling interface for sending datagrams. You probably want `.send_to()` instead.
+    pub fn poll_send_to<'a>(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        path: impl ToUdSocketPath<'a>,
+    ) -> Poll<io::Result<usize>> {
+        let path = path.to_socket_path()?;
+        self._poll_send_to(cx, buf, &path)
+    }
+    fn _poll_send_to(&self, cx: &mut Context<'_>, buf: &[u8], path: &UdSocketPath<'_>) -> Poll<io::Result<usize>> {
+        poll_write_with(&self.0, cx, |s| s.send_to(buf, path))
+    }
+    /// Asynchronously waits until the socket becomes writable due to the other side freeing up space in its OS receive buffer.
+    ///
+    /// May finish spuriously – *do not* perform a blocking write when this future finishes and *do* handle a [`WouldBlock`](io::ErrorKind::WouldBlock) or [`Poll::Pending`].
+    pub async fn send_ready(&self) -> io::Result<()> {
+        self.0.writable().await
+    }
+
+    /// Receives a single datagram and ancillary data from the socket, asynchronously waiting for it to arrive. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
+    pub async fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
+        poll_fn(|cx| self.poll_recv_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for receiving datagrams with ancillary data. You probably want `.recv_ancillary()` instead.
+    #[inline]
+    pub fn poll_recv_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        self.poll_recv_ancillary_vectored(cx, &mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Raw polling interface for receiving datagrams with ancillary data, making use of [scatter input] for the main data.
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_recv_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        poll_read_with(&self.0, cx, |s| s.recv_ancillary_vectored(bufs, abuf))
+    }
+    /// Sends a single datagram and ancillary data into the socket, asynchronously waiting for buffer space to become available. The return value is in the following order:
+    /// - How many bytes of the datagram were sent
+    /// - How many bytes of ancillary data were sent
+    pub async fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
+        poll_fn(|cx| self.poll_send_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for sending datagrams with ancillary data. You probably want `.send_ancillary()` instead.
+    #[inline]
+    pub fn poll_send_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        self.poll_send_ancillary_vectored(cx, &[IoSlice::new(buf)], abuf)
+    }
+    /// Raw polling interface for sending datagrams with ancillary data, making use of [gather output] for the main data.
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_send_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        poll_write_with(&self.0, cx, |s| s.send_ancillary_vectored(bufs, abuf))
+    }
+
+    /// Sends bytes together with the given file descriptors into the socket, splitting them across several datagrams if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each datagram but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the datagrams have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref()).await?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf).await?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
+    }
+
+    /// Enables or disables the `SO_TIMESTAMP` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`Timestamp`](crate::os::unix::udsocket::cmsg::ancillary::timestamp::Timestamp) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestamp template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "solaris",
+            target_os = "illumos"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+    pub fn set_timestamp(&self, enable: bool) -> io::Result<()> {
+        self.0.get_ref().set_timestamp(enable)
+    }
+    /// Enables or disables the `SO_TIMESTAMPNS` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`TimestampNs`](crate::os::unix::udsocket::cmsg::ancillary::timestamp::TimestampNs) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestampns template
+        feature = "doc_cfg",
+        doc(cfg(all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+    pub fn set_timestamp_ns(&self, enable: bool) -> io::Result<()> {
+        self.0.get_ref().set_timestamp_ns(enable)
+    }
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            all(
+                target_os = "linux",
+                any(
+                    target_env = "gnu",
+                    target_env = "musl",
+                    target_env = "musleabi",
+                    target_env = "musleabihf"
+                )
+            ),
+            target_os = "android"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        self.0.get_ref().set_passsec(enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        self.0.get_ref().get_peer_credentials()
+    }
+
+    /// Unwraps into the `async-io` wrapper of the blocking socket. This is a zero-cost operation.
+    pub fn into_async_io(self) -> Async<SyncUdSocket> {
+        self.0
+    }
+    /// Wraps an `async-io` wrapper of a blocking socket. This is a zero-cost operation.
+    pub fn from_async_io(async_io: Async<SyncUdSocket>) -> Self {
+        Self(async_io)
+    }
+}
+async_io_wrapper_impls!(for UdSocket, sync SyncUdSocket, std StdUdSocket);
+
+impl AsyncReliableRecvMsg for &UdSocket {
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        poll_read_with(&self.0, cx, |s| c_wrappers::try_recv_msg(s.as_fd(), buf))
+    }
+}
+impl AsyncReliableRecvMsg for UdSocket {
+    #[inline]
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        Pin::new(&mut &*self).poll_try_recv(cx, buf)
+    }
+}
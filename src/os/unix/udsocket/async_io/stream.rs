@@ -0,0 +1,329 @@
+use super::{poll_read_with, poll_write_with};
+use crate::os::unix::{
+    udsocket::{
+        cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+        fd_chunking, PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocketPath, UdStream as SyncUdStream,
+    },
+    unixprelude::*,
+};
+use async_io::Async;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::{
+    convert::TryFrom,
+    future::poll_fn,
+    io::{self, IoSlice, IoSliceMut},
+    net::Shutdown,
+    os::unix::net::UnixStream as StdUdStream,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A Unix domain socket byte stream driven by `async-io`, obtained either from [`UdStreamListener`](super::UdStreamListener) or by connecting to an existing server.
+///
+/// All methods take `&self`, and `AsyncRead` and `AsyncWrite` are also implemented on `&UdStream`, so reading and writing concurrently only takes two references to the same stream, which can be shared between tasks with an `Arc`.
+///
+/// # Examples
+///
+/// ## Basic client
+/// ```no_run
+/// # futures::executor::block_on(async {
+/// use futures::{
+///     io::{AsyncReadExt, AsyncWriteExt},
+///     try_join,
+/// };
+/// use interprocess::os::unix::udsocket::async_io::UdStream;
+/// use std::net::Shutdown;
+///
+/// let conn = UdStream::connect("/tmp/example.sock").await?;
+///
+/// // Two references to the same stream act as its read half and write half.
+/// let (mut reader, mut writer) = (&conn, &conn);
+/// let mut buffer = String::with_capacity(128);
+///
+/// let write = async {
+///     writer.write_all(b"Hello from client!\n").await?;
+///     conn.shutdown(Shutdown::Write)
+/// };
+/// let read = reader.read_to_string(&mut buffer);
+/// try_join!(write, read)?;
+///
+/// println!("Server answered: {}", buffer.trim());
+/// # std::io::Result::<()>::Ok(()) }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct UdStream(Async<SyncUdStream>);
+impl UdStream {
+    /// Connects to a Unix domain socket server at the specified path.
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    pub async fn connect(path: impl ToUdSocketPath<'_>) -> io::Result<Self> {
+        let path = path.to_socket_path()?;
+        Self::_connect(&path).await
+    }
+    async fn _connect(path: &UdSocketPath<'_>) -> io::Result<Self> {
+        // Connecting a Ud-socket only fails with WouldBlock when the backlog of the server is full, which isn't
+        // reported by readiness, so the attempt is simply retried on the next poll.
+        let stream = poll_fn(|cx| match SyncUdStream::connect_nonblocking(path) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            els => Poll::Ready(els),
+        })
+        .await?;
+        Self::from_sync(stream)
+    }
+
+    /// Shuts down the read, write, or both halves of the stream. See [`Shutdown`].
+    ///
+    /// Attempting to call this method with the same `how` argument multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.0.get_ref().shutdown(how)
+    }
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            all(
+                target_os = "linux",
+                any(
+                    target_env = "gnu",
+                    target_env = "musl",
+                    target_env = "musleabi",
+                    target_env = "musleabihf"
+                )
+            ),
+            target_os = "android"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        self.0.get_ref().set_passsec(enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        self.0.get_ref().get_peer_credentials()
+    }
+
+    /// Receives bytes from the socket stream, asynchronously waiting for them to arrive.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_recv(cx, buf)).await
+    }
+    /// Raw polling interface for receiving bytes. You probably want `.recv()` instead.
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        poll_read_with(&self.0, cx, |s| s.recv(buf))
+    }
+    /// Receives bytes from the socket stream, making use of [scatter input] for the main data and asynchronously waiting for them to arrive.
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub async fn recv_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        poll_fn(|cx| poll_read_with(&self.0, cx, |s| s.recv_vectored(bufs))).await
+    }
+    /// Receives both bytes and ancillary data from the socket stream, asynchronously waiting for them to arrive. The return value reports how many bytes of data and ancillary data were received, as well as whether the ancillary data was truncated.
+    pub async fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
+        poll_fn(|cx| self.poll_recv_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for receiving bytes and ancillary data. You probably want `.recv_ancillary()` instead.
+    #[inline]
+    pub fn poll_recv_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        self.poll_recv_ancillary_vectored(cx, &mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Raw polling interface for receiving bytes and ancillary data, making use of [scatter input] for the main data.
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_recv_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        poll_read_with(&self.0, cx, |s| s.recv_ancillary_vectored(bufs, abuf))
+    }
+    /// Sends bytes into the socket stream, asynchronously waiting for buffer space to become available.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_send(cx, buf)).await
+    }
+    /// Raw polling interface for sending bytes. You probably want `.send()` instead.
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        poll_write_with(&self.0, cx, |s| s.send(buf))
+    }
+    /// Sends bytes into the socket stream, making use of [gather output] for the main data and asynchronously waiting for buffer space to become available.
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub async fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        poll_fn(|cx| poll_write_with(&self.0, cx, |s| s.send_vectored(bufs))).await
+    }
+    /// Sends bytes and ancillary data into the socket stream, asynchronously waiting for buffer space to become available. The return value is in the following order:
+    /// - How many bytes of data were sent
+    /// - How many bytes of ancillary data were sent
+    pub async fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
+        poll_fn(|cx| self.poll_send_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for sending bytes and ancillary data. You probably want `.send_ancillary()` instead.
+    #[inline]
+    pub fn poll_send_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        self.poll_send_ancillary_vectored(cx, &[IoSlice::new(buf)], abuf)
+    }
+    /// Raw polling interface for sending bytes and ancillary data, making use of [gather output] for the main data.
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_send_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        poll_write_with(&self.0, cx, |s| s.send_ancillary_vectored(bufs, abuf))
+    }
+
+    /// Sends bytes together with the given file descriptors into the socket stream, splitting them across several writes if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each write but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the writes have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref()).await?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf).await?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
+    }
+
+    /// Waits until the socket becomes readable, which usually means that [`.try_recv()`](Self::try_recv) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_recv()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn readable(&self) -> io::Result<()> {
+        self.0.readable().await
+    }
+    /// Waits until the socket becomes writable, which usually means that [`.try_send()`](Self::try_send) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_send()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn writable(&self) -> io::Result<()> {
+        self.0.writable().await
+    }
+    /// Receives bytes from the socket stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if none are available.
+    pub fn try_recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.get_ref().recv(buf)
+    }
+    /// Sends bytes into the socket stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no buffer space available.
+    pub fn try_send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.0.get_ref().send(buf)
+    }
+
+    /// Unwraps into the `async-io` wrapper of the blocking stream. This is a zero-cost operation.
+    pub fn into_async_io(self) -> Async<SyncUdStream> {
+        self.0
+    }
+    /// Wraps an `async-io` wrapper of a blocking stream. This is a zero-cost operation.
+    pub fn from_async_io(async_io: Async<SyncUdStream>) -> Self {
+        Self(async_io)
+    }
+}
+async_io_wrapper_impls!(for UdStream, sync SyncUdStream, std StdUdStream);
+
+impl AsyncRead for &UdStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.poll_recv(cx, buf)
+    }
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        poll_read_with(&self.0, cx, |s| s.recv_vectored(bufs))
+    }
+}
+impl AsyncRead for UdStream {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read(cx, buf)
+    }
+    #[inline]
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_read_vectored(cx, bufs)
+    }
+}
+impl AsyncWrite for &UdStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_send(cx, buf)
+    }
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        poll_write_with(&self.0, cx, |s| s.send_vectored(bufs))
+    }
+    /// Finishes immediately, since sockets have nothing to flush.
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    /// Shuts down the write half of the stream, which sends an EOF to the other side. Finishes immediately.
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.shutdown(Shutdown::Write))
+    }
+}
+impl AsyncWrite for UdStream {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write(cx, buf)
+    }
+    #[inline]
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut &*self).poll_write_vectored(cx, bufs)
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_flush(cx)
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut &*self).poll_close(cx)
+    }
+}
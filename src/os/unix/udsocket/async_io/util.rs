@@ -0,0 +1,70 @@
+/// Conversion methods and trait implementations shared by all the types in this module, which need to have
+/// `.into_async_io()` and `::from_async_io()` methods of their own.
+macro_rules! async_io_wrapper_impls {
+    (for $slf:ty, sync $sync:ty, std $std:ty) => {
+        impl $slf {
+            /// Deregisters the socket from the reactor and converts it to a blocking one. The socket is left in nonblocking mode.
+            pub fn into_sync(self) -> io::Result<$sync> {
+                self.into_async_io().into_inner()
+            }
+            /// Puts a blocking socket in nonblocking mode and registers it with the reactor.
+            pub fn from_sync(sync: $sync) -> io::Result<Self> {
+                Ok(Self::from_async_io(async_io::Async::new(sync)?))
+            }
+            /// Deregisters the socket from the reactor and converts it to a blocking one from the standard library. The socket is left in nonblocking mode.
+            pub fn into_std(self) -> io::Result<$std> {
+                let fd = std::os::unix::io::IntoRawFd::into_raw_fd(self.into_sync()?);
+                Ok(unsafe { <$std as std::os::unix::io::FromRawFd>::from_raw_fd(fd) })
+            }
+            /// Puts a blocking socket from the standard library in nonblocking mode and registers it with the reactor.
+            pub fn from_std(std: $std) -> io::Result<Self> {
+                let fd = std::os::unix::io::IntoRawFd::into_raw_fd(std);
+                Self::from_sync(unsafe { <$sync as std::os::unix::io::FromRawFd>::from_raw_fd(fd) })
+            }
+        }
+        impl From<$slf> for async_io::Async<$sync> {
+            fn from(x: $slf) -> Self {
+                x.into_async_io()
+            }
+        }
+        impl From<async_io::Async<$sync>> for $slf {
+            fn from(async_io: async_io::Async<$sync>) -> Self {
+                Self::from_async_io(async_io)
+            }
+        }
+        impl TryFrom<$slf> for $sync {
+            type Error = io::Error;
+            fn try_from(x: $slf) -> Result<Self, Self::Error> {
+                x.into_sync()
+            }
+        }
+        impl TryFrom<$sync> for $slf {
+            type Error = io::Error;
+            fn try_from(sync: $sync) -> Result<Self, Self::Error> {
+                Self::from_sync(sync)
+            }
+        }
+        impl TryFrom<$slf> for $std {
+            type Error = io::Error;
+            fn try_from(x: $slf) -> Result<Self, Self::Error> {
+                x.into_std()
+            }
+        }
+        impl TryFrom<$std> for $slf {
+            type Error = io::Error;
+            fn try_from(std: $std) -> Result<Self, Self::Error> {
+                Self::from_std(std)
+            }
+        }
+        impl std::os::unix::io::AsFd for $slf {
+            fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
+        impl std::os::unix::io::AsRawFd for $slf {
+            fn as_raw_fd(&self) -> libc::c_int {
+                self.0.as_raw_fd()
+            }
+        }
+    };
+}
@@ -1,6 +1,34 @@
-use crate::os::unix::{unixprelude::*, FdOps};
-use libc::{sockaddr, sockaddr_un, AF_UNIX, F_GETFL, F_SETFL, O_NONBLOCK, SHUT_RD, SHUT_RDWR, SHUT_WR};
-use std::{ffi::c_void, io, mem::size_of, net::Shutdown, ptr};
+use super::{
+    cmsg::{CmsgMut, CmsgRef},
+    util::{make_msghdr_r, make_msghdr_w},
+    PeerCredentials, RecvAncillaryResult,
+};
+use crate::{
+    os::unix::{check_file_type, unixprelude::*, FdOps},
+    reliable_recv_msg::TryRecvResult,
+    Error, ErrorKind,
+};
+use libc::{gid_t, sockaddr, sockaddr_un, uid_t, AF_UNIX, F_GETFL, F_SETFL, O_NONBLOCK, SHUT_RD, SHUT_RDWR, SHUT_WR};
+use std::{
+    ffi::{c_void, CStr},
+    io::{self, IoSlice, IoSliceMut},
+    mem::{size_of, zeroed},
+    net::Shutdown,
+    ptr,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// Whether sending into a socket whose peer has disconnected is allowed to raise `SIGPIPE`. See
+/// [`set_sigpipe_on_send()`](super::set_sigpipe_on_send).
+static SIGPIPE_ON_SEND: AtomicBool = AtomicBool::new(false);
+
+pub(super) fn sigpipe_on_send() -> bool {
+    SIGPIPE_ON_SEND.load(Relaxed)
+}
+pub(super) fn set_sigpipe_on_send(raise: bool) {
+    SIGPIPE_ON_SEND.store(raise, Relaxed)
+}
 
 pub(super) fn create_uds(ty: c_int, nonblocking: bool) -> io::Result<FdOps> {
     #[allow(unused_mut, clippy::let_and_return)]
@@ -18,11 +46,20 @@ pub(super) fn create_uds(ty: c_int, nonblocking: bool) -> io::Result<FdOps> {
     let fd = create_uds_raw(ty)?;
     #[cfg(not(target_os = "linux"))]
     {
-        set_nonblocking(&fd, nonblocking)?;
-        set_cloexec(&fd, true)?;
+        set_nonblocking(fd.as_fd(), nonblocking)?;
+        set_cloexec(fd.as_fd(), true)?;
     }
+    set_nosigpipe(fd.as_fd())?;
     Ok(fd)
 }
+/// Sets `SO_NOSIGPIPE` on platforms which suppress `SIGPIPE` per socket rather than per call, unless
+/// [`set_sigpipe_on_send()`](super::set_sigpipe_on_send) has been used to opt out. Does nothing on other platforms.
+#[allow(unused_variables)]
+pub(super) fn set_nosigpipe(fd: BorrowedFd<'_>) -> io::Result<()> {
+    #[cfg(uds_so_nosigpipe)]
+    set_socket_option_bool(fd, libc::SO_NOSIGPIPE, !sigpipe_on_send())?;
+    Ok(())
+}
 fn create_uds_raw(ty: c_int) -> io::Result<FdOps> {
     let (success, fd) = unsafe {
         let result = libc::socket(AF_UNIX, ty, 0);
@@ -43,34 +80,79 @@ fn create_uds_raw(ty: c_int) -> io::Result<FdOps> {
 ///
 /// # Safety
 /// `addr` must be properly null-terminated.
-pub(super) unsafe fn bind(fd: &FdOps, addr: &sockaddr_un) -> io::Result<()> {
+pub(super) unsafe fn bind(fd: BorrowedFd<'_>, addr: &sockaddr_un) -> io::Result<()> {
     let success = unsafe {
         libc::bind(
-            fd.0,
+            fd.as_raw_fd(),
             // Double cast because you cannot cast a reference to a pointer of arbitrary type
             // but you can cast any narrow pointer to any other narrow pointer
             addr as *const _ as *const sockaddr,
             size_of::<sockaddr_un>() as u32,
         ) != -1
     };
-    ok_or_ret_errno!(success => ())
+    if !success {
+        return Err(crate::error::connect_error(io::Error::last_os_error()));
+    }
+    Ok(())
 }
 
 /// Connects the specified Ud-socket file descriptor to the given address.
 ///
 /// # Safety
 /// `addr` must be properly null-terminated.
-pub(super) unsafe fn connect(fd: &FdOps, addr: &sockaddr_un) -> io::Result<()> {
-    let success = unsafe { libc::connect(fd.0, addr as *const _ as *const _, size_of::<sockaddr_un>() as u32) != -1 };
-    ok_or_ret_errno!(success => ())
+pub(super) unsafe fn connect(fd: BorrowedFd<'_>, addr: &sockaddr_un) -> io::Result<()> {
+    let success = unsafe {
+        libc::connect(
+            fd.as_raw_fd(),
+            addr as *const _ as *const _,
+            size_of::<sockaddr_un>() as u32,
+        ) != -1
+    };
+    if !success {
+        return Err(crate::error::connect_error(io::Error::last_os_error()));
+    }
+    Ok(())
 }
 
-pub(super) fn listen(fd: &FdOps, backlog: c_int) -> io::Result<()> {
-    let success = unsafe { libc::listen(fd.0, backlog) != -1 };
+/// Sends a datagram to the given address from the specified Ud-socket file descriptor.
+///
+/// # Safety
+/// `addr` must be properly null-terminated.
+pub(super) unsafe fn sendto(fd: BorrowedFd<'_>, buf: &[u8], addr: &sockaddr_un) -> io::Result<usize> {
+    nosigpipe(|| {
+        let (success, bytes_sent) = unsafe {
+            let result = libc::sendto(
+                fd.as_raw_fd(),
+                buf.as_ptr().cast(),
+                buf.len(),
+                send_flags(),
+                addr as *const _ as *const _,
+                size_of::<sockaddr_un>() as u32,
+            );
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => bytes_sent)
+    })
+}
+
+pub(super) fn chmod(path: &CStr, mode: mode_t) -> io::Result<()> {
+    let success = unsafe { libc::chmod(path.as_ptr(), mode) != -1 };
+    ok_or_ret_errno!(success => ())
+}
+/// Changes the owner and/or the group of a file, leaving the unspecified ones as they are.
+pub(super) fn chown(path: &CStr, owner: Option<uid_t>, group: Option<gid_t>) -> io::Result<()> {
+    // -1 means "unchanged" for both IDs.
+    let owner = owner.unwrap_or(uid_t::MAX);
+    let group = group.unwrap_or(gid_t::MAX);
+    let success = unsafe { libc::chown(path.as_ptr(), owner, group) != -1 };
+    ok_or_ret_errno!(success => ())
+}
+pub(super) fn listen(fd: BorrowedFd<'_>, backlog: c_int) -> io::Result<()> {
+    let success = unsafe { libc::listen(fd.as_raw_fd(), backlog) != -1 };
     ok_or_ret_errno!(success => ())
 }
 
-pub(super) fn set_passcred(fd: &FdOps, passcred: bool) -> io::Result<()> {
+pub(super) fn set_passcred(fd: BorrowedFd<'_>, passcred: bool) -> io::Result<()> {
     #[cfg(uds_scm_credentials)]
     {
         use libc::{SOL_SOCKET, SO_PASSCRED};
@@ -79,7 +161,7 @@ pub(super) fn set_passcred(fd: &FdOps, passcred: bool) -> io::Result<()> {
         let passcred = passcred as c_int;
         let success = unsafe {
             libc::setsockopt(
-                fd.0,
+                fd.as_raw_fd(),
                 SOL_SOCKET,
                 SO_PASSCRED,
                 &passcred as *const _ as *const _,
@@ -94,10 +176,67 @@ pub(super) fn set_passcred(fd: &FdOps, passcred: bool) -> io::Result<()> {
         Ok(())
     }
 }
+/// Sets a boolean `SOL_SOCKET`-level socket option.
+#[allow(dead_code)] // Not every platform has options that go through this
+pub(super) fn set_socket_option_bool(fd: BorrowedFd<'_>, option: c_int, value: bool) -> io::Result<()> {
+    let value = value as c_int;
+    let success = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            option,
+            (&value as *const c_int).cast(),
+            size_of::<c_int>() as _,
+        ) != -1
+    };
+    ok_or_ret_errno!(success => ())
+}
+pub(super) fn get_peer_credentials(fd: BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    #[cfg(uds_peerucred)]
+    {
+        get_peer_ucred(fd).map(PeerCredentials::from)
+    }
+    #[cfg(all(uds_peereid, not(uds_peerucred)))]
+    {
+        let (mut uid, mut gid) = (0, 0);
+        let success = unsafe { libc::getpeereid(fd.as_raw_fd(), &mut uid, &mut gid) != -1 };
+        if !success {
+            return Err(io::Error::last_os_error());
+        }
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        let pid = {
+            let mut pid: libc::pid_t = 0;
+            let mut pid_len = size_of::<libc::pid_t>() as libc::socklen_t;
+            let success = unsafe {
+                libc::getsockopt(
+                    fd.as_raw_fd(),
+                    libc::SOL_LOCAL,
+                    libc::LOCAL_PEERPID,
+                    (&mut pid as *mut libc::pid_t).cast(),
+                    &mut pid_len,
+                ) != -1
+            };
+            if !success {
+                return Err(io::Error::last_os_error());
+            }
+            Some(pid)
+        };
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        let pid = None;
+        Ok(PeerCredentials::new(pid, Some(uid), Some(gid), None))
+    }
+    #[cfg(not(any(uds_peerucred, uds_peereid)))]
+    {
+        let _ = fd;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "retrieving peer credentials is not supported on this platform",
+        ))
+    }
+}
 #[cfg(uds_peerucred)]
-pub(super) fn get_peer_ucred(fd: &FdOps) -> io::Result<libc::ucred> {
+fn get_peer_ucred(fd: BorrowedFd<'_>) -> io::Result<libc::ucred> {
     use libc::{socklen_t, ucred, SOL_SOCKET, SO_PEERCRED};
-    use std::mem::zeroed;
 
     let mut cred = unsafe {
         // SAFETY: it's safe for the ucred structure to be zero-initialized, since
@@ -107,7 +246,7 @@ pub(super) fn get_peer_ucred(fd: &FdOps) -> io::Result<libc::ucred> {
     let mut cred_len = size_of::<ucred>() as socklen_t;
     let success = unsafe {
         libc::getsockopt(
-            fd.0,
+            fd.as_raw_fd(),
             SOL_SOCKET,
             SO_PEERCRED,
             &mut cred as *mut _ as *mut _,
@@ -116,23 +255,141 @@ pub(super) fn get_peer_ucred(fd: &FdOps) -> io::Result<libc::ucred> {
     } != -1;
     ok_or_ret_errno!(success => cred)
 }
-fn get_status_flags(fd: &FdOps) -> io::Result<c_int> {
+/// Receives data and ancillary data from the socket, returning how many bytes of each were received.
+pub(super) fn recvmsg(
+    fd: BorrowedFd<'_>,
+    bufs: &mut [IoSliceMut<'_>],
+    abuf: &mut CmsgMut<'_>,
+    flags: c_int,
+) -> io::Result<RecvAncillaryResult> {
+    let mut hdr = make_msghdr_r(bufs, abuf)?;
+
+    let (success, bytes_read) = unsafe {
+        let result = libc::recvmsg(fd.as_raw_fd(), &mut hdr as *mut _, flags);
+        (result != -1, result as usize)
+    };
+    if success {
+        unsafe {
+            // SAFETY: the kernel has just written this much valid ancillary data to the beginning of the buffer
+            abuf.set_init_len(hdr.msg_controllen as _);
+        }
+    }
+    ok_or_ret_errno!(success => RecvAncillaryResult::new(bytes_read, hdr.msg_controllen as _, hdr.msg_flags))
+}
+/// Receives one message from a socket with message boundaries if it fits into the buffer, leaving it in the socket otherwise.
+pub(super) fn try_recv_msg(fd: BorrowedFd<'_>, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+    let no_abuf = &mut CmsgMut::new(&mut []);
+    let peeked = recvmsg(fd, &mut [IoSliceMut::new(buf)], no_abuf, libc::MSG_PEEK)?;
+    if !peeked.data_truncated {
+        let size = recvmsg(fd, &mut [IoSliceMut::new(buf)], no_abuf, 0)?.size;
+        return Ok(TryRecvResult { size, fit: true });
+    }
+    // The message didn't fit, find out how big it is without consuming it
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let size = recvmsg(fd, &mut [], no_abuf, libc::MSG_PEEK | libc::MSG_TRUNC)?.size;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let size = {
+        let mut bigger = vec![0; buf.len().max(64) * 2];
+        loop {
+            let peeked = recvmsg(fd, &mut [IoSliceMut::new(&mut bigger)], no_abuf, libc::MSG_PEEK)?;
+            if !peeked.data_truncated {
+                break peeked.size;
+            }
+            bigger.resize(bigger.len() * 2, 0);
+        }
+    };
+    Ok(TryRecvResult { size, fit: false })
+}
+/// Sends data and ancillary data into the socket, returning how many bytes of each were sent.
+pub(super) fn sendmsg(
+    fd: BorrowedFd<'_>,
+    bufs: &[IoSlice<'_>],
+    abuf: CmsgRef<'_>,
+    flags: c_int,
+) -> io::Result<(usize, usize)> {
+    let hdr = make_msghdr_w(bufs, abuf)?;
+
+    nosigpipe(|| {
+        let (success, bytes_written) = unsafe {
+            let result = libc::sendmsg(fd.as_raw_fd(), &hdr as *const _, flags | send_flags());
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => (bytes_written, hdr.msg_controllen as _))
+    })
+}
+/// Sends data into a connected socket, returning how many bytes were sent.
+pub(super) fn send(fd: BorrowedFd<'_>, buf: &[u8]) -> io::Result<usize> {
+    nosigpipe(|| {
+        let (success, bytes_written) = unsafe {
+            let result = libc::send(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len(), send_flags());
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => bytes_written)
+    })
+}
+/// The flags which every `send`-family call is made with: `MSG_NOSIGNAL` where it exists, unless
+/// [`set_sigpipe_on_send()`](super::set_sigpipe_on_send) has been used to opt out.
+pub(super) fn send_flags() -> c_int {
+    #[cfg(uds_msg_nosignal)]
+    if !sigpipe_on_send() {
+        return libc::MSG_NOSIGNAL;
+    }
+    0
+}
+/// Performs a `send`-family call with `SIGPIPE` blocked for the calling thread, on platforms which have neither
+/// `MSG_NOSIGNAL` nor `SO_NOSIGPIPE`.
+///
+/// A `SIGPIPE` raised by the call stays pending while it's blocked, and is consumed before unblocking it again, unless
+/// there already was one pending beforehand, which then gets delivered as usual.
+#[cfg(not(any(uds_msg_nosignal, uds_so_nosigpipe)))]
+fn nosigpipe<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    use libc::{pthread_sigmask, sigaddset, sigemptyset, sigismember, sigpending, sigset_t, sigwait, SIGPIPE};
+    if sigpipe_on_send() {
+        return f();
+    }
+    unsafe {
+        // SAFETY: all of the signal sets are initialized with sigemptyset() or by the calls which write to them
+        let mut set: sigset_t = zeroed();
+        sigemptyset(&mut set);
+        sigaddset(&mut set, SIGPIPE);
+        let mut old_mask: sigset_t = zeroed();
+        if pthread_sigmask(libc::SIG_BLOCK, &set, &mut old_mask) != 0 {
+            return f();
+        }
+        let mut pending: sigset_t = zeroed();
+        let already_pending = sigpending(&mut pending) == 0 && sigismember(&pending, SIGPIPE) == 1;
+
+        let result = f();
+        if !already_pending && matches!(&result, Err(e) if e.raw_os_error() == Some(libc::EPIPE)) {
+            let mut signal = 0;
+            sigwait(&set, &mut signal);
+        }
+        pthread_sigmask(libc::SIG_SETMASK, &old_mask, ptr::null_mut());
+        result
+    }
+}
+#[cfg(any(uds_msg_nosignal, uds_so_nosigpipe))]
+#[inline(always)]
+fn nosigpipe<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    f()
+}
+fn get_status_flags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
     let (flags, success) = unsafe {
         // SAFETY: nothing too unsafe about this function. One thing to note is that we're passing
         // it a null pointer, which is, for some reason, required yet ignored for F_GETFL.
-        let result = libc::fcntl(fd.0, F_GETFL, ptr::null::<c_void>());
+        let result = libc::fcntl(fd.as_raw_fd(), F_GETFL, ptr::null::<c_void>());
         (result, result != -1)
     };
     ok_or_ret_errno!(success => flags)
 }
-fn set_status_flags(fd: &FdOps, new_flags: c_int) -> io::Result<()> {
+fn set_status_flags(fd: BorrowedFd<'_>, new_flags: c_int) -> io::Result<()> {
     let success = unsafe {
         // SAFETY: new_flags is a c_int, as documented in the manpage.
-        libc::fcntl(fd.0, F_SETFL, new_flags)
+        libc::fcntl(fd.as_raw_fd(), F_SETFL, new_flags)
     } != -1;
     ok_or_ret_errno!(success => ())
 }
-pub(super) fn set_nonblocking(fd: &FdOps, nonblocking: bool) -> io::Result<()> {
+pub(super) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
     let old_flags = get_status_flags(fd)?;
     let new_flags = if nonblocking {
         old_flags | O_NONBLOCK
@@ -143,36 +400,124 @@ pub(super) fn set_nonblocking(fd: &FdOps, nonblocking: bool) -> io::Result<()> {
     };
     set_status_flags(fd, new_flags)
 }
-pub(super) fn get_nonblocking(fd: &FdOps) -> io::Result<bool> {
+pub(super) fn get_nonblocking(fd: BorrowedFd<'_>) -> io::Result<bool> {
     let flags = get_status_flags(fd)?;
     Ok(flags & O_NONBLOCK != 0)
 }
-pub(super) fn shutdown(fd: &FdOps, how: Shutdown) -> io::Result<()> {
+/// Waits for the socket to become readable (which, for a listener, means having a connection to accept), returning
+/// `false` if the timeout expires first.
+pub(super) fn poll_readable(fd: BorrowedFd<'_>, timeout: Duration) -> io::Result<bool> {
+    poll(fd, libc::POLLIN, Some(timeout))
+}
+/// Waits for any of the given `poll` events to occur on the socket, returning `false` if the timeout expires first. No
+/// timeout means waiting indefinitely.
+pub(super) fn poll(fd: BorrowedFd<'_>, events: libc::c_short, timeout: Option<Duration>) -> io::Result<bool> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let millis = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                // Rounded up so that a sub-millisecond remainder doesn't turn into a busy loop.
+                let millis = (remaining.as_nanos() + 999_999) / 1_000_000;
+                millis.min(c_int::MAX as u128) as c_int
+            }
+            None => -1,
+        };
+        let mut pfd = libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events,
+            revents: 0,
+        };
+        let result = unsafe { libc::poll(&mut pfd, 1, millis) };
+        match result {
+            -1 => {
+                let e = io::Error::last_os_error();
+                if e.kind() != io::ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            0 if deadline.map_or(false, |deadline| Instant::now() >= deadline) => return Ok(false),
+            0 => {}
+            _ => return Ok(true),
+        }
+    }
+}
+/// Returns how many bytes are waiting to be received from the socket.
+pub(super) fn bytes_available(fd: BorrowedFd<'_>) -> io::Result<usize> {
+    let mut available: c_int = 0;
+    let success = unsafe { libc::ioctl(fd.as_raw_fd(), libc::FIONREAD, &mut available as *mut _) } != -1;
+    ok_or_ret_errno!(success => available as usize)
+}
+pub(super) fn shutdown(fd: BorrowedFd<'_>, how: Shutdown) -> io::Result<()> {
     let how = match how {
         Shutdown::Read => SHUT_RD,
         Shutdown::Write => SHUT_WR,
         Shutdown::Both => SHUT_RDWR,
     };
-    let success = unsafe { libc::shutdown(fd.0, how) != -1 };
+    let success = unsafe { libc::shutdown(fd.as_raw_fd(), how) != -1 };
     ok_or_ret_errno!(success => ())
 }
+/// Checks that the file descriptor refers to a Ud-socket of one of the given types, which is listening for incoming
+/// connections if `listening` is `true` and is not otherwise.
+pub(crate) fn check_ud_socket(fd: BorrowedFd<'_>, types: &[c_int], listening: bool) -> io::Result<()> {
+    fn invalid(msg: &'static str) -> io::Error {
+        Error::new(ErrorKind::InvalidHandle, msg).into()
+    }
+    check_file_type(fd, libc::S_IFSOCK, "the file is not a socket")?;
+
+    let mut addr = unsafe { zeroed::<sockaddr_un>() };
+    let mut addr_len = size_of::<sockaddr_un>() as libc::socklen_t;
+    let success = unsafe { libc::getsockname(fd.as_raw_fd(), &mut addr as *mut _ as *mut _, &mut addr_len) } != -1;
+    if !success {
+        return Err(io::Error::last_os_error());
+    }
+    if c_int::from(addr.sun_family) != AF_UNIX {
+        return Err(invalid("the socket is not a Ud-socket"));
+    }
+
+    if !types.contains(&get_socket_option_int(fd, libc::SO_TYPE)?) {
+        return Err(invalid("the socket is of the wrong type"));
+    }
+    if (get_socket_option_int(fd, libc::SO_ACCEPTCONN)? != 0) != listening {
+        return Err(invalid(if listening {
+            "the socket is not listening"
+        } else {
+            "the socket is listening"
+        }));
+    }
+    Ok(())
+}
+fn get_socket_option_int(fd: BorrowedFd<'_>, option: c_int) -> io::Result<c_int> {
+    let mut value: c_int = 0;
+    let mut value_len = size_of::<c_int>() as libc::socklen_t;
+    let success = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            option,
+            &mut value as *mut _ as *mut _,
+            &mut value_len,
+        )
+    } != -1;
+    ok_or_ret_errno!(success => value)
+}
 
 #[cfg(not(target_os = "linux"))]
 mod non_linux {
     use super::*;
     use libc::{FD_CLOEXEC, F_GETFD, F_SETFD};
-    pub(super) fn get_fdflags(fd: &FdOps) -> io::Result<i32> {
+    pub(super) fn get_fdflags(fd: BorrowedFd<'_>) -> io::Result<i32> {
         let (val, success) = unsafe {
-            let ret = libc::fcntl(fd.0, F_GETFD, 0);
+            let ret = libc::fcntl(fd.as_raw_fd(), F_GETFD, 0);
             (ret, ret != -1)
         };
         ok_or_ret_errno!(success => val)
     }
-    pub(super) fn set_fdflags(fd: &FdOps, flags: i32) -> io::Result<()> {
-        let success = unsafe { libc::fcntl(fd.0, F_SETFD, flags) != -1 };
+    pub(super) fn set_fdflags(fd: BorrowedFd<'_>, flags: i32) -> io::Result<()> {
+        let success = unsafe { libc::fcntl(fd.as_raw_fd(), F_SETFD, flags) != -1 };
         ok_or_ret_errno!(success => ())
     }
-    pub(super) fn set_cloexec(fd: &FdOps, cloexec: bool) -> io::Result<()> {
+    pub(super) fn set_cloexec(fd: BorrowedFd<'_>, cloexec: bool) -> io::Result<()> {
         let mut flags = get_fdflags(fd)? & (!FD_CLOEXEC); // Mask out cloexec to set it to a new value
         if cloexec {
             flags |= FD_CLOEXEC;
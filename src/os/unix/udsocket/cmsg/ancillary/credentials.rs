@@ -3,15 +3,16 @@
 // FIXME uds_sockcred is disabled in build.rs for reasons outlined there.
 
 use super::*;
+use crate::os::unix::udsocket::PeerCredentials;
 use libc::{c_int, gid_t, pid_t, uid_t};
 use std::{
-    error::Error,
-    fmt::{self, Display, Formatter},
     // iter::FusedIterator,
     mem::size_of,
     slice,
 };
 
+pub use super::SizeMismatch;
+
 /// Ancillary data message that allows receiving the credentials of the peer process and, on some systems, setting the contents of this ancillary message that the other process will receive.
 ///
 /// To receive this message, the `SO_PASSCRED` socket option must be enabled. After it's enabled, every receive operation that provides an ancillary data buffer will receive an instance of this message.
@@ -136,6 +137,12 @@ impl<'a> Credentials<'a> {
     }
     */
 }
+impl From<Credentials<'_>> for PeerCredentials {
+    #[inline]
+    fn from(creds: Credentials<'_>) -> Self {
+        Self::new(creds.pid(), creds.real_uid(), creds.real_gid(), None)
+    }
+}
 impl PartialEq for Credentials<'_> {
     fn eq(&self, other: &Self) -> bool {
         if self.0 != other.0 {
@@ -219,19 +226,6 @@ impl<'a> FromCmsg<'a> for Credentials<'a> {
         Ok(Self(creds))
     }
 }
-/// A [`MalformedPayload`](ParseErrorKind::MalformedPayload) error indicating that the ancillary message size dosen't match that of the platform-specific credentials structure.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct SizeMismatch {
-    expected: usize,
-    got: usize,
-}
-impl Display for SizeMismatch {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let Self { expected, got } = self;
-        write!(f, "ancillary payload size mismatch (expected {expected}, got {got})")
-    }
-}
-impl Error for SizeMismatch {}
 
 /*
 /// An iterator over supplementary groups of [`Credentials`].
@@ -1,6 +1,16 @@
 #[cfg(any(all(doc, not(doctest)), uds_ucred))]
-use super::credentials::{Credentials, SizeMismatch};
-use super::{file_descriptors::FileDescriptors, Cmsg, FromCmsg, ParseError, ParseErrorKind, ParseResult, LEVEL};
+use super::credentials::Credentials;
+#[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+use super::security::Security;
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+use super::timestamp::Timestamp;
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+use super::timestamp::TimestampNs;
+#[cfg(any(all(doc, not(doctest)), uds_ucred, uds_scm_timestamp))]
+use super::SizeMismatch;
+use super::{
+    file_descriptors::FileDescriptors, Cmsg, FromCmsg, ParseError, ParseErrorKind, ParseResult, ToCmsg, LEVEL,
+};
 use std::{
     convert::Infallible,
     error::Error,
@@ -31,6 +41,54 @@ pub enum Ancillary<'a> {
     )]
     #[cfg(any(all(doc, not(doctest)), uds_ucred))]
     Credentials(Credentials<'a>),
+    #[cfg_attr( // uds_scm_security template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            all(
+                target_os = "linux",
+                any(
+                    target_env = "gnu",
+                    target_env = "musl",
+                    target_env = "musleabi",
+                    target_env = "musleabihf"
+                )
+            ),
+            target_os = "android"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    Security(Security<'a>),
+    #[cfg_attr( // uds_scm_timestamp template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "solaris",
+            target_os = "illumos"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+    Timestamp(Timestamp),
+    #[cfg_attr( // uds_scm_timestampns template
+        feature = "doc_cfg",
+        doc(cfg(all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+    TimestampNs(TimestampNs),
 }
 impl<'a> Ancillary<'a> {
     fn parse_fd(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
@@ -44,6 +102,40 @@ impl<'a> Ancillary<'a> {
             .map(Self::Credentials)
             .map_err(|e| e.map_payload_err(MalformedPayload::Credentials))
     }
+    #[cfg(uds_scm_security)]
+    fn parse_security(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
+        Security::try_parse(cmsg)
+            .map(Self::Security)
+            .map_err(|e| e.map_payload_err(MalformedPayload::from))
+    }
+    #[cfg(uds_scm_timestamp)]
+    fn parse_timestamp(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
+        Timestamp::try_parse(cmsg)
+            .map(Self::Timestamp)
+            .map_err(|e| e.map_payload_err(MalformedPayload::Timestamp))
+    }
+    #[cfg(uds_scm_timestampns)]
+    fn parse_timestamp_ns(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
+        TimestampNs::try_parse(cmsg)
+            .map(Self::TimestampNs)
+            .map_err(|e| e.map_payload_err(MalformedPayload::TimestampNs))
+    }
+}
+/// Serializes whichever message is contained, which allows for packing a heterogeneous collection of messages into one control message buffer via [`CmsgBuffer::add_messages()`](super::super::CmsgBuffer::add_messages).
+impl ToCmsg for Ancillary<'_> {
+    fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
+        match self {
+            Self::FileDescriptors(m) => m.add_to_buffer(add_fn),
+            #[cfg(uds_ucred)]
+            Self::Credentials(m) => m.add_to_buffer(add_fn),
+            #[cfg(uds_scm_security)]
+            Self::Security(m) => m.add_to_buffer(add_fn),
+            #[cfg(uds_scm_timestamp)]
+            Self::Timestamp(m) => m.add_to_buffer(add_fn),
+            #[cfg(uds_scm_timestampns)]
+            Self::TimestampNs(m) => m.add_to_buffer(add_fn),
+        }
+    }
 }
 impl<'a> FromCmsg<'a> for Ancillary<'a> {
     type MalformedPayloadError = MalformedPayload;
@@ -64,6 +156,12 @@ impl<'a> FromCmsg<'a> for Ancillary<'a> {
             FileDescriptors::TYPE => Self::parse_fd(cmsg),
             #[cfg(uds_ucred)]
             Credentials::TYPE => Self::parse_credentials(cmsg),
+            #[cfg(uds_scm_security)]
+            Security::TYPE => Self::parse_security(cmsg),
+            #[cfg(uds_scm_timestamp)]
+            Timestamp::TYPE => Self::parse_timestamp(cmsg),
+            #[cfg(uds_scm_timestampns)]
+            TimestampNs::TYPE => Self::parse_timestamp_ns(cmsg),
             _ => Err(ParseError {
                 cmsg,
                 kind: ParseErrorKind::WrongType {
@@ -97,12 +195,47 @@ pub enum MalformedPayload {
     )]
     #[cfg(any(all(doc, not(doctest)), uds_ucred))]
     Credentials(SizeMismatch),
+    #[cfg_attr( // uds_scm_timestamp template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "solaris",
+            target_os = "illumos"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+    Timestamp(SizeMismatch),
+    #[cfg_attr( // uds_scm_timestampns template
+        feature = "doc_cfg",
+        doc(cfg(all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+    TimestampNs(SizeMismatch),
 }
 impl Display for MalformedPayload {
     fn fmt(&self, _f: &mut Formatter<'_>) -> fmt::Result {
         match *self {
             #[cfg(uds_ucred)]
             Self::Credentials(e) => Display::fmt(&e, _f),
+            #[cfg(uds_scm_timestamp)]
+            Self::Timestamp(e) => Display::fmt(&e, _f),
+            #[cfg(uds_scm_timestampns)]
+            Self::TimestampNs(e) => Display::fmt(&e, _f),
         }
     }
 }
@@ -1,11 +1,17 @@
-//! [`FileDescriptors`] and associated helper types.
+//! [`FileDescriptors`], [`OwnedFileDescriptors`] and associated helper types.
 use super::*;
 use std::{
+    io,
     mem::{size_of, transmute},
     os::fd::{BorrowedFd, FromRawFd, OwnedFd, RawFd},
-    slice,
+    slice, vec,
 };
 
+/// The maximum number of file descriptors that can be sent in one control message.
+///
+/// This is the limit imposed by Linux; other systems have similar ones, which are either the same or higher. The `send_fds()` and `recv_fds()` methods on Ud-socket types transparently split larger transfers into several messages.
+pub const SCM_MAX_FD: usize = 253;
+
 /// Ancillary data message that allows sending ownership of file descriptors over to another process.
 ///
 /// The file descriptors are stored as a slice of [`OwnedFd`]s.
@@ -29,6 +35,49 @@ impl<'a> FileDescriptors<'a> {
     pub const unsafe fn new_raw(descriptors: &'a [RawFd], owned: bool) -> Self {
         unsafe { Self(UnalignedFdSlice::from_raw_fd_slice(descriptors, owned)) }
     }
+    /// Returns the number of file descriptors in the message.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.fds.len()
+    }
+    /// Returns `true` if the message contains no file descriptors, `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.fds.is_empty()
+    }
+    /// Converts the message into one which holds its file descriptors as [`OwnedFd`]s.
+    ///
+    /// If the file descriptors are owned by `self` (which is always the case for messages that were received from a socket), ownership is simply transferred to the return value. Otherwise, the file descriptors are duplicated.
+    ///
+    /// # System calls
+    /// - `fcntl` with `F_DUPFD_CLOEXEC`, only if `self` doesn't own the file descriptors
+    pub fn into_owned(mut self) -> io::Result<OwnedFileDescriptors> {
+        let fds = if self.0.owned {
+            // Disarm the drop handler first, since ownership is about to be transferred
+            self.0.owned = false;
+            self.0
+                .fds
+                .iter()
+                .map(|fd| unsafe {
+                    // SAFETY: we owned those and have just given up ownership
+                    fd.into_owned_fd()
+                })
+                .collect()
+        } else {
+            self.0
+                .fds
+                .iter()
+                .map(|fd| {
+                    let fd = unsafe {
+                        // SAFETY: the lifetime of the borrowed descriptors is tied to that of `self`
+                        BorrowedFd::borrow_raw(fd.to_raw())
+                    };
+                    fd.try_clone_to_owned()
+                })
+                .collect::<io::Result<_>>()?
+        };
+        Ok(OwnedFileDescriptors(fds))
+    }
 }
 impl ToCmsg for FileDescriptors<'_> {
     fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
@@ -69,6 +118,98 @@ impl<'a> FromCmsg<'a> for FileDescriptors<'a> {
     }
 }
 
+/// Ancillary data message that allows sending ownership of file descriptors over to another process, holding them as a `Vec` of [`OwnedFd`]s.
+///
+/// Unlike [`FileDescriptors`], this doesn't borrow from the ancillary data buffer, and the received file descriptors can be moved out of it individually, after which they can be converted into [`File`](std::fs::File), [`UnixStream`](std::os::unix::net::UnixStream) or whatever else implements `From<OwnedFd>`:
+/// ```no_run
+/// # #[cfg(unix)] {
+/// use interprocess::os::unix::udsocket::cmsg::ancillary::file_descriptors::OwnedFileDescriptors;
+/// use std::fs::File;
+/// # fn get_fds() -> OwnedFileDescriptors { unimplemented!() }
+///
+/// let fds: OwnedFileDescriptors = get_fds();
+/// let files = fds.into_iter().map(File::from).collect::<Vec<_>>();
+/// # }
+/// ```
+/// Any file descriptors which are not moved out are closed when the message is dropped.
+#[derive(Debug, Default)]
+pub struct OwnedFileDescriptors(Vec<OwnedFd>);
+impl OwnedFileDescriptors {
+    /// Constructs the ancillary data message from a `Vec` of file descriptors.
+    #[inline]
+    pub fn new(descriptors: Vec<OwnedFd>) -> Self {
+        Self(descriptors)
+    }
+    /// Borrows the file descriptors as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[OwnedFd] {
+        &self.0
+    }
+    /// Returns the number of file descriptors in the message.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Returns `true` if the message contains no file descriptors, `false` otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Unwraps the `Vec` of file descriptors.
+    #[inline]
+    pub fn into_inner(self) -> Vec<OwnedFd> {
+        self.0
+    }
+}
+impl From<Vec<OwnedFd>> for OwnedFileDescriptors {
+    #[inline]
+    fn from(descriptors: Vec<OwnedFd>) -> Self {
+        Self(descriptors)
+    }
+}
+impl From<OwnedFileDescriptors> for Vec<OwnedFd> {
+    #[inline]
+    fn from(descriptors: OwnedFileDescriptors) -> Self {
+        descriptors.0
+    }
+}
+impl IntoIterator for OwnedFileDescriptors {
+    type Item = OwnedFd;
+    type IntoIter = vec::IntoIter<OwnedFd>;
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl ToCmsg for OwnedFileDescriptors {
+    fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
+        let fds = unsafe {
+            // SAFETY: OwnedFd is repr(transparent) over RawFd
+            transmute::<&[OwnedFd], &[RawFd]>(&self.0)
+        };
+        let fds = unsafe {
+            // SAFETY: the descriptors are valid for as long as `self` is borrowed
+            UnalignedFdSlice::from_raw_fd_slice(fds, false)
+        };
+        let cmsg = unsafe {
+            // SAFETY: a bunch of file descriptors is all you need for a SCM_RIGHTS control message
+            Cmsg::new(LEVEL, FileDescriptors::TYPE, fds.as_bytes())
+        };
+        add_fn(cmsg);
+    }
+}
+impl<'a> FromCmsg<'a> for OwnedFileDescriptors {
+    type MalformedPayloadError = Infallible;
+
+    #[inline]
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
+        let fds = FileDescriptors::try_parse(cmsg)?;
+        Ok(fds
+            .into_owned()
+            .expect("taking ownership of received file descriptors cannot fail"))
+    }
+}
+
 type UnalignedFdBytes = [u8; size_of::<RawFd>()];
 /// Unaligned file descriptor with an initialization guarantee.
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -3,7 +3,6 @@
 //! This module features safe wrappers for well-defined types of Unix domain socket control messages, allowing for their serialization without the use of unsafe code. It also includes parsers for those types of control messages and a catch-all parser that can parse all control message types that are known to this module.
 
 // TODO SCM_CREDS2 from FreeBSD
-// TODO SCM_TIMESTAMP, also the one with nanosecond precision
 
 #[cfg_attr( // uds_ucred template
     feature = "doc_cfg",
@@ -25,9 +24,44 @@
 // FIXME only enabled on ucred, sockcred is disabled
 pub mod credentials;
 pub mod file_descriptors;
+#[cfg_attr( // uds_scm_security template
+    feature = "doc_cfg",
+    doc(cfg(any(
+        all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        ),
+        target_os = "android"
+    )))
+)]
+#[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+pub mod security;
+#[cfg_attr( // uds_scm_timestamp template
+    feature = "doc_cfg",
+    doc(cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "solaris",
+        target_os = "illumos"
+    )))
+)]
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+pub mod timestamp;
 
 mod dispatcher;
-pub use dispatcher::*;
+mod or;
+pub use {dispatcher::*, or::*};
 
 use super::*;
 use std::{
@@ -147,3 +181,17 @@ impl<E: Display> Display for ParseErrorKind<E> {
     }
 }
 impl<E: Debug + Display> Error for ParseErrorKind<E> {}
+
+/// A [`MalformedPayload`](ParseErrorKind::MalformedPayload) error indicating that the ancillary message size doesn't match that of the platform-specific structure it's supposed to contain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SizeMismatch {
+    expected: usize,
+    got: usize,
+}
+impl Display for SizeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { expected, got } = self;
+        write!(f, "ancillary payload size mismatch (expected {expected}, got {got})")
+    }
+}
+impl Error for SizeMismatch {}
@@ -0,0 +1,63 @@
+use super::*;
+
+/// A combinator which parses a control message as `L`, falling back to `R` if `L` rejects it because of a [level](ParseErrorKind::WrongLevel) or [type](ParseErrorKind::WrongType) mismatch.
+///
+/// This is how support for control messages which aren't known to this crate can be plugged into the [`Ancillary`] dispatcher: `Or<Ancillary<'a>, MyMessage>` parses everything `Ancillary` knows about and hands the rest to `MyMessage`. Since `Or` itself implements [`FromCmsg`], it can be nested to try any number of types in order. Using [`Cmsg`] as the last type produces a catch-all which never fails with a level or type mismatch:
+/// ```no_run
+/// # #[cfg(unix)] {
+/// use interprocess::os::unix::udsocket::cmsg::{ancillary::{Ancillary, Or}, Cmsg, CmsgRef};
+/// # fn get_buffer() -> CmsgRef<'static> { unimplemented!() }
+///
+/// let abuf: CmsgRef<'_> = get_buffer();
+/// for msg in abuf.decode_as::<Or<Ancillary<'_>, Cmsg<'_>>>() {
+///     match msg {
+///         Ok(Or::Left(known)) => println!("known control message: {known:?}"),
+///         Ok(Or::Right(unknown)) => println!("unknown control message: {unknown:?}"),
+///         Err(e) => println!("malformed control message: {e}"),
+///     }
+/// }
+/// # }
+/// ```
+///
+/// If both `L` and `R` reject the control message, the error returned by `R` is the one that gets propagated. Malformed payload errors are wrapped in `Or` as well, to tell which of the two types produced them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Or<L, R> {
+    /// The control message was successfully parsed as (or rejected as malformed by) the first type.
+    Left(L),
+    /// The control message was successfully parsed as (or rejected as malformed by) the second type.
+    Right(R),
+}
+impl<'a, L: FromCmsg<'a>, R: FromCmsg<'a>> FromCmsg<'a> for Or<L, R> {
+    type MalformedPayloadError = Or<L::MalformedPayloadError, R::MalformedPayloadError>;
+
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
+        match L::try_parse(cmsg) {
+            Ok(l) => Ok(Self::Left(l)),
+            Err(ParseError {
+                cmsg,
+                kind: ParseErrorKind::MalformedPayload(e),
+            }) => Err(ParseErrorKind::MalformedPayload(Or::Left(e)).wrap(cmsg)),
+            Err(ParseError { cmsg, .. }) => R::try_parse(cmsg)
+                .map(Self::Right)
+                .map_err(|e| e.map_payload_err(Or::Right)),
+        }
+    }
+}
+impl<L: Display, R: Display> Display for Or<L, R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left(l) => Display::fmt(l, f),
+            Self::Right(r) => Display::fmt(r, f),
+        }
+    }
+}
+impl<L: Debug + Display, R: Debug + Display> Error for Or<L, R> {}
+
+/// Accepts any control message without inspecting it, for use as a catch-all with [`Or`].
+impl<'a> FromCmsg<'a> for Cmsg<'a> {
+    type MalformedPayloadError = Infallible;
+    #[inline(always)]
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Infallible> {
+        Ok(cmsg)
+    }
+}
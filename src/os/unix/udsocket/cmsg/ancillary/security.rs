@@ -0,0 +1,66 @@
+//! [`Security`] and associated helper types.
+
+use super::*;
+use std::ffi::CStr;
+
+/// Ancillary data message carrying the security context (such as an SELinux label) of the peer process that sent the message. Only available on Linux.
+///
+/// To receive this message, the `SO_PASSSEC` socket option must be enabled, which can be done with the `.set_passsec()` method on Ud-socket types. After it's enabled, every receive operation that provides an ancillary data buffer will receive an instance of this message, provided that a Linux Security Module which supports security contexts is active. The kernel never accepts it from userspace, and thus it cannot be sent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Security<'a>(&'a [u8]);
+impl<'a> Security<'a> {
+    // Not exposed by the libc crate.
+    pub(super) const TYPE: c_int = 0x03;
+
+    /// Returns the security context as a byte string, without the trailing nul terminator if there is one.
+    #[inline]
+    pub fn label(&self) -> &'a [u8] {
+        match self.0.split_last() {
+            Some((0, rest)) => rest,
+            _ => self.0,
+        }
+    }
+    /// Returns the security context as a C string, or `None` if the kernel didn't terminate it with a nul byte or there is an interior nul byte.
+    #[inline]
+    pub fn as_c_str(&self) -> Option<&'a CStr> {
+        CStr::from_bytes_with_nul(self.0).ok()
+    }
+    /// Returns the security context as a UTF-8 string, or `None` if it isn't valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.label()).ok()
+    }
+}
+/// The kernel doesn't accept this message from userspace, so this is only useful for APIs other than Ud-sockets or for testing.
+impl ToCmsg for Security<'_> {
+    fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
+        let cmsg = unsafe {
+            // SAFETY: a security context is just a string
+            Cmsg::new(LEVEL, Self::TYPE, self.0)
+        };
+        add_fn(cmsg);
+    }
+}
+impl<'a> FromCmsg<'a> for Security<'a> {
+    type MalformedPayloadError = Infallible;
+
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Infallible> {
+        use ParseErrorKind::*;
+        let (lvl, ty) = (cmsg.cmsg_level(), cmsg.cmsg_type());
+        if lvl != LEVEL {
+            return Err(WrongLevel {
+                expected: Some(LEVEL),
+                got: lvl,
+            }
+            .wrap(cmsg));
+        }
+        if ty != Self::TYPE {
+            return Err(WrongType {
+                expected: Some(Self::TYPE),
+                got: ty,
+            }
+            .wrap(cmsg));
+        }
+        Ok(Self(cmsg.data()))
+    }
+}
@@ -0,0 +1,180 @@
+//! [`Timestamp`], [`TimestampNs`] and associated helper types.
+
+use super::*;
+use std::{
+    mem::size_of,
+    ptr, slice,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Ancillary data message carrying the time at which the kernel received the datagram, with microsecond precision.
+///
+/// To receive this message, the `SO_TIMESTAMP` socket option must be enabled, which can be done with [`UdSocket::set_timestamp()`](crate::os::unix::udsocket::UdSocket::set_timestamp). After it's enabled, every receive operation that provides an ancillary data buffer will receive an instance of this message. The kernel never accepts it from userspace, and thus it cannot be sent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timestamp(libc::timeval);
+impl Timestamp {
+    pub(super) const TYPE: c_int = libc::SCM_TIMESTAMP;
+
+    /// Returns the timestamp as a duration since the Unix epoch.
+    ///
+    /// Timestamps before the epoch are clamped to zero.
+    #[inline]
+    pub fn since_epoch(&self) -> Duration {
+        let usecs = u32::try_from(self.0.tv_usec).unwrap_or(0).min(999_999);
+        Duration::new(u64::try_from(self.0.tv_sec).unwrap_or(0), usecs * 1000)
+    }
+    /// Returns the timestamp as a [`SystemTime`].
+    #[inline]
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.since_epoch()
+    }
+    /// Returns the raw `timeval` structure received from the kernel.
+    #[inline(always)]
+    pub fn as_timeval(&self) -> libc::timeval {
+        self.0
+    }
+}
+/// The kernel doesn't accept this message from userspace, so this is only useful for APIs other than Ud-sockets or for testing.
+impl ToCmsg for Timestamp {
+    fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
+        let st_bytes = unsafe {
+            // SAFETY: well-initialized POD struct with #[repr(C)]
+            slice::from_raw_parts(
+                (&self.0 as *const libc::timeval).cast::<u8>(),
+                size_of::<libc::timeval>(),
+            )
+        };
+        let cmsg = unsafe {
+            // SAFETY: the payload is exactly what the kernel would put there
+            Cmsg::new(LEVEL, Self::TYPE, st_bytes)
+        };
+        add_fn(cmsg);
+    }
+}
+impl<'a> FromCmsg<'a> for Timestamp {
+    type MalformedPayloadError = SizeMismatch;
+
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, SizeMismatch> {
+        use ParseErrorKind::*;
+        let (lvl, ty) = (cmsg.cmsg_level(), cmsg.cmsg_type());
+        if lvl != LEVEL {
+            return Err(WrongLevel {
+                expected: Some(LEVEL),
+                got: lvl,
+            }
+            .wrap(cmsg));
+        }
+        if ty != Self::TYPE {
+            return Err(WrongType {
+                expected: Some(Self::TYPE),
+                got: ty,
+            }
+            .wrap(cmsg));
+        }
+        let (sz, expected) = (cmsg.data().len(), size_of::<libc::timeval>());
+        if sz != expected {
+            return Err(MalformedPayload(SizeMismatch { expected, got: sz }).wrap(cmsg));
+        }
+
+        let timeval = unsafe {
+            // SAFETY: we just checked for the size match, and timeval is POD. The payload isn't guaranteed to be
+            // aligned, hence the unaligned read.
+            ptr::read_unaligned(cmsg.data().as_ptr().cast::<libc::timeval>())
+        };
+        Ok(Self(timeval))
+    }
+}
+
+/// Ancillary data message carrying the time at which the kernel received the datagram, with nanosecond precision. Only available on Linux.
+///
+/// To receive this message, the `SO_TIMESTAMPNS` socket option must be enabled, which can be done with [`UdSocket::set_timestamp_ns()`](crate::os::unix::udsocket::UdSocket::set_timestamp_ns). After it's enabled, every receive operation that provides an ancillary data buffer will receive an instance of this message. The kernel never accepts it from userspace, and thus it cannot be sent.
+#[cfg_attr( // uds_scm_timestampns template
+    feature = "doc_cfg",
+    doc(cfg(all(
+        target_os = "linux",
+        any(
+            target_env = "gnu",
+            target_env = "musl",
+            target_env = "musleabi",
+            target_env = "musleabihf"
+        )
+    )))
+)]
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimestampNs(libc::timespec);
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+impl TimestampNs {
+    pub(super) const TYPE: c_int = libc::SCM_TIMESTAMPNS;
+
+    /// Returns the timestamp as a duration since the Unix epoch.
+    ///
+    /// Timestamps before the epoch are clamped to zero.
+    #[inline]
+    pub fn since_epoch(&self) -> Duration {
+        let nsecs = u32::try_from(self.0.tv_nsec).unwrap_or(0).min(999_999_999);
+        Duration::new(u64::try_from(self.0.tv_sec).unwrap_or(0), nsecs)
+    }
+    /// Returns the timestamp as a [`SystemTime`].
+    #[inline]
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.since_epoch()
+    }
+    /// Returns the raw `timespec` structure received from the kernel.
+    #[inline(always)]
+    pub fn as_timespec(&self) -> libc::timespec {
+        self.0
+    }
+}
+/// The kernel doesn't accept this message from userspace, so this is only useful for APIs other than Ud-sockets or for testing.
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+impl ToCmsg for TimestampNs {
+    fn add_to_buffer(&self, add_fn: impl FnOnce(Cmsg<'_>)) {
+        let st_bytes = unsafe {
+            // SAFETY: well-initialized POD struct with #[repr(C)]
+            slice::from_raw_parts(
+                (&self.0 as *const libc::timespec).cast::<u8>(),
+                size_of::<libc::timespec>(),
+            )
+        };
+        let cmsg = unsafe {
+            // SAFETY: the payload is exactly what the kernel would put there
+            Cmsg::new(LEVEL, Self::TYPE, st_bytes)
+        };
+        add_fn(cmsg);
+    }
+}
+#[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+impl<'a> FromCmsg<'a> for TimestampNs {
+    type MalformedPayloadError = SizeMismatch;
+
+    fn try_parse(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, SizeMismatch> {
+        use ParseErrorKind::*;
+        let (lvl, ty) = (cmsg.cmsg_level(), cmsg.cmsg_type());
+        if lvl != LEVEL {
+            return Err(WrongLevel {
+                expected: Some(LEVEL),
+                got: lvl,
+            }
+            .wrap(cmsg));
+        }
+        if ty != Self::TYPE {
+            return Err(WrongType {
+                expected: Some(Self::TYPE),
+                got: ty,
+            }
+            .wrap(cmsg));
+        }
+        let (sz, expected) = (cmsg.data().len(), size_of::<libc::timespec>());
+        if sz != expected {
+            return Err(MalformedPayload(SizeMismatch { expected, got: sz }).wrap(cmsg));
+        }
+
+        let timespec = unsafe {
+            // SAFETY: we just checked for the size match, and timespec is POD. The payload isn't guaranteed to be
+            // aligned, hence the unaligned read.
+            ptr::read_unaligned(cmsg.data().as_ptr().cast::<libc::timespec>())
+        };
+        Ok(Self(timespec))
+    }
+}
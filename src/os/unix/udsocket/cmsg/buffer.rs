@@ -34,11 +34,17 @@ impl CmsgBuffer {
     /// Mutably borrows the control message buffer. The resulting type retains the validity guarantee, but does not feed the initialization cursor back into the owned buffer object.
     #[inline(always)]
     pub fn as_mut(&mut self) -> CmsgMut<'_> {
+        let init_len = self.0.len();
         let buf = unsafe {
             // SAFETY: CmsgMut would never do anything bad to our lil' buffer, would it?
             self.as_uninit_slice_mut()
         };
-        CmsgMut::new(buf)
+        let mut mmut = CmsgMut::new(buf);
+        unsafe {
+            // SAFETY: the initialized part of the Vec holds valid ancillary data as per our own guarantee
+            mmut.set_init_len(init_len);
+        }
+        mmut
     }
 
     /// Converts the given message object to a [`Cmsg`] and adds it to the buffer, advances the initialization cursor of `self` such that the next message, if one is added, will appear after it, and returns how much the cursor was advanced by (i.e. how many more contiguous bytes in the beginning of `self`'s buffer are now well-initialized).
@@ -49,6 +55,12 @@ impl CmsgBuffer {
         msg.add_to_buffer(|cmsg| ret = self.add_raw_message(cmsg));
         ret
     }
+    /// Adds every message yielded by the given iterator to the buffer in order, reserving space as needed, and returns the total amount by which the initialization cursor was advanced.
+    ///
+    /// Each message is padded to the alignment required by the system C library, so messages of different types (say, [`Ancillary::FileDescriptors`](ancillary::Ancillary::FileDescriptors) and [`Ancillary::Credentials`](ancillary::Ancillary::Credentials)) can be freely mixed.
+    pub fn add_messages<'m, T: ToCmsg + 'm>(&mut self, msgs: impl IntoIterator<Item = &'m T>) -> usize {
+        msgs.into_iter().map(|msg| self.add_message(msg)).sum()
+    }
     /// Adds the specified control message to the buffer, advances the initialization cursor of `self` such that the next message, if one is added, will appear after it, and returns how much the cursor was advanced by (i.e. how many more contiguous bytes in the beginning of `self`'s buffer are now well-initialized).
     ///
     /// Using the return value isn't strictly necessary – calling `.add_raw_message()` again will correctly add one more message to the buffer.
@@ -88,6 +100,20 @@ impl CmsgBuffer {
         }
     }
 }
+impl<'m, T: ToCmsg + 'm> Extend<&'m T> for CmsgBuffer {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'m T>>(&mut self, iter: I) {
+        self.add_messages(iter);
+    }
+}
+impl<'m, T: ToCmsg + 'm> FromIterator<&'m T> for CmsgBuffer {
+    /// Creates a buffer and fills it with the messages via [`add_messages()`](Self::add_messages).
+    fn from_iter<I: IntoIterator<Item = &'m T>>(iter: I) -> Self {
+        let mut buf = Self::new(0);
+        buf.add_messages(iter);
+        buf
+    }
+}
 impl From<Vec<u8>> for CmsgBuffer {
     #[inline]
     fn from(buf: Vec<u8>) -> Self {
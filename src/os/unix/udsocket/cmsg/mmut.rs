@@ -5,11 +5,11 @@ use super::{
     ancillary::ToCmsg,
     *,
 };
-use libc::{c_char, c_int, c_uint, c_void, cmsghdr, msghdr, CMSG_DATA, CMSG_FIRSTHDR, CMSG_LEN, CMSG_NXTHDR};
+use libc::{c_char, c_int, c_uint, c_void, cmsghdr, msghdr, CMSG_DATA, CMSG_FIRSTHDR, CMSG_LEN, CMSG_SPACE};
 use std::{
+    cmp::min,
     io,
     mem::{size_of, transmute, zeroed, MaybeUninit},
-    num::NonZeroUsize,
     ptr, slice,
 };
 
@@ -21,7 +21,7 @@ pub struct CmsgMut<'a> {
     // without straying too far from what the manpage says is permissible
     buf: &'a mut [MaybeUninit<u8>],
     init_len: usize,
-    cmsghdr_offset: Option<NonZeroUsize>,
+    cmsghdr_offset: Option<usize>,
 }
 impl<'a> CmsgMut<'a> {
     /// Creates a control message buffer from the given uninitialized slice.
@@ -146,36 +146,33 @@ impl<'a> CmsgMut<'a> {
         Some(first)
     }
 
-    /// Returns a reference to the next `cmsghdr` after the one specified by `offset`.
+    /// Returns a reference to the next `cmsghdr` after the one specified by `offset`, or `None` if it wouldn't fit into `buf`.
+    ///
+    /// `CMSG_NXTHDR` is deliberately not used here, since it inspects the `cmsg_len` of the next header, which is very much uninitialized memory if we're about to put a new control message there.
     ///
     /// # Safety
-    /// `offset` must point to a `cmsghdr`, be within `self.buf` and fit into an `isize`.
-    unsafe fn next_cmsghdr<'x>(
-        buf: &'x mut [MaybeUninit<u8>],
-        dummy_msghdr: &msghdr,
-        offset: usize,
-    ) -> Option<&'x mut MaybeUninit<cmsghdr>> {
-        let origin = buf.as_ptr_range().start.cast::<u8>();
-
-        let p_cmsghdr = unsafe {
+    /// `offset` must point to an initialized `cmsghdr`, be within `buf` and fit into an `isize`.
+    unsafe fn next_cmsghdr(buf: &mut [MaybeUninit<u8>], offset: usize) -> Option<&mut MaybeUninit<cmsghdr>> {
+        let cmsg_len = unsafe {
             // SAFETY: as per safety contract
-            origin.add(offset).cast::<cmsghdr>()
+            ptr::read_unaligned(buf.as_ptr().add(offset).cast::<cmsghdr>()).cmsg_len as usize
         };
-        let next_ptr = unsafe { CMSG_NXTHDR(dummy_msghdr, p_cmsghdr) }.cast::<MaybeUninit<cmsghdr>>();
-        if next_ptr.is_null() {
-            // Return early, since we have absolutely nothing to do if there's no next cmsghdr
+        let hdr_len = unsafe { CMSG_LEN(0) } as usize;
+        if cmsg_len < hdr_len {
+            // Malformed header, can't go any further than this
+            return None;
+        }
+        let payload_len = c_uint::try_from(cmsg_len - hdr_len).ok()?;
+        let next_offset = offset.checked_add(unsafe { CMSG_SPACE(payload_len) } as usize)?;
+        if next_offset.checked_add(size_of::<cmsghdr>())? > buf.len() {
             return None;
         }
-        debug_assert!(
-            next_ptr.cast::<u8>().cast_const() >= origin,
-            "CMSG_NXTHDR gave a pointer outside of the buffer"
-        );
 
         // No zero-fill here, `initialize_post_payload()` does that bit.
 
         unsafe {
-            // SAFETY: we trust the implementation of CMSG_NXTHDR to do its thing correctly
-            Some(&mut *next_ptr)
+            // SAFETY: we just checked that the next cmsghdr is within the buffer
+            Some(&mut *buf.as_mut_ptr().add(next_offset).cast::<MaybeUninit<cmsghdr>>())
         }
     }
     /// Finds the last `cmsghdr` in a buffer that was previously initialized by some other routine, most likely the kernel (via `recv_ancillary`).
@@ -197,9 +194,9 @@ impl<'a> CmsgMut<'a> {
             Self::first_cmsghdr(self.buf, dummy_msghdr).map(|r| tooffset(r as *mut MaybeUninit<cmsghdr>))
         };
         while let Some(voffset) = offset {
-            let nxt = unsafe { Self::next_cmsghdr(self.buf, dummy_msghdr, voffset) };
+            let nxt = unsafe { Self::next_cmsghdr(&mut self.buf[..self.init_len], voffset) };
             offset = nxt.map(|r| tooffset(r as *mut MaybeUninit<cmsghdr>));
-            self.cmsghdr_offset = NonZeroUsize::new(voffset);
+            self.cmsghdr_offset = Some(voffset);
         }
     }
     /// Returns a reference to the next `cmsghdr`, depending on the value of `self.cmghdr_offset`: if it's `None`, uses `prepare_first_cmsghdr()`, and if it's `Some`, uses `CMSG_NXTHDR()`.
@@ -215,7 +212,7 @@ impl<'a> CmsgMut<'a> {
             }
             match self.cmsghdr_offset {
                 None => Self::prepare_first_cmsghdr(self.buf, dummy_msghdr),
-                Some(offset) => Self::next_cmsghdr(self.buf, dummy_msghdr, offset.get()),
+                Some(offset) => Self::next_cmsghdr(self.buf, offset),
             }?
         };
 
@@ -228,7 +225,7 @@ impl<'a> CmsgMut<'a> {
         debug_assert!(offset >= 0);
         let offset = offset as usize;
 
-        self.cmsghdr_offset = Some(NonZeroUsize::new(offset).unwrap());
+        self.cmsghdr_offset = Some(offset);
         Some(cmsghdr)
     }
     fn fill_cmsghdr(
@@ -273,7 +270,7 @@ impl<'a> CmsgMut<'a> {
             // We aren't actually required to do a null check, but let's do one here just in case.
             return None;
         }
-        if data_end >= one_past_end {
+        if data_end > one_past_end {
             // The more important check here, the buffer overflow guard.
             return None;
         }
@@ -281,7 +278,7 @@ impl<'a> CmsgMut<'a> {
         let one_past_hdr = unsafe {
             // SAFETY: we checked for buffer overrun just above, so we know that the byte after the cmsghdr is inside
             // the allocated object (besides, .offset() even allows you to go one byte past).
-            hdr.cast::<u8>().cast_mut().offset(1)
+            hdr.offset(1).cast::<u8>().cast_mut()
         };
         if data_start > one_past_hdr {
             unsafe {
@@ -298,57 +295,37 @@ impl<'a> CmsgMut<'a> {
             slice::from_raw_parts_mut(data_start.cast::<MaybeUninit<u8>>(), msg_len)
         })
     }
-    /// Initializes the bytes that are either padding between the current control message and the next or dead space at the end of the buffer, returning how much `self`'s init cursor needs to be advanced by.
+    /// Initializes the bytes that are padding between the current control message and the next (or the end of the buffer, whichever comes first), returning how much `self`'s init cursor needs to be advanced by.
     ///
     /// # Safety
-    /// - The dummy `msghdr` must be the product of `make_msghdr(true)` called on the exact same bytecrop of `self` (same base address, same length).
     /// - `cmsghdr` must be non-null, must point somewhere within `self`'s buffer and must be, well, the `cmsghdr` the payload of which has just been filled in.
-    /// - `one_past_end_of_payload` must be a pointer to the byte directly past the end of the data payload, the base address of which is the output of `CMSG_DATA` and the length of which is the output of `CMSG_LEN`.
+    /// - `one_past_end_of_payload` must be a pointer to the byte directly past the end of the data payload, the base address of which is the output of `CMSG_DATA` and the length of which is `msg_len`.
     unsafe fn initialize_post_payload(
         &mut self,
-        dummy_msghdr: &msghdr,
         cmsghdr: *const cmsghdr,
         one_past_end_of_payload: *mut u8,
+        msg_len: c_uint,
     ) -> usize {
-        let origin_of_uninit = unsafe {
-            // SAFETY: the init cursor isn't borked... is it?
-            self.buf.as_ptr_range().start.cast::<u8>().add(self.init_len)
-        };
-        let next = unsafe { CMSG_NXTHDR(dummy_msghdr, cmsghdr) }.cast::<u8>();
-
-        let (fill_len, init_cur_offset) = if !next.is_null() {
-            let fill_len = unsafe {
-                // SAFETY: we made sure that both are non-null and within the same allocated object
-                next.offset_from(one_past_end_of_payload)
-            };
-            let init_cur_offset = unsafe {
-                // SAFETY: same here.
-                next.offset_from(origin_of_uninit)
-            };
-            (fill_len, init_cur_offset)
-        } else {
-            let one_past_end = self.buf.as_mut_ptr_range().end.cast::<u8>();
-            let fill_len = unsafe {
-                // SAFETY: we, again, made sure that both pointers are non-null and are within the same allocated
-                // object, but this time one of them is actually one byte past, which is fine because the .offset()
-                // family allows that.
-                one_past_end.offset_from(one_past_end_of_payload.cast_const())
-            };
-            let init_cur_offset = unsafe {
-                // SAFETY: same here.
-                one_past_end.offset_from(origin_of_uninit)
-            };
-            (fill_len, init_cur_offset)
+        let origin = self.buf.as_ptr_range().start.cast::<u8>();
+        let (hdr_offset, payload_end_offset) = unsafe {
+            // SAFETY: both pointers are within the buffer as per the safety contract
+            (
+                cmsghdr.cast::<u8>().offset_from(origin),
+                one_past_end_of_payload.cast_const().offset_from(origin),
+            )
         };
+        debug_assert!(hdr_offset >= 0 && payload_end_offset >= hdr_offset);
+        let space = unsafe { CMSG_SPACE(msg_len) } as usize;
+        let end_offset = min(hdr_offset as usize + space, self.buf.len());
 
-        debug_assert!(fill_len >= 0);
+        let fill_len = end_offset - payload_end_offset as usize;
         unsafe {
             // SAFETY: pointer validity is ensured, alignment requirements are irrelevant for bytes.
-            ptr::write_bytes(one_past_end_of_payload, 0, fill_len as usize);
+            ptr::write_bytes(one_past_end_of_payload, 0, fill_len);
         };
 
-        debug_assert!(init_cur_offset >= 0);
-        init_cur_offset as usize
+        debug_assert!(end_offset >= self.init_len);
+        end_offset - self.init_len
     }
 
     /// Converts the given message object to a [`Cmsg`] and adds it to the buffer, advances the initialization cursor of `self` such that the next message, if one is added, will appear after it, and returns how much the cursor was advanced by (i.e. how many more contiguous bytes in the beginning of `self`'s buffer are now well-initialized).
@@ -359,6 +336,19 @@ impl<'a> CmsgMut<'a> {
         msg.add_to_buffer(|cmsg| ret = self.add_raw_message(cmsg));
         ret
     }
+    /// Adds every message yielded by the given iterator to the buffer in order and returns the total amount by which the initialization cursor was advanced.
+    ///
+    /// Stops at the first message that doesn't fit, leaving it and all the ones after it out of the buffer.
+    pub fn add_messages<'m, T: ToCmsg + 'm>(&mut self, msgs: impl IntoIterator<Item = &'m T>) -> usize {
+        let mut total = 0;
+        for msg in msgs {
+            match self.add_message(msg) {
+                0 => break,
+                delta => total += delta,
+            }
+        }
+        total
+    }
     /// Adds the specified control message to the buffer, advances the initialization cursor of `self` such that the next message, if one is added, will appear after it, and returns how much the cursor was advanced by (i.e. how many more contiguous bytes in the beginning of `self`'s buffer are now well-initialized).
     ///
     /// If there isn't enough space, 0 is returned.
@@ -396,9 +386,8 @@ impl<'a> CmsgMut<'a> {
 
         let one_past_end_of_payload = data.as_mut_ptr_range().end.cast::<u8>();
         let init_cur_incr = unsafe {
-            // SAFETY: dummy_msghdr is the correct thing, cmsghdr is as well, one_past_end_of_payload being correct
-            // is the public API of slices
-            self.initialize_post_payload(&dummy_msghdr, cmsghdr, one_past_end_of_payload)
+            // SAFETY: cmsghdr is the correct thing, one_past_end_of_payload being correct is the public API of slices
+            self.initialize_post_payload(cmsghdr, one_past_end_of_payload, msg_len)
         };
 
         self.init_len += init_cur_incr;
@@ -2,8 +2,7 @@
 //!
 //! This module contains a generic safe framework for control messages – not just for Unix domain sockets, but for any `recvmsg`/`sendmsg`-based API in the Unix socket architecture.
 //!
-//! The [`ancillary`] module contains safe wrappers that can help you correctly initialize and parse ancillary data control messages; its types can then be fed into [`CmsgBuffer`] or [`CmsgMut`] via the `.add_message()` method on both of those structs.
-// TODO parser
+//! The [`ancillary`] module contains safe wrappers that can help you correctly initialize and parse ancillary data control messages; its types can then be fed into [`CmsgBuffer`] or [`CmsgMut`] via the `.add_message()` and `.add_messages()` methods on both of those structs. Received control message buffers can then be walked with [`CmsgRef::decode()`], which yields one parsed [`Ancillary`](ancillary::Ancillary) per message.
 
 pub mod ancillary;
 
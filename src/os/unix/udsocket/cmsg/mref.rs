@@ -1,6 +1,6 @@
 use super::{
     super::util::{to_msghdr_controllen, DUMMY_MSGHDR},
-    ancillary::{Ancillary, FromCmsg, MalformedPayload, ParseError},
+    ancillary::{Ancillary, FromCmsg, MalformedPayload, ParseError, ParseResult},
     *,
 };
 use libc::{c_void, cmsghdr, CMSG_DATA, CMSG_FIRSTHDR, CMSG_NXTHDR};
@@ -9,7 +9,29 @@ use std::{cmp::min, io, slice};
 /// An immutable reference to a control message buffer that allows for decoding of ancillary data messages.
 ///
 /// The [`decode()`](Self::decode) iterator allows for easy decoding, while [`cmsgs()`](Self::cmsgs) provides low-level access to the raw ancillary message data.
-// TODO decoding example
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use interprocess::os::unix::udsocket::{
+///     cmsg::{ancillary::Ancillary, CmsgBuffer},
+///     UdSocket,
+/// };
+///
+/// let socket = UdSocket::bind("/tmp/example.sock")?;
+/// let (mut buf, mut abuf) = ([0; 128], CmsgBuffer::new(256));
+/// let mut abuf = abuf.as_mut();
+/// socket.recv_ancillary(&mut buf, &mut abuf)?;
+///
+/// for msg in abuf.as_ref().decode() {
+///     match msg {
+///         Ok(Ancillary::FileDescriptors(fds)) => println!("Received {} file descriptors", fds.len()),
+///         Ok(other) => println!("Received {:?}", other),
+///         Err(e) => eprintln!("Malformed control message: {}", e),
+///     }
+/// }
+/// # Ok(()) }
+/// ```
 #[derive(Copy, Clone, Debug)]
 pub struct CmsgRef<'a>(&'a [u8]);
 impl<'a> CmsgRef<'a> {
@@ -49,6 +71,11 @@ impl<'a> CmsgRef<'a> {
     pub fn decode(self) -> impl Iterator<Item = Result<Ancillary<'a>, ParseError<'a, MalformedPayload>>> {
         self.cmsgs().map(Ancillary::try_parse)
     }
+    /// Returns an iterator that wraps [`cmsgs()`](Self::cmsgs) and decodes them into the given type, which can be used to parse control messages that [`Ancillary`] doesn't know about. See [`Or`](ancillary::Or) for a way to combine such types with `Ancillary`.
+    #[inline]
+    pub fn decode_as<T: FromCmsg<'a>>(self) -> impl Iterator<Item = ParseResult<'a, T, T::MalformedPayloadError>> {
+        self.cmsgs().map(T::try_parse)
+    }
 
     pub(crate) fn fill_msghdr(&self, hdr: &mut msghdr) -> io::Result<()> {
         hdr.msg_control = self.0.as_ptr().cast::<c_void>().cast_mut();
@@ -106,7 +133,10 @@ impl<'a> Iterator for Cmsgs<'a> {
             // Buffer overflow check because some OSes (such as everyone's favorite putrid hellspawn macOS) don't
             // even fucking clip the fucking cmsg_len thing to the buffer end as specified by msg_controllen.
             // Source: https://gist.github.com/kentonv/bc7592af98c68ba2738f4436920868dc
-            let len = min(cmsghdr.cmsg_len as isize, max_len);
+            // cmsg_len also counts the header (and whatever padding there is between it and the payload), so that
+            // has to be subtracted to get the length of the payload itself.
+            let hdr_len = dptr.offset_from(self.cur.cast::<u8>());
+            let len = min(cmsghdr.cmsg_len as isize - hdr_len, max_len).max(0);
 
             // SAFETY: we trust CMSG_DATA; the init guarantee comes from CmsgRef containing a slice of initialized data
             slice::from_raw_parts(dptr, len as usize)
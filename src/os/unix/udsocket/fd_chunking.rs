@@ -0,0 +1,99 @@
+//! Splitting of large file descriptor transfers into several `sendmsg` calls and their reassembly on the receiving end.
+//!
+//! The kernel refuses to transfer more than [`SCM_MAX_FD`] file descriptors in one message. To get around this, every
+//! chunk but the last one is sent with exactly one byte of the main data and exactly `SCM_MAX_FD` descriptors, while the
+//! last one carries the rest of the data and fewer than `SCM_MAX_FD` descriptors (possibly none at all). The receiving
+//! end thus knows that more descriptors are coming whenever it gets a full chunk.
+
+use super::{
+    cmsg::{
+        ancillary::{
+            file_descriptors::{FileDescriptors, OwnedFileDescriptors, SCM_MAX_FD},
+            Ancillary,
+        },
+        CmsgBuffer, CmsgRef,
+    },
+    RecvAncillaryResult,
+};
+use libc::{c_int, c_uint, CMSG_SPACE};
+use std::{
+    io,
+    mem::size_of,
+    os::fd::{BorrowedFd, OwnedFd},
+};
+
+/// Extra space in the receive buffer for control messages that the socket attaches on its own, such as credentials.
+const EXTRA_CMSG_SPACE: usize = 128;
+
+/// Splits the main data and the file descriptors into pairs, each of which is to be sent with one `sendmsg` call.
+pub(super) fn chunks<'b, 'f, 'fd>(
+    buf: &'b [u8],
+    fds: &'f [BorrowedFd<'fd>],
+) -> io::Result<impl Iterator<Item = (&'b [u8], &'f [BorrowedFd<'fd>])>> {
+    let full_chunks = fds.len() / SCM_MAX_FD;
+    if full_chunks > 0 && buf.len() <= full_chunks {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "sending {} file descriptors requires at least {} bytes of main data to carry them",
+                fds.len(),
+                full_chunks + 1,
+            ),
+        ));
+    }
+    let (head_data, last_data) = buf.split_at(full_chunks);
+    let (head_fds, last_fds) = fds.split_at(full_chunks * SCM_MAX_FD);
+    let head = head_data.chunks(1).zip(head_fds.chunks(SCM_MAX_FD));
+    Ok(head.chain(Some((last_data, last_fds))))
+}
+
+/// Encodes one chunk of file descriptors produced by [`chunks()`].
+pub(super) fn encode(fds: &[BorrowedFd<'_>]) -> CmsgBuffer {
+    let mut abuf = CmsgBuffer::new(0);
+    if !fds.is_empty() {
+        abuf.add_message(&FileDescriptors::new(fds));
+    }
+    abuf
+}
+
+/// Collects the file descriptors and tracks the amount of main data received over the course of several `recvmsg` calls.
+#[derive(Debug, Default)]
+pub(super) struct Reassembler {
+    fds: Vec<OwnedFd>,
+    filled: usize,
+}
+impl Reassembler {
+    /// Allocates a control message buffer big enough for one chunk.
+    pub fn cmsg_buffer() -> CmsgBuffer {
+        let fd_space = unsafe { CMSG_SPACE((SCM_MAX_FD * size_of::<c_int>()) as c_uint) } as usize;
+        CmsgBuffer::new(fd_space + EXTRA_CMSG_SPACE)
+    }
+    /// Returns how many bytes of main data have been received so far.
+    pub fn filled(&self) -> usize {
+        self.filled
+    }
+    /// Processes the result of one `recvmsg` call and returns whether another chunk is to be expected.
+    pub fn feed(&mut self, rslt: RecvAncillaryResult, abuf: CmsgRef<'_>, buf_len: usize) -> io::Result<bool> {
+        let mut received = 0;
+        for msg in abuf.decode() {
+            // Nothing but SCM_RIGHTS can own resources, and other messages are of no interest here.
+            if let Ok(Ancillary::FileDescriptors(fds)) = msg {
+                let fds = fds.into_owned()?;
+                received += fds.len();
+                self.fds.extend(fds);
+            }
+        }
+        self.filled += rslt.size;
+        if rslt.ancillary_truncated {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "control message buffer was truncated, some file descriptors have been lost",
+            ));
+        }
+        Ok(received == SCM_MAX_FD && rslt.size != 0 && self.filled < buf_len)
+    }
+    /// Returns the total amount of main data received and all the file descriptors.
+    pub fn finish(self) -> (usize, OwnedFileDescriptors) {
+        (self.filled, OwnedFileDescriptors::new(self.fds))
+    }
+}
@@ -0,0 +1,567 @@
+//! Unix domain socket I/O through `io_uring`, which submits many operations to the kernel and collects their results
+//! with a single system call.
+//!
+//! With the readiness-based approach of the blocking and asynchronous socket types, every send and receive is a system
+//! call of its own, and with nonblocking sockets, so is every wait for readiness. An [`IoUring`] instead places a batch
+//! of [operations](Op) in a ring buffer shared with the kernel, and a single `io_uring_enter` call both submits them and
+//! waits for all of them to complete. Listeners can also [accept](IoUring::accept_multishot) many connections with a
+//! single request, which the kernel keeps serving until it's cancelled.
+//!
+//! The ring is a thin one: operations are submitted and waited for in the same call, which means that the buffers they
+//! borrow are never used by the kernel after the call returns, and no runtime is needed to drive the ring. Only plain
+//! sends and receives are supported – ancillary data still requires the regular socket types.
+//!
+//! `io_uring` requires Linux 5.6 or newer, and multishot accepting requires Linux 5.19 or newer. On older kernels, or if
+//! `io_uring` is disabled by the system administrator or a seccomp filter, [`IoUring::new()`] or the unsupported
+//! operations fail.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::os::unix::udsocket::{
+//!     io_uring::{IoUring, Op},
+//!     UdStream,
+//! };
+//!
+//! let conn = UdStream::connect("/tmp/example.sock")?;
+//! let mut ring = IoUring::new(64)?;
+//! let msgs: Vec<String> = (0..64).map(|i| format!("Message #{i}\n")).collect();
+//! // One system call for all 64 sends.
+//! let mut ops: Vec<Op<'_>> = msgs.iter().map(|msg| Op::send(&conn, msg.as_bytes())).collect();
+//! ring.submit(&mut ops)?;
+//! for op in ops {
+//!     op.take_result().unwrap()?;
+//! }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{c_wrappers, UdStream, UdStreamListener};
+use libc::{c_int, c_long, c_uint, c_void};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io, mem,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd},
+    process, ptr,
+    sync::atomic::{AtomicU32, Ordering::*},
+};
+
+const IORING_OFF_SQ_RING: i64 = 0;
+const IORING_OFF_CQ_RING: i64 = 0x8000000;
+const IORING_OFF_SQES: i64 = 0x10000000;
+const IORING_ENTER_GETEVENTS: c_uint = 1 << 0;
+const IORING_OP_ACCEPT: u8 = 13;
+const IORING_OP_ASYNC_CANCEL: u8 = 14;
+const IORING_OP_SEND: u8 = 26;
+const IORING_OP_RECV: u8 = 27;
+const IORING_ACCEPT_MULTISHOT: u16 = 1 << 0;
+const IORING_CQE_F_MORE: u32 = 1 << 1;
+
+/// The `user_data` of the multishot accept request, which can't collide with the indices of regular operations, since
+/// those are limited by the size of the ring.
+const ACCEPT_USER_DATA: u64 = u64::MAX;
+/// The `user_data` of cancellation requests.
+const CANCEL_USER_DATA: u64 = u64::MAX - 1;
+
+#[repr(C)]
+#[derive(Default)]
+struct SqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    flags: u32,
+    dropped: u32,
+    array: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+#[repr(C)]
+#[derive(Default)]
+struct CqringOffsets {
+    head: u32,
+    tail: u32,
+    ring_mask: u32,
+    ring_entries: u32,
+    overflow: u32,
+    cqes: u32,
+    flags: u32,
+    resv1: u32,
+    user_addr: u64,
+}
+#[repr(C)]
+#[derive(Default)]
+struct Params {
+    sq_entries: u32,
+    cq_entries: u32,
+    flags: u32,
+    sq_thread_cpu: u32,
+    sq_thread_idle: u32,
+    features: u32,
+    wq_fd: u32,
+    resv: [u32; 3],
+    sq_off: SqringOffsets,
+    cq_off: CqringOffsets,
+}
+/// A submission queue entry, with the unions of the kernel's definition reduced to the members used here.
+#[repr(C)]
+#[derive(Default)]
+struct Sqe {
+    opcode: u8,
+    flags: u8,
+    ioprio: u16,
+    fd: i32,
+    off: u64,
+    addr: u64,
+    len: u32,
+    op_flags: u32,
+    user_data: u64,
+    buf_index: u16,
+    personality: u16,
+    file_index: i32,
+    addr3: u64,
+    pad: u64,
+}
+#[repr(C)]
+struct Cqe {
+    user_data: u64,
+    res: i32,
+    flags: u32,
+}
+
+/// A memory mapping of one of the parts of the ring, unmapped when dropped.
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+impl Mapping {
+    fn new(fd: BorrowedFd<'_>, offset: i64, len: usize) -> io::Result<Self> {
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd.as_raw_fd(),
+                offset,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { ptr: ptr.cast(), len })
+    }
+    /// Returns a pointer to the value at the given offset, as reported by the kernel.
+    fn at<T>(&self, offset: u32) -> *mut T {
+        self.ptr.wrapping_add(offset as usize).cast()
+    }
+}
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr.cast(), self.len) };
+    }
+}
+
+/// An `io_uring` instance for performing socket I/O in batches.
+///
+/// See the [module-level documentation](self) for more.
+pub struct IoUring {
+    // The mappings are declared before the descriptor so that they're unmapped before it's closed.
+    sq_ring: Mapping,
+    cq_ring: Mapping,
+    sqes: Mapping,
+    params: Params,
+    /// The `user_data` of every request which has been queued and hasn't completed yet.
+    pending: Vec<u64>,
+    fd: OwnedFd,
+}
+// SAFETY: the ring is only ever accessed through a mutable reference, and the kernel doesn't care which thread does
+// that.
+unsafe impl Send for IoUring {}
+unsafe impl Sync for IoUring {}
+impl IoUring {
+    /// Creates a ring which can hold the given number of operations, which is rounded up to a power of two.
+    ///
+    /// Fails with [`Unsupported`](io::ErrorKind::Unsupported) if the kernel doesn't support `io_uring` or doesn't let
+    /// the process use it.
+    ///
+    /// # System calls
+    /// - `io_uring_setup`
+    /// - `mmap`
+    pub fn new(entries: u32) -> io::Result<Self> {
+        let mut params = Params::default();
+        let fd = unsafe { libc::syscall(libc::SYS_io_uring_setup, entries as c_long, &mut params as *mut Params) };
+        if fd == -1 {
+            let e = io::Error::last_os_error();
+            return Err(match e.raw_os_error() {
+                Some(libc::ENOSYS) | Some(libc::EPERM) => io::Error::new(io::ErrorKind::Unsupported, e),
+                _ => e,
+            });
+        }
+        // SAFETY: we just created the file descriptor
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as c_int) };
+
+        let sq_len = params.sq_off.array as usize + params.sq_entries as usize * mem::size_of::<u32>();
+        let cq_len = params.cq_off.cqes as usize + params.cq_entries as usize * mem::size_of::<Cqe>();
+        let sq_ring = Mapping::new(fd.as_fd(), IORING_OFF_SQ_RING, sq_len)?;
+        let cq_ring = Mapping::new(fd.as_fd(), IORING_OFF_CQ_RING, cq_len)?;
+        let sqes = Mapping::new(
+            fd.as_fd(),
+            IORING_OFF_SQES,
+            params.sq_entries as usize * mem::size_of::<Sqe>(),
+        )?;
+        // Every slot of the submission queue always refers to the entry with the same index.
+        let array = sq_ring.at::<u32>(params.sq_off.array);
+        for i in 0..params.sq_entries {
+            unsafe { *array.add(i as usize) = i };
+        }
+        Ok(Self {
+            sq_ring,
+            cq_ring,
+            sqes,
+            params,
+            pending: Vec::new(),
+            fd,
+        })
+    }
+    /// Returns the number of operations which can be submitted at once. Bigger batches are split into several
+    /// submissions.
+    pub fn entries(&self) -> u32 {
+        self.params.sq_entries
+    }
+
+    /// Submits the given operations and waits for all of them to complete, storing their results in them.
+    ///
+    /// The operations are performed concurrently, in no particular order – operations which need to happen in order,
+    /// such as several sends into the same stream, should be submitted in separate calls. An error is only returned if
+    /// submitting fails, in which case the operations of the failed part of the batch have either not been performed,
+    /// leaving them without a result, or been cancelled, while those of the preceding parts, if the batch is bigger
+    /// than the [ring](Self::entries), have been performed.
+    ///
+    /// # System calls
+    /// - `io_uring_enter`, at least once for every [`.entries()`](Self::entries) operations
+    pub fn submit(&mut self, ops: &mut [Op<'_>]) -> io::Result<()> {
+        for chunk in ops.chunks_mut(self.entries() as usize) {
+            for (i, op) in chunk.iter_mut().enumerate() {
+                op.result = None;
+                let sqe = op.sqe(i as u64);
+                self.push(sqe);
+            }
+            self.enter_until(|cqe| {
+                if let Some(op) = chunk.get_mut(cqe.user_data as usize) {
+                    op.result = Some(cqe_result(cqe));
+                }
+                false
+            })?;
+        }
+        Ok(())
+    }
+    /// Sends bytes into a connected socket, returning how many bytes were sent. Mostly useful for checking whether the
+    /// kernel supports `io_uring` sends, since a single operation is faster without a ring.
+    pub fn send(&mut self, socket: &impl AsFd, buf: &[u8]) -> io::Result<usize> {
+        let mut op = [Op::send(socket, buf)];
+        self.submit(&mut op)?;
+        let [op] = op;
+        op.take_result().unwrap()
+    }
+    /// Receives bytes from a connected socket, returning how many bytes were received. Mostly useful for checking
+    /// whether the kernel supports `io_uring` receives, since a single operation is faster without a ring.
+    pub fn recv(&mut self, socket: &impl AsFd, buf: &mut [u8]) -> io::Result<usize> {
+        let mut op = [Op::recv(socket, buf)];
+        self.submit(&mut op)?;
+        let [op] = op;
+        op.take_result().unwrap()
+    }
+
+    /// Accepts at least `count` connections with a single multishot accept request, which is cancelled once enough
+    /// connections have been accepted. Connections which the kernel accepts before the cancellation takes effect are
+    /// returned as well, so that no client gets dropped.
+    ///
+    /// If the request fails after some connections have been accepted, they're returned rather than the error. If it
+    /// fails right away, which is what happens if the kernel doesn't support multishot accepting, the error is returned.
+    ///
+    /// # System calls
+    /// - `io_uring_enter`, once or more
+    pub fn accept_multishot(&mut self, listener: &UdStreamListener, count: usize) -> io::Result<Vec<UdStream>> {
+        let mut accepted = Vec::with_capacity(count);
+        if count == 0 {
+            return Ok(accepted);
+        }
+        self.push(Sqe {
+            opcode: IORING_OP_ACCEPT,
+            ioprio: IORING_ACCEPT_MULTISHOT,
+            fd: listener.as_raw_fd(),
+            op_flags: libc::SOCK_CLOEXEC as u32,
+            user_data: ACCEPT_USER_DATA,
+            ..Sqe::default()
+        });
+        let mut error = None;
+        let mut done = false;
+        self.enter_until(|cqe| {
+            if cqe.user_data == ACCEPT_USER_DATA {
+                done = collect_accepted(cqe, &mut accepted, &mut error);
+            }
+            accepted.len() >= count
+        })?;
+        if !done {
+            self.push(Sqe {
+                opcode: IORING_OP_ASYNC_CANCEL,
+                addr: ACCEPT_USER_DATA,
+                user_data: CANCEL_USER_DATA,
+                ..Sqe::default()
+            });
+            self.enter_until(|cqe| {
+                if cqe.user_data == ACCEPT_USER_DATA {
+                    collect_accepted(cqe, &mut accepted, &mut error);
+                }
+                false
+            })?;
+        }
+        for stream in &accepted {
+            c_wrappers::set_nosigpipe(stream.as_fd())?;
+        }
+        match error {
+            Some(e) if accepted.is_empty() => Err(e),
+            // The final completion of a cancelled request reports the cancellation as an error.
+            _ => Ok(accepted),
+        }
+    }
+
+    /// Places an entry in the submission queue, which must have room for it.
+    fn push(&mut self, sqe: Sqe) {
+        let tail = self.sq_ring.at::<AtomicU32>(self.params.sq_off.tail);
+        let mask = unsafe { *self.sq_ring.at::<u32>(self.params.sq_off.ring_mask) };
+        self.pending.push(sqe.user_data);
+        // SAFETY: only we write to the tail, and the entry at the tail is not used by the kernel until it's advanced
+        unsafe {
+            let t = (*tail).load(Relaxed);
+            self.sqes.at::<Sqe>(0).add((t & mask) as usize).write(sqe);
+            (*tail).store(t.wrapping_add(1), Release);
+        }
+    }
+    /// Submits the queued requests and hands their completions to `f` until it returns `true` or none of the requests
+    /// are pending anymore.
+    ///
+    /// Submitted operations refer to buffers which are only borrowed for the duration of the call that submitted them,
+    /// which is why this never returns while any of them might still be in flight. If `io_uring_enter` fails, the
+    /// requests which the kernel hasn't taken yet are withdrawn, the rest are cancelled, and their completions are
+    /// waited for and handed to `f` before the error is returned. Should even submitting the cancellations fail, the
+    /// process is aborted, since there is no other way of keeping the kernel from using the buffers after they've
+    /// been freed.
+    fn enter_until(&mut self, mut f: impl FnMut(&Cqe) -> bool) -> io::Result<()> {
+        let e = match self.drive(&mut f) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        self.withdraw();
+        let in_flight = self.pending.clone();
+        for user_data in in_flight.into_iter().filter(|&u| u != CANCEL_USER_DATA) {
+            self.push(Sqe {
+                opcode: IORING_OP_ASYNC_CANCEL,
+                addr: user_data,
+                user_data: CANCEL_USER_DATA,
+                ..Sqe::default()
+            });
+        }
+        if self
+            .drive(&mut |cqe| {
+                f(cqe);
+                false
+            })
+            .is_err()
+        {
+            process::abort();
+        }
+        Err(e)
+    }
+    /// The loop of [`enter_until()`](Self::enter_until), which returns the first error that can't be recovered from.
+    fn drive(&mut self, f: &mut dyn FnMut(&Cqe) -> bool) -> io::Result<()> {
+        let cq_head = self.cq_ring.at::<AtomicU32>(self.params.cq_off.head);
+        let cq_tail = self.cq_ring.at::<AtomicU32>(self.params.cq_off.tail);
+        let cq_mask = unsafe { *self.cq_ring.at::<u32>(self.params.cq_off.ring_mask) };
+        let cqes = self.cq_ring.at::<Cqe>(self.params.cq_off.cqes);
+        let mut submit = true;
+        while !self.pending.is_empty() {
+            let unsubmitted = self.unsubmitted();
+            // Every pending request has at least one completion coming, so waiting for that many never hangs. The
+            // kernel doesn't wait at all if it fails to submit everything it's given.
+            let (to_submit, min_complete) = if submit {
+                (unsubmitted, self.pending.len())
+            } else {
+                (0, 1)
+            };
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_io_uring_enter,
+                    self.fd.as_raw_fd() as c_long,
+                    to_submit as c_long,
+                    min_complete as c_long,
+                    IORING_ENTER_GETEVENTS as c_long,
+                    ptr::null::<c_void>(),
+                    0 as c_long,
+                )
+            };
+            submit = true;
+            if ret == -1 {
+                let e = io::Error::last_os_error();
+                let in_flight = self.pending.len() - unsubmitted as usize;
+                match e.raw_os_error() {
+                    Some(libc::EINTR) => {}
+                    // The kernel is short on memory or on room for completions, which is freed up by the requests
+                    // already in flight completing, so the next call only waits for one of them.
+                    Some(libc::EAGAIN) | Some(libc::EBUSY) if in_flight != 0 => submit = false,
+                    _ => return Err(e),
+                }
+            }
+
+            let tail = unsafe { (*cq_tail).load(Acquire) };
+            let mut head = unsafe { (*cq_head).load(Relaxed) };
+            let mut finished = false;
+            while head != tail && !finished {
+                // SAFETY: the entries between the head and the tail are filled in by the kernel
+                let cqe = unsafe { &*cqes.add((head & cq_mask) as usize) };
+                // The request stays active for as long as the kernel says there's more to come.
+                if cqe.flags & IORING_CQE_F_MORE == 0 {
+                    if let Some(i) = self.pending.iter().position(|&u| u == cqe.user_data) {
+                        self.pending.swap_remove(i);
+                    }
+                }
+                finished = f(cqe);
+                head = head.wrapping_add(1);
+            }
+            unsafe { (*cq_head).store(head, Release) };
+            if finished {
+                break;
+            }
+        }
+        Ok(())
+    }
+    /// Returns the number of queued requests which the kernel hasn't taken yet.
+    fn unsubmitted(&self) -> u32 {
+        let head = self.sq_ring.at::<AtomicU32>(self.params.sq_off.head);
+        let tail = self.sq_ring.at::<AtomicU32>(self.params.sq_off.tail);
+        unsafe { (*tail).load(Relaxed).wrapping_sub((*head).load(Acquire)) }
+    }
+    /// Takes the requests which the kernel hasn't taken yet back out of the submission queue.
+    fn withdraw(&mut self) {
+        let head = self.sq_ring.at::<AtomicU32>(self.params.sq_off.head);
+        let tail = self.sq_ring.at::<AtomicU32>(self.params.sq_off.tail);
+        let mask = unsafe { *self.sq_ring.at::<u32>(self.params.sq_off.ring_mask) };
+        // SAFETY: the kernel only advances the head when it's called, and the entries past it are still ours
+        unsafe {
+            let h = (*head).load(Acquire);
+            let mut t = (*tail).load(Relaxed);
+            while t != h {
+                t = t.wrapping_sub(1);
+                let user_data = (*self.sqes.at::<Sqe>(0).add((t & mask) as usize)).user_data;
+                if let Some(i) = self.pending.iter().rposition(|&u| u == user_data) {
+                    self.pending.swap_remove(i);
+                }
+            }
+            (*tail).store(h, Release);
+        }
+    }
+}
+impl Debug for IoUring {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IoUring")
+            .field("fd", &self.fd.as_raw_fd())
+            .field("entries", &self.params.sq_entries)
+            .finish()
+    }
+}
+impl AsFd for IoUring {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+/// Takes in a completion of the multishot accept request, returning whether the request is finished.
+fn collect_accepted(cqe: &Cqe, accepted: &mut Vec<UdStream>, error: &mut Option<io::Error>) -> bool {
+    match cqe_result(cqe) {
+        // SAFETY: the kernel just created the file descriptor for us
+        Ok(fd) => accepted.push(unsafe { UdStream::from_raw_fd(fd as c_int) }),
+        Err(e) => *error = Some(e),
+    }
+    cqe.flags & IORING_CQE_F_MORE == 0
+}
+fn cqe_result(cqe: &Cqe) -> io::Result<usize> {
+    if cqe.res < 0 {
+        Err(io::Error::from_raw_os_error(-cqe.res))
+    } else {
+        Ok(cqe.res as usize)
+    }
+}
+
+/// An operation to be [submitted](IoUring::submit) to an [`IoUring`], which holds its result once it's completed.
+///
+/// The operations work on any connected socket, such as a [`UdStream`] or a [`UdSocket`](super::UdSocket) with a
+/// [destination](super::UdSocket::set_destination).
+pub struct Op<'a> {
+    kind: OpKind<'a>,
+    result: Option<io::Result<usize>>,
+}
+enum OpKind<'a> {
+    Send(BorrowedFd<'a>, &'a [u8]),
+    Recv(BorrowedFd<'a>, &'a mut [u8]),
+}
+impl<'a> Op<'a> {
+    /// Creates an operation which sends bytes into a socket. Sending after the other side has hung up fails with
+    /// [`BrokenPipe`](io::ErrorKind::BrokenPipe) instead of raising `SIGPIPE`, unless that has been [opted out
+    /// of](super::set_sigpipe_on_send).
+    pub fn send(socket: &'a impl AsFd, buf: &'a [u8]) -> Self {
+        Self {
+            kind: OpKind::Send(socket.as_fd(), buf),
+            result: None,
+        }
+    }
+    /// Creates an operation which receives bytes from a socket.
+    pub fn recv(socket: &'a impl AsFd, buf: &'a mut [u8]) -> Self {
+        Self {
+            kind: OpKind::Recv(socket.as_fd(), buf),
+            result: None,
+        }
+    }
+    /// Returns the number of bytes transferred by the operation, or the error it failed with. Returns `None` if the
+    /// operation hasn't been submitted yet.
+    pub fn result(&self) -> Option<&io::Result<usize>> {
+        self.result.as_ref()
+    }
+    /// Consumes the operation, returning its [result](Self::result).
+    pub fn take_result(self) -> Option<io::Result<usize>> {
+        self.result
+    }
+    fn sqe(&mut self, user_data: u64) -> Sqe {
+        let (opcode, fd, addr, len, op_flags) = match &mut self.kind {
+            OpKind::Send(fd, buf) => (
+                IORING_OP_SEND,
+                fd.as_raw_fd(),
+                buf.as_ptr() as u64,
+                buf.len(),
+                c_wrappers::send_flags() as u32,
+            ),
+            OpKind::Recv(fd, buf) => (IORING_OP_RECV, fd.as_raw_fd(), buf.as_mut_ptr() as u64, buf.len(), 0),
+        };
+        Sqe {
+            opcode,
+            fd,
+            addr,
+            // Lengths which don't fit are clamped, resulting in a partial transfer, as with a regular short write.
+            len: len.min(u32::MAX as usize) as u32,
+            op_flags,
+            user_data,
+            ..Sqe::default()
+        }
+    }
+}
+impl Debug for Op<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (name, fd, len) = match &self.kind {
+            OpKind::Send(fd, buf) => ("Send", fd, buf.len()),
+            OpKind::Recv(fd, buf) => ("Recv", fd, buf.len()),
+        };
+        f.debug_struct(name)
+            .field("fd", &fd.as_raw_fd())
+            .field("len", &len)
+            .field("result", &self.result)
+            .finish()
+    }
+}
@@ -1,13 +1,38 @@
-use super::{c_wrappers, PathDropGuard, ToUdSocketPath, UdSocketPath, UdStream};
-use crate::os::unix::{unixprelude::*, FdOps};
-use libc::{sockaddr_un, SOCK_STREAM};
+use super::{c_wrappers, long_path::with_sockaddr_un, PathDropGuard, ToUdSocketPath, UdSocketPath, UdStream};
+use crate::{
+    handle_conversion::FromHandleError,
+    os::unix::{unixprelude::*, FdOps},
+    timeout::AcceptTimeout,
+};
+use libc::{gid_t, uid_t, SOCK_SEQPACKET, SOCK_STREAM};
 use std::{
+    ffi::{CStr, OsStr},
     fmt::{self, Debug, Formatter},
-    io,
+    fs, io,
     iter::FusedIterator,
     mem::zeroed,
+    time::Duration,
 };
-use to_method::To;
+
+/// Ownership and permissions applied to the socket file of a listener right after it is bound.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct SocketFileOptions {
+    pub mode: Option<mode_t>,
+    pub owner: Option<uid_t>,
+    pub group: Option<gid_t>,
+}
+impl SocketFileOptions {
+    fn apply(&self, file: &CStr) -> io::Result<()> {
+        // Ownership goes first, since changing it may clear some of the permission bits on certain platforms.
+        if self.owner.is_some() || self.group.is_some() {
+            c_wrappers::chown(file, self.owner, self.group)?;
+        }
+        if let Some(mode) = self.mode {
+            c_wrappers::chmod(file, mode)?;
+        }
+        Ok(())
+    }
+}
 
 /// A Unix domain byte stream socket server, listening for connections.
 ///
@@ -77,20 +102,56 @@ impl UdStreamListener {
     pub fn bind_with_drop_guard<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
         Self::_bind(path.to_socket_path()?, true, false)
     }
+    /// Creates a `SOCK_SEQPACKET` listener with a drop guard, for use by message-mode local sockets. All of the methods work on such a listener the same way they do on a regular one.
+    pub(crate) fn bind_seqpacket(path: UdSocketPath<'_>) -> io::Result<Self> {
+        Self::_bind_with_type(path, SOCK_SEQPACKET, true, false)
+    }
     pub(crate) fn _bind(path: UdSocketPath<'_>, keep_drop_guard: bool, nonblocking: bool) -> io::Result<Self> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
-
-        let fd = c_wrappers::create_uds(SOCK_STREAM, nonblocking)?;
-        unsafe {
-            // SAFETY: addr is well-constructed
-            c_wrappers::bind(&fd, &addr)?;
-        }
+        Self::_bind_with_type(path, SOCK_STREAM, keep_drop_guard, nonblocking)
+    }
+    fn _bind_with_type(
+        path: UdSocketPath<'_>,
+        ty: c_int,
+        keep_drop_guard: bool,
+        nonblocking: bool,
+    ) -> io::Result<Self> {
         // FIXME the standard library uses 128 here without an option to change this
         // number, why? If std has solid reasons to do this, remove this notice and
         // document the method's behavior on this matter explicitly; otherwise, add
-        // an option to change this value.
-        c_wrappers::listen(&fd, 128)?;
-        c_wrappers::set_passcred(&fd, true)?;
+        // an option to change this value. (Local socket listener options already can.)
+        Self::_bind_with_options(
+            path,
+            ty,
+            keep_drop_guard,
+            nonblocking,
+            128,
+            SocketFileOptions::default(),
+        )
+    }
+    /// The most general form of `bind`, used by the local socket listener options. If the socket is a file, the
+    /// specified ownership and permissions are applied to it before it starts listening, so that no client can ever
+    /// connect to it with the default ones. Should that fail, the file is deleted again.
+    pub(crate) fn _bind_with_options(
+        path: UdSocketPath<'_>,
+        ty: c_int,
+        keep_drop_guard: bool,
+        nonblocking: bool,
+        backlog: c_int,
+        file_options: SocketFileOptions,
+    ) -> io::Result<Self> {
+        let fd = c_wrappers::create_uds(ty, nonblocking)?;
+        with_sockaddr_un(path.borrow(), |addr| unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::bind(fd.as_fd(), addr)
+        })?;
+        if let UdSocketPath::File(file) = &path {
+            if let Err(e) = file_options.apply(file) {
+                let _ = fs::remove_file(OsStr::from_bytes(file.to_bytes()));
+                return Err(e);
+            }
+        }
+        c_wrappers::listen(fd.as_fd(), backlog)?;
+        c_wrappers::set_passcred(fd.as_fd(), true)?;
 
         let dg = if keep_drop_guard {
             PathDropGuard {
@@ -103,6 +164,12 @@ impl UdStreamListener {
 
         Ok(Self { fd, _drop_guard: dg })
     }
+    /// Takes the drop guard out of the listener, leaving a disabled one in its place. Used when converting the listener
+    /// into a type which has to delete the socket file by itself.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn take_drop_guard(&mut self) -> PathDropGuard<'static> {
+        std::mem::replace(&mut self._drop_guard, PathDropGuard::dummy())
+    }
 
     /// Listens for incoming connections to the socket, blocking until a client is connected.
     ///
@@ -136,16 +203,38 @@ impl UdStreamListener {
             (result != -1, result)
         };
         if success {
-            Ok(unsafe {
+            let stream = unsafe {
                 // SAFETY: we just created the file descriptor, meaning that it's guaranteeed
                 // not to be used elsewhere
                 UdStream::from_raw_fd(fd)
-            })
+            };
+            c_wrappers::set_nosigpipe(stream.as_fd())?;
+            Ok(stream)
         } else {
             Err(io::Error::last_os_error())
         }
     }
 
+    /// Like [`accept`](Self::accept), but gives up with a [`TimedOut`](io::ErrorKind::TimedOut) error if no client
+    /// connects within the given timeout.
+    ///
+    /// If another thread accepts the pending connection between the wait and the `accept` call, the latter will block
+    /// (or fail with [`WouldBlock`](io::ErrorKind::WouldBlock) in nonblocking mode) just like [`accept`](Self::accept)
+    /// would.
+    ///
+    /// # System calls
+    /// - `poll`
+    /// - `accept`
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<UdStream> {
+        if !c_wrappers::poll_readable(self.fd.as_fd(), timeout)? {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no client connected before the timeout expired",
+            ));
+        }
+        self.accept()
+    }
+
     /// Creates an infinite iterator which calls `accept()` with each iteration. Used together with `for` loops to conveniently create a main loop for a socket server.
     ///
     /// # Example
@@ -175,11 +264,20 @@ impl UdStreamListener {
     /// [`incoming`]: #method.incoming " "
     /// [`WouldBlock`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock " "
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        c_wrappers::set_nonblocking(&self.fd, nonblocking)
+        c_wrappers::set_nonblocking(self.fd.as_fd(), nonblocking)
     }
     /// Checks whether the socket is currently in nonblocking mode or not.
     pub fn is_nonblocking(&self) -> io::Result<bool> {
-        c_wrappers::get_nonblocking(&self.fd)
+        c_wrappers::get_nonblocking(self.fd.as_fd())
+    }
+}
+impl AcceptTimeout for UdStreamListener {
+    type Stream = UdStream;
+    fn accept(&self) -> io::Result<UdStream> {
+        self.accept()
+    }
+    fn accept_timeout(&self, timeout: Duration) -> io::Result<UdStream> {
+        self.accept_timeout(timeout)
     }
 }
 impl Debug for UdStreamListener {
@@ -190,11 +288,35 @@ impl Debug for UdStreamListener {
             .finish()
     }
 }
+impl AsFd for UdStreamListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
 impl AsRawFd for UdStreamListener {
     fn as_raw_fd(&self) -> c_int {
         self.fd.as_raw_fd()
     }
 }
+impl From<UdStreamListener> for OwnedFd {
+    fn from(x: UdStreamListener) -> Self {
+        x.fd.into()
+    }
+}
+/// Fails if the file descriptor is not a Ud-socket of the `SOCK_STREAM` or `SOCK_SEQPACKET` type which is listening for
+/// incoming connections.
+impl TryFrom<OwnedFd> for UdStreamListener {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| {
+            c_wrappers::check_ud_socket(fd.as_fd(), &[SOCK_STREAM, SOCK_SEQPACKET], true)
+        })?;
+        Ok(Self {
+            fd: fd.into(),
+            _drop_guard: PathDropGuard::dummy(),
+        })
+    }
+}
 impl IntoRawFd for UdStreamListener {
     fn into_raw_fd(self) -> c_int {
         self.fd.into_raw_fd()
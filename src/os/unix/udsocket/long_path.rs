@@ -0,0 +1,118 @@
+//! The workaround for socket paths which don't fit into `sun_path`: the parent directory is opened, and the socket is
+//! reached through a short path relative to it.
+
+use super::UdSocketPath;
+use crate::{os::unix::unixprelude::*, ErrorKind};
+use libc::sockaddr_un;
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString, OsStr},
+    io,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+};
+use to_method::To;
+
+/// Whether the workaround is enabled. See [`set_long_path_workaround()`](super::set_long_path_workaround).
+static LONG_PATH_WORKAROUND: AtomicBool = AtomicBool::new(false);
+
+pub(super) fn long_path_workaround() -> bool {
+    LONG_PATH_WORKAROUND.load(Relaxed)
+}
+pub(super) fn set_long_path_workaround(enabled: bool) {
+    LONG_PATH_WORKAROUND.store(enabled, Relaxed)
+}
+
+/// Converts the path into a socket address and passes it to `f`, which performs the system call that takes it. If the
+/// path is too long and the workaround is enabled, the address refers to the socket through its parent directory,
+/// which is kept open until `f` returns.
+pub(super) fn with_sockaddr_un<T>(
+    path: UdSocketPath<'_>,
+    f: impl FnOnce(&sockaddr_un) -> io::Result<T>,
+) -> io::Result<T> {
+    match path.borrow().try_to::<sockaddr_un>() {
+        Ok(addr) => f(&addr),
+        Err(e) if ErrorKind::of(&e) == ErrorKind::NameTooLong && long_path_workaround() => match &path {
+            // The file name alone being too long is best reported with the original message.
+            UdSocketPath::File(file) => via_parent_dir(file, f).map_err(|e2| match ErrorKind::of(&e2) {
+                ErrorKind::NameTooLong => e,
+                _ => e2,
+            }),
+            _ => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn via_parent_dir<T>(file: &CStr, f: impl FnOnce(&sockaddr_un) -> io::Result<T>) -> io::Result<T> {
+    let path = Path::new(OsStr::from_bytes(file.to_bytes()));
+    let (dir, file_name) = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(file_name)) => (dir, file_name),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "socket path does not name a file in a directory",
+            ))
+        }
+    };
+    let dir = open_dir(match dir.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => dir,
+    })?;
+    via_dir_fd(dir.as_fd(), file_name, f)
+}
+
+/// On Linux, `/proc/self/fd/<fd>` is a link to the open directory which `bind` and `connect` follow like any other.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn via_dir_fd<T>(
+    dir: BorrowedFd<'_>,
+    file_name: &OsStr,
+    f: impl FnOnce(&sockaddr_un) -> io::Result<T>,
+) -> io::Result<T> {
+    let mut short = format!("/proc/self/fd/{}/", dir.as_raw_fd()).into_bytes();
+    short.extend_from_slice(file_name.as_bytes());
+    let short = CString::new(short).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let addr = UdSocketPath::File(Cow::Owned(short)).try_to::<sockaddr_un>()?;
+    f(&addr)
+}
+/// Elsewhere, the working directory is switched to the open directory for the duration of the call, so that the file
+/// name alone can be used as a relative path.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn via_dir_fd<T>(
+    dir: BorrowedFd<'_>,
+    file_name: &OsStr,
+    f: impl FnOnce(&sockaddr_un) -> io::Result<T>,
+) -> io::Result<T> {
+    use std::sync::{Mutex, PoisonError};
+    /// Keeps two threads of this process from switching the working directory at the same time.
+    static CWD_LOCK: Mutex<()> = Mutex::new(());
+
+    let short = CString::new(file_name.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let addr = UdSocketPath::File(Cow::Owned(short)).try_to::<sockaddr_un>()?;
+    let _guard = CWD_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let cwd = open_dir(Path::new("."))?;
+    fchdir(dir)?;
+    let result = f(&addr);
+    // Failing to switch back would leave the process in the wrong directory, which is worse than failing the call.
+    fchdir(cwd.as_fd())?;
+    result
+}
+
+fn open_dir(dir: &Path) -> io::Result<OwnedFd> {
+    let dir = CString::new(dir.as_os_str().as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let flags = libc::O_PATH | libc::O_DIRECTORY | libc::O_CLOEXEC;
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC;
+    let fd = unsafe { libc::open(dir.as_ptr(), flags) };
+    if fd == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: we just opened the file descriptor
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn fchdir(dir: BorrowedFd<'_>) -> io::Result<()> {
+    let success = unsafe { libc::fchdir(dir.as_raw_fd()) != -1 };
+    ok_or_ret_errno!(success => ())
+}
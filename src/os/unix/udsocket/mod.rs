@@ -11,27 +11,42 @@
 //! [`UdStreamListener`]: struct.UdStreamListener.html " "
 //! [`UdSocket`]: struct.UdSocket.html " "
 
+#[cfg(feature = "async-io")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "async-io")))]
+pub mod async_io;
 pub mod cmsg;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(all(target_os = "linux", feature = "io-uring"))))]
+pub mod io_uring;
 #[cfg(feature = "tokio")]
 #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
+mod fd_chunking;
 mod listener;
+mod long_path;
 mod path;
+mod peer_credentials;
+mod recv_result;
 mod socket;
 mod stream;
 mod util;
-pub use {listener::*, path::*, socket::*, stream::*};
+pub use {listener::*, path::*, peer_credentials::*, recv_result::*, socket::*, stream::*};
+
+pub use crate::handle_conversion::FromHandleError;
 
 mod path_drop_guard;
-use path_drop_guard::*;
+pub(crate) use path_drop_guard::*;
 
 mod c_wrappers;
+pub(crate) use c_wrappers::check_ud_socket;
 
 use libc::{sa_family_t, sockaddr_un};
 use std::mem::size_of;
 
-/// The maximum path length for Unix domain sockets. [`UdStreamListener::bind`] panics if the specified path exceeds this value.
+/// The maximum path length for Unix domain sockets. Binding or connecting to a longer path fails with
+/// [`ErrorKind::NameTooLong`](crate::ErrorKind::NameTooLong), unless the [long path
+/// workaround](set_long_path_workaround) is enabled.
 ///
 /// When using the [socket namespace], this value is reduced by 1, since enabling the usage of that namespace takes up one character.
 ///
@@ -76,3 +91,48 @@ pub const MAX_UDSOCKET_PATH_LEN: usize = {
     };
     LENGTH
 };
+
+/// Sets whether sending into a Ud-socket whose peer has disconnected raises `SIGPIPE`, as it does by default in C.
+///
+/// By default, all sending methods of the Ud-socket types in this crate, both blocking and asynchronous, fail with
+/// [`BrokenPipe`](std::io::ErrorKind::BrokenPipe) in that case instead, so that the process doesn't get killed in
+/// contexts where the Rust runtime hasn't set `SIGPIPE` to be ignored, such as in a library loaded by a C program.
+/// Passing `true` restores the signal for programs which install their own handler for it and rely on it.
+///
+/// The setting applies to the whole process. This is implemented with the `MSG_NOSIGNAL` flag on most platforms,
+/// which the setting affects immediately. On macOS and iOS, it is implemented with the `SO_NOSIGPIPE` socket option,
+/// which is only set on sockets created or accepted after the setting is changed. On platforms which support
+/// neither, `SIGPIPE` is blocked for the calling thread for the duration of every sending call.
+///
+/// This has no effect on sockets which aren't sent into through the types of this crate, nor on pipes and FIFO files.
+pub fn set_sigpipe_on_send(raise: bool) {
+    c_wrappers::set_sigpipe_on_send(raise)
+}
+/// Returns whether sending into a Ud-socket whose peer has disconnected raises `SIGPIPE`, as set by
+/// [`set_sigpipe_on_send()`]. Defaults to `false`.
+pub fn sigpipe_on_send() -> bool {
+    c_wrappers::sigpipe_on_send()
+}
+
+/// Sets whether Ud-socket paths which don't fit into [`MAX_UDSOCKET_PATH_LEN`] are reached through their parent
+/// directory instead of failing with [`ErrorKind::NameTooLong`](crate::ErrorKind::NameTooLong).
+///
+/// Runtime directories nested deep inside a home directory or a container's filesystem can easily exceed the limit,
+/// which leaves the socket unusable by its full path. With the workaround enabled, binding, connecting and sending to
+/// such a path opens its parent directory and refers to the socket relative to it:
+/// - On Linux and Android, the path is rewritten to `/proc/self/fd/<fd>/<file name>`, which requires `/proc` to be
+///   mounted.
+/// - On other platforms, the working directory of the process is switched to the parent directory for the duration
+///   of the call, and the file name alone is used. Calls made this way are serialized with each other, but other
+///   threads which use relative paths in the meantime will observe the changed working directory.
+///
+/// The file name itself still has to fit into the limit. Namespaced names are not affected. The setting applies to
+/// the whole process and defaults to `false`.
+pub fn set_long_path_workaround(enabled: bool) {
+    long_path::set_long_path_workaround(enabled)
+}
+/// Returns whether the workaround for long Ud-socket paths is enabled, as set by [`set_long_path_workaround()`].
+/// Defaults to `false`.
+pub fn long_path_workaround() -> bool {
+    long_path::long_path_workaround()
+}
@@ -2,17 +2,18 @@ use super::{
     util::{empty_cstr, empty_cstring, eunreachable},
     MAX_UDSOCKET_PATH_LEN,
 };
-use crate::os::unix::unixprelude::*;
+use crate::{os::unix::unixprelude::*, Error, ErrorKind};
 use libc::{sockaddr_un, AF_UNIX};
 use std::{
     borrow::{Cow, ToOwned},
+    cmp::min,
     convert::TryFrom,
     ffi::{CStr, CString, NulError, OsStr, OsString},
     io,
     mem::{replace, size_of_val, zeroed},
     ops::Deref,
     path::{Path, PathBuf},
-    ptr,
+    ptr, slice,
 };
 
 /// Represents a name for a Unix domain socket.
@@ -142,74 +143,40 @@ impl<'a> UdSocketPath<'a> {
     }
 
     pub(super) fn write_sockaddr_un_to_self(&mut self, addr: &sockaddr_un, addrlen: usize) {
-        let sun_path_length = (addrlen as isize) - (size_of_val(&addr.sun_family) as isize);
-        let sun_path_length = match usize::try_from(sun_path_length) {
-            Ok(val) => val,
-            Err(..) => {
+        let sun_path_length = match addrlen.checked_sub(size_of_val(&addr.sun_family)) {
+            Some(0) | None => {
                 *self = Self::Unnamed;
                 return;
             }
+            Some(len) => min(len, addr.sun_path.len()),
         };
-        if let Some(cstring) = self.try_get_cstring_mut() {
-            let cstring = replace(cstring, empty_cstring());
-            let mut vec = cstring.into_bytes_with_nul();
-            let mut _namespaced = false;
-            unsafe {
-                #[cfg(uds_linux_namespace)]
-                let (src_ptr, path_length) = if addr.sun_path[0] == 0 {
-                    _namespaced = true;
-                    (addr.sun_path.as_ptr().offset(1) as *const u8, sun_path_length - 1)
-                } else {
-                    (addr.sun_path.as_ptr() as *const u8, sun_path_length)
-                };
-                #[cfg(not(uds_linux_namespace))]
-                let (src_ptr, path_length) = { (addr.sun_path.as_ptr() as *const u8, sun_path_length) };
-                // Fill the space for the name and the nul terminator with nuls
-                vec.resize(path_length, 0);
-                ptr::copy_nonoverlapping(src_ptr, vec.as_mut_ptr(), path_length);
-            };
-            // If the system added a nul byte as part of the length, remove the one we added ourselves.
-            if vec.last() == Some(&0) && vec[vec.len() - 2] == 0 {
-                vec.pop();
-            }
-            let new_cstring = CString::new(vec).unwrap_or_else(eunreachable);
-            #[cfg(uds_linux_namespace)]
-            let path_to_write = if _namespaced {
-                UdSocketPath::Namespaced(Cow::Owned(new_cstring))
-            } else {
-                UdSocketPath::File(Cow::Owned(new_cstring))
-            };
-            #[cfg(not(uds_linux_namespace))]
-            let path_to_write = UdSocketPath::File(Cow::Owned(new_cstring));
-            *self = path_to_write;
-            // Implicitly drops the empty CString we wrote in the beginning
-        } else {
-            let mut _namespaced = false;
-            let mut vec = unsafe {
-                let (src_ptr, path_length) = if addr.sun_path[0] == 0 {
-                    (addr.sun_path.as_ptr().offset(1) as *const u8, sun_path_length - 1)
-                } else {
-                    (addr.sun_path.as_ptr() as *const u8, sun_path_length)
-                };
-                let mut vec = vec![0; path_length];
-                ptr::copy_nonoverlapping(src_ptr, vec.as_mut_ptr(), path_length);
-                vec
-            };
-            // If the system added a nul byte as part of the length, remove it.
-            if vec.last() == Some(&0) {
-                vec.pop();
-            }
-            let cstring = CString::new(vec).unwrap_or_else(eunreachable);
-            #[cfg(uds_linux_namespace)]
-            let path_to_write = if _namespaced {
-                UdSocketPath::Namespaced(Cow::Owned(cstring))
-            } else {
-                UdSocketPath::File(Cow::Owned(cstring))
-            };
-            #[cfg(not(uds_linux_namespace))]
-            let path_to_write = UdSocketPath::File(Cow::Owned(cstring));
-            *self = path_to_write;
+        let raw = unsafe {
+            // SAFETY: the length is clamped to that of the array
+            slice::from_raw_parts(addr.sun_path.as_ptr().cast::<u8>(), sun_path_length)
+        };
+        #[cfg(uds_linux_namespace)]
+        let (raw, namespaced) = match raw.split_first() {
+            Some((0, rest)) => (rest, true),
+            _ => (raw, false),
+        };
+        // Paths are terminated by a nul byte, which the kernel may or may not count in the address length, and
+        // namespaced names are padded with nuls up to the full length of `sun_path` by `bind()`/`connect()`.
+        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+
+        // Reuse the allocation of the buffer if there is one
+        let mut vec = match self.try_get_cstring_mut() {
+            Some(cstring) => replace(cstring, empty_cstring()).into_bytes(),
+            None => Vec::new(),
+        };
+        vec.clear();
+        vec.extend_from_slice(&raw[..end]);
+        let cstring = CString::new(vec).unwrap_or_else(eunreachable);
+        #[cfg(uds_linux_namespace)]
+        if namespaced {
+            *self = UdSocketPath::Namespaced(Cow::Owned(cstring));
+            return;
         }
+        *self = UdSocketPath::File(Cow::Owned(cstring));
     }
     /// Returns `addr_len` to pass to `bind`/`connect`.
     pub(super) fn write_self_to_sockaddr_un(&self, addr: &mut sockaddr_un) -> io::Result<()> {
@@ -219,10 +186,12 @@ impl<'a> UdSocketPath<'a> {
             UdSocketPath::File(..) => {
                 is_namespaced = false;
                 if len_of_self > MAX_UDSOCKET_PATH_LEN {
-                    return Err(io::Error::new(
+                    return Err(Error::with_io_kind(
+                        ErrorKind::NameTooLong,
                         io::ErrorKind::InvalidInput,
                         format!("socket path should not be longer than {MAX_UDSOCKET_PATH_LEN} bytes"),
-                    ));
+                    )
+                    .into());
                 }
             }
             #[cfg(uds_linux_namespace)]
@@ -230,10 +199,12 @@ impl<'a> UdSocketPath<'a> {
                 is_namespaced = true;
                 const MAX_NAMESPACED_LEN: usize = MAX_UDSOCKET_PATH_LEN - 1;
                 if len_of_self > MAX_NAMESPACED_LEN {
-                    return Err(io::Error::new(
+                    return Err(Error::with_io_kind(
+                        ErrorKind::NameTooLong,
                         io::ErrorKind::InvalidInput,
                         format!("namespaced socket name should not be longer than {MAX_NAMESPACED_LEN} bytes"),
-                    ));
+                    )
+                    .into());
                 }
             }
             UdSocketPath::Unnamed => {
@@ -0,0 +1,50 @@
+use libc::{gid_t, pid_t, uid_t};
+
+/// Identity of the process on the other end of a Ud-socket connection, as reported by the operating system.
+///
+/// Different platforms report different subsets of this information, which is why every accessor returns an `Option`. The following are currently supported:
+/// - Linux, Emscripten, Redox and Haiku (`SO_PEERCRED`): process ID, user ID and group ID
+/// - macOS and iOS (`getpeereid` and `LOCAL_PEERPID`): process ID, user ID and group ID
+/// - FreeBSD, OpenBSD, NetBSD and DragonFly BSD (`getpeereid`): user ID and group ID
+///
+/// On other platforms, retrieving peer credentials fails with [`Unsupported`](std::io::ErrorKind::Unsupported).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PeerCredentials {
+    pid: Option<pid_t>,
+    uid: Option<uid_t>,
+    gid: Option<gid_t>,
+    groups: Option<Vec<gid_t>>,
+}
+impl PeerCredentials {
+    #[allow(dead_code)] // Unused on platforms without any peer credential retrieval mechanism
+    pub(super) fn new(pid: Option<pid_t>, uid: Option<uid_t>, gid: Option<gid_t>, groups: Option<Vec<gid_t>>) -> Self {
+        Self { pid, uid, gid, groups }
+    }
+    /// Returns the process ID of the peer, or `None` if the platform doesn't report it.
+    #[inline]
+    pub fn pid(&self) -> Option<pid_t> {
+        self.pid
+    }
+    /// Returns the effective user ID of the peer, or `None` if the platform doesn't report it.
+    #[inline]
+    pub fn uid(&self) -> Option<uid_t> {
+        self.uid
+    }
+    /// Returns the effective group ID of the peer, or `None` if the platform doesn't report it.
+    #[inline]
+    pub fn gid(&self) -> Option<gid_t> {
+        self.gid
+    }
+    /// Returns the supplementary groups of the peer, or `None` if the platform doesn't report them.
+    #[inline]
+    pub fn groups(&self) -> Option<&[gid_t]> {
+        self.groups.as_deref()
+    }
+}
+#[cfg(any(uds_ucred, uds_peerucred))]
+impl From<libc::ucred> for PeerCredentials {
+    #[inline]
+    fn from(cred: libc::ucred) -> Self {
+        Self::new(Some(cred.pid), Some(cred.uid), Some(cred.gid), None)
+    }
+}
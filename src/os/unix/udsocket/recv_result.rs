@@ -0,0 +1,39 @@
+use libc::{MSG_CTRUNC, MSG_TRUNC};
+
+/// The result of a successful `.recv_ancillary()` family call, reporting how much data was received and whether any of it had to be discarded.
+///
+/// Protocols which pass file descriptors must check [`ancillary_truncated`](Self::ancillary_truncated): if it's `true`, some of the control messages didn't fit into the ancillary data buffer and were dropped by the kernel, and any file descriptors they contained were closed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct RecvAncillaryResult {
+    /// How many bytes of the main data were received.
+    pub size: usize,
+    /// How many bytes of ancillary data were received.
+    pub ancillary_size: usize,
+    /// Whether the datagram was larger than the buffer it was received into and the remainder of it was discarded (`MSG_TRUNC`). Never set for streams.
+    pub data_truncated: bool,
+    /// Whether the ancillary data buffer was too small to fit all of the control messages and some of them were discarded (`MSG_CTRUNC`).
+    pub ancillary_truncated: bool,
+}
+impl RecvAncillaryResult {
+    pub(super) fn new(size: usize, ancillary_size: usize, msg_flags: libc::c_int) -> Self {
+        Self {
+            size,
+            ancillary_size,
+            data_truncated: msg_flags & MSG_TRUNC != 0,
+            ancillary_truncated: msg_flags & MSG_CTRUNC != 0,
+        }
+    }
+    /// Returns `true` if either the main data or the ancillary data has been truncated, `false` otherwise.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.data_truncated || self.ancillary_truncated
+    }
+}
+/// Discards the truncation flags, producing a tuple of the main data size and the ancillary data size in that order.
+impl From<RecvAncillaryResult> for (usize, usize) {
+    #[inline]
+    fn from(res: RecvAncillaryResult) -> Self {
+        (res.size, res.ancillary_size)
+    }
+}
@@ -1,10 +1,15 @@
 use super::{
     c_wrappers,
-    cmsg::{CmsgMut, CmsgRef},
-    util::{make_msghdr_r, make_msghdr_w},
-    PathDropGuard, ToUdSocketPath, UdSocketPath,
+    cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+    fd_chunking,
+    long_path::with_sockaddr_un,
+    util::make_msghdr_r,
+    PathDropGuard, PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocketPath,
+};
+use crate::{
+    handle_conversion::FromHandleError,
+    os::unix::{unixprelude::*, FdOps},
 };
-use crate::os::unix::{unixprelude::*, FdOps};
 #[cfg(target_os = "linux")]
 use crate::{
     reliable_recv_msg::{ReliableRecvMsg, TryRecvResult},
@@ -53,15 +58,28 @@ impl UdSocket {
     pub fn bind_with_drop_guard<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
         Self::_bind(path.to_socket_path()?, true)
     }
+    /// Creates a new socket that isn't bound to any path. Such a socket can send datagrams via [`.send_to()`](Self::send_to) or after [`.set_destination()`](Self::set_destination), but whoever receives them will see an [unnamed](UdSocketPath::Unnamed) source address and thus won't be able to reply.
+    ///
+    /// Unlike the other constructors, this one doesn't enable `SO_PASSCRED`, since doing so makes Linux bind the socket to an autogenerated name in the [socket namespace] the first time a datagram is sent.
+    ///
+    /// # System calls
+    /// - `socket`
+    ///
+    /// [socket namespace]: enum.UdSocketPath.html#namespaced " "
+    pub fn unbound() -> io::Result<Self> {
+        let fd = c_wrappers::create_uds(SOCK_DGRAM, false)?;
+        Ok(Self {
+            fd,
+            _drop_guard: PathDropGuard::dummy(),
+        })
+    }
     fn _bind(path: UdSocketPath<'_>, keep_drop_guard: bool) -> io::Result<Self> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
-
         let fd = c_wrappers::create_uds(SOCK_DGRAM, false)?;
-        unsafe {
+        with_sockaddr_un(path.borrow(), |addr| unsafe {
             // SAFETY: addr is well-constructed
-            c_wrappers::bind(&fd, &addr)?;
-        }
-        c_wrappers::set_passcred(&fd, true)?;
+            c_wrappers::bind(fd.as_fd(), addr)
+        })?;
+        c_wrappers::set_passcred(fd.as_fd(), true)?;
 
         let dg = if keep_drop_guard && matches!(path, UdSocketPath::File(..)) {
             PathDropGuard {
@@ -94,14 +112,10 @@ impl UdSocket {
         self._set_destination(&path)
     }
     fn _set_destination(&self, path: &UdSocketPath<'_>) -> io::Result<()> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
-
-        unsafe {
+        with_sockaddr_un(path.borrow(), |addr| unsafe {
             // SAFETY: addr is well-constructed
-            c_wrappers::connect(&self.fd, &addr)?;
-        }
-
-        Ok(())
+            c_wrappers::connect(self.fd.as_fd(), addr)
+        })
     }
 
     /// Receives a single datagram from the socket, returning the size of the received datagram.
@@ -122,21 +136,17 @@ impl UdSocket {
         self.fd.read_vectored(bufs)
     }
 
-    /// Receives a single datagram and ancillary data from the socket. The return value is in the following order:
-    /// - How many bytes of the datagram were received
-    /// - How many bytes of ancillary data were received
+    /// Receives a single datagram and ancillary data from the socket. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
     ///
     /// # System calls
     /// - `recvmsg`
     ///
     /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
-    pub fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<(usize, usize)> {
+    pub fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
         self.recv_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf)
     }
 
-    /// Receives a single datagram and ancillary data from the socket, making use of [scatter input]. The return value is in the following order:
-    /// - How many bytes of the datagram were received
-    /// - How many bytes of ancillary data were received
+    /// Receives a single datagram and ancillary data from the socket, making use of [scatter input]. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
     ///
     /// # System calls
     /// - `recvmsg`
@@ -146,14 +156,8 @@ impl UdSocket {
         &self,
         bufs: &mut [IoSliceMut<'_>],
         abuf: &mut CmsgMut<'_>,
-    ) -> io::Result<(usize, usize)> {
-        let mut hdr = make_msghdr_r(bufs, abuf)?;
-
-        let (success, bytes_read) = unsafe {
-            let result = libc::recvmsg(self.as_raw_fd(), &mut hdr as *mut _, 0);
-            (result != -1, result as usize)
-        };
-        ok_or_ret_errno!(success => (bytes_read, hdr.msg_controllen as _))
+    ) -> io::Result<RecvAncillaryResult> {
+        c_wrappers::recvmsg(self.fd.as_fd(), bufs, abuf, 0)
     }
 
     /// Receives a single datagram and the source address from the socket, returning how much of the buffer was filled out.
@@ -180,12 +184,10 @@ impl UdSocket {
         addr_buf: &'b mut UdSocketPath<'a>,
     ) -> io::Result<usize> {
         self.recv_from_ancillary_vectored(bufs, &mut CmsgMut::new(&mut []), addr_buf)
-            .map(|x| x.0)
+            .map(|x| x.size)
     }
 
-    /// Receives a single datagram, ancillary data and the source address from the socket. The return value is in the following order:
-    /// - How many bytes of the datagram were received
-    /// - How many bytes of ancillary data were received
+    /// Receives a single datagram, ancillary data and the source address from the socket. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
     ///
     /// # System calls
     /// - `recvmsg`
@@ -195,13 +197,11 @@ impl UdSocket {
         buf: &mut [u8],
         abuf: &mut CmsgMut<'_>,
         addr_buf: &mut UdSocketPath<'_>,
-    ) -> io::Result<(usize, usize)> {
+    ) -> io::Result<RecvAncillaryResult> {
         self.recv_from_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf, addr_buf)
     }
 
-    /// Receives a single datagram, ancillary data and the source address from the socket, making use of [scatter input]. The return value is in the following order:
-    /// - How many bytes of the datagram were received
-    /// - How many bytes of ancillary data were received
+    /// Receives a single datagram, ancillary data and the source address from the socket, making use of [scatter input]. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
     ///
     /// # System calls
     /// - `recvmsg`
@@ -212,7 +212,7 @@ impl UdSocket {
         bufs: &mut [IoSliceMut<'_>],
         abuf: &mut CmsgMut<'_>,
         addr_buf: &mut UdSocketPath<'_>,
-    ) -> io::Result<(usize, usize)> {
+    ) -> io::Result<RecvAncillaryResult> {
         let mut hdr = make_msghdr_r(bufs, abuf)?;
 
         // SAFETY: sockaddr_un is POD
@@ -227,7 +227,15 @@ impl UdSocket {
         let path_length = hdr.msg_namelen as usize;
         if success {
             addr_buf.write_sockaddr_un_to_self(&addr_buf_staging, path_length);
-            Ok((bytes_read, hdr.msg_controllen as _))
+            unsafe {
+                // SAFETY: the kernel has just written this much valid ancillary data to the beginning of the buffer
+                abuf.set_init_len(hdr.msg_controllen as _);
+            }
+            Ok(RecvAncillaryResult::new(
+                bytes_read,
+                hdr.msg_controllen as _,
+                hdr.msg_flags,
+            ))
         } else {
             Err(io::Error::last_os_error())
         }
@@ -258,22 +266,38 @@ impl UdSocket {
     /// Sends a datagram into the socket.
     ///
     /// # System calls
-    /// - `write`
+    /// - `send`
     #[inline]
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.fd.write(buf)
+        c_wrappers::send(self.fd.as_fd(), buf)
     }
     // TODO sendto
+    /// Sends a datagram to the given address, regardless of the destination set by [`.set_destination()`](Self::set_destination), returning how many bytes were actually sent.
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    ///
+    /// # System calls
+    /// - `sendto`
+    pub fn send_to<'a>(&self, buf: &[u8], path: impl ToUdSocketPath<'a>) -> io::Result<usize> {
+        let path = path.to_socket_path()?;
+        self._send_to(buf, &path)
+    }
+    fn _send_to(&self, buf: &[u8], path: &UdSocketPath<'_>) -> io::Result<usize> {
+        with_sockaddr_un(path.borrow(), |addr| unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::sendto(self.fd.as_fd(), buf, addr)
+        })
+    }
     /// Sends a datagram into the socket, making use of [gather output] for the main data.
     ///
     ///
     /// # System calls
-    /// - `writev`
+    /// - `sendmsg`
     ///
     /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
     #[inline]
     pub fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.fd.write_vectored(bufs)
+        c_wrappers::sendmsg(self.fd.as_fd(), bufs, CmsgRef::empty(), 0).map(|(sent, _)| sent)
     }
     /// Sends a datagram and ancillary data into the socket.
     ///
@@ -294,13 +318,46 @@ impl UdSocket {
     ///
     /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
     pub fn send_ancillary_vectored(&self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
-        let hdr = make_msghdr_w(bufs, abuf)?;
+        c_wrappers::sendmsg(self.fd.as_fd(), bufs, abuf, 0)
+    }
 
-        let (success, bytes_written) = unsafe {
-            let result = libc::sendmsg(self.as_raw_fd(), &hdr as *const _, 0);
-            (result != -1, result as usize)
-        };
-        ok_or_ret_errno!(success => (bytes_written, hdr.msg_controllen as _))
+    /// Sends bytes together with the given file descriptors into the socket, splitting them across several datagrams if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each datagram but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the datagrams have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref())?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf)?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
     }
 
     /// Enables or disables the nonblocking mode for the socket. By default, it is disabled.
@@ -311,16 +368,59 @@ impl UdSocket {
     /// [`incoming`]: #method.incoming " "
     /// [`WouldBlock`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock " "
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        c_wrappers::set_nonblocking(&self.fd, nonblocking)
+        c_wrappers::set_nonblocking(self.fd.as_fd(), nonblocking)
     }
     /// Checks whether the socket is currently in nonblocking mode or not.
     pub fn is_nonblocking(&self) -> io::Result<bool> {
-        c_wrappers::get_nonblocking(&self.fd)
+        c_wrappers::get_nonblocking(self.fd.as_fd())
+    }
+
+    /// Enables or disables the `SO_TIMESTAMP` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`Timestamp`](super::cmsg::ancillary::timestamp::Timestamp) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestamp template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "solaris",
+            target_os = "illumos"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+    pub fn set_timestamp(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.fd.as_fd(), libc::SO_TIMESTAMP, enable)
+    }
+    /// Enables or disables the `SO_TIMESTAMPNS` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`TimestampNs`](super::cmsg::ancillary::timestamp::TimestampNs) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestampns template
+        feature = "doc_cfg",
+        doc(cfg(all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+    pub fn set_timestamp_ns(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.fd.as_fd(), libc::SO_TIMESTAMPNS, enable)
     }
 
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
         feature = "doc_cfg",
         doc(cfg(any(
             all(
@@ -332,13 +432,16 @@ impl UdSocket {
                     target_env = "musleabihf"
                 )
             ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
+            target_os = "android"
         )))
     )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(&self.fd)
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.fd.as_fd(), libc::SO_PASSSEC, enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.fd.as_fd())
     }
 }
 
@@ -356,7 +459,7 @@ impl Debug for UdSocket {
 impl ReliableRecvMsg for UdSocket {
     fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
         let mut size = self.peek_msg_size()?;
-        let fit = size > buf.len();
+        let fit = size <= buf.len();
         if fit {
             size = UdSocket::recv(self, buf)?;
         }
@@ -366,11 +469,32 @@ impl ReliableRecvMsg for UdSocket {
 #[cfg(target_os = "linux")]
 impl Sealed for UdSocket {}
 
+impl AsFd for UdSocket {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
 impl AsRawFd for UdSocket {
     fn as_raw_fd(&self) -> c_int {
         self.fd.as_raw_fd()
     }
 }
+impl From<UdSocket> for OwnedFd {
+    fn from(x: UdSocket) -> Self {
+        x.fd.into()
+    }
+}
+/// Fails if the file descriptor is not a Ud-socket of the `SOCK_DGRAM` type.
+impl TryFrom<OwnedFd> for UdSocket {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| c_wrappers::check_ud_socket(fd.as_fd(), &[SOCK_DGRAM], false))?;
+        Ok(Self {
+            fd: fd.into(),
+            _drop_guard: PathDropGuard::dummy(),
+        })
+    }
+}
 impl IntoRawFd for UdSocket {
     fn into_raw_fd(self) -> c_int {
         self.fd.into_raw_fd()
@@ -1,20 +1,28 @@
 use super::{
     c_wrappers,
-    cmsg::{CmsgMut, CmsgRef},
-    util::{make_msghdr_r, make_msghdr_w},
-    ToUdSocketPath, UdSocketPath,
+    cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+    fd_chunking,
+    long_path::with_sockaddr_un,
+    PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocketPath,
 };
-use crate::os::unix::{unixprelude::*, FdOps};
-use libc::{sockaddr_un, SOCK_STREAM};
+use crate::{
+    handle_conversion::FromHandleError,
+    os::unix::{unixprelude::*, FdOps},
+    reliable_recv_msg::TryRecvResult,
+    timeout::WaitReady,
+};
+use libc::{SOCK_SEQPACKET, SOCK_STREAM};
 use std::{
     fmt::{self, Debug, Formatter},
     io::{self, IoSlice, IoSliceMut, Read, Write},
     net::Shutdown,
+    time::Duration,
 };
-use to_method::To;
 
 /// A Unix domain socket byte stream, obtained either from [`UdStreamListener`](super::UdStreamListener) or by connecting to an existing server.
 ///
+/// Like with the other stream types of the crate, both `UdStream` and `&UdStream` implement [`Read`] and [`Write`], since sockets can be read from and written to by several threads at once. Receiving from one thread while sending from another therefore only needs two shared references to the same stream.
+///
 /// # Examples
 ///
 /// ## Basic client
@@ -42,21 +50,23 @@ impl UdStream {
     /// - `socket`
     /// - `connect`
     pub fn connect<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
-        Self::_connect(path.to_socket_path()?, false)
+        Self::_connect(path.to_socket_path()?, SOCK_STREAM, false)
+    }
+    /// Connects a `SOCK_SEQPACKET` socket, for use by message-mode local sockets.
+    pub(crate) fn connect_seqpacket(path: UdSocketPath<'_>) -> io::Result<Self> {
+        Self::_connect(path, SOCK_SEQPACKET, false)
     }
-    #[cfg(feature = "tokio")]
+    #[cfg(any(feature = "tokio", feature = "async-io"))]
     pub(crate) fn connect_nonblocking<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
-        Self::_connect(path.to_socket_path()?, true)
+        Self::_connect(path.to_socket_path()?, SOCK_STREAM, true)
     }
-    fn _connect(path: UdSocketPath<'_>, nonblocking: bool) -> io::Result<Self> {
-        let addr = path.try_to::<sockaddr_un>()?;
-
-        let fd = c_wrappers::create_uds(SOCK_STREAM, nonblocking)?;
-        unsafe {
+    fn _connect(path: UdSocketPath<'_>, ty: c_int, nonblocking: bool) -> io::Result<Self> {
+        let fd = c_wrappers::create_uds(ty, nonblocking)?;
+        with_sockaddr_un(path, |addr| unsafe {
             // SAFETY: addr is well-constructed
-            c_wrappers::connect(&fd, &addr)?;
-        }
-        c_wrappers::set_passcred(&fd, true)?;
+            c_wrappers::connect(fd.as_fd(), addr)
+        })?;
+        c_wrappers::set_passcred(fd.as_fd(), true)?;
 
         Ok(Self { fd })
     }
@@ -68,6 +78,11 @@ impl UdStream {
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
         self.fd.read(buf)
     }
+    /// Same as [`.recv()`](Self::recv), but accepts an uninitialized buffer.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn recv_to_uninit(&self, buf: &mut [std::mem::MaybeUninit<u8>]) -> io::Result<usize> {
+        self.fd.read_to_uninit(buf)
+    }
     /// Receives bytes from the socket stream, making use of [scatter input] for the main data.
     ///
     /// # System calls
@@ -84,7 +99,7 @@ impl UdStream {
     /// # System calls
     /// - `recvmsg`
     #[inline]
-    pub fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<(usize, usize)> {
+    pub fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
         self.recv_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf)
     }
     /// Receives bytes and ancillary data from the socket stream, making use of [scatter input] for the main data.
@@ -99,31 +114,65 @@ impl UdStream {
         &self,
         bufs: &mut [IoSliceMut<'_>],
         abuf: &mut CmsgMut<'_>,
-    ) -> io::Result<(usize, usize)> {
-        let mut hdr = make_msghdr_r(bufs, abuf)?;
+    ) -> io::Result<RecvAncillaryResult> {
+        c_wrappers::recvmsg(self.fd.as_fd(), bufs, abuf, 0)
+    }
 
-        let (success, bytes_read) = unsafe {
-            let result = libc::recvmsg(self.as_raw_fd(), &mut hdr as *mut _, 0);
-            (result != -1, result as usize)
-        };
-        ok_or_ret_errno!(success => (bytes_read, hdr.msg_controllen as _))
+    /// Receives bytes from the socket stream without removing them, so that they are received again by the next
+    /// receive operation. Blocks until data is available unless the stream is in nonblocking mode, like a regular receive
+    /// operation would.
+    ///
+    /// # System calls
+    /// - `recvmsg` with `MSG_PEEK`
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let no_abuf = &mut CmsgMut::new(&mut []);
+        Ok(c_wrappers::recvmsg(self.fd.as_fd(), &mut [IoSliceMut::new(buf)], no_abuf, libc::MSG_PEEK)?.size)
+    }
+    /// Returns how many bytes can be received from the socket stream without blocking.
+    ///
+    /// # System calls
+    /// - `ioctl` with `FIONREAD`
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        c_wrappers::bytes_available(self.fd.as_fd())
+    }
+    /// Waits until receiving from the socket stream would not block, returning `false` if the timeout expires first. No
+    /// timeout means waiting indefinitely.
+    ///
+    /// The stream is also considered readable once the other side has closed the connection or an error is pending, since
+    /// a receive operation would then return immediately as well.
+    ///
+    /// # System calls
+    /// - `poll`
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        c_wrappers::poll(self.fd.as_fd(), libc::POLLIN, timeout)
+    }
+    /// Waits until sending into the socket stream would not block, returning `false` if the timeout expires first. No
+    /// timeout means waiting indefinitely.
+    ///
+    /// # System calls
+    /// - `poll`
+    pub fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        c_wrappers::poll(self.fd.as_fd(), libc::POLLOUT, timeout)
     }
 
     /// Sends bytes into the socket stream.
     ///
+    /// Sending after the other side has hung up fails with [`BrokenPipe`](io::ErrorKind::BrokenPipe) instead of raising
+    /// `SIGPIPE`, unless that has been [opted out of](super::set_sigpipe_on_send).
+    ///
     /// # System calls
-    /// - `write`
+    /// - `send`
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.fd.write(buf)
+        c_wrappers::send(self.fd.as_fd(), buf)
     }
     /// Sends bytes into the socket stream, making use of [gather output] for the main data.
     ///
     /// # System calls
-    /// - `writev`
+    /// - `sendmsg`
     ///
     /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
     pub fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.fd.write_vectored(bufs)
+        c_wrappers::sendmsg(self.fd.as_fd(), bufs, CmsgRef::empty(), 0).map(|(sent, _)| sent)
     }
     /// Sends bytes and ancillary data into the socket stream.
     ///
@@ -145,13 +194,52 @@ impl UdStream {
     ///
     /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
     pub fn send_ancillary_vectored(&self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
-        let hdr = make_msghdr_w(bufs, abuf)?;
+        c_wrappers::sendmsg(self.fd.as_fd(), bufs, abuf, 0)
+    }
 
-        let (success, bytes_written) = unsafe {
-            let result = libc::sendmsg(self.as_raw_fd(), &hdr as *const _, 0);
-            (result != -1, result as usize)
-        };
-        ok_or_ret_errno!(success => (bytes_written, hdr.msg_controllen as _))
+    /// Sends bytes together with the given file descriptors into the socket stream, splitting them across several writes if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each write but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the writes have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref())?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf)?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
+    }
+
+    /// Receives one message from a `SOCK_SEQPACKET` socket if it fits into the buffer, leaving it in the socket otherwise, for use by message-mode local sockets.
+    #[inline]
+    pub(crate) fn try_recv_msg(&self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        c_wrappers::try_recv_msg(self.fd.as_fd(), buf)
     }
 
     /// Shuts down the read, write, or both halves of the stream. See [`Shutdown`].
@@ -159,7 +247,21 @@ impl UdStream {
     /// Attempting to call this method with the same `how` argument multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     #[inline]
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
-        c_wrappers::shutdown(&self.fd, how)
+        c_wrappers::shutdown(self.fd.as_fd(), how)
+    }
+
+    /// Creates a new handle to the same connection, which can be used from another thread independently of the original
+    /// one. The connection is closed once all handles are dropped.
+    ///
+    /// The clones share the nonblocking mode and all socket options, since those belong to the socket rather than the
+    /// file descriptor.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_DUPFD_CLOEXEC`)
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            fd: self.fd.try_clone()?,
+        })
     }
 
     /// Enables or disables the nonblocking mode for the stream. By default, it is disabled.
@@ -171,17 +273,18 @@ impl UdStream {
     /// [`WouldBlock`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.WouldBlock " "
     #[inline]
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
-        c_wrappers::set_nonblocking(&self.fd, nonblocking)
+        c_wrappers::set_nonblocking(self.fd.as_fd(), nonblocking)
     }
     /// Checks whether the stream is currently in nonblocking mode or not.
     #[inline]
     pub fn is_nonblocking(&self) -> io::Result<bool> {
-        c_wrappers::get_nonblocking(&self.fd)
+        c_wrappers::get_nonblocking(self.fd.as_fd())
     }
 
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
         feature = "doc_cfg",
         doc(cfg(any(
             all(
@@ -193,17 +296,20 @@ impl UdStream {
                     target_env = "musleabihf"
                 )
             ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
+            target_os = "android"
         )))
     )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(&self.fd)
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.fd.as_fd(), libc::SO_PASSSEC, enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.fd.as_fd())
     }
 }
 
-impl Read for UdStream {
+impl Read for &UdStream {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.fd.read(buf)
@@ -213,32 +319,88 @@ impl Read for UdStream {
         self.fd.read_vectored(bufs)
     }
 }
-impl Write for UdStream {
+impl Read for UdStream {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+    #[inline(always)]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&*self).read_vectored(bufs)
+    }
+}
+impl Write for &UdStream {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.fd.write(buf)
+        self.send(buf)
     }
     #[inline]
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.fd.write_vectored(bufs)
+        self.send_vectored(bufs)
     }
     fn flush(&mut self) -> io::Result<()> {
         // You cannot flush a socket
         Ok(())
     }
 }
+impl Write for UdStream {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+    #[inline(always)]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&*self).write_vectored(bufs)
+    }
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
 
+impl WaitReady for UdStream {
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_readable(timeout)
+    }
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.wait_writable(timeout)
+    }
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.set_nonblocking(nonblocking)
+    }
+}
 impl Debug for UdStream {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("UdStream").field("fd", &self.as_raw_fd()).finish()
     }
 }
 
+impl AsFd for UdStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
 impl AsRawFd for UdStream {
     fn as_raw_fd(&self) -> c_int {
         self.fd.as_raw_fd()
     }
 }
+impl From<UdStream> for OwnedFd {
+    fn from(x: UdStream) -> Self {
+        x.fd.into()
+    }
+}
+/// Fails if the file descriptor is not a Ud-socket of the `SOCK_STREAM` or `SOCK_SEQPACKET` type, or if it is a listening
+/// socket.
+impl TryFrom<OwnedFd> for UdStream {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| {
+            c_wrappers::check_ud_socket(fd.as_fd(), &[SOCK_STREAM, SOCK_SEQPACKET], false)
+        })?;
+        Ok(Self { fd: fd.into() })
+    }
+}
 impl IntoRawFd for UdStream {
     fn into_raw_fd(self) -> c_int {
         self.fd.into_raw_fd()
@@ -246,6 +408,8 @@ impl IntoRawFd for UdStream {
 }
 impl FromRawFd for UdStream {
     unsafe fn from_raw_fd(fd: c_int) -> Self {
-        Self { fd: FdOps::new(fd) }
+        Self {
+            fd: unsafe { FdOps::from_raw_fd(fd) },
+        }
     }
 }
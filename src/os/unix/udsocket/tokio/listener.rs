@@ -1,7 +1,19 @@
-use crate::os::unix::udsocket::{
-    tokio::UdStream, ToUdSocketPath, UdSocketPath, UdStreamListener as SyncUdStreamListener,
+use crate::{
+    listener_close::{AsyncCloseSignal, ListenerClosedError},
+    os::unix::{
+        udsocket::{
+            c_wrappers, tokio::UdStream, ToUdSocketPath, UdSocketPath, UdStreamListener as SyncUdStreamListener,
+        },
+        unixprelude::*,
+    },
+};
+use futures_core::ready;
+use std::{
+    convert::TryFrom,
+    fs, io,
+    os::unix::net::UnixListener as StdUdStreamListener,
+    task::{Context, Poll},
 };
-use std::{convert::TryFrom, io, os::unix::net::UnixListener as StdUdStreamListener};
 use tokio::net::UnixListener as TokioUdStreamListener;
 
 /// A Tokio-based Unix domain byte stream socket server, listening for connections.
@@ -93,7 +105,7 @@ use tokio::net::UnixListener as TokioUdStreamListener;
 /// # Ok(()) }
 /// ```
 #[derive(Debug)]
-pub struct UdStreamListener(TokioUdStreamListener);
+pub struct UdStreamListener(TokioUdStreamListener, AsyncCloseSignal);
 impl UdStreamListener {
     /// Creates a new listener socket at the specified address.
     ///
@@ -116,13 +128,84 @@ impl UdStreamListener {
         Self::from_sync(listener)
     }
     /// Listens for incoming connections to the socket, asynchronously waiting a client is connected.
+    ///
+    /// If the listener is [closed](Self::close) while this is pending or before it is called, an error wrapping [`ListenerClosedError`](super::ListenerClosedError) is returned.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: a client is only taken from the queue of pending connections once it can be returned.
     pub async fn accept(&self) -> io::Result<UdStream> {
-        Ok(self.0.accept().await?.0.into())
+        let (stream, _) = self.1.race(self.0.accept()).await?;
+        c_wrappers::set_nosigpipe(stream.as_fd())?;
+        Ok(stream.into())
+    }
+    /// Raw polling interface for accepting incoming connections. You probably want `.accept()` instead.
+    ///
+    /// Returns an error wrapping [`ListenerClosedError`](super::ListenerClosedError) if the listener has been [closed](Self::close), including when it is closed while the task is waiting for this method to return `Poll::Ready`.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<UdStream>> {
+        if self.1.poll_closed(cx) {
+            return Poll::Ready(Err(ListenerClosedError.into()));
+        }
+        let (stream, _) = ready!(self.0.poll_accept(cx)).map_err(|e| {
+            self.1.forget(cx);
+            e
+        })?;
+        self.1.forget(cx);
+        c_wrappers::set_nosigpipe(stream.as_fd())?;
+        Poll::Ready(Ok(stream.into()))
+    }
+    /// Closes the listener, waking up all pending [`.accept()`](Self::accept) calls with an error wrapping [`ListenerClosedError`](super::ListenerClosedError). All subsequent calls to `.accept()` will fail with the same error.
+    ///
+    /// The socket file is left intact – use [`.close_and_unlink()`](Self::close_and_unlink) to remove it as well. The file descriptor of the listener is only closed when it's dropped.
+    pub fn close(&self) {
+        self.1.close()
+    }
+    /// Same as [`.close()`](Self::close), but also deletes the socket file if the listener is bound to a filesystem path. Listeners in the [socket namespace] don't have files and thus are simply closed.
+    ///
+    /// The socket path is retrieved via `getsockname`, meaning that a listener bound to a relative path resolves it relative to the *current* working directory, which may have changed since the listener was created.
+    ///
+    /// # System calls
+    /// - `getsockname`
+    /// - `unlink`
+    ///
+    /// [socket namespace]: super::super::UdSocketPath::Namespaced
+    pub fn close_and_unlink(&self) -> io::Result<()> {
+        self.close();
+        if let Some(path) = self.0.local_addr()?.as_pathname() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+    /// Returns `true` if the listener has been [closed](Self::close), `false` otherwise.
+    pub fn is_closed(&self) -> bool {
+        self.1.is_closed()
+    }
+    /// Unwraps into Tokio's corresponding type. This is a zero-cost operation.
+    pub fn into_tokio(self) -> TokioUdStreamListener {
+        self.0
+    }
+    /// Wraps Tokio's corresponding type. This is a zero-cost operation.
+    pub fn from_tokio(tokio: TokioUdStreamListener) -> Self {
+        Self(tokio, AsyncCloseSignal::default())
+    }
+    /// Creates a Tokio-based async object from a given raw file descriptor. This will also attach the object to the Tokio runtime this function is called in, so calling it outside a runtime will result in an error (which is why the `FromRawFd` trait can't be implemented instead).
+    ///
+    /// # Safety
+    /// The given file descriptor must be valid (i.e. refer to an existing kernel object) and must not be owned by any other file descriptor container. If this is not upheld, an arbitrary file descriptor will be closed when the returned object is dropped.
+    pub unsafe fn from_raw_fd(fd: libc::c_int) -> io::Result<Self> {
+        let std = unsafe { std::os::unix::io::FromRawFd::from_raw_fd(fd) };
+        let tokio = TokioUdStreamListener::from_std(std)?;
+        Ok(Self::from_tokio(tokio))
+    }
+    /// Releases ownership of the raw file descriptor, detaches the object from the Tokio runtime (therefore has to be called within the runtime) and returns the file descriptor as an integer.
+    pub fn into_raw_fd(self) -> io::Result<libc::c_int> {
+        let std = TokioUdStreamListener::into_std(self.0)?;
+        let fd = std::os::unix::io::IntoRawFd::into_raw_fd(std);
+        Ok(fd)
     }
+    // The close signal doesn't fit into the tuple struct layout expected by the `tokio` case.
     tokio_wrapper_conversion_methods!(
         sync SyncUdStreamListener,
-        std StdUdStreamListener,
-        tokio TokioUdStreamListener);
+        std StdUdStreamListener);
 }
 tokio_wrapper_trait_impls!(
     for UdStreamListener,
@@ -12,3 +12,5 @@ mod listener;
 mod socket;
 mod stream;
 pub use {listener::*, socket::*, stream::*};
+
+pub use crate::listener_close::ListenerClosedError;
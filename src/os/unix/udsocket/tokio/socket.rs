@@ -1,16 +1,31 @@
-use crate::os::unix::udsocket::{ToUdSocketPath, UdSocket as SyncUdSocket, UdSocketPath};
-#[cfg(uds_peerucred)]
-use crate::os::unix::{udsocket::c_wrappers, unixprelude::*};
+use crate::{
+    msg_adapters::AsyncSendMsg,
+    os::unix::{
+        udsocket::{
+            c_wrappers,
+            cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+            fd_chunking,
+            long_path::with_sockaddr_un,
+            PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocket as SyncUdSocket, UdSocketPath,
+        },
+        unixprelude::*,
+    },
+    reliable_recv_msg::{AsyncReliableRecvMsg, TryRecvResult},
+};
+use futures_core::ready;
 use std::{
     convert::TryFrom,
-    future::Future,
-    io,
+    future::{poll_fn, Future},
+    io::{self, IoSlice, IoSliceMut},
     net::Shutdown,
     os::unix::net::UnixDatagram as StdUdSocket,
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::{io::ReadBuf as TokioReadBuf, net::UnixDatagram as TokioUdSocket};
+use tokio::{
+    io::{Interest, ReadBuf as TokioReadBuf},
+    net::UnixDatagram as TokioUdSocket,
+};
 
 /// A Unix domain datagram socket, obtained either from [`UdSocketListener`](super::UdSocketListener) or by connecting to an existing server.
 ///
@@ -76,8 +91,10 @@ impl UdSocket {
         Self::_bind(path.to_socket_path()?)
     }
     fn _bind(path: UdSocketPath<'_>) -> io::Result<Self> {
-        let socket = TokioUdSocket::bind(path.as_osstr())?;
-        Ok(Self(socket))
+        // Going through the blocking socket, since it knows how to bind to namespaced and overlong paths.
+        let socket = SyncUdSocket::bind(path)?;
+        socket.set_nonblocking(true)?;
+        Self::from_sync(socket)
     }
     /// Selects the Unix domain socket to send packets to. You can also just use [`.send_to()`](Self::send_to) instead, but supplying the address to the kernel once is more efficient.
     ///
@@ -86,7 +103,10 @@ impl UdSocket {
         self._set_destination(path.to_socket_path()?)
     }
     fn _set_destination(&self, path: UdSocketPath<'_>) -> io::Result<()> {
-        self.0.connect(path.as_osstr())
+        with_sockaddr_un(path, |addr| unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::connect(self.as_fd(), addr)
+        })
     }
     /// Shuts down the read, write, or both halves of the socket. See [`Shutdown`].
     ///
@@ -123,7 +143,7 @@ impl UdSocket {
     }
     /// Sends a single datagram into the socket, returning how many bytes were actually sent.
     pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.0.send(buf).await
+        poll_fn(|cx| self.poll_send(cx, buf)).await
     }
     /// Sends a single datagram to the given address, returning how many bytes were actually sent.
     pub async fn send_to(&self, buf: &[u8], path: impl ToUdSocketPath<'_>) -> io::Result<usize> {
@@ -131,7 +151,7 @@ impl UdSocket {
         self._send_to(buf, &path).await
     }
     async fn _send_to(&self, buf: &[u8], path: &UdSocketPath<'_>) -> io::Result<usize> {
-        self.0.send_to(buf, path.as_osstr()).await
+        poll_fn(|cx| self._poll_send_to(cx, buf, path)).await
     }
     /// Asynchronously waits until the socket becomes writable due to the other side freeing up space in its OS receive buffer.
     ///
@@ -150,7 +170,8 @@ impl UdSocket {
     }
     /// Raw polling interface for sending datagrams. You probably want `.send()` instead.
     pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.0.poll_send(cx, buf)
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::WRITABLE, || c_wrappers::send(fd, buf))
     }
     /// Raw polling interface for sending datagrams. You probably want `.send_to()` instead.
     pub fn poll_send_to<'a>(
@@ -163,11 +184,171 @@ impl UdSocket {
         self._poll_send_to(cx, buf, &path)
     }
     fn _poll_send_to(&self, cx: &mut Context<'_>, buf: &[u8], path: &UdSocketPath<'_>) -> Poll<io::Result<usize>> {
-        self.0.poll_send_to(cx, buf, path.as_osstr())
+        let fd = self.as_fd();
+        with_sockaddr_un(path.borrow(), |addr| {
+            Ok(self.poll_io(cx, Interest::WRITABLE, || unsafe {
+                // SAFETY: addr is well-constructed
+                c_wrappers::sendto(fd, buf, addr)
+            }))
+        })?
+    }
+    /// Receives a single datagram and ancillary data from the socket, asynchronously waiting for it to arrive. The return value reports how many bytes of data and ancillary data were received, as well as whether either of them was truncated.
+    pub async fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
+        poll_fn(|cx| self.poll_recv_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for receiving datagrams with ancillary data. You probably want `.recv_ancillary()` instead.
+    #[inline]
+    pub fn poll_recv_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        self.poll_recv_ancillary_vectored(cx, &mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Raw polling interface for receiving datagrams with ancillary data, making use of [scatter input] for the main data.
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_recv_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::READABLE, || c_wrappers::recvmsg(fd, bufs, abuf, 0))
+    }
+    /// Sends a single datagram and ancillary data into the socket, asynchronously waiting for buffer space to become available. The return value is in the following order:
+    /// - How many bytes of the datagram were sent
+    /// - How many bytes of ancillary data were sent
+    pub async fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
+        poll_fn(|cx| self.poll_send_ancillary(cx, buf, abuf)).await
     }
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
+
+    /// Sends bytes together with the given file descriptors into the socket, splitting them across several datagrams if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each datagram but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the datagrams have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref()).await?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf).await?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
+    }
+    /// Raw polling interface for sending datagrams with ancillary data. You probably want `.send_ancillary()` instead.
+    #[inline]
+    pub fn poll_send_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        self.poll_send_ancillary_vectored(cx, &[IoSlice::new(buf)], abuf)
+    }
+    /// Raw polling interface for sending datagrams with ancillary data, making use of [gather output] for the main data.
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_send_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::WRITABLE, || c_wrappers::sendmsg(fd, bufs, abuf, 0))
+    }
+    /// Waits for the given readiness and performs the nonblocking operation, repeating if the readiness event turns out to be spurious.
+    fn poll_io<T>(
+        &self,
+        cx: &mut Context<'_>,
+        interest: Interest,
+        mut f: impl FnMut() -> io::Result<T>,
+    ) -> Poll<io::Result<T>> {
+        loop {
+            if interest.is_readable() {
+                ready!(self.0.poll_recv_ready(cx))?;
+            } else {
+                ready!(self.0.poll_send_ready(cx))?;
+            }
+            match self.0.try_io(interest, &mut f) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                els => return Poll::Ready(els),
+            }
+        }
+    }
+    /// Enables or disables the `SO_TIMESTAMP` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`Timestamp`](crate::os::unix::udsocket::cmsg::ancillary::timestamp::Timestamp) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestamp template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "solaris",
+            target_os = "illumos"
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestamp))]
+    pub fn set_timestamp(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.as_fd(), libc::SO_TIMESTAMP, enable)
+    }
+    /// Enables or disables the `SO_TIMESTAMPNS` socket option. By default, it is disabled.
+    ///
+    /// While enabled, every datagram received with an ancillary data buffer will be accompanied by a [`TimestampNs`](crate::os::unix::udsocket::cmsg::ancillary::timestamp::TimestampNs) control message specifying when it was received by the kernel.
+    #[cfg_attr( // uds_scm_timestampns template
+        feature = "doc_cfg",
+        doc(cfg(all(
+            target_os = "linux",
+            any(
+                target_env = "gnu",
+                target_env = "musl",
+                target_env = "musleabi",
+                target_env = "musleabihf"
+            )
+        )))
+    )]
+    #[cfg(any(all(doc, not(doctest)), uds_scm_timestampns))]
+    pub fn set_timestamp_ns(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.as_fd(), libc::SO_TIMESTAMPNS, enable)
+    }
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
         feature = "doc_cfg",
         doc(cfg(any(
             all(
@@ -179,13 +360,16 @@ impl UdSocket {
                     target_env = "musleabihf"
                 )
             ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
+            target_os = "android"
         )))
     )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_raw_fd().as_ref())
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.as_fd(), libc::SO_PASSSEC, enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_fd())
     }
     tokio_wrapper_conversion_methods!(
         sync SyncUdSocket,
@@ -193,6 +377,33 @@ impl UdSocket {
         tokio TokioUdSocket);
 }
 
+impl AsyncReliableRecvMsg for &UdSocket {
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::READABLE, || c_wrappers::try_recv_msg(fd, buf))
+    }
+}
+impl AsyncReliableRecvMsg for UdSocket {
+    #[inline]
+    fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        Pin::new(&mut &*self).poll_try_recv(cx, buf)
+    }
+}
+impl AsyncSendMsg for &UdSocket {
+    /// Sends a datagram to the [destination](UdSocket::set_destination) of the socket.
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_send(cx, buf)
+    }
+}
+impl AsyncSendMsg for UdSocket {
+    /// Sends a datagram to the [destination](UdSocket::set_destination) of the socket.
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_send(cx, buf)
+    }
+}
+
 tokio_wrapper_trait_impls!(
     for UdSocket,
     sync SyncUdSocket,
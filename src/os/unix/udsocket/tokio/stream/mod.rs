@@ -1,18 +1,24 @@
-use crate::os::unix::udsocket::{c_wrappers, ToUdSocketPath, UdSocketPath, UdStream as SyncUdStream};
+use crate::os::unix::udsocket::{
+    c_wrappers,
+    cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgMut, CmsgRef},
+    fd_chunking, PeerCredentials, RecvAncillaryResult, ToUdSocketPath, UdSocketPath, UdStream as SyncUdStream,
+};
 use crate::os::unix::unixprelude::*;
+use futures_core::ready;
 use futures_io::{AsyncRead, AsyncWrite};
 use std::{
     convert::TryFrom,
     error::Error,
     fmt::{self, Formatter},
-    io,
+    future::poll_fn,
+    io::{self, IoSlice, IoSliceMut},
     net::Shutdown,
     os::unix::net::UnixStream as StdUdStream,
     pin::Pin,
     task::{Context, Poll},
 };
 use tokio::{
-    io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
+    io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, Interest, ReadBuf as TokioReadBuf},
     net::{unix::ReuniteError as TokioReuniteError, UnixStream as TokioUdStream},
 };
 
@@ -22,6 +28,18 @@ mod write_half;
 use connect_future::*;
 pub use {read_half::*, write_half::*};
 
+/// Sends bytes into a Tokio stream with [`c_wrappers::send()`], which, unlike the `write` that Tokio's own
+/// `AsyncWrite` implementations use, doesn't raise `SIGPIPE` if the other side has hung up.
+fn poll_send(stream: &TokioUdStream, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+    loop {
+        ready!(stream.poll_write_ready(cx))?;
+        match stream.try_io(Interest::WRITABLE, || c_wrappers::send(stream.as_fd(), buf)) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            els => return Poll::Ready(els),
+        }
+    }
+}
+
 /// A Unix domain socket byte stream, obtained either from [`UdStreamListener`](super::UdStreamListener) or by connecting to an existing server.
 ///
 /// # Examples
@@ -112,11 +130,12 @@ impl UdStream {
     ///
     /// Attempting to call this method with the same `how` argument multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
-        c_wrappers::shutdown(self.as_raw_fd().as_ref(), how)
+        c_wrappers::shutdown(self.as_fd(), how)
     }
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
+    /// Enables or disables the `SO_PASSSEC` socket option. By default, it is disabled.
+    ///
+    /// While enabled, data received with an ancillary data buffer will be accompanied by a [`Security`](crate::os::unix::udsocket::cmsg::ancillary::security::Security) control message containing the security context of the sending process, as long as a Linux Security Module which supports security contexts is active.
+    #[cfg_attr( // uds_scm_security template
         feature = "doc_cfg",
         doc(cfg(any(
             all(
@@ -128,13 +147,166 @@ impl UdStream {
                     target_env = "musleabihf"
                 )
             ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
+            target_os = "android"
         )))
     )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_raw_fd().as_ref())
+    #[cfg(any(all(doc, not(doctest)), uds_scm_security))]
+    pub fn set_passsec(&self, enable: bool) -> io::Result<()> {
+        c_wrappers::set_socket_option_bool(self.as_fd(), libc::SO_PASSSEC, enable)
+    }
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_fd())
+    }
+
+    /// Receives bytes from the socket stream, asynchronously waiting for them to arrive. Unlike the `AsyncRead` implementations, this only requires a shared reference.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_recv(cx, buf)).await
+    }
+    /// Raw polling interface for receiving bytes. You probably want `.recv()` instead.
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.poll_io(cx, Interest::READABLE, || self.0.try_read(buf))
+    }
+    /// Receives both bytes and ancillary data from the socket stream, asynchronously waiting for them to arrive. The return value reports how many bytes of data and ancillary data were received, as well as whether the ancillary data was truncated.
+    pub async fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut CmsgMut<'_>) -> io::Result<RecvAncillaryResult> {
+        poll_fn(|cx| self.poll_recv_ancillary(cx, buf, abuf)).await
+    }
+    /// Raw polling interface for receiving bytes and ancillary data. You probably want `.recv_ancillary()` instead.
+    #[inline]
+    pub fn poll_recv_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        self.poll_recv_ancillary_vectored(cx, &mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Raw polling interface for receiving bytes and ancillary data, making use of [scatter input] for the main data.
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_recv_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut CmsgMut<'_>,
+    ) -> Poll<io::Result<RecvAncillaryResult>> {
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::READABLE, || c_wrappers::recvmsg(fd, bufs, abuf, 0))
+    }
+    /// Sends bytes into the socket stream, asynchronously waiting for buffer space to become available. Unlike the `AsyncWrite` implementations, this only requires a shared reference.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        poll_fn(|cx| self.poll_send(cx, buf)).await
+    }
+    /// Raw polling interface for sending bytes. You probably want `.send()` instead.
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        poll_send(&self.0, cx, buf)
+    }
+    /// Waits until the socket becomes readable, which usually means that [`.try_recv()`](Self::try_recv) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_recv()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn readable(&self) -> io::Result<()> {
+        self.0.readable().await
+    }
+    /// Waits until the socket becomes writable, which usually means that [`.try_send()`](Self::try_send) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_send()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn writable(&self) -> io::Result<()> {
+        self.0.writable().await
+    }
+    /// Receives bytes from the socket stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if none are available.
+    pub fn try_recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.try_read(buf)
+    }
+    /// Sends bytes into the socket stream without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no buffer space available.
+    pub fn try_send(&self, buf: &[u8]) -> io::Result<usize> {
+        let fd = self.as_fd();
+        self.0.try_io(Interest::WRITABLE, || c_wrappers::send(fd, buf))
+    }
+    /// Sends bytes and ancillary data into the socket stream, asynchronously waiting for buffer space to become available. The return value is in the following order:
+    /// - How many bytes of data were sent
+    /// - How many bytes of ancillary data were sent
+    pub async fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<(usize, usize)> {
+        poll_fn(|cx| self.poll_send_ancillary(cx, buf, abuf)).await
+    }
+
+    /// Sends bytes together with the given file descriptors into the socket stream, splitting them across several writes if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Each write but the last one carries exactly one byte of `buf`, which means that sending `n` file descriptors requires `buf` to be at least `n / SCM_MAX_FD + 1` bytes long. If an error occurs after some of the writes have already been sent, the peer will have received a part of the file descriptors.
+    ///
+    /// The other side should use [`recv_fds()`](Self::recv_fds) to put the pieces back together.
+    ///
+    /// # System calls
+    /// - `sendmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn send_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut sent = 0;
+        for (data, fds) in fd_chunking::chunks(buf, fds)? {
+            let abuf = fd_chunking::encode(fds);
+            sent += self.send_ancillary(data, abuf.as_ref()).await?.0;
+        }
+        Ok(sent)
+    }
+    /// Receives bytes and file descriptors sent via [`send_fds()`](Self::send_fds), performing as many receive operations as needed to reassemble all the file descriptors if there are more than [`SCM_MAX_FD`] of them.
+    ///
+    /// Returns the amount of bytes received and the file descriptors. Control messages other than file descriptors are discarded.
+    ///
+    /// # System calls
+    /// - `recvmsg`, once for every `SCM_MAX_FD` file descriptors
+    ///
+    /// [`SCM_MAX_FD`]: crate::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD
+    pub async fn recv_fds(&self, buf: &mut [u8]) -> io::Result<(usize, OwnedFileDescriptors)> {
+        let mut state = fd_chunking::Reassembler::default();
+        let mut abuf_storage = fd_chunking::Reassembler::cmsg_buffer();
+        loop {
+            let mut abuf = abuf_storage.as_mut();
+            let rslt = self.recv_ancillary(&mut buf[state.filled()..], &mut abuf).await?;
+            if !state.feed(rslt, abuf.as_ref(), buf.len())? {
+                break;
+            }
+        }
+        Ok(state.finish())
+    }
+    /// Raw polling interface for sending bytes and ancillary data. You probably want `.send_ancillary()` instead.
+    #[inline]
+    pub fn poll_send_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        self.poll_send_ancillary_vectored(cx, &[IoSlice::new(buf)], abuf)
+    }
+    /// Raw polling interface for sending bytes and ancillary data, making use of [gather output] for the main data.
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn poll_send_ancillary_vectored(
+        &self,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+    ) -> Poll<io::Result<(usize, usize)>> {
+        let fd = self.as_fd();
+        self.poll_io(cx, Interest::WRITABLE, || c_wrappers::sendmsg(fd, bufs, abuf, 0))
+    }
+    /// Waits for the given readiness and performs the nonblocking operation, repeating if the readiness event turns out to be spurious.
+    fn poll_io<T>(
+        &self,
+        cx: &mut Context<'_>,
+        interest: Interest,
+        mut f: impl FnMut() -> io::Result<T>,
+    ) -> Poll<io::Result<T>> {
+        loop {
+            if interest.is_readable() {
+                ready!(self.0.poll_read_ready(cx))?;
+            } else {
+                ready!(self.0.poll_write_ready(cx))?;
+            }
+            match self.0.try_io(interest, &mut f) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                els => return Poll::Ready(els),
+            }
+        }
     }
     fn pinproject(self: Pin<&mut Self>) -> Pin<&mut TokioUdStream> {
         Pin::new(&mut self.get_mut().0)
@@ -167,7 +339,7 @@ impl AsyncRead for UdStream {
 }
 impl TokioAsyncWrite for UdStream {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, io::Error>> {
-        self.pinproject().poll_write(cx, buf)
+        self.poll_send(cx, buf)
     }
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
         self.pinproject().poll_flush(cx)
@@ -179,7 +351,7 @@ impl TokioAsyncWrite for UdStream {
 }
 impl AsyncWrite for UdStream {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, io::Error>> {
-        self.pinproject().poll_write(cx, buf)
+        self.poll_send(cx, buf)
     }
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
         self.pinproject().poll_flush(cx)
@@ -1,4 +1,4 @@
-use super::{c_wrappers, OwnedWriteHalf, ReuniteError, UdStream};
+use super::{c_wrappers, OwnedWriteHalf, PeerCredentials, ReuniteError, UdStream};
 use crate::os::unix::unixprelude::*;
 use futures_io::AsyncRead;
 use std::{
@@ -20,39 +20,21 @@ use tokio::{
 pub struct BorrowedReadHalf<'a>(pub(super) TokioUdStreamReadHalf<'a>);
 
 impl<'a> BorrowedReadHalf<'a> {
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
-        feature = "doc_cfg",
-        doc(cfg(any(
-            all(
-                target_os = "linux",
-                any(
-                    target_env = "gnu",
-                    target_env = "musl",
-                    target_env = "musleabi",
-                    target_env = "musleabihf"
-                )
-            ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
-        )))
-    )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_stream_raw_fd().as_ref())
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_stream_fd())
     }
     /// Shuts down the read half.
     ///
     /// Attempting to call this method multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     pub fn shutdown(&self) -> io::Result<()> {
-        c_wrappers::shutdown(self.as_stream_raw_fd().as_ref(), Shutdown::Read)
+        c_wrappers::shutdown(self.as_stream_fd(), Shutdown::Read)
     }
 
     /// Returns the underlying file descriptor. Note that this isn't a file descriptor for the read half specifically, but rather for the whole stream, so this isn't exposed as a struct method.
-    fn as_stream_raw_fd(&self) -> c_int {
+    fn as_stream_fd(&self) -> BorrowedFd<'_> {
         let stream: &TokioUdStream = self.0.as_ref();
-        stream.as_raw_fd()
+        stream.as_fd()
     }
 
     fn pinproject(self: Pin<&mut Self>) -> Pin<&mut TokioUdStreamReadHalf<'a>> {
@@ -90,40 +72,22 @@ impl OwnedReadHalf {
         UdStream::reunite(self, write)
     }
 
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
-        feature = "doc_cfg",
-        doc(cfg(any(
-            all(
-                target_os = "linux",
-                any(
-                    target_env = "gnu",
-                    target_env = "musl",
-                    target_env = "musleabi",
-                    target_env = "musleabihf"
-                )
-            ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
-        )))
-    )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_stream_raw_fd().as_ref())
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_stream_fd())
     }
 
     /// Shuts down the read half.
     ///
     /// Attempting to call this method multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     pub fn shutdown(&self) -> io::Result<()> {
-        c_wrappers::shutdown(self.as_stream_raw_fd().as_ref(), Shutdown::Read)
+        c_wrappers::shutdown(self.as_stream_fd(), Shutdown::Read)
     }
 
     /// Returns the underlying file descriptor. Note that this isn't a file descriptor for the read half specifically, but rather for the whole stream, so this isn't exposed as a struct method.
-    fn as_stream_raw_fd(&self) -> c_int {
+    fn as_stream_fd(&self) -> BorrowedFd<'_> {
         let stream: &TokioUdStream = self.0.as_ref();
-        stream.as_raw_fd()
+        stream.as_fd()
     }
 
     fn pinproject(self: Pin<&mut Self>) -> Pin<&mut TokioUdStreamOwnedReadHalf> {
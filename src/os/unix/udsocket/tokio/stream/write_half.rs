@@ -1,4 +1,4 @@
-use super::{c_wrappers, OwnedReadHalf, ReuniteError, UdStream};
+use super::{c_wrappers, poll_send, OwnedReadHalf, PeerCredentials, ReuniteError, UdStream};
 use crate::os::unix::unixprelude::*;
 use futures_io::AsyncWrite;
 use std::{
@@ -20,39 +20,21 @@ use tokio::{
 pub struct BorrowedWriteHalf<'a>(pub(super) TokioUdStreamWriteHalf<'a>);
 
 impl<'a> BorrowedWriteHalf<'a> {
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
-        feature = "doc_cfg",
-        doc(cfg(any(
-            all(
-                target_os = "linux",
-                any(
-                    target_env = "gnu",
-                    target_env = "musl",
-                    target_env = "musleabi",
-                    target_env = "musleabihf"
-                )
-            ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
-        )))
-    )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_stream_raw_fd().as_ref())
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_stream_fd())
     }
     /// Shuts down the write half.
     ///
     /// Attempting to call this method multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     pub fn shutdown(&self) -> io::Result<()> {
-        c_wrappers::shutdown(self.as_stream_raw_fd().as_ref(), Shutdown::Write)
+        c_wrappers::shutdown(self.as_stream_fd(), Shutdown::Write)
     }
 
     /// Returns the underlying file descriptor. Note that this isn't a file descriptor for the write half specifically, but rather for the whole stream, so this isn't exposed as a struct method.
-    fn as_stream_raw_fd(&self) -> c_int {
+    fn as_stream_fd(&self) -> BorrowedFd<'_> {
         let stream: &TokioUdStream = self.0.as_ref();
-        stream.as_raw_fd()
+        stream.as_fd()
     }
 
     fn pinproject(self: Pin<&mut Self>) -> Pin<&mut TokioUdStreamWriteHalf<'a>> {
@@ -64,7 +46,7 @@ impl<'a> BorrowedWriteHalf<'a> {
 
 impl TokioAsyncWrite for BorrowedWriteHalf<'_> {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, io::Error>> {
-        self.pinproject().poll_write(cx, buf)
+        poll_send(self.0.as_ref(), cx, buf)
     }
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
         self.pinproject().poll_flush(cx)
@@ -76,7 +58,7 @@ impl TokioAsyncWrite for BorrowedWriteHalf<'_> {
 }
 impl AsyncWrite for BorrowedWriteHalf<'_> {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, io::Error>> {
-        self.pinproject().poll_write(cx, buf)
+        poll_send(self.0.as_ref(), cx, buf)
     }
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
         self.pinproject().poll_flush(cx)
@@ -100,40 +82,22 @@ impl OwnedWriteHalf {
         UdStream::reunite(read, self)
     }
 
-    /// Fetches the credentials of the other end of the connection without using ancillary data. The returned structure contains the process identifier, user identifier and group identifier of the peer.
-    #[cfg(uds_peerucred)]
-    #[cfg_attr( // uds_peerucred template
-        feature = "doc_cfg",
-        doc(cfg(any(
-            all(
-                target_os = "linux",
-                any(
-                    target_env = "gnu",
-                    target_env = "musl",
-                    target_env = "musleabi",
-                    target_env = "musleabihf"
-                )
-            ),
-            target_os = "emscripten",
-            target_os = "redox",
-            target_os = "haiku"
-        )))
-    )]
-    pub fn get_peer_credentials(&self) -> io::Result<libc::ucred> {
-        c_wrappers::get_peer_ucred(self.as_stream_raw_fd().as_ref())
+    /// Fetches the credentials of the other end of the connection without using ancillary data. See [`PeerCredentials`] for which of them are available on which platforms.
+    pub fn get_peer_credentials(&self) -> io::Result<PeerCredentials> {
+        c_wrappers::get_peer_credentials(self.as_stream_fd())
     }
 
     /// Shuts down the write half.
     ///
     /// Attempting to call this method multiple times may return `Ok(())` every time or it may return an error the second time it is called, depending on the platform. You must either avoid using the same value twice or ignore the error entirely.
     pub fn shutdown(&self) -> io::Result<()> {
-        c_wrappers::shutdown(self.as_stream_raw_fd().as_ref(), Shutdown::Write)
+        c_wrappers::shutdown(self.as_stream_fd(), Shutdown::Write)
     }
 
     /// Returns the underlying file descriptor. Note that this isn't a file descriptor for the write half specifically, but rather for the whole stream, so this isn't exposed as a struct method.
-    fn as_stream_raw_fd(&self) -> c_int {
+    fn as_stream_fd(&self) -> BorrowedFd<'_> {
         let stream: &TokioUdStream = self.0.as_ref();
-        stream.as_raw_fd()
+        stream.as_fd()
     }
 
     fn pinproject(self: Pin<&mut Self>) -> Pin<&mut TokioUdStreamOwnedWriteHalf> {
@@ -145,7 +109,7 @@ impl OwnedWriteHalf {
 
 impl TokioAsyncWrite for OwnedWriteHalf {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproject().poll_write(cx, buf)
+        poll_send(self.0.as_ref(), cx, buf)
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
@@ -159,7 +123,7 @@ impl TokioAsyncWrite for OwnedWriteHalf {
 }
 impl AsyncWrite for OwnedWriteHalf {
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproject().poll_write(cx, buf)
+        poll_send(self.0.as_ref(), cx, buf)
     }
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
         self.pinproject().poll_flush(cx)
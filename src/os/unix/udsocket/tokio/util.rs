@@ -76,6 +76,11 @@ macro_rules! tokio_wrapper_trait_impls {
     (for $slf:ty, tokio $tok:ty) => {
         tokio_wrapper_trait_impls!(for $slf, tokio_norawfd $tok);
 
+        impl std::os::unix::io::AsFd for $slf {
+            fn as_fd(&self) -> std::os::unix::io::BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
         impl std::os::unix::io::AsRawFd for $slf {
             fn as_raw_fd(&self) -> libc::c_int {
                 self.0.as_raw_fd()
@@ -1,37 +1,374 @@
-use super::FdOps;
+//! Unix-specific functionality for unnamed pipes.
+//!
+//! This consists of the [`UnnamedPipeCreationOptions`] builder, which controls which ends of the pipe are inherited by
+//! child processes, and, on Linux and Android, the [`UnnamedPipeExt`] trait, which allows the capacity of a pipe to be
+//! queried and changed, as well as the [`UnnamedPipeReaderExt`] and [`UnnamedPipeWriterExt`] traits, which move data
+//! between pipes and other files without copying it through userspace.
+
+use super::{check_file_type, set_nonblocking, FdOps};
 use crate::{
-    unnamed_pipe::{UnnamedPipeReader as PubReader, UnnamedPipeWriter as PubWriter},
-    Sealed,
+    handle_conversion::FromHandleError,
+    unnamed_pipe::{UnnamedPipeOptions, UnnamedPipeReader as PubReader, UnnamedPipeWriter as PubWriter},
+    Error, ErrorKind, Sealed,
 };
 use libc::c_int;
 use std::{
     fmt::{self, Debug, Formatter},
-    io::{self, Read, Write},
-    mem::ManuallyDrop,
-    os::unix::io::{AsRawFd, FromRawFd, IntoRawFd},
+    io::{self, IoSlice, IoSliceMut, Read, Write},
+    mem::MaybeUninit,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
+    process::{ChildStderr, ChildStdin, ChildStdout, Stdio},
 };
 
-pub(crate) fn pipe() -> io::Result<(PubWriter, PubReader)> {
-    let (success, fds) = unsafe {
-        let mut fds: [c_int; 2] = [0; 2];
-        let result = libc::pipe(fds.as_mut_ptr());
-        (result == 0, fds)
+/// Linux-specific extensions for the [reading](PubReader) and [writing](PubWriter) ends of unnamed pipes.
+///
+/// The capacity of a pipe is the amount of data that can be written into it without the reader having to catch up,
+/// 64 KiB by default. Raising it can considerably improve the throughput of bulk transfers, in which the writer would
+/// otherwise frequently block waiting for the reader. Since the capacity belongs to the pipe rather than to either of
+/// its ends, it can be queried and changed from both of them.
+///
+/// # Example
+/// ```
+/// # #[cfg(any(target_os = "linux", target_os = "android"))] {
+/// use interprocess::{os::unix::unnamed_pipe::UnnamedPipeExt, unnamed_pipe::pipe};
+///
+/// let (writer, _reader) = pipe()?;
+/// let capacity = writer.set_pipe_size(256 * 1024)?;
+/// assert!(capacity >= 256 * 1024);
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg_attr( // uds_linux_namespace template
+    feature = "doc_cfg",
+    doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+pub trait UnnamedPipeExt: AsRawFd + Sealed {
+    /// Returns the capacity of the pipe, in bytes.
+    fn pipe_size(&self) -> io::Result<usize> {
+        let size = unsafe { libc::fcntl(self.as_raw_fd(), libc::F_GETPIPE_SZ) };
+        ok_or_ret_errno!(size != -1 => size as usize)
+    }
+    /// Sets the capacity of the pipe to at least the given amount of bytes, returning the capacity that was actually
+    /// set.
+    ///
+    /// The kernel rounds the size up to a power of two number of pages. Unprivileged processes cannot raise the
+    /// capacity above the limit in `/proc/sys/fs/pipe-max-size`, which is 1 MiB by default, and reducing the capacity
+    /// below the amount of data currently in the pipe fails with `EBUSY`.
+    fn set_pipe_size(&self, size: usize) -> io::Result<usize> {
+        let size = c_int::try_from(size)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "pipe size does not fit into an int"))?;
+        let size = unsafe { libc::fcntl(self.as_raw_fd(), libc::F_SETPIPE_SZ, size) };
+        ok_or_ret_errno!(size != -1 => size as usize)
+    }
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl UnnamedPipeExt for PubReader {}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl UnnamedPipeExt for PubWriter {}
+impl Sealed for PubReader {}
+impl Sealed for PubWriter {}
+
+/// Linux-specific extensions for the [reading end](PubReader) of unnamed pipes, which move data out of the pipe
+/// without copying it through userspace.
+///
+/// Since the pipe ends themselves implement [`AsFd`], data can be spliced from one pipe into another as well as into
+/// files and sockets.
+///
+/// # Example
+/// ```
+/// # #[cfg(any(target_os = "linux", target_os = "android"))] {
+/// use interprocess::{
+///     os::unix::unnamed_pipe::{UnnamedPipeReaderExt, UnnamedPipeWriterExt},
+///     unnamed_pipe::pipe,
+/// };
+/// use std::io::prelude::*;
+///
+/// let (mut writer, mut reader) = pipe()?;
+/// let (mut copy_writer, mut copy_reader) = pipe()?;
+/// let (mut moved_writer, mut moved_reader) = pipe()?;
+/// writer.write_all(b"Hello!")?;
+///
+/// // Duplicates the data into the second pipe, leaving it in the first one...
+/// assert_eq!(reader.tee(&mut copy_writer, 6)?, 6);
+/// // ...and then moves it into the third one.
+/// assert_eq!(moved_writer.splice_from(&reader, 6)?, 6);
+///
+/// let mut buf = [0; 6];
+/// copy_reader.read_exact(&mut buf)?;
+/// assert_eq!(&buf, b"Hello!");
+/// moved_reader.read_exact(&mut buf)?;
+/// assert_eq!(&buf, b"Hello!");
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg_attr( // uds_linux_namespace template
+    feature = "doc_cfg",
+    doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+pub trait UnnamedPipeReaderExt: Sealed {
+    /// Moves up to `len` bytes out of the pipe into the given file descriptor, returning how many bytes were moved.
+    ///
+    /// Like [`read()`](Read::read), this blocks until there is data in the pipe and returns `0` once the writing end
+    /// has been dropped and the pipe is empty. If the destination is a file, the data is written at the current file
+    /// position, which is then advanced.
+    fn splice_to(&mut self, to: impl AsFd, len: usize) -> io::Result<usize>;
+    /// Copies up to `len` bytes from the pipe into another pipe without consuming them, returning how many bytes were
+    /// copied.
+    ///
+    /// The data stays in this pipe and can still be read, spliced or teed afterwards. Blocks until there is data in
+    /// this pipe and until the other pipe has room for it, and returns `0` once the writing end of this pipe has been
+    /// dropped and the pipe is empty.
+    fn tee(&self, to: &mut PubWriter, len: usize) -> io::Result<usize>;
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl UnnamedPipeReaderExt for PubReader {
+    fn splice_to(&mut self, to: impl AsFd, len: usize) -> io::Result<usize> {
+        splice(self.as_fd(), to.as_fd(), len)
+    }
+    fn tee(&self, to: &mut PubWriter, len: usize) -> io::Result<usize> {
+        let bytes_copied = unsafe { libc::tee(self.as_raw_fd(), to.as_raw_fd(), len, 0) };
+        ok_or_ret_errno!(bytes_copied != -1 => bytes_copied as usize)
+    }
+}
+
+/// Linux-specific extensions for the [writing end](PubWriter) of unnamed pipes, which move data into the pipe without
+/// copying it through userspace.
+///
+/// See [`UnnamedPipeReaderExt`] for an example.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[cfg_attr( // uds_linux_namespace template
+    feature = "doc_cfg",
+    doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+pub trait UnnamedPipeWriterExt: Sealed {
+    /// Moves up to `len` bytes from the given file descriptor into the pipe, returning how many bytes were moved.
+    ///
+    /// Blocks until the source has data and the pipe has room for it, and returns `0` once the source reaches
+    /// end-of-file. If the source is a file, the data is read at the current file position, which is then advanced.
+    fn splice_from(&mut self, from: impl AsFd, len: usize) -> io::Result<usize>;
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+impl UnnamedPipeWriterExt for PubWriter {
+    fn splice_from(&mut self, from: impl AsFd, len: usize) -> io::Result<usize> {
+        splice(from.as_fd(), self.as_fd(), len)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn splice(from: BorrowedFd<'_>, to: BorrowedFd<'_>, len: usize) -> io::Result<usize> {
+    let bytes_moved = unsafe {
+        libc::splice(
+            from.as_raw_fd(),
+            std::ptr::null_mut(),
+            to.as_raw_fd(),
+            std::ptr::null_mut(),
+            len,
+            libc::SPLICE_F_MOVE,
+        )
     };
-    if success {
-        unsafe {
-            // SAFETY: we just created both of those file descriptors, which means that neither of
-            // them can be in use elsewhere.
-            let reader = PubReader {
-                inner: UnnamedPipeReader::from_raw_fd(fds[0]),
-            };
-            let writer = PubWriter {
-                inner: UnnamedPipeWriter::from_raw_fd(fds[1]),
-            };
-            Ok((writer, reader))
+    ok_or_ret_errno!(bytes_moved != -1 => bytes_moved as usize)
+}
+
+impl From<PubReader> for Stdio {
+    fn from(reader: PubReader) -> Self {
+        OwnedFd::from(reader).into()
+    }
+}
+impl From<PubWriter> for Stdio {
+    fn from(writer: PubWriter) -> Self {
+        OwnedFd::from(writer).into()
+    }
+}
+/// Takes over the pipe that feeds the standard input of a child process.
+impl From<ChildStdin> for PubWriter {
+    fn from(stdin: ChildStdin) -> Self {
+        // SAFETY: ownership of the file descriptor is transferred from the standard library
+        unsafe { PubWriter::from_raw_fd(stdin.into_raw_fd()) }
+    }
+}
+/// Takes over the pipe that receives the standard output of a child process.
+impl From<ChildStdout> for PubReader {
+    fn from(stdout: ChildStdout) -> Self {
+        // SAFETY: as above
+        unsafe { PubReader::from_raw_fd(stdout.into_raw_fd()) }
+    }
+}
+/// Takes over the pipe that receives the standard error of a child process.
+impl From<ChildStderr> for PubReader {
+    fn from(stderr: ChildStderr) -> Self {
+        // SAFETY: as above
+        unsafe { PubReader::from_raw_fd(stderr.into_raw_fd()) }
+    }
+}
+
+/// Builder used to create unnamed pipes while supplying additional options.
+///
+/// You can use this instead of the simple [`pipe` function](crate::unnamed_pipe::pipe) to control which ends of the
+/// pipe have the close-on-exec flag set. A file descriptor without the flag is inherited by every program started with
+/// `exec()`, which is how the end of a pipe is normally passed to a child process; the end that stays in the parent
+/// should have the flag set, so that it doesn't leak into the child (or any other program spawned at the same time)
+/// and keep the pipe from reporting end-of-file when the child exits.
+///
+/// # Example
+/// ```
+/// use interprocess::os::unix::unnamed_pipe::UnnamedPipeCreationOptions;
+/// use std::os::unix::io::AsRawFd;
+///
+/// // Only the writing end is to be inherited by the child.
+/// let (writer, reader) = UnnamedPipeCreationOptions::new()
+///     .reader_cloexec(true)
+///     .build()?;
+/// // ...spawn the child, telling it the value of `writer.as_raw_fd()`, then drop `writer`...
+/// # let _ = (writer.as_raw_fd(), reader);
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug)]
+pub struct UnnamedPipeCreationOptions {
+    /// Specifies whether the reading end of the pipe is to have the close-on-exec flag set.
+    ///
+    /// The default value is `false`, which makes the reading end inheritable, as with the [`pipe`
+    /// function](crate::unnamed_pipe::pipe).
+    pub reader_cloexec: bool,
+    /// Specifies whether the writing end of the pipe is to have the close-on-exec flag set.
+    ///
+    /// The default value is `false`, which makes the writing end inheritable, as with the [`pipe`
+    /// function](crate::unnamed_pipe::pipe).
+    pub writer_cloexec: bool,
+}
+impl UnnamedPipeCreationOptions {
+    /// Starts with the default parameters for the pipe. Identical to `Default::default()`.
+    pub const fn new() -> Self {
+        Self {
+            reader_cloexec: false,
+            writer_cloexec: false,
         }
+    }
+    /// Specifies whether both ends of the pipe are to have the close-on-exec flag set.
+    #[must_use = "this is not an in-place operation"]
+    pub fn cloexec(self, cloexec: bool) -> Self {
+        self.reader_cloexec(cloexec).writer_cloexec(cloexec)
+    }
+    /// Specifies whether the reading end of the pipe is to have the close-on-exec flag set.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.reader_cloexec " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn reader_cloexec(mut self, reader_cloexec: bool) -> Self {
+        self.reader_cloexec = reader_cloexec;
+        self
+    }
+    /// Specifies whether the writing end of the pipe is to have the close-on-exec flag set.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.writer_cloexec " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn writer_cloexec(mut self, writer_cloexec: bool) -> Self {
+        self.writer_cloexec = writer_cloexec;
+        self
+    }
+
+    /// Creates the pipe and returns its writing and reading ends, or the error if one occurred.
+    ///
+    /// Where the platform allows it, both ends are created with the close-on-exec flag already set, and the flag is
+    /// then cleared on the ends which are to be inherited. This way, a program spawned by another thread in the
+    /// meantime can never inherit an end which was meant to have the flag set.
+    pub fn build(self) -> io::Result<(PubWriter, PubReader)> {
+        let [reader, writer] = create_pipe()?;
+        // SAFETY: we just created both of those file descriptors, which means that neither of them can be in use
+        // elsewhere.
+        let (reader, writer) = unsafe {
+            (
+                UnnamedPipeReader::from_raw_fd(reader),
+                UnnamedPipeWriter::from_raw_fd(writer),
+            )
+        };
+        set_cloexec(reader.as_raw_fd(), self.reader_cloexec)?;
+        set_cloexec(writer.as_raw_fd(), self.writer_cloexec)?;
+        Ok((PubWriter { inner: writer }, PubReader { inner: reader }))
+    }
+}
+impl Default for UnnamedPipeCreationOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a pipe with the close-on-exec flag set on both ends, atomically if the platform supports it.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn create_pipe() -> io::Result<[c_int; 2]> {
+    let mut fds: [c_int; 2] = [0; 2];
+    let success = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) == 0 };
+    ok_or_ret_errno!(success => fds)
+}
+/// Creates a pipe with the close-on-exec flag set on both ends, atomically if the platform supports it.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn create_pipe() -> io::Result<[c_int; 2]> {
+    let mut fds: [c_int; 2] = [0; 2];
+    let success = unsafe { libc::pipe(fds.as_mut_ptr()) == 0 };
+    if !success {
+        return Err(io::Error::last_os_error());
+    }
+    // Closes both file descriptors if setting the flag fails.
+    let fds = fds.map(|fd| unsafe {
+        // SAFETY: pipe() just created the descriptors
+        FdOps::from_raw_fd(fd)
+    });
+    for fd in &fds {
+        set_cloexec(fd.as_raw_fd(), true)?;
+    }
+    Ok(fds.map(IntoRawFd::into_raw_fd))
+}
+fn set_cloexec(fd: c_int, cloexec: bool) -> io::Result<()> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let new_flags = if cloexec {
+        flags | libc::FD_CLOEXEC
     } else {
-        Err(io::Error::last_os_error())
+        flags & !libc::FD_CLOEXEC
+    };
+    if new_flags == flags {
+        return Ok(());
     }
+    let success = unsafe { libc::fcntl(fd, libc::F_SETFD, new_flags) != -1 };
+    ok_or_ret_errno!(success => ())
+}
+
+pub(crate) fn pipe() -> io::Result<(PubWriter, PubReader)> {
+    UnnamedPipeCreationOptions::default().build()
+}
+pub(crate) fn pipe_with_options(options: &UnnamedPipeOptions) -> io::Result<(PubWriter, PubReader)> {
+    let (writer, reader) = UnnamedPipeCreationOptions::new()
+        .reader_cloexec(!options.reader_inheritable)
+        .writer_cloexec(!options.writer_inheritable)
+        .build()?;
+    set_nonblocking(reader.as_raw_fd(), options.reader_nonblocking)?;
+    set_nonblocking(writer.as_raw_fd(), options.writer_nonblocking)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if let Some(buffer_size) = options.buffer_size {
+        writer.set_pipe_size(buffer_size)?;
+    }
+    Ok((writer, reader))
 }
 
 pub(crate) struct UnnamedPipeReader(FdOps);
@@ -42,8 +379,34 @@ impl Read for UnnamedPipeReader {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.0.read(buf)
     }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.0.read_vectored(bufs)
+    }
+}
+impl UnnamedPipeReader {
+    pub fn read_to_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read_to_uninit(buf)
+    }
 }
 impl Sealed for UnnamedPipeReader {}
+impl AsFd for UnnamedPipeReader {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+impl From<UnnamedPipeReader> for OwnedFd {
+    fn from(x: UnnamedPipeReader) -> Self {
+        x.0.into()
+    }
+}
+/// Fails if the file descriptor is not a pipe or a FIFO, or if it's only open for writing.
+impl TryFrom<OwnedFd> for UnnamedPipeReader {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_pipe(fd.as_fd(), libc::O_WRONLY))?;
+        Ok(Self(fd.into()))
+    }
+}
 impl AsRawFd for UnnamedPipeReader {
     fn as_raw_fd(&self) -> c_int {
         self.0.as_raw_fd()
@@ -51,8 +414,7 @@ impl AsRawFd for UnnamedPipeReader {
 }
 impl IntoRawFd for UnnamedPipeReader {
     fn into_raw_fd(self) -> c_int {
-        let self_ = ManuallyDrop::new(self);
-        self_.as_raw_fd()
+        self.0.into_raw_fd()
     }
 }
 impl FromRawFd for UnnamedPipeReader {
@@ -76,11 +438,32 @@ impl Write for UnnamedPipeWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.0.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> {
         self.0.flush()
     }
 }
 impl Sealed for UnnamedPipeWriter {}
+impl AsFd for UnnamedPipeWriter {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+impl From<UnnamedPipeWriter> for OwnedFd {
+    fn from(x: UnnamedPipeWriter) -> Self {
+        x.0.into()
+    }
+}
+/// Fails if the file descriptor is not a pipe or a FIFO, or if it's only open for reading.
+impl TryFrom<OwnedFd> for UnnamedPipeWriter {
+    type Error = FromHandleError;
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        let fd = FromHandleError::check(fd, |fd| check_pipe(fd.as_fd(), libc::O_RDONLY))?;
+        Ok(Self(fd.into()))
+    }
+}
 impl AsRawFd for UnnamedPipeWriter {
     fn as_raw_fd(&self) -> c_int {
         self.0.as_raw_fd()
@@ -93,7 +476,10 @@ impl IntoRawFd for UnnamedPipeWriter {
 }
 impl FromRawFd for UnnamedPipeWriter {
     unsafe fn from_raw_fd(fd: c_int) -> Self {
-        Self(FdOps::new(fd))
+        Self(unsafe {
+            // SAFETY: guaranteed by safety contract
+            FdOps::from_raw_fd(fd)
+        })
     }
 }
 impl Debug for UnnamedPipeWriter {
@@ -103,3 +489,16 @@ impl Debug for UnnamedPipeWriter {
             .finish()
     }
 }
+
+/// Checks that the file descriptor is a pipe or a FIFO which is not opened with the given access mode.
+fn check_pipe(fd: BorrowedFd<'_>, wrong_access_mode: c_int) -> io::Result<()> {
+    check_file_type(fd, libc::S_IFIFO, "the file is not a pipe")?;
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    if flags == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    if flags & libc::O_ACCMODE == wrong_access_mode {
+        return Err(Error::new(ErrorKind::InvalidHandle, "the pipe end is open for the wrong direction").into());
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use {
+    crate::{local_socket::LocalSocketConnectOptions, Sealed},
+    std::ffi::OsString,
+};
+
+/// Windows-specific options for [`LocalSocketConnectOptions`].
+pub trait LocalSocketConnectOptionsExt: Sealed {
+    /// Sets the computer on which the server is to be found, making the connection go to `\\<hostname>\pipe\<name>`
+    /// instead of `\\.\pipe\<name>`. By default, the server is looked for on the local computer.
+    ///
+    /// The server has to allow this with
+    /// [`LocalSocketListenerOptionsExt::accept_remote()`](super::LocalSocketListenerOptionsExt::accept_remote), and
+    /// the current user has to be able to authenticate to the remote computer over SMB. The
+    /// [peer process ID](crate::local_socket::LocalSocketStream::peer_pid) of such a connection refers to a process on
+    /// the remote computer, and so do the credentials derived from it.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn hostname(self, hostname: impl Into<OsString>) -> Self;
+}
+impl LocalSocketConnectOptionsExt for LocalSocketConnectOptions {
+    fn hostname(mut self, hostname: impl Into<OsString>) -> Self {
+        self.hostname = Some(hostname.into());
+        self
+    }
+}
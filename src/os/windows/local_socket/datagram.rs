@@ -0,0 +1,167 @@
+use {
+    crate::{
+        local_socket::{LocalSocketName, ToLocalSocketName},
+        os::windows::{weaken_buf_init, winprelude::*, FileHandle},
+    },
+    std::{
+        ffi::{c_void, OsStr},
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle},
+        ptr,
+    },
+    winapi::{
+        shared::winerror::ERROR_SEM_TIMEOUT,
+        um::{
+            fileapi::{CreateFileW, OPEN_EXISTING},
+            winbase::{CreateMailslotW, SetMailslotInfo},
+            winnt::{FILE_SHARE_READ, GENERIC_WRITE},
+        },
+    },
+};
+
+// Not exposed by winapi.
+const MAILSLOT_WAIT_FOREVER: DWORD = DWORD::MAX;
+
+pub struct LocalSocketDatagram {
+    slot: Option<FileHandle>,
+    destination: Option<FileHandle>,
+}
+impl LocalSocketDatagram {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let path = mailslot_path(name.inner());
+        let handle = unsafe { CreateMailslotW(path.as_ptr(), 0, MAILSLOT_WAIT_FOREVER, ptr::null_mut()) };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        let slot = unsafe {
+            // SAFETY: we just created this handle
+            FileHandle::from_raw_handle(handle)
+        };
+        Ok(Self {
+            slot: Some(slot),
+            destination: None,
+        })
+    }
+    pub fn unbound() -> io::Result<Self> {
+        Ok(Self {
+            slot: None,
+            destination: None,
+        })
+    }
+    pub fn connect<'a>(&mut self, name: impl ToLocalSocketName<'a>) -> io::Result<()> {
+        self.destination = Some(open_mailslot(name)?);
+        Ok(())
+    }
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match &self.destination {
+            Some(dst) => dst.write(buf),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "no destination has been set via connect()",
+            )),
+        }
+    }
+    pub fn send_to<'a>(&self, buf: &[u8], name: impl ToLocalSocketName<'a>) -> io::Result<usize> {
+        open_mailslot(name)?.write(buf)
+    }
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.slot()?.read(weaken_buf_init(buf)) {
+            Err(e) if e.raw_os_error() == Some(ERROR_SEM_TIMEOUT as _) => Err(io::ErrorKind::WouldBlock.into()),
+            els => els,
+        }
+    }
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, Option<LocalSocketName<'static>>)> {
+        // Mailslots are anonymous on the sending side
+        self.recv(buf).map(|size| (size, None))
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        let slot = match &self.slot {
+            Some(slot) => slot,
+            // Sending to a mailslot never blocks
+            None => return Ok(()),
+        };
+        let timeout = if nonblocking { 0 } else { MAILSLOT_WAIT_FOREVER };
+        let success = unsafe { SetMailslotInfo(slot.as_raw_handle(), timeout) != 0 };
+        ok_or_ret_errno!(success => ())
+    }
+    fn slot(&self) -> io::Result<&FileHandle> {
+        self.slot.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotConnected,
+                "an unbound datagram socket cannot receive datagrams",
+            )
+        })
+    }
+}
+
+fn mailslot_path(name: &OsStr) -> Vec<u16> {
+    let mut path = OsStr::new(r"\\.\mailslot\").encode_wide().collect::<Vec<u16>>();
+    path.extend(name.encode_wide());
+    path.push(0);
+    path
+}
+fn open_mailslot<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<FileHandle> {
+    let name = name.to_local_socket_name()?;
+    let path = mailslot_path(name.inner());
+    let handle = unsafe {
+        CreateFileW(
+            path.as_ptr(),
+            GENERIC_WRITE,
+            FILE_SHARE_READ,
+            ptr::null_mut(),
+            OPEN_EXISTING,
+            0,
+            ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe {
+        // SAFETY: we just created this handle
+        FileHandle::from_raw_handle(handle)
+    })
+}
+
+impl Debug for LocalSocketDatagram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketDatagram")
+            .field("slot", &self.slot.as_ref().map(AsRawHandle::as_raw_handle))
+            .field(
+                "destination",
+                &self.destination.as_ref().map(AsRawHandle::as_raw_handle),
+            )
+            .finish()
+    }
+}
+/// Returns the handle of the mailslot the socket is bound to, or `INVALID_HANDLE_VALUE` if it is unbound.
+impl AsRawHandle for LocalSocketDatagram {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.slot
+            .as_ref()
+            .map_or(INVALID_HANDLE_VALUE, AsRawHandle::as_raw_handle)
+    }
+}
+impl IntoRawHandle for LocalSocketDatagram {
+    #[inline]
+    fn into_raw_handle(mut self) -> *mut c_void {
+        self.slot
+            .take()
+            .map_or(INVALID_HANDLE_VALUE, IntoRawHandle::into_raw_handle)
+    }
+}
+/// Takes ownership of a mailslot handle as returned by `CreateMailslotW`, creating a bound socket.
+impl FromRawHandle for LocalSocketDatagram {
+    unsafe fn from_raw_handle(handle: *mut c_void) -> Self {
+        Self {
+            slot: Some(unsafe {
+                // SAFETY: guaranteed via safety contract
+                FileHandle::from_raw_handle(handle)
+            }),
+            destination: None,
+        }
+    }
+}
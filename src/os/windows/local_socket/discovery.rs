@@ -0,0 +1,17 @@
+use {
+    crate::{local_socket::LocalSocketName, os::windows::named_pipe::list_pipes},
+    std::{borrow::Cow, ffi::OsStr, io, os::windows::ffi::OsStrExt},
+};
+
+/// Lists the named pipes by enumerating the `\\.\pipe\` directory.
+pub fn enumerate_servers(prefix: &OsStr) -> io::Result<Vec<LocalSocketName<'static>>> {
+    let prefix = prefix.encode_wide().collect::<Vec<_>>();
+    let mut servers = Vec::new();
+    for pipe in list_pipes()? {
+        let pipe = pipe?;
+        if pipe.name().encode_wide().take(prefix.len()).eq(prefix.iter().copied()) {
+            servers.push(LocalSocketName::from_raw_parts(Cow::Owned(pipe.into_name()), true));
+        }
+    }
+    Ok(servers)
+}
@@ -0,0 +1,122 @@
+use {
+    crate::{
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream},
+        reactor::Reactor,
+    },
+    std::{
+        fmt::{self, Debug, Formatter},
+        future::Future,
+        io::{self, Read, Write},
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+    },
+};
+
+pub(super) type PipeImpl = Arc<DuplexPipeStream<pipe_mode::Bytes>>;
+
+/// Reads from a pipe on the thread pool, one operation at a time.
+///
+/// If the buffer given when the read completes is smaller than the one it was started with, the rest of the data is
+/// kept for the next call.
+pub(super) struct ReadIo<R: Reactor> {
+    task: Option<R::Task<io::Result<Vec<u8>>>>,
+    leftover: Vec<u8>,
+}
+impl<R: Reactor> ReadIo<R> {
+    pub(super) fn poll_read(
+        &mut self,
+        pipe: &PipeImpl,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if self.leftover.is_empty() {
+            let task = self.task.get_or_insert_with(|| {
+                let pipe = Arc::clone(pipe);
+                let len = buf.len();
+                R::spawn_blocking(move || {
+                    let mut data = vec![0; len];
+                    let size = (&*pipe).read(&mut data)?;
+                    data.truncate(size);
+                    Ok(data)
+                })
+            });
+            let rslt = match Pin::new(task).poll(cx) {
+                Poll::Ready(r) => r,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.task = None;
+            self.leftover = rslt?;
+        }
+        let size = self.leftover.len().min(buf.len());
+        buf[..size].copy_from_slice(&self.leftover[..size]);
+        self.leftover.drain(..size);
+        Poll::Ready(Ok(size))
+    }
+}
+
+/// Writes to a pipe on the thread pool, one operation at a time.
+pub(super) struct WriteIo<R: Reactor> {
+    task: Option<R::Task<io::Result<usize>>>,
+}
+impl<R: Reactor> WriteIo<R> {
+    pub(super) fn poll_write(&mut self, pipe: &PipeImpl, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let task = self.task.get_or_insert_with(|| {
+            let pipe = Arc::clone(pipe);
+            let data = buf.to_vec();
+            R::spawn_blocking(move || (&*pipe).write(&data))
+        });
+        let rslt = match Pin::new(task).poll(cx) {
+            Poll::Ready(r) => r,
+            Poll::Pending => return Poll::Pending,
+        };
+        self.task = None;
+        Poll::Ready(rslt)
+    }
+    /// Waits for the write in progress, if there is one, to finish.
+    pub(super) fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if let Some(task) = &mut self.task {
+            let rslt = match Pin::new(task).poll(cx) {
+                Poll::Ready(r) => r,
+                Poll::Pending => return Poll::Pending,
+            };
+            self.task = None;
+            rslt?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: Reactor> Default for ReadIo<R> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            task: None,
+            leftover: Vec::new(),
+        }
+    }
+}
+impl<R: Reactor> Debug for ReadIo<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadIo")
+            .field("pending", &self.task.is_some())
+            .field("leftover", &self.leftover.len())
+            .finish()
+    }
+}
+impl<R: Reactor> Default for WriteIo<R> {
+    #[inline]
+    fn default() -> Self {
+        Self { task: None }
+    }
+}
+impl<R: Reactor> Debug for WriteIo<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteIo")
+            .field("pending", &self.task.is_some())
+            .finish()
+    }
+}
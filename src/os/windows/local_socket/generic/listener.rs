@@ -0,0 +1,44 @@
+use super::LocalSocketStream;
+use crate::{
+    local_socket::ToLocalSocketName,
+    os::windows::named_pipe::{pipe_mode, PipeListener as GenericPipeListener, PipeListenerOptions, PipeMode},
+    reactor::Reactor,
+};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+type PipeListener = GenericPipeListener<pipe_mode::Bytes, pipe_mode::Bytes>;
+
+pub struct LocalSocketListener<R: Reactor> {
+    inner: Arc<PipeListener>,
+    _reactor: PhantomData<R>,
+}
+impl<R: Reactor> LocalSocketListener<R> {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = PipeListenerOptions::new()
+            .name(name.into_inner())
+            .mode(PipeMode::Bytes)
+            .create()?;
+        Ok(Self {
+            inner: Arc::new(inner),
+            _reactor: PhantomData,
+        })
+    }
+    pub async fn accept(&self) -> io::Result<LocalSocketStream<R>> {
+        let listener = Arc::clone(&self.inner);
+        let inner = R::spawn_blocking(move || listener.accept()).await?;
+        Ok(LocalSocketStream::from_sync(inner))
+    }
+}
+impl<R: Reactor> Debug for LocalSocketListener<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketListener")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
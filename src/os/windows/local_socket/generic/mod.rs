@@ -0,0 +1,13 @@
+//! Adapter module, implements generic asynchronous local sockets under Windows.
+//!
+//! The named pipes used by the synchronous local sockets are not opened for overlapped I/O, so the blocking operations
+//! are offloaded to the thread pool of the reactor instead.
+
+mod blocking_io;
+use blocking_io::*;
+
+mod listener;
+pub use listener::*;
+
+mod stream;
+pub use stream::*;
@@ -0,0 +1,111 @@
+mod read_half;
+pub use read_half::*;
+
+mod write_half;
+pub use write_half::*;
+
+use {
+    super::{super::peer_credentials_from_pid, PipeImpl, ReadIo, WriteIo},
+    crate::{
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream},
+        reactor::Reactor,
+    },
+    futures_io::{AsyncRead, AsyncWrite},
+    std::{
+        ffi::c_void,
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::AsRawHandle,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+    },
+};
+
+pub struct LocalSocketStream<R: Reactor> {
+    inner: PipeImpl,
+    read: ReadIo<R>,
+    write: WriteIo<R>,
+}
+impl<R: Reactor> LocalSocketStream<R> {
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let pipename = name.to_local_socket_name()?.inner().to_os_string();
+        let inner = R::spawn_blocking(move || DuplexPipeStream::connect(pipename)).await?;
+        Ok(Self::from_sync(inner))
+    }
+    pub(super) fn from_sync(inner: DuplexPipeStream<pipe_mode::Bytes>) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            read: ReadIo::default(),
+            write: WriteIo::default(),
+        }
+    }
+    pub fn into_split(self) -> (OwnedReadHalf<R>, OwnedWriteHalf<R>) {
+        let rh = OwnedReadHalf {
+            inner: Arc::clone(&self.inner),
+            io: self.read,
+        };
+        let wh = OwnedWriteHalf {
+            inner: self.inner,
+            io: self.write,
+        };
+        (rh, wh)
+    }
+    pub fn reunite(rh: OwnedReadHalf<R>, wh: OwnedWriteHalf<R>) -> Result<Self, (OwnedReadHalf<R>, OwnedWriteHalf<R>)> {
+        if !Arc::ptr_eq(&rh.inner, &wh.inner) {
+            return Err((rh, wh));
+        }
+        let OwnedWriteHalf { io: write, .. } = wh;
+        Ok(Self {
+            inner: rh.inner,
+            read: rh.io,
+            write,
+        })
+    }
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        match self.inner.is_server() {
+            true => self.inner.client_process_id(),
+            false => self.inner.server_process_id(),
+        }
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+}
+impl<R: Reactor> AsyncRead for LocalSocketStream<R> {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let slf = self.get_mut();
+        slf.read.poll_read(&slf.inner, cx, buf)
+    }
+}
+impl<R: Reactor> AsyncWrite for LocalSocketStream<R> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let slf = self.get_mut();
+        slf.write.poll_write(&slf.inner, cx, buf)
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().write.poll_flush(cx)
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+impl<R: Reactor> Debug for LocalSocketStream<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketStream")
+            .field("handle", &self.as_raw_handle())
+            .finish()
+    }
+}
+impl<R: Reactor> AsRawHandle for LocalSocketStream<R> {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.inner.as_raw_handle()
+    }
+}
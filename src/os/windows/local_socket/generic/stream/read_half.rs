@@ -0,0 +1,50 @@
+use {
+    super::super::{super::peer_credentials_from_pid, PipeImpl, ReadIo},
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncRead,
+    std::{
+        ffi::c_void,
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::AsRawHandle,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+pub struct OwnedReadHalf<R: Reactor> {
+    pub(super) inner: PipeImpl,
+    pub(super) io: ReadIo<R>,
+}
+impl<R: Reactor> OwnedReadHalf<R> {
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        match self.inner.is_server() {
+            true => self.inner.client_process_id(),
+            false => self.inner.server_process_id(),
+        }
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+}
+impl<R: Reactor> AsyncRead for OwnedReadHalf<R> {
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let slf = self.get_mut();
+        slf.io.poll_read(&slf.inner, cx, buf)
+    }
+}
+impl<R: Reactor> Debug for OwnedReadHalf<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("local_socket::OwnedReadHalf")
+            .field("handle", &self.as_raw_handle())
+            .finish()
+    }
+}
+impl<R: Reactor> AsRawHandle for OwnedReadHalf<R> {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.inner.as_raw_handle()
+    }
+}
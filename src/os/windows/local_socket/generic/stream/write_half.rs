@@ -0,0 +1,58 @@
+use {
+    super::super::{super::peer_credentials_from_pid, PipeImpl, WriteIo},
+    crate::{local_socket::LocalSocketPeerCredentials, reactor::Reactor},
+    futures_io::AsyncWrite,
+    std::{
+        ffi::c_void,
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::AsRawHandle,
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+pub struct OwnedWriteHalf<R: Reactor> {
+    pub(super) inner: PipeImpl,
+    pub(super) io: WriteIo<R>,
+}
+impl<R: Reactor> OwnedWriteHalf<R> {
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        match self.inner.is_server() {
+            true => self.inner.client_process_id(),
+            false => self.inner.server_process_id(),
+        }
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+}
+impl<R: Reactor> AsyncWrite for OwnedWriteHalf<R> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let slf = self.get_mut();
+        slf.io.poll_write(&slf.inner, cx, buf)
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().io.poll_flush(cx)
+    }
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+impl<R: Reactor> Debug for OwnedWriteHalf<R> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("local_socket::OwnedWriteHalf")
+            .field("handle", &self.as_raw_handle())
+            .finish()
+    }
+}
+impl<R: Reactor> AsRawHandle for OwnedWriteHalf<R> {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.inner.as_raw_handle()
+    }
+}
@@ -1,15 +1,45 @@
-use super::LocalSocketStream;
+use super::{user_sid_of_process, LocalSocketStream};
 use crate::{
-    local_socket::ToLocalSocketName,
-    os::windows::named_pipe::{pipe_mode, PipeListener as GenericPipeListener, PipeListenerOptions, PipeMode},
+    listener_close::ListenerClosedError,
+    local_socket::{ListenerHooks, LocalSocketListenerOptions, ToLocalSocketName},
+    os::windows::{
+        named_pipe::{pipe_mode, PipeListener as GenericPipeListener, PipeListenerOptions, PipeMode},
+        FileHandle,
+    },
 };
-use std::io;
+use std::{
+    borrow::Cow,
+    io,
+    os::windows::io::{AsRawHandle, FromRawHandle},
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering::*},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use winapi::{
+    shared::minwindef::DWORD,
+    um::{
+        ioapiset::CancelSynchronousIo,
+        processthreadsapi::{GetCurrentThreadId, OpenThread},
+        winnt::THREAD_TERMINATE,
+    },
+};
+
+/// How often `accept_timeout()` checks whether the listener has been closed.
+const CLOSE_CHECK_INTERVAL: Duration = Duration::from_millis(10);
 
 type PipeListener = GenericPipeListener<pipe_mode::Bytes, pipe_mode::Bytes>;
 
 #[derive(Debug)]
 pub struct LocalSocketListener {
     inner: PipeListener,
+    /// The SID of the user this process runs as, if connections from other users are to be rejected.
+    owner_sid: Option<String>,
+    close_signal: CloseSignal,
+    hooks: Option<ListenerHooks>,
 }
 impl LocalSocketListener {
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
@@ -18,13 +48,164 @@ impl LocalSocketListener {
             .name(name.into_inner())
             .mode(PipeMode::Bytes)
             .create()?;
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            owner_sid: None,
+            close_signal: CloseSignal::default(),
+            hooks: None,
+        })
+    }
+    pub fn bind_with_options<'a>(
+        name: impl ToLocalSocketName<'a>,
+        options: &LocalSocketListenerOptions,
+    ) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let hooks = ListenerHooks::new(options.hooks.as_ref(), &name);
+        let inner = PipeListenerOptions::new()
+            .name(name.into_inner())
+            .mode(PipeMode::Bytes)
+            .nonblocking(options.nonblocking)
+            .instance_limit(options.instance_limit)
+            .accept_remote(options.accept_remote)
+            .security_descriptor(options.security_descriptor.as_deref().map(Cow::Borrowed))
+            .create()?;
+        let owner_sid = match options.same_user_only {
+            true => Some(user_sid_of_process(process::id())?),
+            false => None,
+        };
+        Ok(Self {
+            inner,
+            owner_sid,
+            close_signal: CloseSignal::default(),
+            hooks,
+        })
     }
     pub fn accept(&self) -> io::Result<LocalSocketStream> {
-        let inner = self.inner.accept()?;
-        Ok(LocalSocketStream { inner })
+        loop {
+            let conn = LocalSocketStream {
+                inner: self.close_signal.interruptible(|| self.inner.accept())?,
+            };
+            if self.admits(&conn) {
+                return Ok(conn);
+            }
+            if let Some(hooks) = &self.hooks {
+                hooks.rejected(conn.peer_pid());
+            }
+        }
+    }
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<LocalSocketStream> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.close_signal.check()?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            // Blocking accepts get cancelled upon closure, but the polling done by this one has to be interrupted
+            // every now and then to check for it.
+            let inner = match self.inner.accept_timeout(remaining.min(CLOSE_CHECK_INTERVAL)) {
+                Err(e) if e.kind() == io::ErrorKind::TimedOut && remaining > CLOSE_CHECK_INTERVAL => continue,
+                els => els?,
+            };
+            let conn = LocalSocketStream { inner };
+            if self.admits(&conn) {
+                return Ok(conn);
+            }
+            if let Some(hooks) = &self.hooks {
+                hooks.rejected(conn.peer_pid());
+            }
+        }
+    }
+    pub fn wait_accept(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            self.close_signal.check()?;
+            // Sliced just like in `accept_timeout()` so that closure is noticed.
+            let slice = match deadline {
+                Some(deadline) => deadline
+                    .saturating_duration_since(Instant::now())
+                    .min(CLOSE_CHECK_INTERVAL),
+                None => CLOSE_CHECK_INTERVAL,
+            };
+            if self.inner.wait_for_client(slice)? {
+                return Ok(true);
+            }
+            if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                return Ok(false);
+            }
+        }
+    }
+    pub fn close(&self) {
+        self.close_signal.close()
+    }
+    pub fn is_closed(&self) -> bool {
+        self.close_signal.closed.load(Acquire)
+    }
+    /// Applies the same-user-only policy to a freshly accepted connection.
+    fn admits(&self, conn: &LocalSocketStream) -> bool {
+        let owner_sid = match &self.owner_sid {
+            Some(sid) => sid,
+            None => return true,
+        };
+        matches!(conn.peer_pid().and_then(user_sid_of_process), Ok(sid) if sid == *owner_sid)
+    }
+    pub fn hooks(&self) -> Option<&ListenerHooks> {
+        self.hooks.as_ref()
     }
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         self.inner.set_nonblocking(nonblocking)
     }
 }
+
+/// Lets `close()` wake up threads blocked in `accept()` by cancelling their synchronous I/O.
+#[derive(Debug, Default)]
+struct CloseSignal {
+    closed: AtomicBool,
+    /// Threads that are currently inside `accept()`, along with handles to them which allow cancelling their I/O.
+    waiters: Mutex<Vec<(DWORD, FileHandle)>>,
+}
+impl CloseSignal {
+    fn close(&self) {
+        self.closed.store(true, Release);
+        // A waiter might have checked the flag just before it was raised, and not have started waiting for a client
+        // yet, in which case there is nothing to cancel – keep trying until every waiter is gone.
+        loop {
+            let waiters = self.waiters.lock().expect("unexpected lock poison");
+            if waiters.is_empty() {
+                break;
+            }
+            for (_, handle) in waiters.iter() {
+                unsafe { CancelSynchronousIo(handle.as_raw_handle()) };
+            }
+            drop(waiters);
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+    fn check(&self) -> io::Result<()> {
+        match self.closed.load(Acquire) {
+            true => Err(ListenerClosedError.into()),
+            false => Ok(()),
+        }
+    }
+    /// Runs a blocking operation in such a way that `close()` can interrupt it.
+    fn interruptible<T>(&self, f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+        let id = unsafe { GetCurrentThreadId() };
+        let handle = unsafe { OpenThread(THREAD_TERMINATE, 0, id) };
+        ok_or_ret_errno!(!handle.is_null() => ())?;
+        self.waiters
+            .lock()
+            .expect("unexpected lock poison")
+            .push((id, unsafe { FileHandle::from_raw_handle(handle) }));
+
+        // Registering before checking the flag ensures that a `close()` in between the two can't slip by unnoticed.
+        let result = self.check().and_then(|()| f());
+
+        let mut waiters = self.waiters.lock().expect("unexpected lock poison");
+        if let Some(idx) = waiters.iter().position(|(waiter, _)| *waiter == id) {
+            waiters.swap_remove(idx);
+        }
+        drop(waiters);
+        // The cancelled operation fails with ERROR_OPERATION_ABORTED, which is replaced with the proper error.
+        match result {
+            Err(..) if self.closed.load(Acquire) => Err(ListenerClosedError.into()),
+            els => els,
+        }
+    }
+}
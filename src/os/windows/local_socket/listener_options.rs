@@ -0,0 +1,39 @@
+use {
+    crate::local_socket::LocalSocketListenerOptions,
+    crate::Sealed,
+    std::{ffi::OsString, num::NonZeroU8},
+};
+
+/// Windows-specific options for [`LocalSocketListenerOptions`].
+pub trait LocalSocketListenerOptionsExt: Sealed {
+    /// Sets the security descriptor of the named pipe, in the security descriptor definition language (SDDL) string
+    /// format. By default, a security descriptor is used which gives everyone read access and only the LocalSystem
+    /// account, administrators and the owner full access.
+    ///
+    /// See the [`security_descriptor` field](crate::os::windows::named_pipe::PipeListenerOptions::security_descriptor)
+    /// of the named pipe listener options for more.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn security_descriptor(self, sddl: impl Into<OsString>) -> Self;
+    /// Sets the maximum number of instances of the pipe, i.e. how many clients can be connected at once. By default, no
+    /// limit is applied. The value 255 is not allowed because of Windows limitations.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn instance_limit(self, instance_limit: impl Into<Option<NonZeroU8>>) -> Self;
+    /// Sets whether clients from remote machines may connect to the pipe over the network. By default, they are
+    /// rejected.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn accept_remote(self, accept_remote: bool) -> Self;
+}
+impl LocalSocketListenerOptionsExt for LocalSocketListenerOptions {
+    fn security_descriptor(mut self, sddl: impl Into<OsString>) -> Self {
+        self.security_descriptor = Some(sddl.into());
+        self
+    }
+    fn instance_limit(mut self, instance_limit: impl Into<Option<NonZeroU8>>) -> Self {
+        self.instance_limit = instance_limit.into();
+        self
+    }
+    fn accept_remote(mut self, accept_remote: bool) -> Self {
+        self.accept_remote = accept_remote;
+        self
+    }
+}
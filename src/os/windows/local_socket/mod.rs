@@ -1,20 +1,160 @@
 //! Adapter module, implements local sockets under Windows.
 
-use crate::local_socket::{LocalSocketName, NameTypeSupport};
+use crate::{
+    local_socket::{LocalSocketName, LocalSocketPeerCredentials, LocalSocketPeerIdentity, NameTypeSupport},
+    os::windows::FileHandle,
+};
 use std::{
     borrow::Cow,
+    env,
     ffi::{OsStr, OsString},
+    io,
+    mem::size_of,
+    os::windows::io::{AsRawHandle, FromRawHandle},
+    path::PathBuf,
+    process, ptr, slice,
+};
+use winapi::{
+    shared::{minwindef::DWORD, sddl::ConvertSidToStringSidW},
+    um::{
+        processthreadsapi::{OpenProcess, OpenProcessToken, ProcessIdToSessionId},
+        securitybaseapi::{GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation},
+        winbase::LocalFree,
+        winnt::{
+            TokenElevation, TokenIntegrityLevel, TokenUser, PROCESS_QUERY_LIMITED_INFORMATION, TOKEN_ELEVATION,
+            TOKEN_INFORMATION_CLASS, TOKEN_MANDATORY_LABEL, TOKEN_QUERY, TOKEN_USER,
+        },
+    },
 };
 
 #[cfg(feature = "tokio")]
 pub mod tokio;
 
+#[cfg(feature = "async")]
+pub mod generic;
+
+mod connect_options;
+pub use connect_options::*;
+
+mod datagram;
+pub use datagram::*;
+
+mod discovery;
+pub use discovery::*;
+
 mod listener;
 pub use listener::*;
 
+mod listener_options;
+pub use listener_options::*;
+
 mod stream;
 pub use stream::*;
 
+mod msg_listener;
+pub use msg_listener::*;
+
+mod msg_stream;
+pub use msg_stream::*;
+
+/// Looks up the session and the user SID of the process with the given ID to assemble its credentials.
+fn peer_credentials_from_pid(pid: io::Result<u32>) -> io::Result<LocalSocketPeerCredentials> {
+    let pid = pid?;
+    let mut session_id: DWORD = 0;
+    let success = unsafe { ProcessIdToSessionId(pid, &mut session_id as *mut _) != 0 };
+    ok_or_ret_errno!(success => ())?;
+
+    let token = process_token(pid)?;
+    let sid = token_user_sid(&token)?;
+    Ok(LocalSocketPeerCredentials::Windows { pid, session_id, sid })
+}
+
+/// Looks up the user SID, elevation and integrity level of the process with the given ID.
+fn peer_identity_from_pid(pid: io::Result<u32>) -> io::Result<LocalSocketPeerIdentity> {
+    let token = process_token(pid?)?;
+    let sid = token_user_sid(&token)?;
+
+    let buf = token_information(&token, TokenElevation)?;
+    let elevated = unsafe { (*buf.as_ptr().cast::<TOKEN_ELEVATION>()).TokenIsElevated != 0 };
+
+    let buf = token_information(&token, TokenIntegrityLevel)?;
+    let integrity_level = unsafe {
+        let label_sid = (*buf.as_ptr().cast::<TOKEN_MANDATORY_LABEL>()).Label.Sid;
+        // The RID of a mandatory label is its last subauthority.
+        let count = *GetSidSubAuthorityCount(label_sid);
+        *GetSidSubAuthority(label_sid, DWORD::from(count.saturating_sub(1)))
+    };
+
+    Ok(LocalSocketPeerIdentity::Windows {
+        sid,
+        elevated,
+        integrity_level,
+    })
+}
+
+/// Looks up the user SID of the process with the given ID, in its string form.
+pub(crate) fn user_sid_of_process(pid: u32) -> io::Result<String> {
+    token_user_sid(&process_token(pid)?)
+}
+
+pub fn current_process_identity() -> io::Result<LocalSocketPeerIdentity> {
+    peer_identity_from_pid(Ok(process::id()))
+}
+
+fn process_token(pid: u32) -> io::Result<FileHandle> {
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    ok_or_ret_errno!(!process.is_null() => ())?;
+    let process = unsafe { FileHandle::from_raw_handle(process) };
+
+    let mut token = ptr::null_mut();
+    let success = unsafe { OpenProcessToken(process.as_raw_handle(), TOKEN_QUERY, &mut token as *mut _) != 0 };
+    ok_or_ret_errno!(success => unsafe { FileHandle::from_raw_handle(token) })
+}
+
+/// Retrieves a piece of information about an access token into a buffer of usizes, which keeps the structure at its
+/// beginning properly aligned.
+fn token_information(token: &FileHandle, class: TOKEN_INFORMATION_CLASS) -> io::Result<Vec<usize>> {
+    // Some structures are followed by variable-length data such as SIDs, so the size has to be queried first. The call
+    // fails with ERROR_INSUFFICIENT_BUFFER, which is expected and thus ignored.
+    let mut len: DWORD = 0;
+    unsafe { GetTokenInformation(token.as_raw_handle(), class, ptr::null_mut(), 0, &mut len as *mut _) };
+    let mut buf = vec![0_usize; len as usize / size_of::<usize>() + 1];
+    let success = unsafe {
+        GetTokenInformation(
+            token.as_raw_handle(),
+            class,
+            buf.as_mut_ptr().cast(),
+            (buf.len() * size_of::<usize>()) as DWORD,
+            &mut len as *mut _,
+        ) != 0
+    };
+    ok_or_ret_errno!(success => buf)
+}
+
+fn token_user_sid(token: &FileHandle) -> io::Result<String> {
+    let buf = token_information(token, TokenUser)?;
+    let sid = unsafe { (*buf.as_ptr().cast::<TOKEN_USER>()).User.Sid };
+
+    let mut wide: *mut u16 = ptr::null_mut();
+    let success = unsafe { ConvertSidToStringSidW(sid, &mut wide as *mut _) != 0 };
+    ok_or_ret_errno!(success => ())?;
+    let sid = unsafe {
+        let len = (0..).take_while(|&i| *wide.add(i) != 0).count();
+        let sid = String::from_utf16_lossy(slice::from_raw_parts(wide, len));
+        LocalFree(wide.cast());
+        sid
+    };
+    Ok(sid)
+}
+
+pub fn name_gen_user_tag() -> OsString {
+    env::var_os("USERNAME").unwrap_or_else(|| "unknown".into())
+}
+/// Never actually used, since paths are not supported.
+pub fn name_gen_directory(_per_user: bool) -> PathBuf {
+    env::temp_dir()
+}
+
 pub const NAME_TYPE_ALWAYS_SUPPORTED: NameTypeSupport = NameTypeSupport::OnlyNamespaced;
 
 pub fn name_type_support_query() -> NameTypeSupport {
@@ -0,0 +1,30 @@
+use super::LocalSocketMsgStream;
+use crate::{
+    local_socket::ToLocalSocketName,
+    os::windows::named_pipe::{pipe_mode, PipeListener as GenericPipeListener, PipeListenerOptions, PipeMode},
+};
+use std::io;
+
+type PipeListener = GenericPipeListener<pipe_mode::Messages, pipe_mode::Messages>;
+
+#[derive(Debug)]
+pub struct LocalSocketMsgListener {
+    inner: PipeListener,
+}
+impl LocalSocketMsgListener {
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = PipeListenerOptions::new()
+            .name(name.into_inner())
+            .mode(PipeMode::Messages)
+            .create()?;
+        Ok(Self { inner })
+    }
+    pub fn accept(&self) -> io::Result<LocalSocketMsgStream> {
+        let inner = self.inner.accept()?;
+        Ok(LocalSocketMsgStream { inner })
+    }
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
@@ -0,0 +1,115 @@
+use {
+    super::peer_credentials_from_pid,
+    crate::{
+        handle_conversion::FromHandleError,
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream},
+        reliable_recv_msg::{RecvBufPool, RecvResult, ReliableRecvMsg, TryRecvResult},
+    },
+    std::{
+        ffi::c_void,
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, FromRawHandle, IntoRawHandle, OwnedHandle},
+        time::Duration,
+    },
+};
+
+pub struct LocalSocketMsgStream {
+    pub(super) inner: DuplexPipeStream<pipe_mode::Messages>,
+}
+impl LocalSocketMsgStream {
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = DuplexPipeStream::connect(name.inner())?;
+        Ok(Self { inner })
+    }
+    #[inline]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.send(buf)
+    }
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        match self.inner.is_server() {
+            true => self.inner.client_process_id(),
+            false => self.inner.server_process_id(),
+        }
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+    #[inline]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+    #[inline]
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    pub fn wait_writable(&self, _timeout: Option<Duration>) -> io::Result<bool> {
+        // Like with byte-mode pipes, nonblocking writes never wait for buffer space, so there is nothing to wait for.
+        Ok(true)
+    }
+}
+impl ReliableRecvMsg for &LocalSocketMsgStream {
+    #[inline]
+    fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
+        (&self.inner).recv(buf)
+    }
+    #[inline]
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (&self.inner).recv_pooled(buf, pool)
+    }
+    #[inline]
+    fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
+        (&self.inner).try_recv(buf)
+    }
+}
+impl Debug for LocalSocketMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgStream")
+            .field("handle", &self.as_raw_handle())
+            .finish()
+    }
+}
+impl AsHandle for LocalSocketMsgStream {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+impl From<LocalSocketMsgStream> for OwnedHandle {
+    #[inline]
+    fn from(x: LocalSocketMsgStream) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedHandle> for LocalSocketMsgStream {
+    type Error = FromHandleError;
+    fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: DuplexPipeStream::try_from(handle)?,
+        })
+    }
+}
+impl AsRawHandle for LocalSocketMsgStream {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.inner.as_raw_handle()
+    }
+}
+impl IntoRawHandle for LocalSocketMsgStream {
+    #[inline]
+    fn into_raw_handle(self) -> *mut c_void {
+        self.inner.into_raw_handle()
+    }
+}
+impl FromRawHandle for LocalSocketMsgStream {
+    unsafe fn from_raw_handle(handle: *mut c_void) -> Self {
+        let inner = unsafe {
+            // SAFETY: guaranteed via safety contract
+            DuplexPipeStream::from_raw_handle(handle).expect("creation from raw handle failed")
+        };
+        Self { inner }
+    }
+}
@@ -1,16 +1,36 @@
 use {
+    super::{peer_credentials_from_pid, peer_identity_from_pid},
     crate::{
-        local_socket::ToLocalSocketName,
-        os::windows::named_pipe::{pipe_mode, DuplexPipeStream},
+        handle_conversion::FromHandleError,
+        local_socket::{LocalSocketPeerCredentials, LocalSocketPeerIdentity, ToLocalSocketName},
+        os::windows::{
+            named_pipe::{pipe_mode, DuplexPipeStream},
+            FileHandle,
+        },
     },
     std::{
-        ffi::c_void,
+        ffi::{c_void, OsStr},
         fmt::{self, Debug, Formatter},
         io::{self, prelude::*, IoSlice, IoSliceMut},
-        os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle},
+        os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, FromRawHandle, IntoRawHandle, OwnedHandle},
+        process, ptr,
+        time::Duration,
+    },
+    winapi::um::{
+        handleapi::DuplicateHandle,
+        processthreadsapi::{GetCurrentProcess, OpenProcess},
+        winnt::{DUPLICATE_CLOSE_SOURCE, DUPLICATE_SAME_ACCESS, PROCESS_DUP_HANDLE},
     },
 };
 
+/// Size of the header that precedes data sent with `.send_handles()`: the number of handles, the length of the data and
+/// the ID of the sending process, all as little-endian `u32`s. The process ID is sent rather than taken from the pipe,
+/// since the end of the pipe may have been handed over to a process other than the one that created or opened it.
+const HANDLE_HEADER_SIZE: usize = 12;
+/// Size of a handle value in the header, which is always 64 bits wide so that 32-bit and 64-bit processes can exchange
+/// handles.
+const HANDLE_VALUE_SIZE: usize = 8;
+
 pub struct LocalSocketStream {
     pub(super) inner: DuplexPipeStream<pipe_mode::Bytes>,
 }
@@ -20,6 +40,11 @@ impl LocalSocketStream {
         let inner = DuplexPipeStream::connect(name.inner())?;
         Ok(Self { inner })
     }
+    pub fn connect_to_remote<'a>(name: impl ToLocalSocketName<'a>, hostname: &OsStr) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = DuplexPipeStream::connect_to_remote(name.inner(), hostname)?;
+        Ok(Self { inner })
+    }
     #[inline]
     pub fn peer_pid(&self) -> io::Result<u32> {
         match self.inner.is_server() {
@@ -27,38 +52,173 @@ impl LocalSocketStream {
             false => self.inner.server_process_id(),
         }
     }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        peer_identity_from_pid(self.peer_pid())
+    }
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            inner: self.inner.try_clone()?,
+        })
+    }
+    pub fn send_handles(&self, buf: &[u8], handles: &[BorrowedHandle<'_>]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "handles must be sent together with at least one byte of data",
+            ));
+        }
+        let too_long = |_| io::Error::new(io::ErrorKind::InvalidInput, "too many handles or too much data");
+        let count = u32::try_from(handles.len()).map_err(too_long)?;
+        let len = u32::try_from(buf.len()).map_err(too_long)?;
+
+        // The receiver takes the duplicates over and closes them in this process, which is why they are never closed
+        // here once they have been sent.
+        let in_flight = handles
+            .iter()
+            .map(BorrowedHandle::try_clone_to_owned)
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut msg = Vec::with_capacity(HANDLE_HEADER_SIZE + HANDLE_VALUE_SIZE * handles.len() + buf.len());
+        msg.extend_from_slice(&count.to_le_bytes());
+        msg.extend_from_slice(&len.to_le_bytes());
+        msg.extend_from_slice(&process::id().to_le_bytes());
+        for handle in &in_flight {
+            msg.extend_from_slice(&(handle.as_raw_handle() as usize as u64).to_le_bytes());
+        }
+        msg.extend_from_slice(buf);
+        (&self.inner).write_all(&msg)?;
+
+        for handle in in_flight {
+            let _ = handle.into_raw_handle();
+        }
+        Ok(buf.len())
+    }
+    pub fn recv_handles(&self, buf: &mut [u8]) -> io::Result<(usize, Vec<OwnedHandle>)> {
+        let mut header = [0; HANDLE_HEADER_SIZE];
+        (&self.inner).read_exact(&mut header)?;
+        let [c0, c1, c2, c3, l0, l1, l2, l3, p0, p1, p2, p3] = header;
+        let count = u32::from_le_bytes([c0, c1, c2, c3]) as usize;
+        let len = u32::from_le_bytes([l0, l1, l2, l3]) as usize;
+        let sender_pid = u32::from_le_bytes([p0, p1, p2, p3]);
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed handle transfer header",
+            ));
+        }
+
+        let mut values = vec![0; HANDLE_VALUE_SIZE * count];
+        (&self.inner).read_exact(&mut values)?;
+
+        let peer = unsafe { OpenProcess(PROCESS_DUP_HANDLE, 0, sender_pid) };
+        ok_or_ret_errno!(!peer.is_null() => ())?;
+        let peer = unsafe { FileHandle::from_raw_handle(peer) };
+
+        let mut handles = Vec::with_capacity(count);
+        for value in values.chunks_exact(HANDLE_VALUE_SIZE) {
+            let mut bytes = [0; HANDLE_VALUE_SIZE];
+            bytes.copy_from_slice(value);
+            let source = u64::from_le_bytes(bytes) as usize as *mut c_void;
+            let mut handle = ptr::null_mut();
+            let success = unsafe {
+                DuplicateHandle(
+                    peer.as_raw_handle(),
+                    source.cast(),
+                    GetCurrentProcess(),
+                    &mut handle,
+                    0,
+                    0,
+                    DUPLICATE_SAME_ACCESS | DUPLICATE_CLOSE_SOURCE,
+                ) != 0
+            };
+            ok_or_ret_errno!(success => ())?;
+            handles.push(unsafe {
+                // SAFETY: the handle has just been created in this process, and nothing else owns it
+                OwnedHandle::from_raw_handle(handle.cast())
+            });
+        }
+
+        let size = len.min(buf.len());
+        (&self.inner).read_exact(&mut buf[..size])?;
+        Ok((size, handles))
+    }
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.peek(buf)
+    }
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        self.inner.bytes_available()
+    }
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        self.inner.wait_readable(timeout)
+    }
+    pub fn wait_writable(&self, _timeout: Option<Duration>) -> io::Result<bool> {
+        // In nonblocking mode, writes to a byte-mode pipe write as much as fits into the buffer instead of blocking, so
+        // there is no readiness to wait for.
+        Ok(true)
+    }
     #[inline]
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         self.inner.set_nonblocking(nonblocking)
     }
+    #[cfg(feature = "bytes")]
+    pub fn read_to_uninit(&self, buf: &mut [std::mem::MaybeUninit<u8>]) -> io::Result<usize> {
+        self.inner.read_to_uninit(buf)
+    }
 }
 
 /// Thunks broken pipe errors into EOFs because broken pipe to the writer is what EOF is to the
 /// reader, but Windows shoehorns both into the former.
-impl Read for LocalSocketStream {
+impl Read for &LocalSocketStream {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.read(buf)
+        (&self.inner).read(buf)
     }
     #[inline]
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.read_vectored(bufs)
+        (&self.inner).read_vectored(bufs)
     }
 }
-impl Write for LocalSocketStream {
+impl Write for &LocalSocketStream {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.write(buf)
+        (&self.inner).write(buf)
     }
     #[inline]
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.write_vectored(bufs)
+        (&self.inner).write_vectored(bufs)
     }
     #[inline]
     fn flush(&mut self) -> io::Result<()> {
         self.inner.flush()
     }
 }
+impl Read for LocalSocketStream {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+    #[inline(always)]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&*self).read_vectored(bufs)
+    }
+}
+impl Write for LocalSocketStream {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+    #[inline(always)]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&*self).write_vectored(bufs)
+    }
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
 impl Debug for LocalSocketStream {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("LocalSocketStream")
@@ -66,6 +226,26 @@ impl Debug for LocalSocketStream {
             .finish()
     }
 }
+impl AsHandle for LocalSocketStream {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+impl From<LocalSocketStream> for OwnedHandle {
+    #[inline]
+    fn from(x: LocalSocketStream) -> Self {
+        x.inner.into()
+    }
+}
+impl TryFrom<OwnedHandle> for LocalSocketStream {
+    type Error = FromHandleError;
+    fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            inner: DuplexPipeStream::try_from(handle)?,
+        })
+    }
+}
 impl AsRawHandle for LocalSocketStream {
     #[inline]
     fn as_raw_handle(&self) -> *mut c_void {
@@ -1,19 +1,32 @@
 use super::LocalSocketStream;
 use crate::{
-    local_socket::ToLocalSocketName,
+    listener_close::{AsyncCloseSignal, ListenerClosedError},
+    local_socket::{LocalSocketListenerOptions, ToLocalSocketName},
     os::windows::named_pipe::{
         pipe_mode,
         tokio::{PipeListener as GenericPipeListener, PipeListenerOptionsExt as _},
         PipeListenerOptions, PipeMode,
     },
 };
-use std::io;
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
 
 type PipeListener = GenericPipeListener<pipe_mode::Bytes, pipe_mode::Bytes>;
+type AcceptFuture = Pin<Box<dyn Future<Output = io::Result<LocalSocketStream>> + Send>>;
 
-#[derive(Debug)]
 pub struct LocalSocketListener {
-    inner: PipeListener,
+    inner: Arc<PipeListener>,
+    close: AsyncCloseSignal,
+    /// The accept operation driven by `poll_accept()`. Since accepting is cancel safe, this can be dropped at any
+    /// point without losing a client.
+    pending_accept: Mutex<Option<AcceptFuture>>,
 }
 impl LocalSocketListener {
     pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
@@ -22,10 +35,70 @@ impl LocalSocketListener {
             .name(name.into_inner())
             .mode(PipeMode::Bytes)
             .create_tokio()?;
-        Ok(Self { inner })
+        Ok(Self::from_pipe_listener(inner))
+    }
+    pub fn bind_with_options<'a>(
+        name: impl ToLocalSocketName<'a>,
+        options: &LocalSocketListenerOptions,
+    ) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = PipeListenerOptions::new()
+            .name(name.into_inner())
+            .mode(PipeMode::Bytes)
+            .instance_limit(options.instance_limit)
+            .accept_remote(options.accept_remote)
+            .security_descriptor(options.security_descriptor.as_deref().map(Cow::Borrowed))
+            .create_tokio()?;
+        Ok(Self::from_pipe_listener(inner))
+    }
+    fn from_pipe_listener(inner: PipeListener) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            close: AsyncCloseSignal::default(),
+            pending_accept: Mutex::new(None),
+        }
     }
     pub async fn accept(&self) -> io::Result<LocalSocketStream> {
-        let inner = self.inner.accept().await?;
+        let inner = self.close.race(self.inner.accept()).await?;
         Ok(LocalSocketStream { inner })
     }
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<LocalSocketStream>> {
+        if self.close.poll_closed(cx) {
+            *self.pending_accept.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            return Poll::Ready(Err(ListenerClosedError.into()));
+        }
+        let mut pending_accept = self.pending_accept.lock().unwrap_or_else(|e| e.into_inner());
+        let fut = pending_accept.get_or_insert_with(|| {
+            let inner = Arc::clone(&self.inner);
+            Box::pin(async move { inner.accept().await.map(|inner| LocalSocketStream { inner }) })
+        });
+        let rslt = fut.as_mut().poll(cx);
+        if rslt.is_ready() {
+            *pending_accept = None;
+            self.close.forget(cx);
+        }
+        rslt
+    }
+    #[inline]
+    pub fn close(&self) {
+        self.close.close()
+    }
+    /// Named pipes don't reside on the filesystem, so there's nothing to unlink.
+    #[inline]
+    pub fn close_and_unlink(&self) -> io::Result<()> {
+        self.close();
+        Ok(())
+    }
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.close.is_closed()
+    }
+}
+impl Debug for LocalSocketListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketListener")
+            .field("inner", &self.inner)
+            .field("close", &self.close)
+            .finish_non_exhaustive()
+    }
 }
@@ -3,5 +3,11 @@
 mod listener;
 pub use listener::*;
 
+mod msg_listener;
+pub use msg_listener::*;
+
+mod msg_stream;
+pub use msg_stream::*;
+
 mod stream;
 pub use stream::*;
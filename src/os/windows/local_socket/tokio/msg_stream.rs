@@ -0,0 +1,66 @@
+use {
+    super::super::peer_credentials_from_pid,
+    crate::{
+        local_socket::{LocalSocketPeerCredentials, ToLocalSocketName},
+        os::windows::named_pipe::{pipe_mode, tokio::DuplexPipeStream},
+        reliable_recv_msg::{AsyncReliableRecvMsg, TryRecvResult},
+    },
+    std::{
+        ffi::c_void,
+        fmt::{self, Debug, Formatter},
+        io,
+        os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle},
+        pin::Pin,
+        task::{Context, Poll},
+    },
+};
+
+type StreamImpl = DuplexPipeStream<pipe_mode::Messages>;
+
+pub struct LocalSocketMsgStream {
+    pub(super) inner: StreamImpl,
+}
+impl LocalSocketMsgStream {
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = DuplexPipeStream::connect(name.inner()).await?;
+        Ok(Self { inner })
+    }
+    #[inline]
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.inner.poll_send(cx, buf)
+    }
+    #[inline]
+    pub fn poll_try_recv(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
+        Pin::new(&mut &self.inner).poll_try_recv(cx, buf)
+    }
+    #[inline]
+    pub fn peer_pid(&self) -> io::Result<u32> {
+        match self.inner.is_server() {
+            true => self.inner.client_process_id(),
+            false => self.inner.server_process_id(),
+        }
+    }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+}
+impl Debug for LocalSocketMsgStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSocketMsgStream")
+            .field("handle", &self.as_raw_handle())
+            .finish()
+    }
+}
+impl AsHandle for LocalSocketMsgStream {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+impl AsRawHandle for LocalSocketMsgStream {
+    #[inline]
+    fn as_raw_handle(&self) -> *mut c_void {
+        self.inner.as_raw_handle()
+    }
+}
@@ -3,11 +3,11 @@ pub use read_half::*;
 
 mod write_half;
 pub use write_half::*;
-// TODO reunite
 
 use {
+    super::super::{peer_credentials_from_pid, peer_identity_from_pid},
     crate::{
-        local_socket::ToLocalSocketName,
+        local_socket::{LocalSocketPeerCredentials, LocalSocketPeerIdentity, ToLocalSocketName},
         os::windows::named_pipe::{pipe_mode, tokio::DuplexPipeStream},
     },
     futures_io::{AsyncRead, AsyncWrite},
@@ -15,10 +15,12 @@ use {
         ffi::c_void,
         fmt::{self, Debug, Formatter},
         io,
-        os::windows::io::AsRawHandle,
+        os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle},
         pin::Pin,
         task::{Context, Poll},
+        time::Duration,
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
 };
 
 type StreamImpl = DuplexPipeStream<pipe_mode::Bytes>;
@@ -32,6 +34,11 @@ impl LocalSocketStream {
         let inner = DuplexPipeStream::connect(name.inner()).await?;
         Ok(Self { inner })
     }
+    pub async fn connect_with_timeout<'a>(name: impl ToLocalSocketName<'a>, timeout: Duration) -> io::Result<Self> {
+        let name = name.to_local_socket_name()?;
+        let inner = DuplexPipeStream::connect_with_timeout(name.inner(), timeout).await?;
+        Ok(Self { inner })
+    }
     #[inline]
     pub fn peer_pid(&self) -> io::Result<u32> {
         match self.inner.is_server() {
@@ -39,15 +46,37 @@ impl LocalSocketStream {
             false => self.inner.server_process_id(),
         }
     }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
+    pub fn peer_identity(&self) -> io::Result<LocalSocketPeerIdentity> {
+        peer_identity_from_pid(self.peer_pid())
+    }
+    #[inline]
+    pub async fn readable(&self) -> io::Result<()> {
+        self.inner.readable().await
+    }
+    #[inline]
+    pub async fn writable(&self) -> io::Result<()> {
+        self.inner.writable().await
+    }
+    #[inline]
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.try_read(buf)
+    }
+    #[inline]
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.try_write(buf)
+    }
     #[inline]
     pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
         let (r, w) = self.inner.split();
-        (OwnedReadHalf { inner: r }, OwnedWriteHalf { inner: w })
+        (OwnedReadHalf { inner: r }, OwnedWriteHalf::new(w))
     }
-    pub fn reunite(rh: OwnedReadHalf, wh: OwnedWriteHalf) -> io::Result<Self> {
+    pub fn reunite(rh: OwnedReadHalf, wh: OwnedWriteHalf) -> Result<Self, (OwnedReadHalf, OwnedWriteHalf)> {
         match rh.inner.reunite(wh.inner) {
             Ok(inner) => Ok(Self { inner }),
-            Err(_) => todo!(),
+            Err(e) => Err((OwnedReadHalf { inner: e.recv_half }, OwnedWriteHalf::new(e.send_half))),
         }
     }
     #[inline]
@@ -61,23 +90,43 @@ impl LocalSocketStream {
 impl AsyncRead for LocalSocketStream {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
     }
 }
 impl AsyncWrite for LocalSocketStream {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         self.pinproj().poll_close(cx)
     }
 }
+impl TokioAsyncRead for LocalSocketStream {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
+impl TokioAsyncWrite for LocalSocketStream {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_shutdown(self.pinproj(), cx)
+    }
+}
 impl Debug for LocalSocketStream {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("LocalSocketStream")
@@ -85,6 +134,12 @@ impl Debug for LocalSocketStream {
             .finish()
     }
 }
+impl AsHandle for LocalSocketStream {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
 impl AsRawHandle for LocalSocketStream {
     #[inline]
     fn as_raw_handle(&self) -> *mut c_void {
@@ -1,5 +1,9 @@
 use {
-    crate::os::windows::named_pipe::{pipe_mode, tokio::RecvHalf},
+    super::super::super::peer_credentials_from_pid,
+    crate::{
+        local_socket::LocalSocketPeerCredentials,
+        os::windows::named_pipe::{pipe_mode, tokio::RecvHalf},
+    },
     futures_io::AsyncRead,
     std::{
         ffi::c_void,
@@ -9,6 +13,7 @@ use {
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::{AsyncRead as TokioAsyncRead, ReadBuf as TokioReadBuf},
 };
 
 type ReadHalfImpl = RecvHalf<pipe_mode::Bytes>;
@@ -24,6 +29,9 @@ impl OwnedReadHalf {
             false => self.inner.server_process_id(),
         }
     }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
     fn pinproj(&mut self) -> Pin<&mut ReadHalfImpl> {
         Pin::new(&mut self.inner)
     }
@@ -34,7 +42,13 @@ impl OwnedReadHalf {
 impl AsyncRead for OwnedReadHalf {
     #[inline]
     fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_read(cx, buf)
+        AsyncRead::poll_read(self.pinproj(), cx, buf)
+    }
+}
+impl TokioAsyncRead for OwnedReadHalf {
+    #[inline]
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncRead::poll_read(self.pinproj(), cx, buf)
     }
 }
 impl Debug for OwnedReadHalf {
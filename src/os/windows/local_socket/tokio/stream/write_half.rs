@@ -1,5 +1,10 @@
 use {
-    crate::os::windows::named_pipe::{pipe_mode, tokio::SendHalf},
+    super::super::super::peer_credentials_from_pid,
+    crate::{
+        local_socket::LocalSocketPeerCredentials,
+        os::windows::named_pipe::{pipe_mode, tokio::SendHalf},
+    },
+    futures_core::ready,
     futures_io::AsyncWrite,
     std::{
         ffi::c_void,
@@ -9,14 +14,24 @@ use {
         pin::Pin,
         task::{Context, Poll},
     },
+    tokio::io::AsyncWrite as TokioAsyncWrite,
 };
 
 type WriteHalfImpl = SendHalf<pipe_mode::Bytes>;
 
 pub struct OwnedWriteHalf {
     pub(super) inner: WriteHalfImpl,
+    // Named pipes can't be shut down in one direction, so this is emulated by refusing further writes.
+    shut_down: bool,
 }
 impl OwnedWriteHalf {
+    #[inline]
+    pub(super) fn new(inner: WriteHalfImpl) -> Self {
+        Self {
+            inner,
+            shut_down: false,
+        }
+    }
     #[inline]
     pub fn peer_pid(&self) -> io::Result<u32> {
         match self.inner.is_server() {
@@ -24,22 +39,59 @@ impl OwnedWriteHalf {
             false => self.inner.server_process_id(),
         }
     }
+    pub fn peer_credentials(&self) -> io::Result<LocalSocketPeerCredentials> {
+        peer_credentials_from_pid(self.peer_pid())
+    }
     fn pinproj(&mut self) -> Pin<&mut WriteHalfImpl> {
         Pin::new(&mut self.inner)
     }
+    fn ensure_not_shut_down(&self) -> io::Result<()> {
+        if self.shut_down {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the write half of the local socket stream has been shut down",
+            ));
+        }
+        Ok(())
+    }
+    /// Waits for the peer to receive everything that was sent and refuses further writes.
+    fn poll_shutdown_impl(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if !self.shut_down {
+            ready!(TokioAsyncWrite::poll_flush(self.pinproj(), cx))?;
+            self.shut_down = true;
+        }
+        Poll::Ready(Ok(()))
+    }
 }
 impl AsyncWrite for OwnedWriteHalf {
     #[inline]
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        self.pinproj().poll_write(cx, buf)
+        self.ensure_not_shut_down()?;
+        AsyncWrite::poll_write(self.pinproj(), cx, buf)
     }
     #[inline]
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_flush(cx)
+        AsyncWrite::poll_flush(self.pinproj(), cx)
     }
     #[inline]
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        self.pinproj().poll_close(cx)
+        self.poll_shutdown_impl(cx)
+    }
+}
+
+impl TokioAsyncWrite for OwnedWriteHalf {
+    #[inline]
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.ensure_not_shut_down()?;
+        TokioAsyncWrite::poll_write(self.pinproj(), cx, buf)
+    }
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        TokioAsyncWrite::poll_flush(self.pinproj(), cx)
+    }
+    #[inline]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_shutdown_impl(cx)
     }
 }
 
@@ -47,6 +99,7 @@ impl Debug for OwnedWriteHalf {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("local_socket::OwnedWriteHalf")
             .field("handle", &self.as_raw_handle())
+            .field("shut_down", &self.shut_down)
             .finish()
     }
 }
@@ -2,22 +2,32 @@
 #![cfg_attr(not(windows), allow(warnings))]
 
 pub mod named_pipe;
+pub(crate) mod platform;
+pub(crate) mod select;
+pub mod shared_memory;
+pub(crate) mod signal;
+pub(crate) mod spawn;
+pub(crate) mod sync;
 pub mod unnamed_pipe;
 // TODO mailslots
 //pub mod mailslot;
 pub(crate) mod local_socket;
+pub use local_socket::{LocalSocketConnectOptionsExt, LocalSocketListenerOptionsExt};
 
+use crate::{Error, ErrorKind};
 use std::{
     io,
-    mem::{transmute, ManuallyDrop, MaybeUninit},
+    mem::{transmute, MaybeUninit},
     ptr,
 };
 use winapi::{
-    shared::winerror::ERROR_PIPE_NOT_CONNECTED,
+    shared::winerror::{ERROR_PIPE_NOT_CONNECTED, NO_ERROR},
     um::{
-        fileapi::{FlushFileBuffers, ReadFile, WriteFile},
-        handleapi::{CloseHandle, DuplicateHandle, INVALID_HANDLE_VALUE},
+        fileapi::{FlushFileBuffers, GetFileType, ReadFile, WriteFile},
+        handleapi::{DuplicateHandle, INVALID_HANDLE_VALUE},
         processthreadsapi::GetCurrentProcess,
+        winbase::{FILE_TYPE_PIPE, FILE_TYPE_UNKNOWN},
+        winnt::DUPLICATE_SAME_ACCESS,
     },
 };
 mod winprelude {
@@ -34,7 +44,7 @@ use winprelude::*;
 
 /// Objects which own handles which can be shared with another processes.
 ///
-/// On Windows, like with most other operating systems, handles belong to specific processes. You shouldn't just send the value of a handle to another process (with a named pipe, for example) and expect it to work on the other side. For this to work, you need [`DuplicateHandle`] – the Win32 API function which duplicates a handle into the handle table of the specified process (the reciever is referred to by its handle). This trait exposes the `DuplicateHandle` functionality in a safe manner. If the handle is *inheritable*, however, all child processes of a process inherit the handle and thus can use the same value safely without the need to share it. *All Windows handle objects created by this crate are inheritable.*
+/// On Windows, like with most other operating systems, handles belong to specific processes. You shouldn't just send the value of a handle to another process (with a named pipe, for example) and expect it to work on the other side. For this to work, you need [`DuplicateHandle`] – the Win32 API function which duplicates a handle into the handle table of the specified process (the reciever is referred to by its handle). This trait exposes the `DuplicateHandle` functionality in a safe manner. If the handle is *inheritable*, however, all child processes of a process inherit the handle and thus can use the same value safely without the need to share it. *All Windows handle objects created by this crate are inheritable, unless configured otherwise.*
 ///
 /// **Implemented for all types inside this crate which implement [`AsRawHandle`] and are supposed to be shared between processes.**
 ///
@@ -76,11 +86,31 @@ fn weaken_buf_init(buf: &mut [u8]) -> &mut [MaybeUninit<u8>] {
     }
 }
 
-/// Newtype wrapper which defines file I/O operations on a `HANDLE` to a file.
+/// Newtype wrapper which defines file I/O operations on an owned `HANDLE` to a file.
 #[repr(transparent)]
 #[derive(Debug)]
-pub(crate) struct FileHandle(pub(crate) HANDLE);
+pub(crate) struct FileHandle(pub(crate) OwnedHandle);
 impl FileHandle {
+    /// Duplicates the handle within the current process, keeping it inheritable like all other handles of the crate.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let (success, new_handle) = unsafe {
+            let mut new_handle = INVALID_HANDLE_VALUE;
+            let success = DuplicateHandle(
+                GetCurrentProcess(),
+                self.as_raw_handle(),
+                GetCurrentProcess(),
+                &mut new_handle,
+                0,
+                1,
+                DUPLICATE_SAME_ACCESS,
+            );
+            (success != 0, new_handle)
+        };
+        ok_or_ret_errno!(success => unsafe {
+            // SAFETY: DuplicateHandle() just created the handle
+            Self::from_raw_handle(new_handle)
+        })
+    }
     pub fn read(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
         debug_assert!(
             buf.len() <= DWORD::max_value() as usize,
@@ -89,7 +119,7 @@ impl FileHandle {
         let (success, num_bytes_read) = unsafe {
             let mut num_bytes_read: DWORD = 0;
             let result = ReadFile(
-                self.0,
+                self.as_raw_handle(),
                 buf.as_mut_ptr() as *mut _,
                 buf.len() as DWORD,
                 &mut num_bytes_read as *mut _,
@@ -110,7 +140,7 @@ impl FileHandle {
         let (success, bytes_written) = unsafe {
             let mut number_of_bytes_written: DWORD = 0;
             let result = WriteFile(
-                self.0,
+                self.as_raw_handle(),
                 buf.as_ptr() as *mut _,
                 buf.len() as DWORD,
                 &mut number_of_bytes_written as *mut _,
@@ -122,7 +152,7 @@ impl FileHandle {
     }
     #[inline(always)]
     pub fn flush(&self) -> io::Result<()> {
-        Self::flush_hndl(self.0)
+        Self::flush_hndl(self.as_raw_handle())
     }
     #[inline]
     pub fn flush_hndl(handle: HANDLE) -> io::Result<()> {
@@ -133,34 +163,57 @@ impl FileHandle {
         }
     }
 }
-impl Drop for FileHandle {
-    #[inline]
-    fn drop(&mut self) {
-        let _success = unsafe { CloseHandle(self.0) != 0 };
-        debug_assert!(_success, "failed to close file handle: {}", io::Error::last_os_error());
+impl AsHandle for FileHandle {
+    #[inline(always)]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.0.as_handle()
     }
 }
 impl AsRawHandle for FileHandle {
     #[inline(always)]
     fn as_raw_handle(&self) -> HANDLE {
-        self.0
+        self.0.as_raw_handle()
+    }
+}
+impl From<FileHandle> for OwnedHandle {
+    #[inline(always)]
+    fn from(fh: FileHandle) -> Self {
+        fh.0
+    }
+}
+impl From<OwnedHandle> for FileHandle {
+    #[inline(always)]
+    fn from(handle: OwnedHandle) -> Self {
+        Self(handle)
     }
 }
 impl IntoRawHandle for FileHandle {
     #[inline(always)]
     fn into_raw_handle(self) -> HANDLE {
-        let self_ = ManuallyDrop::new(self);
-        self_.as_raw_handle()
+        self.0.into_raw_handle()
     }
 }
 impl FromRawHandle for FileHandle {
     #[inline(always)]
     unsafe fn from_raw_handle(op: HANDLE) -> Self {
-        Self(op)
+        Self(unsafe { OwnedHandle::from_raw_handle(op) })
+    }
+}
+
+/// Checks that the handle refers to a pipe, named or unnamed.
+pub(crate) fn check_pipe_handle(handle: BorrowedHandle<'_>) -> io::Result<()> {
+    let ty = unsafe { GetFileType(handle.as_raw_handle()) };
+    if ty == FILE_TYPE_UNKNOWN {
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() != Some(NO_ERROR as _) {
+            return Err(e);
+        }
+    }
+    if ty != FILE_TYPE_PIPE {
+        return Err(Error::new(ErrorKind::InvalidHandle, "the handle is not a pipe").into());
     }
+    Ok(())
 }
-unsafe impl Send for FileHandle {}
-unsafe impl Sync for FileHandle {} // WriteFile and ReadFile are thread-safe, apparently
 
 fn is_eof_like(e: &io::Error) -> bool {
     e.kind() == io::ErrorKind::BrokenPipe || e.raw_os_error() == Some(ERROR_PIPE_NOT_CONNECTED as _)
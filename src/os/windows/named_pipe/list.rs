@@ -0,0 +1,153 @@
+use {
+    crate::os::windows::winprelude::*,
+    std::{
+        ffi::{OsStr, OsString},
+        fmt::{self, Debug, Formatter},
+        io,
+        iter::{once, FusedIterator},
+        mem,
+        os::windows::ffi::{OsStrExt, OsStringExt},
+    },
+    winapi::{
+        shared::winerror::{ERROR_FILE_NOT_FOUND, ERROR_NO_MORE_FILES},
+        um::{
+            fileapi::{FindClose, FindFirstFileW, FindNextFileW},
+            minwinbase::WIN32_FIND_DATAW,
+        },
+    },
+};
+
+/// A named pipe found by [`list_pipes()`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PipeInfo {
+    name: OsString,
+    instances: u32,
+}
+impl PipeInfo {
+    /// Returns the name of the pipe, without the `\\.\pipe\` prefix, in the form accepted by
+    /// [`PipeListenerOptions::name()`](super::PipeListenerOptions::name) and
+    /// [`PipeStream::connect()`](super::PipeStream::connect).
+    pub fn name(&self) -> &OsStr {
+        &self.name
+    }
+    /// Consumes the value, returning the name of the pipe.
+    pub fn into_name(self) -> OsString {
+        self.name
+    }
+    /// Returns how many instances of the pipe existed when it was listed, connected or not.
+    pub fn instances(&self) -> u32 {
+        self.instances
+    }
+}
+
+/// Lists the named pipes on the local computer, yielding their names and instance counts, so that diagnostic tools
+/// can find out which servers are running.
+///
+/// The listing is a snapshot taken as the iterator advances – pipes created or destroyed in the meantime may or may not
+/// show up. Pipes which the current user has no access to are listed all the same, since their names are public.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::windows::named_pipe::list_pipes;
+///
+/// for pipe in list_pipes()? {
+///     let pipe = pipe?;
+///     println!("{:?}: {} instance(s)", pipe.name(), pipe.instances());
+/// }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+///
+/// # System calls
+/// - `FindFirstFileW` on `\\.\pipe\*`, which queries the root directory of the named pipe filesystem with
+///   `NtQueryDirectoryFile`. The instance count is what it reports as the size of the file.
+/// - `FindNextFileW`
+/// - `FindClose`
+pub fn list_pipes() -> io::Result<PipeList> {
+    let pattern = OsStr::new(r"\\.\pipe\*")
+        .encode_wide()
+        .chain(once(0))
+        .collect::<Vec<_>>();
+    // SAFETY: plain old data
+    let mut data: WIN32_FIND_DATAW = unsafe { mem::zeroed() };
+    let handle = unsafe { FindFirstFileW(pattern.as_ptr(), &mut data as *mut _) };
+    if handle == INVALID_HANDLE_VALUE {
+        let e = io::Error::last_os_error();
+        return match e.raw_os_error() {
+            Some(code) if code == ERROR_FILE_NOT_FOUND as i32 => Ok(PipeList {
+                handle,
+                data,
+                state: State::Done,
+            }),
+            _ => Err(e),
+        };
+    }
+    Ok(PipeList {
+        handle,
+        data,
+        state: State::Pending,
+    })
+}
+
+/// Iterator over the named pipes on the local computer, created by [`list_pipes()`].
+pub struct PipeList {
+    handle: HANDLE,
+    data: WIN32_FIND_DATAW,
+    state: State,
+}
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    /// `data` holds an entry which hasn't been yielded yet.
+    Pending,
+    /// The next entry has to be fetched with `FindNextFileW`.
+    Fetch,
+    Done,
+}
+impl PipeList {
+    fn current(&self) -> PipeInfo {
+        let file_name = &self.data.cFileName;
+        let len = file_name.iter().position(|&c| c == 0).unwrap_or(file_name.len());
+        PipeInfo {
+            name: OsString::from_wide(&file_name[..len]),
+            instances: self.data.nFileSizeLow,
+        }
+    }
+}
+impl Iterator for PipeList {
+    type Item = io::Result<PipeInfo>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.state {
+            State::Done => return None,
+            State::Pending => self.state = State::Fetch,
+            State::Fetch => {
+                if unsafe { FindNextFileW(self.handle, &mut self.data as *mut _) } == 0 {
+                    self.state = State::Done;
+                    let e = io::Error::last_os_error();
+                    return match e.raw_os_error() {
+                        Some(code) if code == ERROR_NO_MORE_FILES as i32 => None,
+                        _ => Some(Err(e)),
+                    };
+                }
+            }
+        }
+        Some(Ok(self.current()))
+    }
+}
+impl FusedIterator for PipeList {}
+impl Debug for PipeList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipeList")
+            .field("handle", &self.handle)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+impl Drop for PipeList {
+    fn drop(&mut self) {
+        if self.handle != INVALID_HANDLE_VALUE {
+            unsafe { FindClose(self.handle) };
+        }
+    }
+}
+// SAFETY: find handles are not tied to the thread which opened them.
+unsafe impl Send for PipeList {}
+unsafe impl Sync for PipeList {}
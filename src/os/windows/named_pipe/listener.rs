@@ -6,23 +6,30 @@ use std::{
     fmt::{self, Debug, Formatter},
     io,
     marker::PhantomData,
-    mem::replace,
+    mem::{replace, size_of},
     num::{NonZeroU32, NonZeroU8},
     ptr,
     sync::{
         atomic::{AtomicBool, Ordering::Relaxed},
         Mutex,
     },
+    thread,
+    time::{Duration, Instant},
 };
 use to_method::To;
 use winapi::{
-    shared::winerror::ERROR_PIPE_CONNECTED,
+    shared::{
+        sddl::{ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1},
+        winerror::{ERROR_PIPE_CONNECTED, ERROR_PIPE_LISTENING},
+    },
     um::{
+        minwinbase::SECURITY_ATTRIBUTES,
         namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW},
         winbase::{
-            FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAG_OVERLAPPED, FILE_FLAG_WRITE_THROUGH, PIPE_NOWAIT,
+            LocalFree, FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAG_OVERLAPPED, FILE_FLAG_WRITE_THROUGH, PIPE_NOWAIT,
             PIPE_REJECT_REMOTE_CLIENTS,
         },
+        winnt::PSECURITY_DESCRIPTOR,
     },
 };
 
@@ -65,20 +72,53 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
     ///
     /// See `incoming` for an iterator version of this.
     pub fn accept(&self) -> io::Result<PipeStream<Rm, Sm>> {
+        self.accept_with(|instance, _| block_on_connect(instance))
+    }
+    /// Like [`accept`](Self::accept), but gives up with a [`TimedOut`](io::ErrorKind::TimedOut) error if no client connects within the given timeout.
+    ///
+    /// Since the instances of the pipe are not created for overlapped I/O, the waiting is done by temporarily switching the pending instance to nonblocking mode and polling it at short intervals.
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<PipeStream<Rm, Sm>> {
+        self.accept_with(|instance, nonblocking| {
+            if !nonblocking {
+                unsafe { super::set_nonblocking_for_stream(instance.as_raw_handle(), Rm::MODE, true)? };
+            }
+            let result = poll_connect(instance, timeout);
+            if !nonblocking {
+                unsafe { super::set_nonblocking_for_stream(instance.as_raw_handle(), Rm::MODE, false)? };
+            }
+            result
+        })
+    }
+    /// Waits for a client to connect to the pending instance without accepting it, returning `false` if the timeout
+    /// expires first. The next call to `accept` then returns that client.
+    pub(crate) fn wait_for_client(&self, timeout: Duration) -> io::Result<bool> {
+        let stored_instance = self.stored_instance.lock().expect("unexpected lock poison");
+        let nonblocking = self.nonblocking.load(Relaxed);
+        if !nonblocking {
+            unsafe { super::set_nonblocking_for_stream(stored_instance.as_raw_handle(), Rm::MODE, true)? };
+        }
+        let result = poll_connect(&stored_instance, timeout);
+        if !nonblocking {
+            unsafe { super::set_nonblocking_for_stream(stored_instance.as_raw_handle(), Rm::MODE, false)? };
+        }
+        match result {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    fn accept_with(&self, connect: impl FnOnce(&FileHandle, bool) -> io::Result<()>) -> io::Result<PipeStream<Rm, Sm>> {
         let instance_to_hand_out = {
             let mut stored_instance = self.stored_instance.lock().expect("unexpected lock poison");
             // Doesn't actually even need to be atomic to begin with, but it's simpler and more
             // convenient to do this instead. The mutex takes care of ordering.
             let nonblocking = self.nonblocking.load(Relaxed);
-            block_on_connect(&stored_instance)?;
+            connect(&stored_instance, nonblocking)?;
             let new_instance = self.create_instance(nonblocking)?;
             replace(&mut *stored_instance, new_instance)
         };
 
-        let raw = RawPipeStream {
-            handle: instance_to_hand_out,
-            is_server: true,
-        };
+        let raw = RawPipeStream::new(instance_to_hand_out, true);
 
         Ok(PipeStream::new(raw))
     }
@@ -157,6 +197,10 @@ pub struct PipeListenerOptions<'a> {
     /// The default timeout clients use when connecting. Used unless another timeout is specified when waiting by a client.
     // TODO use WaitTimeout struct
     pub wait_timeout: NonZeroU32,
+    /// The security descriptor for the named pipe, in the [security descriptor definition language] (SDDL) string format. If set to `None`, the pipe gets a default security descriptor, which grants full control to the LocalSystem account, administrators and the creator owner, and read access to members of the Everyone group and the anonymous account.
+    ///
+    /// [security descriptor definition language]: https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-definition-language " "
+    pub security_descriptor: Option<Cow<'a, OsStr>>,
 }
 macro_rules! genset {
     ($name:ident : $ty:ty) => {
@@ -189,6 +233,7 @@ impl<'a> PipeListenerOptions<'a> {
             input_buffer_size_hint: 512,
             output_buffer_size_hint: 512,
             wait_timeout: NonZeroU32::new(50).unwrap(),
+            security_descriptor: None,
         }
     }
     /// Clones configuration options which are not owned by value and returns a copy of the original option table which is guaranteed not to borrow anything and thus ascribes to the `'static` lifetime.
@@ -208,6 +253,10 @@ impl<'a> PipeListenerOptions<'a> {
             input_buffer_size_hint: self.input_buffer_size_hint,
             output_buffer_size_hint: self.output_buffer_size_hint,
             wait_timeout: self.wait_timeout,
+            security_descriptor: self
+                .security_descriptor
+                .as_ref()
+                .map(|sd| Cow::Owned(sd.clone().into_owned())),
         }
     }
     genset!(
@@ -220,6 +269,7 @@ impl<'a> PipeListenerOptions<'a> {
         input_buffer_size_hint: DWORD,
         output_buffer_size_hint: DWORD,
         wait_timeout: NonZeroU32,
+        security_descriptor: Option<Cow<'a, OsStr>>,
     );
     /// Creates an instance of a pipe for a listener with the specified stream type and with the first-instance flag set to the specified value.
     pub(super) fn create_instance(
@@ -242,8 +292,13 @@ cannot create pipe server that has byte type but reads messages – have you for
         let path = super::convert_and_encode_path(&self.name, None);
         let open_mode = self.open_mode(first, role, overlapped);
         let pipe_mode = self.pipe_mode(read_mode, nonblocking);
+        let security_descriptor = self
+            .security_descriptor
+            .as_deref()
+            .map(SecurityDescriptor::from_sddl)
+            .transpose()?;
+        let mut security_attributes = security_descriptor.as_ref().map(SecurityDescriptor::to_attributes);
         let (handle, success) = unsafe {
-            // TODO security attributes
             let handle = CreateNamedPipeW(
                 path.as_ptr(),
                 open_mode,
@@ -258,10 +313,14 @@ cannot create pipe server that has byte type but reads messages – have you for
                 self.output_buffer_size_hint,
                 self.input_buffer_size_hint,
                 self.wait_timeout.get(),
-                ptr::null_mut(),
+                security_attributes.as_mut().map_or(ptr::null_mut(), |sa| sa as *mut _),
             );
             (handle, handle != INVALID_HANDLE_VALUE)
         };
+        if !success && first {
+            // Only the first instance can run into a pipe of another server.
+            return Err(crate::error::create_pipe_error(io::Error::last_os_error()));
+        }
         ok_or_ret_errno!(success => handle)
     }
     /// Creates the pipe listener from the builder. The `Rm` and `Sm` generic arguments specify the type of pipe stream that the listener will create, thus determining the direction of the pipe and its mode.
@@ -345,6 +404,40 @@ impl Default for PipeListenerOptions<'_> {
     }
 }
 
+/// A security descriptor allocated by `ConvertStringSecurityDescriptorToSecurityDescriptorW`, freed on drop.
+pub(crate) struct SecurityDescriptor(PSECURITY_DESCRIPTOR);
+impl SecurityDescriptor {
+    pub(crate) fn from_sddl(sddl: &OsStr) -> io::Result<Self> {
+        let sddl = sddl.encode_wide().chain(Some(0)).collect::<Vec<_>>();
+        let mut sd = ptr::null_mut();
+        let success = unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1.into(),
+                &mut sd as *mut _,
+                ptr::null_mut(),
+            ) != 0
+        };
+        ok_or_ret_errno!(success => Self(sd))
+    }
+    pub(crate) fn as_ptr(&self) -> PSECURITY_DESCRIPTOR {
+        self.0
+    }
+    fn to_attributes(&self) -> SECURITY_ATTRIBUTES {
+        SECURITY_ATTRIBUTES {
+            nLength: size_of::<SECURITY_ATTRIBUTES>() as DWORD,
+            lpSecurityDescriptor: self.0,
+            // Matches what passing no security attributes at all does.
+            bInheritHandle: 0,
+        }
+    }
+}
+impl Drop for SecurityDescriptor {
+    fn drop(&mut self) {
+        unsafe { LocalFree(self.0) };
+    }
+}
+
 fn block_on_connect(handle: &FileHandle) -> io::Result<()> {
     let success = unsafe { ConnectNamedPipe(handle.as_raw_handle(), ptr::null_mut()) != 0 };
     if success {
@@ -358,3 +451,25 @@ fn block_on_connect(handle: &FileHandle) -> io::Result<()> {
         }
     }
 }
+
+/// Calls `ConnectNamedPipe` on an instance in nonblocking mode until a client connects or the timeout expires.
+fn poll_connect(handle: &FileHandle, timeout: Duration) -> io::Result<()> {
+    const MAX_INTERVAL: Duration = Duration::from_millis(50);
+    let deadline = Instant::now() + timeout;
+    let mut interval = Duration::from_millis(1);
+    loop {
+        match block_on_connect(handle) {
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_LISTENING as i32) => {}
+            els => return els,
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining == Duration::ZERO {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no client connected before the timeout expired",
+            ));
+        }
+        thread::sleep(interval.min(remaining));
+        interval = (interval * 2).min(MAX_INTERVAL);
+    }
+}
@@ -17,22 +17,27 @@
 // FIXME message streams should have methods instead of I/O traits
 
 mod enums;
+mod list;
 mod listener;
 mod stream;
-pub use {enums::*, listener::*, stream::*};
+pub use crate::handle_conversion::FromHandleError;
+pub use {enums::*, list::*, listener::*, stream::*};
 
 #[cfg(feature = "tokio")]
 #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
-use super::winprelude::*;
+use super::{winprelude::*, FileHandle};
+use crate::drop_policy::DropPolicy;
 use std::{
     ffi::{OsStr, OsString},
     io, iter,
     os::windows::ffi::OsStrExt,
     ptr,
+    sync::{mpsc, Mutex, PoisonError},
+    thread,
 };
-use winapi::um::namedpipeapi::SetNamedPipeHandleState;
+use winapi::um::namedpipeapi::{DisconnectNamedPipe, SetNamedPipeHandleState};
 
 fn pathcvt<'a>(pipe_name: &'a OsStr, hostname: Option<&'a OsStr>) -> (impl Iterator<Item = &'a OsStr>, usize) {
     use iter::once as i;
@@ -68,7 +73,55 @@ fn encode_to_utf16(s: &OsStr) -> Vec<u16> {
     path.push(0);
     path
 }
-unsafe fn set_nonblocking_for_stream(handle: HANDLE, read_mode: Option<PipeMode>, nonblocking: bool) -> io::Result<()> {
+/// Carries out the drop policy of a server-side stream whose last handle to the connection is being dropped. Errors
+/// are ignored, since there is nobody to report them to.
+pub(crate) fn drop_server_stream(handle: BorrowedHandle<'_>, policy: DropPolicy) {
+    let disconnect = |handle: HANDLE| unsafe { DisconnectNamedPipe(handle) };
+    match policy {
+        DropPolicy::Detach => {}
+        DropPolicy::Disconnect => {
+            disconnect(handle.as_raw_handle());
+        }
+        DropPolicy::FlushThenDisconnect => {
+            // The flush waits for the client to read everything, so it is handed off to the flusher thread, with a
+            // duplicate of the handle keeping the pipe instance alive in the meantime.
+            let queued = handle
+                .try_clone_to_owned()
+                .map(FileHandle::from)
+                .map_err(drop)
+                .and_then(queue_flush);
+            if queued.is_err() {
+                disconnect(handle.as_raw_handle());
+            }
+        }
+    }
+}
+/// Hands a handle over to the thread which flushes and disconnects the streams dropped under
+/// [`FlushThenDisconnect`](DropPolicy::FlushThenDisconnect), starting it on first use. One thread serves the whole
+/// process, so the flushes are carried out one after another.
+fn queue_flush(handle: FileHandle) -> Result<(), ()> {
+    static FLUSHER: Mutex<Option<mpsc::Sender<FileHandle>>> = Mutex::new(None);
+    let mut flusher = FLUSHER.lock().unwrap_or_else(PoisonError::into_inner);
+    if flusher.is_none() {
+        let (sender, receiver) = mpsc::channel::<FileHandle>();
+        thread::Builder::new()
+            .name("interprocess-pipe-flusher".to_owned())
+            .spawn(move || {
+                for handle in receiver {
+                    let _ = handle.flush();
+                    unsafe { DisconnectNamedPipe(handle.as_raw_handle()) };
+                }
+            })
+            .map_err(drop)?;
+        *flusher = Some(sender);
+    }
+    flusher.as_ref().unwrap().send(handle).map_err(drop)
+}
+pub(crate) unsafe fn set_nonblocking_for_stream(
+    handle: HANDLE,
+    read_mode: Option<PipeMode>,
+    nonblocking: bool,
+) -> io::Result<()> {
     let read_mode: u32 = read_mode.map_or(0, PipeMode::to_readmode);
     // Bitcast the boolean without additional transformations since
     // the flag is in the first bit.
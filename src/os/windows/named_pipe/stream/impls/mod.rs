@@ -3,33 +3,43 @@
 mod split_owned;
 pub(crate) use split_owned::UNWRAP_FAIL_MSG;
 
-use super::{super::set_nonblocking_for_stream, *};
+use super::{
+    super::{drop_server_stream, set_nonblocking_for_stream},
+    *,
+};
 use crate::{
+    drop_policy::DropPolicy,
+    error::{self, Error, ErrorKind},
+    handle_conversion::FromHandleError,
     os::windows::{
+        is_eof_like,
         named_pipe::{convert_and_encode_path, PipeMode},
         weaken_buf_init,
         winprelude::*,
         FileHandle,
     },
-    reliable_recv_msg::{RecvResult, ReliableRecvMsg, TryRecvResult},
+    reliable_recv_msg::{RecvBufPool, RecvResult, ReliableRecvMsg, TryRecvResult},
 };
 use std::{
-    ffi::OsStr,
+    ffi::{OsStr, OsString},
     fmt::{self, Debug, DebugStruct, Formatter},
     io::{self, prelude::*},
     marker::PhantomData,
     mem::{ManuallyDrop, MaybeUninit},
     os::windows::prelude::*,
     ptr, slice,
+    sync::{
+        atomic::{AtomicUsize, Ordering::*},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 use winapi::{
     shared::winerror::ERROR_MORE_DATA,
-    um::{
-        namedpipeapi::DisconnectNamedPipe,
-        winbase::{
-            GetNamedPipeClientProcessId, GetNamedPipeClientSessionId, GetNamedPipeServerProcessId,
-            GetNamedPipeServerSessionId,
-        },
+    um::winbase::{
+        GetNamedPipeClientProcessId, GetNamedPipeClientSessionId, GetNamedPipeServerProcessId,
+        GetNamedPipeServerSessionId,
     },
 };
 
@@ -44,65 +54,79 @@ impl RawPipeStream {
     fn connect(pipename: &OsStr, hostname: Option<&OsStr>, read: bool, write: bool) -> io::Result<Self> {
         let path = convert_and_encode_path(pipename, hostname);
         let handle = _connect(&path, read, write, WaitTimeout::DEFAULT)?;
+        Ok(Self::new(handle, false))
+    }
+    pub(crate) fn new(handle: FileHandle, is_server: bool) -> Self {
+        Self {
+            handle,
+            is_server,
+            handle_count: Arc::new(AtomicUsize::new(1)),
+            drop_policy: Arc::default(),
+        }
+    }
+    fn try_clone(&self) -> io::Result<Self> {
+        let handle = self.handle.try_clone()?;
+        self.handle_count.fetch_add(1, Relaxed);
         Ok(Self {
             handle,
-            is_server: false,
+            is_server: self.is_server,
+            handle_count: Arc::clone(&self.handle_count),
+            drop_policy: Arc::clone(&self.drop_policy),
         })
     }
 
     fn try_recv_msg(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<TryRecvResult> {
-        let mut size = 0;
-        let mut fit = false;
-        while size == 0 {
-            size = peek_msg_len(self.handle.0)?;
-            fit = buf.len() >= size;
-            if fit {
-                match self.handle.read(&mut buf[0..size]) {
-                    // The ERROR_MORE_DATA here can only be hit if we're spinning in the loop and using the `.read()`
-                    // to block until a message arrives, so that we could figure out for real if it fits or not.
-                    // It doesn't mean that the message gets torn, as it normally does if the buffer given to the
-                    // ReadFile call is non-zero in size.
-                    Err(e) if e.raw_os_error() == Some(ERROR_MORE_DATA as _) => continue,
-                    Err(e) => return Err(e),
-                    Ok(nsz) => size = nsz,
+        let mut size = peek_msg_len(self.handle.as_raw_handle())?;
+        if size == 0 {
+            // Either no message has arrived yet or the one at the front of the queue is empty. A zero-sized read
+            // blocks until there is a message and tells the two cases apart without tearing it: an empty message
+            // gets received in full, while a non-empty one makes the read fail with ERROR_MORE_DATA and stays in
+            // the pipe for us to peek its size again.
+            match self.handle.read(&mut []) {
+                Ok(..) => return Ok(TryRecvResult { size: 0, fit: true }),
+                Err(e) if e.raw_os_error() == Some(ERROR_MORE_DATA as _) => {
+                    size = peek_msg_len(self.handle.as_raw_handle())?;
                 }
-            } else {
-                break;
+                Err(e) => return Err(e),
             }
         }
+        let fit = buf.len() >= size;
+        if fit {
+            size = self.handle.read(&mut buf[..size])?;
+        }
         Ok(TryRecvResult { size, fit })
     }
-    fn recv_msg(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<RecvResult> {
-        let TryRecvResult { mut size, fit } = self.try_recv_msg(buf)?;
-        if fit {
-            Ok(RecvResult::Fit(size))
-        } else {
-            let mut buf = Vec::with_capacity(size);
-            debug_assert!(buf.capacity() >= size);
-
-            size = self.handle.read(vec_as_uninit(&mut buf))?;
-            unsafe {
-                // SAFETY: Win32 guarantees that at least this much is initialized.
-                buf.set_len(size)
-            };
-            Ok(RecvResult::Alloc(buf))
+    fn recv_msg(&self, buf: &mut [MaybeUninit<u8>], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        // Unlike with try_recv_msg(), the message doesn't have to stay in the pipe if it doesn't fit, so it's read
+        // right away, which only takes one system call if it does fit. If it doesn't, ReadFile fills the buffer with
+        // the beginning of the message and fails with ERROR_MORE_DATA, leaving the rest of it in the pipe.
+        let e = match self.handle.read(buf) {
+            Ok(size) => return Ok(RecvResult::Fit(size)),
+            Err(e) => e,
+        };
+        if e.raw_os_error() != Some(ERROR_MORE_DATA as _) {
+            return Err(e);
         }
+        let rem = peek_msg_len(self.handle.as_raw_handle())?;
+        let mut new_buf = pool.take(buf.len() + rem);
+        new_buf.extend_from_slice(unsafe {
+            // SAFETY: ERROR_MORE_DATA means that the whole buffer has been filled.
+            slice::from_raw_parts(buf.as_ptr().cast::<u8>(), buf.len())
+        });
+        let size = self.handle.read(&mut vec_as_uninit(&mut new_buf)[buf.len()..])?;
+        unsafe {
+            // SAFETY: Win32 guarantees that at least this much is initialized.
+            new_buf.set_len(buf.len() + size)
+        };
+        Ok(RecvResult::Alloc(new_buf))
     }
 
     fn set_nonblocking(&self, readmode: Option<PipeMode>, nonblocking: bool) -> io::Result<()> {
-        unsafe { set_nonblocking_for_stream(self.handle.0, readmode, nonblocking) }
+        unsafe { set_nonblocking_for_stream(self.handle.as_raw_handle(), readmode, nonblocking) }
     }
-    unsafe fn try_from_raw_handle(handle: HANDLE) -> Result<Self, FromRawHandleError> {
-        let is_server = is_server_from_sys(handle).map_err(|e| (FromRawHandleErrorKind::IsServerCheckFailed, e))?;
-        Ok(Self {
-            handle: FileHandle(handle),
-            is_server,
-        })
-    }
-
-    fn disconnect(&self) -> io::Result<()> {
-        let success = unsafe { DisconnectNamedPipe(self.as_raw_handle()) != 0 };
-        ok_or_ret_errno!(success => ())
+    unsafe fn try_from_raw_handle(handle: HANDLE) -> Result<Self, Error> {
+        let is_server = is_server_from_sys(handle)?;
+        Ok(Self::new(unsafe { FileHandle::from_raw_handle(handle) }, is_server))
     }
 
     fn fill_fields<'a, 'b, 'c>(
@@ -122,25 +146,34 @@ impl RawPipeStream {
 }
 impl Drop for RawPipeStream {
     fn drop(&mut self) {
-        if self.is_server {
-            self.disconnect().expect("failed to disconnect server from client");
+        // Only the last clone gets to disconnect, since the rest of them are still using the connection.
+        let last = self.handle_count.fetch_sub(1, AcqRel) == 1;
+        if self.is_server && last {
+            drop_server_stream(self.handle.as_handle(), self.drop_policy.get());
         }
     }
 }
 impl AsRawHandle for RawPipeStream {
     #[inline(always)]
     fn as_raw_handle(&self) -> HANDLE {
-        self.handle.0
+        self.handle.as_raw_handle()
     }
 }
 impl IntoRawHandle for RawPipeStream {
     #[inline]
     fn into_raw_handle(self) -> HANDLE {
         let slf = ManuallyDrop::new(self);
-        let handle = unsafe {
+        let (handle, handle_count, drop_policy) = unsafe {
             // SAFETY: `slf` is never dropped
-            ptr::read(&slf.handle)
+            (
+                ptr::read(&slf.handle),
+                ptr::read(&slf.handle_count),
+                ptr::read(&slf.drop_policy),
+            )
         };
+        drop(drop_policy);
+        // The handle lives on, but not as a stream that's going to disconnect anything.
+        handle_count.fetch_sub(1, AcqRel);
         handle.into_raw_handle()
     }
 }
@@ -149,7 +182,12 @@ impl<Sm: PipeModeTag> PipeStream<pipe_mode::Messages, Sm> {
     /// Same as [`.recv()`](Self::recv), but accepts an uninitialized buffer.
     #[inline]
     pub fn recv_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<RecvResult> {
-        self.raw.recv_msg(buf)
+        self.raw.recv_msg(buf, &mut RecvBufPool::new())
+    }
+    /// Same as [`.recv_pooled()`](ReliableRecvMsg::recv_pooled), but accepts an uninitialized buffer.
+    #[inline]
+    pub fn recv_pooled_to_uninit(&self, buf: &mut [MaybeUninit<u8>], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        self.raw.recv_msg(buf, pool)
     }
     /// Same as [`.try_recv()`](Self::try_recv), but accepts an uninitialized buffer.
     #[inline]
@@ -163,6 +201,12 @@ impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Messages> {
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
         self.raw.handle.write(buf)
     }
+    /// Sends a message into the pipe, failing with [`MessageTooLarge`](ErrorKind::MessageTooLarge) if only part of it
+    /// could be sent instead of returning how many bytes were sent.
+    pub fn send_all(&self, buf: &[u8]) -> Result<(), Error> {
+        let sent = self.send(buf)?;
+        error::check_msg_sent(sent, buf.len())
+    }
 }
 impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
     /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer.
@@ -170,6 +214,70 @@ impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
     pub fn read_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
         self.raw.handle.read(buf)
     }
+    /// Copies data from the pipe into the given buffer without removing it, returning how many bytes were copied.
+    ///
+    /// Unlike reading, this never waits for data to arrive, regardless of the nonblocking mode: if the pipe is empty, a
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned. Zero is returned if the other side has disconnected.
+    ///
+    /// # System calls
+    /// - `PeekNamedPipe`
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match peek_bytes(self.raw.handle.as_raw_handle(), buf) {
+            Ok((_, 0)) => Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "there is no data available in the pipe",
+            )),
+            Ok((copied, _)) => Ok(copied),
+            Err(e) if is_eof_like(&e) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+    /// Returns how many bytes can be read from the pipe without blocking, or zero if the other side has disconnected.
+    ///
+    /// # System calls
+    /// - `PeekNamedPipe`
+    pub fn bytes_available(&self) -> io::Result<usize> {
+        match peek_bytes(self.raw.handle.as_raw_handle(), &mut []) {
+            Ok((_, available)) => Ok(available),
+            Err(e) if is_eof_like(&e) => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+}
+impl<Rm: PipeModeTag + PmtNotNone, Sm: PipeModeTag> PipeStream<Rm, Sm> {
+    /// Waits until reading from the pipe would not block, which is the case when data or a message is available or the
+    /// other side has disconnected, returning `false` if the timeout expires first. No timeout means waiting
+    /// indefinitely.
+    ///
+    /// Since the pipe is not created for overlapped I/O, the waiting is done by polling it at short intervals.
+    ///
+    /// # System calls
+    /// - `PeekNamedPipe`
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        const MAX_INTERVAL: Duration = Duration::from_millis(50);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut interval = Duration::from_millis(1);
+        loop {
+            match peek_bytes(self.raw.handle.as_raw_handle(), &mut []) {
+                Ok((_, 0)) => {}
+                Ok(..) => return Ok(true),
+                Err(e) if is_eof_like(&e) => return Ok(true),
+                Err(e) => return Err(e),
+            }
+            let sleep = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining == Duration::ZERO {
+                        return Ok(false);
+                    }
+                    interval.min(remaining)
+                }
+                None => interval,
+            };
+            thread::sleep(sleep);
+            interval = (interval * 2).min(MAX_INTERVAL);
+        }
+    }
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     /// Connects to the specified named pipe (the `\\.\pipe\` prefix is added automatically), blocking until a server instance is dispatched.
@@ -202,25 +310,68 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
             },
         )
     }
+    /// Creates a new handle to the same pipe stream, which can be used from another thread independently of the original one, much like [`TcpStream::try_clone()`](std::net::TcpStream::try_clone).
+    ///
+    /// On the server side, the client is disconnected according to the [drop policy](Self::set_drop_policy) once all clones are dropped rather than any one of them. The clones share the nonblocking mode, since it belongs to the pipe rather than the handle.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self::new(self.raw.try_clone()?))
+    }
     /// Retrieves the process identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientProcessId) }
     }
     /// Retrieves the session identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientSessionId) }
     }
     /// Retrieves the process identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerProcessId) }
     }
     /// Retrieves the session identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerSessionId) }
+    }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.raw.handle.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    ///
+    /// The SID is looked up from the token of the [client process](Self::client_process_id), which thus has to still
+    /// be running and has to be accessible to the current user. For clients on other computers, the process is the one
+    /// which relays the connection on the local computer rather than the client itself.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    ///
+    /// Subject to the same restrictions as [`.client_user_sid()`](Self::client_user_sid).
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
+    }
+    /// Sets what the stream does with the connection when it is dropped, overriding the
+    /// [process-wide default](crate::drop_policy::set_default_drop_policy). Only has an effect on the server side.
+    ///
+    /// The policy is shared with [clones](Self::try_clone) and split halves, and carried out when the last of them is
+    /// dropped.
+    #[inline]
+    pub fn set_drop_policy(&self, policy: DropPolicy) {
+        self.raw.drop_policy.set(policy)
+    }
+    /// Returns what the stream does with the connection when it is dropped, as set by
+    /// [`.set_drop_policy()`](Self::set_drop_policy) or the process-wide default.
+    #[inline]
+    pub fn drop_policy(&self) -> DropPolicy {
+        self.raw.drop_policy.get()
     }
     /// Returns `true` if the stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
@@ -246,11 +397,11 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     }
     /// Attempts to wrap the given handle into the high-level pipe stream type. If the underlying pipe type is wrong or trying to figure out whether it's wrong or not caused a system call error, the corresponding error condition is returned.
     ///
-    /// For more on why this can fail, see [`FromRawHandleError`]. Most notably, server-side write-only pipes will cause "access denied" errors because they lack permissions to check whether it's a server-side pipe and whether it has message boundaries.
+    /// Wrapping a byte pipe into a stream which receives messages fails with [`InvalidHandle`](ErrorKind::InvalidHandle), and failed checks of the pipe type are reported with the kind of the system call error. Most notably, server-side write-only pipes will cause "access denied" errors because they lack permissions to check whether it's a server-side pipe and whether it has message boundaries.
     ///
     /// # Safety
     /// See equivalent safety notes on [`FromRawHandle`].
-    pub unsafe fn from_raw_handle(handle: HANDLE) -> Result<Self, FromRawHandleError> {
+    pub unsafe fn from_raw_handle(handle: HANDLE) -> Result<Self, Error> {
         let raw = unsafe {
             // SAFETY: safety contract is propagated.
             RawPipeStream::try_from_raw_handle(handle)?
@@ -258,12 +409,11 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
         // If the wrapper type tries to read incoming data as messages, that might break if
         // the underlying pipe has no message boundaries. Let's check for that.
         if Rm::MODE == Some(PipeMode::Messages) {
-            let msg_bnd = has_msg_boundaries_from_sys(raw.handle.0)
-                .map_err(|e| (FromRawHandleErrorKind::MessageBoundariesCheckFailed, e))?;
+            let msg_bnd = has_msg_boundaries_from_sys(raw.handle.as_raw_handle())?;
             if !msg_bnd {
-                return Err((
-                    FromRawHandleErrorKind::NoMessageBoundaries,
-                    io::Error::from(io::ErrorKind::InvalidInput),
+                return Err(Error::new(
+                    ErrorKind::InvalidHandle,
+                    "the pipe does not preserve message boundaries",
                 ));
             }
         }
@@ -323,6 +473,9 @@ impl<Sm: PipeModeTag> ReliableRecvMsg for &PipeStream<pipe_mode::Messages, Sm> {
     fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
         self.recv_to_uninit(weaken_buf_init(buf))
     }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        self.recv_pooled_to_uninit(weaken_buf_init(buf), pool)
+    }
     fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
         self.try_recv_to_uninit(weaken_buf_init(buf))
     }
@@ -331,6 +484,9 @@ impl<Sm: PipeModeTag> ReliableRecvMsg for PipeStream<pipe_mode::Messages, Sm> {
     fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
         (self as &PipeStream<_, _>).recv(buf)
     }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (self as &PipeStream<_, _>).recv_pooled(buf, pool)
+    }
     fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
         (self as &PipeStream<_, _>).try_recv(buf)
     }
@@ -341,10 +497,51 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeStream<Rm, Sm> {
         self.raw.fill_fields(&mut dbst, Rm::MODE, Sm::MODE).finish()
     }
 }
+impl<Rm: PipeModeTag, Sm: PipeModeTag> AsHandle for PipeStream<Rm, Sm> {
+    #[inline(always)]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.raw.handle.as_handle()
+    }
+}
+impl<Rm: PipeModeTag, Sm: PipeModeTag> From<PipeStream<Rm, Sm>> for OwnedHandle {
+    #[inline]
+    fn from(x: PipeStream<Rm, Sm>) -> Self {
+        unsafe {
+            // SAFETY: ownership of the handle is transferred from the stream
+            OwnedHandle::from_raw_handle(x.into_raw_handle())
+        }
+    }
+}
+/// Fails if the handle is not a named pipe, or if the stream type has message semantics but the pipe doesn't preserve
+/// message boundaries. Unlike with [`from_raw_handle()`](PipeStream::from_raw_handle), the handle is handed back in the
+/// error instead of being closed.
+impl<Rm: PipeModeTag, Sm: PipeModeTag> TryFrom<OwnedHandle> for PipeStream<Rm, Sm> {
+    type Error = FromHandleError;
+    fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+        let is_server = match is_server_from_sys(handle.as_raw_handle()) {
+            Ok(is_server) => is_server,
+            Err(e) => return Err(FromHandleError::new(handle, e)),
+        };
+        if Rm::MODE == Some(PipeMode::Messages) {
+            match has_msg_boundaries_from_sys(handle.as_raw_handle()) {
+                Ok(true) => {}
+                Ok(false) => {
+                    let e = Error::new(
+                        ErrorKind::InvalidHandle,
+                        "the pipe does not preserve message boundaries",
+                    );
+                    return Err(FromHandleError::new(handle, e.into()));
+                }
+                Err(e) => return Err(FromHandleError::new(handle, e)),
+            }
+        }
+        Ok(Self::new(RawPipeStream::new(handle.into(), is_server)))
+    }
+}
 impl<Rm: PipeModeTag, Sm: PipeModeTag> AsRawHandle for PipeStream<Rm, Sm> {
     #[inline(always)]
     fn as_raw_handle(&self) -> HANDLE {
-        self.raw.handle.0
+        self.raw.handle.as_raw_handle()
     }
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> IntoRawHandle for PipeStream<Rm, Sm> {
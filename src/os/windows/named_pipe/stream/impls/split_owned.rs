@@ -30,22 +30,38 @@ impl<Rm: PipeModeTag> RecvHalf<Rm> {
     /// Retrieves the process identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientProcessId) }
     }
     /// Retrieves the session identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientSessionId) }
     }
     /// Retrieves the process identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerProcessId) }
     }
     /// Retrieves the session identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerSessionId) }
+    }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.raw.handle.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
     }
     /// Returns `true` if the underlying stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
@@ -73,7 +89,12 @@ impl RecvHalf<pipe_mode::Messages> {
     /// Same as [`.recv()`](ReliableRecvMsg::recv), but accepts an uninitialized buffer.
     #[inline]
     pub fn recv_to_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> io::Result<RecvResult> {
-        self.raw.recv_msg(buf)
+        self.raw.recv_msg(buf, &mut RecvBufPool::new())
+    }
+    /// Same as [`.recv_pooled()`](ReliableRecvMsg::recv_pooled), but accepts an uninitialized buffer.
+    #[inline]
+    pub fn recv_pooled_to_uninit(&self, buf: &mut [MaybeUninit<u8>], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        self.raw.recv_msg(buf, pool)
     }
     /// Same as [`.try_recv()`](ReliableRecvMsg::try_recv), but accepts an uninitialized buffer.
     #[inline]
@@ -104,6 +125,9 @@ impl ReliableRecvMsg for &RecvHalf<pipe_mode::Messages> {
     fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
         self.recv_to_uninit(weaken_buf_init(buf))
     }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        self.recv_pooled_to_uninit(weaken_buf_init(buf), pool)
+    }
     fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
         self.try_recv_to_uninit(weaken_buf_init(buf))
     }
@@ -112,6 +136,9 @@ impl ReliableRecvMsg for RecvHalf<pipe_mode::Messages> {
     fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
         (self as &RecvHalf<_>).recv(buf)
     }
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
+        (self as &RecvHalf<_>).recv_pooled(buf, pool)
+    }
     fn try_recv(&mut self, buf: &mut [u8]) -> io::Result<TryRecvResult> {
         (self as &RecvHalf<_>).try_recv(buf)
     }
@@ -138,22 +165,38 @@ impl<Sm: PipeModeTag> SendHalf<Sm> {
     /// Retrieves the process identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientProcessId) }
     }
     /// Retrieves the session identifier of the client side of the named pipe connection.
     #[inline]
     pub fn client_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeClientSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeClientSessionId) }
     }
     /// Retrieves the process identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_process_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerProcessId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerProcessId) }
     }
     /// Retrieves the session identifier of the server side of the named pipe connection.
     #[inline]
     pub fn server_session_id(&self) -> io::Result<u32> {
-        unsafe { hget(self.raw.handle.0, GetNamedPipeServerSessionId) }
+        unsafe { hget(self.raw.handle.as_raw_handle(), GetNamedPipeServerSessionId) }
+    }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.raw.handle.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
     }
     /// Returns `true` if the underlying stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
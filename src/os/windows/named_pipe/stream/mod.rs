@@ -5,13 +5,12 @@ mod impls;
 mod wrapper_fns;
 pub(crate) use {impls::*, wrapper_fns::*};
 
-use crate::os::windows::FileHandle;
+use crate::{drop_policy::DropPolicyCell, os::windows::FileHandle};
 use std::{
     error::Error,
     fmt::{self, Debug, Display, Formatter},
-    io,
     marker::PhantomData,
-    sync::Arc,
+    sync::{atomic::AtomicUsize, Arc},
 };
 
 pub(crate) static REUNITE_ERROR_MSG: &str = "the receive and self halves belong to different pipe stream objects";
@@ -124,21 +123,13 @@ pub struct SendHalf<Sm: PipeModeTag> {
 pub(crate) struct RawPipeStream {
     pub(crate) handle: FileHandle,
     pub(crate) is_server: bool,
+    /// How many streams share the connection because of `try_clone()`. Only the last one to be dropped disconnects the
+    /// server from the client.
+    pub(crate) handle_count: Arc<AtomicUsize>,
+    /// What the last stream to be dropped does with the connection, shared between clones.
+    pub(crate) drop_policy: Arc<DropPolicyCell>,
 }
 
-/// Additional contextual information for conversions from a raw handle to a named pipe stream.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum FromRawHandleErrorKind {
-    /// It wasn't possible to determine whether the pipe handle corresponds to a pipe server or a pipe client.
-    IsServerCheckFailed,
-    /// The type being converted into has message semantics, but it wasn't possible to determine whether message boundaries are preserved in the pipe.
-    MessageBoundariesCheckFailed,
-    /// The type being converted into has message semantics, but message boundaries are not preserved in the pipe.
-    NoMessageBoundaries,
-}
-/// Error type for `from_raw_handle()` constructors.
-pub type FromRawHandleError = (FromRawHandleErrorKind, io::Error);
-
 /// Error type for `.reunite()` on split receive and send halves.
 ///
 /// The error indicates that the halves belong to different streams and allows to recover both of them.
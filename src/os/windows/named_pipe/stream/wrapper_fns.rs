@@ -1,7 +1,8 @@
+pub(crate) use crate::os::windows::local_socket::user_sid_of_process;
 use crate::os::windows::{winprelude::*, FileHandle};
-use std::{io, os::windows::prelude::*, ptr};
+use std::{ffi::OsString, io, mem::size_of, os::windows::prelude::*, ptr, time::Duration};
 use winapi::{
-    shared::winerror::ERROR_PIPE_BUSY,
+    shared::winerror::{ERROR_PIPE_BUSY, ERROR_PIPE_LOCAL},
     um::{
         fileapi::{CreateFileW, OPEN_EXISTING},
         handleapi::INVALID_HANDLE_VALUE,
@@ -10,6 +11,12 @@ use winapi::{
     },
 };
 
+// Not exposed by winapi.
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetNamedPipeClientComputerNameW(pipe: HANDLE, client_computer_name: *mut u16, len: DWORD) -> BOOL;
+}
+
 /// Helper for several functions that take a handle and a DWORD out-pointer.
 pub(crate) unsafe fn hget(
     handle: HANDLE,
@@ -20,6 +27,24 @@ pub(crate) unsafe fn hget(
     ok_or_ret_errno!(ok => x)
 }
 
+/// Retrieves the name of the computer the client of a server-side pipe is on, or `None` if it's the local one.
+pub(crate) fn client_computer_name(handle: HANDLE) -> io::Result<Option<OsString>> {
+    // DNS host names are at most 255 characters long, plus the nul terminator.
+    let mut buf = [0_u16; 256];
+    let success = unsafe {
+        GetNamedPipeClientComputerNameW(handle, buf.as_mut_ptr(), (buf.len() * size_of::<u16>()) as u32) != 0
+    };
+    if !success {
+        let e = io::Error::last_os_error();
+        return match e.raw_os_error() {
+            Some(code) if code == ERROR_PIPE_LOCAL as i32 => Ok(None),
+            _ => Err(e),
+        };
+    }
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    Ok(Some(OsString::from_wide(&buf[..len])))
+}
+
 pub(crate) fn get_flags(handle: HANDLE) -> io::Result<DWORD> {
     let mut flags: u32 = 0;
     let success = unsafe {
@@ -61,15 +86,31 @@ pub(crate) fn peek_msg_len(handle: HANDLE) -> io::Result<usize> {
     };
     ok_or_ret_errno!(ok => len as usize)
 }
+/// Copies as much of the data in the pipe as fits into the buffer without removing it, returning how many bytes were
+/// copied and how many are available in total.
+pub(crate) fn peek_bytes(handle: HANDLE, buf: &mut [u8]) -> io::Result<(usize, usize)> {
+    let (mut copied, mut available): (DWORD, DWORD) = (0, 0);
+    let ok = unsafe {
+        PeekNamedPipe(
+            handle,
+            buf.as_mut_ptr().cast(),
+            buf.len().min(DWORD::max_value() as usize) as DWORD,
+            &mut copied as *mut _,
+            &mut available as *mut _,
+            ptr::null_mut(),
+        ) != 0
+    };
+    ok_or_ret_errno!(ok => (copied as usize, available as usize))
+}
 
 pub(crate) fn _connect(path: &[u16], read: bool, write: bool, timeout: WaitTimeout) -> io::Result<FileHandle> {
     loop {
         match connect_without_waiting(path, read, write) {
             Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => {
-                block_for_server(path, timeout)?;
+                block_for_server(path, timeout).map_err(crate::error::connect_error)?;
                 continue;
             }
-            els => return els,
+            els => return els.map_err(crate::error::connect_error),
         }
     }
 }
@@ -113,6 +154,12 @@ pub(crate) struct WaitTimeout(u32);
 impl WaitTimeout {
     pub(crate) const DEFAULT: Self = Self(0x00000000);
     //pub(crate) const FOREVER: Self = Self(0xffffffff);
+    /// Converts a duration to a timeout in milliseconds, rounding up so as to not turn short timeouts into either of the
+    /// two special values.
+    pub(crate) fn from_duration(d: Duration) -> Self {
+        let ms = d.as_nanos().saturating_add(999_999) / 1_000_000;
+        Self(ms.clamp(1, 0xfffffffe) as u32)
+    }
 }
 impl From<WaitTimeout> for u32 {
     fn from(x: WaitTimeout) -> Self {
@@ -1,4 +1,5 @@
 use crate::{
+    drop_policy::DropPolicyCell,
     os::windows::named_pipe::{
         enums::{PipeMode, PipeStreamRole},
         pipe_mode,
@@ -106,6 +107,11 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
     const STREAM_ROLE: PipeStreamRole = PipeStreamRole::get_for_rm_sm::<Rm, Sm>();
 
     /// Asynchronously waits until a client connects to the named pipe, creating a `Stream` to communicate with the pipe.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: the pending instance is only replaced after a client has connected to it, which
+    /// happens without any further suspension points, so dropping the future keeps the instance – and any client which
+    /// connects to it in the meantime – for the next call.
     pub async fn accept(&self) -> io::Result<PipeStream<Rm, Sm>> {
         let instance_to_hand_out = {
             let mut stored_instance = self.stored_instance.lock().await;
@@ -114,7 +120,7 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeListener<Rm, Sm> {
             replace(&mut *stored_instance, new_instance)
         };
 
-        let raw = RawPipeStream::Server(instance_to_hand_out);
+        let raw = RawPipeStream::Server(instance_to_hand_out, DropPolicyCell::default());
         Ok(PipeStream::new(raw))
     }
 
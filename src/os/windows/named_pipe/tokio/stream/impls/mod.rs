@@ -3,13 +3,16 @@
 mod split_owned;
 
 use crate::{
+    drop_policy::{default_drop_policy, DropPolicy, DropPolicyCell},
+    error::{self, Error, ErrorKind},
+    msg_adapters::AsyncSendMsg,
     os::windows::{
         is_eof_like,
         named_pipe::{
-            convert_path, encode_to_utf16,
+            convert_path, drop_server_stream, encode_to_utf16,
             stream::{
-                block_for_server, has_msg_boundaries_from_sys, hget, is_server_from_sys, peek_msg_len, WaitTimeout,
-                UNWRAP_FAIL_MSG,
+                block_for_server, client_computer_name, has_msg_boundaries_from_sys, hget, is_server_from_sys,
+                peek_msg_len, user_sid_of_process, WaitTimeout, UNWRAP_FAIL_MSG,
             },
             tokio::stream::*,
             PipeMode, PmtNotNone,
@@ -22,13 +25,14 @@ use crate::{
 use futures_core::ready;
 use futures_io::{AsyncRead, AsyncWrite};
 use std::{
-    ffi::OsStr,
+    ffi::{OsStr, OsString},
     fmt::{self, Debug, DebugStruct, Formatter},
     future::Future,
     mem::MaybeUninit,
     ops::Deref,
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::{
     io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
@@ -47,7 +51,7 @@ macro_rules! same_clsrv {
     ($nm:ident in $var:expr => $e:expr) => {
         match $var {
             RawPipeStream::Client($nm) => $e,
-            RawPipeStream::Server($nm) => $e,
+            RawPipeStream::Server($nm, _) => $e,
         }
     };
 }
@@ -68,15 +72,21 @@ unsafe impl Sync for AssertHandleSyncSend {}
 unsafe impl Send for AssertHandleSyncSend {}
 
 impl RawPipeStream {
-    async fn wait_for_server(path: Vec<u16>) -> io::Result<Vec<u16>> {
+    async fn wait_for_server(path: Vec<u16>, timeout: WaitTimeout) -> io::Result<Vec<u16>> {
         tokio::task::spawn_blocking(move || {
-            block_for_server(&path, WaitTimeout::DEFAULT)?;
+            block_for_server(&path, timeout)?;
             Ok(path)
         })
         .await
         .expect("waiting for server panicked")
     }
-    async fn connect(pipename: &OsStr, hostname: Option<&OsStr>, read: bool, write: bool) -> io::Result<Self> {
+    async fn connect(
+        pipename: &OsStr,
+        hostname: Option<&OsStr>,
+        read: bool,
+        write: bool,
+        deadline: Option<Instant>,
+    ) -> io::Result<Self> {
         let path = convert_path(pipename, hostname);
         let mut path16 = None::<Vec<u16>>;
         let client = loop {
@@ -86,7 +96,22 @@ impl RawPipeStream {
                         Some(p) => p,
                         None => encode_to_utf16(&path),
                     };
-                    let p16_take = Self::wait_for_server(p16_take).await?;
+                    // The wait happens on a blocking thread which cannot be cancelled, so the deadline is enforced by
+                    // the wait itself rather than by the future that's polling it.
+                    let timeout = match deadline {
+                        Some(deadline) => {
+                            let remaining = deadline.saturating_duration_since(Instant::now());
+                            if remaining == Duration::ZERO {
+                                return Err(io::Error::new(
+                                    io::ErrorKind::TimedOut,
+                                    "no server instance became available before the timeout expired",
+                                ));
+                            }
+                            WaitTimeout::from_duration(remaining)
+                        }
+                        None => WaitTimeout::DEFAULT,
+                    };
+                    let p16_take = Self::wait_for_server(p16_take, timeout).await?;
                     path16 = Some(p16_take);
                 }
                 not_waiting => break not_waiting?,
@@ -94,15 +119,15 @@ impl RawPipeStream {
         };
         Ok(Self::Client(client))
     }
-    unsafe fn try_from_raw_handle(handle: HANDLE) -> Result<Self, FromRawHandleError> {
-        let is_server = is_server_from_sys(handle).map_err(|e| (FromRawHandleErrorKind::IsServerCheckFailed, e))?;
+    unsafe fn try_from_raw_handle(handle: HANDLE) -> Result<Self, Error> {
+        let is_server = is_server_from_sys(handle)?;
 
         unsafe {
             match is_server {
-                true => TokioNPServer::from_raw_handle(handle).map(Self::Server),
+                true => TokioNPServer::from_raw_handle(handle).map(|s| Self::Server(s, DropPolicyCell::default())),
                 false => TokioNPClient::from_raw_handle(handle).map(Self::Client),
             }
-            .map_err(|e| (FromRawHandleErrorKind::TokioError, e))
+            .map_err(Error::from)
         }
     }
 
@@ -147,6 +172,19 @@ impl RawPipeStream {
         Write(self, buf)
     }
 
+    async fn readable(&self) -> io::Result<()> {
+        same_clsrv!(x in self => x.readable().await)
+    }
+    async fn writable(&self) -> io::Result<()> {
+        same_clsrv!(x in self => x.writable().await)
+    }
+    fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        downgrade_read_eof(same_clsrv!(x in self => x.try_read(buf)))
+    }
+    fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        same_clsrv!(x in self => x.try_write(buf))
+    }
+
     fn poll_try_recv_msg(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
         let mut size = 0;
         let mut fit = false;
@@ -175,13 +213,6 @@ impl RawPipeStream {
         Poll::Ready(Ok(TryRecvResult { size, fit }))
     }
 
-    fn disconnect(&self) -> io::Result<()> {
-        match self {
-            Self::Server(s) => s.disconnect(),
-            Self::Client(_) => Ok(()),
-        }
-    }
-
     fn fill_fields<'a, 'b, 'c>(
         &self,
         dbst: &'a mut DebugStruct<'b, 'c>,
@@ -189,7 +220,7 @@ impl RawPipeStream {
         writemode: Option<PipeMode>,
     ) -> &'a mut DebugStruct<'b, 'c> {
         let (tokio_object, is_server) = match self {
-            RawPipeStream::Server(s) => (s as _, true),
+            RawPipeStream::Server(s, _) => (s as _, true),
             RawPipeStream::Client(c) => (c as _, false),
         };
         if let Some(readmode) = readmode {
@@ -203,7 +234,14 @@ impl RawPipeStream {
 }
 impl Drop for RawPipeStream {
     fn drop(&mut self) {
-        self.disconnect().expect("failed to disconnect server from client");
+        if let Self::Server(s, drop_policy) = self {
+            drop_server_stream(s.as_handle(), drop_policy.get());
+        }
+    }
+}
+impl AsHandle for RawPipeStream {
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        same_clsrv!(x in self => x.as_handle())
     }
 }
 impl AsRawHandle for RawPipeStream {
@@ -256,6 +294,17 @@ impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Messages> {
     pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
         self.raw.write(buf).await
     }
+    /// Sends a message into the pipe, failing with [`MessageTooLarge`](ErrorKind::MessageTooLarge) if only part of it
+    /// could be sent instead of returning how many bytes were sent.
+    pub async fn send_all(&self, buf: &[u8]) -> Result<(), Error> {
+        let sent = self.send(buf).await?;
+        error::check_msg_sent(sent, buf.len())
+    }
+    /// Polling counterpart of [`.send()`](Self::send), for use by types that implement sinks on top of pipe streams.
+    #[inline]
+    pub(crate) fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.raw.poll_write(cx, buf)
+    }
 }
 impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
     /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer.
@@ -263,11 +312,49 @@ impl<Sm: PipeModeTag> PipeStream<pipe_mode::Bytes, Sm> {
     pub async fn read_to_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
         self.raw.read_uninit(buf).await
     }
+    /// Waits until the pipe becomes readable, which usually means that [`.try_read()`](Self::try_read) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_read()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn readable(&self) -> io::Result<()> {
+        self.raw.readable().await
+    }
+    /// Reads bytes from the pipe without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if none are available.
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.raw.try_read(buf)
+    }
+}
+impl<Rm: PipeModeTag> PipeStream<Rm, pipe_mode::Bytes> {
+    /// Waits until the pipe becomes writable, which usually means that [`.try_write()`](Self::try_write) can be called without it failing with [`WouldBlock`](io::ErrorKind::WouldBlock).
+    ///
+    /// Readiness may be spurious, in which case the following call to `.try_write()` fails with `WouldBlock` and this method needs to be called again.
+    pub async fn writable(&self) -> io::Result<()> {
+        self.raw.writable().await
+    }
+    /// Writes bytes to the pipe without waiting, failing with [`WouldBlock`](io::ErrorKind::WouldBlock) if there is no buffer space available.
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.raw.try_write(buf)
+    }
 }
 impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     /// Connects to the specified named pipe (the `\\.\pipe\` prefix is added automatically), waiting until a server instance is dispatched.
     pub async fn connect(pipename: impl AsRef<OsStr>) -> io::Result<Self> {
-        let raw = RawPipeStream::connect(pipename.as_ref(), None, Rm::MODE.is_some(), Sm::MODE.is_some()).await?;
+        let raw = RawPipeStream::connect(pipename.as_ref(), None, Rm::MODE.is_some(), Sm::MODE.is_some(), None).await?;
+        Ok(Self::new(raw))
+    }
+    /// Like [`connect`](Self::connect), but gives up with a [`TimedOut`](io::ErrorKind::TimedOut) error if no server
+    /// instance becomes available within the given timeout.
+    ///
+    /// The timeout covers the entire operation, including the waits for a busy server to dispatch a new instance.
+    pub async fn connect_with_timeout(pipename: impl AsRef<OsStr>, timeout: Duration) -> io::Result<Self> {
+        let deadline = Instant::now() + timeout;
+        let raw = RawPipeStream::connect(
+            pipename.as_ref(),
+            None,
+            Rm::MODE.is_some(),
+            Sm::MODE.is_some(),
+            Some(deadline),
+        )
+        .await?;
         Ok(Self::new(raw))
     }
     /// Connects to the specified named pipe at a remote computer (the `\\<hostname>\pipe\` prefix is added automatically), blocking until a server instance is dispatched.
@@ -277,6 +364,7 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
             Some(hostname.as_ref()),
             Rm::MODE.is_some(),
             Sm::MODE.is_some(),
+            None,
         )
         .await?;
         Ok(Self::new(raw))
@@ -317,6 +405,53 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     pub fn server_session_id(&self) -> io::Result<u32> {
         unsafe { hget(self.as_raw_handle(), GetNamedPipeServerSessionId) }
     }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    ///
+    /// The SID is looked up from the token of the [client process](Self::client_process_id), which thus has to still
+    /// be running and has to be accessible to the current user. For clients on other computers, the process is the one
+    /// which relays the connection on the local computer rather than the client itself.
+    ///
+    /// Like the other connection metadata, this is retrieved with system calls which return right away rather than
+    /// waiting on the other side of the connection, and thus doesn't block the runtime for any significant amount of
+    /// time.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    ///
+    /// Subject to the same restrictions as [`.client_user_sid()`](Self::client_user_sid).
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
+    }
+    /// Sets what the stream does with the connection when it is dropped, overriding the
+    /// [process-wide default](crate::drop_policy::set_default_drop_policy). Only has an effect on the server side.
+    ///
+    /// The policy is shared with split halves, and carried out when the last of them is dropped. The flush of
+    /// [`FlushThenDisconnect`](DropPolicy::FlushThenDisconnect) happens on a background thread, so it doesn't require
+    /// a Tokio runtime.
+    #[inline]
+    pub fn set_drop_policy(&self, policy: DropPolicy) {
+        if let RawPipeStream::Server(_, drop_policy) = &self.raw {
+            drop_policy.set(policy);
+        }
+    }
+    /// Returns what the stream does with the connection when it is dropped, as set by
+    /// [`.set_drop_policy()`](Self::set_drop_policy) or the process-wide default.
+    #[inline]
+    pub fn drop_policy(&self) -> DropPolicy {
+        match &self.raw {
+            RawPipeStream::Server(_, drop_policy) => drop_policy.get(),
+            RawPipeStream::Client(..) => default_drop_policy(),
+        }
+    }
     /// Returns `true` if the stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
     pub fn is_server(&self) -> bool {
@@ -329,11 +464,11 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
     }
     /// Attempts to wrap the given handle into the high-level pipe stream type. If the underlying pipe type is wrong or trying to figure out whether it's wrong or not caused a system call error, the corresponding error condition is returned.
     ///
-    /// For more on why this can fail, see [`FromRawHandleError`]. Most notably, server-side write-only pipes will cause "access denied" errors because they lack permissions to check whether it's a server-side pipe and whether it has message boundaries.
+    /// Wrapping a byte pipe into a stream which receives messages fails with [`InvalidHandle`](ErrorKind::InvalidHandle), and failed checks of the pipe type are reported with the kind of the system call error. Most notably, server-side write-only pipes will cause "access denied" errors because they lack permissions to check whether it's a server-side pipe and whether it has message boundaries.
     ///
     /// # Safety
     /// See equivalent safety notes on [`FromRawHandle`].
-    pub unsafe fn from_raw_handle(handle: HANDLE) -> Result<Self, FromRawHandleError> {
+    pub unsafe fn from_raw_handle(handle: HANDLE) -> Result<Self, Error> {
         let raw = unsafe {
             // SAFETY: safety contract is propagated.
             RawPipeStream::try_from_raw_handle(handle)?
@@ -341,12 +476,11 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> PipeStream<Rm, Sm> {
         // If the wrapper type tries to read incoming data as messages, that might break if
         // the underlying pipe has no message boundaries. Let's check for that.
         if Rm::MODE == Some(PipeMode::Messages) {
-            let msg_bnd = has_msg_boundaries_from_sys(raw.as_raw_handle())
-                .map_err(|e| (FromRawHandleErrorKind::MessageBoundariesCheckFailed, e))?;
+            let msg_bnd = has_msg_boundaries_from_sys(raw.as_raw_handle())?;
             if !msg_bnd {
-                return Err((
-                    FromRawHandleErrorKind::NoMessageBoundaries,
-                    io::Error::from(io::ErrorKind::InvalidInput),
+                return Err(Error::new(
+                    ErrorKind::InvalidHandle,
+                    "the pipe does not preserve message boundaries",
                 ));
             }
         }
@@ -470,6 +604,18 @@ impl<Rm: PipeModeTag> TokioAsyncWrite for PipeStream<Rm, pipe_mode::Bytes> {
         <Self as TokioAsyncWrite>::poll_flush(self, cx)
     }
 }
+impl<Rm: PipeModeTag> AsyncSendMsg for &PipeStream<Rm, pipe_mode::Messages> {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_send(cx, buf)
+    }
+}
+impl<Rm: PipeModeTag> AsyncSendMsg for PipeStream<Rm, pipe_mode::Messages> {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_send(cx, buf)
+    }
+}
 impl<Sm: PipeModeTag> AsyncReliableRecvMsg for &PipeStream<pipe_mode::Messages, Sm> {
     #[inline]
     fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
@@ -496,6 +642,12 @@ impl<Rm: PipeModeTag, Sm: PipeModeTag> Debug for PipeStream<Rm, Sm> {
         dbst.finish()
     }
 }
+impl<Rm: PipeModeTag, Sm: PipeModeTag> AsHandle for PipeStream<Rm, Sm> {
+    #[inline(always)]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.raw.as_handle()
+    }
+}
 impl<Rm: PipeModeTag, Sm: PipeModeTag> AsRawHandle for PipeStream<Rm, Sm> {
     #[inline(always)]
     fn as_raw_handle(&self) -> HANDLE {
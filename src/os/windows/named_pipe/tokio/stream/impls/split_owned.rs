@@ -41,6 +41,22 @@ impl<Rm: PipeModeTag> RecvHalf<Rm> {
     pub fn server_session_id(&self) -> io::Result<u32> {
         unsafe { hget(self.raw.as_raw_handle(), GetNamedPipeServerSessionId) }
     }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.raw.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
+    }
     /// Returns `true` if the underlying stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
     pub fn is_server(&self) -> bool {
@@ -81,6 +97,15 @@ impl AsyncRead for RecvHalf<pipe_mode::Bytes> {
         Pin::new(&mut self.deref()).poll_read(cx, buf)
     }
 }
+impl TokioAsyncRead for RecvHalf<pipe_mode::Bytes> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut TokioReadBuf<'_>) -> Poll<io::Result<()>> {
+        // Tokio only polls named pipes for reading through exclusive references, which the shared pipe cannot provide,
+        // so the initialized variant is used instead.
+        let size = ready!(self.raw.poll_read_init(cx, buf.initialize_unfilled()))?;
+        buf.advance(size);
+        Poll::Ready(Ok(()))
+    }
+}
 impl AsyncReliableRecvMsg for &RecvHalf<pipe_mode::Messages> {
     #[inline]
     fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>> {
@@ -167,6 +192,22 @@ impl<Sm: PipeModeTag> SendHalf<Sm> {
     pub fn server_session_id(&self) -> io::Result<u32> {
         unsafe { hget(self.raw.as_raw_handle(), GetNamedPipeServerSessionId) }
     }
+    /// Retrieves the name of the computer the client side of the named pipe connection is on, or `None` if it's the
+    /// local computer. Only available on the server side.
+    #[inline]
+    pub fn client_computer_name(&self) -> io::Result<Option<OsString>> {
+        client_computer_name(self.raw.as_raw_handle())
+    }
+    /// Retrieves the SID of the user the client side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn client_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.client_process_id()?)
+    }
+    /// Retrieves the SID of the user the server side of the named pipe connection runs as, in its string form.
+    #[inline]
+    pub fn server_user_sid(&self) -> io::Result<String> {
+        user_sid_of_process(self.server_process_id()?)
+    }
     /// Returns `true` if the underlying stream was created by a listener (server-side), `false` if it was created by connecting to a server (server-side).
     #[inline]
     pub fn is_server(&self) -> bool {
@@ -185,6 +226,18 @@ impl SendHalf<pipe_mode::Messages> {
         self.raw.write(buf).await
     }
 }
+impl AsyncSendMsg for &SendHalf<pipe_mode::Messages> {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.raw.poll_write(cx, buf)
+    }
+}
+impl AsyncSendMsg for SendHalf<pipe_mode::Messages> {
+    #[inline]
+    fn poll_send_msg(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.raw.poll_write(cx, buf)
+    }
+}
 impl AsyncWrite for &SendHalf<pipe_mode::Bytes> {
     #[inline]
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
@@ -225,6 +278,20 @@ impl AsyncWrite for SendHalf<pipe_mode::Bytes> {
         Pin::new(&mut self.deref()).poll_close(cx)
     }
 }
+impl TokioAsyncWrite for SendHalf<pipe_mode::Bytes> {
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.raw.poll_write(cx, buf)
+    }
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        <&Self as AsyncWrite>::poll_flush(Pin::new(&mut &*self), cx)
+    }
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        <Self as TokioAsyncWrite>::poll_flush(self, cx)
+    }
+}
 impl<Sm: PipeModeTag> Debug for SendHalf<Sm> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut dbst = f.debug_struct("SendHalf");
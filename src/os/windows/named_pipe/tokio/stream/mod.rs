@@ -3,6 +3,7 @@ mod wrapper_fns;
 pub(crate) use wrapper_fns::*;
 
 use super::super::stream::{pipe_mode, PipeModeTag, REUNITE_ERROR_MSG};
+use crate::drop_policy::DropPolicyCell;
 use std::{
     error::Error,
     fmt::{self, Display, Formatter},
@@ -95,27 +96,11 @@ pub struct SendHalf<Sm: PipeModeTag> {
 }
 
 pub(crate) enum RawPipeStream {
-    Server(TokioNPServer),
+    /// Server-side streams carry the policy of what to do with the connection when they are dropped.
+    Server(TokioNPServer, DropPolicyCell),
     Client(TokioNPClient),
 }
 
-/// Additional contextual information for conversions from a raw handle to a named pipe stream.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum FromRawHandleErrorKind {
-    /// It wasn't possible to determine whether the pipe handle corresponds to a pipe server or a pipe client.
-    IsServerCheckFailed,
-    /// The type being converted into has message semantics, but it wasn't possible to determine whether message boundaries are preserved in the pipe.
-    MessageBoundariesCheckFailed,
-    /// The type being converted into has message semantics, but message boundaries are not preserved in the pipe.
-    NoMessageBoundaries,
-    /// An error was reported by Tokio.
-    ///
-    /// Most of the time, this means that `from_raw_handle()` call was performed outside of the Tokio runtime, but OS errors associated with the registration of the handle in the runtime belong to this category as well.
-    TokioError,
-}
-/// Error type for `from_raw_handle()` constructors.
-pub type FromRawHandleError = (FromRawHandleErrorKind, io::Error);
-
 /// Error type for `.reunite()` on split receive and send halves.
 ///
 /// The error indicates that the halves belong to different streams and allows to recover both of them.
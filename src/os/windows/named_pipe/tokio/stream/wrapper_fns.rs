@@ -6,7 +6,7 @@ pub(crate) fn _connect(path: &OsStr, read: bool, write: bool) -> io::Result<Toki
     let result = ClientOptions::new().read(read).write(write).open(path);
     match result {
         Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => Err(io::ErrorKind::WouldBlock.into()),
-        els => els,
+        els => els.map_err(crate::error::connect_error),
     }
 }
 // TODO connect with wait
@@ -0,0 +1,39 @@
+//! Windows implementation of capability probing.
+
+use crate::{local_socket::NameTypeSupport, platform::Capabilities};
+use std::mem::zeroed;
+use winapi::{
+    shared::ws2def::AF_UNIX,
+    um::winsock2::{closesocket, socket, WSACleanup, WSAStartup, INVALID_SOCKET, SOCK_STREAM, WSADATA},
+};
+
+pub(crate) fn probe() -> Capabilities {
+    Capabilities {
+        name_types: NameTypeSupport::query(),
+        abstract_namespace: false,
+        unix_domain_sockets: af_unix_supported(),
+        seqpacket: false,
+        ancillary_credentials: false,
+        handle_passing: true,
+        shared_memory: true,
+        message_queues: false,
+    }
+}
+
+/// Checks whether Winsock supports `AF_UNIX`, which it does since Windows 10 version 1803.
+fn af_unix_supported() -> bool {
+    unsafe {
+        let mut data: WSADATA = zeroed();
+        // Winsock initialization is reference-counted, so this doesn't interfere with the standard library's.
+        if WSAStartup(0x0202, &mut data) != 0 {
+            return false;
+        }
+        let sock = socket(AF_UNIX, SOCK_STREAM, 0);
+        let supported = sock != INVALID_SOCKET;
+        if supported {
+            closesocket(sock);
+        }
+        WSACleanup();
+        supported
+    }
+}
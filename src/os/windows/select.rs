@@ -0,0 +1,49 @@
+//! Windows implementation of [`Select`](crate::select::Select): since local sockets are not opened for overlapped I/O,
+//! every listener and stream in the set is checked in turn at short intervals.
+
+use {
+    crate::{listener_close::ListenerClosedError, select::Entry},
+    std::{
+        io, thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Waits for any of the listeners and streams in the set to become ready, adding their keys to `ready`, or until the
+/// timeout expires. Events are skipped, since they're checked by the caller.
+pub(crate) fn wait_sockets(entries: &[Entry<'_>], timeout: Option<Duration>, ready: &mut Vec<usize>) -> io::Result<()> {
+    const MAX_INTERVAL: Duration = Duration::from_millis(50);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut interval = Duration::from_millis(1);
+    loop {
+        for (key, entry) in entries.iter().enumerate() {
+            let is_ready = match entry {
+                Entry::Accept(listener) => match listener.wait_accept(Some(Duration::ZERO)) {
+                    Err(e) if ListenerClosedError::is_in(&e) => true,
+                    els => els?,
+                },
+                Entry::Readable(stream) => stream.wait_readable(Some(Duration::ZERO))?,
+                Entry::Writable(stream) => stream.wait_writable(Some(Duration::ZERO))?,
+                Entry::Event { .. } => false,
+            };
+            if is_ready {
+                ready.push(key);
+            }
+        }
+        if !ready.is_empty() {
+            return Ok(());
+        }
+        let sleep = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining == Duration::ZERO {
+                    return Ok(());
+                }
+                interval.min(remaining)
+            }
+            None => interval,
+        };
+        thread::sleep(sleep);
+        interval = (interval * 2).min(MAX_INTERVAL);
+    }
+}
@@ -0,0 +1,173 @@
+//! Windows-specific functionality for shared memory.
+//!
+//! Currently, this consists of the [`SharedMemoryExt`] trait, which allows shared memory objects to be created with a custom security descriptor.
+
+use super::{named_pipe::SecurityDescriptor, winprelude::*, FileHandle, ShareHandle};
+use crate::{shared_memory::SharedMemory as PubSharedMemory, Sealed};
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    mem::{size_of, zeroed},
+    ptr,
+};
+use winapi::{
+    shared::winerror::ERROR_ALREADY_EXISTS,
+    um::{
+        memoryapi::{
+            CreateFileMappingW, MapViewOfFile, OpenFileMappingW, UnmapViewOfFile, VirtualQuery, FILE_MAP_READ,
+            FILE_MAP_WRITE,
+        },
+        minwinbase::SECURITY_ATTRIBUTES,
+        winnt::{MEMORY_BASIC_INFORMATION, PAGE_READWRITE},
+    },
+};
+
+/// Windows-specific extensions for [shared memory objects](PubSharedMemory).
+pub trait SharedMemoryExt: Sealed + Sized {
+    /// Same as [`SharedMemory::create()`](PubSharedMemory::create), but sets the security descriptor of the new object
+    /// to the one described by the given [SDDL] string, which can be used to control which users may open it.
+    ///
+    /// [SDDL]: https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format " "
+    fn create_with_security_descriptor(
+        name: impl AsRef<OsStr>,
+        size: usize,
+        sddl: impl AsRef<OsStr>,
+    ) -> io::Result<Self>;
+}
+impl SharedMemoryExt for PubSharedMemory {
+    fn create_with_security_descriptor(
+        name: impl AsRef<OsStr>,
+        size: usize,
+        sddl: impl AsRef<OsStr>,
+    ) -> io::Result<Self> {
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shared memory objects cannot be empty",
+            ));
+        }
+        let sd = SecurityDescriptor::from_sddl(sddl.as_ref())?;
+        SharedMemory::create_with_sd(name.as_ref(), size, Some(&sd)).map(|inner| Self { inner })
+    }
+}
+
+/// Converts the name of a named kernel object to a nul-terminated wide string.
+pub(crate) fn to_wide(name: &OsStr) -> io::Result<Vec<u16>> {
+    let name = name.encode_wide().collect::<Vec<_>>();
+    if name.contains(&0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "object names cannot contain interior nul characters",
+        ));
+    }
+    Ok(name.into_iter().chain(Some(0)).collect())
+}
+
+pub(crate) struct SharedMemory {
+    handle: FileHandle,
+    len: usize,
+    read_only: bool,
+}
+impl SharedMemory {
+    pub fn create(name: &OsStr, size: usize) -> io::Result<Self> {
+        Self::create_with_sd(name, size, None)
+    }
+    fn create_with_sd(name: &OsStr, size: usize, sd: Option<&SecurityDescriptor>) -> io::Result<Self> {
+        let name = to_wide(name)?;
+        let mut security_attributes = unsafe { zeroed::<SECURITY_ATTRIBUTES>() };
+        security_attributes.nLength = size_of::<SECURITY_ATTRIBUTES>() as DWORD;
+        security_attributes.lpSecurityDescriptor = sd.map_or(ptr::null_mut(), SecurityDescriptor::as_ptr);
+        security_attributes.bInheritHandle = 1;
+
+        let size64 = size as u64;
+        let handle = unsafe {
+            CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                &mut security_attributes,
+                PAGE_READWRITE,
+                (size64 >> 32) as DWORD,
+                size64 as DWORD,
+                name.as_ptr(),
+            )
+        };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        // An existing mapping is opened instead of failing, which is reported through the last error code.
+        let already_exists = io::Error::last_os_error().raw_os_error() == Some(ERROR_ALREADY_EXISTS as i32);
+        let handle = unsafe { FileHandle::from_raw_handle(handle) };
+        if already_exists {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        Ok(Self {
+            handle,
+            len: size,
+            read_only: false,
+        })
+    }
+    pub fn open(name: &OsStr, read_only: bool) -> io::Result<Self> {
+        let name = to_wide(name)?;
+        let handle = unsafe { OpenFileMappingW(access(read_only), 1, name.as_ptr()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let mut slf = Self {
+            handle: unsafe { FileHandle::from_raw_handle(handle) },
+            len: 0,
+            read_only,
+        };
+        // The size of a file mapping isn't stored anywhere, so the best that can be done is to see how big of a view
+        // the system is willing to make of it.
+        let ptr = slf.map(true)?;
+        let mut info = unsafe { zeroed::<MEMORY_BASIC_INFORMATION>() };
+        let success = unsafe { VirtualQuery(ptr as *const _, &mut info, size_of::<MEMORY_BASIC_INFORMATION>()) } != 0;
+        let error = io::Error::last_os_error();
+        unmap(ptr, 0);
+        if !success {
+            return Err(error);
+        }
+        slf.len = info.RegionSize;
+        Ok(slf)
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    pub fn map(&self, read_only: bool) -> io::Result<*mut u8> {
+        let ptr = unsafe { MapViewOfFile(self.handle.as_raw_handle(), access(read_only), 0, 0, self.len) };
+        ok_or_ret_errno!(!ptr.is_null() => ptr as *mut u8)
+    }
+}
+impl Debug for SharedMemory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedMemory")
+            .field("handle", &self.handle.0)
+            .field("len", &self.len)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
+
+fn access(read_only: bool) -> DWORD {
+    if read_only {
+        FILE_MAP_READ
+    } else {
+        FILE_MAP_READ | FILE_MAP_WRITE
+    }
+}
+
+pub(crate) fn unmap(ptr: *mut u8, _len: usize) {
+    unsafe {
+        UnmapViewOfFile(ptr as *const _);
+    }
+}
+
+impl AsRawHandle for PubSharedMemory {
+    fn as_raw_handle(&self) -> HANDLE {
+        self.inner.handle.as_raw_handle()
+    }
+}
+impl ShareHandle for PubSharedMemory {}
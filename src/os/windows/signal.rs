@@ -0,0 +1,120 @@
+//! Windows emulation of signals: every signal that a process receives has a named semaphore, which senders post and a
+//! background thread waits on, dispatching the signal to the receivers. Console control events are dispatched as
+//! signals as well.
+
+use super::{winprelude::*, FileHandle};
+use crate::{
+    signal::{dispatch, Signal},
+    sync::NamedSemaphore,
+};
+use std::{
+    io, process,
+    sync::{Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+use winapi::{
+    shared::winerror::ERROR_INVALID_PARAMETER,
+    um::{
+        consoleapi::SetConsoleCtrlHandler,
+        processthreadsapi::{OpenProcess, TerminateProcess},
+        wincon::{
+            GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_C_EVENT, CTRL_LOGOFF_EVENT,
+            CTRL_SHUTDOWN_EVENT,
+        },
+        winnt::PROCESS_TERMINATE,
+    },
+};
+
+/// Bitmask of the signals which are being received, with the highest bit set once the console control handler is
+/// installed.
+static INSTALLED: Mutex<u8> = Mutex::new(0);
+const CONSOLE_HANDLER_INSTALLED: u8 = 0x80;
+
+fn semaphore_name(pid: u32, signal: Signal) -> String {
+    let signal = match signal {
+        Signal::Interrupt => "interrupt",
+        Signal::Terminate => "terminate",
+        Signal::User1 => "user1",
+    };
+    format!("interprocess-signal-{pid}-{signal}")
+}
+
+pub(crate) fn send(pid: u32, signal: Signal) -> io::Result<()> {
+    match NamedSemaphore::open(semaphore_name(pid, signal)) {
+        Ok(sem) => return sem.post(),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        Err(_) => {}
+    }
+    // The process isn't receiving the signal, so the closest native equivalent is used instead.
+    match signal {
+        Signal::Interrupt => {
+            let success = unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) } != 0;
+            ok_or_ret_errno!(success => ())
+        }
+        Signal::Terminate => {
+            let handle = unsafe { OpenProcess(PROCESS_TERMINATE, 0, pid) };
+            if handle.is_null() {
+                let e = io::Error::last_os_error();
+                return Err(if e.raw_os_error() == Some(ERROR_INVALID_PARAMETER as i32) {
+                    io::Error::new(io::ErrorKind::NotFound, "no process with the specified ID exists")
+                } else {
+                    e
+                });
+            }
+            let handle = unsafe { FileHandle::from_raw_handle(handle) };
+            let success = unsafe { TerminateProcess(handle.as_raw_handle(), 1) } != 0;
+            ok_or_ret_errno!(success => ())
+        }
+        Signal::User1 => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the process is not receiving the signal",
+        )),
+    }
+}
+
+pub(crate) fn install(signal: Signal) -> io::Result<()> {
+    let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+    if *installed & (1 << signal.index()) != 0 {
+        return Ok(());
+    }
+    if matches!(signal, Signal::Interrupt | Signal::Terminate) && *installed & CONSOLE_HANDLER_INSTALLED == 0 {
+        let success = unsafe { SetConsoleCtrlHandler(Some(console_handler), 1) } != 0;
+        if !success {
+            return Err(io::Error::last_os_error());
+        }
+        *installed |= CONSOLE_HANDLER_INSTALLED;
+    }
+
+    let sem = NamedSemaphore::create(semaphore_name(process::id(), signal), 0)?;
+    thread::Builder::new()
+        .name("interprocess signal dispatcher".to_owned())
+        .spawn(move || {
+            while sem.wait().is_ok() {
+                dispatch(signal);
+            }
+        })?;
+    *installed |= 1 << signal.index();
+    Ok(())
+}
+
+/// Runs on a thread spawned by the system for every console control event.
+unsafe extern "system" fn console_handler(ctrl_type: DWORD) -> BOOL {
+    let signal = match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => Signal::Interrupt,
+        CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => Signal::Terminate,
+        _ => return 0,
+    };
+    if !dispatch(signal) {
+        // Let the default handler terminate the process.
+        return 0;
+    }
+    if signal == Signal::Terminate {
+        // The process is terminated as soon as the handler returns, so it has to be kept from returning to give the
+        // receivers time to exit on their own, until the system runs out of patience.
+        loop {
+            thread::sleep(Duration::from_secs(60));
+        }
+    }
+    1
+}
@@ -0,0 +1,42 @@
+//! Windows implementation of endpoint inheritance for the spawn helper: the child's ends are made inheritable right
+//! before the process is created, and the parent closes them right after.
+
+use super::winprelude::*;
+use crate::local_socket::{BorrowedTransferable, OwnedTransferable};
+use std::{io, process::Command};
+use winapi::um::{handleapi::SetHandleInformation, winbase::HANDLE_FLAG_INHERIT};
+
+fn set_inheritable(handle: HANDLE, inheritable: bool) -> io::Result<()> {
+    let flags = if inheritable { HANDLE_FLAG_INHERIT } else { 0 };
+    let success = unsafe { SetHandleInformation(handle, HANDLE_FLAG_INHERIT, flags) } != 0;
+    ok_or_ret_errno!(success => ())
+}
+
+/// Arranges for the given resources to be inherited by the process spawned by the command. They must stay open until
+/// it's spawned.
+///
+/// The handles stay inheritable until they're closed, so processes spawned by other threads in the meantime inherit
+/// them as well.
+pub(crate) fn inherit(_command: &mut Command, resources: &[BorrowedTransferable<'_>]) -> io::Result<()> {
+    for resource in resources {
+        set_inheritable(resource.as_raw_handle(), true)?;
+    }
+    Ok(())
+}
+
+/// The value by which the child process refers to an inherited resource.
+pub(crate) fn raw_value(resource: BorrowedTransferable<'_>) -> u64 {
+    resource.as_raw_handle() as usize as u64
+}
+
+/// Takes ownership of an inherited resource, making sure that it's open and stops being inherited by further children.
+///
+/// # Safety
+/// The resource must have been inherited from the parent process and not be owned by anything else in this process.
+pub(crate) unsafe fn take_inherited(value: u64) -> io::Result<OwnedTransferable> {
+    let handle = usize::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid inherited handle"))?
+        as HANDLE;
+    set_inheritable(handle, false)?;
+    Ok(unsafe { OwnedTransferable::from_raw_handle(handle) })
+}
@@ -0,0 +1,46 @@
+//! Windows backends of the named synchronization primitives.
+
+pub(crate) mod mutex;
+pub(crate) mod semaphore;
+
+use super::winprelude::*;
+use std::{io, time::Duration};
+use winapi::{
+    shared::winerror::WAIT_TIMEOUT,
+    um::{
+        synchapi::WaitForSingleObject,
+        winbase::{INFINITE, WAIT_ABANDONED, WAIT_FAILED, WAIT_OBJECT_0},
+    },
+};
+
+/// Waits for the object to become signaled, returning `None` if the timeout ran out first, or whether the object is an
+/// abandoned mutex otherwise. Timeouts too long to be expressed in milliseconds by a `DWORD` are split into several
+/// waits.
+fn wait_for_object(handle: HANDLE, timeout: Option<Duration>) -> io::Result<Option<bool>> {
+    let mut remaining = timeout.map(|t| t.as_nanos().saturating_add(999_999) / 1_000_000);
+    loop {
+        let chunk = match remaining {
+            // One less than INFINITE, which would make the wait unbounded.
+            Some(ms) => ms.min(u128::from(INFINITE - 1)) as DWORD,
+            None => INFINITE,
+        };
+        match unsafe { WaitForSingleObject(handle, chunk) } {
+            WAIT_OBJECT_0 => return Ok(Some(false)),
+            WAIT_ABANDONED => return Ok(Some(true)),
+            WAIT_FAILED => return Err(io::Error::last_os_error()),
+            WAIT_TIMEOUT => {
+                let ms = remaining.unwrap_or(0) - u128::from(chunk);
+                if ms == 0 {
+                    return Ok(None);
+                }
+                remaining = Some(ms);
+            }
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("unexpected WaitForSingleObject() result {other:#x}"),
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use super::{
+    super::{shared_memory::to_wide, winprelude::*, FileHandle, ShareHandle},
+    wait_for_object,
+};
+use crate::sync::NamedMutex as PubNamedMutex;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    mem::{size_of, zeroed},
+    time::Duration,
+};
+use winapi::{
+    shared::winerror::ERROR_ALREADY_EXISTS,
+    um::{
+        minwinbase::SECURITY_ATTRIBUTES,
+        synchapi::{CreateMutexW, OpenMutexW, ReleaseMutex},
+        winnt::{MUTANT_QUERY_STATE, SYNCHRONIZE},
+    },
+};
+
+/// `MUTEX_MODIFY_STATE`, which is missing from `winapi`.
+const MUTEX_MODIFY_STATE: DWORD = MUTANT_QUERY_STATE;
+
+pub(crate) struct NamedMutex(FileHandle);
+// SAFETY: mutex handles can be used from any thread
+unsafe impl Send for NamedMutex {}
+unsafe impl Sync for NamedMutex {}
+impl NamedMutex {
+    pub fn create(name: &OsStr) -> io::Result<Self> {
+        let name = to_wide(name)?;
+        let mut security_attributes = unsafe { zeroed::<SECURITY_ATTRIBUTES>() };
+        security_attributes.nLength = size_of::<SECURITY_ATTRIBUTES>() as DWORD;
+        security_attributes.bInheritHandle = 1;
+
+        let handle = unsafe { CreateMutexW(&mut security_attributes, 0, name.as_ptr()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        // An existing mutex is opened instead of failing, which is reported through the last error code.
+        let already_exists = io::Error::last_os_error().raw_os_error() == Some(ERROR_ALREADY_EXISTS as i32);
+        let handle = unsafe { FileHandle::from_raw_handle(handle) };
+        if already_exists {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        Ok(Self(handle))
+    }
+    pub fn open(name: &OsStr) -> io::Result<Self> {
+        let name = to_wide(name)?;
+        let handle = unsafe { OpenMutexW(SYNCHRONIZE | MUTEX_MODIFY_STATE, 1, name.as_ptr()) };
+        ok_or_ret_errno!(!handle.is_null() => Self(unsafe { FileHandle::from_raw_handle(handle) }))
+    }
+    pub fn lock(&self) -> io::Result<(NamedMutexGuard<'_>, bool)> {
+        let abandoned = wait_for_object(self.0.as_raw_handle(), None)?.unwrap_or(false);
+        Ok((NamedMutexGuard(self), abandoned))
+    }
+    pub fn try_lock(&self) -> io::Result<Option<(NamedMutexGuard<'_>, bool)>> {
+        let abandoned = wait_for_object(self.0.as_raw_handle(), Some(Duration::ZERO))?;
+        Ok(abandoned.map(|abandoned| (NamedMutexGuard(self), abandoned)))
+    }
+}
+impl Debug for NamedMutex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NamedMutex").field(&self.0 .0).finish()
+    }
+}
+
+pub(crate) struct NamedMutexGuard<'a>(&'a NamedMutex);
+impl Debug for NamedMutexGuard<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NamedMutexGuard").field(&self.0 .0 .0).finish()
+    }
+}
+impl Drop for NamedMutexGuard<'_> {
+    fn drop(&mut self) {
+        let _success = unsafe { ReleaseMutex(self.0 .0.as_raw_handle()) != 0 };
+        debug_assert!(_success, "failed to release mutex: {}", io::Error::last_os_error());
+    }
+}
+
+impl AsRawHandle for PubNamedMutex {
+    fn as_raw_handle(&self) -> HANDLE {
+        self.inner.0.as_raw_handle()
+    }
+}
+impl ShareHandle for PubNamedMutex {}
@@ -0,0 +1,82 @@
+use super::{
+    super::{shared_memory::to_wide, winprelude::*, FileHandle, ShareHandle},
+    wait_for_object,
+};
+use crate::sync::NamedSemaphore as PubNamedSemaphore;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    mem::{size_of, zeroed},
+    ptr,
+    time::Duration,
+};
+use winapi::{
+    shared::winerror::ERROR_ALREADY_EXISTS,
+    um::{
+        minwinbase::SECURITY_ATTRIBUTES,
+        synchapi::{CreateSemaphoreW, OpenSemaphoreW, ReleaseSemaphore},
+        winnt::{LONG, SEMAPHORE_MODIFY_STATE, SYNCHRONIZE},
+    },
+};
+
+pub(crate) struct NamedSemaphore(FileHandle);
+// SAFETY: semaphore handles can be used from any thread
+unsafe impl Send for NamedSemaphore {}
+unsafe impl Sync for NamedSemaphore {}
+impl NamedSemaphore {
+    pub fn create(name: &OsStr, initial_count: u32) -> io::Result<Self> {
+        let initial_count = LONG::try_from(initial_count).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "initial count exceeds the maximum count of a semaphore",
+            )
+        })?;
+        let name = to_wide(name)?;
+        let mut security_attributes = unsafe { zeroed::<SECURITY_ATTRIBUTES>() };
+        security_attributes.nLength = size_of::<SECURITY_ATTRIBUTES>() as DWORD;
+        security_attributes.bInheritHandle = 1;
+
+        let handle = unsafe { CreateSemaphoreW(&mut security_attributes, initial_count, LONG::MAX, name.as_ptr()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        // An existing semaphore is opened instead of failing, which is reported through the last error code.
+        let already_exists = io::Error::last_os_error().raw_os_error() == Some(ERROR_ALREADY_EXISTS as i32);
+        let handle = unsafe { FileHandle::from_raw_handle(handle) };
+        if already_exists {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+        }
+        Ok(Self(handle))
+    }
+    pub fn open(name: &OsStr) -> io::Result<Self> {
+        let name = to_wide(name)?;
+        let handle = unsafe { OpenSemaphoreW(SYNCHRONIZE | SEMAPHORE_MODIFY_STATE, 1, name.as_ptr()) };
+        ok_or_ret_errno!(!handle.is_null() => Self(unsafe { FileHandle::from_raw_handle(handle) }))
+    }
+    pub fn post(&self) -> io::Result<()> {
+        let success = unsafe { ReleaseSemaphore(self.0.as_raw_handle(), 1, ptr::null_mut()) } != 0;
+        ok_or_ret_errno!(success => ())
+    }
+    pub fn wait(&self) -> io::Result<()> {
+        wait_for_object(self.0.as_raw_handle(), None).map(drop)
+    }
+    pub fn try_wait(&self) -> io::Result<bool> {
+        wait_for_object(self.0.as_raw_handle(), Some(Duration::ZERO)).map(|r| r.is_some())
+    }
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        wait_for_object(self.0.as_raw_handle(), Some(timeout)).map(|r| r.is_some())
+    }
+}
+impl Debug for NamedSemaphore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NamedSemaphore").field(&self.0 .0).finish()
+    }
+}
+
+impl AsRawHandle for PubNamedSemaphore {
+    fn as_raw_handle(&self) -> HANDLE {
+        self.inner.0.as_raw_handle()
+    }
+}
+impl ShareHandle for PubNamedSemaphore {}
@@ -1,21 +1,36 @@
 //! Platform-specific functionality for unnamed pipes.
 //!
-//! Currently, this consists of only the [`UnnamedPipeCreationOptions`] builder, but more might be added.
+//! Currently, this consists of the [`UnnamedPipeCreationOptions`] builder and the [`UnnamedPipeOptionsExt`] trait, but more might be added.
 //!
 //! [`UnnamedPipeCreationOptions`]: struct.UnnamedPipeCreationOptions.html " "
 
 // TODO add examples
 
-use super::{weaken_buf_init, winprelude::*, FileHandle};
-use crate::unnamed_pipe::{UnnamedPipeReader as PubReader, UnnamedPipeWriter as PubWriter};
+use super::{
+    check_pipe_handle,
+    named_pipe::{set_nonblocking_for_stream, SecurityDescriptor},
+    weaken_buf_init,
+    winprelude::*,
+    FileHandle,
+};
+use crate::{
+    handle_conversion::FromHandleError,
+    unnamed_pipe::{UnnamedPipeOptions, UnnamedPipeReader as PubReader, UnnamedPipeWriter as PubWriter},
+    Sealed,
+};
 use std::{
+    ffi::OsString,
     fmt::{self, Debug, Formatter},
-    io::{self, Read, Write},
-    mem::{size_of, zeroed, ManuallyDrop},
+    io::{self, IoSlice, Read, Write},
+    mem::{size_of, zeroed, MaybeUninit},
     num::NonZeroUsize,
+    process::Stdio,
     ptr,
 };
-use winapi::um::{minwinbase::SECURITY_ATTRIBUTES, namedpipeapi::CreatePipe};
+use winapi::um::{
+    handleapi::SetHandleInformation, minwinbase::SECURITY_ATTRIBUTES, namedpipeapi::CreatePipe,
+    winbase::HANDLE_FLAG_INHERIT,
+};
 
 /// Builder used to create unnamed pipes while supplying additional options.
 ///
@@ -25,10 +40,14 @@ use winapi::um::{minwinbase::SECURITY_ATTRIBUTES, namedpipeapi::CreatePipe};
 #[non_exhaustive]
 #[derive(Copy, Clone, Debug)]
 pub struct UnnamedPipeCreationOptions {
-    /// Specifies whether the resulting pipe can be inherited by child processes.
+    /// Specifies whether the reading end of the pipe can be inherited by child processes.
     ///
-    /// The default value is `true` and you probably shouldn't modify this, unless you want all child processes to explicitly be unable to use the pipe even if they attempt to use various fishy methods to find the handle in the parent process.
-    pub inheritable: bool,
+    /// The default value is `true`. When only one end of the pipe is meant to be passed to a child process, the other one should be made non-inheritable, so that it doesn't leak into the child (or any other process spawned with handle inheritance at the same time) and keep the pipe from reporting end-of-file when the child exits.
+    pub reader_inheritable: bool,
+    /// Specifies whether the writing end of the pipe can be inherited by child processes.
+    ///
+    /// The default value is `true`. See [`reader_inheritable`](#structfield.reader_inheritable) for when this should be changed.
+    pub writer_inheritable: bool,
     /// A pointer to the [security descriptor] for the pipe. Leave this at the default `NULL` unless you want something specific.
     ///
     /// [security descriptor]: https://docs.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-security_descriptor " "
@@ -40,19 +59,35 @@ impl UnnamedPipeCreationOptions {
     /// Starts with the default parameters for the pipe. Identical to `Default::default()`.
     pub const fn new() -> Self {
         Self {
-            inheritable: true,
+            reader_inheritable: true,
+            writer_inheritable: true,
             security_descriptor: ptr::null_mut(),
             buffer_size_hint: None,
         }
     }
-    /// Specifies whether the resulting pipe can be inherited by child processes.
+    /// Specifies whether both ends of the pipe can be inherited by child processes.
+    #[must_use = "this is not an in-place operation"]
+    pub fn inheritable(self, inheritable: bool) -> Self {
+        self.reader_inheritable(inheritable).writer_inheritable(inheritable)
+    }
+    /// Specifies whether the reading end of the pipe can be inherited by child processes.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.reader_inheritable " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn reader_inheritable(mut self, reader_inheritable: bool) -> Self {
+        self.reader_inheritable = reader_inheritable;
+        self
+    }
+    /// Specifies whether the writing end of the pipe can be inherited by child processes.
     ///
     /// See the [associated field] for more.
     ///
-    /// [associated field]: #structfield.inheritable " "
+    /// [associated field]: #structfield.writer_inheritable " "
     #[must_use = "this is not an in-place operation"]
-    pub fn inheritable(mut self, inheritable: bool) -> Self {
-        self.inheritable = inheritable;
+    pub fn writer_inheritable(mut self, writer_inheritable: bool) -> Self {
+        self.writer_inheritable = writer_inheritable;
         self
     }
     /// Specifies the pointer to the security descriptor for the pipe.
@@ -78,13 +113,15 @@ impl UnnamedPipeCreationOptions {
 
     /// Extracts the [`SECURITY_ATTRIBUTES`] from the builder. Primarily an implementation detail, but has other uses.
     ///
+    /// The handles are only marked as inheritable in the resulting structure if both ends of the pipe are to be inheritable.
+    ///
     /// [`SECURITY_ATTRIBUTES`]: https://docs.microsoft.com/en-us/previous-versions/windows/desktop/legacy/aa379560(v=vs.85)
     pub fn extract_security_attributes(self) -> SECURITY_ATTRIBUTES {
         // Safe because WinAPI parameter structs are typically rejected if a required field is zero
         let mut security_attributes = unsafe { zeroed::<SECURITY_ATTRIBUTES>() };
         security_attributes.nLength = size_of::<SECURITY_ATTRIBUTES>() as u32;
         security_attributes.lpSecurityDescriptor = self.security_descriptor;
-        security_attributes.bInheritHandle = self.inheritable as i32;
+        security_attributes.bInheritHandle = (self.reader_inheritable && self.writer_inheritable) as i32;
         security_attributes
     }
 
@@ -106,6 +143,8 @@ impl UnnamedPipeCreationOptions {
 
     /// Creates the pipe and returns its writing and reading ends, or the error if one occurred. Allows for a security descriptor pointer to be passed.
     ///
+    /// If only one of the ends is to be inheritable, the pipe is created with both ends non-inheritable, and the inheritance flag is then set on that one end. This way, a process spawned by another thread in the meantime can never inherit the end which is to remain in the current process.
+    ///
     /// # Safety
     /// The [`security_descriptor`](Self.security_descriptor) field is passed directly to Win32 which is then dereferenced there, resulting in undefined behavior if it was an invalid non-null pointer. For the default configuration, this should never be a concern.
     pub unsafe fn build_with_security_descriptor(self) -> io::Result<(PubWriter, PubReader)> {
@@ -133,6 +172,16 @@ impl UnnamedPipeCreationOptions {
                 };
                 (writer, reader)
             };
+            if self.reader_inheritable != self.writer_inheritable {
+                let handle = match self.reader_inheritable {
+                    true => reader.as_raw_handle(),
+                    false => writer.as_raw_handle(),
+                };
+                let success = unsafe { SetHandleInformation(handle, HANDLE_FLAG_INHERIT, HANDLE_FLAG_INHERIT) != 0 };
+                if !success {
+                    return Err(io::Error::last_os_error());
+                }
+            }
             Ok((writer, reader))
         } else {
             Err(io::Error::last_os_error())
@@ -147,9 +196,56 @@ impl Default for UnnamedPipeCreationOptions {
 unsafe impl Send for UnnamedPipeCreationOptions {}
 unsafe impl Sync for UnnamedPipeCreationOptions {}
 
+/// Windows-specific options for [`UnnamedPipeOptions`].
+pub trait UnnamedPipeOptionsExt: Sealed {
+    /// Sets the security descriptor of the pipe, in the security descriptor definition language (SDDL) string format. By default, the default security descriptor of the current process is used.
+    ///
+    /// Creating the pipe fails if the string is not a valid security descriptor.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    fn security_descriptor(self, sddl: impl Into<OsString>) -> Self;
+}
+impl UnnamedPipeOptionsExt for UnnamedPipeOptions {
+    fn security_descriptor(mut self, sddl: impl Into<OsString>) -> Self {
+        self.security_descriptor = Some(sddl.into());
+        self
+    }
+}
+
 pub(crate) fn pipe() -> io::Result<(PubWriter, PubReader)> {
     UnnamedPipeCreationOptions::default().build()
 }
+pub(crate) fn pipe_with_options(options: &UnnamedPipeOptions) -> io::Result<(PubWriter, PubReader)> {
+    let security_descriptor = options
+        .security_descriptor
+        .as_deref()
+        .map(SecurityDescriptor::from_sddl)
+        .transpose()?;
+    let creation_options = UnnamedPipeCreationOptions::new()
+        .reader_inheritable(options.reader_inheritable)
+        .writer_inheritable(options.writer_inheritable)
+        .buffer_size_hint(options.buffer_size.and_then(NonZeroUsize::new))
+        .security_descriptor(
+            security_descriptor
+                .as_ref()
+                .map_or(ptr::null_mut(), SecurityDescriptor::as_ptr),
+        );
+    let (writer, reader) = unsafe {
+        // SAFETY: the security descriptor is either null or valid until the end of the function
+        creation_options.build_with_security_descriptor()?
+    };
+    for (handle, nonblocking) in [
+        (reader.as_raw_handle(), options.reader_nonblocking),
+        (writer.as_raw_handle(), options.writer_nonblocking),
+    ] {
+        if nonblocking {
+            unsafe {
+                // SAFETY: the handle is owned by one of the ends of the pipe
+                set_nonblocking_for_stream(handle, None, true)?;
+            }
+        }
+    }
+    Ok((writer, reader))
+}
 
 pub(crate) struct UnnamedPipeReader(FileHandle);
 impl Read for UnnamedPipeReader {
@@ -157,6 +253,29 @@ impl Read for UnnamedPipeReader {
         self.0.read(weaken_buf_init(buf))
     }
 }
+impl UnnamedPipeReader {
+    pub fn read_to_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+impl AsHandle for UnnamedPipeReader {
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.0.as_handle()
+    }
+}
+impl From<UnnamedPipeReader> for OwnedHandle {
+    fn from(x: UnnamedPipeReader) -> Self {
+        x.0.into()
+    }
+}
+/// Fails if the handle is not a pipe.
+impl TryFrom<OwnedHandle> for UnnamedPipeReader {
+    type Error = FromHandleError;
+    fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+        let handle = FromHandleError::check(handle, |h| check_pipe_handle(h.as_handle()))?;
+        Ok(Self(handle.into()))
+    }
+}
 impl AsRawHandle for UnnamedPipeReader {
     fn as_raw_handle(&self) -> HANDLE {
         self.0.as_raw_handle()
@@ -164,8 +283,7 @@ impl AsRawHandle for UnnamedPipeReader {
 }
 impl IntoRawHandle for UnnamedPipeReader {
     fn into_raw_handle(self) -> HANDLE {
-        let self_ = ManuallyDrop::new(self);
-        self_.as_raw_handle()
+        self.0.into_raw_handle()
     }
 }
 impl FromRawHandle for UnnamedPipeReader {
@@ -185,15 +303,68 @@ impl Debug for UnnamedPipeReader {
     }
 }
 
+impl From<PubReader> for Stdio {
+    fn from(reader: PubReader) -> Self {
+        OwnedHandle::from(reader).into()
+    }
+}
+impl From<PubWriter> for Stdio {
+    fn from(writer: PubWriter) -> Self {
+        OwnedHandle::from(writer).into()
+    }
+}
+
+/// The maximum amount of data copied by [`UnnamedPipeWriter::write_vectored()`] to make a single write out of multiple buffers.
+const COALESCE_LIMIT: usize = 64 * 1024;
+
 pub(crate) struct UnnamedPipeWriter(FileHandle);
 impl Write for UnnamedPipeWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.0.write(buf)
     }
+    /// Windows has no gather write for pipes, so multiple buffers are copied into one to have them written with a single system call rather than one call per buffer. Only as many whole buffers as fit into [`COALESCE_LIMIT`] are coalesced; a bigger first buffer is written on its own.
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let mut nonempty = bufs.iter().filter(|buf| !buf.is_empty());
+        let first = match nonempty.next() {
+            Some(first) => first,
+            None => return Ok(0),
+        };
+        if first.len() >= COALESCE_LIMIT || nonempty.clone().next().is_none() {
+            return self.0.write(first);
+        }
+        let total_len = bufs.iter().fold(0_usize, |total, buf| total.saturating_add(buf.len()));
+        let mut coalesced = Vec::with_capacity(total_len.min(COALESCE_LIMIT));
+        coalesced.extend_from_slice(first);
+        for buf in nonempty {
+            if coalesced.len() + buf.len() > COALESCE_LIMIT {
+                break;
+            }
+            coalesced.extend_from_slice(buf);
+        }
+        self.0.write(&coalesced)
+    }
     fn flush(&mut self) -> io::Result<()> {
         self.0.flush()
     }
 }
+impl AsHandle for UnnamedPipeWriter {
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.0.as_handle()
+    }
+}
+impl From<UnnamedPipeWriter> for OwnedHandle {
+    fn from(x: UnnamedPipeWriter) -> Self {
+        x.0.into()
+    }
+}
+/// Fails if the handle is not a pipe.
+impl TryFrom<OwnedHandle> for UnnamedPipeWriter {
+    type Error = FromHandleError;
+    fn try_from(handle: OwnedHandle) -> Result<Self, Self::Error> {
+        let handle = FromHandleError::check(handle, |h| check_pipe_handle(h.as_handle()))?;
+        Ok(Self(handle.into()))
+    }
+}
 impl AsRawHandle for UnnamedPipeWriter {
     fn as_raw_handle(&self) -> HANDLE {
         self.0.as_raw_handle()
@@ -201,13 +372,12 @@ impl AsRawHandle for UnnamedPipeWriter {
 }
 impl IntoRawHandle for UnnamedPipeWriter {
     fn into_raw_handle(self) -> HANDLE {
-        let self_ = ManuallyDrop::new(self);
-        self_.as_raw_handle()
+        self.0.into_raw_handle()
     }
 }
 impl FromRawHandle for UnnamedPipeWriter {
     unsafe fn from_raw_handle(handle: HANDLE) -> Self {
-        Self(FileHandle(handle))
+        Self(unsafe { FileHandle::from_raw_handle(handle) })
     }
 }
 impl Debug for UnnamedPipeWriter {
@@ -0,0 +1,69 @@
+//! Runtime queries of the IPC primitives available on the current system.
+//!
+//! Which primitives can be used is only partially known at compile time: some depend on the version of the OS, on how
+//! its kernel was configured, or on the sandbox the program runs in – POSIX shared memory is unusable in containers
+//! without `/dev/shm`, message queues can be compiled out of the Linux kernel, and Unix domain sockets only exist on
+//! Windows 10 version 1803 and newer. [`capabilities()`] probes the system and reports what is available, allowing
+//! portable programs to select a transport dynamically rather than by guessing at compile time.
+//!
+//! # Example
+//! ```
+//! use interprocess::platform::capabilities;
+//!
+//! let caps = capabilities();
+//! let transport = if caps.shared_memory {
+//!     "shared memory ring buffers"
+//! } else {
+//!     "local sockets"
+//! };
+//! println!("Using {transport}");
+//! ```
+
+use crate::local_socket::NameTypeSupport;
+
+impmod! {platform,
+    probe as probe_impl,
+}
+
+/// A report of the IPC primitives and features available on the current system, as returned by [`capabilities()`].
+///
+/// More fields may be added in the future as the crate gains support for more primitives.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// The types of names local sockets can have, as returned by [`NameTypeSupport::query()`].
+    pub name_types: NameTypeSupport,
+    /// Whether Unix domain sockets can be bound in the Linux abstract namespace, which the `@` prefix of local socket
+    /// names refers to on Linux. Always `false` on Windows, where namespaced names refer to named pipes instead.
+    pub abstract_namespace: bool,
+    /// Whether the system supports Unix domain sockets (`AF_UNIX`). On Windows, local sockets are implemented with named
+    /// pipes regardless, and this only reports whether the OS is recent enough to support `AF_UNIX` for use by other
+    /// means.
+    pub unix_domain_sockets: bool,
+    /// Whether Unix domain sockets of type `SOCK_SEQPACKET`, which preserve message boundaries and back
+    /// [`LocalSocketMsgStream`](crate::local_socket::LocalSocketMsgStream) on Unix, are supported. Always `false` on
+    /// Windows.
+    pub seqpacket: bool,
+    /// Whether the credentials of the sender can be received as ancillary data over Unix domain sockets
+    /// (`SCM_CREDENTIALS` or its equivalents). Always `false` on Windows.
+    pub ancillary_credentials: bool,
+    /// Whether file descriptors or handles can be sent over local sockets with
+    /// [`.send_handles()`](crate::local_socket::LocalSocketStream::send_handles).
+    pub handle_passing: bool,
+    /// Whether named [shared memory](crate::shared_memory) can be created. Always `false` on Android.
+    pub shared_memory: bool,
+    /// Whether POSIX message queues (`mq_open()`) can be created. Always `false` on Windows and on Unix-like systems
+    /// which don't implement them. The crate doesn't wrap message queues, so this is only of use to programs which
+    /// access them by other means.
+    pub message_queues: bool,
+}
+
+/// Probes the current system for the IPC primitives and features available on it.
+///
+/// Features which are known at compile time are reported without any probing. Others are probed for by creating and
+/// immediately destroying a resource of the corresponding type, which takes a few system calls – the result should be
+/// stored if it's needed repeatedly. A probe which fails for any reason, including transient ones such as running out
+/// of file descriptors, reports the feature as unavailable.
+pub fn capabilities() -> Capabilities {
+    probe_impl()
+}
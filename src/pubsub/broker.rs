@@ -0,0 +1,152 @@
+use super::{encode_frame, read_frame, MESSAGE, PUBLISH, SUBSCRIBE, SUBSCRIBED, UNSUBSCRIBE};
+use crate::local_socket::{ListenerClosedError, LocalSocketListener, LocalSocketStream, ToLocalSocketName};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+};
+
+/// The queues of encoded frames to be written to the subscribers of every topic, indexed by connection ID.
+type Topics = HashMap<String, HashMap<u64, Sender<Arc<[u8]>>>>;
+
+/// A message broker, which accepts connections from [`Publisher`](super::Publisher)s and
+/// [`Subscriber`](super::Subscriber)s and relays published messages to the subscribers of their topics.
+///
+/// Every client is served by two threads of its own: one reads its requests, the other writes the messages it's
+/// subscribed to, so that a subscriber which is slow to receive them doesn't hold up the publishers or the other
+/// subscribers. See the [module-level documentation](super) for an example.
+pub struct Broker {
+    listener: LocalSocketListener,
+    shared: Arc<Shared>,
+}
+impl Broker {
+    /// Creates a broker which accepts connections from the given listener.
+    pub fn new(listener: LocalSocketListener) -> Self {
+        Self {
+            listener,
+            shared: Arc::new(Shared {
+                topics: Mutex::new(HashMap::new()),
+                next_id: AtomicU64::new(0),
+            }),
+        }
+    }
+    /// Creates a broker listening on the specified local socket name.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketListener::bind(name).map(Self::new)
+    }
+    /// Borrows the listener, which can be used to [close](LocalSocketListener::close) it and thus stop the broker.
+    #[inline]
+    pub fn listener(&self) -> &LocalSocketListener {
+        &self.listener
+    }
+    /// Accepts clients and serves them, blocking until the listener is closed.
+    ///
+    /// Returns `Ok(())` once the listener is closed, and an error if accepting a client fails for any other reason.
+    /// Clients which are already connected keep being served after that, until they disconnect. A client which sends
+    /// malformed data is disconnected.
+    pub fn run(&self) -> io::Result<()> {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok(stream) => stream,
+                Err(e) if ListenerClosedError::is_in(&e) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let shared = Arc::clone(&self.shared);
+            thread::Builder::new()
+                .name("interprocess pubsub broker".to_owned())
+                .spawn(move || shared.serve(stream))?;
+        }
+    }
+    /// Returns the number of clients currently subscribed to the given topic.
+    pub fn subscriber_count(&self, topic: &str) -> usize {
+        self.shared.topics().get(topic).map_or(0, HashMap::len)
+    }
+}
+impl Debug for Broker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broker")
+            .field("listener", &self.listener)
+            .field("topics", &self.shared.topics().len())
+            .finish()
+    }
+}
+
+struct Shared {
+    topics: Mutex<Topics>,
+    next_id: AtomicU64,
+}
+impl Shared {
+    fn topics(&self) -> MutexGuard<'_, Topics> {
+        // The map is never left in an inconsistent state, so poisoning can be ignored.
+        self.topics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    fn serve(&self, stream: LocalSocketStream) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (mut reader, mut writer) = stream.into_split();
+        let (queue, queue_rx) = mpsc::channel::<Arc<[u8]>>();
+        // The writer finishes once this thread and all of the topics are done with the queue.
+        let spawned = thread::Builder::new()
+            .name("interprocess pubsub broker".to_owned())
+            .spawn(move || {
+                for frame in queue_rx {
+                    if writer.write_all(&frame).is_err() {
+                        break;
+                    }
+                }
+            });
+        if spawned.is_err() {
+            return;
+        }
+
+        let mut subscriptions = HashSet::new();
+        while let Ok(Some(frame)) = read_frame(&mut reader) {
+            match frame.kind {
+                SUBSCRIBE => {
+                    // The acknowledgement is queued while the map is locked, so that every message published after
+                    // the subscription takes effect is queued after it.
+                    let mut topics = self.topics();
+                    topics.entry(frame.topic.clone()).or_default().insert(id, queue.clone());
+                    let ack = encode_frame(SUBSCRIBED, "", &[]).expect("failed to encode an acknowledgement");
+                    let _ = queue.send(ack.into());
+                    drop(topics);
+                    subscriptions.insert(frame.topic);
+                }
+                UNSUBSCRIBE => {
+                    self.unsubscribe(id, &frame.topic);
+                    subscriptions.remove(&frame.topic);
+                }
+                PUBLISH => {
+                    // The frame has already been validated by being received, so it can be encoded again.
+                    let Ok(message) = encode_frame(MESSAGE, &frame.topic, &frame.payload) else {
+                        break;
+                    };
+                    let message = Arc::<[u8]>::from(message);
+                    if let Some(subscribers) = self.topics().get(&frame.topic) {
+                        for subscriber in subscribers.values() {
+                            let _ = subscriber.send(Arc::clone(&message));
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+        for topic in &subscriptions {
+            self.unsubscribe(id, topic);
+        }
+    }
+    fn unsubscribe(&self, id: u64, topic: &str) {
+        let mut topics = self.topics();
+        if let Some(subscribers) = topics.get_mut(topic) {
+            subscribers.remove(&id);
+            if subscribers.is_empty() {
+                topics.remove(topic);
+            }
+        }
+    }
+}
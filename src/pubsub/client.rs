@@ -0,0 +1,111 @@
+use super::{broker_gone, encode_frame, read_frame, Frame, Message, PUBLISH, SUBSCRIBE, SUBSCRIBED, UNSUBSCRIBE};
+use crate::local_socket::{LocalSocketStream, ToLocalSocketName};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    io::{self, Write},
+};
+
+/// A client of a [`Broker`](super::Broker) which publishes messages.
+///
+/// See the [module-level documentation](super) for an example.
+pub struct Publisher {
+    stream: LocalSocketStream,
+}
+impl Publisher {
+    /// Connects to a broker.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketStream::connect(name).map(Self::new)
+    }
+    /// Creates a publisher on a stream which is already connected to a broker.
+    #[inline]
+    pub fn new(stream: LocalSocketStream) -> Self {
+        Self { stream }
+    }
+    /// Publishes a message on the given topic.
+    ///
+    /// Topics longer than 65535 bytes and payloads bigger than [`MAX_PAYLOAD_SIZE`](super::MAX_PAYLOAD_SIZE) are
+    /// reported as [`InvalidInput`](io::ErrorKind::InvalidInput) errors without sending anything. Success means that
+    /// the message was handed over to the broker, not that any subscriber received it.
+    pub fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+        self.stream.write_all(&encode_frame(PUBLISH, topic, payload)?)
+    }
+    /// Unwraps the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> LocalSocketStream {
+        self.stream
+    }
+}
+impl Debug for Publisher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publisher").field("stream", &self.stream).finish()
+    }
+}
+
+/// A client of a [`Broker`](super::Broker) which receives the messages published on the topics it's subscribed to.
+///
+/// See the [module-level documentation](super) for an example.
+pub struct Subscriber {
+    stream: LocalSocketStream,
+    /// Messages received while waiting for a subscription to be acknowledged.
+    pending: VecDeque<Message>,
+}
+impl Subscriber {
+    /// Connects to a broker. The subscriber starts out not subscribed to any topics.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketStream::connect(name).map(Self::new)
+    }
+    /// Creates a subscriber on a stream which is already connected to a broker.
+    #[inline]
+    pub fn new(stream: LocalSocketStream) -> Self {
+        Self {
+            stream,
+            pending: VecDeque::new(),
+        }
+    }
+    /// Subscribes to a topic, waiting for the broker to acknowledge the subscription. Every message published on the
+    /// topic after this returns is received, including ones published by other processes.
+    ///
+    /// Subscribing to a topic more than once has no effect. Messages on other topics which arrive in the meantime are
+    /// kept for [`.recv()`](Self::recv). Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the topic is
+    /// longer than 65535 bytes.
+    pub fn subscribe(&mut self, topic: &str) -> io::Result<()> {
+        self.stream.write_all(&encode_frame(SUBSCRIBE, topic, &[])?)?;
+        loop {
+            let frame = read_frame(&mut self.stream)?.ok_or_else(broker_gone)?;
+            if frame.kind == SUBSCRIBED {
+                return Ok(());
+            }
+            self.pending.push_back(frame.into_message()?);
+        }
+    }
+    /// Unsubscribes from a topic. Messages which the broker had relayed before receiving the request may still be
+    /// received afterwards.
+    pub fn unsubscribe(&mut self, topic: &str) -> io::Result<()> {
+        self.stream.write_all(&encode_frame(UNSUBSCRIBE, topic, &[])?)
+    }
+    /// Receives a message published on one of the topics the subscriber is subscribed to, returning `None` if the
+    /// broker closes the connection.
+    ///
+    /// Frames which cannot be interpreted are reported as [`InvalidData`](io::ErrorKind::InvalidData) errors.
+    pub fn recv(&mut self) -> io::Result<Option<Message>> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+        read_frame(&mut self.stream)?.map(Frame::into_message).transpose()
+    }
+    /// Unwraps the underlying stream, discarding any messages which were received but not yet returned by
+    /// [`.recv()`](Self::recv).
+    #[inline]
+    pub fn into_inner(self) -> LocalSocketStream {
+        self.stream
+    }
+}
+impl Debug for Subscriber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("stream", &self.stream)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
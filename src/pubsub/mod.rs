@@ -0,0 +1,187 @@
+//! A publish/subscribe message broker, which relays messages published on named topics to all of the processes
+//! subscribed to them.
+//!
+//! Applications made of several processes often need to broadcast events – a setting was changed, a file was
+//! downloaded – to whichever processes happen to be interested. The [`Broker`] listens on a local socket name and keeps
+//! track of the topics every connected client is subscribed to. Clients connect to it as a [`Publisher`], which sends
+//! messages, or as a [`Subscriber`], which receives the messages published on the topics it's subscribed to. The
+//! `tokio` submodule has the asynchronous counterparts of the clients, with the `tokio` feature enabled.
+//!
+//! Topics are arbitrary strings, matched exactly, and messages are arbitrary bytes – use a serialization format of
+//! choice for structured data. Every message is delivered to every subscriber of its topic which was subscribed by the
+//! time the broker received the message, in the order in which the broker received the messages. Messages are not
+//! stored: a subscriber only receives messages published after it subscribed, and a topic without subscribers simply
+//! discards them.
+//!
+//! # Example
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use interprocess::{
+//!     local_socket::LocalSocketNameGen,
+//!     pubsub::{Broker, Publisher, Subscriber},
+//! };
+//! use std::{sync::Arc, thread};
+//!
+//! let name = LocalSocketNameGen::new("pubsub-example").next().unwrap();
+//! let broker = Arc::new(Broker::bind(name.clone())?);
+//! let broker_thread = thread::spawn({
+//!     let broker = Arc::clone(&broker);
+//!     move || broker.run()
+//! });
+//!
+//! // In one process:
+//! let mut subscriber = Subscriber::connect(name.clone())?;
+//! subscriber.subscribe("downloads")?;
+//!
+//! // In another one:
+//! let mut publisher = Publisher::connect(name)?;
+//! publisher.publish("downloads", b"report.pdf")?;
+//! publisher.publish("uploads", b"this one is not received")?;
+//! publisher.publish("downloads", b"photo.jpg")?;
+//!
+//! let message = subscriber.recv()?.unwrap();
+//! assert_eq!((message.topic.as_str(), &message.payload[..]), ("downloads", &b"report.pdf"[..]));
+//! assert_eq!(subscriber.recv()?.unwrap().payload, b"photo.jpg");
+//!
+//! broker.listener().close();
+//! broker_thread.join().unwrap()?;
+//! # Ok(()) }
+//! ```
+//!
+//! # Wire format
+//! Clients and the broker exchange frames, each of which consists of a one-byte frame kind, the length of the topic as
+//! a little-endian 16-bit integer, the length of the payload as a little-endian 32-bit integer, the topic in UTF-8 and
+//! the payload. Clients send subscription requests, unsubscription requests and published messages; the broker sends
+//! messages and acknowledgements of subscriptions.
+
+mod broker;
+mod client;
+pub use {broker::*, client::*};
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use crate::{Error, ErrorKind};
+use std::io::{self, Read};
+
+/// The maximum size of the payload of a message, in bytes: 16 MiB.
+///
+/// Publishing a bigger message fails, and a client that sends one to the broker anyway is disconnected.
+pub const MAX_PAYLOAD_SIZE: usize = 16 * 1024 * 1024;
+
+/// A message received by a [`Subscriber`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Message {
+    /// The topic the message was published on.
+    pub topic: String,
+    /// The contents of the message.
+    pub payload: Vec<u8>,
+}
+
+// Frames sent by clients.
+const SUBSCRIBE: u8 = 0;
+const UNSUBSCRIBE: u8 = 1;
+const PUBLISH: u8 = 2;
+// Frames sent by the broker.
+const MESSAGE: u8 = 0;
+const SUBSCRIBED: u8 = 1;
+
+const FRAME_HEADER_LEN: usize = 7;
+
+/// A frame as it's received, before it's interpreted according to its kind.
+struct Frame {
+    kind: u8,
+    topic: String,
+    payload: Vec<u8>,
+}
+impl Frame {
+    /// Converts a frame received by a subscriber into a message, failing if it's an acknowledgement or of an unknown
+    /// kind.
+    fn into_message(self) -> io::Result<Message> {
+        match self.kind {
+            MESSAGE => Ok(Message {
+                topic: self.topic,
+                payload: self.payload,
+            }),
+            _ => Err(unexpected_frame()),
+        }
+    }
+}
+
+fn encode_frame(kind: u8, topic: &str, payload: &[u8]) -> io::Result<Vec<u8>> {
+    let topic_len = u16::try_from(topic.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the topic is longer than 65535 bytes"))?;
+    if payload.len() > MAX_PAYLOAD_SIZE {
+        return Err(Error::new(
+            ErrorKind::MessageTooLarge,
+            "the payload exceeds the maximum message size",
+        )
+        .into());
+    }
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + topic.len() + payload.len());
+    frame.push(kind);
+    frame.extend_from_slice(&topic_len.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(topic.as_bytes());
+    frame.extend_from_slice(payload);
+    Ok(frame)
+}
+
+/// Parses the header of a frame into its kind and the lengths of the topic and the payload.
+fn decode_header(header: [u8; FRAME_HEADER_LEN]) -> io::Result<(u8, usize, usize)> {
+    let [kind, t0, t1, p0, p1, p2, p3] = header;
+    let topic_len = u16::from_le_bytes([t0, t1]) as usize;
+    let payload_len = u32::from_le_bytes([p0, p1, p2, p3]) as usize;
+    if payload_len > MAX_PAYLOAD_SIZE {
+        return Err(Error::with_io_kind(
+            ErrorKind::MessageTooLarge,
+            io::ErrorKind::InvalidData,
+            "received a message which exceeds the maximum message size",
+        )
+        .into());
+    }
+    Ok((kind, topic_len, payload_len))
+}
+/// Splits the body of a frame, which follows the header, into the topic and the payload.
+fn decode_body(kind: u8, mut body: Vec<u8>, topic_len: usize) -> io::Result<Frame> {
+    let payload = body.split_off(topic_len);
+    let topic = String::from_utf8(body)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "received a topic which is not valid UTF-8"))?;
+    Ok(Frame { kind, topic, payload })
+}
+
+/// Reads one frame, returning `None` if the stream ends before it.
+fn read_frame(reader: &mut impl Read) -> io::Result<Option<Frame>> {
+    let mut header = [0; FRAME_HEADER_LEN];
+    let mut filled = 0;
+    while filled < FRAME_HEADER_LEN {
+        match reader.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(eof_in_frame()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let (kind, topic_len, payload_len) = decode_header(header)?;
+    let mut body = vec![0; topic_len + payload_len];
+    reader.read_exact(&mut body).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => eof_in_frame(),
+        _ => e,
+    })?;
+    decode_body(kind, body, topic_len).map(Some)
+}
+
+fn eof_in_frame() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "the connection ended in the middle of a frame",
+    )
+}
+fn unexpected_frame() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "received an unexpected frame")
+}
+fn broker_gone() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "the broker closed the connection")
+}
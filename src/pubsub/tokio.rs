@@ -0,0 +1,157 @@
+//! Tokio-based asynchronous publish/subscribe clients.
+//!
+//! The types in this module are the asynchronous counterparts of [`Publisher`](super::Publisher) and
+//! [`Subscriber`](super::Subscriber), and connect to the same [`Broker`](super::Broker).
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! use interprocess::pubsub::tokio::Subscriber;
+//!
+//! let mut subscriber = Subscriber::connect("/tmp/example-broker.sock").await?;
+//! subscriber.subscribe("downloads").await?;
+//! while let Some(message) = subscriber.recv().await? {
+//!     println!("Downloaded {}", String::from_utf8_lossy(&message.payload));
+//! }
+//! # Ok(()) }
+//! ```
+
+use super::{
+    broker_gone, decode_body, decode_header, encode_frame, eof_in_frame, Frame, Message, FRAME_HEADER_LEN, PUBLISH,
+    SUBSCRIBE, SUBSCRIBED, UNSUBSCRIBE,
+};
+use crate::local_socket::{tokio::LocalSocketStream, ToLocalSocketName};
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use std::{
+    collections::VecDeque,
+    fmt::{self, Debug, Formatter},
+    io,
+};
+
+/// Reads one frame, returning `None` if the stream ends before it.
+async fn read_frame(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<Option<Frame>> {
+    let mut header = [0; FRAME_HEADER_LEN];
+    let mut filled = 0;
+    while filled < FRAME_HEADER_LEN {
+        match reader.read(&mut header[filled..]).await? {
+            0 if filled == 0 => return Ok(None),
+            0 => return Err(eof_in_frame()),
+            n => filled += n,
+        }
+    }
+    let (kind, topic_len, payload_len) = decode_header(header)?;
+    let mut body = vec![0; topic_len + payload_len];
+    if let Err(e) = reader.read_exact(&mut body).await {
+        return Err(if e.kind() == io::ErrorKind::UnexpectedEof {
+            eof_in_frame()
+        } else {
+            e
+        });
+    }
+    decode_body(kind, body, topic_len).map(Some)
+}
+
+/// An asynchronous client of a [`Broker`](super::Broker) which publishes messages.
+pub struct Publisher {
+    stream: LocalSocketStream,
+}
+impl Publisher {
+    /// Connects to a broker.
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketStream::connect(name).await.map(Self::new)
+    }
+    /// Creates a publisher on a stream which is already connected to a broker.
+    #[inline]
+    pub fn new(stream: LocalSocketStream) -> Self {
+        Self { stream }
+    }
+    /// Publishes a message on the given topic.
+    ///
+    /// Errors are reported the same way as by the [synchronous version](super::Publisher::publish). The future is not
+    /// cancel-safe: dropping it before it completes may leave a partially written frame in the stream.
+    pub async fn publish(&mut self, topic: &str, payload: &[u8]) -> io::Result<()> {
+        let frame = encode_frame(PUBLISH, topic, payload)?;
+        self.stream.write_all(&frame).await
+    }
+    /// Unwraps the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> LocalSocketStream {
+        self.stream
+    }
+}
+impl Debug for Publisher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publisher").field("stream", &self.stream).finish()
+    }
+}
+
+/// An asynchronous client of a [`Broker`](super::Broker) which receives the messages published on the topics it's
+/// subscribed to.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct Subscriber {
+    stream: LocalSocketStream,
+    /// Messages received while waiting for a subscription to be acknowledged.
+    pending: VecDeque<Message>,
+}
+impl Subscriber {
+    /// Connects to a broker. The subscriber starts out not subscribed to any topics.
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketStream::connect(name).await.map(Self::new)
+    }
+    /// Creates a subscriber on a stream which is already connected to a broker.
+    #[inline]
+    pub fn new(stream: LocalSocketStream) -> Self {
+        Self {
+            stream,
+            pending: VecDeque::new(),
+        }
+    }
+    /// Subscribes to a topic, waiting for the broker to acknowledge the subscription.
+    ///
+    /// Behaves like the [synchronous version](super::Subscriber::subscribe). The future is not cancel-safe: dropping
+    /// it before it completes may leave the connection in an unusable state.
+    pub async fn subscribe(&mut self, topic: &str) -> io::Result<()> {
+        let frame = encode_frame(SUBSCRIBE, topic, &[])?;
+        self.stream.write_all(&frame).await?;
+        loop {
+            let frame = read_frame(&mut self.stream).await?.ok_or_else(broker_gone)?;
+            if frame.kind == SUBSCRIBED {
+                return Ok(());
+            }
+            self.pending.push_back(frame.into_message()?);
+        }
+    }
+    /// Unsubscribes from a topic. Messages which the broker had relayed before receiving the request may still be
+    /// received afterwards.
+    pub async fn unsubscribe(&mut self, topic: &str) -> io::Result<()> {
+        let frame = encode_frame(UNSUBSCRIBE, topic, &[])?;
+        self.stream.write_all(&frame).await
+    }
+    /// Receives a message published on one of the topics the subscriber is subscribed to, returning `None` if the
+    /// broker closes the connection.
+    ///
+    /// Errors are reported the same way as by the [synchronous version](super::Subscriber::recv). The future is not
+    /// cancel-safe: dropping it before it completes may discard a partially received message.
+    pub async fn recv(&mut self) -> io::Result<Option<Message>> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+        read_frame(&mut self.stream).await?.map(Frame::into_message).transpose()
+    }
+    /// Unwraps the underlying stream, discarding any messages which were received but not yet returned by
+    /// [`.recv()`](Self::recv).
+    #[inline]
+    pub fn into_inner(self) -> LocalSocketStream {
+        self.stream
+    }
+}
+impl Debug for Subscriber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("stream", &self.stream)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
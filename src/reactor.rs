@@ -0,0 +1,124 @@
+//! Abstraction over asynchronous runtimes, used to drive the [generic asynchronous local
+//! sockets](crate::local_socket::generic).
+//!
+//! Every runtime has its own way of waiting for I/O, but the asynchronous local sockets only need a small part of it.
+//! The [`Reactor`] trait captures exactly that part, so that supporting a new runtime is a matter of implementing one
+//! trait instead of duplicating the whole asynchronous module tree.
+//!
+//! The required functionality differs between platforms:
+//! - On Unix, local sockets are nonblocking file descriptors, and the reactor is used to wait for them to become
+//!   readable or writable.
+//! - On Windows, the named pipes backing local sockets are not opened for overlapped I/O, and thus the blocking
+//!   operations are offloaded to a thread pool provided by the reactor.
+//!
+//! An implementation for `async-io`, the reactor of async-std and `smol`, is provided as [`AsyncIo`] with the
+//! `async-std` feature enabled. Tokio has its own, more efficient [integration](crate::local_socket::tokio) and doesn't
+//! go through this trait.
+
+#[cfg(windows)]
+use std::future::Future;
+#[cfg(unix)]
+use std::{
+    io,
+    os::unix::io::RawFd,
+    task::{Context, Poll},
+};
+
+/// The part of an asynchronous runtime used to perform I/O on the [generic asynchronous local
+/// sockets](crate::local_socket::generic).
+///
+/// The trait is implemented on marker types, none of its methods take `self`. Its items depend on the platform – see
+/// the [module-level documentation](self) for the reasoning.
+///
+/// # Unix
+/// A file descriptor is registered with [`register()`](Self::register) once it has been put in nonblocking mode, and
+/// stays registered until the returned registration is dropped, which always happens before the descriptor is closed.
+/// Every time an operation on the descriptor fails with [`WouldBlock`](std::io::ErrorKind::WouldBlock),
+/// [`poll_readable()`](Self::poll_readable) or [`poll_writable()`](Self::poll_writable) is called to wait until it
+/// can be retried. Since those are only called after the descriptor was found to not be ready, implementations may
+/// discard any readiness they have cached for it. Spurious wakeups are allowed, since the operation is simply retried.
+///
+/// # Windows
+/// Every potentially blocking operation is run with `spawn_blocking()`, which is expected to run the closure on a
+/// thread where blocking is acceptable and to return a future that completes with its result. Dropping the future must
+/// not abort the closure.
+pub trait Reactor: Send + Sync + 'static {
+    /// A file descriptor registered with the reactor. The registration is undone when this is dropped.
+    #[cfg(unix)]
+    type Registration: Send + Sync + 'static;
+    /// Registers a nonblocking file descriptor with the reactor.
+    #[cfg(unix)]
+    fn register(fd: RawFd) -> io::Result<Self::Registration>;
+    /// Waits for the registered file descriptor to become readable, registering the waker of the context to be woken
+    /// up when that happens.
+    #[cfg(unix)]
+    fn poll_readable(registration: &Self::Registration, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+    /// Waits for the registered file descriptor to become writable, registering the waker of the context to be woken
+    /// up when that happens.
+    #[cfg(unix)]
+    fn poll_writable(registration: &Self::Registration, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+
+    /// The future returned by `spawn_blocking()`.
+    #[cfg(windows)]
+    type Task<T: Send + 'static>: Future<Output = T> + Send + Unpin + 'static;
+    /// Runs a blocking closure on a thread pool, returning a future that completes with its result.
+    #[cfg(windows)]
+    fn spawn_blocking<F, T>(f: F) -> Self::Task<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static;
+}
+
+/// The reactor of `async-io`, which is used by async-std and `smol`.
+///
+/// On Windows, the blocking operations are offloaded to the thread pool of the `blocking` crate instead, since
+/// `async-io` can only drive sockets there.
+#[cfg(feature = "async-std")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "async-std")))]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AsyncIo;
+#[cfg(feature = "async-std")]
+impl Reactor for AsyncIo {
+    #[cfg(unix)]
+    type Registration = async_io::Async<BorrowedRawFd>;
+    #[cfg(unix)]
+    #[inline]
+    fn register(fd: RawFd) -> io::Result<Self::Registration> {
+        async_io::Async::new(BorrowedRawFd(fd))
+    }
+    #[cfg(unix)]
+    #[inline]
+    fn poll_readable(registration: &Self::Registration, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        registration.poll_readable(cx)
+    }
+    #[cfg(unix)]
+    #[inline]
+    fn poll_writable(registration: &Self::Registration, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        registration.poll_writable(cx)
+    }
+
+    #[cfg(windows)]
+    type Task<T: Send + 'static> = blocking::Task<T>;
+    #[cfg(windows)]
+    #[inline]
+    fn spawn_blocking<F, T>(f: F) -> Self::Task<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        blocking::unblock(f)
+    }
+}
+
+/// A file descriptor which is owned by someone else, for registering with `async-io` without transferring ownership.
+#[cfg(all(unix, feature = "async-std"))]
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct BorrowedRawFd(RawFd);
+#[cfg(all(unix, feature = "async-std"))]
+impl std::os::unix::io::AsRawFd for BorrowedRawFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
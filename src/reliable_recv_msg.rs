@@ -25,19 +25,64 @@
 //! ```
 //! The inner [`TryRecvResult`] reports both the size of the message and whether it fit into the buffer or not. If it didn't fit, the buffer is unaffected (unlike with `RecvResult`).
 //!
+//! ## Reusing buffers
+//! Every message which doesn't fit into the buffer passed to `recv` gets a freshly allocated buffer of its own, which adds up for servers that receive large messages at a high rate. The `recv_pooled` methods take those buffers from a [`RecvBufPool`] instead, and [`RecvResult::recycle_into`] hands them back to it once the message has been processed:
+//! ```no_run
+//! # use interprocess::reliable_recv_msg::*;
+//! # fn _swag(conn: &mut dyn ReliableRecvMsg) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut buf = [0_u8; 64];
+//! let mut pool = RecvBufPool::new();
+//! loop {
+//!     let rslt = conn.recv_pooled(&mut buf, &mut pool)?;
+//!     let msg = rslt.borrow_to_size(&buf);
+//!     // do stuff with the message
+//!     # let _ = msg;
+//!     rslt.recycle_into(&mut pool);
+//! }
+//! # }
+//! ```
+//!
+//! ## Asynchronous receiving
+//! [`AsyncReliableRecvMsg`] is the poll-based counterpart of [`ReliableRecvMsg`], and [`AsyncReliableRecvMsgExt`] provides the futures for `.await`ing its methods. Since every async message source of the crate implements it, code that only needs to receive messages can be written once for all of them:
+//! ```no_run
+//! # #[cfg(feature = "tokio")] {
+//! use interprocess::reliable_recv_msg::{AsyncReliableRecvMsg, AsyncReliableRecvMsgExt};
+//!
+//! async fn count_bytes(mut source: impl AsyncReliableRecvMsg + Unpin) -> std::io::Result<usize> {
+//!     let mut buf = [0_u8; 64];
+//!     let mut total = 0;
+//!     loop {
+//!         match source.recv(&mut buf).await?.size() {
+//!             0 => return Ok(total),
+//!             size => total += size,
+//!         }
+//!     }
+//! }
+//! # }
+//! ```
+//!
 //! ## Platform support
-//! The traits are implemented for:
+//! The synchronous trait is implemented for:
 //! - Named pipes on Windows (module `interprocess::os::windows::named_pipe`)
 //! - Unix domain pipes, but only on Linux (module `interprocess::os::unix::udsocket`)
 //!     - This is because only Linux provides a special flag for `recv` which returns the amount of bytes in the message regardless of the provided buffer size when peeking.
+//! - Message-mode local sockets, including the in-process ones from [`loopback`](crate::local_socket::loopback)
+//!
+//! The asynchronous trait is implemented for:
+//! - Tokio-based message-mode named pipes on Windows (module `interprocess::os::windows::named_pipe::tokio`)
+//! - Tokio-based Unix domain datagram sockets (module `interprocess::os::unix::udsocket::tokio`)
+//! - `async-io`-based Unix domain datagram sockets (module `interprocess::os::unix::udsocket::async_io`)
+//! - Tokio-based message-mode local sockets
 
+use crate::timeout::WaitReady;
+#[cfg(feature = "bytes")]
+use bytes::BytesMut;
 use std::{
-    error::Error,
-    fmt::{self, Display, Formatter},
     future::Future,
     io,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// Receiving from IPC channels with message boundaries reliably, without truncation.
@@ -49,11 +94,16 @@ pub trait ReliableRecvMsg {
 
     /// Receives one message from the stream into the specified buffer, returning either the size of the message written, a bigger buffer if the one provided was too small, or an error in the outermost `Result` if the operation could not be completed for OS reasons.
     fn recv(&mut self, buf: &mut [u8]) -> io::Result<RecvResult> {
+        self.recv_pooled(buf, &mut RecvBufPool::new())
+    }
+
+    /// Same as [`.recv()`](Self::recv), but takes the bigger buffer from the given pool instead of allocating a new one if the message doesn't fit into the one provided.
+    fn recv_pooled(&mut self, buf: &mut [u8], pool: &mut RecvBufPool) -> io::Result<RecvResult> {
         let TryRecvResult { size, fit } = self.try_recv(buf)?;
         if fit {
             Ok(RecvResult::Fit(size))
         } else {
-            let mut new_buf = vec![0; size];
+            let mut new_buf = pool.take_zeroed(size);
             let TryRecvResult { size, fit } = self.try_recv(&mut new_buf)?;
             assert!(
                 fit,
@@ -63,6 +113,52 @@ pub trait ReliableRecvMsg {
             Ok(RecvResult::Alloc(new_buf))
         }
     }
+
+    /// Receives one message from the stream into a freshly allocated buffer of its exact size.
+    ///
+    /// The size of the message is found out by attempting to receive it into an empty buffer first, which leaves it in the stream unless it's empty.
+    fn recv_vec(&mut self) -> io::Result<Vec<u8>> {
+        let TryRecvResult { size, fit } = self.try_recv(&mut [])?;
+        if fit {
+            return Ok(Vec::new());
+        }
+        let mut msg = vec![0; size];
+        let TryRecvResult { size, fit } = self.try_recv(&mut msg)?;
+        assert!(
+            fit,
+            "try_recv() returned fit = false for a buffer of a size that it reported was sufficient"
+        );
+        msg.truncate(size);
+        Ok(msg)
+    }
+
+    /// Same as [`.recv()`](Self::recv), but fails with [`TimedOut`](io::ErrorKind::TimedOut) if no message arrives before the timeout expires.
+    ///
+    /// Available for streams which can [wait for readiness](WaitReady) with a timeout.
+    fn recv_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<RecvResult>
+    where
+        Self: WaitReady + Sized,
+    {
+        if !self.wait_readable(Some(timeout))? {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no message arrived before the timeout expired",
+            ));
+        }
+        self.recv(buf)
+    }
+
+    /// Receives one message from the stream and appends it to the given buffer, growing it if the message doesn't fit into its spare capacity, and returns the size of the message.
+    ///
+    /// Unlike with [`.recv()`](Self::recv), the message always ends up in the same buffer, which can then be split and frozen into [`Bytes`](bytes::Bytes) without copying.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+    fn recv_buf(&mut self, buf: &mut BytesMut) -> io::Result<usize> {
+        match poll_recv_bytes_mut(buf, |b| Poll::Ready(self.try_recv(b))) {
+            Poll::Ready(r) => r,
+            Poll::Pending => unreachable!(),
+        }
+    }
 }
 
 /// Implementation of asynchronously receiving from IPC channels with message boundaries reliably, without truncation.
@@ -73,7 +169,17 @@ pub trait AsyncReliableRecvMsg {
     fn poll_try_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<TryRecvResult>>;
 
     /// Polls a future that aeceives one message from the stream into the specified buffer, returning either the size of the message written, a bigger buffer if the one provided was too small, or an error in the outermost `Result` if the operation could not be completed for OS reasons.
-    fn poll_recv(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<RecvResult>> {
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<RecvResult>> {
+        self.poll_recv_pooled(cx, buf, &mut RecvBufPool::new())
+    }
+
+    /// Same as [`.poll_recv()`](Self::poll_recv), but takes the bigger buffer from the given pool instead of allocating a new one if the message doesn't fit into the one provided.
+    fn poll_recv_pooled(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        pool: &mut RecvBufPool,
+    ) -> Poll<io::Result<RecvResult>> {
         let TryRecvResult { size, fit } = match self.as_mut().poll_try_recv(cx, buf) {
             Poll::Ready(r) => r?,
             Poll::Pending => return Poll::Pending,
@@ -81,11 +187,14 @@ pub trait AsyncReliableRecvMsg {
         if fit {
             Poll::Ready(Ok(RecvResult::Fit(size)))
         } else {
-            let mut new_buf = vec![0; size];
+            let mut new_buf = pool.take_zeroed(size);
             let TryRecvResult { size, fit } = match self.poll_try_recv(cx, &mut new_buf) {
                 Poll::Ready(r) => r?,
                 // This isn't supposed to be hit normally, since the buffer would be wasted then.
-                Poll::Pending => return Poll::Pending,
+                Poll::Pending => {
+                    pool.recycle(new_buf);
+                    return Poll::Pending;
+                }
             };
             assert!(
                 fit,
@@ -95,6 +204,42 @@ pub trait AsyncReliableRecvMsg {
             Poll::Ready(Ok(RecvResult::Alloc(new_buf)))
         }
     }
+
+    /// Polls a future that receives one message from the stream and appends it to the given buffer, growing it if the message doesn't fit into its spare capacity, and returns the size of the message. See [`ReliableRecvMsg::recv_buf()`] for more.
+    #[cfg(feature = "bytes")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytes")))]
+    fn poll_recv_buf(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut BytesMut) -> Poll<io::Result<usize>> {
+        poll_recv_bytes_mut(buf, |b| self.as_mut().poll_try_recv(cx, b))
+    }
+}
+
+/// The amount of spare capacity reserved in a `BytesMut` which has little or none before receiving into it.
+#[cfg(feature = "bytes")]
+pub(crate) const BYTES_MUT_MIN_SPARE: usize = 1024;
+
+/// Receives one message into the spare capacity of `buf` with `try_recv`, appending it to the contents and retrying with more capacity if it doesn't fit. Nothing is appended if `try_recv` fails or is pending.
+#[cfg(feature = "bytes")]
+fn poll_recv_bytes_mut(
+    buf: &mut BytesMut,
+    mut try_recv: impl FnMut(&mut [u8]) -> Poll<io::Result<TryRecvResult>>,
+) -> Poll<io::Result<usize>> {
+    let start = buf.len();
+    let mut spare = (buf.capacity() - start).max(BYTES_MUT_MIN_SPARE);
+    loop {
+        // try_recv() needs an initialized buffer, so the spare capacity is zeroed first.
+        buf.resize(start + spare, 0);
+        let rslt = try_recv(&mut buf[start..]);
+        let size = match rslt {
+            Poll::Ready(Ok(TryRecvResult { size, fit: false })) => {
+                spare = size;
+                continue;
+            }
+            Poll::Ready(Ok(TryRecvResult { size, fit: true })) => size,
+            _ => 0,
+        };
+        buf.truncate(start + size);
+        return rslt.map_ok(|r| r.size);
+    }
 }
 
 /// Futures for asynchronously receiving from IPC channels with message boundaries reliably, without truncation.
@@ -109,6 +254,18 @@ pub trait AsyncReliableRecvMsgExt: AsyncReliableRecvMsg {
         Recv(self, buf)
     }
 
+    /// Same as [`.recv()`](Self::recv), but takes the bigger buffer from the given pool instead of allocating a new one if the message doesn't fit into the one provided.
+    fn recv_pooled<'a, 'b, 'c>(
+        &'a mut self,
+        buf: &'b mut [u8],
+        pool: &'c mut RecvBufPool,
+    ) -> RecvPooled<'a, 'b, 'c, Self>
+    where
+        Self: Unpin,
+    {
+        RecvPooled(self, buf, pool)
+    }
+
     /// Asynchronously attempts to receive one message from the stream into the specified buffer, returning the size of the message, which, depending on whether it was in the `Ok` or `Err` variant, either did fit or did not fit into the provided buffer, respectively; if the operation could not be completed for OS reasons, an error from the outermost `Result` is returned.
     fn try_recv<'a, 'b>(&'a mut self, buf: &'b mut [u8]) -> TryRecv<'a, 'b, Self>
     where
@@ -129,6 +286,16 @@ impl<T: AsyncReliableRecvMsg + Unpin + ?Sized> Future for Recv<'_, '_, T> {
         Pin::new(&mut **slf).poll_recv(cx, buf)
     }
 }
+/// Future type returned by [`.recv_pooled()`](AsyncReliableRecvMsgExt::recv_pooled).
+#[derive(Debug)]
+pub struct RecvPooled<'a, 'b, 'c, T: ?Sized>(&'a mut T, &'b mut [u8], &'c mut RecvBufPool);
+impl<T: AsyncReliableRecvMsg + Unpin + ?Sized> Future for RecvPooled<'_, '_, '_, T> {
+    type Output = io::Result<RecvResult>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let RecvPooled(slf, buf, pool) = self.get_mut();
+        Pin::new(&mut **slf).poll_recv_pooled(cx, buf, pool)
+    }
+}
 /// Future type returned by [`.try_recv()`](AsyncReliableRecvMsgExt::try_recv).
 #[derive(Debug)]
 pub struct TryRecv<'a, 'b, T: ?Sized>(&'a mut T, &'b mut [u8]);
@@ -140,18 +307,6 @@ impl<T: AsyncReliableRecvMsg + Unpin + ?Sized> Future for TryRecv<'_, '_, T> {
     }
 }
 
-/// Marker error indicating that a datagram write operation failed because the amount of bytes which were actually written as reported by the operating system was smaller than the size of the message which was requested to be written.
-///
-/// Always emitted with the `ErrorKind::Other` error type.
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
-pub struct PartialMsgWriteError;
-impl Display for PartialMsgWriteError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str("message write operation wrote less than the size of the message")
-    }
-}
-impl Error for PartialMsgWriteError {}
-
 /// Result type for `.recv()` methods.
 #[derive(Clone, Debug)]
 pub enum RecvResult {
@@ -203,6 +358,13 @@ impl RecvResult {
             Self::Alloc(buf) => buf,
         }
     }
+    /// If `Alloc`, returns the buffer to the given pool so that it could be reused by a later `.recv_pooled()`; if `Fit`, does nothing.
+    #[inline]
+    pub fn recycle_into(self, pool: &mut RecvBufPool) {
+        if let Self::Alloc(buf) = self {
+            pool.recycle(buf);
+        }
+    }
     /// Converts to a `Result<usize, Vec<u8>>`, where `Ok` represents `Fit` and `Err` represents `Alloc`.
     #[inline]
     pub fn into_result(self) -> Result<usize, Vec<u8>> {
@@ -219,6 +381,76 @@ impl From<RecvResult> for Result<usize, Vec<u8>> {
     }
 }
 
+/// A pool of buffers for the messages which don't fit into the buffer passed to `.recv_pooled()` methods.
+///
+/// Buffers are handed back to the pool with [`.recycle()`](Self::recycle) or [`RecvResult::recycle_into`]. At most [`max_retained()`](Self::max_retained) buffers are kept around; those recycled past that limit are deallocated. See the [module-level documentation](self) for an example.
+#[derive(Debug)]
+pub struct RecvBufPool {
+    bufs: Vec<Vec<u8>>,
+    max_retained: usize,
+}
+impl RecvBufPool {
+    /// The number of buffers retained by a pool created with [`new()`](Self::new).
+    pub const DEFAULT_MAX_RETAINED: usize = 8;
+    /// Creates an empty pool which retains up to [`DEFAULT_MAX_RETAINED`](Self::DEFAULT_MAX_RETAINED) buffers.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_max_retained(Self::DEFAULT_MAX_RETAINED)
+    }
+    /// Creates an empty pool which retains up to the given number of buffers.
+    #[inline]
+    pub const fn with_max_retained(max_retained: usize) -> Self {
+        Self {
+            bufs: Vec::new(),
+            max_retained,
+        }
+    }
+    /// Returns the maximum number of buffers the pool retains.
+    #[inline]
+    pub fn max_retained(&self) -> usize {
+        self.max_retained
+    }
+    /// Returns the number of buffers currently in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bufs.len()
+    }
+    /// Returns `true` if there are no buffers in the pool.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bufs.is_empty()
+    }
+    /// Takes an empty buffer with a capacity of at least `capacity` bytes out of the pool, preferring one which doesn't need to be reallocated and allocating a new one if the pool is empty.
+    pub fn take(&mut self, capacity: usize) -> Vec<u8> {
+        let fitting = self.bufs.iter().position(|b| b.capacity() >= capacity);
+        let mut buf = match fitting {
+            Some(idx) => self.bufs.swap_remove(idx),
+            None => self.bufs.pop().unwrap_or_default(),
+        };
+        buf.clear();
+        buf.reserve_exact(capacity);
+        buf
+    }
+    /// Same as [`.take()`](Self::take), but fills the buffer with `size` zero bytes.
+    pub(crate) fn take_zeroed(&mut self, size: usize) -> Vec<u8> {
+        let mut buf = self.take(size);
+        buf.resize(size, 0);
+        buf
+    }
+    /// Puts a buffer into the pool for it to be reused later, or deallocates it if the pool is full.
+    pub fn recycle(&mut self, buf: Vec<u8>) {
+        if self.bufs.len() < self.max_retained && buf.capacity() != 0 {
+            self.bufs.push(buf);
+        }
+    }
+}
+impl Default for RecvBufPool {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Result type for `.try_recv()` methods.
 ///
 /// `Ok` indicates that the message fits in the provided buffer and was successfully received, `Err` indicates that it doesn't and hence wasn't written into the buffer. Both variants' payload is the total size of the message.
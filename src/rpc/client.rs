@@ -0,0 +1,187 @@
+use super::write_messages;
+use crate::{
+    local_socket::{
+        tokio::{LocalSocketStream, OwnedReadHalf},
+        ToLocalSocketName,
+    },
+    typed::{encode, tokio::TypedReceiver, Codec},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    io,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        oneshot,
+    },
+    task::JoinHandle,
+};
+
+/// The client side of an RPC connection, which sends requests of type `Req` to an [`RpcServer`](super::RpcServer) and
+/// receives responses of type `Resp`.
+///
+/// Cloning the client is cheap and yields another handle to the same connection, which is closed once all of them are
+/// dropped. See the [module-level documentation](super) for an example.
+pub struct RpcClient<Req, Resp> {
+    shared: Arc<Shared<Resp>>,
+    _phantom: PhantomData<fn(&Req)>,
+}
+impl<Req: Serialize, Resp: DeserializeOwned + Send + 'static> RpcClient<Req, Resp> {
+    /// Connects to an RPC server. Has to be called within a Tokio runtime.
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Self::new(LocalSocketStream::connect(name).await?).await
+    }
+    /// Creates a client on a stream which is already connected to an RPC server. Has to be called within a Tokio
+    /// runtime, which runs the background tasks that write requests into the stream and dispatch responses to the
+    /// callers.
+    pub async fn new(stream: LocalSocketStream) -> io::Result<Self> {
+        let (reader, writer) = stream.into_split();
+        let calls = Arc::new(Mutex::new(Calls {
+            pending: HashMap::new(),
+            closed: false,
+        }));
+
+        let (queue, queue_rx) = mpsc::unbounded_channel();
+        let writer_calls = Arc::clone(&calls);
+        tokio::spawn(async move {
+            let _ = write_messages(writer, queue_rx).await;
+            lock(&writer_calls).close();
+        });
+        let reader = TypedReceiver::new(reader).await?;
+        let reader = tokio::spawn(dispatch_responses(reader, Arc::clone(&calls)));
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                queue,
+                calls,
+                next_id: AtomicU64::new(0),
+                reader,
+            }),
+            _phantom: PhantomData,
+        })
+    }
+    /// Sends a request and waits for the response to it.
+    ///
+    /// Requests which cannot be serialized are reported as [`InvalidInput`](io::ErrorKind::InvalidInput) errors without
+    /// sending anything. If the connection is lost before the response arrives, a
+    /// [`BrokenPipe`](io::ErrorKind::BrokenPipe) error is returned, and if the server failed to handle the request
+    /// because the handler panicked or the response couldn't be serialized, an [`Other`](io::ErrorKind::Other) error
+    /// is returned.
+    ///
+    /// # Cancel safety
+    /// This method is cancel safe: if the future is dropped, the request is either sent in full or not at all, and
+    /// its response is discarded once it arrives.
+    pub async fn call(&self, request: &Req) -> io::Result<Resp> {
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut msg = Vec::new();
+        encode(&mut msg, Codec::Bincode, &(id, request))?;
+
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut calls = lock(&self.shared.calls);
+            if calls.closed {
+                return Err(connection_lost());
+            }
+            calls.pending.insert(id, sender);
+        }
+        let _guard = PendingGuard {
+            calls: &self.shared.calls,
+            id,
+        };
+        self.shared.queue.send(msg).map_err(|_| connection_lost())?;
+
+        match receiver.await {
+            Ok(Some(response)) => Ok(response),
+            Ok(None) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "the server failed to handle the request",
+            )),
+            Err(_) => Err(connection_lost()),
+        }
+    }
+}
+impl<Req, Resp> Clone for RpcClient<Req, Resp> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<Req, Resp> Debug for RpcClient<Req, Resp> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let calls = lock(&self.shared.calls);
+        f.debug_struct("RpcClient")
+            .field("pending_calls", &calls.pending.len())
+            .field("closed", &calls.closed)
+            .finish()
+    }
+}
+
+struct Shared<Resp> {
+    queue: UnboundedSender<Vec<u8>>,
+    calls: Arc<Mutex<Calls<Resp>>>,
+    next_id: AtomicU64,
+    reader: JoinHandle<()>,
+}
+impl<Resp> Drop for Shared<Resp> {
+    fn drop(&mut self) {
+        // The writer task finishes on its own once the queue is dropped, but the reader would keep waiting for the
+        // server to close the connection, which in turn waits for the reading half to be closed.
+        self.reader.abort();
+    }
+}
+
+/// The callers which are waiting for responses, indexed by request ID.
+struct Calls<Resp> {
+    pending: HashMap<u64, oneshot::Sender<Option<Resp>>>,
+    closed: bool,
+}
+impl<Resp> Calls<Resp> {
+    /// Marks the connection as lost, failing all pending and future calls.
+    fn close(&mut self) {
+        self.closed = true;
+        self.pending.clear();
+    }
+}
+
+fn lock<Resp>(calls: &Mutex<Calls<Resp>>) -> MutexGuard<'_, Calls<Resp>> {
+    // The map is never left in an inconsistent state, so poisoning can be ignored.
+    calls.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Removes the entry of a call from the map when the call finishes or is cancelled.
+struct PendingGuard<'a, Resp> {
+    calls: &'a Mutex<Calls<Resp>>,
+    id: u64,
+}
+impl<Resp> Drop for PendingGuard<'_, Resp> {
+    fn drop(&mut self) {
+        lock(self.calls).pending.remove(&self.id);
+    }
+}
+
+async fn dispatch_responses<Resp: DeserializeOwned>(
+    mut reader: TypedReceiver<(u64, Option<Resp>), OwnedReadHalf>,
+    calls: Arc<Mutex<Calls<Resp>>>,
+) {
+    while let Ok(Some((id, response))) = reader.recv().await {
+        // The caller may have been cancelled in the meantime, in which case the response is dropped.
+        if let Some(sender) = lock(&calls).pending.remove(&id) {
+            let _ = sender.send(response);
+        }
+    }
+    lock(&calls).close();
+}
+
+fn connection_lost() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "the connection to the RPC server was lost")
+}
@@ -0,0 +1,85 @@
+//! A request/response layer on top of the Tokio local sockets, for calling into another process as if it was an
+//! asynchronous function.
+//!
+//! A service is defined by two Serde-serializable types – usually enums – one for the requests and one for the
+//! responses. The [`RpcServer`] accepts connections and calls a handler for every request it receives, running up to a
+//! configurable number of them concurrently, while an [`RpcClient`] sends requests over a single connection and awaits
+//! their responses with [`.call()`](RpcClient::call). The client can be cloned and used from any number of tasks at
+//! once: every request is tagged with an ID, and responses are routed back to the right caller no matter in which order
+//! the server finishes handling them.
+//!
+//! # Example
+//! ```
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! use interprocess::{
+//!     local_socket::LocalSocketNameGen,
+//!     rpc::{RpcClient, RpcServer},
+//! };
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! enum Request {
+//!     Add(u32, u32),
+//!     Greet(String),
+//! }
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! enum Response {
+//!     Sum(u32),
+//!     Greeting(String),
+//! }
+//!
+//! let name = LocalSocketNameGen::new("rpc-example").next().unwrap();
+//!
+//! // Server side:
+//! let server = RpcServer::bind(name.clone())?.max_concurrent_requests(16);
+//! tokio::spawn(async move {
+//!     server
+//!         .serve(|request| async move {
+//!             match request {
+//!                 Request::Add(a, b) => Response::Sum(a + b),
+//!                 Request::Greet(name) => Response::Greeting(format!("Hello, {name}!")),
+//!             }
+//!         })
+//!         .await
+//! });
+//!
+//! // Client side:
+//! let client = RpcClient::<Request, Response>::connect(name).await?;
+//! assert_eq!(client.call(&Request::Add(2, 3)).await?, Response::Sum(5));
+//! assert_eq!(
+//!     client.call(&Request::Greet("client".to_string())).await?,
+//!     Response::Greeting("Hello, client!".to_string()),
+//! );
+//! # Ok(()) }
+//! ```
+//!
+//! # Wire format
+//! The connection consists of a [typed channel](crate::typed) in each direction. Requests are sent as tuples of a
+//! 64-bit request ID and the request, and responses as tuples of the ID of the request they answer and
+//! `Option<Response>`, which is `None` if the handler panicked or the response could not be serialized.
+
+mod client;
+mod server;
+pub use {client::*, server::*};
+
+use crate::{
+    local_socket::tokio::OwnedWriteHalf,
+    typed::{header, Codec},
+};
+use std::io;
+use tokio::{io::AsyncWriteExt, sync::mpsc::UnboundedReceiver};
+
+/// The maximum number of requests handled concurrently by an [`RpcServer`] unless configured otherwise.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 64;
+
+/// Writes the header of the typed channel and then every encoded message received from the queue, until all senders
+/// are dropped or writing fails. Running this in a dedicated task keeps callers from leaving partially written
+/// messages in the stream when they're cancelled.
+async fn write_messages(mut writer: OwnedWriteHalf, mut queue: UnboundedReceiver<Vec<u8>>) -> io::Result<()> {
+    writer.write_all(&header(Codec::Bincode)).await?;
+    while let Some(msg) = queue.recv().await {
+        writer.write_all(&msg).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use super::{write_messages, DEFAULT_MAX_CONCURRENT_REQUESTS};
+use crate::{
+    local_socket::{
+        tokio::{LocalSocketListener, LocalSocketStream},
+        ListenerClosedError, ToLocalSocketName,
+    },
+    typed::{encode, tokio::TypedReceiver, Codec},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    io,
+    marker::PhantomData,
+    sync::Arc,
+};
+use tokio::sync::{mpsc, Semaphore};
+
+/// The server side of RPC connections, which receives requests of type `Req` from [`RpcClient`](super::RpcClient)s
+/// and answers them with responses of type `Resp` produced by a handler.
+///
+/// See the [module-level documentation](super) for an example.
+pub struct RpcServer<Req, Resp> {
+    listener: LocalSocketListener,
+    max_concurrent_requests: usize,
+    _phantom: PhantomData<fn(Req) -> Resp>,
+}
+impl<Req, Resp> RpcServer<Req, Resp>
+where
+    Req: DeserializeOwned + Send + 'static,
+    Resp: Serialize + Send + 'static,
+{
+    /// Creates a server which accepts connections from the given listener.
+    pub fn new(listener: LocalSocketListener) -> Self {
+        Self {
+            listener,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            _phantom: PhantomData,
+        }
+    }
+    /// Creates a server listening on the specified local socket name. Has to be called within a Tokio runtime.
+    pub fn bind<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        LocalSocketListener::bind(name).map(Self::new)
+    }
+    /// Sets the maximum number of requests which are handled at the same time, across all connections. Defaults to
+    /// [`DEFAULT_MAX_CONCURRENT_REQUESTS`].
+    ///
+    /// Once the limit is reached, the server stops reading requests until one of the handlers finishes, which
+    /// eventually makes clients wait as well. [Serving](Self::serve) fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the limit is zero.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+    /// Borrows the listener, which can be used to [close](LocalSocketListener::close) it and thus stop the server.
+    #[inline]
+    pub fn listener(&self) -> &LocalSocketListener {
+        &self.listener
+    }
+    /// Accepts connections and handles the requests received over them by calling the handler, each in its own task,
+    /// until the listener is closed.
+    ///
+    /// Returns `Ok(())` once the listener is closed, and an error if accepting a connection fails for any other
+    /// reason. Connections which are already established keep being served in the background after that, as they are
+    /// when the future is dropped. A connection is closed once the client disconnects or sends malformed data.
+    ///
+    /// If the handler panics, the client is told that the request failed and the connection remains usable.
+    pub async fn serve<F, Fut>(&self, handler: F) -> io::Result<()>
+    where
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Resp> + Send + 'static,
+    {
+        if self.max_concurrent_requests == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the maximum amount of concurrent requests must not be zero",
+            ));
+        }
+        let limit = Arc::new(Semaphore::new(self.max_concurrent_requests.min(Semaphore::MAX_PERMITS)));
+        let handler = Arc::new(handler);
+        loop {
+            let stream = match self.listener.accept().await {
+                Ok(stream) => stream,
+                Err(e) if ListenerClosedError::is_in(&e) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            tokio::spawn(serve_connection(stream, Arc::clone(&handler), Arc::clone(&limit)));
+        }
+    }
+}
+impl<Req, Resp> Debug for RpcServer<Req, Resp> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcServer")
+            .field("listener", &self.listener)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .finish()
+    }
+}
+
+async fn serve_connection<Req, Resp, F, Fut>(stream: LocalSocketStream, handler: Arc<F>, limit: Arc<Semaphore>)
+where
+    Req: DeserializeOwned + Send + 'static,
+    Resp: Serialize + Send + 'static,
+    F: Fn(Req) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Resp> + Send + 'static,
+{
+    let (reader, writer) = stream.into_split();
+    let Ok(mut reader) = TypedReceiver::<(u64, Req), _>::new(reader).await else {
+        return;
+    };
+    // The writer finishes once the loop below and all of the handlers are done with the queue.
+    let (queue, queue_rx) = mpsc::unbounded_channel();
+    tokio::spawn(write_messages(writer, queue_rx));
+
+    while let Ok(Some((id, request))) = reader.recv().await {
+        let Ok(permit) = Arc::clone(&limit).acquire_owned().await else {
+            return;
+        };
+        let handler = Arc::clone(&handler);
+        let queue = queue.clone();
+        tokio::spawn(async move {
+            // Running the handler in a task of its own turns a panic into an error which can be reported to the
+            // client.
+            let response = tokio::spawn(handler(request)).await.ok();
+            drop(permit);
+            let mut msg = Vec::new();
+            if encode(&mut msg, Codec::Bincode, &(id, &response)).is_err() {
+                msg.clear();
+                encode(&mut msg, Codec::Bincode, &(id, None::<Resp>)).expect("failed to serialize an empty response");
+            }
+            let _ = queue.send(msg);
+        });
+    }
+}
@@ -0,0 +1,183 @@
+//! Waiting for any of several listeners, streams and events at once, without an asynchronous runtime.
+//!
+//! A server which handles a handful of clients on one thread needs to know which of them has something to say, which
+//! the waiting methods of the individual objects can't tell it. A [`Select`] is a set of objects, each paired with
+//! what it's being waited for – a listener having a client to accept, a stream being readable or writable or a
+//! [named event](crate::sync::NamedEvent) being notified – and [`.wait()`](Select::wait) blocks until at least one of
+//! them is ready, returning the keys of all ready ones:
+//! ```no_run
+//! use interprocess::{
+//!     local_socket::{LocalSocketListener, LocalSocketStream},
+//!     select::Select,
+//! };
+//! use std::time::Duration;
+//!
+//! let listener = LocalSocketListener::bind("@example_select.sock")?;
+//! listener.set_nonblocking(true)?;
+//! let conn = LocalSocketStream::connect("@example_other.sock")?;
+//! conn.set_nonblocking(true)?;
+//!
+//! let mut select = Select::new();
+//! let accept = select.accept(&listener);
+//! let read = select.readable(&conn);
+//! for key in select.wait(Some(Duration::from_secs(5)))? {
+//!     if key == accept {
+//!         let _client = listener.accept()?;
+//!     } else if key == read {
+//!         // Read from `conn` until it fails with WouldBlock...
+//!     }
+//! }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+//!
+//! Readiness means that the next operation is unlikely to block, not that it's guaranteed not to – another thread might
+//! accept the client or read the data first, and a listener with a [connection
+//! limit](crate::local_socket::LocalSocketListenerOptions::max_concurrent_connections) may have reached it. The objects
+//! should thus be in nonblocking mode, and operations on them should be retried until they fail with
+//! [`WouldBlock`](io::ErrorKind::WouldBlock).
+//!
+//! # Platform-specific behavior
+//! ## Unix
+//! Listeners and streams are waited for with a single `poll` call. Named events cannot be waited for together with file
+//! descriptors, so if there are any in the set, the call is cut short every 10 milliseconds to check them.
+//! ## Windows
+//! Local sockets are not opened for overlapped I/O, which leaves `WaitForMultipleObjects` with nothing to wait on, so
+//! the objects in the set are checked in turn at short intervals, which may delay the wakeup by a few milliseconds.
+
+#[cfg(not(target_os = "android"))]
+use crate::sync::NamedEvent;
+use {
+    crate::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io,
+        time::{Duration, Instant},
+    },
+};
+
+impmod! {select,
+    wait_sockets
+}
+
+/// How often named events in the set are checked while waiting for listeners and streams on Unix.
+#[cfg(not(target_os = "android"))]
+const EVENT_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A set of objects to wait for at once. See the [module-level documentation](self) for more.
+///
+/// Every object added to the set is given a key, which is its position in the set, and is returned by
+/// [`.wait()`](Self::wait) once the object is ready. The same object may be added several times, for example to wait
+/// for a stream to become either readable or writable.
+#[derive(Debug, Default)]
+pub struct Select<'a> {
+    entries: Vec<Entry<'a>>,
+}
+#[derive(Debug)]
+pub(crate) enum Entry<'a> {
+    Accept(&'a LocalSocketListener),
+    Readable(&'a LocalSocketStream),
+    Writable(&'a LocalSocketStream),
+    #[cfg(not(target_os = "android"))]
+    Event {
+        event: &'a NamedEvent,
+        /// The notification count of the event when it was last reported, or when it was added.
+        seq: u32,
+    },
+}
+impl<'a> Select<'a> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    fn add(&mut self, entry: Entry<'a>) -> usize {
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+    /// Adds a listener which is ready once it has a client to accept, returning the key of the entry.
+    ///
+    /// A [closed](LocalSocketListener::close) listener counts as ready, since accepting then fails right away.
+    pub fn accept(&mut self, listener: &'a LocalSocketListener) -> usize {
+        self.add(Entry::Accept(listener))
+    }
+    /// Adds a stream which is ready once reading from it would not block, returning the key of the entry.
+    ///
+    /// As with [`LocalSocketStream::wait_readable()`], this includes the other side having closed the connection.
+    pub fn readable(&mut self, stream: &'a LocalSocketStream) -> usize {
+        self.add(Entry::Readable(stream))
+    }
+    /// Adds a stream which is ready once writing to it would not block, returning the key of the entry.
+    ///
+    /// On Windows, such a stream is always ready, for the reasons described at
+    /// [`LocalSocketStream::wait_writable()`].
+    pub fn writable(&mut self, stream: &'a LocalSocketStream) -> usize {
+        self.add(Entry::Writable(stream))
+    }
+    /// Adds a named event which is ready once it has been notified, returning the key of the entry.
+    ///
+    /// Only notifications sent after the event was added are taken into account, and each of them is reported by one
+    /// call to [`.wait()`](Self::wait) – the same notification does not make the event ready again. Notifications which
+    /// arrive while no thread is in `.wait()` are still noticed by the next call.
+    #[cfg(not(target_os = "android"))]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(not(target_os = "android"))))]
+    pub fn event(&mut self, event: &'a NamedEvent) -> usize {
+        let seq = event.inner.seq();
+        self.add(Entry::Event { event, seq })
+    }
+    /// Returns the amount of entries in the set.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Returns `true` if there are no entries in the set, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Waits until at least one of the objects in the set is ready, returning the keys of all ready ones in ascending
+    /// order, or an empty list if the timeout expires first. No timeout means waiting indefinitely, which, for an empty
+    /// set, means forever.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<usize>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut ready = Vec::new();
+        loop {
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let slice = match self.has_events() {
+                #[cfg(not(target_os = "android"))]
+                true => Some(remaining.map_or(EVENT_CHECK_INTERVAL, |r| r.min(EVENT_CHECK_INTERVAL))),
+                _ => remaining,
+            };
+            wait_sockets(&self.entries, slice, &mut ready)?;
+            self.check_events(&mut ready);
+            if !ready.is_empty() {
+                ready.sort_unstable();
+                ready.dedup();
+                return Ok(ready);
+            }
+            if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                return Ok(ready);
+            }
+        }
+    }
+    fn has_events(&self) -> bool {
+        #[cfg(not(target_os = "android"))]
+        {
+            self.entries.iter().any(|entry| matches!(entry, Entry::Event { .. }))
+        }
+        #[cfg(target_os = "android")]
+        {
+            false
+        }
+    }
+    /// Reports the events which have been notified since they were last reported.
+    fn check_events(&mut self, ready: &mut Vec<usize>) {
+        #[cfg(not(target_os = "android"))]
+        for (key, entry) in self.entries.iter_mut().enumerate() {
+            if let Entry::Event { event, seq } = entry {
+                let current = event.inner.seq();
+                if current != *seq {
+                    *seq = current;
+                    ready.push(key);
+                }
+            }
+        }
+        #[cfg(target_os = "android")]
+        let _ = ready;
+    }
+}
@@ -0,0 +1,620 @@
+//! An allocator which carves a shared memory mapping into individually allocated values, along with boxes and vectors
+//! which live inside of it.
+//!
+//! Exchanging structured data through shared memory normally means either serializing it into a flat buffer or laying
+//! out the whole mapping as one fixed struct. A [`ShmArena`] instead lets processes allocate values in the mapping as
+//! they go, with [`ShmBox`] and [`ShmVec`] as the owning containers. Since every process maps the memory at a
+//! different address, nothing inside of the mapping can hold ordinary pointers: allocations are identified by
+//! [`ShmOffset`]s relative to the start of the mapping instead, which can be sent to another process through any
+//! channel or stored inside of shared data structures, and resolved by it in its own mapping.
+//!
+//! Handing over a container is a transfer of ownership: the sending side turns it into an offset with `.into_offset()`
+//! and stops using it, and the receiving side takes it over with the unsafe `from_offset()` constructor. Only
+//! [plain old data](bytemuck::Pod) can be stored, which rules out types that own memory in the heap of one process or
+//! need to be dropped.
+//!
+//! The allocator is deliberately simple. Memory is taken from the end of the used space until it runs out, and freed
+//! blocks are put on a free list from which later allocations are served on a first-fit basis, with large blocks being
+//! split but adjacent free blocks never being merged back together. Allocations are aligned to 16 bytes, which is also
+//! the maximum supported alignment. The state of the allocator is protected by a spinlock inside of the mapping,
+//! which is only held for the duration of a single allocation or deallocation – a process which crashes while holding
+//! it, however, leaves the arena unusable. The processes sharing an arena are trusted not to corrupt it, and doing so
+//! can make allocation misbehave in arbitrary ways.
+//!
+//! # Example
+//! ```
+//! use interprocess::shared_memory::{
+//!     arena::{ShmArena, ShmBox, ShmVec},
+//!     SharedMemory,
+//! };
+//!
+//! let name = format!("interprocess-doctest-arena-{}", std::process::id());
+//! let shm = SharedMemory::create(&name, 64 * 1024)?;
+//! let arena = ShmArena::init(shm.map()?)?;
+//! let mut primes = ShmVec::<u32>::new_in(&arena)?;
+//! primes.extend_from_slice(&[2, 3, 5, 7, 11])?;
+//! let count = ShmBox::new_in(primes.len() as u64, &arena)?;
+//! let (primes, count) = (primes.into_offset(), count.into_offset());
+//!
+//! // Would normally be done in another process, with the offsets sent to it through a local socket or a pipe.
+//! let arena = ShmArena::open(SharedMemory::open(&name)?.map()?)?;
+//! unsafe {
+//!     // SAFETY: the offsets were produced from containers of the same types, which are no longer used
+//!     let primes = ShmVec::from_offset(primes, &arena);
+//!     let count = ShmBox::from_offset(count, &arena);
+//!     assert_eq!(&*primes, &[2, 3, 5, 7, 11]);
+//!     assert_eq!(*count, 5);
+//! }
+//!
+//! # #[cfg(unix)] {
+//! use interprocess::os::unix::shared_memory::SharedMemoryExt;
+//! shm.unlink()?;
+//! # }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::SharedMemoryMapping;
+use bytemuck::{Pod, Zeroable};
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    hint, io,
+    marker::PhantomData,
+    mem::{align_of, size_of, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    ptr, slice,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    thread,
+};
+
+/// Identifies a shared memory mapping as an arena, and changes whenever the layout of the arena does.
+const MAGIC: u64 = u64::from_le_bytes(*b"ipcarna1");
+/// The alignment of every allocation, which is also the granularity of their sizes.
+const ALIGN: u64 = 16;
+/// The size of the block header that precedes every allocation.
+const BLOCK_HEADER: u64 = 16;
+/// The offset of the first block, right after the arena header.
+const DATA_START: u64 = (size_of::<Header>() as u64 + ALIGN - 1) / ALIGN * ALIGN;
+/// How many times the lock is tried before yielding to other threads between attempts.
+const SPIN_LIMIT: u32 = 128;
+
+/// The layout of the beginning of the mapping, which is followed by the blocks.
+#[repr(C)]
+struct Header {
+    /// Set to [`MAGIC`] once the creator is done initializing the arena.
+    magic: AtomicU64,
+    /// The size of the arena, including the header, which is not necessarily that of the mapping of an opener.
+    capacity: AtomicU64,
+    lock: AtomicU32,
+    /// The offset of the first byte which has never been allocated, only accessed with the lock held.
+    bump: AtomicU64,
+    /// The offset of the first free block, or zero if there is none, only accessed with the lock held.
+    free_list: AtomicU64,
+}
+
+/// An allocator for values in a shared memory mapping.
+///
+/// See the [module-level documentation](self) for more.
+pub struct ShmArena {
+    mapping: SharedMemoryMapping,
+    capacity: u64,
+}
+impl ShmArena {
+    /// Sets up a new arena in the given mapping, discarding whatever was in it before.
+    ///
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the mapping is read-only, and with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if it's too small to hold a single allocation.
+    pub fn init(mapping: SharedMemoryMapping) -> io::Result<Self> {
+        check_writable(&mapping)?;
+        let capacity = mapping.len() as u64;
+        if capacity < DATA_START + BLOCK_HEADER + ALIGN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the mapping is too small to hold a shared memory arena",
+            ));
+        }
+        let arena = Self { mapping, capacity };
+        let header = arena.header();
+        header.capacity.store(capacity, Ordering::Relaxed);
+        header.lock.store(0, Ordering::Relaxed);
+        header.bump.store(DATA_START, Ordering::Relaxed);
+        header.free_list.store(0, Ordering::Relaxed);
+        header.magic.store(MAGIC, Ordering::Release);
+        Ok(arena)
+    }
+    /// Opens an arena which was set up in another mapping of the same shared memory object with
+    /// [`init()`](Self::init).
+    ///
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the mapping is read-only, and with
+    /// [`InvalidData`](io::ErrorKind::InvalidData) if the mapping doesn't contain an arena.
+    pub fn open(mapping: SharedMemoryMapping) -> io::Result<Self> {
+        check_writable(&mapping)?;
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the mapping does not contain a shared memory arena",
+            )
+        };
+        if (mapping.len() as u64) < DATA_START {
+            return Err(invalid());
+        }
+        let header = unsafe { &*(mapping.as_ptr() as *const Header) };
+        if header.magic.load(Ordering::Acquire) != MAGIC {
+            return Err(invalid());
+        }
+        let capacity = header.capacity.load(Ordering::Relaxed);
+        // On Windows, the mappings of openers can be larger than that of the creator, but never smaller.
+        if capacity > mapping.len() as u64 {
+            return Err(invalid());
+        }
+        Ok(Self { mapping, capacity })
+    }
+    /// Returns the size of the space from which allocations are made, in bytes. Every allocation takes up 16 bytes of
+    /// it for bookkeeping in addition to its size, which is rounded up to a multiple of 16 bytes.
+    pub fn capacity(&self) -> usize {
+        (self.capacity - DATA_START) as usize
+    }
+    /// Returns a pointer to the value with the given offset in the mapping of the current process, which can be used
+    /// to follow offsets stored inside of other values without taking ownership of them.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the value doesn't fit into the arena at that
+    /// offset, or if the offset is not suitably aligned for `T`.
+    pub fn ptr<T: Pod>(&self, offset: ShmOffset<T>) -> io::Result<*mut T> {
+        let fits = offset
+            .offset
+            .checked_add(size_of::<T>() as u64)
+            .map_or(false, |end| offset.offset >= DATA_START && end <= self.capacity);
+        if !fits || offset.offset % align_of::<T>() as u64 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the offset does not point to a value of the requested type inside of the arena",
+            ));
+        }
+        Ok(self.at(offset.offset) as *mut T)
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*(self.mapping.as_ptr() as *const Header) }
+    }
+    fn at(&self, offset: u64) -> *mut u8 {
+        self.mapping.as_mut_ptr().wrapping_add(offset as usize)
+    }
+    /// Accesses the size field of the header of the block at the given offset.
+    fn block_size(&self, block: u64) -> *mut u64 {
+        self.at(block) as *mut u64
+    }
+    /// Accesses the field of the header of the free block at the given offset which links it to the next one.
+    fn block_next(&self, block: u64) -> *mut u64 {
+        self.at(block + 8) as *mut u64
+    }
+    fn lock(&self) -> ArenaLock<'_> {
+        let lock = &self.header().lock;
+        let mut attempts = 0;
+        while lock
+            .compare_exchange_weak(0, 1, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            if attempts < SPIN_LIMIT {
+                attempts += 1;
+                hint::spin_loop();
+            } else {
+                thread::yield_now();
+            }
+        }
+        ArenaLock(lock)
+    }
+
+    /// Allocates space for `size` bytes with the given alignment, returning the offset of the allocation.
+    fn alloc(&self, size: usize, align: usize) -> io::Result<u64> {
+        check_align(align)?;
+        let size = (size.max(1) as u64)
+            .checked_add(ALIGN - 1)
+            .map(|size| size / ALIGN * ALIGN)
+            .ok_or_else(out_of_space)?;
+
+        let header = self.header();
+        let _lock = self.lock();
+        unsafe {
+            // SAFETY: the headers of free blocks are only accessed with the lock held
+            let mut prev: Option<u64> = None;
+            let mut block = header.free_list.load(Ordering::Relaxed);
+            while block != 0 {
+                let block_size = *self.block_size(block);
+                let next = *self.block_next(block);
+                if block_size >= size {
+                    let replacement = if block_size - size >= BLOCK_HEADER + ALIGN {
+                        // Splitting off the rest of the block and leaving it in the list in place of this one.
+                        let rest = block + BLOCK_HEADER + size;
+                        *self.block_size(rest) = block_size - size - BLOCK_HEADER;
+                        *self.block_next(rest) = next;
+                        *self.block_size(block) = size;
+                        rest
+                    } else {
+                        next
+                    };
+                    match prev {
+                        Some(prev) => *self.block_next(prev) = replacement,
+                        None => header.free_list.store(replacement, Ordering::Relaxed),
+                    }
+                    return Ok(block + BLOCK_HEADER);
+                }
+                prev = Some(block);
+                block = next;
+            }
+
+            let block = header.bump.load(Ordering::Relaxed);
+            let end = block
+                .checked_add(BLOCK_HEADER)
+                .and_then(|start| start.checked_add(size))
+                .filter(|&end| end <= self.capacity)
+                .ok_or_else(out_of_space)?;
+            header.bump.store(end, Ordering::Relaxed);
+            *self.block_size(block) = size;
+            Ok(block + BLOCK_HEADER)
+        }
+    }
+    /// Returns the allocation with the given offset to the free list.
+    fn dealloc(&self, offset: u64) {
+        let header = self.header();
+        let block = offset - BLOCK_HEADER;
+        let _lock = self.lock();
+        unsafe {
+            // SAFETY: as above
+            *self.block_next(block) = header.free_list.load(Ordering::Relaxed);
+        }
+        header.free_list.store(block, Ordering::Relaxed);
+    }
+}
+impl Debug for ShmArena {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmArena")
+            .field("mapping", &self.mapping)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+fn check_align(align: usize) -> io::Result<()> {
+    if align as u64 > ALIGN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "shared memory arenas do not support alignments greater than 16 bytes",
+        ));
+    }
+    Ok(())
+}
+fn out_of_space() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::OutOfMemory,
+        "the shared memory arena has run out of space",
+    )
+}
+fn check_writable(mapping: &SharedMemoryMapping) -> io::Result<()> {
+    if mapping.is_read_only() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "shared memory arenas cannot be used through read-only mappings",
+        ));
+    }
+    Ok(())
+}
+
+/// Releases the lock of the allocator on drop.
+struct ArenaLock<'a>(&'a AtomicU32);
+impl Drop for ArenaLock<'_> {
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::Release);
+    }
+}
+
+/// The location of a value inside of a [`ShmArena`], relative to the start of the mapping, which is the same in every
+/// process.
+///
+/// Offsets are plain old data themselves, and can thus be stored inside of values in the arena to link them together.
+/// The offsets of [vectors](ShmVec) have the type `ShmOffset<[T]>`.
+#[repr(transparent)]
+pub struct ShmOffset<T: ?Sized> {
+    offset: u64,
+    _phantom: PhantomData<*const T>,
+}
+impl<T: ?Sized> ShmOffset<T> {
+    fn new(offset: u64) -> Self {
+        Self {
+            offset,
+            _phantom: PhantomData,
+        }
+    }
+    /// Returns the offset in bytes from the start of the mapping.
+    pub fn as_u64(self) -> u64 {
+        self.offset
+    }
+    /// Creates an offset from a value returned by [`.as_u64()`](Self::as_u64), as it would be after being sent to
+    /// another process in serialized form.
+    pub fn from_u64(offset: u64) -> Self {
+        Self::new(offset)
+    }
+}
+// The derives would put bounds on T.
+impl<T: ?Sized> Copy for ShmOffset<T> {}
+impl<T: ?Sized> Clone for ShmOffset<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: ?Sized> PartialEq for ShmOffset<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+impl<T: ?Sized> Eq for ShmOffset<T> {}
+impl<T: ?Sized> Hash for ShmOffset<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.offset.hash(state);
+    }
+}
+impl<T: ?Sized> Debug for ShmOffset<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ShmOffset").field(&self.offset).finish()
+    }
+}
+// SAFETY: the offset is a plain integer, and the phantom data takes up no space
+unsafe impl<T: ?Sized> Send for ShmOffset<T> {}
+unsafe impl<T: ?Sized> Sync for ShmOffset<T> {}
+unsafe impl<T: ?Sized> Zeroable for ShmOffset<T> {}
+unsafe impl<T: ?Sized + 'static> Pod for ShmOffset<T> {}
+
+/// A value allocated in a [`ShmArena`], which is freed on drop.
+///
+/// Dereferencing a box gives direct access to the value in shared memory. Boxes are owning, which means that the
+/// value must not be accessed by other processes while the box exists – to hand it over, turn the box into an offset
+/// with [`.into_offset()`](Self::into_offset).
+pub struct ShmBox<'a, T: Pod> {
+    arena: &'a ShmArena,
+    offset: u64,
+    _phantom: PhantomData<T>,
+}
+impl<'a, T: Pod> ShmBox<'a, T> {
+    /// Allocates space for the value in the arena and moves it there.
+    ///
+    /// Fails with [`OutOfMemory`](io::ErrorKind::OutOfMemory) if the arena has no space left, and with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the alignment of `T` is greater than 16 bytes.
+    pub fn new_in(value: T, arena: &'a ShmArena) -> io::Result<Self> {
+        let offset = arena.alloc(size_of::<T>(), align_of::<T>())?;
+        unsafe {
+            // SAFETY: the allocation is suitably sized and aligned, and not accessed by anything else yet
+            ptr::write(arena.at(offset) as *mut T, value);
+        }
+        Ok(Self {
+            arena,
+            offset,
+            _phantom: PhantomData,
+        })
+    }
+    /// Takes ownership of a value whose box was turned into an offset with [`.into_offset()`](Self::into_offset),
+    /// possibly in another process.
+    ///
+    /// # Safety
+    /// The offset must have been produced by a box of the same type in an arena in the same shared memory object, and
+    /// it must not be taken over more than once.
+    pub unsafe fn from_offset(offset: ShmOffset<T>, arena: &'a ShmArena) -> Self {
+        Self {
+            arena,
+            offset: offset.offset,
+            _phantom: PhantomData,
+        }
+    }
+    /// Returns the offset of the value, which can be stored inside of other values to refer to this one.
+    pub fn offset(&self) -> ShmOffset<T> {
+        ShmOffset::new(self.offset)
+    }
+    /// Gives up ownership of the value without freeing it, returning its offset, which can be sent to another process
+    /// for it to take over the value with [`from_offset()`](Self::from_offset).
+    pub fn into_offset(self) -> ShmOffset<T> {
+        ManuallyDrop::new(self).offset()
+    }
+}
+impl<T: Pod> Deref for ShmBox<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*(self.arena.at(self.offset) as *const T) }
+    }
+}
+impl<T: Pod> DerefMut for ShmBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *(self.arena.at(self.offset) as *mut T) }
+    }
+}
+impl<T: Pod> Drop for ShmBox<'_, T> {
+    fn drop(&mut self) {
+        self.arena.dealloc(self.offset);
+    }
+}
+impl<T: Pod + Debug> Debug for ShmBox<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// The part of a vector which is stored in the arena, so that the vector can be handed over as a whole.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct VecHeader {
+    /// The offset of the elements, or zero if nothing has been allocated for them yet.
+    data: u64,
+    len: u64,
+    capacity: u64,
+}
+
+/// A growable array allocated in a [`ShmArena`], which is freed on drop.
+///
+/// Like a `Vec`, the vector dereferences to a slice of its elements, and reallocates them when it runs out of
+/// capacity. The length and the capacity are kept in the arena along with the elements, which is why creating even an
+/// empty vector allocates. As with [`ShmBox`], the vector must not be accessed by other processes while it exists.
+pub struct ShmVec<'a, T: Pod> {
+    arena: &'a ShmArena,
+    header: u64,
+    _phantom: PhantomData<T>,
+}
+impl<'a, T: Pod> ShmVec<'a, T> {
+    /// Creates an empty vector in the arena.
+    ///
+    /// Fails with [`OutOfMemory`](io::ErrorKind::OutOfMemory) if the arena has no space left, and with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the alignment of `T` is greater than 16 bytes.
+    pub fn new_in(arena: &'a ShmArena) -> io::Result<Self> {
+        // Checked early so that vectors can't be created just to fail on the first push.
+        check_align(align_of::<T>())?;
+        let header = arena.alloc(size_of::<VecHeader>(), align_of::<VecHeader>())?;
+        unsafe {
+            ptr::write(
+                arena.at(header) as *mut VecHeader,
+                VecHeader {
+                    data: 0,
+                    len: 0,
+                    capacity: 0,
+                },
+            );
+        }
+        Ok(Self {
+            arena,
+            header,
+            _phantom: PhantomData,
+        })
+    }
+    /// Creates an empty vector in the arena with space for at least `capacity` elements.
+    pub fn with_capacity_in(capacity: usize, arena: &'a ShmArena) -> io::Result<Self> {
+        let mut vec = Self::new_in(arena)?;
+        vec.reserve(capacity)?;
+        Ok(vec)
+    }
+    /// Takes ownership of a vector which was turned into an offset with [`.into_offset()`](Self::into_offset),
+    /// possibly in another process.
+    ///
+    /// # Safety
+    /// The offset must have been produced by a vector of the same element type in an arena in the same shared memory
+    /// object, and it must not be taken over more than once.
+    pub unsafe fn from_offset(offset: ShmOffset<[T]>, arena: &'a ShmArena) -> Self {
+        Self {
+            arena,
+            header: offset.offset,
+            _phantom: PhantomData,
+        }
+    }
+    /// Returns the offset of the vector, which can be stored inside of other values to refer to this one.
+    pub fn offset(&self) -> ShmOffset<[T]> {
+        ShmOffset::new(self.header)
+    }
+    /// Gives up ownership of the vector without freeing it, returning its offset, which can be sent to another process
+    /// for it to take over the vector with [`from_offset()`](Self::from_offset).
+    pub fn into_offset(self) -> ShmOffset<[T]> {
+        ManuallyDrop::new(self).offset()
+    }
+    /// Returns the number of elements that the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec_header().capacity as usize
+    }
+    /// Makes sure that at least `additional` more elements fit into the vector without reallocating.
+    ///
+    /// Fails with [`OutOfMemory`](io::ErrorKind::OutOfMemory) if the arena has no space left, in which case the vector
+    /// is left as it was.
+    pub fn reserve(&mut self, additional: usize) -> io::Result<()> {
+        let VecHeader { data, len, capacity } = *self.vec_header();
+        let required = (len as usize).checked_add(additional).ok_or_else(out_of_space)?;
+        if required <= capacity as usize {
+            return Ok(());
+        }
+        let new_capacity = required.max(capacity as usize * 2).max(4);
+        let size = new_capacity.checked_mul(size_of::<T>()).ok_or_else(out_of_space)?;
+        let new_data = self.arena.alloc(size, align_of::<T>())?;
+        if data != 0 {
+            unsafe {
+                // SAFETY: both allocations are large enough for the elements, and distinct
+                ptr::copy_nonoverlapping(
+                    self.arena.at(data),
+                    self.arena.at(new_data),
+                    len as usize * size_of::<T>(),
+                );
+            }
+            self.arena.dealloc(data);
+        }
+        let header = self.vec_header_mut();
+        header.data = new_data;
+        header.capacity = new_capacity as u64;
+        Ok(())
+    }
+    /// Appends an element to the end of the vector, reallocating it if it's full.
+    ///
+    /// Fails with [`OutOfMemory`](io::ErrorKind::OutOfMemory) if the arena has no space left, in which case the vector
+    /// is left as it was.
+    pub fn push(&mut self, value: T) -> io::Result<()> {
+        self.reserve(1)?;
+        let header = *self.vec_header();
+        unsafe {
+            // SAFETY: the reservation made room for the element
+            ptr::write((self.arena.at(header.data) as *mut T).add(header.len as usize), value);
+        }
+        self.vec_header_mut().len += 1;
+        Ok(())
+    }
+    /// Appends all elements of the slice to the end of the vector, reallocating it if they don't fit.
+    ///
+    /// Fails with [`OutOfMemory`](io::ErrorKind::OutOfMemory) if the arena has no space left, in which case the vector
+    /// is left as it was.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> io::Result<()> {
+        self.reserve(values.len())?;
+        let len = self.len();
+        let header = self.vec_header_mut();
+        header.len += values.len() as u64;
+        self[len..].copy_from_slice(values);
+        Ok(())
+    }
+    /// Removes the last element of the vector and returns it, or `None` if the vector is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = *self.last()?;
+        self.vec_header_mut().len -= 1;
+        Some(value)
+    }
+    /// Removes all elements from the vector, keeping its capacity.
+    pub fn clear(&mut self) {
+        self.vec_header_mut().len = 0;
+    }
+
+    fn vec_header(&self) -> &VecHeader {
+        unsafe { &*(self.arena.at(self.header) as *const VecHeader) }
+    }
+    fn vec_header_mut(&mut self) -> &mut VecHeader {
+        unsafe { &mut *(self.arena.at(self.header) as *mut VecHeader) }
+    }
+}
+impl<T: Pod> Deref for ShmVec<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        let VecHeader { data, len, .. } = *self.vec_header();
+        if data == 0 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.arena.at(data) as *const T, len as usize) }
+    }
+}
+impl<T: Pod> DerefMut for ShmVec<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        let VecHeader { data, len, .. } = *self.vec_header();
+        if data == 0 {
+            return &mut [];
+        }
+        unsafe { slice::from_raw_parts_mut(self.arena.at(data) as *mut T, len as usize) }
+    }
+}
+impl<T: Pod> Drop for ShmVec<'_, T> {
+    fn drop(&mut self) {
+        let data = self.vec_header().data;
+        if data != 0 {
+            self.arena.dealloc(data);
+        }
+        self.arena.dealloc(self.header);
+    }
+}
+impl<T: Pod + Debug> Debug for ShmVec<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
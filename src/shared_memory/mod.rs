@@ -0,0 +1,217 @@
+//! Named shared memory objects, which can be mapped into the address spaces of several processes at once.
+//!
+//! Shared memory is the fastest form of interprocess communication there is, since data written by one process becomes visible to all others without being copied by the kernel. The flip side is that no synchronization of any kind is provided – the processes have to agree on who writes what and when, using either atomics placed inside the shared memory or some other form of IPC.
+//!
+//! A shared memory object is identified by a name, which is used to [create](SharedMemory::create) it in one process and [open](SharedMemory::open) it in another. The object itself only holds the memory: to access it, a [mapping](SharedMemoryMapping) of it has to be made with [`.map()`](SharedMemory::map) or [`.map_read_only()`](SharedMemory::map_read_only). Mappings keep the memory alive on their own, and the `SharedMemory` they came from can be dropped right after they are made.
+//!
+//! With the `bytemuck` feature, mappings can also be [viewed](SharedMemoryMapping::view) as values and slices of plain old data types, with the size and alignment checked, and the data can be prefixed with a [header](VersionedHeader) that makes sure that all processes agree on its layout. The [`arena`] submodule goes further and provides an allocator with boxes and vectors inside of shared memory, for exchanging structured data without serializing it.
+//!
+//! For the common case of streaming messages from one process to another, the [`ring`] submodule provides a ready-made channel built on shared memory.
+//!
+//! # Platform-specific behavior
+//! ## Unix
+//! Backed by POSIX shared memory (`shm_open`). The name is required to consist of a single leading slash followed by one or more non-slash characters, and the slash is prepended automatically if it's missing. Shared memory objects are **persistent**: they outlive all processes using them, and are only destroyed once they are unlinked by name, which is done through the `os::unix::shared_memory::SharedMemoryExt` extension trait. Not available on Android, which doesn't implement POSIX shared memory.
+//!
+//! ## Windows
+//! Backed by file mappings in the paging file (`CreateFileMapping`). Names may use the `Global\` and `Local\` prefixes to select the namespace, with the session-local one being the default. Shared memory objects are **reference-counted**: they are destroyed as soon as the last handle and the last view of them are closed, and there is no way to unlink them explicitly. Windows does not store the size of a file mapping, so the size of an [opened](SharedMemory::open) object is that of the largest view the system allows, which is rounded up to a whole number of pages.
+//!
+//! # Example
+//! ```
+//! use interprocess::shared_memory::SharedMemory;
+//!
+//! let name = format!("interprocess-doctest-{}", std::process::id());
+//! let shm = SharedMemory::create(&name, 4096)?;
+//! let writer = shm.map()?;
+//!
+//! // Would normally be done in another process.
+//! let reader = SharedMemory::open_read_only(&name)?.map_read_only()?;
+//! unsafe {
+//!     // SAFETY: no other process knows about this shared memory object
+//!     writer.as_mut_slice()[..6].copy_from_slice(b"Hello!");
+//!     assert_eq!(&reader.as_slice()[..6], b"Hello!");
+//! }
+//!
+//! # #[cfg(unix)] {
+//! use interprocess::os::unix::shared_memory::SharedMemoryExt;
+//! // Unix shared memory would otherwise persist until reboot.
+//! shm.unlink()?;
+//! # }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+pub mod arena;
+pub mod ring;
+#[cfg(feature = "bytemuck")]
+mod view;
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+pub use view::VersionedHeader;
+
+impmod! {shared_memory,
+    SharedMemory as SharedMemoryImpl,
+    unmap as unmap_impl,
+}
+use crate::Sealed;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io, slice,
+};
+
+/// A named shared memory object.
+///
+/// See the [module-level documentation](self) for more.
+pub struct SharedMemory {
+    // pub(crate) to allow the platform specific extension traits to reach the handle and the name
+    pub(crate) inner: SharedMemoryImpl,
+}
+impl SharedMemory {
+    /// Creates a new shared memory object with the specified name and size in bytes, opening it for both reading and writing.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if a shared memory object with the same name exists already, and with [`InvalidInput`](io::ErrorKind::InvalidInput) if the size is zero. The contents of the memory are initialized with zeroes.
+    pub fn create(name: impl AsRef<OsStr>, size: usize) -> io::Result<Self> {
+        if size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "shared memory objects cannot be empty",
+            ));
+        }
+        SharedMemoryImpl::create(name.as_ref(), size).map(|inner| Self { inner })
+    }
+    /// Opens an existing shared memory object with the specified name for both reading and writing.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such object.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        SharedMemoryImpl::open(name.as_ref(), false).map(|inner| Self { inner })
+    }
+    /// Opens an existing shared memory object with the specified name for reading only. Only
+    /// [read-only mappings](Self::map_read_only) can be made of such objects.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such object.
+    pub fn open_read_only(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        SharedMemoryImpl::open(name.as_ref(), true).map(|inner| Self { inner })
+    }
+    /// Returns the size of the shared memory object in bytes, as it was when the object was created or opened.
+    ///
+    /// On Windows, the size of an opened object is rounded up to a whole number of pages.
+    #[allow(clippy::len_without_is_empty)] // Shared memory objects are never empty.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+    /// Returns `true` if the object was [opened for reading only](Self::open_read_only), `false` otherwise.
+    pub fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+    /// Maps the whole shared memory object into the address space of the current process for reading and writing.
+    ///
+    /// Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the object was
+    /// [opened for reading only](Self::open_read_only).
+    pub fn map(&self) -> io::Result<SharedMemoryMapping> {
+        if self.is_read_only() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "cannot make a writable mapping of a read-only shared memory object",
+            ));
+        }
+        self.inner.map(false).map(|ptr| SharedMemoryMapping {
+            ptr,
+            len: self.len(),
+            read_only: false,
+        })
+    }
+    /// Maps the whole shared memory object into the address space of the current process for reading only.
+    pub fn map_read_only(&self) -> io::Result<SharedMemoryMapping> {
+        self.inner.map(true).map(|ptr| SharedMemoryMapping {
+            ptr,
+            len: self.len(),
+            read_only: true,
+        })
+    }
+}
+impl Debug for SharedMemory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl Sealed for SharedMemory {}
+
+/// A view of a [shared memory object](SharedMemory) in the address space of the current process, unmapped on drop.
+///
+/// Since the memory can be modified by other processes at any moment, no safe references to it can be handed out.
+/// Instead, the mapping can either be accessed through raw pointers (most commonly ones to atomic types placed inside
+/// the memory), or be borrowed as a slice with the unsafe [`.as_slice()`](Self::as_slice) and
+/// [`.as_mut_slice()`](Self::as_mut_slice) methods.
+pub struct SharedMemoryMapping {
+    ptr: *mut u8,
+    len: usize,
+    read_only: bool,
+}
+// SAFETY: the mapping owns nothing thread-specific, and all access to the memory goes through unsafe methods anyway
+unsafe impl Send for SharedMemoryMapping {}
+unsafe impl Sync for SharedMemoryMapping {}
+impl SharedMemoryMapping {
+    /// Takes ownership of a mapping made by platform-specific code, which is unmapped on drop.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn from_raw_parts(ptr: *mut u8, len: usize, read_only: bool) -> Self {
+        Self { ptr, len, read_only }
+    }
+    /// Returns a pointer to the start of the mapping.
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+    /// Returns a mutable pointer to the start of the mapping. Writing through it is only allowed if the mapping is not
+    /// [read-only](Self::is_read_only); otherwise, the process is killed by an access violation.
+    pub fn as_mut_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+    /// Returns the size of the mapping in bytes.
+    #[allow(clippy::len_without_is_empty)] // Mappings are never empty.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if the mapping was made with [`.map_read_only()`](SharedMemory::map_read_only), `false`
+    /// otherwise.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    /// Borrows the memory as a byte slice.
+    ///
+    /// # Safety
+    /// The memory must not be modified for as long as the slice exists, neither through this mapping nor by any other
+    /// mapping of the same object, including ones in other processes.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+    /// Borrows the memory as a mutable byte slice.
+    ///
+    /// # Panics
+    /// If the mapping is [read-only](Self::is_read_only).
+    ///
+    /// # Safety
+    /// The memory must not be accessed for as long as the slice exists, neither through this mapping nor by any other
+    /// mapping of the same object, including ones in other processes.
+    #[allow(clippy::mut_from_ref)] // The exclusivity is up to the caller, as with a raw pointer.
+    pub unsafe fn as_mut_slice(&self) -> &mut [u8] {
+        assert!(
+            !self.read_only,
+            "attempt to mutably borrow a read-only shared memory mapping"
+        );
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+impl Drop for SharedMemoryMapping {
+    fn drop(&mut self) {
+        unmap_impl(self.ptr, self.len);
+    }
+}
+impl Debug for SharedMemoryMapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedMemoryMapping")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("read_only", &self.read_only)
+            .finish()
+    }
+}
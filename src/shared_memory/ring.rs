@@ -0,0 +1,497 @@
+//! Single-producer single-consumer message channels which pass data through a ring buffer in shared memory.
+//!
+//! A ring buffer channel consists of a [`RingSender`] in one process and a [`RingReceiver`] in another, connected by a name. Either side can [create](RingReceiver::create) the channel with a fixed capacity, after which the other side [opens](RingSender::open) it. Messages are byte strings which are copied into shared memory by the sender and out of it by the receiver, without any system calls as long as neither side has to wait for the other. This makes ring buffers considerably faster than sockets and pipes for passing lots of small messages between processes on the same machine.
+//!
+//! When the buffer is empty, the receiver first spins for a short while, and then goes to sleep on a [named event](crate::sync::NamedEvent) which the sender notifies once it writes a message; the same happens to the sender when the buffer is full. Every message takes up 4 bytes of the buffer for its length in addition to its contents, so messages can be at most 4 bytes shorter than the capacity.
+//!
+//! There can only be one sender and one receiver at a time. Once one of the sides is dropped, the other one gets [`BrokenPipe`](io::ErrorKind::BrokenPipe) errors, after receiving all of the remaining messages in the case of the receiver. A new sender or receiver can then open the channel again and pick up where the previous one left off. A side which exits without being dropped is not detected, however.
+//!
+//! # Platform-specific behavior
+//! The channel is made of a [shared memory object](super::SharedMemory) with the name of the channel and two named events, whose names are the name of the channel with `-data` and `-space` appended. On Unix, all three are persistent and have to be unlinked through the `os::unix::shared_memory::RingBufferExt` extension trait. Not available on Android, which has neither shared memory nor named events.
+//!
+//! # Example
+//! ```
+//! use interprocess::shared_memory::ring::{RingReceiver, RingSender};
+//!
+//! let name = format!("interprocess-doctest-ring-{}", std::process::id());
+//! let mut receiver = RingReceiver::create(&name, 4096)?;
+//!
+//! // Would normally be done in another process.
+//! let sender = std::thread::spawn({
+//!     let name = name.clone();
+//!     move || {
+//!         let mut sender = RingSender::open(name)?;
+//!         for i in 0..1000_u32 {
+//!             sender.send(&i.to_le_bytes())?;
+//!         }
+//!         std::io::Result::Ok(())
+//!     }
+//! });
+//! for i in 0..1000_u32 {
+//!     assert_eq!(receiver.recv()?, i.to_le_bytes());
+//! }
+//! sender.join().unwrap()?;
+//!
+//! # #[cfg(unix)] {
+//! use interprocess::os::unix::shared_memory::RingBufferExt;
+//! receiver.unlink()?;
+//! # }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use super::{SharedMemory, SharedMemoryMapping};
+use crate::sync::{open_when_ready, NamedEvent};
+use std::{
+    ffi::{OsStr, OsString},
+    fmt::{self, Debug, Formatter},
+    hint, io,
+    mem::size_of,
+    ptr,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// How many times the state of the buffer is checked before going to sleep.
+const SPIN_LIMIT: u32 = 128;
+/// The size of the length that precedes every message.
+const LEN_PREFIX: u64 = size_of::<u32>() as u64;
+
+/// Places a value on its own cache line, so that the sender and the receiver don't slow each other down by writing
+/// to the same one.
+#[repr(C, align(64))]
+struct CacheAligned<T>(T);
+
+/// The layout of the beginning of the shared memory object, which is followed by the contents of the buffer.
+#[repr(C)]
+struct Header {
+    /// Set once the creator is done initializing the channel.
+    initialized: AtomicU32,
+    capacity: AtomicU64,
+    /// The total amount of bytes written by the sender so far.
+    write_pos: CacheAligned<AtomicU64>,
+    /// The total amount of bytes read by the receiver so far.
+    read_pos: CacheAligned<AtomicU64>,
+    /// The number of threads waiting for the respective events, which are only notified if this is nonzero.
+    sender_waiting: AtomicU32,
+    receiver_waiting: AtomicU32,
+    sender_closed: AtomicU32,
+    receiver_closed: AtomicU32,
+}
+
+fn event_name(name: &OsStr, suffix: &str) -> OsString {
+    let mut event_name = name.to_owned();
+    event_name.push(suffix);
+    event_name
+}
+
+/// The state shared by both sides, along with the handles to the objects that make up the channel.
+pub(crate) struct Ring {
+    mapping: SharedMemoryMapping,
+    capacity: u64,
+    /// Notified by the sender when it writes a message or is dropped.
+    data_event: NamedEvent,
+    /// Notified by the receiver when it reads a message or is dropped.
+    space_event: NamedEvent,
+    pub(crate) name: OsString,
+}
+impl Ring {
+    fn create(name: &OsStr, capacity: usize) -> io::Result<Self> {
+        let size = size_of::<Header>()
+            .checked_add(capacity)
+            .filter(|_| capacity as u64 > LEN_PREFIX);
+        let size = size.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ring buffer capacity must be greater than 4 bytes and fit into the address space",
+            )
+        })?;
+        let shm = SharedMemory::create(name, size)?;
+        let ring = shm.map().and_then(|mapping| {
+            let data_event = NamedEvent::create(event_name(name, "-data"))?;
+            let space_event = NamedEvent::create(event_name(name, "-space"))?;
+            Ok(Self {
+                mapping,
+                capacity: capacity as u64,
+                data_event,
+                space_event,
+                name: name.to_owned(),
+            })
+        });
+        let ring = match ring {
+            Ok(ring) => ring,
+            Err(e) => {
+                // Don't leave behind a channel that can never be opened.
+                #[cfg(unix)]
+                let _ = unlink(name);
+                return Err(e);
+            }
+        };
+        let header = ring.header();
+        header.capacity.store(capacity as u64, Ordering::Relaxed);
+        header.initialized.store(1, Ordering::Release);
+        Ok(ring)
+    }
+    fn open(name: &OsStr) -> io::Result<Self> {
+        open_when_ready(|| {
+            // The creator might not have gotten to setting the size of the shared memory or initializing the channel
+            // yet.
+            let shm = SharedMemory::open(name)?;
+            if shm.len() < size_of::<Header>() {
+                return Ok(None);
+            }
+            let mapping = shm.map()?;
+            let header = unsafe {
+                // SAFETY: as in header(), which can't be used before the ring is constructed
+                &*(mapping.as_ptr() as *const Header)
+            };
+            if header.initialized.load(Ordering::Acquire) == 0 {
+                return Ok(None);
+            }
+            let capacity = header.capacity.load(Ordering::Relaxed);
+            if capacity <= LEN_PREFIX || ((mapping.len() - size_of::<Header>()) as u64) < capacity {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the shared memory object is not a ring buffer",
+                ));
+            }
+            // The events are created before the channel is marked as initialized.
+            Ok(Some(Self {
+                mapping,
+                capacity,
+                data_event: NamedEvent::open(event_name(name, "-data"))?,
+                space_event: NamedEvent::open(event_name(name, "-space"))?,
+                name: name.to_owned(),
+            }))
+        })
+    }
+    fn header(&self) -> &Header {
+        unsafe {
+            // SAFETY: the mapping is big enough and page-aligned, and the header only consists of atomics
+            &*(self.mapping.as_ptr() as *const Header)
+        }
+    }
+    fn data(&self) -> *mut u8 {
+        unsafe { self.mapping.as_mut_ptr().add(size_of::<Header>()) }
+    }
+    /// Copies bytes into the buffer at the given position, wrapping around at the end.
+    ///
+    /// # Safety
+    /// The region must be owned by the sender, i.e. lie between the write position and the read position plus the
+    /// capacity.
+    unsafe fn copy_in(&self, pos: u64, src: &[u8]) {
+        let start = (pos % self.capacity) as usize;
+        let first = src.len().min(self.capacity as usize - start);
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), self.data().add(start), first);
+            ptr::copy_nonoverlapping(src.as_ptr().add(first), self.data(), src.len() - first);
+        }
+    }
+    /// Copies bytes out of the buffer at the given position, wrapping around at the end.
+    ///
+    /// # Safety
+    /// The region must be owned by the receiver, i.e. lie between the read position and the write position.
+    unsafe fn copy_out(&self, pos: u64, dst: &mut [u8]) {
+        let start = (pos % self.capacity) as usize;
+        let first = dst.len().min(self.capacity as usize - start);
+        unsafe {
+            ptr::copy_nonoverlapping(self.data().add(start), dst.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(self.data(), dst.as_mut_ptr().add(first), dst.len() - first);
+        }
+    }
+    /// Spins for a while, and then sleeps on the event, until the condition becomes true or the timeout runs out.
+    fn wait(
+        event: &NamedEvent,
+        waiting: &AtomicU32,
+        mut condition: impl FnMut() -> bool,
+        timeout: Option<Duration>,
+    ) -> io::Result<bool> {
+        for _ in 0..SPIN_LIMIT {
+            if condition() {
+                return Ok(true);
+            }
+            hint::spin_loop();
+        }
+        // The other side checks the counter after updating its position, and the condition is checked after
+        // incrementing the counter, so either this side sees the update or the other side sees the waiter.
+        waiting.fetch_add(1, Ordering::SeqCst);
+        let rslt = match timeout {
+            None => event.wait_until(&mut condition).map(|()| true),
+            Some(timeout) => event.wait_until_timeout(&mut condition, timeout),
+        };
+        waiting.fetch_sub(1, Ordering::SeqCst);
+        rslt
+    }
+    fn has_data(&self, read_pos: u64) -> bool {
+        let header = self.header();
+        header.write_pos.0.load(Ordering::SeqCst) != read_pos || header.sender_closed.load(Ordering::SeqCst) != 0
+    }
+    fn wait_for_data(&self, read_pos: u64, timeout: Option<Duration>) -> io::Result<bool> {
+        let header = self.header();
+        Self::wait(
+            &self.data_event,
+            &header.receiver_waiting,
+            || self.has_data(read_pos),
+            timeout,
+        )
+    }
+}
+impl Debug for Ring {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ring")
+            .field("mapping", &self.mapping)
+            .field("capacity", &self.capacity)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the message does not fit into the ring buffer",
+    )
+}
+
+/// The sending side of a ring buffer channel.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct RingSender {
+    pub(crate) ring: Ring,
+    write_pos: u64,
+}
+impl RingSender {
+    /// Creates a new channel with the specified name and capacity in bytes, returning its sending side.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if a channel with the same name exists already, and
+    /// with [`InvalidInput`](io::ErrorKind::InvalidInput) if the capacity is 4 bytes or less.
+    pub fn create(name: impl AsRef<OsStr>, capacity: usize) -> io::Result<Self> {
+        Ring::create(name.as_ref(), capacity).map(|ring| Self { ring, write_pos: 0 })
+    }
+    /// Opens the sending side of an existing channel with the specified name.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such channel.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        let ring = Ring::open(name.as_ref())?;
+        let header = ring.header();
+        let write_pos = header.write_pos.0.load(Ordering::SeqCst);
+        header.sender_closed.store(0, Ordering::SeqCst);
+        Ok(Self { ring, write_pos })
+    }
+    /// Returns the capacity of the buffer, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity as usize
+    }
+    /// Sends a message, waiting for the receiver to make room for it in the buffer if needed.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the message is longer than the capacity minus 4
+    /// bytes, and with [`BrokenPipe`](io::ErrorKind::BrokenPipe) if the receiver has been dropped.
+    pub fn send(&mut self, msg: &[u8]) -> io::Result<()> {
+        let needed = self.needed_space(msg)?;
+        let header = self.ring.header();
+        Ring::wait(
+            &self.ring.space_event,
+            &header.sender_waiting,
+            || self.has_space(needed) || self.receiver_closed(),
+            None,
+        )?;
+        self.check_receiver()?;
+        self.write(msg);
+        Ok(())
+    }
+    /// Sends a message if there is enough room for it in the buffer, returning `false` otherwise.
+    ///
+    /// Fails in the same cases as [`.send()`](Self::send).
+    pub fn try_send(&mut self, msg: &[u8]) -> io::Result<bool> {
+        let needed = self.needed_space(msg)?;
+        self.check_receiver()?;
+        if !self.has_space(needed) {
+            return Ok(false);
+        }
+        self.write(msg);
+        Ok(true)
+    }
+    fn needed_space(&self, msg: &[u8]) -> io::Result<u64> {
+        u32::try_from(msg.len())
+            .ok()
+            .map(|len| LEN_PREFIX + u64::from(len))
+            .filter(|&needed| needed <= self.ring.capacity)
+            .ok_or_else(too_long)
+    }
+    fn has_space(&self, needed: u64) -> bool {
+        let read_pos = self.ring.header().read_pos.0.load(Ordering::SeqCst);
+        self.ring.capacity - (self.write_pos - read_pos) >= needed
+    }
+    fn receiver_closed(&self) -> bool {
+        self.ring.header().receiver_closed.load(Ordering::SeqCst) != 0
+    }
+    fn check_receiver(&self) -> io::Result<()> {
+        if self.receiver_closed() {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the receiver has been dropped",
+            ));
+        }
+        Ok(())
+    }
+    /// Writes a message for which there is enough room and lets the receiver know about it.
+    fn write(&mut self, msg: &[u8]) {
+        unsafe {
+            // SAFETY: the caller made sure that the message fits between the write and read positions
+            self.ring.copy_in(self.write_pos, &(msg.len() as u32).to_le_bytes());
+            self.ring.copy_in(self.write_pos + LEN_PREFIX, msg);
+        }
+        self.write_pos += LEN_PREFIX + msg.len() as u64;
+        let header = self.ring.header();
+        header.write_pos.0.store(self.write_pos, Ordering::SeqCst);
+        if header.receiver_waiting.load(Ordering::SeqCst) != 0 {
+            // There is nothing to be done about a failure to wake the receiver up, which will then find the message
+            // on its next wakeup.
+            let _ = self.ring.data_event.notify_all();
+        }
+    }
+}
+impl Drop for RingSender {
+    fn drop(&mut self) {
+        self.ring.header().sender_closed.store(1, Ordering::SeqCst);
+        let _ = self.ring.data_event.notify_all();
+    }
+}
+
+/// The receiving side of a ring buffer channel.
+///
+/// See the [module-level documentation](self) for more.
+#[derive(Debug)]
+pub struct RingReceiver {
+    // Shared with the blocking tasks of asynchronous receives.
+    pub(crate) ring: Arc<Ring>,
+    read_pos: u64,
+}
+impl RingReceiver {
+    /// Creates a new channel with the specified name and capacity in bytes, returning its receiving side.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if a channel with the same name exists already, and
+    /// with [`InvalidInput`](io::ErrorKind::InvalidInput) if the capacity is 4 bytes or less.
+    pub fn create(name: impl AsRef<OsStr>, capacity: usize) -> io::Result<Self> {
+        Ring::create(name.as_ref(), capacity).map(|ring| Self {
+            ring: Arc::new(ring),
+            read_pos: 0,
+        })
+    }
+    /// Opens the receiving side of an existing channel with the specified name.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such channel.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        let ring = Ring::open(name.as_ref())?;
+        let header = ring.header();
+        let read_pos = header.read_pos.0.load(Ordering::SeqCst);
+        header.receiver_closed.store(0, Ordering::SeqCst);
+        Ok(Self {
+            ring: Arc::new(ring),
+            read_pos,
+        })
+    }
+    /// Returns the capacity of the buffer, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity as usize
+    }
+    /// Receives a message, waiting for the sender to send one if the buffer is empty.
+    ///
+    /// Fails with [`BrokenPipe`](io::ErrorKind::BrokenPipe) if the buffer is empty and the sender has been dropped.
+    pub fn recv(&mut self) -> io::Result<Vec<u8>> {
+        loop {
+            if let Some(msg) = self.try_recv()? {
+                return Ok(msg);
+            }
+            self.ring.wait_for_data(self.read_pos, None)?;
+        }
+    }
+    /// Receives a message, waiting for at most the given amount of time for the sender to send one if the buffer is
+    /// empty, and returning `None` if the timeout runs out first.
+    ///
+    /// Fails in the same cases as [`.recv()`](Self::recv).
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<Vec<u8>>> {
+        self.ring.wait_for_data(self.read_pos, Some(timeout))?;
+        self.try_recv()
+    }
+    /// Receives a message if there is one in the buffer, returning `None` otherwise.
+    ///
+    /// Fails in the same cases as [`.recv()`](Self::recv).
+    pub fn try_recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let header = self.ring.header();
+        let write_pos = header.write_pos.0.load(Ordering::SeqCst);
+        if write_pos == self.read_pos {
+            // The sender sets the flag after writing its last message, so the position has to be checked again.
+            let closed = header.sender_closed.load(Ordering::SeqCst) != 0;
+            if closed && header.write_pos.0.load(Ordering::SeqCst) == self.read_pos {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "the sender has been dropped"));
+            }
+            return Ok(None);
+        }
+
+        let available = write_pos.wrapping_sub(self.read_pos);
+        let corrupted = || io::Error::new(io::ErrorKind::InvalidData, "the ring buffer is corrupted");
+        if available < LEN_PREFIX || available > self.ring.capacity {
+            return Err(corrupted());
+        }
+        let mut len = [0; LEN_PREFIX as usize];
+        unsafe {
+            // SAFETY: the sender never touches data between the read and write positions
+            self.ring.copy_out(self.read_pos, &mut len);
+        }
+        let len = u64::from(u32::from_le_bytes(len));
+        if LEN_PREFIX + len > available {
+            return Err(corrupted());
+        }
+        let mut msg = vec![0; len as usize];
+        unsafe {
+            // SAFETY: as above
+            self.ring.copy_out(self.read_pos + LEN_PREFIX, &mut msg);
+        }
+
+        self.read_pos += LEN_PREFIX + len;
+        header.read_pos.0.store(self.read_pos, Ordering::SeqCst);
+        if header.sender_waiting.load(Ordering::SeqCst) != 0 {
+            let _ = self.ring.space_event.notify_all();
+        }
+        Ok(Some(msg))
+    }
+    /// Asynchronously receives a message, waiting for the sender to send one if the buffer is empty.
+    ///
+    /// The waiting is done on Tokio's blocking thread pool, in slices of at most 100 milliseconds, so dropping the
+    /// future can leave a blocking task running for that long. Fails in the same cases as [`.recv()`](Self::recv).
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+    pub async fn recv_async(&mut self) -> io::Result<Vec<u8>> {
+        const WAIT_SLICE: Duration = Duration::from_millis(100);
+        loop {
+            if let Some(msg) = self.try_recv()? {
+                return Ok(msg);
+            }
+            let (ring, read_pos) = (Arc::clone(&self.ring), self.read_pos);
+            tokio::task::spawn_blocking(move || ring.wait_for_data(read_pos, Some(WAIT_SLICE)))
+                .await
+                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))??;
+        }
+    }
+}
+impl Drop for RingReceiver {
+    fn drop(&mut self) {
+        self.ring.header().receiver_closed.store(1, Ordering::SeqCst);
+        let _ = self.ring.space_event.notify_all();
+    }
+}
+
+/// Removes the names of the shared memory object and the events that make up the channel with the specified name.
+#[cfg(unix)]
+pub(crate) fn unlink(name: &OsStr) -> io::Result<()> {
+    use crate::os::unix::sync::unlink_event;
+    crate::os::unix::shared_memory::unlink(name)?;
+    // Unlinking the events only fails if they have been unlinked already.
+    let _ = unlink_event(event_name(name, "-data"));
+    let _ = unlink_event(event_name(name, "-space"));
+    Ok(())
+}
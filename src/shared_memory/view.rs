@@ -0,0 +1,255 @@
+use super::SharedMemoryMapping;
+use bytemuck::{Pod, Zeroable};
+use std::{
+    io,
+    mem::{align_of, size_of},
+    slice,
+};
+
+/// A header placed at the start of shared memory to identify the layout of the data that follows it, written with
+/// [`.init_versioned()`](SharedMemoryMapping::init_versioned) and checked by
+/// [`.versioned()`](SharedMemoryMapping::versioned).
+///
+/// Processes built from different versions of a program, or different programs altogether, can end up opening the same
+/// shared memory object. Without a header, each of them would interpret the memory according to its own idea of what's
+/// in there, which goes unnoticed until the data gets corrupted. The header records a magic number which identifies the
+/// application, a version number which is to be bumped whenever the layout of the data changes, and the offset and size
+/// of the data, so that a mismatch is detected when the memory is opened instead.
+///
+/// The layout of the header itself is fixed: 24 bytes, aligned to 8 bytes, with the fields in declaration order and in
+/// native byte order.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionedHeader {
+    /// Identifies the application or protocol that the memory belongs to.
+    pub magic: [u8; 8],
+    /// The version of the layout of the data.
+    pub version: u32,
+    /// The offset of the data from the start of the header, in bytes, which depends on the alignment of the data.
+    pub payload_offset: u32,
+    /// The size of the data in bytes.
+    pub payload_len: u64,
+}
+// SAFETY: the header is plain old data with no padding, as the fields add up to its size
+unsafe impl Zeroable for VersionedHeader {}
+unsafe impl Pod for VersionedHeader {}
+impl VersionedHeader {
+    /// Returns the header which describes data of type `T`.
+    pub fn new<T: Pod>(magic: [u8; 8], version: u32) -> Self {
+        Self {
+            magic,
+            version,
+            payload_offset: payload_offset::<T>() as u32,
+            payload_len: size_of::<T>() as u64,
+        }
+    }
+}
+
+/// The offset of data of type `T` placed right after the header, rounded up to the alignment of `T`.
+fn payload_offset<T>() -> usize {
+    let align = align_of::<T>();
+    (size_of::<VersionedHeader>() + align - 1) / align * align
+}
+
+/// Typed views, available with the `bytemuck` feature.
+///
+/// Instead of casting the pointer to the start of the mapping to a pointer to some other type by hand, which is
+/// unsound if the mapping is too small or not suitably aligned, or if not every bit pattern is a valid value of the
+/// type, views check the size and alignment and only allow types which implement [`Pod`]. What remains unsafe is the
+/// same as with [`.as_slice()`](Self::as_slice) and [`.as_mut_slice()`](Self::as_mut_slice): the views must not
+/// overlap with concurrent modifications of the memory. For data that is modified concurrently, view atomic types
+/// through [`.as_ptr()`](Self::as_ptr) instead.
+///
+/// # Example
+/// ```
+/// use bytemuck::{Pod, Zeroable};
+/// use interprocess::shared_memory::SharedMemory;
+///
+/// #[repr(C)]
+/// #[derive(Copy, Clone, Pod, Zeroable)]
+/// struct Stats {
+///     requests: u64,
+///     errors: u64,
+/// }
+/// const MAGIC: [u8; 8] = *b"myserver";
+///
+/// let name = format!("interprocess-doctest-view-{}", std::process::id());
+/// let shm = SharedMemory::create(&name, 4096)?;
+/// let writer = shm.map()?;
+/// unsafe {
+///     // SAFETY: no other process knows about this shared memory object yet
+///     let stats = writer.init_versioned::<Stats>(MAGIC, 1)?;
+///     stats.requests = 42;
+/// }
+///
+/// // Would normally be done in another process.
+/// let reader = SharedMemory::open_read_only(&name)?.map_read_only()?;
+/// unsafe {
+///     // SAFETY: the writer is done writing
+///     assert_eq!(reader.versioned::<Stats>(MAGIC, 1)?.requests, 42);
+///     assert!(reader.versioned::<Stats>(MAGIC, 2).is_err());
+/// }
+/// # #[cfg(unix)] {
+/// # use interprocess::os::unix::shared_memory::SharedMemoryExt;
+/// # shm.unlink()?;
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+impl SharedMemoryMapping {
+    /// Borrows the memory at the given offset as a value of type `T`.
+    ///
+    /// Fails with [`InvalidInput`](io::ErrorKind::InvalidInput) if the value doesn't fit into the mapping at that
+    /// offset, or if the offset is not suitably aligned for `T`. Offset zero is always aligned, since mappings start at
+    /// a page boundary.
+    ///
+    /// # Safety
+    /// The value must not be modified for as long as the reference exists, neither through this mapping nor by any
+    /// other mapping of the same object, including ones in other processes.
+    pub unsafe fn view<T: Pod>(&self, offset: usize) -> io::Result<&T> {
+        let ptr = self.checked_ptr::<T>(offset, 1)?;
+        Ok(unsafe { &*(ptr as *const T) })
+    }
+    /// Borrows the memory at the given offset as a mutable value of type `T`, with the same checks as
+    /// [`.view()`](Self::view).
+    ///
+    /// # Panics
+    /// If the mapping is [read-only](Self::is_read_only).
+    ///
+    /// # Safety
+    /// The value must not be accessed for as long as the reference exists, neither through this mapping nor by any
+    /// other mapping of the same object, including ones in other processes.
+    #[allow(clippy::mut_from_ref)] // The exclusivity is up to the caller, as with .as_mut_slice().
+    pub unsafe fn view_mut<T: Pod>(&self, offset: usize) -> io::Result<&mut T> {
+        self.assert_writable();
+        let ptr = self.checked_ptr::<T>(offset, 1)?;
+        Ok(unsafe { &mut *(ptr as *mut T) })
+    }
+    /// Borrows `count` consecutive values of type `T` starting at the given offset as a slice, with the same checks as
+    /// [`.view()`](Self::view).
+    ///
+    /// # Safety
+    /// The values must not be modified for as long as the slice exists, neither through this mapping nor by any other
+    /// mapping of the same object, including ones in other processes.
+    pub unsafe fn view_slice<T: Pod>(&self, offset: usize, count: usize) -> io::Result<&[T]> {
+        let ptr = self.checked_ptr::<T>(offset, count)?;
+        Ok(unsafe { slice::from_raw_parts(ptr as *const T, count) })
+    }
+    /// Borrows `count` consecutive values of type `T` starting at the given offset as a mutable slice, with the same
+    /// checks as [`.view()`](Self::view).
+    ///
+    /// # Panics
+    /// If the mapping is [read-only](Self::is_read_only).
+    ///
+    /// # Safety
+    /// The values must not be accessed for as long as the slice exists, neither through this mapping nor by any other
+    /// mapping of the same object, including ones in other processes.
+    #[allow(clippy::mut_from_ref)] // The exclusivity is up to the caller, as with .as_mut_slice().
+    pub unsafe fn view_slice_mut<T: Pod>(&self, offset: usize, count: usize) -> io::Result<&mut [T]> {
+        self.assert_writable();
+        let ptr = self.checked_ptr::<T>(offset, count)?;
+        Ok(unsafe { slice::from_raw_parts_mut(ptr as *mut T, count) })
+    }
+
+    /// Writes a [`VersionedHeader`] describing data of type `T` to the start of the mapping, and borrows the data that
+    /// follows it, which is left as it was.
+    ///
+    /// Other processes can check the header with [`.versioned()`](Self::versioned), which means that they must not be
+    /// told about the memory before both the header and the data are written. Fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the header and the data don't fit into the mapping.
+    ///
+    /// # Panics
+    /// If the mapping is [read-only](Self::is_read_only).
+    ///
+    /// # Safety
+    /// The header and the data must not be accessed for as long as the reference exists, neither through this mapping
+    /// nor by any other mapping of the same object, including ones in other processes.
+    #[allow(clippy::mut_from_ref)] // The exclusivity is up to the caller, as with .as_mut_slice().
+    pub unsafe fn init_versioned<T: Pod>(&self, magic: [u8; 8], version: u32) -> io::Result<&mut T> {
+        // Checking that the data fits first keeps the header from being written to a mapping that's too small.
+        let data = unsafe { self.view_mut::<T>(payload_offset::<T>())? };
+        *unsafe { self.view_mut::<VersionedHeader>(0)? } = VersionedHeader::new::<T>(magic, version);
+        Ok(data)
+    }
+    /// Checks the [`VersionedHeader`] at the start of the mapping, and borrows the data of type `T` that follows it.
+    ///
+    /// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the header has a different magic number or version,
+    /// or describes data with a different size or alignment than that of `T`, and with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the header and the data don't fit into the mapping.
+    ///
+    /// # Safety
+    /// The header and the data must not be modified for as long as the reference exists, neither through this mapping
+    /// nor by any other mapping of the same object, including ones in other processes.
+    pub unsafe fn versioned<T: Pod>(&self, magic: [u8; 8], version: u32) -> io::Result<&T> {
+        unsafe { self.check_header::<T>(magic, version)? };
+        unsafe { self.view(payload_offset::<T>()) }
+    }
+    /// Checks the [`VersionedHeader`] at the start of the mapping like [`.versioned()`](Self::versioned) does, and
+    /// mutably borrows the data of type `T` that follows it.
+    ///
+    /// # Panics
+    /// If the mapping is [read-only](Self::is_read_only).
+    ///
+    /// # Safety
+    /// The header and the data must not be accessed for as long as the reference exists, neither through this mapping
+    /// nor by any other mapping of the same object, including ones in other processes.
+    #[allow(clippy::mut_from_ref)] // The exclusivity is up to the caller, as with .as_mut_slice().
+    pub unsafe fn versioned_mut<T: Pod>(&self, magic: [u8; 8], version: u32) -> io::Result<&mut T> {
+        self.assert_writable();
+        unsafe { self.check_header::<T>(magic, version)? };
+        unsafe { self.view_mut(payload_offset::<T>()) }
+    }
+
+    unsafe fn check_header<T: Pod>(&self, magic: [u8; 8], version: u32) -> io::Result<()> {
+        let header = unsafe { self.view::<VersionedHeader>(0)? };
+        let expected = VersionedHeader::new::<T>(magic, version);
+        if header.magic != expected.magic {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the shared memory belongs to a different application",
+            ));
+        }
+        if header.version != expected.version {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the shared memory has layout version {}, expected {}",
+                    header.version, expected.version,
+                ),
+            ));
+        }
+        if header != &expected {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the size or alignment of the data in the shared memory differs from that of the requested type",
+            ));
+        }
+        Ok(())
+    }
+    fn checked_ptr<T>(&self, offset: usize, count: usize) -> io::Result<*mut u8> {
+        let fits = size_of::<T>()
+            .checked_mul(count)
+            .and_then(|size| size.checked_add(offset))
+            .map_or(false, |end| end <= self.len);
+        if !fits {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the requested view does not fit into the shared memory mapping",
+            ));
+        }
+        let ptr = self.ptr.wrapping_add(offset);
+        if ptr as usize % align_of::<T>() != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the requested view is not suitably aligned",
+            ));
+        }
+        Ok(ptr)
+    }
+    fn assert_writable(&self) {
+        assert!(
+            !self.read_only,
+            "attempt to mutably borrow a read-only shared memory mapping"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Sending and receiving a small set of portable signals, which ask a process to stop what it's doing or to perform some application-defined action.
+//!
+//! The [`Signal`] enumeration lists the signals that have a meaning on all platforms. They can be sent to another process by its ID with [`send`], and received by creating a [`SignalReceiver`] (or, with the `tokio` feature, a [`SignalStream`](tokio::SignalStream)) subscribed to them. Every receiver gets its own copy of every signal it's subscribed to, so independent parts of a program can listen for the same signal without stealing it from each other.
+//!
+//! Signals are not queued: if a signal arrives several times before a receiver gets to it, the receiver may see it only once. Once a process has started receiving a signal, the signal is discarded whenever it arrives while no receiver is subscribed to it.
+//!
+//! # Platform-specific behavior
+//! ## Unix
+//! The signals are mapped to `SIGINT`, `SIGTERM` and `SIGUSR1`, sent with `kill()` and received with a `sigaction()` handler which forwards them to a background thread through a pipe. Once a receiver has been created for a signal, the handler stays installed for the rest of the lifetime of the process, meaning that the default action of the signal (terminating the process) no longer takes place even after all receivers are dropped.
+//!
+//! ## Windows
+//! Windows has no signals, so they are emulated with named semaphores: a process that receives a signal creates a semaphore named `interprocess-signal-<pid>-<signal>`, which [`send`] opens and posts. Additionally, [`Signal::Interrupt`] is received when Ctrl+C or Ctrl+Break is pressed in the console of the process, and [`Signal::Terminate`] is received when the console is closed or the user logs off or shuts the system down, in which case the system gives the process a few seconds to exit before terminating it.
+//!
+//! If the target process isn't receiving the signal, [`Signal::Interrupt`] is sent as a Ctrl+Break event to the console process group with the ID of the target process, which only reaches it if it was started with the `CREATE_NEW_PROCESS_GROUP` flag and shares a console with the sender, and [`Signal::Terminate`] terminates the target process with exit code 1. Sending [`Signal::User1`] fails with [`NotFound`](io::ErrorKind::NotFound).
+//!
+//! # Example
+//! ```
+//! use interprocess::signal::{send, Signal, SignalReceiver};
+//! use std::time::Duration;
+//!
+//! let receiver = SignalReceiver::new(&[Signal::User1])?;
+//! // Would normally be done by another process.
+//! send(std::process::id(), Signal::User1)?;
+//! assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Some(Signal::User1));
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+impmod! {signal,
+    install as install_impl,
+    send as send_impl,
+}
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
+
+/// A signal that can be sent to and received by processes on all platforms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Signal {
+    /// A request to interrupt the current operation, typically sent when the user presses Ctrl+C. Processes which
+    /// don't receive it are terminated.
+    ///
+    /// Corresponds to `SIGINT` on Unix.
+    Interrupt,
+    /// A request to exit gracefully, sent by process managers and when the system is shutting down. Processes which
+    /// don't receive it are terminated.
+    ///
+    /// Corresponds to `SIGTERM` on Unix.
+    Terminate,
+    /// A signal with an application-defined meaning.
+    ///
+    /// Corresponds to `SIGUSR1` on Unix.
+    User1,
+}
+impl Signal {
+    #[cfg(unix)]
+    pub(crate) const ALL: [Self; 3] = [Self::Interrupt, Self::Terminate, Self::User1];
+    /// Returns the position of the signal in [`ALL`](Self::ALL), which is used to identify it in bitmasks and
+    /// platform-specific channels.
+    pub(crate) fn index(self) -> u8 {
+        self as u8
+    }
+    #[cfg(unix)]
+    pub(crate) fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(index)).copied()
+    }
+    fn bit(self) -> u8 {
+        1 << self.index()
+    }
+}
+
+/// Sends a signal to the process with the specified ID.
+///
+/// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such process, and with
+/// [`PermissionDenied`](io::ErrorKind::PermissionDenied) if the current process isn't allowed to send signals to it.
+/// See the [module-level documentation](self) for how signals are sent on Windows.
+pub fn send(pid: u32, signal: Signal) -> io::Result<()> {
+    send_impl(pid, signal)
+}
+
+enum Sender {
+    Sync(mpsc::Sender<Signal>),
+    #[cfg(feature = "tokio")]
+    Tokio(::tokio::sync::mpsc::UnboundedSender<Signal>),
+}
+impl Sender {
+    fn send(&self, signal: Signal) {
+        // Receivers unsubscribe when they're dropped, so the channel is never disconnected at this point.
+        match self {
+            Self::Sync(sender) => drop(sender.send(signal)),
+            #[cfg(feature = "tokio")]
+            Self::Tokio(sender) => drop(sender.send(signal)),
+        }
+    }
+}
+
+struct Subscriber {
+    id: u64,
+    signals: u8,
+    sender: Sender,
+}
+
+static SUBSCRIBERS: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+
+fn subscribers() -> MutexGuard<'static, Vec<Subscriber>> {
+    // The list is never left in an inconsistent state, so poisoning can be ignored.
+    SUBSCRIBERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Starts delivering the specified signals to the given channel, returning the ID with which to
+/// [unsubscribe](unsubscribe) it.
+fn subscribe(signals: &[Signal], sender: Sender) -> io::Result<u64> {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let mut mask = 0;
+    for &signal in signals {
+        install_impl(signal)?;
+        mask |= signal.bit();
+    }
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    subscribers().push(Subscriber {
+        id,
+        signals: mask,
+        sender,
+    });
+    Ok(id)
+}
+fn unsubscribe(id: u64) {
+    subscribers().retain(|sub| sub.id != id);
+}
+
+/// Hands a signal received by the current process over to all of the receivers subscribed to it, returning whether
+/// there were any. Called by the platform-specific code, which is responsible for making sure that it's only called
+/// outside of signal handlers.
+pub(crate) fn dispatch(signal: Signal) -> bool {
+    let subscribers = subscribers();
+    let mut delivered = false;
+    for sub in subscribers.iter().filter(|sub| sub.signals & signal.bit() != 0) {
+        sub.sender.send(signal);
+        delivered = true;
+    }
+    delivered
+}
+
+/// A subscription to one or more [signals](Signal), through which they can be received synchronously.
+///
+/// Dropping the receiver ends the subscription. See the [module-level documentation](self) for an example.
+pub struct SignalReceiver {
+    id: u64,
+    receiver: mpsc::Receiver<Signal>,
+}
+impl SignalReceiver {
+    /// Starts receiving the specified signals.
+    pub fn new(signals: &[Signal]) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let id = subscribe(signals, Sender::Sync(sender))?;
+        Ok(Self { id, receiver })
+    }
+    /// Waits for a signal to arrive.
+    pub fn recv(&self) -> Signal {
+        self.receiver
+            .recv()
+            .expect("signal receiver was unsubscribed while in use")
+    }
+    /// Waits for a signal to arrive for at most the given amount of time, returning `None` if the timeout ran out
+    /// first.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Signal> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(signal) => Some(signal),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => panic!("signal receiver was unsubscribed while in use"),
+        }
+    }
+    /// Returns a signal that has already arrived, if any, without waiting.
+    pub fn try_recv(&self) -> Option<Signal> {
+        self.receiver.try_recv().ok()
+    }
+}
+impl Drop for SignalReceiver {
+    fn drop(&mut self) {
+        unsubscribe(self.id);
+    }
+}
+impl Debug for SignalReceiver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalReceiver").field("id", &self.id).finish()
+    }
+}
@@ -0,0 +1,69 @@
+//! Tokio-based asynchronous reception of signals.
+
+use super::{subscribe, unsubscribe, Sender, Signal};
+use futures_core::Stream;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+/// A subscription to one or more [signals](Signal), through which they can be received asynchronously, either with
+/// [`.recv()`](Self::recv) or as a [`Stream`].
+///
+/// The stream never ends. Dropping it ends the subscription.
+///
+/// # Example
+/// ```
+/// # #[tokio::main(flavor = "current_thread")] async fn main() -> std::io::Result<()> {
+/// use interprocess::signal::{send, tokio::SignalStream, Signal};
+///
+/// let mut signals = SignalStream::new(&[Signal::User1])?;
+/// // Would normally be done by another process.
+/// send(std::process::id(), Signal::User1)?;
+/// assert_eq!(signals.recv().await, Signal::User1);
+/// # Ok(()) }
+/// ```
+pub struct SignalStream {
+    id: u64,
+    receiver: UnboundedReceiver<Signal>,
+}
+impl SignalStream {
+    /// Starts receiving the specified signals.
+    pub fn new(signals: &[Signal]) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = subscribe(signals, Sender::Tokio(sender))?;
+        Ok(Self { id, receiver })
+    }
+    /// Waits for a signal to arrive.
+    pub async fn recv(&mut self) -> Signal {
+        self.receiver
+            .recv()
+            .await
+            .expect("signal stream was unsubscribed while in use")
+    }
+    /// Polls for a signal to arrive, registering the current task for wakeup if there are none yet.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Signal> {
+        self.receiver
+            .poll_recv(cx)
+            .map(|signal| signal.expect("signal stream was unsubscribed while in use"))
+    }
+}
+impl Stream for SignalStream {
+    type Item = Signal;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Signal>> {
+        self.get_mut().poll_recv(cx).map(Some)
+    }
+}
+impl Drop for SignalStream {
+    fn drop(&mut self) {
+        unsubscribe(self.id);
+    }
+}
+impl Debug for SignalStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalStream").field("id", &self.id).finish()
+    }
+}
@@ -0,0 +1,596 @@
+//! Spawning child processes with IPC endpoints already connected to the parent.
+//!
+//! Handing a freshly spawned child process a way to talk to its parent is deceptively hard to get right: resources have
+//! to be made inheritable for exactly the one child that is supposed to get them – or else other children keep them
+//! open and the parent never sees the end of the connection – the child has to be told which of its inherited file
+//! descriptors or handles is which, and if a name is used to connect instead, the parent has to make sure that it's
+//! really the child that connects to it, and not wait forever if the child dies before doing so.
+//!
+//! A [`Spawner`] takes care of all of that. It wraps a [`Command`] and a list of endpoints identified by string keys –
+//! [local socket streams](Spawner::stream) and [unnamed pipes](Spawner::pipe_to_child) in either direction – and spawns
+//! the child with its ends of them, returning the parent's ends together with the [`Child`] in a [`SpawnedChild`]. The
+//! child picks up its ends by key with [`stream_from_parent()`], [`reader_from_parent()`] and [`writer_to_parent()`].
+//!
+//! # Passing the endpoints
+//! There are two ways for the endpoints to get to the child, chosen with [`Spawner::passing()`]:
+//! - **[Inheritance](EndpointPassing::Inheritance)**, the default – the child inherits its ends, and the
+//!   [`ENDPOINTS_VAR`] environment variable tells it their file descriptor or handle values. Only the spawned child
+//!   inherits them on Unix. On Windows, the standard library offers no way to restrict the handles a child inherits,
+//!   which means that processes spawned by other threads at the same time as this one inherit the ends as well.
+//! - **[Bootstrapping](EndpointPassing::Bootstrap)** – the parent listens on a generated local socket name, which the
+//!   [`BOOTSTRAP_VAR`] environment variable passes to the child. The child connects to it and receives its ends over
+//!   the connection, as described in [`LocalSocketStream::send_handles()`]. Nothing is inherited, at the cost of the
+//!   parent waiting for the child to connect before [`.spawn()`](Spawner::spawn) returns. Connections from processes
+//!   other than the child are ignored where the [identity of the peer](LocalSocketStream::peer_pid) can be queried.
+//!
+//! Either way, the child's ends are only ever inheritable while the child is being spawned, and the child's side of
+//! this module takes them over as non-inheritable and removes both environment variables from its environment once it
+//! reads them, so that they don't leak into its own children. Since modifying the environment is only sound while no
+//! other thread accesses it, the functions picking up the endpoints are `unsafe` – they should be called before the
+//! child starts other threads or spawns processes of its own.
+//!
+//! # Example
+//! ```no_run
+//! use interprocess::spawn::{stream_from_parent, Spawner, ENDPOINTS_VAR, BOOTSTRAP_VAR};
+//! use std::{env, io::{prelude::*, BufReader}, process::Command};
+//!
+//! if env::var_os(ENDPOINTS_VAR).is_none() && env::var_os(BOOTSTRAP_VAR).is_none() {
+//!     // In the parent, spawn another copy of the same program:
+//!     let command = Command::new(env::current_exe()?);
+//!     let mut spawned = Spawner::new(command).stream("control").spawn()?;
+//!     let mut control = BufReader::new(spawned.take_stream("control").unwrap());
+//!     control.get_mut().write_all(b"Hello from the parent!\n")?;
+//!     let mut reply = String::new();
+//!     control.read_line(&mut reply)?;
+//!     print!("The child said: {reply}");
+//!     spawned.child.wait()?;
+//! } else {
+//!     // In the child:
+//!     // SAFETY: no other threads have been started yet.
+//!     let mut control = BufReader::new(unsafe { stream_from_parent("control") }?);
+//!     let mut greeting = String::new();
+//!     control.read_line(&mut greeting)?;
+//!     control.get_mut().write_all(b"Hello from the child!\n")?;
+//! }
+//! # std::io::Result::<()>::Ok(())
+//! ```
+
+use crate::{
+    local_socket::{BorrowedTransferable, LocalSocketListener, LocalSocketStream, OwnedTransferable},
+    unnamed_pipe::{UnnamedPipeOptions, UnnamedPipeReader, UnnamedPipeWriter},
+};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, FromRawFd, IntoRawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, FromRawHandle, IntoRawHandle};
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Debug, Formatter},
+    io::{self, Read, Write},
+    process::{Child, Command},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+impmod! {spawn,
+    inherit,
+    raw_value,
+    take_inherited,
+}
+
+/// The environment variable which lists the inherited endpoints of a child spawned with
+/// [`EndpointPassing::Inheritance`].
+pub const ENDPOINTS_VAR: &str = "INTERPROCESS_ENDPOINTS";
+/// The environment variable which holds the local socket name a child spawned with [`EndpointPassing::Bootstrap`]
+/// connects to in order to receive its endpoints.
+pub const BOOTSTRAP_VAR: &str = "INTERPROCESS_BOOTSTRAP";
+/// The default time the parent waits for a child spawned with [`EndpointPassing::Bootstrap`] to pick up its endpoints:
+/// 10 seconds.
+pub const DEFAULT_BOOTSTRAP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum number of endpoints which can be passed with [`EndpointPassing::Bootstrap`], all of which are sent in one
+/// message.
+const MAX_BOOTSTRAP_ENDPOINTS: usize = 253;
+/// How often the parent checks whether the child has exited while waiting for it to connect.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The way in which a [`Spawner`] passes the endpoints to the child. See the
+/// [module-level documentation](self#passing-the-endpoints) for a comparison.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EndpointPassing {
+    /// The child inherits its ends, which are listed in the [`ENDPOINTS_VAR`] environment variable.
+    #[default]
+    Inheritance,
+    /// The child connects to a name listed in the [`BOOTSTRAP_VAR`] environment variable and receives its ends over
+    /// that connection.
+    Bootstrap,
+}
+
+/// The type of an endpoint, as seen by the child.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Kind {
+    Stream,
+    Reader,
+    Writer,
+}
+impl Kind {
+    fn code(self) -> char {
+        match self {
+            Self::Stream => 's',
+            Self::Reader => 'r',
+            Self::Writer => 'w',
+        }
+    }
+    fn from_code(code: char) -> Option<Self> {
+        match code {
+            's' => Some(Self::Stream),
+            'r' => Some(Self::Reader),
+            'w' => Some(Self::Writer),
+            _ => None,
+        }
+    }
+    fn describe(self) -> &'static str {
+        match self {
+            Self::Stream => "a local socket stream",
+            Self::Reader => "the reading end of a pipe",
+            Self::Writer => "the writing end of a pipe",
+        }
+    }
+}
+
+/// The parent's end of an endpoint.
+enum ParentEnd {
+    Stream(LocalSocketStream),
+    Writer(UnnamedPipeWriter),
+    Reader(UnnamedPipeReader),
+}
+
+/// Builder for spawning a child process with IPC endpoints connected to the parent.
+///
+/// See the [module-level documentation](self) for an overview and an example.
+#[derive(Debug)]
+pub struct Spawner {
+    command: Command,
+    endpoints: Vec<(String, Kind)>,
+    passing: EndpointPassing,
+    bootstrap_timeout: Duration,
+}
+impl Spawner {
+    /// Creates a spawner for the given command, with no endpoints and the endpoints passed by
+    /// [inheritance](EndpointPassing::Inheritance).
+    ///
+    /// The command is spawned with its own configuration – arguments, environment, working directory, standard
+    /// streams – plus one of the [environment variables](self#passing-the-endpoints) of this module.
+    pub fn new(command: Command) -> Self {
+        Self {
+            command,
+            endpoints: Vec::new(),
+            passing: EndpointPassing::default(),
+            bootstrap_timeout: DEFAULT_BOOTSTRAP_TIMEOUT,
+        }
+    }
+    /// Sets the way in which the endpoints are passed to the child.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn passing(mut self, passing: EndpointPassing) -> Self {
+        self.passing = passing;
+        self
+    }
+    /// Sets how long [`.spawn()`](Self::spawn) waits for a child spawned with [`EndpointPassing::Bootstrap`] to pick up
+    /// its endpoints before killing it. Defaults to [`DEFAULT_BOOTSTRAP_TIMEOUT`].
+    ///
+    /// Has no effect with other ways of passing the endpoints.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn bootstrap_timeout(mut self, timeout: Duration) -> Self {
+        self.bootstrap_timeout = timeout;
+        self
+    }
+    /// Adds a local socket stream connected to the parent, which the child obtains with [`stream_from_parent()`] and the
+    /// parent with [`SpawnedChild::take_stream()`].
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn stream(self, key: impl Into<String>) -> Self {
+        self.endpoint(key.into(), Kind::Stream)
+    }
+    /// Adds an unnamed pipe from the parent to the child, whose reading end the child obtains with
+    /// [`reader_from_parent()`] and whose writing end the parent obtains with [`SpawnedChild::take_writer()`].
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn pipe_to_child(self, key: impl Into<String>) -> Self {
+        self.endpoint(key.into(), Kind::Reader)
+    }
+    /// Adds an unnamed pipe from the child to the parent, whose writing end the child obtains with
+    /// [`writer_to_parent()`] and whose reading end the parent obtains with [`SpawnedChild::take_reader()`].
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn pipe_from_child(self, key: impl Into<String>) -> Self {
+        self.endpoint(key.into(), Kind::Writer)
+    }
+    fn endpoint(mut self, key: String, kind: Kind) -> Self {
+        self.endpoints.push((key, kind));
+        self
+    }
+
+    /// Creates the endpoints and spawns the child, returning it together with the parent's ends.
+    ///
+    /// Keys must be non-empty and consist of ASCII letters, digits, `_`, `-` and `.`, and must not repeat; otherwise,
+    /// an [`InvalidInput`](io::ErrorKind::InvalidInput) error is returned, as it is for more than 253 endpoints with
+    /// [`EndpointPassing::Bootstrap`].
+    ///
+    /// With [`EndpointPassing::Bootstrap`], this waits for the child to pick up its endpoints. If the child exits first,
+    /// a [`BrokenPipe`](io::ErrorKind::BrokenPipe) error is returned; if the
+    /// [timeout](Self::bootstrap_timeout) runs out first, the child is killed and a
+    /// [`TimedOut`](io::ErrorKind::TimedOut) error is returned. Either way, the child is waited for, so that it doesn't
+    /// linger as a zombie.
+    pub fn spawn(mut self) -> io::Result<SpawnedChild> {
+        self.check_keys()?;
+        let mut parent_ends = HashMap::with_capacity(self.endpoints.len());
+        let mut child_ends = Vec::with_capacity(self.endpoints.len());
+        for (key, kind) in &self.endpoints {
+            let (parent_end, child_end) = match kind {
+                Kind::Stream => {
+                    let (parent_end, child_end) = stream_pair()?;
+                    (ParentEnd::Stream(parent_end), into_transferable(child_end))
+                }
+                Kind::Reader => {
+                    let (writer, reader) = UnnamedPipeOptions::new().inheritable(false).build()?;
+                    (ParentEnd::Writer(writer), into_transferable(reader))
+                }
+                Kind::Writer => {
+                    let (writer, reader) = UnnamedPipeOptions::new().inheritable(false).build()?;
+                    (ParentEnd::Reader(reader), into_transferable(writer))
+                }
+            };
+            parent_ends.insert(key.clone(), parent_end);
+            child_ends.push(child_end);
+        }
+        let child = match self.passing {
+            EndpointPassing::Inheritance => self.spawn_inheriting(&child_ends)?,
+            EndpointPassing::Bootstrap => self.spawn_bootstrapping(&child_ends)?,
+        };
+        // The child has its own copies of its ends by now, and the parent's copies are dropped here.
+        Ok(SpawnedChild {
+            child,
+            endpoints: parent_ends,
+        })
+    }
+    fn check_keys(&self) -> io::Result<()> {
+        for (i, (key, _)) in self.endpoints.iter().enumerate() {
+            let valid = !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+            if !valid {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid endpoint key {key:?}"),
+                ));
+            }
+            if self.endpoints[..i].iter().any(|(other, _)| other == key) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("duplicate endpoint key {key:?}"),
+                ));
+            }
+        }
+        if self.passing == EndpointPassing::Bootstrap && self.endpoints.len() > MAX_BOOTSTRAP_ENDPOINTS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no more than {MAX_BOOTSTRAP_ENDPOINTS} endpoints can be passed by bootstrapping"),
+            ));
+        }
+        Ok(())
+    }
+    fn spawn_inheriting(&mut self, child_ends: &[OwnedTransferable]) -> io::Result<Child> {
+        let borrowed = borrow_all(child_ends);
+        let description = self
+            .endpoints
+            .iter()
+            .zip(&borrowed)
+            .map(|((key, kind), end)| format!("{key}={}{}", kind.code(), raw_value(*end)))
+            .collect::<Vec<_>>()
+            .join(";");
+        inherit(&mut self.command, &borrowed)?;
+        self.command
+            .env(ENDPOINTS_VAR, description)
+            .env_remove(BOOTSTRAP_VAR)
+            .spawn()
+    }
+    fn spawn_bootstrapping(&mut self, child_ends: &[OwnedTransferable]) -> io::Result<Child> {
+        let (listener, bound_name) = LocalSocketListener::bind_generated("interprocess-spawn")?;
+        let name = bound_name.name().to_portable_string()?;
+        let mut child = self
+            .command
+            .env(BOOTSTRAP_VAR, name)
+            .env_remove(ENDPOINTS_VAR)
+            .spawn()?;
+        match self.bootstrap(&mut child, &listener, child_ends) {
+            Ok(()) => Ok(child),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(e)
+            }
+        }
+    }
+    fn bootstrap(
+        &self,
+        child: &mut Child,
+        listener: &LocalSocketListener,
+        child_ends: &[OwnedTransferable],
+    ) -> io::Result<()> {
+        let deadline = Instant::now() + self.bootstrap_timeout;
+        let mut conn = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match listener.accept_timeout(remaining.min(POLL_INTERVAL)) {
+                Ok(conn) => match conn.peer_pid() {
+                    Ok(pid) if pid != child.id() => continue,
+                    _ => break conn,
+                },
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(e),
+            }
+            if let Some(status) = child.try_wait()? {
+                return Err(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    format!("the child process exited ({status}) before picking up its endpoints"),
+                ));
+            }
+            if Instant::now() >= deadline {
+                return Err(bootstrap_timed_out());
+            }
+        };
+
+        let description = self
+            .endpoints
+            .iter()
+            .map(|(key, kind)| format!("{key}={}", kind.code()))
+            .collect::<Vec<_>>()
+            .join(";");
+        let mut message = Vec::with_capacity(4 + description.len());
+        message.extend_from_slice(&(description.len() as u32).to_le_bytes());
+        message.extend_from_slice(description.as_bytes());
+        let sent = conn.send_handles(&message, &borrow_all(child_ends))?;
+        conn.write_all(&message[sent..])?;
+
+        // On Windows, the child duplicates the handles out of this process, so they have to stay open until it's done.
+        if !conn.wait_readable(Some(deadline.saturating_duration_since(Instant::now())))? {
+            return Err(bootstrap_timed_out());
+        }
+        let mut ack = [0];
+        match conn.read(&mut ack)? {
+            1 => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the child process closed the bootstrap connection before picking up its endpoints",
+            )),
+        }
+    }
+}
+
+/// A child process spawned by a [`Spawner`], together with the parent's ends of its endpoints.
+pub struct SpawnedChild {
+    /// The child process.
+    pub child: Child,
+    endpoints: HashMap<String, ParentEnd>,
+}
+impl SpawnedChild {
+    /// Takes the parent's end of the [stream](Spawner::stream) with the given key, returning `None` if there is no
+    /// such stream or it was already taken.
+    pub fn take_stream(&mut self, key: &str) -> Option<LocalSocketStream> {
+        match self.endpoints.remove(key)? {
+            ParentEnd::Stream(stream) => Some(stream),
+            other => self.put_back(key, other),
+        }
+    }
+    /// Takes the writing end of the [pipe to the child](Spawner::pipe_to_child) with the given key, returning `None` if
+    /// there is no such pipe or it was already taken.
+    pub fn take_writer(&mut self, key: &str) -> Option<UnnamedPipeWriter> {
+        match self.endpoints.remove(key)? {
+            ParentEnd::Writer(writer) => Some(writer),
+            other => self.put_back(key, other),
+        }
+    }
+    /// Takes the reading end of the [pipe from the child](Spawner::pipe_from_child) with the given key, returning
+    /// `None` if there is no such pipe or it was already taken.
+    pub fn take_reader(&mut self, key: &str) -> Option<UnnamedPipeReader> {
+        match self.endpoints.remove(key)? {
+            ParentEnd::Reader(reader) => Some(reader),
+            other => self.put_back(key, other),
+        }
+    }
+    fn put_back<T>(&mut self, key: &str, end: ParentEnd) -> Option<T> {
+        self.endpoints.insert(key.to_string(), end);
+        None
+    }
+}
+impl Debug for SpawnedChild {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnedChild")
+            .field("child", &self.child)
+            .field("endpoints", &self.endpoints.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Obtains the child's end of the [stream](Spawner::stream) with the given key, connected to the parent.
+///
+/// Fails with [`NotFound`](io::ErrorKind::NotFound) if the process wasn't spawned with such an endpoint or it was
+/// already taken, and with [`InvalidInput`](io::ErrorKind::InvalidInput) if the endpoint is not a stream. Errors which
+/// occur while picking up the endpoints from the parent are only reported by the first call to this function,
+/// [`reader_from_parent()`] or [`writer_to_parent()`].
+///
+/// # Safety
+/// The first call to this function, [`reader_from_parent()`] or [`writer_to_parent()`] reads and then removes the
+/// [`ENDPOINTS_VAR`] and [`BOOTSTRAP_VAR`] environment variables, and takes ownership of the file descriptors or handles
+/// listed in the former. The caller must therefore ensure that:
+/// - no other thread reads or modifies the environment at the same time, which includes spawning processes;
+/// - if [`ENDPOINTS_VAR`] is set, it was set by a [`Spawner`] in the parent, and nothing else in this process owns or
+///   uses the file descriptors or handles it lists.
+///
+/// Calling it early in `main()`, before any other threads are started, satisfies the first requirement.
+pub unsafe fn stream_from_parent(key: &str) -> io::Result<LocalSocketStream> {
+    unsafe { take_from_parent(key, Kind::Stream) }.map(from_transferable)
+}
+/// Obtains the child's end of the [pipe from the parent](Spawner::pipe_to_child) with the given key.
+///
+/// Errors are reported the same way as by [`stream_from_parent()`].
+///
+/// # Safety
+/// See [`stream_from_parent()`].
+pub unsafe fn reader_from_parent(key: &str) -> io::Result<UnnamedPipeReader> {
+    unsafe { take_from_parent(key, Kind::Reader) }.map(from_transferable)
+}
+/// Obtains the child's end of the [pipe to the parent](Spawner::pipe_from_child) with the given key.
+///
+/// Errors are reported the same way as by [`stream_from_parent()`].
+///
+/// # Safety
+/// See [`stream_from_parent()`].
+pub unsafe fn writer_to_parent(key: &str) -> io::Result<UnnamedPipeWriter> {
+    unsafe { take_from_parent(key, Kind::Writer) }.map(from_transferable)
+}
+
+/// The endpoints passed to this process by its parent, once they're picked up.
+static FROM_PARENT: Mutex<Option<HashMap<String, (Kind, OwnedTransferable)>>> = Mutex::new(None);
+
+/// # Safety
+/// See [`stream_from_parent()`].
+unsafe fn take_from_parent(key: &str, kind: Kind) -> io::Result<OwnedTransferable> {
+    let mut from_parent = FROM_PARENT.lock().unwrap_or_else(PoisonError::into_inner);
+    if from_parent.is_none() {
+        // The environment variables are only ever interpreted once, even if that fails, since taking over the same
+        // inherited resource twice would result in it being closed twice.
+        *from_parent = Some(HashMap::new());
+        let picked_up = unsafe { pick_up_endpoints() };
+        env::remove_var(ENDPOINTS_VAR);
+        env::remove_var(BOOTSTRAP_VAR);
+        *from_parent = Some(picked_up?);
+    }
+    let endpoints = from_parent.get_or_insert_with(HashMap::new);
+    let (actual_kind, resource) = endpoints.remove(key).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no endpoint with the key {key:?} was passed to this process"),
+        )
+    })?;
+    if actual_kind != kind {
+        endpoints.insert(key.to_string(), (actual_kind, resource));
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the endpoint {key:?} is {}, not {}",
+                actual_kind.describe(),
+                kind.describe()
+            ),
+        ));
+    }
+    Ok(resource)
+}
+
+type Endpoints = HashMap<String, (Kind, OwnedTransferable)>;
+
+/// # Safety
+/// See [`stream_from_parent()`].
+unsafe fn pick_up_endpoints() -> io::Result<Endpoints> {
+    if let Some(description) = env::var_os(ENDPOINTS_VAR) {
+        let description = description.into_string().map_err(|_| invalid_description())?;
+        let mut endpoints = HashMap::new();
+        for entry in parse_description(&description) {
+            let (key, kind, value) = entry?;
+            let value = value.parse().map_err(|_| invalid_description())?;
+            // SAFETY: the caller guarantees that the parent has passed the resource for this process to take over, and
+            // the variable is only interpreted once.
+            let resource = unsafe { take_inherited(value)? };
+            endpoints.insert(key.to_string(), (kind, resource));
+        }
+        Ok(endpoints)
+    } else if let Some(name) = env::var_os(BOOTSTRAP_VAR) {
+        let mut conn = LocalSocketStream::connect(name)?;
+        let mut prefix = [0; 4];
+        let (filled, resources) = conn.recv_handles(&mut prefix)?;
+        conn.read_exact(&mut prefix[filled..])?;
+        let mut description = vec![0; u32::from_le_bytes(prefix) as usize];
+        conn.read_exact(&mut description)?;
+        let description = String::from_utf8(description).map_err(|_| invalid_description())?;
+
+        let mut endpoints = HashMap::new();
+        let mut resources = resources.into_iter();
+        for entry in parse_description(&description) {
+            let (key, kind, _) = entry?;
+            let resource = resources.next().ok_or_else(invalid_description)?;
+            endpoints.insert(key.to_string(), (kind, resource));
+        }
+        if resources.next().is_some() {
+            return Err(invalid_description());
+        }
+        conn.write_all(&[0])?;
+        Ok(endpoints)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "this process was not spawned with endpoints connected to its parent",
+        ))
+    }
+}
+
+/// Splits a description of the endpoints into keys, kinds and whatever follows the kind, which is the inherited value
+/// with [`EndpointPassing::Inheritance`] and nothing with [`EndpointPassing::Bootstrap`].
+fn parse_description(description: &str) -> impl Iterator<Item = io::Result<(&str, Kind, &str)>> {
+    description.split(';').filter(|entry| !entry.is_empty()).map(|entry| {
+        let (key, rest) = entry.split_once('=').ok_or_else(invalid_description)?;
+        let mut chars = rest.chars();
+        let kind = chars.next().and_then(Kind::from_code).ok_or_else(invalid_description)?;
+        Ok((key, kind, chars.as_str()))
+    })
+}
+
+fn invalid_description() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "the description of the endpoints passed by the parent is malformed",
+    )
+}
+fn bootstrap_timed_out() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        "the child process did not pick up its endpoints in time",
+    )
+}
+
+/// Creates a pair of connected streams, the first of which is not inheritable.
+fn stream_pair() -> io::Result<(LocalSocketStream, LocalSocketStream)> {
+    let (listener, bound_name) = LocalSocketListener::bind_generated("interprocess-spawn")?;
+    // Connected streams are created with the close-on-exec flag on Unix, unlike accepted ones, which is why the parent
+    // keeps the connecting end.
+    let connected = LocalSocketStream::connect(bound_name.name().clone())?;
+    let accepted = listener.accept()?;
+    Ok((connected, accepted))
+}
+
+#[cfg(unix)]
+fn into_transferable(resource: impl IntoRawFd) -> OwnedTransferable {
+    // SAFETY: the resource gives up ownership of the file descriptor
+    unsafe { OwnedTransferable::from_raw_fd(resource.into_raw_fd()) }
+}
+#[cfg(windows)]
+fn into_transferable(resource: impl IntoRawHandle) -> OwnedTransferable {
+    // SAFETY: the resource gives up ownership of the handle
+    unsafe { OwnedTransferable::from_raw_handle(resource.into_raw_handle()) }
+}
+#[cfg(unix)]
+fn from_transferable<T: FromRawFd>(resource: OwnedTransferable) -> T {
+    // SAFETY: the resource gives up ownership of the file descriptor
+    unsafe { T::from_raw_fd(resource.into_raw_fd()) }
+}
+#[cfg(windows)]
+fn from_transferable<T: FromRawHandle>(resource: OwnedTransferable) -> T {
+    // SAFETY: the resource gives up ownership of the handle
+    unsafe { T::from_raw_handle(resource.into_raw_handle()) }
+}
+#[cfg(unix)]
+fn borrow_all(resources: &[OwnedTransferable]) -> Vec<BorrowedTransferable<'_>> {
+    resources.iter().map(AsFd::as_fd).collect()
+}
+#[cfg(windows)]
+fn borrow_all(resources: &[OwnedTransferable]) -> Vec<BorrowedTransferable<'_>> {
+    resources.iter().map(AsHandle::as_handle).collect()
+}
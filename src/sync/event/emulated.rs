@@ -0,0 +1,143 @@
+//! An event emulated with a named semaphore, for platforms which lack a way to wait on an address in shared memory
+//! across processes.
+
+use crate::{
+    shared_memory::{SharedMemory, SharedMemoryMapping},
+    sync::{open_when_ready, NamedSemaphore},
+};
+use std::{
+    ffi::{OsStr, OsString},
+    fmt::{self, Debug, Formatter},
+    io,
+    mem::size_of,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+/// The layout of the shared memory object.
+#[repr(C)]
+struct SharedState {
+    /// Incremented by every notification.
+    seq: AtomicU32,
+    /// The number of waiters that are yet to be woken up by posting the semaphore.
+    waiters: AtomicU32,
+}
+
+fn semaphore_name(name: &OsStr) -> OsString {
+    let mut sem_name = name.to_owned();
+    sem_name.push("-event");
+    sem_name
+}
+
+pub(crate) struct NamedEvent {
+    mapping: SharedMemoryMapping,
+    sem: NamedSemaphore,
+    pub(crate) name: OsString,
+}
+impl NamedEvent {
+    pub fn create(name: &OsStr) -> io::Result<Self> {
+        // All zeroes is a valid initial state, so there's nothing to initialize.
+        let shm = SharedMemory::create(name, size_of::<SharedState>())?;
+        let slf = shm.map().and_then(|mapping| {
+            Ok(Self {
+                mapping,
+                sem: NamedSemaphore::create(semaphore_name(name), 0)?,
+                name: name.to_owned(),
+            })
+        });
+        #[cfg(unix)]
+        if slf.is_err() {
+            // Don't leave behind an event that can never be opened.
+            let _ = crate::os::unix::shared_memory::unlink(name);
+        }
+        slf
+    }
+    pub fn open(name: &OsStr) -> io::Result<Self> {
+        open_when_ready(|| {
+            // The creator might not have gotten to setting the size of the shared memory or creating the semaphore
+            // yet.
+            let shm = SharedMemory::open(name)?;
+            if shm.len() < size_of::<SharedState>() {
+                return Ok(None);
+            }
+            let sem = match NamedSemaphore::open(semaphore_name(name)) {
+                Ok(sem) => sem,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            Ok(Some(Self {
+                mapping: shm.map()?,
+                sem,
+                name: name.to_owned(),
+            }))
+        })
+    }
+    fn state(&self) -> &SharedState {
+        unsafe {
+            // SAFETY: the mapping is big enough and page-aligned, and the state only consists of atomics
+            &*(self.mapping.as_ptr() as *const SharedState)
+        }
+    }
+    pub fn seq(&self) -> u32 {
+        self.state().seq.load(Ordering::SeqCst)
+    }
+    /// Removes the current thread from the count of waiters, unless a notifier has already done that, in which case
+    /// the semaphore is going to be posted for it anyway, making some future wait end spuriously.
+    fn unregister(&self) {
+        let _ = self
+            .state()
+            .waiters
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+    }
+    pub fn wait_for_change(&self, seq: u32, timeout: Option<Duration>) -> io::Result<bool> {
+        let state = self.state();
+        state.waiters.fetch_add(1, Ordering::SeqCst);
+        // The notifier increments the sequence number before reading the number of waiters, so either it sees this
+        // waiter, or this waiter sees the new sequence number.
+        if state.seq.load(Ordering::SeqCst) != seq {
+            self.unregister();
+            return Ok(true);
+        }
+        let woken = match timeout {
+            None => self.sem.wait().map(|()| true),
+            Some(timeout) => self.sem.wait_timeout(timeout),
+        };
+        if !matches!(woken, Ok(true)) {
+            self.unregister();
+        }
+        woken
+    }
+    pub fn notify(&self, all: bool) -> io::Result<()> {
+        let state = self.state();
+        state.seq.fetch_add(1, Ordering::SeqCst);
+        let woken = if all {
+            state.waiters.swap(0, Ordering::SeqCst)
+        } else {
+            match state
+                .waiters
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            {
+                Ok(_) => 1,
+                Err(_) => 0,
+            }
+        };
+        for _ in 0..woken {
+            self.sem.post()?;
+        }
+        Ok(())
+    }
+    #[cfg(unix)]
+    pub fn unlink(name: &OsStr) -> io::Result<()> {
+        crate::os::unix::shared_memory::unlink(name)?;
+        crate::os::unix::sync::unlink_semaphore(semaphore_name(name))
+    }
+}
+impl Debug for NamedEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamedEvent")
+            .field("mapping", &self.mapping)
+            .field("sem", &self.sem)
+            .field("name", &self.name)
+            .finish()
+    }
+}
@@ -0,0 +1,138 @@
+#[cfg(not(target_os = "linux"))]
+mod emulated;
+#[cfg(target_os = "linux")]
+pub(crate) use crate::os::unix::sync::event::NamedEvent as NamedEventImpl;
+#[cfg(not(target_os = "linux"))]
+pub(crate) use emulated::NamedEvent as NamedEventImpl;
+
+use crate::Sealed;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    time::{Duration, Instant},
+};
+
+/// A named event, which allows processes to sleep until another process notifies them, typically about a change it
+/// made to [shared memory](crate::shared_memory).
+///
+/// The event works like a [condition variable](std::sync::Condvar) that isn't tied to any particular mutex: waiters
+/// are blocked until the event is notified with [`.notify_one()`](Self::notify_one) or
+/// [`.notify_all()`](Self::notify_all), and notifications sent while nobody is waiting are not remembered. To avoid
+/// missing a notification that arrives between checking the shared data and starting to wait, the
+/// [`.wait_until()`](Self::wait_until) family of methods should be used, which check a condition in a way that's
+/// guaranteed to notice any notification sent after the data it depends on was changed.
+///
+/// Waits may end spuriously, i.e. without a notification having been sent, which is why the condition being waited
+/// for should always be checked after waking up.
+///
+/// # Platform-specific behavior
+/// ## Linux
+/// Backed by a futex in a [shared memory object](crate::shared_memory) with the same name.
+///
+/// ## Other platforms
+/// Backed by a shared memory object with the same name, which holds a notification counter and the number of waiters,
+/// together with a [named semaphore](super::NamedSemaphore) whose name is the name of the event with `-event`
+/// appended, which the waiters sleep on. On Windows, `WaitOnAddress` cannot be used instead, since it only works within
+/// a single process.
+///
+/// # Example
+/// ```
+/// use interprocess::{shared_memory::SharedMemory, sync::NamedEvent};
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// let name = format!("interprocess-doctest-event-{}", std::process::id());
+/// let event = NamedEvent::create(&name)?;
+/// let shm = SharedMemory::create(format!("{name}-data"), 1)?;
+/// let mapping = shm.map()?;
+/// let ready = unsafe { &*(mapping.as_ptr() as *const AtomicBool) };
+///
+/// std::thread::scope(|scope| {
+///     // Would normally be done in another process.
+///     let notifier = scope.spawn(|| {
+///         ready.store(true, Ordering::Release);
+///         NamedEvent::open(&name)?.notify_all()
+///     });
+///     event.wait_until(|| ready.load(Ordering::Acquire))?;
+///     notifier.join().unwrap()
+/// })?;
+///
+/// # #[cfg(unix)] {
+/// use interprocess::os::unix::{shared_memory::SharedMemoryExt, sync::NamedEventExt};
+/// event.unlink()?;
+/// shm.unlink()?;
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct NamedEvent {
+    // pub(crate) to allow the platform specific extension traits to reach the name
+    pub(crate) inner: NamedEventImpl,
+}
+impl NamedEvent {
+    /// Creates a new event with the specified name.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if an event with the same name exists already.
+    pub fn create(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        NamedEventImpl::create(name.as_ref()).map(|inner| Self { inner })
+    }
+    /// Opens an existing event with the specified name.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such event.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        NamedEventImpl::open(name.as_ref()).map(|inner| Self { inner })
+    }
+    /// Wakes up one of the threads waiting on the event, if any.
+    pub fn notify_one(&self) -> io::Result<()> {
+        self.inner.notify(false)
+    }
+    /// Wakes up all of the threads waiting on the event.
+    pub fn notify_all(&self) -> io::Result<()> {
+        self.inner.notify(true)
+    }
+    /// Waits for the event to be notified.
+    pub fn wait(&self) -> io::Result<()> {
+        let seq = self.inner.seq();
+        self.inner.wait_for_change(seq, None).map(drop)
+    }
+    /// Waits for the event to be notified for at most the given amount of time, returning `false` if the timeout ran
+    /// out first.
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        let seq = self.inner.seq();
+        self.inner.wait_for_change(seq, Some(timeout))
+    }
+    /// Waits until the given condition is true, checking it once right away and then every time the event is
+    /// notified.
+    ///
+    /// As long as the data that the condition depends on is changed before the event is notified, the change is
+    /// guaranteed to be noticed.
+    pub fn wait_until(&self, mut condition: impl FnMut() -> bool) -> io::Result<()> {
+        loop {
+            let seq = self.inner.seq();
+            if condition() {
+                return Ok(());
+            }
+            self.inner.wait_for_change(seq, None)?;
+        }
+    }
+    /// Same as [`.wait_until()`](Self::wait_until), but gives up once the given amount of time has passed, returning
+    /// `false` if the condition is still not true by then.
+    pub fn wait_until_timeout(&self, mut condition: impl FnMut() -> bool, timeout: Duration) -> io::Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let seq = self.inner.seq();
+            if condition() {
+                return Ok(true);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.inner.wait_for_change(seq, Some(remaining))? {
+                return Ok(condition());
+            }
+        }
+    }
+}
+impl Debug for NamedEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl Sealed for NamedEvent {}
@@ -0,0 +1,43 @@
+//! Named synchronization primitives, which can be opened by unrelated processes to coordinate access to
+//! [shared memory](crate::shared_memory) or other shared resources.
+//!
+//! Like [shared memory objects](crate::shared_memory::SharedMemory), the primitives of this module are identified by
+//! names, and the same platform-specific rules apply to them: on Unix, they persist until unlinked, which is done
+//! through the extension traits in `os::unix::sync`, while on Windows, they are destroyed once the last handle to them
+//! is closed. Since Windows keeps all named kernel objects in a single namespace, a name can only be used by one object
+//! at a time there, even if the objects are of different types.
+
+pub(crate) mod event;
+pub use event::NamedEvent;
+
+mod mutex;
+pub use mutex::*;
+
+mod semaphore;
+pub use semaphore::*;
+
+use std::{
+    io, thread,
+    time::{Duration, Instant},
+};
+
+/// How long openers of a primitive wait for its creator to finish setting it up before giving up.
+const INIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Retries opening a primitive until the given function reports that its creator has finished setting it up, failing
+/// with [`TimedOut`](io::ErrorKind::TimedOut) if that takes too long.
+pub(crate) fn open_when_ready<T>(mut try_open: impl FnMut() -> io::Result<Option<T>>) -> io::Result<T> {
+    let deadline = Instant::now() + INIT_TIMEOUT;
+    loop {
+        if let Some(opened) = try_open()? {
+            return Ok(opened);
+        }
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the object was not set up by its creator in time",
+            ));
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
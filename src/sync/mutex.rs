@@ -0,0 +1,122 @@
+impmod! {sync::mutex,
+    NamedMutex as NamedMutexImpl,
+    NamedMutexGuard as NamedMutexGuardImpl,
+}
+use crate::Sealed;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    marker::PhantomData,
+};
+
+/// A named mutual exclusion lock, which can be held by at most one thread of one process at a time.
+///
+/// Unlike [`std::sync::Mutex`], this mutex doesn't contain the data it protects, since that data normally lives in
+/// [shared memory](crate::shared_memory) that is mapped separately by every process. Holding a [guard](NamedMutexGuard)
+/// is what grants permission to access the data.
+///
+/// # Abandonment
+/// If a process exits or crashes while holding the mutex, the mutex is unlocked by the system and the next process to
+/// lock it is informed of this through [`.was_abandoned()`](NamedMutexGuard::was_abandoned) on its guard. The data
+/// protected by the mutex might have been left in an inconsistent state by the previous owner, and should be checked
+/// or reset before being relied upon. Abandonment is only reported once: from then on, the mutex is considered
+/// consistent again.
+///
+/// # Platform-specific behavior
+/// ## Windows
+/// Backed by a mutex object (`CreateMutexW`). A thread that holds the mutex can lock it again without blocking, in
+/// which case the mutex is only unlocked once all of the guards are dropped.
+///
+/// ## Linux
+/// Backed by a robust process-shared `pthread_mutex_t` placed in a [shared memory object](crate::shared_memory) with
+/// the same name. Locking the mutex again from the thread that holds it fails with an error.
+///
+/// ## Other Unix-like systems
+/// Backed by an advisory `flock()` lock on a file in `/tmp`, since robust process-shared mutexes are not widely
+/// supported outside of Linux. Locking the mutex again from the thread that holds it deadlocks.
+///
+/// # Example
+/// ```
+/// use interprocess::sync::NamedMutex;
+///
+/// let name = format!("interprocess-doctest-mutex-{}", std::process::id());
+/// let mutex = NamedMutex::create(&name)?;
+///
+/// let guard = mutex.lock()?;
+/// assert!(!guard.was_abandoned());
+/// // Would normally be done in another process.
+/// let other = NamedMutex::open(&name)?;
+/// let locked_elsewhere = std::thread::spawn(move || other.try_lock().map(|g| g.is_some())).join().unwrap()?;
+/// assert!(!locked_elsewhere);
+/// drop(guard);
+///
+/// # #[cfg(unix)] {
+/// use interprocess::os::unix::sync::NamedMutexExt;
+/// mutex.unlink()?;
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct NamedMutex {
+    // pub(crate) to allow the platform specific extension traits to reach the handle and the name
+    pub(crate) inner: NamedMutexImpl,
+}
+impl NamedMutex {
+    /// Creates a new unlocked mutex with the specified name.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if a mutex with the same name exists already.
+    pub fn create(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        NamedMutexImpl::create(name.as_ref()).map(|inner| Self { inner })
+    }
+    /// Opens an existing mutex with the specified name.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such mutex.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        NamedMutexImpl::open(name.as_ref()).map(|inner| Self { inner })
+    }
+    /// Waits for the mutex to become unlocked, then locks it, returning a guard which unlocks it when dropped.
+    pub fn lock(&self) -> io::Result<NamedMutexGuard<'_>> {
+        self.inner.lock().map(NamedMutexGuard::new)
+    }
+    /// Locks the mutex if it's unlocked, returning `None` if it's held by someone else.
+    pub fn try_lock(&self) -> io::Result<Option<NamedMutexGuard<'_>>> {
+        self.inner.try_lock().map(|g| g.map(NamedMutexGuard::new))
+    }
+}
+impl Debug for NamedMutex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl Sealed for NamedMutex {}
+
+/// A guard which keeps a [`NamedMutex`] locked until it's dropped.
+///
+/// The guard cannot be sent to other threads, since mutexes have to be unlocked by the same thread that locked them.
+pub struct NamedMutexGuard<'a> {
+    inner: NamedMutexGuardImpl<'a>,
+    abandoned: bool,
+    _thread_bound: PhantomData<*const ()>,
+}
+impl<'a> NamedMutexGuard<'a> {
+    fn new((inner, abandoned): (NamedMutexGuardImpl<'a>, bool)) -> Self {
+        Self {
+            inner,
+            abandoned,
+            _thread_bound: PhantomData,
+        }
+    }
+    /// Returns `true` if the previous owner of the mutex exited without unlocking it, meaning that the data it protects
+    /// might be in an inconsistent state. See the [type-level documentation](NamedMutex#abandonment) for more.
+    pub fn was_abandoned(&self) -> bool {
+        self.abandoned
+    }
+}
+impl Debug for NamedMutexGuard<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamedMutexGuard")
+            .field("inner", &self.inner)
+            .field("abandoned", &self.abandoned)
+            .finish()
+    }
+}
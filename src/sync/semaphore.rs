@@ -0,0 +1,84 @@
+impmod! {sync::semaphore,
+    NamedSemaphore as NamedSemaphoreImpl,
+}
+use crate::Sealed;
+use std::{
+    ffi::OsStr,
+    fmt::{self, Debug, Formatter},
+    io,
+    time::Duration,
+};
+
+/// A named counting semaphore, backed by `sem_open` on Unix and `CreateSemaphoreW` on Windows.
+///
+/// A semaphore holds a count which is decremented by [waiting](Self::wait) and incremented by [posting](Self::post),
+/// with waits blocking for as long as the count is zero. The most common use for a semaphore is to signal that a piece
+/// of shared memory has been filled with data.
+///
+/// # Example
+/// ```
+/// use interprocess::sync::NamedSemaphore;
+/// use std::time::Duration;
+///
+/// let name = format!("interprocess-doctest-sem-{}", std::process::id());
+/// let sem = NamedSemaphore::create(&name, 0)?;
+/// assert!(!sem.try_wait()?);
+///
+/// // Would normally be done in another process.
+/// NamedSemaphore::open(&name)?.post()?;
+///
+/// assert!(sem.wait_timeout(Duration::from_secs(1))?);
+/// assert!(!sem.wait_timeout(Duration::from_millis(10))?);
+/// # #[cfg(unix)] {
+/// use interprocess::os::unix::sync::NamedSemaphoreExt;
+/// sem.unlink()?;
+/// # }
+/// # std::io::Result::<()>::Ok(())
+/// ```
+pub struct NamedSemaphore {
+    // pub(crate) to allow the platform specific extension traits to reach the handle and the name
+    pub(crate) inner: NamedSemaphoreImpl,
+}
+impl NamedSemaphore {
+    /// Creates a new semaphore with the specified name and initial count.
+    ///
+    /// Fails with [`AlreadyExists`](io::ErrorKind::AlreadyExists) if a semaphore with the same name exists already.
+    /// The highest allowed count is platform-specific, but is at least 32767 everywhere.
+    pub fn create(name: impl AsRef<OsStr>, initial_count: u32) -> io::Result<Self> {
+        NamedSemaphoreImpl::create(name.as_ref(), initial_count).map(|inner| Self { inner })
+    }
+    /// Opens an existing semaphore with the specified name.
+    ///
+    /// Fails with [`NotFound`](io::ErrorKind::NotFound) if there is no such semaphore.
+    pub fn open(name: impl AsRef<OsStr>) -> io::Result<Self> {
+        NamedSemaphoreImpl::open(name.as_ref()).map(|inner| Self { inner })
+    }
+    /// Increments the count of the semaphore, waking up one of the processes waiting on it, if any.
+    pub fn post(&self) -> io::Result<()> {
+        self.inner.post()
+    }
+    /// Waits for the count of the semaphore to become nonzero, then decrements it.
+    pub fn wait(&self) -> io::Result<()> {
+        self.inner.wait()
+    }
+    /// Decrements the count of the semaphore if it's nonzero, returning whether it was.
+    pub fn try_wait(&self) -> io::Result<bool> {
+        self.inner.try_wait()
+    }
+    /// Waits for the count of the semaphore to become nonzero for at most the given amount of time, then decrements it,
+    /// returning `false` if the timeout ran out first.
+    ///
+    /// # Platform-specific behavior
+    /// ## macOS and iOS
+    /// Apple platforms lack `sem_timedwait`, so the wait is done by repeatedly polling the semaphore with short sleeps
+    /// in between, which makes the wakeup less prompt.
+    pub fn wait_timeout(&self, timeout: Duration) -> io::Result<bool> {
+        self.inner.wait_timeout(timeout)
+    }
+}
+impl Debug for NamedSemaphore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.inner, f)
+    }
+}
+impl Sealed for NamedSemaphore {}
@@ -0,0 +1,188 @@
+//! A wrapper which puts a deadline on every individual blocking operation of a stream or a listener.
+//!
+//! Blocking streams and listeners wait for as long as it takes for the other side to do its part, and the ways to put a
+//! limit on that differ from type to type – some listeners have an `accept_timeout()` method, while streams only offer
+//! waiting for readiness in [nonblocking mode](crate::local_socket::LocalSocketStream::set_nonblocking). A [`Timeout`]
+//! wraps any of them and gives each read, write or accept a deadline of its own, after which it fails with
+//! [`TimedOut`](io::ErrorKind::TimedOut):
+//! ```no_run
+//! use interprocess::{local_socket::LocalSocketStream, timeout::Timeout};
+//! use std::{io::prelude::*, time::Duration};
+//!
+//! let conn = LocalSocketStream::connect("@example_timeout.sock")?;
+//! let mut conn = Timeout::new(conn)
+//!     .read_timeout(Duration::from_secs(5))
+//!     .write_timeout(Duration::from_secs(1));
+//! conn.write_all(b"Hello from client!\n")?;
+//! let mut reply = [0; 128];
+//! // Fails if the server doesn't answer within five seconds.
+//! let size = conn.read(&mut reply)?;
+//! # let _ = size;
+//! # std::io::Result::<()>::Ok(())
+//! ```
+//!
+//! The deadlines are enforced with the same mechanisms as the waiting methods of the wrapped types use: `poll` on Unix
+//! and, since local sockets on Windows don't use overlapped I/O, polling at short intervals on Windows, which may delay
+//! the wakeup by a few milliseconds.
+
+use std::{
+    io::{self, prelude::*},
+    time::{Duration, Instant},
+};
+
+/// Streams whose readiness for reading and writing can be waited for with a timeout, which makes them usable with
+/// [`Timeout`].
+pub trait WaitReady {
+    /// Waits until reading would not block, returning `false` if the timeout expires first. No timeout means waiting
+    /// indefinitely.
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool>;
+    /// Waits until writing would not block, returning `false` if the timeout expires first. No timeout means waiting
+    /// indefinitely.
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool>;
+    /// Enables or disables the nonblocking mode.
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+}
+
+impl<T: WaitReady + ?Sized> WaitReady for &T {
+    #[inline]
+    fn wait_readable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        (**self).wait_readable(timeout)
+    }
+    #[inline]
+    fn wait_writable(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        (**self).wait_writable(timeout)
+    }
+    #[inline]
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        (**self).set_nonblocking(nonblocking)
+    }
+}
+
+/// Listeners which can accept connections with a timeout, which makes them usable with [`Timeout`].
+pub trait AcceptTimeout {
+    /// The type of the accepted connections.
+    type Stream;
+    /// Blocks until a client connects.
+    fn accept(&self) -> io::Result<Self::Stream>;
+    /// Blocks until a client connects or the timeout expires, in which case a [`TimedOut`](io::ErrorKind::TimedOut)
+    /// error is returned.
+    fn accept_timeout(&self, timeout: Duration) -> io::Result<Self::Stream>;
+}
+
+/// A stream or a listener with a deadline on each of its blocking operations.
+///
+/// Streams implementing [`WaitReady`] are readable and writable through the wrapper, and listeners implementing
+/// [`AcceptTimeout`] can accept connections through it. Operations without a timeout set block for as long as they would
+/// without the wrapper. See the [module-level documentation](self) for more.
+///
+/// # Nonblocking mode
+/// Writes can only be interrupted once they've started if the stream is in nonblocking mode, which is why the stream is
+/// switched to it by the first read or write which has a timeout. [`.into_inner()`](Self::into_inner) switches it back.
+#[derive(Debug)]
+pub struct Timeout<T> {
+    inner: T,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    accept_timeout: Option<Duration>,
+    switched_to_nonblocking: bool,
+}
+impl<T> Timeout<T> {
+    /// Wraps the given stream or listener without setting any timeouts.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            read_timeout: None,
+            write_timeout: None,
+            accept_timeout: None,
+            switched_to_nonblocking: false,
+        }
+    }
+    /// Sets the timeout for each individual read.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+    /// Sets the timeout for each individual write.
+    ///
+    /// With [`write_all()`](Write::write_all), each of the writes it consists of gets the full timeout, which means that
+    /// it only fails once the other side stops receiving data for that long.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+    /// Sets the timeout for each individual accept.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn accept_timeout(mut self, timeout: Duration) -> Self {
+        self.accept_timeout = Some(timeout);
+        self
+    }
+    /// Borrows the wrapped stream or listener.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+}
+impl<T: WaitReady> Timeout<T> {
+    /// Unwraps the stream, switching it back to blocking mode if the wrapper has switched it to nonblocking mode.
+    pub fn into_inner(self) -> io::Result<T> {
+        if self.switched_to_nonblocking {
+            self.inner.set_nonblocking(false)?;
+        }
+        Ok(self.inner)
+    }
+    /// Performs `op`, waiting for the stream to become ready with `wait` for as long as `timeout` allows if it would
+    /// block.
+    fn with_deadline<R>(
+        &mut self,
+        timeout: Option<Duration>,
+        wait: fn(&T, Option<Duration>) -> io::Result<bool>,
+        mut op: impl FnMut(&mut T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        if timeout.is_some() && !self.switched_to_nonblocking {
+            self.inner.set_nonblocking(true)?;
+            self.switched_to_nonblocking = true;
+        }
+        if !self.switched_to_nonblocking {
+            return op(&mut self.inner);
+        }
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match op(&mut self.inner) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                els => return els,
+            }
+            let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if remaining == Some(Duration::ZERO) || !wait(&self.inner, remaining)? {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the stream didn't become ready before the timeout expired",
+                ));
+            }
+        }
+    }
+}
+impl<T: AcceptTimeout> Timeout<T> {
+    /// Accepts a connection, failing with [`TimedOut`](io::ErrorKind::TimedOut) if no client connects before the
+    /// [accept timeout](Self::accept_timeout) expires.
+    pub fn accept(&self) -> io::Result<T::Stream> {
+        match self.accept_timeout {
+            Some(timeout) => self.inner.accept_timeout(timeout),
+            None => self.inner.accept(),
+        }
+    }
+}
+impl<T: WaitReady + Read> Read for Timeout<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_deadline(self.read_timeout, T::wait_readable, |inner| inner.read(buf))
+    }
+}
+impl<T: WaitReady + Write> Write for Timeout<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.with_deadline(self.write_timeout, T::wait_writable, |inner| inner.write(buf))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.with_deadline(self.write_timeout, T::wait_writable, |inner| inner.flush())
+    }
+}
@@ -0,0 +1,439 @@
+use super::{
+    check_header, decode, decode_len, encode, eof_in_message, header, Codec, DEFAULT_MAX_MESSAGE_SIZE, HEADER_LEN,
+    LEN_PREFIX_LEN,
+};
+use crate::local_socket::{
+    BorrowedTransferable, BoundName, LocalSocketListener, LocalSocketStream, OwnedTransferable, ToLocalSocketName,
+};
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, FromRawFd, IntoRawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, FromRawHandle, IntoRawHandle};
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug, Formatter},
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+/// The maximum number of file descriptors or handles that can be attached to one message.
+const MAX_HANDLES_PER_MESSAGE: usize = 253;
+
+thread_local! {
+    /// The resources attached to the message which is being serialized on this thread by an [`IpcSender`], if any.
+    static OUTGOING: RefCell<Option<Vec<OwnedTransferable>>> = const { RefCell::new(None) };
+    /// The resources received with the message which is being deserialized on this thread by an [`IpcReceiver`], if
+    /// any. Each one is taken out of its slot once it's deserialized.
+    static INCOMING: RefCell<Option<Vec<Option<OwnedTransferable>>>> = const { RefCell::new(None) };
+}
+
+/// Adds a resource to the message being serialized, returning its index among the attached resources.
+fn attach(resource: OwnedTransferable) -> Result<u32, &'static str> {
+    OUTGOING.with(|outgoing| match &mut *outgoing.borrow_mut() {
+        Some(resources) => {
+            resources.push(resource);
+            Ok((resources.len() - 1) as u32)
+        }
+        None => Err("file descriptors and handles can only be serialized by an IpcSender"),
+    })
+}
+/// Takes a resource received with the message being deserialized out of its slot.
+fn take_attached(index: u32) -> Result<OwnedTransferable, &'static str> {
+    INCOMING.with(|incoming| match &mut *incoming.borrow_mut() {
+        Some(resources) => resources
+            .get_mut(index as usize)
+            .and_then(Option::take)
+            .ok_or("the message refers to a file descriptor or handle which was not attached to it"),
+        None => Err("file descriptors and handles can only be deserialized by an IpcReceiver"),
+    })
+}
+
+#[cfg(unix)]
+fn stream_into_transferable(stream: LocalSocketStream) -> OwnedTransferable {
+    // SAFETY: the stream gives up ownership of the descriptor
+    unsafe { OwnedTransferable::from_raw_fd(stream.into_raw_fd()) }
+}
+#[cfg(windows)]
+fn stream_into_transferable(stream: LocalSocketStream) -> OwnedTransferable {
+    // SAFETY: the stream gives up ownership of the handle
+    unsafe { OwnedTransferable::from_raw_handle(stream.into_raw_handle()) }
+}
+// The resource is only turned into a stream when an IpcSender or IpcReceiver is deserialized, so it's a local socket
+// stream unless the peer is misbehaving, in which case the resulting stream simply fails to work.
+#[cfg(unix)]
+fn stream_from_transferable(resource: OwnedTransferable) -> LocalSocketStream {
+    // SAFETY: the resource gives up ownership of the descriptor
+    unsafe { LocalSocketStream::from_raw_fd(resource.into_raw_fd()) }
+}
+#[cfg(windows)]
+fn stream_from_transferable(resource: OwnedTransferable) -> LocalSocketStream {
+    // SAFETY: the resource gives up ownership of the handle
+    unsafe { LocalSocketStream::from_raw_handle(resource.into_raw_handle()) }
+}
+
+/// Creates a connected pair of a sender and a receiver of values of type `T`, either of which can then be sent to
+/// another process.
+///
+/// The ends are connected by a local socket with a [generated name](crate::local_socket::LocalSocketNameGen), which is
+/// only used to establish the connection and disappears right after. Either end can then be moved to another process by
+/// sending it through an existing channel as part of a message, since both [`IpcSender`] and [`IpcReceiver`] are
+/// serializable – or, for the first channel between two processes, by [bootstrapping](IpcOneShotServer) it through a
+/// name.
+///
+/// # Example
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use interprocess::typed::{channel, IpcReceiver};
+///
+/// let (mut sender, mut receiver) = channel::<IpcReceiver<String>>()?;
+/// let (mut inner_sender, inner_receiver) = channel::<String>()?;
+/// // Would normally be received by another process.
+/// sender.send(&inner_receiver)?;
+/// drop(inner_receiver);
+/// let mut inner_receiver = receiver.recv()?.unwrap();
+///
+/// inner_sender.send(&"Hello from the other side!".to_string())?;
+/// assert_eq!(inner_receiver.recv()?.as_deref(), Some("Hello from the other side!"));
+/// # Ok(()) }
+/// ```
+pub fn channel<T: Serialize + for<'de> Deserialize<'de>>() -> io::Result<(IpcSender<T>, IpcReceiver<T>)> {
+    let (listener, bound_name) = bind_generated()?;
+    let sender = IpcSender::connect(bound_name.name().clone())?;
+    let receiver = IpcReceiver::new(listener.accept()?)?;
+    Ok((sender, receiver))
+}
+
+fn bind_generated() -> io::Result<(LocalSocketListener, BoundName)> {
+    LocalSocketListener::bind_generated("interprocess-channel")
+}
+
+/// The sending end of a channel created with [`channel()`] or [`IpcSender::connect()`], which serializes values of type
+/// `T` and sends them over a local socket, together with the file descriptors or handles they contain.
+///
+/// The values are serialized with `bincode`, in the same format as with [`TypedSender`](super::TypedSender), except
+/// that every message is sent together with the resources wrapped in [`IpcHandle`]s, [`IpcSender`]s and
+/// [`IpcReceiver`]s found in the value. On Unix, they are sent as `SCM_RIGHTS` ancillary data; on Windows, they are
+/// duplicated into the receiving process. See [`LocalSocketStream::send_handles()`] for the details.
+///
+/// The sender itself can be sent to another process as part of a value, in which case the stream is duplicated and the
+/// copy in the current process should be dropped – messages from both copies end up in the same channel, and the
+/// receiver only sees the end of the channel once all of them are gone.
+pub struct IpcSender<T> {
+    stream: LocalSocketStream,
+    buf: Vec<u8>,
+    _phantom: PhantomData<fn(&T)>,
+}
+impl<T: Serialize> IpcSender<T> {
+    /// Connects to an [`IpcOneShotServer`] by the name it was created with.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        let stream = LocalSocketStream::connect(name)?;
+        (&stream).write_all(&header(Codec::Bincode))?;
+        Ok(Self::from_stream(stream))
+    }
+    /// Serializes the value and sends it as one message, together with the file descriptors or handles it contains.
+    ///
+    /// Values which cannot be serialized, ones whose serialized form doesn't fit into 4 GiB and ones that contain
+    /// more than 253 resources are reported as [`InvalidInput`](io::ErrorKind::InvalidInput) errors without sending
+    /// anything.
+    pub fn send(&mut self, value: &T) -> io::Result<()> {
+        let previous = OUTGOING.with(|outgoing| outgoing.replace(Some(Vec::new())));
+        let encoded = encode(&mut self.buf, Codec::Bincode, value);
+        let resources = OUTGOING.with(|outgoing| outgoing.replace(previous)).unwrap_or_default();
+        encoded?;
+        if resources.len() > MAX_HANDLES_PER_MESSAGE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("a message cannot carry more than {MAX_HANDLES_PER_MESSAGE} file descriptors or handles"),
+            ));
+        }
+
+        #[cfg(unix)]
+        let borrowed = resources
+            .iter()
+            .map(AsFd::as_fd)
+            .collect::<Vec<BorrowedTransferable<'_>>>();
+        #[cfg(windows)]
+        let borrowed = resources
+            .iter()
+            .map(AsHandle::as_handle)
+            .collect::<Vec<BorrowedTransferable<'_>>>();
+        let sent = self.stream.send_handles(&self.buf, &borrowed)?;
+        (&self.stream).write_all(&self.buf[sent..])
+    }
+}
+impl<T> IpcSender<T> {
+    fn from_stream(stream: LocalSocketStream) -> Self {
+        Self {
+            stream,
+            buf: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<T> Debug for IpcSender<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcSender").field("stream", &self.stream).finish()
+    }
+}
+impl<T> Serialize for IpcSender<T> {
+    /// Attaches a duplicate of the underlying stream to the message. Fails outside of [`IpcSender::send()`].
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stream = self.stream.try_clone().map_err(ser::Error::custom)?;
+        let index = attach(stream_into_transferable(stream)).map_err(ser::Error::custom)?;
+        serializer.serialize_u32(index)
+    }
+}
+impl<'de, T> Deserialize<'de> for IpcSender<T> {
+    /// Takes the attached stream over. Fails outside of [`IpcReceiver::recv()`].
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)?;
+        let resource = take_attached(index).map_err(de::Error::custom)?;
+        Ok(Self::from_stream(stream_from_transferable(resource)))
+    }
+}
+
+/// The receiving end of a channel created with [`channel()`] or accepted by an [`IpcOneShotServer`], which receives
+/// messages from a local socket together with the file descriptors or handles attached to them and deserializes them
+/// into values of type `T`.
+///
+/// The receiver can be sent to another process as part of a value, in which case the stream is duplicated and the copy
+/// in the current process should be dropped – otherwise, the two copies compete for the messages.
+pub struct IpcReceiver<T> {
+    stream: LocalSocketStream,
+    buf: Vec<u8>,
+    max_message_size: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+impl<T: for<'de> Deserialize<'de>> IpcReceiver<T> {
+    fn new(stream: LocalSocketStream) -> io::Result<Self> {
+        let mut header = [0; HEADER_LEN];
+        (&stream).read_exact(&mut header)?;
+        if check_header(&header)?.is_none() {
+            let mut id = [0];
+            (&stream).read_exact(&mut id)?;
+            if Codec::from_id(id[0])? != Codec::Bincode {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "channels with resources attached to messages only support the bincode codec",
+                ));
+            }
+        }
+        Ok(Self::from_stream(stream))
+    }
+    /// Receives one message and deserializes it, taking over the resources attached to it. Returns `None` if all
+    /// senders have been dropped before the next message.
+    ///
+    /// Messages bigger than the [maximum message size](Self::set_max_message_size) and ones that fail to deserialize
+    /// are reported as [`InvalidData`](io::ErrorKind::InvalidData) errors, and resources which were attached to the
+    /// message but are not part of the deserialized value are closed. If the channel ends in the middle of a message,
+    /// an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned.
+    pub fn recv(&mut self) -> io::Result<Option<T>> {
+        let mut prefix = [0; LEN_PREFIX_LEN];
+        let (filled, resources) = match self.stream.recv_handles(&mut prefix) {
+            Ok((0, _)) => return Ok(None),
+            Ok(rslt) => rslt,
+            // Named pipes end in the middle of the header of `.recv_handles()` rather than with a zero-sized read.
+            #[cfg(windows)]
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let rest = (&self.stream).read_exact(&mut prefix[filled..]);
+        let len = rest
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::UnexpectedEof => eof_in_message(),
+                _ => e,
+            })
+            .and_then(|()| decode_len(prefix, self.max_message_size))?;
+        self.buf.resize(len, 0);
+        (&self.stream).read_exact(&mut self.buf).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => eof_in_message(),
+            _ => e,
+        })?;
+
+        let resources = resources.into_iter().map(Some).collect();
+        let previous = INCOMING.with(|incoming| incoming.replace(Some(resources)));
+        let value = decode(&self.buf, Codec::Bincode);
+        // Whatever the value didn't take over is closed here.
+        INCOMING.with(|incoming| incoming.replace(previous));
+        value.map(Some)
+    }
+}
+impl<T> IpcReceiver<T> {
+    fn from_stream(stream: LocalSocketStream) -> Self {
+        Self {
+            stream,
+            buf: Vec::new(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            _phantom: PhantomData,
+        }
+    }
+    /// Returns the maximum size of a serialized value accepted by the receiver, in bytes.
+    #[inline]
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+    /// Sets the maximum size of a serialized value accepted by the receiver, in bytes. Defaults to
+    /// [`DEFAULT_MAX_MESSAGE_SIZE`]. The setting is not transferred when the receiver is sent to another process.
+    #[inline]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+}
+impl<T> Debug for IpcReceiver<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcReceiver")
+            .field("stream", &self.stream)
+            .field("max_message_size", &self.max_message_size)
+            .finish()
+    }
+}
+impl<T> Serialize for IpcReceiver<T> {
+    /// Attaches a duplicate of the underlying stream to the message. Fails outside of [`IpcSender::send()`].
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stream = self.stream.try_clone().map_err(ser::Error::custom)?;
+        let index = attach(stream_into_transferable(stream)).map_err(ser::Error::custom)?;
+        serializer.serialize_u32(index)
+    }
+}
+impl<'de, T> Deserialize<'de> for IpcReceiver<T> {
+    /// Takes the attached stream over. Fails outside of [`IpcReceiver::recv()`].
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)?;
+        let resource = take_attached(index).map_err(de::Error::custom)?;
+        Ok(Self::from_stream(stream_from_transferable(resource)))
+    }
+}
+
+/// A file descriptor or handle which can be sent to another process as part of a value sent through an
+/// [`IpcSender`].
+///
+/// Serializing the wrapper attaches a duplicate of the resource to the message, and deserializing it on the receiving
+/// end yields the received copy. Outside of [`IpcSender::send()`] and [`IpcReceiver::recv()`], serialization and
+/// deserialization fail.
+///
+/// # Example
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use interprocess::typed::{channel, IpcHandle};
+/// use std::{env, fs::{self, File}, io::prelude::*};
+///
+/// let (mut sender, mut receiver) = channel::<IpcHandle>()?;
+/// let path = env::temp_dir().join(format!("interprocess-ipc-handle-{}.txt", std::process::id()));
+/// let mut file = File::options().read(true).write(true).create(true).truncate(true).open(&path)?;
+/// file.write_all(b"Hello from the other side!")?;
+/// // Would normally be received by another process.
+/// sender.send(&IpcHandle::new(file))?;
+///
+/// let mut file = File::from(receiver.recv()?.unwrap().into_inner());
+/// let mut contents = String::new();
+/// file.rewind()?;
+/// file.read_to_string(&mut contents)?;
+/// assert_eq!(contents, "Hello from the other side!");
+/// drop(file);
+/// fs::remove_file(path)?;
+/// # Ok(()) }
+/// ```
+pub struct IpcHandle(OwnedTransferable);
+impl IpcHandle {
+    /// Wraps a resource, such as a [`File`](std::fs::File), to be sent to another process.
+    pub fn new(resource: impl Into<OwnedTransferable>) -> Self {
+        Self(resource.into())
+    }
+    /// Unwraps the resource, which can then be converted into the appropriate standard library type via `From`.
+    pub fn into_inner(self) -> OwnedTransferable {
+        self.0
+    }
+}
+impl Debug for IpcHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IpcHandle").field(&self.0).finish()
+    }
+}
+impl Serialize for IpcHandle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let resource = self.0.try_clone().map_err(ser::Error::custom)?;
+        let index = attach(resource).map_err(ser::Error::custom)?;
+        serializer.serialize_u32(index)
+    }
+}
+impl<'de> Deserialize<'de> for IpcHandle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)?;
+        take_attached(index).map(Self).map_err(de::Error::custom)
+    }
+}
+
+/// A server which accepts exactly one [`IpcSender`], for bootstrapping a channel between processes which don't have one
+/// yet, such as a parent and a newly spawned child.
+///
+/// The server listens on a [generated name](crate::local_socket::LocalSocketNameGen), which is passed to the other
+/// process out of band – typically as a command-line argument or an environment variable. The other process connects
+/// to it with [`IpcSender::connect()`] and sends the first message, which commonly contains more channel ends for
+/// further communication in either direction.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> std::io::Result<()> {
+/// use interprocess::typed::{channel, IpcOneShotServer, IpcReceiver, IpcSender};
+/// use std::{env, process::Command};
+///
+/// if let Ok(name) = env::var("BOOTSTRAP") {
+///     // Child process: send a receiver to the parent and wait for its greeting.
+///     let (sender, mut receiver) = channel::<String>()?;
+///     IpcSender::<IpcSender<String>>::connect(name)?.send(&sender)?;
+///     drop(sender);
+///     println!("{}", receiver.recv()?.unwrap());
+/// } else {
+///     let (server, name) = IpcOneShotServer::<IpcSender<String>>::new()?;
+///     let mut child = Command::new(env::current_exe()?).env("BOOTSTRAP", name).spawn()?;
+///     let (_receiver, mut sender) = server.accept()?;
+///     sender.send(&"Hello, child!".to_string())?;
+///     child.wait()?;
+/// }
+/// # Ok(()) }
+/// ```
+pub struct IpcOneShotServer<T> {
+    listener: LocalSocketListener,
+    _bound_name: BoundName,
+    _phantom: PhantomData<fn() -> T>,
+}
+impl<T: for<'de> Deserialize<'de>> IpcOneShotServer<T> {
+    /// Starts listening on a generated name, returning the server and the name, which can be passed to
+    /// [`IpcSender::connect()`].
+    ///
+    /// The name uses the [`@` syntax](ToLocalSocketName#-syntax-for-namespaced-paths) for namespaced names on platforms
+    /// which support both kinds. Fails with [`InvalidData`](io::ErrorKind::InvalidData) if the generated name is a
+    /// path that isn't valid Unicode, which happens if the temporary directory has such a path.
+    pub fn new() -> io::Result<(Self, String)> {
+        let (listener, bound_name) = bind_generated()?;
+        let string = bound_name.name().to_portable_string()?;
+        let server = Self {
+            listener,
+            _bound_name: bound_name,
+            _phantom: PhantomData,
+        };
+        Ok((server, string))
+    }
+    /// Waits for the other process to connect and send its first message, returning the receiver and the message.
+    ///
+    /// Fails with [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the other process disconnects without sending
+    /// anything.
+    pub fn accept(self) -> io::Result<(IpcReceiver<T>, T)> {
+        let mut receiver = IpcReceiver::new(self.listener.accept()?)?;
+        let first = receiver.recv()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the client disconnected before sending the first message",
+            )
+        })?;
+        Ok((receiver, first))
+    }
+}
+impl<T> Debug for IpcOneShotServer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcOneShotServer")
+            .field("listener", &self.listener)
+            .finish_non_exhaustive()
+    }
+}
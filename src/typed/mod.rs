@@ -0,0 +1,239 @@
+//! Typed channels, which send and receive Serde-serializable values over local sockets.
+//!
+//! Most applications don't want to deal with byte streams at all – they have a struct on one end of the connection
+//! and want the same struct on the other end. A [`TypedSender`] serializes values with `bincode` (or another
+//! [codec](#codecs)) and writes them into a stream, one message per value, and a [`TypedReceiver`] reads them back, so
+//! that the only thing the two ends need to agree on is the type being sent:
+//! ```no_run
+//! # fn main() -> std::io::Result<()> {
+//! use interprocess::{
+//!     local_socket::LocalSocketListener,
+//!     typed::{TypedReceiver, TypedSender},
+//! };
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Greeting {
+//!     name: String,
+//!     times: u32,
+//! }
+//!
+//! // Client side:
+//! let mut sender = TypedSender::<Greeting>::connect("/tmp/example.sock")?;
+//! sender.send(&Greeting { name: "client".to_string(), times: 3 })?;
+//!
+//! // Server side:
+//! let listener = LocalSocketListener::bind("/tmp/example.sock")?;
+//! let mut receiver = TypedReceiver::<Greeting>::new(listener.accept()?)?;
+//! while let Some(greeting) = receiver.recv()? {
+//!     println!("Received {greeting:?}");
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! The channels are one-directional. For a conversation in both directions, split the stream and wrap each half –
+//! the sender and the receiver work with any stream that implements [`Write`](std::io::Write) and
+//! [`Read`](std::io::Read) respectively. The `tokio` submodule has the asynchronous counterparts, with the `tokio`
+//! feature enabled.
+//!
+//! # Channels between processes
+//! For programs made of several cooperating processes, [`channel()`] creates a connected pair of an [`IpcSender`] and
+//! an [`IpcReceiver`] in the spirit of the `ipc-channel` crate. Both ends can be sent to other processes as part of a
+//! value – the file descriptor or handle of the underlying local socket is attached to the message and taken over by
+//! the receiving process, as are arbitrary resources wrapped in an [`IpcHandle`]. The first channel between two
+//! processes is established through a name with an [`IpcOneShotServer`].
+//!
+//! # Codecs
+//! Values are serialized with `bincode` unless the sender is created with a different [`Codec`] – JSON with the
+//! `typed-json` feature and CBOR with the `typed-cbor` feature. The receiver learns the codec from the header of the
+//! channel, so a receiver built with the corresponding feature accepts values in any of them, and a peer written in
+//! another language only needs to implement the framing described below and speak JSON or CBOR to exchange values with
+//! a Rust process over the same local socket. The channels between processes described above always use `bincode`.
+//!
+//! # Wire format
+//! Before sending anything, the sender writes a header which consists of the 8 bytes `IPCTYPED`, the format version, a
+//! little-endian 16-bit integer, currently equal to [`FORMAT_VERSION`], and the one-byte [identifier](Codec::id) of
+//! the codec: `0` for `bincode`, `1` for JSON and `2` for CBOR. The receiver checks the header when it is created,
+//! which prevents it from misinterpreting a stream that wasn't produced by a compatible sender. Headers of format
+//! version 1, which lack the codec identifier and imply `bincode`, are still accepted.
+//!
+//! After the header, every value is sent as a little-endian 32-bit length, followed by that many bytes of the
+//! serialized value – with the default configuration of `bincode` 1.x, as one JSON document in UTF-8, or as one CBOR
+//! data item. Since `bincode` is not self-describing, receiving a different type than the one that was sent is not
+//! guaranteed to fail – it's up to the application to only connect compatible ends, for example by including a version
+//! of its own in the socket name or in the first message.
+
+mod channel;
+mod receiver;
+mod sender;
+pub use {channel::*, receiver::*, sender::*};
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+use crate::{Error, ErrorKind};
+use serde::{de::DeserializeOwned, Serialize};
+use std::io;
+
+/// The version of the wire format which is sent by the typed channels.
+///
+/// Receivers also accept streams of format version 1, which had no codec identifier and always used `bincode`. See the
+/// [module-level documentation](self) for the description of the format.
+pub const FORMAT_VERSION: u16 = 2;
+
+/// The maximum size of a serialized value accepted by [`TypedReceiver`] unless configured otherwise: 16 MiB.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// The serialization format of the values sent over a typed channel.
+///
+/// The sender announces its codec in the header of the channel, and the receiver picks it up from there, so only the
+/// sending end needs to be configured. Codecs other than `bincode` are enabled by their own features. Every codec has
+/// a fixed identifier on the wire, which stays the same regardless of which features are enabled.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Codec {
+    /// The default configuration of `bincode` 1.x, which is compact but not self-describing and mostly limited to
+    /// Rust peers. Identified by `0` on the wire.
+    #[default]
+    Bincode,
+    /// JSON, as produced and parsed by `serde_json`, for interoperating with peers written in other languages.
+    /// Identified by `1` on the wire.
+    #[cfg(feature = "typed-json")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "typed-json")))]
+    Json,
+    /// CBOR, as produced and parsed by `ciborium`, a self-describing binary format with implementations in most
+    /// languages. Identified by `2` on the wire.
+    #[cfg(feature = "typed-cbor")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "typed-cbor")))]
+    Cbor,
+}
+impl Codec {
+    const BINCODE_ID: u8 = 0;
+    const JSON_ID: u8 = 1;
+    const CBOR_ID: u8 = 2;
+
+    /// Returns the identifier of the codec in the header of the channel.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Bincode => Self::BINCODE_ID,
+            #[cfg(feature = "typed-json")]
+            Self::Json => Self::JSON_ID,
+            #[cfg(feature = "typed-cbor")]
+            Self::Cbor => Self::CBOR_ID,
+        }
+    }
+    /// Looks up a codec by its identifier in the header of the channel, failing with an
+    /// [`InvalidData`](io::ErrorKind::InvalidData) error if it is unknown or its feature is disabled.
+    pub fn from_id(id: u8) -> io::Result<Self> {
+        match id {
+            Self::BINCODE_ID => Ok(Self::Bincode),
+            #[cfg(feature = "typed-json")]
+            Self::JSON_ID => Ok(Self::Json),
+            #[cfg(not(feature = "typed-json"))]
+            Self::JSON_ID => Err(missing_codec_feature(id, "typed-json")),
+            #[cfg(feature = "typed-cbor")]
+            Self::CBOR_ID => Ok(Self::Cbor),
+            #[cfg(not(feature = "typed-cbor"))]
+            Self::CBOR_ID => Err(missing_codec_feature(id, "typed-cbor")),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown typed channel codec {id}"),
+            )),
+        }
+    }
+}
+#[cfg(not(all(feature = "typed-json", feature = "typed-cbor")))]
+fn missing_codec_feature(id: u8, feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("typed channel codec {id} requires the `{feature}` feature of `interprocess`"),
+    )
+}
+
+const MAGIC: [u8; 8] = *b"IPCTYPED";
+const HEADER_LEN: usize = MAGIC.len() + 2;
+const CODEC_ID_LEN: usize = 1;
+const LEN_PREFIX_LEN: usize = 4;
+
+pub(crate) fn header(codec: Codec) -> [u8; HEADER_LEN + CODEC_ID_LEN] {
+    let mut header = [0; HEADER_LEN + CODEC_ID_LEN];
+    header[..MAGIC.len()].copy_from_slice(&MAGIC);
+    header[MAGIC.len()..HEADER_LEN].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header[HEADER_LEN] = codec.id();
+    header
+}
+/// Checks the fixed part of a received header. Returns the codec right away for format version 1, or `None` if the
+/// codec identifier follows, to be passed to [`Codec::from_id()`].
+fn check_header(header: &[u8; HEADER_LEN]) -> io::Result<Option<Codec>> {
+    if header[..MAGIC.len()] != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the peer is not a typed channel sender",
+        ));
+    }
+    match u16::from_le_bytes([header[MAGIC.len()], header[MAGIC.len() + 1]]) {
+        1 => Ok(Some(Codec::Bincode)),
+        FORMAT_VERSION => Ok(None),
+        version => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported typed channel format version {version} (expected {FORMAT_VERSION} or 1)"),
+        )),
+    }
+}
+
+/// Serializes the value into the buffer, prefixed with its length.
+pub(crate) fn encode<T: Serialize + ?Sized>(buf: &mut Vec<u8>, codec: Codec, value: &T) -> io::Result<()> {
+    buf.clear();
+    buf.extend_from_slice(&[0; LEN_PREFIX_LEN]);
+    match codec {
+        Codec::Bincode => {
+            bincode::serialize_into(&mut *buf, value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+        }
+        #[cfg(feature = "typed-json")]
+        Codec::Json => {
+            serde_json::to_writer(&mut *buf, value).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+        }
+        #[cfg(feature = "typed-cbor")]
+        Codec::Cbor => {
+            ciborium::into_writer(value, &mut *buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+        }
+    }
+    let len = u32::try_from(buf.len() - LEN_PREFIX_LEN).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "serialized value does not fit into a typed channel message",
+        )
+    })?;
+    buf[..LEN_PREFIX_LEN].copy_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+/// Checks a received length prefix against the maximum message size.
+fn decode_len(prefix: [u8; LEN_PREFIX_LEN], max_message_size: usize) -> io::Result<usize> {
+    let len = u32::from_le_bytes(prefix) as usize;
+    if len > max_message_size {
+        return Err(Error::with_io_kind(
+            ErrorKind::MessageTooLarge,
+            io::ErrorKind::InvalidData,
+            format!("typed channel message of {len} bytes exceeds the maximum size of {max_message_size} bytes"),
+        )
+        .into());
+    }
+    Ok(len)
+}
+fn decode<T: DeserializeOwned>(buf: &[u8], codec: Codec) -> io::Result<T> {
+    match codec {
+        Codec::Bincode => bincode::deserialize(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        #[cfg(feature = "typed-json")]
+        Codec::Json => serde_json::from_slice(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        #[cfg(feature = "typed-cbor")]
+        Codec::Cbor => ciborium::from_reader(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+fn eof_in_message() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "the stream ended in the middle of a typed channel message",
+    )
+}
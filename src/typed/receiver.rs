@@ -0,0 +1,117 @@
+use super::{
+    check_header, decode, decode_len, eof_in_message, Codec, DEFAULT_MAX_MESSAGE_SIZE, HEADER_LEN, LEN_PREFIX_LEN,
+};
+use crate::local_socket::LocalSocketStream;
+use serde::de::DeserializeOwned;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, Read},
+    marker::PhantomData,
+};
+
+/// The receiving end of a typed channel, which reads messages from a stream and deserializes them into values of type
+/// `T`.
+///
+/// See the [module-level documentation](super) for an example and the description of the wire format.
+pub struct TypedReceiver<T, S = LocalSocketStream> {
+    stream: S,
+    buf: Vec<u8>,
+    codec: Codec,
+    max_message_size: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+impl<T: DeserializeOwned, S: Read> TypedReceiver<T, S> {
+    /// Creates a receiver on the given stream, reading the header of the channel from it and deserializing values with
+    /// the codec announced there.
+    ///
+    /// Blocks until the sender has been created on the other end. Fails with an
+    /// [`InvalidData`](io::ErrorKind::InvalidData) error if the header doesn't match or names a codec which is not
+    /// enabled, and with an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error if the stream ends before the whole header is received.
+    pub fn new(mut stream: S) -> io::Result<Self> {
+        let mut header = [0; HEADER_LEN];
+        stream.read_exact(&mut header)?;
+        let codec = match check_header(&header)? {
+            Some(codec) => codec,
+            None => {
+                let mut id = [0];
+                stream.read_exact(&mut id)?;
+                Codec::from_id(id[0])?
+            }
+        };
+        Ok(Self {
+            stream,
+            buf: Vec::new(),
+            codec,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            _phantom: PhantomData,
+        })
+    }
+    /// Receives one message and deserializes it, returning `None` if the stream ends before the next message.
+    ///
+    /// Messages bigger than the [maximum message size](Self::set_max_message_size) and ones that fail to deserialize
+    /// are reported as [`InvalidData`](io::ErrorKind::InvalidData) errors. If the stream ends in the middle of a
+    /// message, an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned.
+    pub fn recv(&mut self) -> io::Result<Option<T>> {
+        let mut prefix = [0; LEN_PREFIX_LEN];
+        let mut filled = 0;
+        while filled < LEN_PREFIX_LEN {
+            match self.stream.read(&mut prefix[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(eof_in_message()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let len = decode_len(prefix, self.max_message_size)?;
+        self.buf.resize(len, 0);
+        self.stream.read_exact(&mut self.buf).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                eof_in_message()
+            } else {
+                e
+            }
+        })?;
+        decode(&self.buf, self.codec).map(Some)
+    }
+}
+impl<T, S> TypedReceiver<T, S> {
+    /// Returns the codec with which values are deserialized, as announced by the sender.
+    #[inline]
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+    /// Returns the maximum size of a serialized value accepted by the receiver, in bytes.
+    #[inline]
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+    /// Sets the maximum size of a serialized value accepted by the receiver, in bytes. Defaults to
+    /// [`DEFAULT_MAX_MESSAGE_SIZE`].
+    ///
+    /// The limit protects the receiver from allocating arbitrarily large buffers because of a misbehaving sender.
+    #[inline]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+    /// Unwraps the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+impl<T, S: Debug> Debug for TypedReceiver<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedReceiver")
+            .field("stream", &self.stream)
+            .field("codec", &self.codec)
+            .field("max_message_size", &self.max_message_size)
+            .finish()
+    }
+}
@@ -0,0 +1,76 @@
+use super::{encode, header, Codec};
+use crate::local_socket::{LocalSocketStream, ToLocalSocketName};
+use serde::Serialize;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, Write},
+    marker::PhantomData,
+};
+
+/// The sending end of a typed channel, which serializes values of type `T` and writes them into a stream.
+///
+/// See the [module-level documentation](super) for an example and the description of the wire format.
+pub struct TypedSender<T: ?Sized, S = LocalSocketStream> {
+    stream: S,
+    buf: Vec<u8>,
+    codec: Codec,
+    _phantom: PhantomData<fn(&T)>,
+}
+impl<T: Serialize + ?Sized> TypedSender<T> {
+    /// Connects to a local socket server and creates a sender on the resulting stream.
+    pub fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Self::new(LocalSocketStream::connect(name)?)
+    }
+}
+impl<T: Serialize + ?Sized, S: Write> TypedSender<T, S> {
+    /// Creates a sender on the given stream, writing the header of the channel into it. Values are serialized with
+    /// `bincode`.
+    pub fn new(stream: S) -> io::Result<Self> {
+        Self::with_codec(stream, Codec::Bincode)
+    }
+    /// Creates a sender on the given stream which serializes values with the specified codec, writing the header of
+    /// the channel into it. The receiver picks the codec up from the header.
+    pub fn with_codec(mut stream: S, codec: Codec) -> io::Result<Self> {
+        stream.write_all(&header(codec))?;
+        Ok(Self {
+            stream,
+            buf: Vec::new(),
+            codec,
+            _phantom: PhantomData,
+        })
+    }
+    /// Serializes the value and writes it into the stream as one message.
+    ///
+    /// Values which cannot be serialized, as well as ones whose serialized form doesn't fit into 4 GiB, are reported
+    /// as [`InvalidInput`](io::ErrorKind::InvalidInput) errors without writing anything into the stream.
+    pub fn send(&mut self, value: &T) -> io::Result<()> {
+        encode(&mut self.buf, self.codec, value)?;
+        self.stream.write_all(&self.buf)
+    }
+}
+impl<T: ?Sized, S> TypedSender<T, S> {
+    /// Returns the codec with which values are serialized.
+    #[inline]
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+    /// Unwraps the underlying stream. Anything written into it afterwards is interpreted by the receiver as part of
+    /// the channel.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+impl<T: ?Sized, S: Debug> Debug for TypedSender<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedSender")
+            .field("stream", &self.stream)
+            .field("codec", &self.codec)
+            .finish()
+    }
+}
@@ -0,0 +1,199 @@
+//! Asynchronous typed channels for Tokio.
+//!
+//! The types in this module are the asynchronous counterparts of [`TypedSender`](super::TypedSender) and
+//! [`TypedReceiver`](super::TypedReceiver), with the same wire format, so that an asynchronous end can be connected to
+//! a synchronous one. They work with any stream that implements Tokio's [`AsyncWrite`] and [`AsyncRead`] respectively,
+//! including the [Tokio local sockets](crate::local_socket::tokio) and their halves.
+//!
+//! # Example
+//! ```no_run
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! use interprocess::{local_socket::tokio::LocalSocketStream, typed::tokio::{TypedReceiver, TypedSender}};
+//!
+//! let (reader, writer) = LocalSocketStream::connect("/tmp/example.sock").await?.into_split();
+//! let mut sender = TypedSender::<Vec<u32>, _>::new(writer).await?;
+//! let mut receiver = TypedReceiver::<u64, _>::new(reader).await?;
+//!
+//! sender.send(&vec![1, 2, 3]).await?;
+//! if let Some(sum) = receiver.recv().await? {
+//!     println!("Server answered: {sum}");
+//! }
+//! # Ok(()) }
+//! ```
+
+use super::{
+    check_header, decode, decode_len, encode, eof_in_message, header, Codec, DEFAULT_MAX_MESSAGE_SIZE, HEADER_LEN,
+    LEN_PREFIX_LEN,
+};
+use crate::local_socket::{tokio::LocalSocketStream, ToLocalSocketName};
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    marker::PhantomData,
+};
+
+/// The sending end of an asynchronous typed channel, which serializes values of type `T` and writes them into a
+/// stream.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct TypedSender<T: ?Sized, S = LocalSocketStream> {
+    stream: S,
+    buf: Vec<u8>,
+    codec: Codec,
+    _phantom: PhantomData<fn(&T)>,
+}
+impl<T: Serialize + ?Sized> TypedSender<T> {
+    /// Connects to a local socket server and creates a sender on the resulting stream.
+    pub async fn connect<'a>(name: impl ToLocalSocketName<'a>) -> io::Result<Self> {
+        Self::new(LocalSocketStream::connect(name).await?).await
+    }
+}
+impl<T: Serialize + ?Sized, S: AsyncWrite + Unpin> TypedSender<T, S> {
+    /// Creates a sender on the given stream, writing the header of the channel into it. Values are serialized with
+    /// `bincode`.
+    pub async fn new(stream: S) -> io::Result<Self> {
+        Self::with_codec(stream, Codec::Bincode).await
+    }
+    /// Creates a sender on the given stream which serializes values with the specified codec, writing the header of
+    /// the channel into it.
+    pub async fn with_codec(mut stream: S, codec: Codec) -> io::Result<Self> {
+        stream.write_all(&header(codec)).await?;
+        Ok(Self {
+            stream,
+            buf: Vec::new(),
+            codec,
+            _phantom: PhantomData,
+        })
+    }
+    /// Serializes the value and writes it into the stream as one message.
+    ///
+    /// Errors are reported the same way as by the [synchronous version](super::TypedSender::send). The future is not
+    /// cancel-safe: dropping it before it completes may leave a partially written message in the stream.
+    pub async fn send(&mut self, value: &T) -> io::Result<()> {
+        encode(&mut self.buf, self.codec, value)?;
+        self.stream.write_all(&self.buf).await
+    }
+}
+impl<T: ?Sized, S> TypedSender<T, S> {
+    /// Returns the codec with which values are serialized.
+    #[inline]
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+    /// Unwraps the underlying stream. Anything written into it afterwards is interpreted by the receiver as part of
+    /// the channel.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+impl<T: ?Sized, S: Debug> Debug for TypedSender<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedSender")
+            .field("stream", &self.stream)
+            .field("codec", &self.codec)
+            .finish()
+    }
+}
+
+/// The receiving end of an asynchronous typed channel, which reads messages from a stream and deserializes them into
+/// values of type `T`.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct TypedReceiver<T, S = LocalSocketStream> {
+    stream: S,
+    buf: Vec<u8>,
+    codec: Codec,
+    max_message_size: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+impl<T: DeserializeOwned, S: AsyncRead + Unpin> TypedReceiver<T, S> {
+    /// Creates a receiver on the given stream, reading the header of the channel from it.
+    ///
+    /// Errors are reported the same way as by the [synchronous version](super::TypedReceiver::new).
+    pub async fn new(mut stream: S) -> io::Result<Self> {
+        let mut header = [0; HEADER_LEN];
+        stream.read_exact(&mut header).await?;
+        let codec = match check_header(&header)? {
+            Some(codec) => codec,
+            None => Codec::from_id(stream.read_u8().await?)?,
+        };
+        Ok(Self {
+            stream,
+            buf: Vec::new(),
+            codec,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            _phantom: PhantomData,
+        })
+    }
+    /// Receives one message and deserializes it, returning `None` if the stream ends before the next message.
+    ///
+    /// Errors are reported the same way as by the [synchronous version](super::TypedReceiver::recv). The future is not
+    /// cancel-safe: dropping it before it completes may discard a partially received message.
+    pub async fn recv(&mut self) -> io::Result<Option<T>> {
+        let mut prefix = [0; LEN_PREFIX_LEN];
+        let mut filled = 0;
+        while filled < LEN_PREFIX_LEN {
+            match self.stream.read(&mut prefix[filled..]).await? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(eof_in_message()),
+                n => filled += n,
+            }
+        }
+        let len = decode_len(prefix, self.max_message_size)?;
+        self.buf.resize(len, 0);
+        if let Err(e) = self.stream.read_exact(&mut self.buf).await {
+            return Err(if e.kind() == io::ErrorKind::UnexpectedEof {
+                eof_in_message()
+            } else {
+                e
+            });
+        }
+        decode(&self.buf, self.codec).map(Some)
+    }
+}
+impl<T, S> TypedReceiver<T, S> {
+    /// Returns the codec with which values are deserialized, as announced by the sender.
+    #[inline]
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+    /// Returns the maximum size of a serialized value accepted by the receiver, in bytes.
+    #[inline]
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+    /// Sets the maximum size of a serialized value accepted by the receiver, in bytes. Defaults to
+    /// [`DEFAULT_MAX_MESSAGE_SIZE`].
+    #[inline]
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+    /// Unwraps the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+impl<T, S: Debug> Debug for TypedReceiver<T, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedReceiver")
+            .field("stream", &self.stream)
+            .field("codec", &self.codec)
+            .field("max_message_size", &self.max_message_size)
+            .finish()
+    }
+}
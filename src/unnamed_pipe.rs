@@ -3,27 +3,172 @@
 //! The distinction between named and unnamed pipes is concisely expressed by their names: where named pipes have names, unnamed pipes have handles. This can both be useful or problematic, depending on the use case. Unnamed pipes work best when a child process is used. With the fork model on Unix-like systems, the handle can be transferred to the child process thanks to the cloned address space; on Windows, inheritable handles can be used.
 //!
 //! Another way to use unnamed pipes is to use a named pipe or a Unix domain socket to establish an unnamed pipe connection. It just so happens that this crate supports all three.
+//!
+//! # Child processes
+//! Both ends of a pipe can be converted into [`Stdio`](std::process::Stdio), so that they can be given to a child process spawned with [`Command`](std::process::Command) as its standard input, output or error. On Unix, the pipes created by `Command` for a child that was spawned with [`Stdio::piped()`](std::process::Stdio::piped) can in turn be converted into the types of this module, from [`ChildStdin`](std::process::ChildStdin), [`ChildStdout`](std::process::ChildStdout) and [`ChildStderr`](std::process::ChildStderr). This isn't possible on Windows, where the standard library opens its ends of those pipes for asynchronous I/O.
+//! ```
+//! # #[cfg(unix)] {
+//! use interprocess::unnamed_pipe::{UnnamedPipeOptions, UnnamedPipeReader};
+//! use std::{
+//!     io::{prelude::*, BufReader},
+//!     process::{Command, Stdio},
+//! };
+//!
+//! // The writing end stays in this process and must not leak into the child, or the child will never see end-of-file.
+//! let (mut writer, reader) = UnnamedPipeOptions::new().writer_inheritable(false).build()?;
+//! let mut child = Command::new("cat").stdin(reader).stdout(Stdio::piped()).spawn()?;
+//! let output = UnnamedPipeReader::from(child.stdout.take().unwrap());
+//!
+//! writer.write_all(b"Hello through cat!\n")?;
+//! drop(writer);
+//! let mut line = String::new();
+//! BufReader::new(output).read_line(&mut line)?;
+//! assert_eq!(line, "Hello through cat!\n");
+//! child.wait()?;
+//! # }
+//! # std::io::Result::<()>::Ok(())
+//! ```
 
 impmod! {unnamed_pipe,
     UnnamedPipeReader as UnnamedPipeReaderImpl,
     UnnamedPipeWriter as UnnamedPipeWriterImpl,
     pipe as pipe_impl,
+    pipe_with_options as pipe_with_options_impl,
 }
+pub use crate::handle_conversion::FromHandleError;
+use crate::Sealed;
+#[cfg(windows)]
+use std::ffi::OsString;
 use std::{
     fmt::{self, Formatter},
-    io::{self, Read, Write},
+    io::{self, IoSlice, IoSliceMut, Read, Write},
+    mem::MaybeUninit,
 };
 
 /// Creates a new pipe with the default creation settings and returns the handles to its writing end and reading end.
 ///
 /// The platform-specific builders in the `os` module of the crate might be more helpful if a configuration process for the pipe is needed.
+///
+/// Both ends of the pipe are inheritable by child processes, and the capacity of the pipe is left at the system default, which is 64 KiB on Linux. To change either, create the pipe with [`UnnamedPipeOptions`] instead.
 pub fn pipe() -> io::Result<(UnnamedPipeWriter, UnnamedPipeReader)> {
     pipe_impl()
 }
 
+/// A builder for unnamed pipes, providing finer control over their creation than the [`pipe`] function does.
+///
+/// Only the options that have a meaning on all platforms are set through the methods of this type. The security descriptor of the pipe can be set on Windows through the `os::windows::unnamed_pipe::UnnamedPipeOptionsExt` extension trait.
+///
+/// # Example
+/// ```
+/// use interprocess::unnamed_pipe::UnnamedPipeOptions;
+///
+/// // A big pipe whose reading end is to be passed to a child process, with the writing end staying in this one.
+/// let (writer, reader) = UnnamedPipeOptions::new()
+///     .buffer_size(1024 * 1024)
+///     .writer_inheritable(false)
+///     .build()?;
+/// # let _ = (writer, reader);
+/// # std::io::Result::<()>::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnnamedPipeOptions {
+    #[cfg_attr(not(any(windows, target_os = "linux", target_os = "android")), allow(dead_code))]
+    pub(crate) buffer_size: Option<usize>,
+    pub(crate) reader_inheritable: bool,
+    pub(crate) writer_inheritable: bool,
+    pub(crate) reader_nonblocking: bool,
+    pub(crate) writer_nonblocking: bool,
+    #[cfg(windows)]
+    pub(crate) security_descriptor: Option<OsString>,
+}
+impl UnnamedPipeOptions {
+    /// Creates a new builder with default options, which are the same as the ones used by the [`pipe`] function.
+    pub fn new() -> Self {
+        Self {
+            buffer_size: None,
+            reader_inheritable: true,
+            writer_inheritable: true,
+            reader_nonblocking: false,
+            writer_nonblocking: false,
+            #[cfg(windows)]
+            security_descriptor: None,
+        }
+    }
+    /// Sets the capacity of the pipe, i.e. how much data can be written into it before the writer has to wait for the reader to catch up. By default, the system default is used.
+    ///
+    /// # Platform-specific behavior
+    /// ## Windows
+    /// Passed to the system as a hint, which it may round or disregard.
+    ///
+    /// ## Linux and Android
+    /// Set with `F_SETPIPE_SZ` right after the pipe is created, which rounds it up to a power of two number of pages. Creation fails if the size exceeds the limit in `/proc/sys/fs/pipe-max-size` for unprivileged processes; see `set_pipe_size()` of `os::unix::unnamed_pipe::UnnamedPipeExt` for the details.
+    ///
+    /// ## Other Unix-like systems
+    /// Ignored, since there is no way to change the capacity of a pipe.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = Some(buffer_size);
+        self
+    }
+    /// Sets whether both ends of the pipe are inherited by child processes.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn inheritable(self, inheritable: bool) -> Self {
+        self.reader_inheritable(inheritable).writer_inheritable(inheritable)
+    }
+    /// Sets whether the reading end of the pipe is inherited by child processes. By default, it is.
+    ///
+    /// When only one end of the pipe is meant to be passed to a child process, the other one should be made non-inheritable. Otherwise, it leaks into the child, as well as any other process spawned at the same time, and keeps the pipe from reporting end-of-file once the end that stays in the current process is dropped.
+    ///
+    /// On Unix, a non-inheritable end has the close-on-exec flag set, which is done atomically with the creation of the pipe where the platform allows it.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn reader_inheritable(mut self, reader_inheritable: bool) -> Self {
+        self.reader_inheritable = reader_inheritable;
+        self
+    }
+    /// Sets whether the writing end of the pipe is inherited by child processes. By default, it is.
+    ///
+    /// See [`reader_inheritable()`](Self::reader_inheritable) for when this should be disabled.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn writer_inheritable(mut self, writer_inheritable: bool) -> Self {
+        self.writer_inheritable = writer_inheritable;
+        self
+    }
+    /// Sets whether both ends of the pipe are created in nonblocking mode.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn nonblocking(self, nonblocking: bool) -> Self {
+        self.reader_nonblocking(nonblocking).writer_nonblocking(nonblocking)
+    }
+    /// Sets whether the reading end of the pipe is created in nonblocking mode. By default, it is not.
+    ///
+    /// In nonblocking mode, reading from an empty pipe fails with a [`WouldBlock`](io::ErrorKind::WouldBlock) error instead of waiting for data to arrive. Note that on Unix, the mode is shared with child processes that inherit the end, which may not expect it.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn reader_nonblocking(mut self, reader_nonblocking: bool) -> Self {
+        self.reader_nonblocking = reader_nonblocking;
+        self
+    }
+    /// Sets whether the writing end of the pipe is created in nonblocking mode. By default, it is not.
+    ///
+    /// In nonblocking mode, writing into a full pipe writes as much as fits, or fails with a [`WouldBlock`](io::ErrorKind::WouldBlock) error if nothing does, instead of waiting for the reader to make room. Note that on Unix, the mode is shared with child processes that inherit the end, which may not expect it.
+    #[must_use = "builder setters take the entire structure and return the result"]
+    pub fn writer_nonblocking(mut self, writer_nonblocking: bool) -> Self {
+        self.writer_nonblocking = writer_nonblocking;
+        self
+    }
+    /// Creates the pipe with the options of the builder and returns its writing and reading ends.
+    pub fn build(&self) -> io::Result<(UnnamedPipeWriter, UnnamedPipeReader)> {
+        pipe_with_options_impl(self)
+    }
+}
+impl Default for UnnamedPipeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Sealed for UnnamedPipeOptions {}
+
 /// A handle to the reading end of an unnamed pipe, created by the [`pipe`] function together with the [writing end].
 ///
-/// The core functionality is exposed in a file-like [`Read`] interface. On Windows, the [`ShareHandle`] and [`As-`][`AsRawHandle`]/[`Into-`][`IntoRawHandle`]/[`FromRawHandle`] traits are also implemented, along with [`As-`][`AsRawFd`]/[`Into-`][`IntoRawFd`]/[`FromRawFd`] on Unix.
+/// The core functionality is exposed in a file-like [`Read`] interface. On Windows, the [`ShareHandle`] and [`As-`][`AsRawHandle`]/[`Into-`][`IntoRawHandle`]/[`FromRawHandle`] traits are also implemented, along with [`As-`][`AsRawFd`]/[`Into-`][`IntoRawFd`]/[`FromRawFd`] on Unix. Owned handles and file descriptors are supported via `AsHandle`/`AsFd`, conversions into `OwnedHandle`/`OwnedFd` and fallible conversions from them, which reject the opposite end of a pipe with a [`FromHandleError`].
 ///
 /// [`pipe`]: fn.pipe.html " "
 /// [writing end]: struct.UnnamedPipeWriter.html " "
@@ -39,10 +184,41 @@ pub struct UnnamedPipeReader {
     // pub(crate) to allow the platform specific builders to create the public-facing pipe types
     pub(crate) inner: UnnamedPipeReaderImpl,
 }
+impl UnnamedPipeReader {
+    /// Same as `.read()` from the [`Read`] trait, but accepts an uninitialized buffer.
+    ///
+    /// This avoids having to zero out buffers which are about to be overwritten anyway, such as ones taken from a pool
+    /// of reusable buffers. If `Ok(n)` is returned, the first `n` bytes of the buffer are guaranteed to be initialized.
+    ///
+    /// # Example
+    /// ```
+    /// use interprocess::unnamed_pipe::pipe;
+    /// use std::{io::Write, mem::MaybeUninit};
+    ///
+    /// let (mut writer, mut reader) = pipe()?;
+    /// writer.write_all(b"Hello!")?;
+    ///
+    /// let mut buf = [MaybeUninit::<u8>::uninit(); 64];
+    /// let bytes_read = reader.read_to_uninit(&mut buf)?;
+    /// let received = unsafe {
+    ///     // SAFETY: the first `bytes_read` bytes have been initialized by the read
+    ///     std::slice::from_raw_parts(buf.as_ptr().cast::<u8>(), bytes_read)
+    /// };
+    /// assert_eq!(received, b"Hello!");
+    /// # std::io::Result::<()>::Ok(())
+    /// ```
+    #[inline]
+    pub fn read_to_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> io::Result<usize> {
+        self.inner.read_to_uninit(buf)
+    }
+}
 impl Read for UnnamedPipeReader {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.inner.read(buf)
     }
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner.read_vectored(bufs)
+    }
 }
 impl fmt::Debug for UnnamedPipeReader {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -53,7 +229,7 @@ impl_handle_manip!(UnnamedPipeReader);
 
 /// A handle to the writing end of an unnamed pipe, created by the [`pipe`] function together with the [reading end].
 ///
-/// The core functionality is exposed in a file-like [`Write`] interface. On Windows, the [`ShareHandle`] and [`As-`][`AsRawHandle`]/[`Into-`][`IntoRawHandle`]/[`FromRawHandle`] traits are also implemented, along with [`As-`][`AsRawFd`]/[`Into-`][`IntoRawFd`]/[`FromRawFd`] on Unix.
+/// The core functionality is exposed in a file-like [`Write`] interface. On Windows, the [`ShareHandle`] and [`As-`][`AsRawHandle`]/[`Into-`][`IntoRawHandle`]/[`FromRawHandle`] traits are also implemented, along with [`As-`][`AsRawFd`]/[`Into-`][`IntoRawFd`]/[`FromRawFd`] on Unix. Owned handles and file descriptors are supported via `AsHandle`/`AsFd`, conversions into `OwnedHandle`/`OwnedFd` and fallible conversions from them, which reject the opposite end of a pipe with a [`FromHandleError`].
 ///
 /// [`pipe`]: fn.pipe.html " "
 /// [reading end]: struct.UnnamedPipeReader.html " "
@@ -72,6 +248,9 @@ impl Write for UnnamedPipeWriter {
     fn write(&mut self, data: &[u8]) -> io::Result<usize> {
         self.inner.write(data)
     }
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
     fn flush(&mut self) -> io::Result<()> {
         self.inner.flush()
     }
@@ -0,0 +1,20 @@
+#![cfg(feature = "async-std")]
+#[path = "../util/mod.rs"]
+mod util;
+use util::TestResult;
+
+mod stream;
+
+use {futures::executor::block_on, interprocess::local_socket::NameTypeSupport};
+
+#[test]
+fn async_std_local_socket_stream() -> TestResult {
+    // If only one name type is supported, this one will choose the supported one. If both are
+    // supported, this will try paths first.
+    block_on(stream::run(false))?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        // Try the namespace now.
+        block_on(stream::run(true))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Tests a round trip over an async-std-compatible local socket connection, with the halves of the server-side
+//! connection used concurrently and then reunited.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        try_join,
+    },
+    interprocess::local_socket::async_std::{LocalSocketListener, LocalSocketStream},
+    std::io,
+};
+
+static SERVER_LINE: &[u8] = b"Hello from server!\n";
+static CLIENT_LINE: &[u8] = b"Hello from client!\n";
+static CLIENT_BYTES: &[u8] = b"Bytes from client!\0";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let server = async {
+        let conn = listener.accept().await.context("Accept failed")?;
+        let (reader, mut writer) = conn.into_split();
+        let mut reader = BufReader::new(reader);
+        let mut buffer = Vec::with_capacity(128);
+        let read = async {
+            reader
+                .read_until(b'\n', &mut buffer)
+                .await
+                .context("Socket receive failed")
+        };
+        let write = async { writer.write_all(SERVER_LINE).await.context("Socket send failed") };
+        try_join!(read, write)?;
+        ensure!(buffer == CLIENT_LINE, "received line doesn't match");
+
+        let mut conn = reader
+            .into_inner()
+            .reunite(writer)
+            .context("Reuniting the halves failed")?;
+        let mut bytes = [0; CLIENT_BYTES.len()];
+        conn.read_exact(&mut bytes)
+            .await
+            .context("Second socket receive failed")?;
+        ensure!(bytes == CLIENT_BYTES, "received bytes don't match");
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketStream::connect(name.as_str())
+            .await
+            .context("Connect failed")?;
+        conn.write_all(CLIENT_LINE).await.context("Socket send failed")?;
+        let mut buffer = Vec::with_capacity(128);
+        BufReader::new(&mut conn)
+            .read_until(b'\n', &mut buffer)
+            .await
+            .context("Socket receive failed")?;
+        ensure!(buffer == SERVER_LINE, "received line doesn't match");
+        conn.write_all(CLIENT_BYTES)
+            .await
+            .context("Second socket send failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#![cfg(unix)]
+#[path = "../util/mod.rs"]
+mod util;
+
+mod options;
+#[cfg(feature = "tokio")]
+mod tokio;
+
+use {std::path::PathBuf, util::NameGen};
+
+/// Generates a random path in `/tmp` for a FIFO file.
+fn fifo_path() -> PathBuf {
+    let name = NameGen::new(false).next().unwrap();
+    PathBuf::from(name).with_extension("fifo")
+}
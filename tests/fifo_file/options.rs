@@ -0,0 +1,93 @@
+//! Tests opening FIFOs with the various options of the builder.
+
+use {
+    super::{fifo_path, util::TestResult},
+    anyhow::{ensure, Context},
+    interprocess::os::unix::fifo_file::{create_fifo, FifoOptions},
+    std::{
+        fs::{self, File},
+        io::{self, prelude::*},
+        os::unix::fs::{FileTypeExt, PermissionsExt},
+        thread,
+    },
+};
+
+#[test]
+fn fifo_blocking_open() -> TestResult {
+    let path = fifo_path();
+    let options = FifoOptions::new().create(true).mode(0o600);
+    let writer = thread::spawn({
+        let path = path.clone();
+        move || -> io::Result<()> {
+            let mut writer = options.open_writer(path)?;
+            writer.write_all(b"hello")?;
+            writer.write_all(b" world")
+        }
+    });
+    let mut msg = String::new();
+    options
+        .open_reader(&path)
+        .context("Reader open failed")?
+        .read_to_string(&mut msg)
+        .context("Receive failed")?;
+    writer.join().unwrap().context("Writer failed")?;
+    ensure!(msg == "hello world", "received {msg:?}");
+
+    let metadata = fs::metadata(&path)?;
+    fs::remove_file(&path)?;
+    ensure!(metadata.file_type().is_fifo(), "created file is not a FIFO");
+    ensure!(metadata.permissions().mode() & 0o777 == 0o600, "wrong permissions");
+    Ok(())
+}
+
+#[test]
+fn fifo_no_wait_for_peer() -> TestResult {
+    let path = fifo_path();
+    create_fifo(&path, 0o600).context("FIFO creation failed")?;
+    let options = FifoOptions::new().wait_for_peer(false);
+
+    let no_reader = options.open_writer(&path).map(drop);
+    ensure!(
+        matches!(&no_reader, Err(e) if e.kind() == io::ErrorKind::NotConnected),
+        "opening a writer without a reader resulted in {no_reader:?}"
+    );
+
+    let mut reader = options.open_reader(&path).context("Reader open failed")?;
+    let mut writer = options.open_writer(&path).context("Writer open failed")?;
+    writer.write_all(b"ping").context("Send failed")?;
+    drop(writer);
+    let mut msg = Vec::new();
+    // The reader must have been switched back to blocking mode, or this would fail with WouldBlock.
+    reader.read_to_end(&mut msg).context("Receive failed")?;
+    fs::remove_file(&path)?;
+    ensure!(msg == b"ping", "received {msg:?}");
+    Ok(())
+}
+
+#[test]
+fn fifo_nonblocking() -> TestResult {
+    let path = fifo_path();
+    let options = FifoOptions::new().create(true).nonblocking(true);
+    let mut reader = options.open_reader(&path).context("Reader open failed")?;
+    let _writer = options.open_writer(&path).context("Writer open failed")?;
+    let rslt = reader.read(&mut [0; 4]);
+    fs::remove_file(&path)?;
+    ensure!(
+        matches!(&rslt, Err(e) if e.kind() == io::ErrorKind::WouldBlock),
+        "reading an empty FIFO resulted in {rslt:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn fifo_not_a_fifo() -> TestResult {
+    let path = fifo_path();
+    File::create(&path)?;
+    let rslt = FifoOptions::new().open_reader(&path).map(drop);
+    fs::remove_file(&path)?;
+    ensure!(
+        matches!(&rslt, Err(e) if e.kind() == io::ErrorKind::InvalidInput),
+        "opening a regular file resulted in {rslt:?}"
+    );
+    Ok(())
+}
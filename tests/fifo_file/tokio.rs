@@ -0,0 +1,40 @@
+//! Tests the Tokio FIFO reader and writer, including a writer that has to wait for the reader to show up.
+
+use {
+    super::{fifo_path, util::TestResult},
+    anyhow::{ensure, Context},
+    interprocess::os::unix::fifo_file::{
+        create_fifo,
+        tokio::{FifoReader, FifoWriter},
+    },
+    std::{fs, time::Duration},
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        time::sleep,
+        try_join,
+    },
+};
+
+#[tokio::test]
+async fn tokio_fifo() -> TestResult {
+    let path = fifo_path();
+    create_fifo(&path, 0o600).context("FIFO creation failed")?;
+
+    let writer = async {
+        let mut writer = FifoWriter::open(&path).await.context("Writer open failed")?;
+        writer.write_all(b"hello from tokio").await.context("Send failed")?;
+        TestResult::Ok(())
+    };
+    let reader = async {
+        // Give the writer a chance to find the FIFO without a reader.
+        sleep(Duration::from_millis(10)).await;
+        let mut reader = FifoReader::open(&path).await.context("Reader open failed")?;
+        let mut msg = String::new();
+        reader.read_to_string(&mut msg).await.context("Receive failed")?;
+        ensure!(msg == "hello from tokio", "received {msg:?}");
+        TestResult::Ok(())
+    };
+    let rslt = try_join!(writer, reader);
+    fs::remove_file(&path)?;
+    rslt.map(drop)
+}
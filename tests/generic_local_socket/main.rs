@@ -0,0 +1,20 @@
+#![cfg(feature = "async")]
+#[path = "../util/mod.rs"]
+mod util;
+use util::TestResult;
+
+mod spin_reactor;
+
+use {futures::executor::block_on, interprocess::local_socket::NameTypeSupport};
+
+#[test]
+fn generic_local_socket_spin_reactor() -> TestResult {
+    // If only one name type is supported, this one will choose the supported one. If both are
+    // supported, this will try paths first.
+    block_on(spin_reactor::run(false))?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        // Try the namespace now.
+        block_on(spin_reactor::run(true))?;
+    }
+    Ok(())
+}
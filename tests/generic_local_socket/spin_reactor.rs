@@ -0,0 +1,115 @@
+//! Tests the generic local sockets with a reactor implemented outside of the crate, which never actually waits for
+//! anything and instead has the task polled again right away.
+
+#[cfg(windows)]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+    thread::{self, JoinHandle},
+};
+#[cfg(unix)]
+use std::{
+    os::unix::io::RawFd,
+    task::{Context as TaskContext, Poll},
+};
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        try_join,
+    },
+    interprocess::{
+        local_socket::generic::{LocalSocketListener, LocalSocketStream},
+        reactor::Reactor,
+    },
+    std::io,
+};
+
+struct SpinReactor;
+impl Reactor for SpinReactor {
+    #[cfg(unix)]
+    type Registration = ();
+    #[cfg(unix)]
+    fn register(_fd: RawFd) -> io::Result<Self::Registration> {
+        Ok(())
+    }
+    #[cfg(unix)]
+    fn poll_readable(_registration: &(), cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+    #[cfg(unix)]
+    fn poll_writable(_registration: &(), cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+
+    #[cfg(windows)]
+    type Task<T: Send + 'static> = SpinTask<T>;
+    #[cfg(windows)]
+    fn spawn_blocking<F, T>(f: F) -> Self::Task<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        SpinTask(Some(thread::spawn(f)))
+    }
+}
+
+#[cfg(windows)]
+struct SpinTask<T>(Option<JoinHandle<T>>);
+#[cfg(windows)]
+impl<T> Future for SpinTask<T> {
+    type Output = T;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<T> {
+        if !self.0.as_ref().expect("polled after completion").is_finished() {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        let thread = self.0.take().unwrap();
+        Poll::Ready(thread.join().expect("blocking operation panicked"))
+    }
+}
+
+static SERVER_LINE: &[u8] = b"Hello from server!\n";
+static CLIENT_LINE: &[u8] = b"Hello from client!\n";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::<SpinReactor>::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let server = async {
+        let conn = listener.accept().await.context("Accept failed")?;
+        let (reader, mut writer) = conn.into_split();
+        let mut buffer = Vec::with_capacity(128);
+        BufReader::new(reader)
+            .read_until(b'\n', &mut buffer)
+            .await
+            .context("Socket receive failed")?;
+        ensure!(buffer == CLIENT_LINE, "received line doesn't match");
+        writer.write_all(SERVER_LINE).await.context("Socket send failed")?;
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketStream::<SpinReactor>::connect(name.as_str())
+            .await
+            .context("Connect failed")?;
+        conn.write_all(CLIENT_LINE).await.context("Socket send failed")?;
+        let mut buffer = Vec::with_capacity(128);
+        BufReader::new(&mut conn)
+            .read_until(b'\n', &mut buffer)
+            .await
+            .context("Socket receive failed")?;
+        ensure!(buffer == SERVER_LINE, "received line doesn't match");
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
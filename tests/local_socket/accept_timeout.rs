@@ -0,0 +1,34 @@
+//! Tests that `accept_timeout` both times out and accepts clients that do arrive in time.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{io, thread, time::Duration},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    match listener.accept_timeout(Duration::from_millis(50)) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        Err(e) => bail!("expected a timeout, got a different error: {}", e),
+        Result::Ok(..) => bail!("accepted a connection even though there were no clients"),
+    }
+
+    let client = thread::spawn(move || -> TestResult {
+        LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        Ok(())
+    });
+    listener
+        .accept_timeout(Duration::from_secs(10))
+        .context("Accept failed")?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,48 @@
+//! Tests that the bound name guard removes the socket file on drop and during unwinding, but not after `keep()`.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, ToLocalSocketName},
+    std::{fs, io, panic, path::Path},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener, guard) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind_guarded(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|(l, g)| (nm, l, g))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    ensure!(
+        *guard.name() == name.as_str().to_local_socket_name()?,
+        "guard holds {:?} instead of {:?}",
+        guard.name(),
+        name
+    );
+    if name.starts_with('@') {
+        // Nothing to clean up for namespaced names, the guard must not fail on them.
+        drop(listener);
+        return guard.remove().context("Removal of namespaced name failed");
+    }
+    let path = Path::new(&name);
+    ensure!(path.exists(), "socket file was not created");
+    drop(listener);
+    drop(guard);
+    ensure!(!path.exists(), "socket file was not removed on drop");
+
+    let (listener, guard) = LocalSocketListener::bind_guarded(path).context("Rebind failed")?;
+    drop(listener);
+    let kept = guard.keep();
+    ensure!(path.exists(), "socket file was removed after keep()");
+    fs::remove_file(Path::new(kept.inner())).context("Manual removal failed")?;
+
+    let bound = panic::catch_unwind(|| {
+        let _bound = LocalSocketListener::bind_guarded(path).expect("bind failed");
+        panic!("unwinding through the guard");
+    });
+    ensure!(bound.is_err(), "closure did not panic");
+    ensure!(!path.exists(), "socket file was not removed during unwinding");
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Tests receiving into `BytesMut`, both from byte streams and from message-mode streams, where a message bigger than
+//! the spare capacity of the buffer has to grow it.
+
+use {
+    super::util::*,
+    anyhow::*,
+    bytes::BytesMut,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+static MSG: &[u8] = b"Hello from client!";
+// Bigger than the spare capacity reserved in an empty buffer.
+static LONG: &[u8] = &[b'L'; 4096];
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        conn.write_all(MSG).context("First send failed")?;
+        conn.write_all(LONG).context("Second send failed")?;
+        Ok(())
+    });
+
+    let conn = listener.accept().context("Accept failed")?;
+    let mut buf = BytesMut::new();
+    while conn.read_buf(&mut buf).context("Receive failed")? != 0 {}
+    client.join().unwrap()?;
+    ensure!(buf.len() == MSG.len() + LONG.len(), "received {} bytes", buf.len());
+    let head = buf.split_to(MSG.len()).freeze();
+    ensure!(head == MSG, "received {:?} instead of the first message", head);
+    ensure!(buf == LONG, "second message doesn't match");
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+pub fn run_msg(prefer_namespaced: bool) -> TestResult {
+    use interprocess::{
+        local_socket::{LocalSocketMsgListener, LocalSocketMsgStream},
+        reliable_recv_msg::ReliableRecvMsg,
+    };
+
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketMsgListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let conn = LocalSocketMsgStream::connect(name.as_str()).context("Connect failed")?;
+        conn.send(MSG).context("First send failed")?;
+        conn.send(LONG).context("Second send failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    let mut buf = BytesMut::new();
+    let size = conn.recv_buf(&mut buf).context("First receive failed")?;
+    ensure!(size == MSG.len() && buf == MSG, "first message doesn't match");
+    let size = conn.recv_buf(&mut buf).context("Second receive failed")?;
+    ensure!(size == LONG.len(), "second message has {} bytes", size);
+    // The second message is appended to the first one.
+    ensure!(buf[..MSG.len()] == *MSG, "first message was overwritten");
+    ensure!(buf[MSG.len()..] == *LONG, "second message doesn't match");
+    client.join().unwrap()
+}
@@ -0,0 +1,59 @@
+//! Checks that the capability report agrees with what actually works.
+
+use {
+    super::util::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::{
+        local_socket::{LocalSocketListener, LocalSocketNameGen, LocalSocketStream, NameTypeSupport},
+        platform::capabilities,
+    },
+    std::io,
+};
+
+pub fn run() -> TestResult {
+    let caps = capabilities();
+    ensure!(caps == capabilities(), "the report changed between calls");
+    ensure!(caps.name_types == NameTypeSupport::query(), "wrong name types");
+    ensure!(
+        caps.abstract_namespace == (cfg!(unix) && caps.name_types.namespace_supported()),
+        "abstract namespace support disagrees with the supported name types"
+    );
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let name = LocalSocketNameGen::new("capabilities-shm").next().unwrap();
+        let shm = interprocess::shared_memory::SharedMemory::create(name.inner(), 16);
+        ensure!(
+            shm.is_ok() == caps.shared_memory,
+            "shared memory reported as {} but creating it gave {:?}",
+            caps.shared_memory,
+            shm
+        );
+        #[cfg(unix)]
+        if let Ok(shm) = shm {
+            use interprocess::os::unix::shared_memory::SharedMemoryExt;
+            shm.unlink().context("Unlink failed")?;
+        }
+    }
+
+    if caps.handle_passing {
+        let (listener, guard) = LocalSocketNameGen::new("capabilities-handles")
+            .find_map(|name| match LocalSocketListener::bind_guarded(name) {
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+                els => Some(els),
+            })
+            .unwrap()
+            .context("Bind failed")?;
+        let client = LocalSocketStream::connect(guard.name().clone()).context("Connect failed")?;
+        let server = listener.accept().context("Accept failed")?;
+        let file = std::fs::File::open(file!()).context("Open failed")?;
+        #[cfg(unix)]
+        let handle = std::os::unix::io::AsFd::as_fd(&file);
+        #[cfg(windows)]
+        let handle = std::os::windows::io::AsHandle::as_handle(&file);
+        client.send_handles(b"x", &[handle]).context("Send failed")?;
+        let (_, handles) = server.recv_handles(&mut [0]).context("Receive failed")?;
+        ensure!(handles.len() == 1, "received {} handles", handles.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Tests closing a listener while other threads are waiting for an incoming connection.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{ListenerClosedError, LocalSocketListener, LocalSocketStream},
+    std::{
+        io,
+        sync::{mpsc, Arc},
+        thread,
+        time::Duration,
+    },
+};
+
+fn verify(result: io::Result<()>, what: &str) -> TestResult {
+    let err = match result {
+        Err(e) => e,
+        Result::Ok(()) => bail!("{} succeeded on a closed listener", what),
+    };
+    ensure!(
+        ListenerClosedError::is_in(&err),
+        "expected a 'listener closed' error from {}, received '{}'",
+        what,
+        err
+    );
+    Ok(())
+}
+
+fn bind(prefer_namespaced: bool) -> Result<(String, Arc<LocalSocketListener>)> {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    Ok((name, Arc::new(listener)))
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (_name, listener) = bind(prefer_namespaced)?;
+
+    let accepting = thread::spawn({
+        let listener = Arc::clone(&listener);
+        move || listener.accept().map(drop)
+    });
+    let accepting_timeout = thread::spawn({
+        let listener = Arc::clone(&listener);
+        move || listener.accept_timeout(Duration::from_secs(30)).map(drop)
+    });
+    // Give the threads a chance to actually start waiting.
+    thread::sleep(Duration::from_millis(50));
+    listener.close();
+
+    verify(
+        accepting.join().map_err(|_| anyhow!("accepting thread panicked"))?,
+        "accept",
+    )?;
+    verify(
+        accepting_timeout
+            .join()
+            .map_err(|_| anyhow!("accepting thread panicked"))?,
+        "accept_timeout",
+    )?;
+    ensure!(listener.is_closed(), "listener does not report being closed");
+    verify(listener.accept().map(drop), "accept after close")
+}
+
+/// Several threads wait on the same listener, only one of them gets the client, and the rest must still be woken up by
+/// closing the listener rather than being stuck in the `accept` system call.
+pub fn run_contended(prefer_namespaced: bool) -> TestResult {
+    const THREADS: usize = 4;
+    let (name, listener) = bind(prefer_namespaced)?;
+
+    let (tx, rx) = mpsc::channel();
+    for i in 0..THREADS {
+        let listener = Arc::clone(&listener);
+        let tx = tx.clone();
+        thread::spawn(move || {
+            let result = if i % 2 == 0 {
+                listener.accept()
+            } else {
+                listener.accept_timeout(Duration::from_secs(30))
+            };
+            let _ = tx.send(result);
+        });
+    }
+    thread::sleep(Duration::from_millis(50));
+    let _client = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+
+    let first = rx
+        .recv_timeout(Duration::from_secs(10))
+        .context("no thread accepted the client")?;
+    first.context("Accept failed")?;
+    // Give the losing threads a chance to get back to waiting.
+    thread::sleep(Duration::from_millis(50));
+    listener.close();
+    for _ in 1..THREADS {
+        let result = rx
+            .recv_timeout(Duration::from_secs(10))
+            .context("an accepting thread was not woken up by closing the listener")?;
+        verify(result.map(drop), "accept")?;
+    }
+    Ok(())
+}
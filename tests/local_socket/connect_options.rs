@@ -0,0 +1,75 @@
+//! Tests that connecting with retries waits for a server which starts late, and gives up by the deadline.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketConnectOptions, LocalSocketListener},
+    std::{
+        io, thread,
+        time::{Duration, Instant},
+    },
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let name = NameGen::new_auto(prefer_namespaced).next().unwrap();
+
+    let start = Instant::now();
+    let err = LocalSocketConnectOptions::new()
+        .retries(u32::MAX)
+        .backoff(Duration::from_millis(5), Duration::from_millis(20))
+        .deadline(Duration::from_millis(100))
+        .connect(name.as_str())
+        .err()
+        .context("Connected without a server")?;
+    ensure!(
+        matches!(err.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused),
+        "unexpected error: {}",
+        err
+    );
+    ensure!(
+        start.elapsed() >= Duration::from_millis(100),
+        "gave up before the deadline"
+    );
+
+    let client = thread::spawn({
+        let name = name.clone();
+        move || -> TestResult {
+            LocalSocketConnectOptions::new()
+                .retries(u32::MAX)
+                .backoff(Duration::from_millis(5), Duration::from_millis(20))
+                .deadline(Duration::from_secs(10))
+                .connect(name.as_str())
+                .context("Connect failed")?;
+            Ok(())
+        }
+    });
+    // Let the client fail a few times first.
+    thread::sleep(Duration::from_millis(50));
+    let listener = LocalSocketListener::bind(name.as_str()).context("Bind failed")?;
+    listener.accept().context("Accept failed")?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+    drop(listener);
+    if !name.starts_with('@') {
+        std::fs::remove_file(&name).context("Cleanup failed")?;
+    }
+    Ok(())
+}
+
+/// Reaches a server on the local computer through a hostname, the way one on another computer would be reached.
+#[cfg(windows)]
+pub fn run_hostname() -> TestResult {
+    use interprocess::os::windows::LocalSocketConnectOptionsExt;
+
+    let name = NameGen::new_auto(true).next().unwrap();
+    let listener = LocalSocketListener::bind(name.as_str()).context("Bind failed")?;
+    let client = thread::spawn(move || -> TestResult {
+        LocalSocketConnectOptions::new()
+            .hostname(".")
+            .connect(name.as_str())
+            .context("Connect failed")?;
+        Ok(())
+    });
+    listener.accept().context("Accept failed")?;
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,70 @@
+//! Tests that a listener with a connection limit holds off accepting until a live connection is closed.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{
+        ConnectionLimitError, ListenerClosedError, LocalSocketListenerOptions, LocalSocketStream,
+    },
+    std::{io, sync::Arc, thread, time::Duration},
+};
+
+const SHORT: Duration = Duration::from_millis(100);
+const LONG: Duration = Duration::from_secs(10);
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let options = LocalSocketListenerOptions::new().max_concurrent_connections(1);
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match options.bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let _first_client = LocalSocketStream::connect(name.as_str()).context("First connect failed")?;
+    let first = listener.accept().context("First accept failed")?;
+    let _second_client = LocalSocketStream::connect(name.as_str()).context("Second connect failed")?;
+
+    match listener.accept_timeout(SHORT) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        els => bail!("expected a timeout while at the limit, received {:?}", els.map(drop)),
+    }
+    listener
+        .set_nonblocking(true)
+        .context("Setting nonblocking mode failed")?;
+    match listener.accept() {
+        Err(e) if ConnectionLimitError::is_in(&e) && e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("expected a connection limit error, received {:?}", els.map(drop)),
+    }
+    listener
+        .set_nonblocking(false)
+        .context("Setting blocking mode failed")?;
+
+    // A clone keeps the slot taken.
+    let clone = first.try_clone().context("Clone failed")?;
+    drop(first);
+    ensure!(
+        listener.accept_timeout(SHORT).is_err(),
+        "accepted while a clone was alive"
+    );
+    drop(clone);
+    let second = listener
+        .accept_timeout(LONG)
+        .context("Accept after freeing a slot failed")?;
+
+    // Closing must wake up a thread waiting for a slot.
+    let listener = Arc::new(listener);
+    let accepting = thread::spawn({
+        let listener = Arc::clone(&listener);
+        move || listener.accept().map(drop)
+    });
+    thread::sleep(SHORT);
+    listener.close();
+    match accepting.join().map_err(|_| anyhow!("accepting thread panicked"))? {
+        Err(e) if ListenerClosedError::is_in(&e) => {}
+        els => bail!("expected a 'listener closed' error, received {:?}", els),
+    }
+    drop(second);
+    Ok(())
+}
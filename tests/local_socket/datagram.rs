@@ -0,0 +1,56 @@
+//! Tests datagram exchange between a bound and an unbound local socket.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketDatagram, ToLocalSocketName},
+    std::io,
+};
+
+const MSG_1: &[u8] = b"First datagram";
+const MSG_2: &[u8] = b"Second datagram";
+
+fn bind(namegen: &mut NameGen) -> io::Result<(String, LocalSocketDatagram)> {
+    namegen
+        .find_map(|nm| match LocalSocketDatagram::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|s| (nm, s))),
+        })
+        .unwrap()
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let mut namegen = NameGen::new_auto(prefer_namespaced);
+    let (name, receiver) = bind(&mut namegen).context("Receiver bind failed")?;
+    let (sender_name, sender) = bind(&mut namegen).context("Sender bind failed")?;
+
+    sender.send_to(MSG_1, name.as_str()).context("Send failed")?;
+    let mut buf = [0; 64];
+    let (size, from) = receiver.recv_from(&mut buf).context("Receive failed")?;
+    ensure!(&buf[..size] == MSG_1, "received datagram doesn't match");
+    if cfg!(unix) {
+        let from = from.context("sender name not reported")?;
+        let expected = sender_name.as_str().to_local_socket_name()?;
+        ensure!(
+            from.is_namespaced() == expected.is_namespaced(),
+            "sender name type mismatch"
+        );
+        ensure!(from.inner() == expected.inner(), "sender name mismatch");
+    } else {
+        ensure!(from.is_none(), "sender name reported where unsupported");
+    }
+
+    let mut unbound = LocalSocketDatagram::unbound().context("Unbound socket creation failed")?;
+    unbound.connect(name.as_str()).context("Connect failed")?;
+    unbound.send(MSG_2).context("Send via connection failed")?;
+    let (size, from) = receiver.recv_from(&mut buf).context("Second receive failed")?;
+    ensure!(&buf[..size] == MSG_2, "received datagram doesn't match");
+    ensure!(from.is_none(), "unbound sender has a name");
+
+    receiver
+        .set_nonblocking(true)
+        .context("Setting nonblocking mode failed")?;
+    let err = receiver.recv(&mut buf).err().context("nonblocking receive succeeded")?;
+    ensure!(err.kind() == io::ErrorKind::WouldBlock, "unexpected error: {}", err);
+    Ok(())
+}
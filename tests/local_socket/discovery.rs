@@ -0,0 +1,45 @@
+//! Tests that a bound listener shows up among the enumerated servers, and that the prefix filters the results.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{enumerate_servers, LocalSocketListener, ToLocalSocketName},
+    std::{fs, io, path::Path},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    let bound = name.as_str().to_local_socket_name()?;
+    let prefix = match bound.is_path() {
+        true => Path::new(bound.inner()).file_name().unwrap().to_owned(),
+        false => bound.inner().to_owned(),
+    };
+
+    let servers = enumerate_servers(&prefix).context("Enumeration failed")?;
+    ensure!(
+        servers.contains(&bound),
+        "{:?} is not among the enumerated servers {:?}",
+        bound,
+        servers
+    );
+    let mut other_prefix = prefix.clone();
+    other_prefix.push("-not-a-server");
+    ensure!(
+        enumerate_servers(&other_prefix)
+            .context("Enumeration failed")?
+            .is_empty(),
+        "prefix did not filter out the server"
+    );
+
+    drop(listener);
+    if bound.is_path() {
+        let _ = fs::remove_file(bound.inner());
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Tests that the drop policy doesn't make Unix sockets discard data which the peer hasn't received yet.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        drop_policy::{default_drop_policy, set_default_drop_policy, DropPolicy},
+        local_socket::{LocalSocketListener, LocalSocketStream},
+    },
+    std::{
+        io::{self, prelude::*},
+        sync::mpsc,
+        thread,
+    },
+};
+
+static SERVER_MSG: &[u8] = b"Sent right before the drop";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    // The default is process-wide, but has no effect on Unix, so changing it can't disturb the other tests.
+    set_default_drop_policy(DropPolicy::Disconnect);
+    ensure!(
+        default_drop_policy() == DropPolicy::Disconnect,
+        "default policy was not set"
+    );
+
+    let (dropped_sender, dropped) = mpsc::channel::<()>();
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        dropped.recv().context("Server exited early")?;
+        let mut received = Vec::new();
+        conn.read_to_end(&mut received).context("Receive failed")?;
+        ensure!(
+            received == SERVER_MSG,
+            "received {:?}",
+            String::from_utf8_lossy(&received)
+        );
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    conn.write_all(SERVER_MSG).context("Send failed")?;
+    drop(conn);
+    dropped_sender.send(()).context("Client exited early")?;
+    let result = client.join().map_err(|_| anyhow!("client panicked"))?;
+    set_default_drop_policy(DropPolicy::default());
+    result
+}
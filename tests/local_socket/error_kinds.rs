@@ -0,0 +1,69 @@
+//! Tests that common failures of local sockets are reported with the right portable error kinds.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::{LocalSocketListener, LocalSocketStream},
+        Error, ErrorKind,
+    },
+    std::io::{self, prelude::*},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let mut namegen = NameGen::new_auto(prefer_namespaced);
+
+    let err = match LocalSocketStream::connect(namegen.next().unwrap().as_str()) {
+        Err(e) => Error::from(e),
+        Result::Ok(..) => bail!("client successfully connected to nonexistent server"),
+    };
+    ensure!(
+        err.kind() == ErrorKind::ServerNotFound,
+        "expected connecting to a nonexistent server to fail with ServerNotFound, received {:?}",
+        err
+    );
+
+    let (name, listener) = namegen
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    let err = match LocalSocketListener::bind(name.as_str()) {
+        Err(e) => Error::from(e),
+        Result::Ok(..) => bail!("second listener successfully bound to a name in use"),
+    };
+    ensure!(
+        err.kind() == ErrorKind::NameInUse,
+        "expected binding to a name in use to fail with NameInUse, received {:?}",
+        err
+    );
+
+    let mut client = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+    drop(listener.accept().context("Accept failed")?);
+    let err = loop {
+        // The first few writes might end up in the socket buffer before the disconnection is noticed.
+        match client.write_all(b"Are you still there?") {
+            Result::Ok(()) => continue,
+            Err(e) => break Error::from(e),
+        }
+    };
+    ensure!(
+        err.kind() == ErrorKind::PeerDisconnected,
+        "expected writing to a disconnected peer to fail with PeerDisconnected, received {:?}",
+        err
+    );
+
+    // Errors produced by the crate keep their kind when passing through an `io::Error`.
+    let err = io::Error::from(Error::new(ErrorKind::MessageTooLarge, "the message is too large"));
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(ErrorKind::of(&err), ErrorKind::MessageTooLarge);
+    assert_eq!(Error::from(err).kind(), ErrorKind::MessageTooLarge);
+
+    // A missing file only means that there's no server when connecting.
+    let err = std::fs::File::open("interprocess-test-nonexistent-file").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(ErrorKind::of(&err), ErrorKind::Other);
+    Ok(())
+}
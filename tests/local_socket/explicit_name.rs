@@ -0,0 +1,57 @@
+//! Tests the constructors of names with an explicit type and the helper which picks the type.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{
+        LocalSocketListener, LocalSocketName, LocalSocketNameMapper, LocalSocketStream, NameTypeSupport,
+    },
+    std::{env, ffi::OsStr, process},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let namespaced = LocalSocketName::namespaced("@example.sock");
+    ensure!(namespaced.is_namespaced(), "namespaced() produced a path");
+    ensure!(
+        namespaced.inner() == OsStr::new("@example.sock"),
+        "namespaced() altered the name: {:?}",
+        namespaced
+    );
+    let path = LocalSocketName::path("@example.sock");
+    ensure!(path.is_path(), "path() produced a namespaced name");
+    ensure!(
+        path.inner() == OsStr::new("@example.sock"),
+        "path() altered the name: {:?}",
+        path
+    );
+
+    let base = format!("interprocess-test-explicit-{}", process::id());
+    let auto = LocalSocketName::from_base_name(&base);
+    ensure!(
+        auto == LocalSocketNameMapper::new().map(&base),
+        "from_base_name() disagrees with the default mapper: {:?}",
+        auto
+    );
+    ensure!(
+        auto.is_supported(),
+        "from_base_name() produced an unsupported name: {:?}",
+        auto
+    );
+
+    let namespaced = prefer_namespaced || NameTypeSupport::query() == NameTypeSupport::OnlyNamespaced;
+    let file = env::temp_dir().join(format!("{base}.sock"));
+    let name = match namespaced {
+        true => LocalSocketName::namespaced(&base),
+        false => LocalSocketName::path(&file),
+    }
+    .into_owned();
+    let listener = LocalSocketListener::bind(name.clone()).context("Bind failed")?;
+    LocalSocketStream::connect(name).context("Connect failed")?;
+    listener.accept().context("Accept failed")?;
+
+    drop(listener);
+    if !namespaced {
+        std::fs::remove_file(file).context("Cleanup failed")?;
+    }
+    Ok(())
+}
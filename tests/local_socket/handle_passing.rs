@@ -0,0 +1,77 @@
+//! Tests that file descriptors or handles sent over a local socket arrive usable on the other side.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        env,
+        fs::{self, File},
+        io::{self, prelude::*, SeekFrom},
+        process, thread,
+    },
+};
+
+static MSG: &[u8] = b"Here's a file";
+static CONTENTS: &str = "Read through the passed file";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let path = env::temp_dir().join(format!(
+        "interprocess-test-handle-passing-{}-{}",
+        process::id(),
+        prefer_namespaced
+    ));
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .context("Temporary file creation failed")?;
+    file.write_all(CONTENTS.as_bytes())
+        .context("Temporary file write failed")?;
+    file.seek(SeekFrom::Start(0)).context("Temporary file seek failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        #[cfg(unix)]
+        let handle = std::os::fd::AsFd::as_fd(&file);
+        #[cfg(windows)]
+        let handle = std::os::windows::io::AsHandle::as_handle(&file);
+        let sent = conn.send_handles(MSG, &[handle]).context("Send failed")?;
+        ensure!(sent == MSG.len(), "only {} bytes out of {} sent", sent, MSG.len());
+        // The other side gets its own copy.
+        drop(file);
+        // Keep the connection open until the server is done receiving.
+        let mut buf = [0; 1];
+        conn.read_exact(&mut buf).context("Final receive failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    let mut buf = [0; 64];
+    let (size, handles) = conn.recv_handles(&mut buf).context("Receive failed")?;
+    ensure!(
+        &buf[..size] == MSG,
+        "received {:?} instead of the message",
+        &buf[..size]
+    );
+    ensure!(handles.len() == 1, "received {} handles instead of one", handles.len());
+    let mut received = String::new();
+    File::from(handles.into_iter().next().unwrap())
+        .read_to_string(&mut received)
+        .context("Read from received file failed")?;
+    ensure!(received == CONTENTS, "received file contains {:?}", received);
+    conn.write_all(b"\n").context("Final send failed")?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+    fs::remove_file(&path).context("Temporary file removal failed")
+}
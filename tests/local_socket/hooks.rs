@@ -0,0 +1,105 @@
+//! Tests that listeners and connect builders call their hooks with the right events.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{
+        ConnectionEvent, LocalSocketConnectOptions, LocalSocketHooks, LocalSocketListenerOptions, ToLocalSocketName,
+    },
+    std::{
+        io, process,
+        sync::{Arc, Mutex},
+    },
+};
+
+#[derive(Debug, PartialEq)]
+enum Recorded {
+    Accept { name: String, peer_pid: Option<u32> },
+    Connect { name: String, peer_pid: Option<u32> },
+    Error { name: String },
+}
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<Recorded>>);
+impl Recorder {
+    fn push(&self, rec: Recorded) {
+        self.0.lock().unwrap().push(rec);
+    }
+}
+impl LocalSocketHooks for Recorder {
+    fn on_accept(&self, event: &ConnectionEvent<'_>) {
+        self.push(Recorded::Accept {
+            name: event.name.inner().to_string_lossy().into_owned(),
+            peer_pid: event.peer_pid,
+        });
+    }
+    fn on_connect(&self, event: &ConnectionEvent<'_>) {
+        self.push(Recorded::Connect {
+            name: event.name.inner().to_string_lossy().into_owned(),
+            peer_pid: event.peer_pid,
+        });
+    }
+    fn on_error(&self, event: &ConnectionEvent<'_>, _error: &io::Error) {
+        self.push(Recorded::Error {
+            name: event.name.inner().to_string_lossy().into_owned(),
+        });
+    }
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let recorder = Arc::new(Recorder::default());
+    let options = LocalSocketListenerOptions::new().hooks(recorder.clone());
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match options.bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    let inner = name
+        .as_str()
+        .to_local_socket_name()?
+        .inner()
+        .to_string_lossy()
+        .into_owned();
+    // Not reported by the OS there.
+    let pid = match cfg!(any(target_os = "macos", target_os = "ios")) {
+        true => None,
+        false => Some(process::id()),
+    };
+
+    let connect_options = LocalSocketConnectOptions::new().hooks(recorder.clone());
+    let _client = connect_options.connect(name.as_str()).context("Connect failed")?;
+    let _conn = listener.accept().context("Accept failed")?;
+    let missing = format!("{}-missing", name);
+    ensure!(
+        connect_options.connect(missing.as_str()).is_err(),
+        "connected to a name no one is listening on"
+    );
+    let missing = missing
+        .as_str()
+        .to_local_socket_name()?
+        .inner()
+        .to_string_lossy()
+        .into_owned();
+
+    let recorded = recorder.0.lock().unwrap();
+    let expected = [
+        Recorded::Connect {
+            name: inner.clone(),
+            peer_pid: pid,
+        },
+        Recorded::Accept {
+            name: inner,
+            peer_pid: pid,
+        },
+        Recorded::Error { name: missing },
+    ];
+    ensure!(
+        *recorded == expected,
+        "recorded {:?}, expected {:?}",
+        recorded,
+        expected
+    );
+    Ok(())
+}
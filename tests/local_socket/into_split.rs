@@ -0,0 +1,55 @@
+//! Tests that the owned halves of a local socket stream work from different threads and can be reunited.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+static PING: &[u8] = b"ping";
+static PONG: &[u8] = b"pong";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let (r1, w1) = LocalSocketStream::connect(name.as_str())
+            .context("First connect failed")?
+            .into_split();
+        let (r2, w2) = LocalSocketStream::connect(name.as_str())
+            .context("Second connect failed")?
+            .into_split();
+        let err = r1.reunite(w2).err().context("Halves of different streams reunited")?;
+        let (r1, w2) = (err.read_half, err.write_half);
+        drop(r2.reunite(w2).context("Second reunite failed")?);
+
+        let mut conn = w1.reunite(r1).context("First reunite failed")?;
+        let mut buf = [0; 4];
+        conn.read_exact(&mut buf).context("Receive failed")?;
+        ensure!(buf == PING, "received {:?} instead of the ping", buf);
+        conn.write_all(PONG).context("Send failed")?;
+        Ok(())
+    });
+
+    let (mut reader, mut writer) = listener.accept().context("Accept failed")?.into_split();
+    let sender = thread::spawn(move || writer.write_all(PING).context("Send failed").map(|()| writer));
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == PONG, "received {:?} instead of the pong", buf);
+    let writer = sender.join().map_err(|_| anyhow!("sender panicked"))??;
+    drop(reader.reunite(writer).context("Server-side reunite failed")?);
+    // The second connection is dropped by the client without any communication.
+    drop(listener.accept().context("Second accept failed")?);
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,166 @@
+//! Tests channels whose ends and other resources can be sent as part of messages, including bootstrapping one through
+//! a one-shot server.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::LocalSocketStream,
+        typed::{channel, IpcHandle, IpcOneShotServer, IpcSender},
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        env,
+        fs::{self, File},
+        io::{self, prelude::*},
+        process, thread,
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Request {
+    id: u32,
+    reply_to: IpcSender<String>,
+}
+
+/// Sends plain values, then channel ends, then a file, all over channels created in the same process.
+pub fn run() -> TestResult {
+    let (mut sender, mut receiver) = channel::<Vec<u32>>().context("Channel creation failed")?;
+    sender.send(&vec![1, 2, 3]).context("Send failed")?;
+    sender.send(&Vec::new()).context("Empty send failed")?;
+    let received = receiver.recv().context("Receive failed")?;
+    ensure!(received == Some(vec![1, 2, 3]), "received {:?}", received);
+    let received = receiver.recv().context("Empty receive failed")?;
+    ensure!(received == Some(Vec::new()), "received {:?}", received);
+    drop(sender);
+    let eof = receiver.recv().context("Receive at EOF failed")?;
+    ensure!(eof.is_none(), "received {:?} instead of EOF", eof);
+
+    run_nested()?;
+    run_handle()?;
+
+    // Without a sender or receiver driving them, the resources have nowhere to go.
+    let (sender, _receiver) = channel::<String>().context("Channel creation failed")?;
+    ensure!(
+        serde_json::to_string(&sender).is_err(),
+        "sender was serialized outside of a channel"
+    );
+    Ok(())
+}
+
+fn run_nested() -> TestResult {
+    let (mut requests, mut incoming) = channel::<Request>().context("Channel creation failed")?;
+    let server = thread::spawn(move || -> TestResult {
+        while let Some(Request { id, mut reply_to }) = incoming.recv().context("Request receive failed")? {
+            reply_to
+                .send(&format!("reply to {}", id))
+                .context("Reply send failed")?;
+        }
+        Ok(())
+    });
+
+    for id in 0..3 {
+        let (reply_to, mut replies) = channel().context("Reply channel creation failed")?;
+        requests
+            .send(&Request { id, reply_to })
+            .context("Request send failed")?;
+        // The sender that was moved into the request was duplicated, so this copy is the only other one left and the
+        // channel ends once the server drops the received copy.
+        let reply = replies.recv().context("Reply receive failed")?;
+        ensure!(reply == Some(format!("reply to {}", id)), "received {:?}", reply);
+        let eof = replies.recv().context("Reply receive at EOF failed")?;
+        ensure!(eof.is_none(), "received {:?} instead of EOF", eof);
+    }
+    drop(requests);
+    server.join().map_err(|_| anyhow!("server panicked"))?
+}
+
+fn run_handle() -> TestResult {
+    let path = env::temp_dir().join(format!("interprocess-test-ipc-handle-{}.txt", process::id()));
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .context("File creation failed")?;
+    let (mut sender, mut receiver) = channel::<(String, IpcHandle)>().context("Channel creation failed")?;
+    sender
+        .send(&("the file".to_string(), IpcHandle::new(file.try_clone()?)))
+        .context("Send failed")?;
+    let (label, handle) = receiver.recv().context("Receive failed")?.context("Unexpected EOF")?;
+    ensure!(label == "the file", "received label {:?}", label);
+
+    // Both copies refer to the same file description, so writes through one are visible through the other.
+    let mut received = File::from(handle.into_inner());
+    received.write_all(b"written through the received copy")?;
+    file.rewind()?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    drop((file, received));
+    fs::remove_file(&path)?;
+    ensure!(
+        contents == "written through the received copy",
+        "read {:?} from the file",
+        contents
+    );
+    Ok(())
+}
+
+/// Bootstraps a channel through a one-shot server, as a parent would with a child it spawned.
+pub fn run_bootstrap() -> TestResult {
+    let (server, name) = IpcOneShotServer::<IpcSender<String>>::new().context("Server creation failed")?;
+    let child = thread::spawn(move || -> TestResult {
+        let (sender, mut receiver) = channel::<String>().context("Channel creation failed")?;
+        IpcSender::<IpcSender<String>>::connect(name)
+            .context("Connect failed")?
+            .send(&sender)
+            .context("Bootstrap send failed")?;
+        drop(sender);
+        let greeting = receiver.recv().context("Receive failed")?;
+        ensure!(greeting.as_deref() == Some("Hello, child!"), "received {:?}", greeting);
+        Ok(())
+    });
+    let (_receiver, mut sender) = server.accept().context("Accept failed")?;
+    sender.send(&"Hello, child!".to_string()).context("Send failed")?;
+    drop(sender);
+    child.join().map_err(|_| anyhow!("child panicked"))??;
+
+    // A client which connects and leaves without sending anything.
+    let (server, name) = IpcOneShotServer::<String>::new().context("Second server creation failed")?;
+    let client = thread::spawn(move || -> TestResult {
+        let sender = IpcSender::<String>::connect(name).context("Second connect failed")?;
+        drop(sender);
+        Ok(())
+    });
+    match server.accept() {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+        els => bail!("accepting a silent client did not fail with UnexpectedEof: {:?}", els),
+    }
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+
+    // A client which doesn't speak the protocol at all.
+    let (server, name) = IpcOneShotServer::<String>::new().context("Third server creation failed")?;
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name).context("Foreign connect failed")?;
+        conn.write_all(b"GET / HTTP/1.1\r\n").context("Foreign send failed")?;
+        Ok(())
+    });
+    match server.accept() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+        els => bail!("foreign client was not rejected: {:?}", els),
+    }
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
+
+/// Checks that the maximum message size is enforced by the receiver.
+pub fn run_oversized() -> TestResult {
+    let (mut sender, mut receiver) = channel::<String>().context("Channel creation failed")?;
+    receiver.set_max_message_size(16);
+    ensure!(receiver.max_message_size() == 16, "maximum message size was not set");
+    sender.send(&"x".repeat(64)).context("Send failed")?;
+    match receiver.recv() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => Ok(()),
+        els => bail!("oversized message was not rejected: {:?}", els),
+    }
+}
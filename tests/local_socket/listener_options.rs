@@ -0,0 +1,151 @@
+//! Tests the options of local socket listeners which have observable effects.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketListenerOptions, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::Duration,
+    },
+};
+
+fn bind(options: &LocalSocketListenerOptions, prefer_namespaced: bool) -> Result<(String, LocalSocketListener)> {
+    NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match options.bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let options = LocalSocketListenerOptions::new().nonblocking(true).backlog(4);
+    #[cfg(unix)]
+    let options = {
+        use interprocess::os::unix::LocalSocketListenerOptionsExt;
+        options.mode(0o600).unlink_on_drop(true)
+    };
+    let (_name, listener) = bind(&options, prefer_namespaced)?;
+
+    match listener.accept() {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        Err(e) => bail!("expected WouldBlock, got a different error: {}", e),
+        Result::Ok(..) => bail!("accepted a connection even though there were no clients"),
+    }
+
+    #[cfg(unix)]
+    if !_name.starts_with('@') {
+        use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+        let mode = fs::metadata(&_name).context("Stat failed")?.permissions().mode();
+        ensure!(
+            mode & 0o777 == 0o600,
+            "socket file has mode {:o} instead of 600",
+            mode & 0o777
+        );
+        drop(listener);
+        ensure!(!Path::new(&_name).exists(), "socket file wasn't deleted on drop");
+    }
+    Ok(())
+}
+
+/// Checks that a client running as the same user gets through the same-user-only filter.
+pub fn run_same_user_only(prefer_namespaced: bool) -> TestResult {
+    let options = LocalSocketListenerOptions::new().same_user_only(true);
+    let (name, listener) = bind(&options, prefer_namespaced)?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        conn.write_all(b"\n").context("Send failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener
+        .accept_timeout(Duration::from_secs(10))
+        .context("Accept failed")?;
+    let mut buf = [0; 1];
+    conn.read_exact(&mut buf).context("Receive failed")?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
+
+/// Leaves a socket file behind and checks that it is only reclaimed when nobody is listening on it.
+#[cfg(unix)]
+pub fn run_reclaim() -> TestResult {
+    let (name, listener) = bind(&LocalSocketListenerOptions::new(), false)?;
+    let reclaiming = LocalSocketListenerOptions::new().reclaim_stale_name(true);
+
+    match reclaiming.bind(name.as_str()) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+        Err(e) => bail!("expected AddrInUse for a live server, got a different error: {}", e),
+        Result::Ok(..) => bail!("reclaimed the name of a live server"),
+    }
+
+    // The plain listener doesn't delete the file on drop.
+    drop(listener);
+    match LocalSocketListener::bind(name.as_str()) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+        Err(e) => bail!("expected AddrInUse for a stale socket, got a different error: {}", e),
+        Result::Ok(..) => bail!("the socket file was deleted by the listener"),
+    }
+    let listener = reclaiming.bind(name.as_str()).context("Reclaiming bind failed")?;
+    drop(listener);
+    std::fs::remove_file(&name).context("Cleanup failed")?;
+
+    // A regular file refuses connections just like a stale socket does, but must never be deleted.
+    std::fs::write(&name, b"precious").context("File creation failed")?;
+    match reclaiming.bind(name.as_str()) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+        Err(e) => bail!("expected AddrInUse for a regular file, got a different error: {}", e),
+        Result::Ok(..) => bail!("bound over a regular file"),
+    }
+    let contents = std::fs::read(&name).context("regular file was deleted")?;
+    ensure!(contents == b"precious", "regular file was modified");
+    std::fs::remove_file(&name).context("Cleanup failed")?;
+    Ok(())
+}
+
+/// Hands the socket file over to another user and group if running as root, or to the current ones otherwise, and
+/// checks that the ownership was applied.
+#[cfg(unix)]
+pub fn run_ownership() -> TestResult {
+    use {
+        interprocess::os::unix::LocalSocketListenerOptionsExt,
+        std::{fs, os::unix::fs::MetadataExt, path::Path},
+    };
+    let (uid, gid) = match unsafe { libc::geteuid() } {
+        // nobody and nogroup on most systems; root can chown to IDs without a name either way.
+        0 => (65534, 65534),
+        uid => (uid, unsafe { libc::getegid() }),
+    };
+    let options = LocalSocketListenerOptions::new()
+        .owner(uid)
+        .group(gid)
+        .mode(0o660)
+        .unlink_on_drop(true);
+    let (name, listener) = bind(&options, false)?;
+
+    let meta = fs::metadata(&name).context("Stat failed")?;
+    ensure!(
+        meta.uid() == uid,
+        "socket file is owned by {} instead of {}",
+        meta.uid(),
+        uid
+    );
+    ensure!(
+        meta.gid() == gid,
+        "socket file belongs to group {} instead of {}",
+        meta.gid(),
+        gid
+    );
+    ensure!(
+        meta.mode() & 0o777 == 0o660,
+        "socket file has mode {:o} instead of 660",
+        meta.mode() & 0o777
+    );
+    drop(listener);
+    ensure!(!Path::new(&name).exists(), "socket file wasn't deleted on drop");
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Tests the in-process loopback implementation of local sockets in both modes.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::loopback::{LoopbackListener, LoopbackMsgListener, LoopbackMsgStream, LoopbackStream},
+        reliable_recv_msg::{RecvResult, ReliableRecvMsg},
+    },
+    std::{
+        io::{self, prelude::*, BufReader},
+        thread,
+    },
+};
+
+const CLIENT_MSG: &[u8] = b"Hello from client!\n";
+const SERVER_MSG: &[u8] = b"Hello from server!\n";
+const LONG_MSG: &[u8] = b"A message that doesn't fit into the small buffer";
+
+pub fn run_bytes() -> TestResult {
+    const NAME: &str = "@interprocess-test-loopback-bytes";
+    let listener = LoopbackListener::bind(NAME).context("Bind failed")?;
+    match LoopbackListener::bind(NAME) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {}
+        els => bail!(
+            "expected binding to a name in use to fail with AddrInUse, got {:?}",
+            els
+        ),
+    }
+    match LoopbackMsgStream::connect(NAME) {
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {}
+        els => bail!("expected connecting in the wrong mode to be refused, got {:?}", els),
+    }
+
+    let client = thread::spawn(|| -> TestResult {
+        let mut conn = BufReader::new(LoopbackStream::connect(NAME).context("Connect failed")?);
+        conn.get_mut().write_all(CLIENT_MSG).context("Send failed")?;
+        let mut reply = Vec::new();
+        conn.read_until(b'\n', &mut reply).context("Receive failed")?;
+        ensure!(reply == SERVER_MSG, "reply doesn't match");
+        Ok(())
+    });
+
+    let mut conn = BufReader::new(listener.accept().context("Accept failed")?);
+    let mut msg = Vec::new();
+    conn.read_until(b'\n', &mut msg).context("Receive failed")?;
+    ensure!(msg == CLIENT_MSG, "message doesn't match");
+    conn.get_mut().write_all(SERVER_MSG).context("Reply failed")?;
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+
+    // The client is gone by now.
+    ensure!(
+        conn.read_to_end(&mut msg).context("Receive failed")? == 0,
+        "expected end of file after the client disconnected"
+    );
+    match conn.get_mut().write(SERVER_MSG) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        els => bail!(
+            "expected writing to a disconnected client to fail with BrokenPipe, got {:?}",
+            els
+        ),
+    }
+
+    drop(listener);
+    match LoopbackStream::connect(NAME) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        els => bail!(
+            "expected connecting to a dropped listener to fail with NotFound, got {:?}",
+            els
+        ),
+    }
+    LoopbackListener::bind(NAME).context("Rebind failed")?;
+    Ok(())
+}
+
+pub fn run_msg() -> TestResult {
+    const NAME: &str = "@interprocess-test-loopback-msg";
+    let listener = LoopbackMsgListener::bind(NAME).context("Bind failed")?;
+
+    let client = thread::spawn(|| -> TestResult {
+        let conn = LoopbackMsgStream::connect(NAME).context("Connect failed")?;
+        // Sent back to back, so a byte stream would have merged them
+        conn.send(CLIENT_MSG).context("First send failed")?;
+        conn.send(LONG_MSG).context("Second send failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+    let mut buf = [0; 32];
+    match conn.recv(&mut buf).context("First receive failed")? {
+        RecvResult::Fit(size) => ensure!(&buf[..size] == CLIENT_MSG, "first message doesn't match"),
+        RecvResult::Alloc(..) => bail!("first message didn't fit into a buffer big enough for it"),
+    }
+    match conn.recv(&mut buf).context("Second receive failed")? {
+        RecvResult::Alloc(msg) => ensure!(msg == LONG_MSG, "second message doesn't match"),
+        RecvResult::Fit(..) => bail!("second message fit into a buffer too small for it"),
+    }
+    ensure!(
+        conn.recv(&mut buf).context("Third receive failed")?.size() == 0,
+        "expected an empty message after the client disconnected"
+    );
+    Ok(())
+}
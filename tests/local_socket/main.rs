@@ -2,8 +2,49 @@
 mod util;
 use util::*;
 
+mod accept_timeout;
+mod bound_name;
+#[cfg(feature = "bytes")]
+mod bytes_buf;
+mod capabilities;
+mod close;
+mod connect_options;
+mod connection_limit;
+mod datagram;
+mod discovery;
+#[cfg(unix)]
+mod drop_policy;
+mod error_kinds;
+mod explicit_name;
+mod handle_passing;
+mod hooks;
+mod into_split;
+#[cfg(feature = "typed")]
+mod ipc_channel;
+mod listener_options;
+mod loopback;
+mod name_gen;
+mod name_mapper;
+#[cfg(feature = "serde")]
+mod name_serde;
+// SOCK_SEQPACKET Ud-sockets are unsupported on Apple platforms
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod msg_stream;
 mod no_server;
+mod peek;
+mod peer_credentials;
+mod pubsub;
+mod rate_limit;
+mod readiness;
+mod select;
+mod server;
+mod spawn;
+mod split;
 mod stream;
+mod timeout;
+mod try_clone;
+#[cfg(feature = "typed")]
+mod typed;
 
 use interprocess::local_socket::NameTypeSupport;
 
@@ -19,6 +60,319 @@ fn local_socket_stream() {
     }
 }
 #[test]
+fn local_socket_accept_timeout() -> TestResult {
+    // Same as above.
+    accept_timeout::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        accept_timeout::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(feature = "bytes")]
+#[test]
+fn local_socket_bytes_buf() -> TestResult {
+    // Same as above.
+    bytes_buf::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        bytes_buf::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(all(feature = "bytes", not(any(target_os = "macos", target_os = "ios"))))]
+#[test]
+fn local_socket_bytes_buf_msg() -> TestResult {
+    // Same as above.
+    bytes_buf::run_msg(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        bytes_buf::run_msg(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_datagram() -> TestResult {
+    // Same as above.
+    datagram::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        datagram::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[test]
+fn local_socket_msg_stream() -> TestResult {
+    // Same as above.
+    msg_stream::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        msg_stream::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_peer_credentials() -> TestResult {
+    // Same as above.
+    peer_credentials::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        peer_credentials::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_listener_options() -> TestResult {
+    // Same as above.
+    listener_options::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        listener_options::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(unix)]
+#[test]
+fn local_socket_reclaim_stale_name() -> TestResult {
+    listener_options::run_reclaim()
+}
+#[cfg(unix)]
+#[test]
+fn local_socket_socket_file_ownership() -> TestResult {
+    listener_options::run_ownership()
+}
+#[test]
+fn local_socket_same_user_only() -> TestResult {
+    // Same as above.
+    listener_options::run_same_user_only(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        listener_options::run_same_user_only(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_name_gen() -> TestResult {
+    // Same as above.
+    name_gen::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        name_gen::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_explicit_name() -> TestResult {
+    // Same as above.
+    explicit_name::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        explicit_name::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_name_mapper() -> TestResult {
+    // Same as above.
+    name_mapper::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        name_mapper::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(feature = "serde")]
+#[test]
+fn local_socket_name_serde() -> TestResult {
+    name_serde::run()
+}
+#[test]
+fn local_socket_try_clone() -> TestResult {
+    // Same as above.
+    try_clone::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        try_clone::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_split() -> TestResult {
+    // Same as above.
+    split::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        split::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_into_split() -> TestResult {
+    // Same as above.
+    into_split::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        into_split::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_handle_passing() -> TestResult {
+    // Same as above.
+    handle_passing::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        handle_passing::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_close() -> TestResult {
+    // Same as above.
+    close::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        close::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_close_contended() -> TestResult {
+    // Same as above.
+    close::run_contended(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        close::run_contended(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_peek() -> TestResult {
+    // Same as above.
+    peek::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        peek::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_connect_options() -> TestResult {
+    // Same as above.
+    connect_options::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        connect_options::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(windows)]
+#[test]
+fn local_socket_connect_hostname() -> TestResult {
+    connect_options::run_hostname()
+}
+#[test]
+fn local_socket_bound_name() -> TestResult {
+    // Same as above.
+    bound_name::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        bound_name::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_server() -> TestResult {
+    // Same as above.
+    server::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        server::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_pubsub() -> TestResult {
+    // Same as above.
+    pubsub::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        pubsub::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_readiness() -> TestResult {
+    // Same as above.
+    readiness::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        readiness::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_select() -> TestResult {
+    // Same as above.
+    select::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        select::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+#[cfg(not(target_os = "android"))]
+fn local_socket_select_event() -> TestResult {
+    select::run_event()
+}
+#[test]
+fn local_socket_discovery() -> TestResult {
+    // Same as above.
+    discovery::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        discovery::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_connection_limit() -> TestResult {
+    // Same as above.
+    connection_limit::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        connection_limit::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_rate_limit() -> TestResult {
+    // Same as above.
+    rate_limit::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        rate_limit::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_hooks() -> TestResult {
+    // Same as above.
+    hooks::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        hooks::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_error_kinds() -> TestResult {
+    // Same as above.
+    error_kinds::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        error_kinds::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_timeout() -> TestResult {
+    // Same as above.
+    timeout::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        timeout::run(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_loopback() -> TestResult {
+    loopback::run_bytes()?;
+    loopback::run_msg()
+}
+#[cfg(unix)]
+#[test]
+fn local_socket_drop_policy() -> TestResult {
+    // Same as above.
+    drop_policy::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        drop_policy::run(true)?;
+    }
+    Ok(())
+}
+#[test]
 fn local_socket_no_server() -> TestResult {
     // Same as above.
     no_server::run_and_verify_error(false)?;
@@ -27,3 +381,45 @@ fn local_socket_no_server() -> TestResult {
     }
     Ok(())
 }
+#[cfg(feature = "typed")]
+#[test]
+fn local_socket_typed() -> TestResult {
+    // Same as above.
+    typed::run(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        typed::run(true)?;
+    }
+    Ok(())
+}
+#[cfg(feature = "typed")]
+#[test]
+fn local_socket_ipc_channel() -> TestResult {
+    ipc_channel::run()?;
+    ipc_channel::run_bootstrap()?;
+    ipc_channel::run_oversized()
+}
+#[cfg(feature = "typed-json")]
+#[test]
+fn local_socket_typed_json() -> TestResult {
+    // Same as above.
+    typed::run_json(false)?;
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        typed::run_json(true)?;
+    }
+    Ok(())
+}
+#[test]
+fn local_socket_spawn() -> TestResult {
+    spawn::run(interprocess::spawn::EndpointPassing::Inheritance)?;
+    spawn::run(interprocess::spawn::EndpointPassing::Bootstrap)?;
+    spawn::run_invalid()
+}
+/// The child process of `local_socket_spawn`, which does nothing when run on its own.
+#[test]
+fn local_socket_spawn_child() -> TestResult {
+    spawn::child()
+}
+#[test]
+fn local_socket_capabilities() -> TestResult {
+    capabilities::run()
+}
@@ -0,0 +1,94 @@
+//! Tests message boundary preservation by message-mode local sockets.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::{LocalSocketMsgListener, LocalSocketMsgStream},
+        reliable_recv_msg::{RecvBufPool, RecvResult, ReliableRecvMsg},
+    },
+    std::{io, thread, time::Duration},
+};
+
+const SHORT: &[u8] = b"Short";
+const LONG: &[u8] = b"A message that doesn't fit into the small buffer";
+
+fn bind(namegen: &mut NameGen) -> io::Result<(String, LocalSocketMsgListener)> {
+    namegen
+        .find_map(|nm| match LocalSocketMsgListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|s| (nm, s))),
+        })
+        .unwrap()
+}
+
+fn recv_whole(conn: &LocalSocketMsgStream, buf: &mut [u8]) -> Result<Vec<u8>> {
+    Ok(match (&*conn).recv(buf).context("Receive failed")? {
+        RecvResult::Fit(size) => buf[..size].to_vec(),
+        RecvResult::Alloc(bigger) => bigger,
+    })
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = bind(&mut NameGen::new_auto(prefer_namespaced)).context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let conn = LocalSocketMsgStream::connect(name.as_str()).context("Connect failed")?;
+        // Sent back to back, so a byte stream would have merged them
+        conn.send(SHORT).context("First send failed")?;
+        conn.send(LONG).context("Second send failed")?;
+        conn.send(LONG).context("Third send failed")?;
+        conn.send(LONG).context("Fourth send failed")?;
+        let mut buf = [0; 64];
+        let reply = recv_whole(&conn, &mut buf)?;
+        ensure!(reply == SHORT, "reply doesn't match");
+        conn.send(LONG).context("Fifth send failed")?;
+        conn.send_all(SHORT).context("Sixth send failed")?;
+        Ok(())
+    });
+
+    let conn = listener.accept().context("Accept failed")?;
+    let mut buf = [0; 8];
+    let first = recv_whole(&conn, &mut buf)?;
+    ensure!(first == SHORT, "first message doesn't match");
+    let second = recv_whole(&conn, &mut buf)?;
+    ensure!(second == LONG, "second message doesn't match");
+
+    // Buffers for messages which don't fit get reused once recycled
+    let mut pool = RecvBufPool::new();
+    let third = (&conn)
+        .recv_pooled(&mut buf, &mut pool)
+        .context("Pooled receive failed")?;
+    ensure!(third.borrow_to_size(&buf) == LONG, "third message doesn't match");
+    let third_ptr = third.borrow_to_size(&buf).as_ptr();
+    third.recycle_into(&mut pool);
+    ensure!(pool.len() == 1, "buffer wasn't recycled");
+    let fourth = (&conn)
+        .recv_pooled(&mut buf, &mut pool)
+        .context("Pooled receive failed")?;
+    ensure!(fourth.borrow_to_size(&buf) == LONG, "fourth message doesn't match");
+    ensure!(
+        pool.is_empty() && fourth.borrow_to_size(&buf).as_ptr() == third_ptr,
+        "recycled buffer wasn't reused"
+    );
+
+    // Nothing is sent until the reply arrives
+    let err = (&conn)
+        .recv_timeout(&mut buf, Duration::from_millis(10))
+        .err()
+        .context("receive didn't time out")?;
+    ensure!(err.kind() == io::ErrorKind::TimedOut, "unexpected error: {err}");
+    conn.send_all(SHORT).context("Reply failed")?;
+
+    let fifth = (&conn).recv_vec().context("Exact-size receive failed")?;
+    ensure!(
+        fifth == LONG && fifth.capacity() == LONG.len(),
+        "fifth message doesn't match"
+    );
+    let sixth = (&conn)
+        .recv_timeout(&mut buf, Duration::from_secs(10))
+        .context("Receive with timeout failed")?;
+    ensure!(sixth.borrow_to_size(&buf) == SHORT, "sixth message doesn't match");
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
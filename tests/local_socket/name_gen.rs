@@ -0,0 +1,41 @@
+//! Tests that the public name generator produces distinct names which can be bound to.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketNameGen, LocalSocketStream},
+    std::{collections::HashSet, io},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let mut namegen = LocalSocketNameGen::new("interprocess-test")
+        .prefer_namespaced(prefer_namespaced)
+        .per_user(true);
+
+    let names = namegen.clone().take(1000).collect::<HashSet<_>>();
+    ensure!(names.len() == 1000, "generated {} duplicate names", 1000 - names.len());
+    for name in &names {
+        ensure!(
+            name.is_supported(),
+            "generated a name of an unsupported type: {:?}",
+            name
+        );
+    }
+
+    let (name, listener) = namegen
+        .find_map(|nm| match LocalSocketListener::bind(nm.clone()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    let path = name.is_path().then(|| name.inner_cow().clone().into_owned());
+    LocalSocketStream::connect(name).context("Connect failed")?;
+    listener.accept().context("Accept failed")?;
+
+    drop(listener);
+    if let Some(path) = path {
+        std::fs::remove_file(path).context("Cleanup failed")?;
+    }
+    Ok(())
+}
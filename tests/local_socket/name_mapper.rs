@@ -0,0 +1,64 @@
+//! Tests that the name mapper follows its policy and produces names which can be bound to.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketNameMapper, LocalSocketStream},
+    std::{env, path::Path, process},
+};
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let mapper = LocalSocketNameMapper::new()
+        .prefix("interprocess-test-")
+        .extension(".socket")
+        .directory(env::temp_dir())
+        .prefer_namespaced(prefer_namespaced)
+        .per_user(true);
+    let logical = format!("mapper-{}-{}", process::id(), prefer_namespaced);
+    let name = mapper.map(&logical);
+
+    ensure!(
+        name.is_supported(),
+        "mapped to a name of an unsupported type: {:?}",
+        name
+    );
+    ensure!(name == mapper.map(&logical), "mapping is not deterministic");
+    ensure!(
+        name != mapper.map("other"),
+        "different logical names mapped to the same name"
+    );
+    let inner = name.inner().to_string_lossy().into_owned();
+    ensure!(
+        inner.contains(&logical),
+        "{:?} does not contain the logical name",
+        inner
+    );
+    if name.is_path() {
+        let path = Path::new(&inner);
+        ensure!(
+            path.parent() == Some(&*env::temp_dir()),
+            "{:?} is not in the directory",
+            path
+        );
+        let filename = path.file_name().unwrap().to_string_lossy();
+        ensure!(
+            filename.starts_with("interprocess-test-"),
+            "{:?} lacks the prefix",
+            filename
+        );
+        ensure!(filename.ends_with(".socket"), "{:?} lacks the extension", filename);
+    } else {
+        ensure!(inner.starts_with("interprocess-test-"), "{:?} lacks the prefix", inner);
+    }
+
+    let path = name.is_path().then(|| name.inner_cow().clone().into_owned());
+    let listener = LocalSocketListener::bind(name.clone()).context("Bind failed")?;
+    LocalSocketStream::connect(name).context("Connect failed")?;
+    listener.accept().context("Accept failed")?;
+
+    drop(listener);
+    if let Some(path) = path {
+        std::fs::remove_file(path).context("Cleanup failed")?;
+    }
+    Ok(())
+}
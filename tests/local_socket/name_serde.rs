@@ -0,0 +1,37 @@
+//! Tests that local socket names survive a serialization round trip with their type intact.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketName, ToLocalSocketName},
+    std::path::Path,
+};
+
+fn round_trip(name: LocalSocketName<'_>, expected_json: &str) -> TestResult {
+    let json = serde_json::to_string(&name).context("Serialization failed")?;
+    ensure!(
+        json == expected_json,
+        "serialized as {} instead of {}",
+        json,
+        expected_json
+    );
+    let back: LocalSocketName<'static> = serde_json::from_str(&json).context("Deserialization failed")?;
+    ensure!(back == name, "deserialized as {:?} instead of {:?}", back, name);
+    Ok(())
+}
+
+pub fn run() -> TestResult {
+    // Paths are not supported on Windows, but the names can be constructed all the same.
+    round_trip(
+        Path::new("@not-namespaced.sock").to_local_socket_name()?,
+        r#"{"Path":"@not-namespaced.sock"}"#,
+    )?;
+    let namespaced = "@example.sock".to_local_socket_name()?;
+    if namespaced.is_namespaced() {
+        round_trip(namespaced, r#"{"Namespaced":"example.sock"}"#)?;
+    }
+
+    let unknown = serde_json::from_str::<LocalSocketName<'static>>(r#"{"Abstract":"example.sock"}"#);
+    ensure!(unknown.is_err(), "deserialized a name of an unknown type");
+    Ok(())
+}
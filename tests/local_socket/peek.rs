@@ -0,0 +1,72 @@
+//! Tests that peeking and querying the amount of available data leave the data in the stream.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+static MSG: &[u8] = b"Hello from client!";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        conn.write_all(MSG).context("Send failed")?;
+        // Keep the connection open until the server is done.
+        let mut buf = [0; 1];
+        conn.read_exact(&mut buf).context("Final receive failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let available = conn.bytes_available().context("Querying available bytes failed")?;
+        if available == MSG.len() {
+            break;
+        }
+        ensure!(
+            available < MSG.len(),
+            "{} bytes available, expected {}",
+            available,
+            MSG.len()
+        );
+        ensure!(Instant::now() < deadline, "the message didn't arrive in time");
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let mut buf = [0; 64];
+    let peeked = conn.peek(&mut buf).context("Peek failed")?;
+    ensure!(
+        &buf[..peeked] == MSG,
+        "peeked {:?} instead of the message",
+        &buf[..peeked]
+    );
+    ensure!(
+        conn.bytes_available().context("Second query failed")? == MSG.len(),
+        "peeking consumed data"
+    );
+    let mut buf = [0; MSG.len()];
+    conn.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == MSG, "received {:?} instead of the message", buf);
+    ensure!(
+        conn.bytes_available().context("Third query failed")? == 0,
+        "data left over after reading"
+    );
+    conn.write_all(b"\n").context("Final send failed")?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,79 @@
+//! Tests that both ends of a local socket connection see the credentials, identity and process ID of the current process.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{
+        LocalSocketListener, LocalSocketPeerCredentials, LocalSocketPeerIdentity, LocalSocketStream,
+    },
+    std::{io, process, thread},
+};
+
+fn verify_pid(pid: io::Result<u32>) -> TestResult {
+    // Not reported by the OS there.
+    if cfg!(any(target_os = "macos", target_os = "ios")) {
+        return Ok(());
+    }
+    let pid = pid.context("Peer PID query failed")?;
+    ensure!(pid == process::id(), "peer PID {} is not the PID of this process", pid);
+    Ok(())
+}
+
+fn verify(creds: LocalSocketPeerCredentials) -> TestResult {
+    if let Some(pid) = creds.pid() {
+        ensure!(pid == process::id(), "peer PID {} is not the PID of this process", pid);
+    }
+    match creds {
+        #[cfg(unix)]
+        LocalSocketPeerCredentials::Unix { uid, gid, .. } => {
+            ensure!(uid == unsafe { libc::geteuid() }, "peer UID doesn't match");
+            ensure!(gid == unsafe { libc::getegid() }, "peer GID doesn't match");
+        }
+        #[cfg(windows)]
+        LocalSocketPeerCredentials::Windows { sid, .. } => {
+            ensure!(sid.starts_with("S-1-"), "malformed peer SID {:?}", sid);
+        }
+        other => bail!("credentials of the wrong platform: {:?}", other),
+    }
+    Ok(())
+}
+
+fn verify_identity(identity: io::Result<LocalSocketPeerIdentity>) -> TestResult {
+    let identity = identity.context("Peer identity query failed")?;
+    let own = LocalSocketPeerIdentity::of_current_process().context("Own identity query failed")?;
+    ensure!(
+        identity == own,
+        "peer identity {:?} is not the identity of this process {:?}",
+        identity,
+        own
+    );
+    ensure!(
+        identity.is_same_user_as_current_process()?,
+        "peer is not reported to be the same user"
+    );
+    Ok(())
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        verify_pid(conn.peer_pid())?;
+        verify_identity(conn.peer_identity())?;
+        verify(conn.peer_credentials().context("Client-side query failed")?)
+    });
+
+    let conn = listener.accept().context("Accept failed")?;
+    verify_pid(conn.peer_pid())?;
+    verify_identity(conn.peer_identity())?;
+    verify(conn.peer_credentials().context("Server-side query failed")?)?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
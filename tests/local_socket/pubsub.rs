@@ -0,0 +1,84 @@
+//! Tests a publish/subscribe broker with several subscribers, including topic filtering, unsubscribing and the
+//! cleanup of subscriptions when subscribers disconnect.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::pubsub::{Broker, Message, Publisher, Subscriber},
+    std::{io, sync::Arc, thread, time::Duration},
+};
+
+fn expect_message(subscriber: &mut Subscriber, topic: &str, payload: &[u8]) -> TestResult {
+    let message = subscriber.recv().context("Receive failed")?.context("Unexpected EOF")?;
+    let expected = Message {
+        topic: topic.to_string(),
+        payload: payload.to_vec(),
+    };
+    ensure!(message == expected, "received {:?} instead of {:?}", message, expected);
+    Ok(())
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, broker) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match Broker::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|b| (nm, b))),
+        })
+        .unwrap()
+        .context("Broker bind failed")?;
+    let broker = Arc::new(broker);
+    let broker_thread = thread::spawn({
+        let broker = Arc::clone(&broker);
+        move || broker.run()
+    });
+
+    let mut both = Subscriber::connect(name.as_str()).context("Subscriber connect failed")?;
+    both.subscribe("alpha").context("Subscribe failed")?;
+    both.subscribe("beta").context("Subscribe failed")?;
+    let mut alpha = Subscriber::connect(name.as_str()).context("Subscriber connect failed")?;
+    alpha.subscribe("alpha").context("Subscribe failed")?;
+    ensure!(broker.subscriber_count("alpha") == 2, "wrong subscriber count");
+
+    let mut publisher = Publisher::connect(name.as_str()).context("Publisher connect failed")?;
+    publisher.publish("alpha", b"first").context("Publish failed")?;
+    publisher
+        .publish("gamma", b"nobody listens")
+        .context("Publish failed")?;
+    publisher.publish("beta", b"").context("Publish failed")?;
+    publisher.publish("alpha", b"second").context("Publish failed")?;
+
+    expect_message(&mut both, "alpha", b"first")?;
+    expect_message(&mut both, "beta", b"")?;
+    expect_message(&mut both, "alpha", b"second")?;
+    expect_message(&mut alpha, "alpha", b"first")?;
+    expect_message(&mut alpha, "alpha", b"second")?;
+
+    // The broker handles the unsubscription before the subscription that follows it, and the acknowledgement of the
+    // latter ensures that the next message is only relayed according to the new state.
+    both.unsubscribe("alpha").context("Unsubscribe failed")?;
+    both.subscribe("gamma").context("Subscribe failed")?;
+    publisher.publish("alpha", b"third").context("Publish failed")?;
+    publisher.publish("gamma", b"fourth").context("Publish failed")?;
+    expect_message(&mut both, "gamma", b"fourth")?;
+    expect_message(&mut alpha, "alpha", b"third")?;
+
+    ensure!(
+        publisher.publish(&"x".repeat(70000), b"").is_err(),
+        "publishing with an overlong topic succeeded"
+    );
+
+    drop(alpha);
+    let mut remaining = broker.subscriber_count("alpha");
+    for _ in 0..100 {
+        if remaining == 0 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        remaining = broker.subscriber_count("alpha");
+    }
+    ensure!(remaining == 0, "subscription outlived the subscriber");
+
+    broker.listener().close();
+    broker_thread.join().unwrap().context("Broker failed")?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Tests that a listener with an accept rate limit delays or sheds clients beyond the rate.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{
+        AcceptRateLimit, LocalSocketListener, LocalSocketListenerOptions, LocalSocketStream, RateLimitError,
+    },
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const INTERVAL: Duration = Duration::from_millis(300);
+const SHORT: Duration = Duration::from_millis(50);
+const LONG: Duration = Duration::from_secs(10);
+
+fn bind(prefer_namespaced: bool, limit: AcceptRateLimit) -> Result<(String, LocalSocketListener)> {
+    let options = LocalSocketListenerOptions::new().accept_rate_limit(limit);
+    NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match options.bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")
+}
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = bind(prefer_namespaced, AcceptRateLimit::new(1, INTERVAL))?;
+    let _first_client = LocalSocketStream::connect(name.as_str()).context("First connect failed")?;
+    let _second_client = LocalSocketStream::connect(name.as_str()).context("Second connect failed")?;
+    let start = Instant::now();
+    let _first = listener.accept().context("First accept failed")?;
+
+    match listener.accept_timeout(SHORT) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        els => bail!("expected a timeout while over the rate, received {:?}", els.map(drop)),
+    }
+    listener
+        .set_nonblocking(true)
+        .context("Setting nonblocking mode failed")?;
+    match listener.accept() {
+        Err(e) if RateLimitError::is_in(&e) && e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("expected a rate limit error, received {:?}", els.map(drop)),
+    }
+    listener
+        .set_nonblocking(false)
+        .context("Setting blocking mode failed")?;
+    let _second = listener.accept_timeout(LONG).context("Delayed accept failed")?;
+    ensure!(start.elapsed() >= INTERVAL, "second client accepted too early");
+
+    let (name, listener) = bind(prefer_namespaced, AcceptRateLimit::new(1, INTERVAL).shed(true))?;
+    let _first_client = LocalSocketStream::connect(name.as_str()).context("First connect failed")?;
+    let mut shed_client = LocalSocketStream::connect(name.as_str()).context("Second connect failed")?;
+    let _first = listener.accept().context("First accept failed")?;
+    match listener.accept_timeout(SHORT) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        els => bail!("expected a timeout after shedding, received {:?}", els.map(drop)),
+    }
+    let mut buf = [0; 1];
+    match shed_client.read(&mut buf) {
+        Result::Ok(0) => {}
+        // Named pipes report the disconnection as an error.
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        els => bail!("shed client was not disconnected: {:?}", els),
+    }
+    // A client which arrives before the token is regained would be shed as well.
+    thread::sleep(INTERVAL);
+    let _third_client = LocalSocketStream::connect(name.as_str()).context("Third connect failed")?;
+    listener
+        .accept_timeout(LONG)
+        .context("Accept after regaining a token failed")?;
+    Ok(())
+}
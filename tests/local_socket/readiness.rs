@@ -0,0 +1,64 @@
+//! Tests waiting for readiness on nonblocking listeners and streams.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::Duration,
+    },
+};
+
+const SHORT: Duration = Duration::from_millis(50);
+const LONG: Duration = Duration::from_secs(10);
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    listener
+        .set_nonblocking(true)
+        .context("Setting nonblocking mode failed")?;
+
+    ensure!(
+        !listener.wait_accept(Some(SHORT)).context("Wait for client failed")?,
+        "listener reported a client before any connected"
+    );
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        thread::sleep(SHORT);
+        conn.write_all(b"x").context("Send failed")?;
+        Ok(())
+    });
+    ensure!(
+        listener.wait_accept(Some(LONG)).context("Wait for client failed")?,
+        "listener timed out waiting for a client"
+    );
+    let mut conn = listener.accept().context("Accept failed")?;
+    conn.set_nonblocking(true).context("Setting nonblocking mode failed")?;
+
+    ensure!(
+        conn.wait_writable(Some(LONG)).context("Wait for writability failed")?,
+        "fresh stream is not writable"
+    );
+    ensure!(
+        conn.wait_readable(None).context("Wait for readability failed")?,
+        "indefinite wait timed out"
+    );
+    let mut buf = [0; 1];
+    conn.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == *b"x", "received {:?}", buf);
+
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+    ensure!(
+        conn.wait_readable(Some(LONG)).context("Wait for end of file failed")?,
+        "disconnection did not make the stream readable"
+    );
+    Ok(())
+}
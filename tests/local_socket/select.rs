@@ -0,0 +1,109 @@
+//! Tests waiting for several listeners, streams and events at once.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::{LocalSocketListener, LocalSocketStream},
+        select::Select,
+    },
+    std::{
+        io::{self, prelude::*},
+        thread,
+        time::Duration,
+    },
+};
+
+const SHORT: Duration = Duration::from_millis(50);
+const LONG: Duration = Duration::from_secs(10);
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    listener
+        .set_nonblocking(true)
+        .context("Setting nonblocking mode failed")?;
+
+    let mut select = Select::new();
+    let accept = select.accept(&listener);
+    ensure!(
+        select.wait(Some(SHORT)).context("Wait failed")?.is_empty(),
+        "listener reported a client before any connected"
+    );
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        thread::sleep(SHORT);
+        conn.write_all(b"x").context("Send failed")?;
+        Ok(())
+    });
+    let ready = select.wait(Some(LONG)).context("Wait for client failed")?;
+    ensure!(ready == [accept], "expected the listener to be ready, got {:?}", ready);
+    let conn = listener.accept().context("Accept failed")?;
+    conn.set_nonblocking(true).context("Setting nonblocking mode failed")?;
+
+    let mut select = Select::new();
+    let accept = select.accept(&listener);
+    let read = select.readable(&conn);
+    let ready = select.wait(None).context("Wait for readability failed")?;
+    ensure!(ready == [read], "expected the stream to be readable, got {:?}", ready);
+    let mut buf = [0; 1];
+    (&conn).read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == *b"x", "received {:?}", buf);
+
+    let write = select.writable(&conn);
+    let ready = select.wait(Some(LONG)).context("Wait for writability failed")?;
+    ensure!(ready == [write], "expected the stream to be writable, got {:?}", ready);
+
+    client.join().map_err(|_| anyhow!("client panicked"))??;
+    listener.close();
+    let ready = select.wait(Some(LONG)).context("Wait after closing failed")?;
+    ensure!(
+        ready == [accept, read, write],
+        "expected the closed listener and the disconnected stream to be ready, got {:?}",
+        ready
+    );
+    Ok(())
+}
+
+#[cfg(not(target_os = "android"))]
+pub fn run_event() -> TestResult {
+    use interprocess::sync::NamedEvent;
+
+    let name = format!("interprocess-test-select-event-{}", std::process::id());
+    let event = NamedEvent::create(&name).context("Event creation failed")?;
+    let mut select = Select::new();
+    let key = select.event(&event);
+    ensure!(
+        select.wait(Some(SHORT)).context("Wait failed")?.is_empty(),
+        "event reported before being notified"
+    );
+    let notifier = thread::spawn({
+        let name = name.clone();
+        move || -> TestResult {
+            thread::sleep(SHORT);
+            NamedEvent::open(&name)
+                .context("Event opening failed")?
+                .notify_all()
+                .context("Notification failed")
+        }
+    });
+    let ready = select.wait(Some(LONG)).context("Wait for notification failed");
+    notifier.join().map_err(|_| anyhow!("notifier panicked"))??;
+    let ready = ready?;
+    ensure!(ready == [key], "expected the event to be ready, got {:?}", ready);
+    ensure!(
+        select.wait(Some(SHORT)).context("Wait failed")?.is_empty(),
+        "the same notification was reported twice"
+    );
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::sync::NamedEventExt;
+        event.unlink().context("Event unlinking failed")?;
+    }
+    Ok(())
+}
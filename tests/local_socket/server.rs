@@ -0,0 +1,82 @@
+//! Tests that the server pool serves concurrent clients with its handler and stops accepting after a shutdown.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketServer, LocalSocketStream},
+    std::{
+        fs,
+        io::{self, prelude::*, BufReader},
+        sync::{
+            atomic::{AtomicUsize, Ordering::*},
+            Arc,
+        },
+        thread,
+    },
+};
+
+const CLIENTS: usize = 6;
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let served = Arc::new(AtomicUsize::new(0));
+    let server = LocalSocketServer::spawn(listener, 3, {
+        let served = Arc::clone(&served);
+        move |conn| {
+            let mut conn = BufReader::new(conn);
+            let mut line = String::new();
+            conn.read_line(&mut line).expect("receive failed");
+            if line == "panic\n" {
+                panic!("requested by the client");
+            }
+            // Counted before replying, so that the count is complete once every client has its reply.
+            served.fetch_add(1, SeqCst);
+            conn.get_mut()
+                .write_all(line.to_uppercase().as_bytes())
+                .expect("send failed");
+        }
+    })
+    .context("Spawn failed")?;
+
+    // The worker must survive a panic in the handler.
+    let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+    conn.write_all(b"panic\n").context("Send failed")?;
+
+    let clients = (0..CLIENTS)
+        .map(|i| {
+            let name = name.clone();
+            thread::spawn(move || -> TestResult {
+                let msg = format!("hello from client {}\n", i);
+                let mut conn = BufReader::new(LocalSocketStream::connect(name.as_str()).context("Connect failed")?);
+                conn.get_mut().write_all(msg.as_bytes()).context("Send failed")?;
+                let mut line = String::new();
+                conn.read_line(&mut line).context("Receive failed")?;
+                ensure!(line == msg.to_uppercase(), "received {:?}", line);
+                Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+    for client in clients {
+        client.join().map_err(|_| anyhow!("client panicked"))??;
+    }
+    ensure!(served.load(SeqCst) == CLIENTS, "not every client was served");
+
+    let shutdown = server.shutdown_handle();
+    thread::spawn(move || shutdown.shutdown())
+        .join()
+        .map_err(|_| anyhow!("shutdown thread panicked"))?;
+    ensure!(server.is_shut_down(), "server does not report being shut down");
+    server.join();
+
+    if !name.starts_with('@') {
+        let _ = fs::remove_file(&name);
+    }
+    Ok(())
+}
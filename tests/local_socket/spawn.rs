@@ -0,0 +1,108 @@
+//! Tests the spawn helper by running this test binary again as the child process, filtered down to [`child()`], with
+//! both ways of passing the endpoints.
+
+use {
+    super::util::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::spawn::{
+        reader_from_parent, stream_from_parent, writer_to_parent, EndpointPassing, Spawner, BOOTSTRAP_VAR,
+        ENDPOINTS_VAR,
+    },
+    std::{
+        env,
+        io::{self, prelude::*, BufReader},
+        process::{Command, Stdio},
+    },
+};
+
+fn child_command(test: &str) -> io::Result<Command> {
+    let mut command = Command::new(env::current_exe()?);
+    command
+        .args(["--exact", test, "--test-threads=1"])
+        .stdout(Stdio::null());
+    Ok(command)
+}
+
+pub fn run(passing: EndpointPassing) -> TestResult {
+    let spawner = Spawner::new(child_command("local_socket_spawn_child")?)
+        .passing(passing)
+        .stream("control")
+        .pipe_to_child("input")
+        .pipe_from_child("output");
+    let mut spawned = spawner.spawn().context("Spawn failed")?;
+    ensure!(spawned.take_reader("control").is_none(), "took a stream as a pipe");
+    let mut control = BufReader::new(spawned.take_stream("control").context("No control stream")?);
+    let mut input = spawned.take_writer("input").context("No input pipe")?;
+    let mut output = spawned.take_reader("output").context("No output pipe")?;
+    ensure!(spawned.take_stream("control").is_none(), "took a stream twice");
+
+    control.get_mut().write_all(b"hello\n").context("Control send failed")?;
+    let mut reply = String::new();
+    control.read_line(&mut reply).context("Control receive failed")?;
+    ensure!(reply == "HELLO\n", "wrong reply {:?}", reply);
+
+    // The child only sees the end of its input and closes its output once no process but the parent has the writing
+    // end of the former.
+    input.write_all(b"piped data").context("Pipe write failed")?;
+    drop(input);
+    let mut echoed = Vec::new();
+    output.read_to_end(&mut echoed).context("Pipe read failed")?;
+    ensure!(echoed == b"piped data", "wrong data echoed: {:?}", echoed);
+
+    let status = spawned.child.wait().context("Wait failed")?;
+    ensure!(status.success(), "child failed with {}", status);
+    Ok(())
+}
+
+pub fn run_invalid() -> TestResult {
+    let err = Spawner::new(child_command("local_socket_spawn_child")?)
+        .stream("control")
+        .stream("control")
+        .spawn()
+        .err()
+        .context("Spawn with a duplicate key succeeded")?;
+    ensure!(err.kind() == io::ErrorKind::InvalidInput, "wrong error: {}", err);
+
+    // This one exits without picking up its endpoints.
+    let err = Spawner::new(child_command("no_such_test")?)
+        .passing(EndpointPassing::Bootstrap)
+        .stream("control")
+        .spawn()
+        .err()
+        .context("Bootstrapping a child which exits succeeded")?;
+    ensure!(err.kind() == io::ErrorKind::BrokenPipe, "wrong error: {}", err);
+    Ok(())
+}
+
+pub fn child() -> TestResult {
+    if env::var_os(ENDPOINTS_VAR).is_none() && env::var_os(BOOTSTRAP_VAR).is_none() {
+        // Not spawned by `run()`.
+        return Ok(());
+    }
+    // SAFETY: the child process runs this test alone on a single test thread, and the endpoints were set up by `run()`.
+    let err = unsafe { stream_from_parent("input") }
+        .err()
+        .context("Took a pipe as a stream")?;
+    ensure!(err.kind() == io::ErrorKind::InvalidInput, "wrong error: {}", err);
+    let err = unsafe { stream_from_parent("missing") }
+        .err()
+        .context("Took a missing endpoint")?;
+    ensure!(err.kind() == io::ErrorKind::NotFound, "wrong error: {}", err);
+    ensure!(
+        env::var_os(ENDPOINTS_VAR).is_none() && env::var_os(BOOTSTRAP_VAR).is_none(),
+        "environment variables left behind"
+    );
+
+    let mut control = BufReader::new(unsafe { stream_from_parent("control") }.context("No control stream")?);
+    let mut greeting = String::new();
+    control.read_line(&mut greeting).context("Control receive failed")?;
+    control
+        .get_mut()
+        .write_all(greeting.to_uppercase().as_bytes())
+        .context("Control send failed")?;
+
+    let mut input = unsafe { reader_from_parent("input") }.context("No input pipe")?;
+    let mut output = unsafe { writer_to_parent("output") }.context("No output pipe")?;
+    io::copy(&mut input, &mut output).context("Echo failed")?;
+    Ok(())
+}
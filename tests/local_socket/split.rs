@@ -0,0 +1,52 @@
+//! Tests that the borrowed halves of a local socket stream, as well as plain shared references to one, can be used from
+//! two threads at once.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+static PING: &[u8] = b"ping";
+static PONG: &[u8] = b"pong";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        // Read and Write are implemented for &LocalSocketStream, so no splitting is needed
+        thread::scope(|s| {
+            let receiver = s.spawn(|| {
+                let mut buf = [0; 4];
+                (&conn).read_exact(&mut buf).context("Receive failed")?;
+                ensure!(buf == PING, "received {:?} instead of the ping", buf);
+                Ok(())
+            });
+            (&conn).write_all(PONG).context("Send failed")?;
+            receiver.join().map_err(|_| anyhow!("receiver panicked"))?
+        })
+    });
+
+    let conn = listener.accept().context("Accept failed")?;
+    let (mut reader, mut writer) = conn.split();
+    thread::scope(|s| {
+        let sender = s.spawn(move || writer.write_all(PING).context("Send failed"));
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).context("Receive failed")?;
+        ensure!(buf == PONG, "received {:?} instead of the pong", buf);
+        sender.join().map_err(|_| anyhow!("sender panicked"))?
+    })?;
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,62 @@
+//! Tests that the `Timeout` wrapper times out reads and accepts, and lets through the ones that complete in time.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::{LocalSocketListener, LocalSocketStream},
+        timeout::Timeout,
+    },
+    std::{
+        io::{self, prelude::*},
+        sync::mpsc,
+        thread,
+        time::Duration,
+    },
+};
+
+const MSG: &[u8] = b"Hello from client!";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+    let listener = Timeout::new(listener).accept_timeout(Duration::from_millis(50));
+
+    match listener.accept() {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        Err(e) => bail!("expected a timeout, got a different error: {}", e),
+        Result::Ok(..) => bail!("accepted a connection even though there were no clients"),
+    }
+
+    let (go_tx, go_rx) = mpsc::channel();
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        go_rx.recv().context("Server disappeared")?;
+        conn.write_all(MSG).context("Send failed")?;
+        // Keep the connection open until the server is done with it.
+        let _ = go_rx.recv();
+        Ok(())
+    });
+    let conn = listener.accept().context("Accept failed")?;
+    let mut conn = Timeout::new(conn).read_timeout(Duration::from_millis(50));
+
+    let mut buf = [0; MSG.len()];
+    match conn.read(&mut buf) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        Err(e) => bail!("expected a timeout, got a different error: {}", e),
+        Result::Ok(..) => bail!("read succeeded even though nothing was sent"),
+    }
+
+    go_tx.send(()).unwrap();
+    let mut conn = Timeout::new(conn.into_inner().context("Unwrapping failed")?).read_timeout(Duration::from_secs(10));
+    conn.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == MSG, "received message doesn't match");
+    go_tx.send(()).unwrap();
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,48 @@
+//! Tests that a cloned local socket stream can be used from another thread and keeps the connection alive.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::local_socket::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+static PING: &[u8] = b"ping";
+static PONG: &[u8] = b"pong";
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        let mut buf = [0; 4];
+        conn.read_exact(&mut buf).context("Receive failed")?;
+        ensure!(buf == PING, "received {:?} instead of the ping", buf);
+        conn.write_all(PONG).context("Send failed")?;
+        Ok(())
+    });
+
+    let mut conn = listener.accept().context("Accept failed")?;
+    let mut reader = conn.try_clone().context("Clone failed")?;
+    let reader = thread::spawn(move || -> TestResult {
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).context("Receive failed")?;
+        ensure!(buf == PONG, "received {:?} instead of the pong", buf);
+        Ok(())
+    });
+    conn.write_all(PING).context("Send failed")?;
+    // The clone must keep the connection alive on its own.
+    drop(conn);
+
+    reader.join().map_err(|_| anyhow!("reader panicked"))??;
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,171 @@
+//! Tests typed channels over a local socket stream, including the rejection of foreign streams and of oversized
+//! messages.
+
+use {
+    super::util::*,
+    anyhow::*,
+    interprocess::{
+        local_socket::{LocalSocketListener, LocalSocketStream},
+        typed::{TypedReceiver, TypedSender},
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Msg {
+    id: u32,
+    text: String,
+    tags: Vec<String>,
+}
+
+fn msgs() -> Vec<Msg> {
+    vec![
+        Msg {
+            id: 1,
+            text: "First message".to_string(),
+            tags: vec![],
+        },
+        Msg {
+            id: 2,
+            text: String::new(),
+            tags: vec!["empty".to_string()],
+        },
+        Msg {
+            id: 3,
+            text: "Third message, a bit longer than the others".to_string(),
+            tags: vec!["long".to_string(), "last".to_string()],
+        },
+    ]
+}
+const MAX_MESSAGE_SIZE: usize = 64;
+
+pub fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut sender = TypedSender::<Msg>::connect(name.as_str()).context("First connect failed")?;
+        for msg in msgs() {
+            sender.send(&msg).context("Send failed")?;
+        }
+        drop(sender);
+
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Second connect failed")?;
+        conn.write_all(b"GET / HTTP/1.1\r\n").context("Foreign send failed")?;
+        drop(conn);
+
+        let mut sender = TypedSender::<Msg>::connect(name.as_str()).context("Third connect failed")?;
+        let mut big = msgs().pop().unwrap();
+        big.text = "x".repeat(MAX_MESSAGE_SIZE);
+        sender.send(&big).context("Oversized send failed")?;
+        Ok(())
+    });
+
+    let mut receiver = TypedReceiver::<Msg>::new(listener.accept().context("First accept failed")?)
+        .context("Header receive failed")?;
+    for msg in msgs() {
+        let received = receiver.recv().context("Receive failed")?.context("Unexpected EOF")?;
+        ensure!(received == msg, "received {:?} instead of {:?}", received, msg);
+    }
+    let eof = receiver.recv().context("Receive at EOF failed")?;
+    ensure!(eof.is_none(), "received {:?} instead of EOF", eof);
+
+    let conn = listener.accept().context("Second accept failed")?;
+    match TypedReceiver::<Msg>::new(conn) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+        els => bail!("foreign stream was not rejected: {:?}", els),
+    }
+
+    let mut receiver = TypedReceiver::<Msg>::new(listener.accept().context("Third accept failed")?)
+        .context("Second header receive failed")?;
+    receiver.set_max_message_size(MAX_MESSAGE_SIZE);
+    match receiver.recv() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+        els => bail!("oversized message was not rejected: {:?}", els),
+    }
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
+
+/// Exchanges values with a peer that speaks JSON over the typed channel framing without using the crate.
+#[cfg(feature = "typed-json")]
+pub fn run_json(prefer_namespaced: bool) -> TestResult {
+    use interprocess::typed::{Codec, FORMAT_VERSION};
+
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || -> TestResult {
+        let mut conn = LocalSocketStream::connect(name.as_str()).context("Foreign connect failed")?;
+        let mut frame = b"IPCTYPED".to_vec();
+        frame.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        frame.push(Codec::Json.id());
+        let json = br#"{"id":7,"text":"From elsewhere","tags":["json"]}"#;
+        frame.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        frame.extend_from_slice(json);
+        conn.write_all(&frame).context("Foreign send failed")?;
+        drop(conn);
+
+        let conn = LocalSocketStream::connect(name.as_str()).context("Connect failed")?;
+        let mut sender = TypedSender::<Msg>::with_codec(conn, Codec::Json).context("Header send failed")?;
+        sender.send(&msgs()[0]).context("Send failed")?;
+        Ok(())
+    });
+
+    let mut receiver = TypedReceiver::<Msg>::new(listener.accept().context("First accept failed")?)
+        .context("Foreign header receive failed")?;
+    ensure!(
+        receiver.codec() == Codec::Json,
+        "codec {:?} detected instead of JSON",
+        receiver.codec()
+    );
+    let received = receiver
+        .recv()
+        .context("Foreign receive failed")?
+        .context("Unexpected EOF")?;
+    let expected = Msg {
+        id: 7,
+        text: "From elsewhere".to_string(),
+        tags: vec!["json".to_string()],
+    };
+    ensure!(
+        received == expected,
+        "received {:?} instead of {:?}",
+        received,
+        expected
+    );
+
+    let mut conn = listener.accept().context("Second accept failed")?;
+    let mut raw = Vec::new();
+    conn.read_to_end(&mut raw).context("Raw receive failed")?;
+    ensure!(
+        raw.len() > 15 && raw[..8] == *b"IPCTYPED" && raw[10] == Codec::Json.id(),
+        "unexpected header in {:?}",
+        raw
+    );
+    let len = u32::from_le_bytes(raw[11..15].try_into()?) as usize;
+    ensure!(len == raw.len() - 15, "length prefix {} doesn't match", len);
+    let received: Msg = serde_json::from_slice(&raw[15..]).context("Raw JSON parse failed")?;
+    ensure!(
+        received == msgs()[0],
+        "received {:?} instead of {:?}",
+        received,
+        msgs()[0]
+    );
+
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
@@ -0,0 +1,84 @@
+//! Tests what each drop policy does with data which the client hasn't read yet when the server drops its stream.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{anyhow, ensure, Context},
+    interprocess::{
+        drop_policy::{default_drop_policy, DropPolicy},
+        os::windows::named_pipe::{pipe_mode, DuplexPipeStream, PipeListenerOptions},
+    },
+    std::{
+        ffi::OsStr,
+        io::{self, prelude::*},
+        sync::mpsc,
+        thread,
+    },
+};
+
+static SERVER_MSG: &[u8] = b"Sent right before the drop";
+
+pub fn run() -> TestResult {
+    let (name, listener) = NameGen::new(true)
+        .find_map(|nm| {
+            let rnm: &OsStr = nm.as_ref();
+            let l = match PipeListenerOptions::new().name(rnm).create_duplex::<pipe_mode::Bytes>() {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let policies = [
+        DropPolicy::FlushThenDisconnect,
+        DropPolicy::Disconnect,
+        DropPolicy::Detach,
+    ];
+    // The client only starts reading once the server has dropped its stream, so that it can't drain the pipe before
+    // the policy is carried out.
+    let (dropped_sender, dropped) = mpsc::channel::<()>();
+    let client = thread::spawn(move || -> TestResult {
+        for policy in policies {
+            let mut conn = DuplexPipeStream::<pipe_mode::Bytes>::connect(name.as_str()).context("Connect failed")?;
+            dropped.recv().context("Server exited early")?;
+            let received = read_until_closed(&mut conn);
+            let expected: &[u8] = match policy {
+                DropPolicy::Disconnect => b"",
+                _ => SERVER_MSG,
+            };
+            ensure!(
+                received == expected,
+                "client received {:?} under {:?}",
+                String::from_utf8_lossy(&received),
+                policy
+            );
+        }
+        Ok(())
+    });
+
+    for policy in policies {
+        let conn = listener.accept().context("Accept failed")?;
+        ensure!(
+            conn.drop_policy() == default_drop_policy() && default_drop_policy() == DropPolicy::Disconnect,
+            "stream does not follow the default policy"
+        );
+        conn.set_drop_policy(policy);
+        ensure!(conn.drop_policy() == policy, "policy was not set");
+        (&conn).write_all(SERVER_MSG).context("Send failed")?;
+        drop(conn);
+        dropped_sender.send(()).context("Client exited early")?;
+    }
+    client.join().map_err(|_| anyhow!("client panicked"))?
+}
+
+/// Reads until the end of the stream or the first error, which is what a disconnected client gets instead.
+fn read_until_closed(conn: &mut DuplexPipeStream<pipe_mode::Bytes>) -> Vec<u8> {
+    let mut received = Vec::new();
+    let mut buf = [0; 64];
+    while let Ok(n @ 1..) = conn.read(&mut buf) {
+        received.extend_from_slice(&buf[..n]);
+    }
+    received
+}
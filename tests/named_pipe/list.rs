@@ -0,0 +1,32 @@
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::os::windows::named_pipe::{list_pipes, pipe_mode, PipeListenerOptions},
+    std::{ffi::OsStr, io},
+};
+
+pub fn run() -> TestResult {
+    let (name, _listener) = NameGen::new(true)
+        .find_map(|nm| {
+            let rnm: &OsStr = nm.as_ref();
+            let l = match PipeListenerOptions::new().name(rnm).create_duplex::<pipe_mode::Bytes>() {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let mut found = None;
+    for pipe in list_pipes().context("Listing failed")? {
+        let pipe = pipe.context("Listing failed")?;
+        if pipe.name() == OsStr::new(&name) {
+            found = Some(pipe);
+        }
+    }
+    let pipe = found.with_context(|| format!("{name:?} was not listed"))?;
+    ensure!(pipe.instances() >= 1, "{:?} was listed with no instances", pipe);
+    Ok(())
+}
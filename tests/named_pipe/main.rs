@@ -5,6 +5,8 @@ mod util;
 mod bytes;
 mod bytes_unidir_client_to_server;
 mod bytes_unidir_server_to_client;
+mod drop_policy;
+mod list;
 mod msg;
 mod msg_unidir_client_to_server;
 mod msg_unidir_server_to_client;
@@ -48,3 +50,12 @@ fn named_pipe_msg_unidir_server_to_client() {
         msg_unidir_server_to_client::client,
     )
 }
+
+#[test]
+fn named_pipe_drop_policy() -> util::TestResult {
+    drop_policy::run()
+}
+#[test]
+fn named_pipe_list() -> util::TestResult {
+    list::run()
+}
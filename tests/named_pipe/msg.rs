@@ -14,6 +14,7 @@ use {
 
 const SERVER_MSG_1: &[u8] = b"First server message";
 const SERVER_MSG_2: &[u8] = b"Second server message";
+const SERVER_MSG_3: &[u8] = b"Third server message, which doesn't fit into the client's buffer";
 
 const CLIENT_MSG_1: &[u8] = b"First client message";
 const CLIENT_MSG_2: &[u8] = b"Second client message";
@@ -63,6 +64,9 @@ pub fn server(name_sender: Sender<String>, num_clients: u32) -> TestResult {
         let written = conn.send(SERVER_MSG_2).context("Second pipe send failed")?;
         assert_eq!(written, SERVER_MSG_2.len());
 
+        let written = conn.send(SERVER_MSG_3).context("Third pipe send failed")?;
+        assert_eq!(written, SERVER_MSG_3.len());
+
         conn.flush().context("Flush failed")?;
     }
 
@@ -87,6 +91,11 @@ pub fn client(name: Arc<String>) -> TestResult {
     assert_eq!(rslt.size(), SERVER_MSG_2.len());
     assert_eq!(rslt.borrow_to_size(&buf2), SERVER_MSG_2);
 
+    let rslt = conn.recv(&mut buf1).context("Third pipe receive failed")?;
+    assert!(!rslt.fit());
+    assert_eq!(rslt.size(), SERVER_MSG_3.len());
+    assert_eq!(rslt.borrow_to_size(&buf1), SERVER_MSG_3);
+
     conn.flush().context("Flush failed")?;
 
     Ok(())
@@ -0,0 +1,122 @@
+//! Tests the shared memory allocator across two mappings of the same object, including the reuse of freed space,
+//! running out of space and handing containers over by offset.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    bytemuck::{Pod, Zeroable},
+    interprocess::shared_memory::{
+        arena::{ShmArena, ShmBox, ShmOffset, ShmVec},
+        SharedMemory,
+    },
+    std::io,
+};
+
+/// A linked list node, to check that offsets work as relative pointers.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct Node {
+    value: u64,
+    next: ShmOffset<Node>,
+}
+
+pub fn run() -> TestResult {
+    // Namespaced names have no slashes in them, which makes them valid shared memory names everywhere.
+    let shm = NameGen::new(true)
+        .find_map(|nm| match SharedMemory::create(nm, 64 * 1024) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+            els => Some(els),
+        })
+        .unwrap()
+        .context("Creation failed")?;
+    let result = test_arena(&shm);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::shared_memory::SharedMemoryExt;
+        shm.unlink().context("Unlink failed")?;
+    }
+    result
+}
+
+fn test_arena(shm: &SharedMemory) -> TestResult {
+    let arena = ShmArena::init(shm.map()?).context("Arena initialization failed")?;
+    let other = ShmArena::open(shm.map()?).context("Arena opening failed")?;
+    ensure!(arena.capacity() == other.capacity(), "capacities differ");
+    let err = ShmArena::open(shm.map_read_only()?)
+        .err()
+        .context("Opened an arena through a read-only mapping")?;
+    ensure!(err.kind() == io::ErrorKind::PermissionDenied, "wrong error: {}", err);
+
+    // Freed space is reused, including the rest of a block which was split.
+    let first = ShmBox::new_in([0_u8; 256], &arena)?;
+    let first_offset = first.offset();
+    drop(first);
+    let small = ShmBox::new_in(1_u32, &arena)?;
+    ensure!(
+        small.offset() == ShmOffset::from_u64(first_offset.as_u64()),
+        "freed block not reused"
+    );
+    let rest = ShmBox::new_in(2_u32, &arena)?;
+    ensure!(
+        rest.offset().as_u64() < first_offset.as_u64() + 256,
+        "split block not reused"
+    );
+
+    // A vector grows, and is handed over to the other mapping together with a linked list.
+    let mut vec = ShmVec::with_capacity_in(2, &arena)?;
+    for i in 0..1000_u64 {
+        vec.push(i)?;
+    }
+    ensure!(vec.capacity() >= 1000, "capacity below length");
+    ensure!(vec.pop() == Some(999), "wrong element popped");
+    let tail = ShmBox::new_in(
+        Node {
+            value: 2,
+            next: ShmOffset::from_u64(0),
+        },
+        &arena,
+    )?;
+    let head = ShmBox::new_in(
+        Node {
+            value: 1,
+            next: tail.into_offset(),
+        },
+        &arena,
+    )?;
+    let (vec, head) = (vec.into_offset(), head.into_offset());
+
+    let (vec, head) = unsafe {
+        // SAFETY: the containers were given up above
+        (ShmVec::from_offset(vec, &other), ShmBox::from_offset(head, &other))
+    };
+    ensure!(vec.iter().copied().eq(0..999), "vector contents corrupted");
+    ensure!(head.value == 1, "wrong head value");
+    let tail = other.ptr(head.next).context("Following the offset failed")?;
+    ensure!(unsafe { (*tail).value } == 2, "wrong tail value");
+    ensure!(
+        other.ptr(ShmOffset::<Node>::from_u64(u64::MAX - 4)).is_err(),
+        "resolved an offset outside of the arena"
+    );
+    drop(unsafe { ShmBox::from_offset(head.next, &other) });
+    drop((vec, head));
+
+    // Running out of space fails without breaking anything.
+    let err = ShmVec::<u8>::with_capacity_in(128 * 1024, &arena)
+        .err()
+        .context("Allocated more than the arena holds")?;
+    ensure!(err.kind() == io::ErrorKind::OutOfMemory, "wrong error: {}", err);
+    // Sizes close to the maximum are rejected rather than overflowing the end of the allocation.
+    let err = ShmVec::<u8>::with_capacity_in(usize::MAX - 64, &arena)
+        .err()
+        .context("Allocated a huge vector")?;
+    ensure!(err.kind() == io::ErrorKind::OutOfMemory, "wrong error: {}", err);
+    let mut filler = Vec::new();
+    while let Ok(b) = ShmBox::new_in([0_u64; 64], &arena) {
+        filler.push(b);
+    }
+    ensure!(filler.len() > 10, "arena filled up too early");
+    drop(filler);
+    ensure!(*ShmBox::new_in(7_u8, &other)? == 7, "allocation failed after freeing");
+    drop((small, rest));
+    Ok(())
+}
@@ -0,0 +1,30 @@
+#![cfg(not(target_os = "android"))]
+
+#[path = "../util/mod.rs"]
+mod util;
+
+#[cfg(feature = "bytemuck")]
+mod arena;
+mod object;
+mod ring;
+#[cfg(feature = "bytemuck")]
+mod view;
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn shared_memory_arena() -> util::TestResult {
+    arena::run()
+}
+#[test]
+fn shared_memory_object() -> util::TestResult {
+    object::run()
+}
+#[test]
+fn shared_memory_ring() -> util::TestResult {
+    ring::run()
+}
+#[cfg(feature = "bytemuck")]
+#[test]
+fn shared_memory_view() -> util::TestResult {
+    view::run()
+}
@@ -0,0 +1,96 @@
+//! Tests creating, opening and mapping shared memory objects, and the errors for the name being taken or missing.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::shared_memory::SharedMemory,
+    std::io,
+};
+
+const SIZE: usize = 4096;
+
+pub fn run() -> TestResult {
+    let (name, shm) = NameGen::new(true)
+        .find_map(|nm| match SharedMemory::create(&nm, SIZE) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+            els => Some(els.map(|shm| (nm, shm))),
+        })
+        .unwrap()
+        .context("Creation failed")?;
+    let result = test_object(&name, &shm);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::shared_memory::SharedMemoryExt;
+        shm.unlink().context("Unlink failed")?;
+        match SharedMemory::open(&name) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => bail!("opening an unlinked object failed with an unexpected error: {}", e),
+            Ok(..) => bail!("unlinked object could still be opened"),
+        }
+    }
+    result
+}
+
+fn test_object(name: &str, shm: &SharedMemory) -> TestResult {
+    ensure!(shm.len() == SIZE, "created object has a size of {} bytes", shm.len());
+    ensure!(!shm.is_read_only(), "created object is read-only");
+    match SharedMemory::create(name, SIZE) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => bail!("creating a taken name failed with an unexpected error: {}", e),
+        Ok(..) => bail!("second object was created under the same name"),
+    }
+    match SharedMemory::create(format!("{}-empty", name), 0) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        Err(e) => bail!("creating an empty object failed with an unexpected error: {}", e),
+        Ok(..) => bail!("empty object was created"),
+    }
+    match SharedMemory::open(format!("{}-missing", name)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => bail!("opening a nonexistent object failed with an unexpected error: {}", e),
+        Ok(..) => bail!("nonexistent object was opened"),
+    }
+
+    let writer = shm.map().context("Mapping failed")?;
+    ensure!(
+        writer.len() == SIZE && !writer.is_read_only(),
+        "mapping has the wrong properties"
+    );
+    // The contents of a new object are zeroed.
+    ensure!(
+        unsafe { writer.as_slice() }.iter().all(|&b| b == 0),
+        "new object isn't zeroed"
+    );
+
+    let other = SharedMemory::open(name).context("Open failed")?;
+    ensure!(other.len() >= SIZE, "opened object has a size of {} bytes", other.len());
+    let other = other.map().context("Mapping of opened object failed")?;
+    let read_only = SharedMemory::open_read_only(name).context("Read-only open failed")?;
+    ensure!(read_only.is_read_only(), "object opened for reading only is writable");
+    match read_only.map() {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {}
+        Err(e) => bail!(
+            "writable mapping of a read-only object failed with an unexpected error: {}",
+            e
+        ),
+        Ok(..) => bail!("writable mapping of a read-only object was made"),
+    }
+    let reader = read_only.map_read_only().context("Read-only mapping failed")?;
+    ensure!(reader.is_read_only(), "read-only mapping is writable");
+    // The mappings keep the memory alive on their own.
+    drop(read_only);
+
+    unsafe {
+        // SAFETY: only this thread accesses the memory
+        writer.as_mut_slice()[..6].copy_from_slice(b"Hello!");
+        other.as_mut_slice()[SIZE - 6..].copy_from_slice(b"Howdy!");
+        ensure!(
+            &reader.as_slice()[..6] == b"Hello!",
+            "write through one mapping not visible in another"
+        );
+        ensure!(
+            &writer.as_slice()[SIZE - 6..SIZE] == b"Howdy!",
+            "write through an opened object not visible"
+        );
+    }
+    Ok(())
+}
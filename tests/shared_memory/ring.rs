@@ -0,0 +1,128 @@
+//! Tests the empty and full states of ring buffer channels, messages wrapping around the end of the buffer, and the
+//! disconnection of either side.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::shared_memory::ring::{RingReceiver, RingSender},
+    std::{io, thread, time::Duration},
+};
+
+/// Small enough for every test to fill the buffer and wrap around many times, and not a multiple of the message sizes,
+/// so that both the length prefixes and the contents get split by the end of the buffer.
+const CAPACITY: usize = 61;
+
+fn message(i: usize) -> Vec<u8> {
+    (0..(i % 13) + 1).map(|j| (i * 31 + j) as u8).collect()
+}
+
+pub fn run() -> TestResult {
+    let (name, receiver) = NameGen::new(true)
+        .find_map(|nm| match RingReceiver::create(&nm, CAPACITY) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+            els => Some(els.map(|r| (nm, r))),
+        })
+        .unwrap()
+        .context("Creation failed")?;
+    let result = test_ring(&name, receiver);
+    #[cfg(unix)]
+    interprocess::os::unix::shared_memory::unlink_ring_buffer(&name).context("Unlink failed")?;
+    result
+}
+
+fn test_ring(name: &str, mut receiver: RingReceiver) -> TestResult {
+    ensure!(
+        receiver.capacity() == CAPACITY,
+        "capacity is {} bytes",
+        receiver.capacity()
+    );
+    let mut sender = RingSender::open(name).context("Open failed")?;
+
+    // Empty.
+    ensure!(receiver.try_recv()?.is_none(), "message received from an empty buffer");
+    ensure!(
+        receiver.recv_timeout(Duration::from_millis(20))?.is_none(),
+        "message received from an empty buffer with a timeout"
+    );
+
+    // Full: four 10-byte messages take up 56 bytes with their length prefixes, leaving no room for a fifth one.
+    for _ in 0..4 {
+        ensure!(sender.try_send(&[7; 10])?, "message did not fit into the buffer");
+    }
+    ensure!(!sender.try_send(&[7; 10])?, "message fit into a full buffer");
+    ensure!(
+        sender.try_send(&[7])?,
+        "message that fits into the remaining space was not sent"
+    );
+    for _ in 0..4 {
+        ensure!(receiver.try_recv()? == Some(vec![7; 10]), "wrong message received");
+    }
+    ensure!(receiver.try_recv()? == Some(vec![7]), "wrong message received");
+    ensure!(
+        receiver.try_recv()?.is_none(),
+        "message received from an emptied buffer"
+    );
+
+    // The largest possible message takes up the whole buffer, and a larger one is rejected.
+    let largest = vec![1; CAPACITY - 4];
+    ensure!(sender.try_send(&largest)?, "largest possible message was not sent");
+    ensure!(receiver.try_recv()? == Some(largest), "wrong message received");
+    match sender.try_send(&[1; CAPACITY - 3]) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        Err(e) => bail!("sending an oversized message failed with an unexpected error: {}", e),
+        Ok(..) => bail!("oversized message was accepted"),
+    }
+
+    // Wraparound, with messages of varying sizes.
+    for round in 0..200 {
+        let batch = round % 3 + 1;
+        for i in 0..batch {
+            ensure!(
+                sender.try_send(&message(round * 3 + i))?,
+                "message did not fit into the buffer"
+            );
+        }
+        for i in 0..batch {
+            ensure!(
+                receiver.try_recv()? == Some(message(round * 3 + i)),
+                "message {} of round {} was corrupted",
+                i,
+                round
+            );
+        }
+    }
+
+    // A sender blocked on a full buffer is woken up by the receiver making room, and the receiver sees the
+    // disconnection once it has received all of the messages.
+    let sending = thread::spawn(move || -> TestResult {
+        for i in 0..1000 {
+            sender.send(&message(i))?;
+        }
+        Ok(())
+    });
+    for i in 0..1000 {
+        ensure!(receiver.recv()? == message(i), "message {} was corrupted", i);
+    }
+    sending.join().unwrap()?;
+    match receiver.recv() {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => bail!(
+            "receiving after the sender was dropped failed with an unexpected error: {}",
+            e
+        ),
+        Ok(..) => bail!("message received after the sender was dropped"),
+    }
+
+    // The other way around.
+    let mut sender = RingSender::open(name).context("Reopen failed")?;
+    drop(receiver);
+    match sender.send(b"Anyone there?") {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => bail!(
+            "sending after the receiver was dropped failed with an unexpected error: {}",
+            e
+        ),
+        Ok(..) => bail!("message sent after the receiver was dropped"),
+    }
+    Ok(())
+}
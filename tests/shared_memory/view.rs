@@ -0,0 +1,206 @@
+//! Tests typed views over mappings, with their size and alignment checks, and the versioned header.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    bytemuck::{Pod, Zeroable},
+    interprocess::shared_memory::{SharedMemory, SharedMemoryMapping, VersionedHeader},
+    std::{io, mem::size_of},
+};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+struct Stats {
+    requests: u64,
+    errors: u64,
+}
+/// Same as `Stats`, but with a field added, as a later version of the program would have it.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+struct StatsV2 {
+    requests: u64,
+    errors: u64,
+    timeouts: u64,
+}
+/// Aligned to more than the header, so that the data doesn't start right after it.
+#[repr(C, align(64))]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+struct Aligned {
+    value: [u64; 8],
+}
+
+const MAGIC: [u8; 8] = *b"ipc-test";
+const SIZE: usize = 4096;
+
+fn create() -> anyhow::Result<(String, SharedMemory)> {
+    NameGen::new(true)
+        .find_map(|nm| match SharedMemory::create(&nm, SIZE) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+            els => Some(els.map(|shm| (nm, shm))),
+        })
+        .unwrap()
+        .context("Creation failed")
+}
+
+pub fn run() -> TestResult {
+    let (name, shm) = create()?;
+    let result = test_views(&name, &shm);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::shared_memory::SharedMemoryExt;
+        shm.unlink().context("Unlink failed")?;
+    }
+    result
+}
+
+fn expect_error<T>(rslt: io::Result<T>, kind: io::ErrorKind, what: &str) -> TestResult {
+    match rslt {
+        Err(e) if e.kind() == kind => Ok(()),
+        Err(e) => bail!("{} failed with an unexpected error: {}", what, e),
+        Ok(..) => bail!("{} succeeded", what),
+    }
+}
+
+fn test_views(name: &str, shm: &SharedMemory) -> TestResult {
+    let writer = shm.map().context("Mapping failed")?;
+    let reader = SharedMemory::open_read_only(name)
+        .context("Read-only open failed")?
+        .map_read_only()
+        .context("Read-only mapping failed")?;
+
+    // SAFETY: the views are not held across writes
+    unsafe {
+        *writer.view_mut::<u32>(8)? = 0xdead_beef;
+        let value = *reader.view::<u32>(8)?;
+        ensure!(value == 0xdead_beef, "read {:#x} through the other mapping", value);
+
+        writer.view_slice_mut::<u16>(16, 4)?.copy_from_slice(&[1, 2, 3, 4]);
+        let values = reader.view_slice::<u16>(16, 4)?;
+        ensure!(values == [1, 2, 3, 4], "read {:?} through the other mapping", values);
+        ensure!(
+            reader.view_slice::<u64>(0, 0)?.is_empty(),
+            "empty slice view is not empty"
+        );
+
+        expect_error(reader.view::<u32>(2), io::ErrorKind::InvalidInput, "misaligned view")?;
+        expect_error(
+            reader.view::<u64>(SIZE - 4),
+            io::ErrorKind::InvalidInput,
+            "view past the end",
+        )?;
+        expect_error(
+            reader.view_slice::<u8>(1, SIZE),
+            io::ErrorKind::InvalidInput,
+            "slice view past the end",
+        )?;
+        expect_error(
+            reader.view_slice::<u64>(0, usize::MAX),
+            io::ErrorKind::InvalidInput,
+            "slice view with an overflowing size",
+        )?;
+    }
+
+    test_versioned(&writer, &reader)?;
+    test_aligned(&writer, &reader)
+}
+
+fn test_versioned(writer: &SharedMemoryMapping, reader: &SharedMemoryMapping) -> TestResult {
+    ensure!(size_of::<VersionedHeader>() == 24, "header is not 24 bytes in size");
+    // SAFETY: the views are not held across writes
+    unsafe {
+        let stats = writer.init_versioned::<Stats>(MAGIC, 1).context("Header init failed")?;
+        *stats = Stats {
+            requests: 42,
+            errors: 1,
+        };
+        let header = *reader.view::<VersionedHeader>(0)?;
+        ensure!(
+            header == VersionedHeader::new::<Stats>(MAGIC, 1),
+            "unexpected header {:?}",
+            header
+        );
+        ensure!(
+            header.payload_offset == 24 && header.payload_len == 16,
+            "unexpected payload placement in {:?}",
+            header
+        );
+
+        let stats = *reader.versioned::<Stats>(MAGIC, 1).context("Header check failed")?;
+        ensure!(
+            stats
+                == Stats {
+                    requests: 42,
+                    errors: 1
+                },
+            "read {:?}",
+            stats
+        );
+        writer.versioned_mut::<Stats>(MAGIC, 1)?.errors += 1;
+        let errors = reader.versioned::<Stats>(MAGIC, 1)?.errors;
+        ensure!(errors == 2, "read {} errors after an update", errors);
+
+        expect_error(
+            reader.versioned::<Stats>(*b"someapp!", 1),
+            io::ErrorKind::InvalidData,
+            "check with a different magic number",
+        )?;
+        expect_error(
+            reader.versioned::<Stats>(MAGIC, 2),
+            io::ErrorKind::InvalidData,
+            "check with a different version",
+        )?;
+        expect_error(
+            reader.versioned::<StatsV2>(MAGIC, 1),
+            io::ErrorKind::InvalidData,
+            "check with a different size under the same version",
+        )?;
+    }
+    Ok(())
+}
+
+fn test_aligned(writer: &SharedMemoryMapping, reader: &SharedMemoryMapping) -> TestResult {
+    // SAFETY: the views are not held across writes
+    unsafe {
+        writer.init_versioned::<Aligned>(MAGIC, 3)?.value = [7; 8];
+        let header = *reader.view::<VersionedHeader>(0)?;
+        ensure!(
+            header.payload_offset == 64,
+            "data aligned to 64 bytes placed at offset {}",
+            header.payload_offset
+        );
+        let value = reader.versioned::<Aligned>(MAGIC, 3)?.value;
+        ensure!(value == [7; 8], "read {:?}", value);
+        // The previous header was overwritten.
+        expect_error(
+            reader.versioned::<Stats>(MAGIC, 1),
+            io::ErrorKind::InvalidData,
+            "check against an overwritten header",
+        )?;
+    }
+
+    // Data which doesn't fit leaves the header alone.
+    let (_, small) = create()?;
+    let small_writer = small.map()?;
+    let result = unsafe {
+        // SAFETY: the memory is only accessed here
+        expect_error(
+            small_writer.init_versioned::<[u8; SIZE]>(MAGIC, 1),
+            io::ErrorKind::InvalidInput,
+            "initialization of data that doesn't fit",
+        )
+        .and_then(|()| {
+            let header = *small_writer.view::<VersionedHeader>(0)?;
+            ensure!(
+                header == VersionedHeader::zeroed(),
+                "header was written for data that doesn't fit"
+            );
+            Ok(())
+        })
+    };
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::shared_memory::SharedMemoryExt;
+        small.unlink().context("Unlink failed")?;
+    }
+    result
+}
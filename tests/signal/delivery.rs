@@ -0,0 +1,55 @@
+//! Tests that every receiver gets its own copy of the signals it's subscribed to, and only of those.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::signal::{send, Signal, SignalReceiver},
+    std::{process, time::Duration},
+};
+
+pub(super) fn run() -> TestResult {
+    let first = SignalReceiver::new(&[Signal::User1]).context("Receiver creation failed")?;
+    let second = SignalReceiver::new(&[Signal::User1, Signal::Terminate]).context("Receiver creation failed")?;
+    let unrelated = SignalReceiver::new(&[Signal::Interrupt]).context("Receiver creation failed")?;
+
+    send(process::id(), Signal::User1).context("Send failed")?;
+    for (receiver, which) in [(&first, "first"), (&second, "second")] {
+        ensure!(
+            receiver.recv_timeout(Duration::from_secs(10)) == Some(Signal::User1),
+            "{} receiver did not get the signal",
+            which
+        );
+    }
+    ensure!(
+        unrelated.recv_timeout(Duration::from_millis(50)).is_none(),
+        "receiver got a signal it isn't subscribed to"
+    );
+
+    // A dropped receiver no longer takes part, and the remaining ones still get their copies.
+    drop(first);
+    send(process::id(), Signal::Terminate).context("Send failed")?;
+    ensure!(
+        second.recv_timeout(Duration::from_secs(10)) == Some(Signal::Terminate),
+        "receiver did not get the signal after another one was dropped"
+    );
+    Ok(())
+}
+
+/// Sends a signal to a child process which doesn't receive it, which terminates the child.
+#[cfg(unix)]
+pub(super) fn run_child() -> TestResult {
+    use std::{os::unix::process::ExitStatusExt, process::Command};
+
+    let mut child = Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .context("Spawning sleep failed")?;
+    send(child.id(), Signal::Terminate).context("Send failed")?;
+    let status = child.wait()?;
+    ensure!(
+        status.signal() == Some(libc::SIGTERM),
+        "child exited with {} instead of being terminated",
+        status
+    );
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Signals are process-wide, so every test only sends signals that the others don't assert the absence of.
+
+mod delivery;
+#[cfg(feature = "tokio")]
+mod tokio;
+
+type TestResult = anyhow::Result<()>;
+
+#[test]
+fn signal_delivery() -> TestResult {
+    delivery::run()
+}
+#[cfg(unix)]
+#[test]
+fn signal_child() -> TestResult {
+    delivery::run_child()
+}
+#[cfg(feature = "tokio")]
+#[test]
+fn signal_tokio() -> TestResult {
+    tokio::run()
+}
@@ -0,0 +1,24 @@
+//! Tests receiving signals through the Tokio stream.
+
+use {
+    super::TestResult,
+    anyhow::{Context, Result},
+    interprocess::signal::{send, tokio::SignalStream, Signal},
+    std::{process, time::Duration},
+};
+
+pub(super) fn run() -> TestResult {
+    ::tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Runtime creation failed")?
+        .block_on(async {
+            let mut stream = SignalStream::new(&[Signal::User1]).context("Stream creation failed")?;
+            send(process::id(), Signal::User1).context("Send failed")?;
+            let signal = ::tokio::time::timeout(Duration::from_secs(10), stream.recv())
+                .await
+                .context("Signal was not received")?;
+            anyhow::ensure!(signal == Signal::User1, "received {:?}", signal);
+            Result::<()>::Ok(())
+        })
+}
@@ -0,0 +1,112 @@
+//! Tests waking up threads waiting on a named event, and the timeouts of the waits.
+
+use {
+    super::{unique_name, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::sync::NamedEvent,
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicU32, Ordering::*},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+const WAITERS: u32 = 3;
+
+fn with_event(f: impl FnOnce(&str, &NamedEvent) -> TestResult) -> TestResult {
+    let name = unique_name();
+    let event = NamedEvent::create(&name).context("Creation failed")?;
+    let result = f(&name, &event);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::sync::NamedEventExt;
+        event.unlink().context("Unlink failed")?;
+    }
+    result
+}
+
+pub(super) fn run_timeout() -> TestResult {
+    with_event(|name, event| {
+        match NamedEvent::create(name) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => bail!("creating a taken name failed with an unexpected error: {}", e),
+            Ok(..) => bail!("second event was created under the same name"),
+        }
+        // Notifications sent while nobody is waiting are not remembered.
+        event.notify_all()?;
+        let start = Instant::now();
+        ensure!(
+            !event.wait_until_timeout(|| false, Duration::from_millis(50))?,
+            "wait for a condition that never becomes true succeeded"
+        );
+        ensure!(start.elapsed() >= Duration::from_millis(50), "wait timed out early");
+        ensure!(
+            event.wait_until_timeout(|| true, Duration::ZERO)?,
+            "wait for a condition that's already true failed"
+        );
+        Ok(())
+    })
+}
+
+/// Starts waiters which open the event by name and wait until the counter reaches the given value, incrementing
+/// `woken` once they see it.
+fn spawn_waiters(
+    name: &str,
+    counter: &Arc<AtomicU32>,
+    woken: &Arc<AtomicU32>,
+    target: impl Fn(u32) -> u32,
+) -> Vec<thread::JoinHandle<TestResult>> {
+    (0..WAITERS)
+        .map(|i| {
+            let (name, counter, woken, target) = (name.to_owned(), Arc::clone(counter), Arc::clone(woken), target(i));
+            thread::spawn(move || -> TestResult {
+                let event = NamedEvent::open(name)?;
+                let done = event.wait_until_timeout(|| counter.load(Acquire) >= target, Duration::from_secs(10))?;
+                ensure!(done, "waiter was not woken up");
+                woken.fetch_add(1, Relaxed);
+                Ok(())
+            })
+        })
+        .collect()
+}
+
+pub(super) fn run_notify() -> TestResult {
+    with_event(|name, event| {
+        // All waiters wait for the same change, and a single notification wakes all of them up.
+        let counter = Arc::new(AtomicU32::new(0));
+        let woken = Arc::new(AtomicU32::new(0));
+        let waiters = spawn_waiters(name, &counter, &woken, |_| 1);
+        thread::sleep(Duration::from_millis(50));
+        ensure!(woken.load(Relaxed) == 0, "waiters woke up without a notification");
+        counter.store(1, Release);
+        event.notify_all()?;
+        for waiter in waiters {
+            waiter.join().unwrap()?;
+        }
+
+        // Each waiter waits for a different value, and is woken up by one of the notifications sent one at a time.
+        let counter = Arc::new(AtomicU32::new(0));
+        let woken = Arc::new(AtomicU32::new(0));
+        let waiters = spawn_waiters(name, &counter, &woken, |i| i + 1);
+        thread::sleep(Duration::from_millis(50));
+        for i in 1..=WAITERS {
+            counter.store(i, Release);
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while woken.load(Relaxed) < i {
+                ensure!(Instant::now() < deadline, "notification {} did not wake anyone up", i);
+                // The waiter whose turn it is might not be the one that gets woken up, in which case the others go
+                // back to waiting and another notification is needed.
+                event.notify_one()?;
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+        for waiter in waiters {
+            waiter.join().unwrap()?;
+        }
+        Ok(())
+    })
+}
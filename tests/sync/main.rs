@@ -0,0 +1,45 @@
+#![cfg(not(target_os = "android"))]
+
+mod event;
+mod mutex;
+mod semaphore;
+
+use std::{
+    process,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+type TestResult = anyhow::Result<()>;
+
+/// Generates a name for a synchronization primitive that no other test uses. Kept short, since macOS limits the names
+/// of semaphores to 31 characters.
+fn unique_name() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    format!("ipt-{}-{}", process::id(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+#[test]
+fn named_event_timeout() -> TestResult {
+    event::run_timeout()
+}
+#[test]
+fn named_event_notify() -> TestResult {
+    event::run_notify()
+}
+#[test]
+fn named_mutex_contention() -> TestResult {
+    mutex::run_contention()
+}
+#[cfg(any(target_os = "linux", windows))]
+#[test]
+fn named_mutex_abandonment() -> TestResult {
+    mutex::run_abandonment()
+}
+#[test]
+fn named_semaphore_counting() -> TestResult {
+    semaphore::run_counting()
+}
+#[test]
+fn named_semaphore_contention() -> TestResult {
+    semaphore::run_contention()
+}
@@ -0,0 +1,108 @@
+//! Tests the mutual exclusion of named mutexes between threads under contention, and the reporting of abandonment.
+
+use {
+    super::{unique_name, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::sync::NamedMutex,
+    std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU32, Ordering::*},
+            Arc,
+        },
+        thread,
+    },
+};
+
+fn with_mutex(f: impl FnOnce(&str, &NamedMutex) -> TestResult) -> TestResult {
+    let name = unique_name();
+    let mutex = NamedMutex::create(&name).context("Creation failed")?;
+    let result = f(&name, &mutex);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::sync::NamedMutexExt;
+        mutex.unlink().context("Unlink failed")?;
+    }
+    result
+}
+
+/// Opens the mutex in a new thread, like another process would, and checks whether it could be locked right away.
+fn locked_elsewhere(name: &str) -> anyhow::Result<bool> {
+    let name = name.to_owned();
+    thread::spawn(move || -> anyhow::Result<bool> { Ok(NamedMutex::open(name)?.try_lock()?.is_none()) })
+        .join()
+        .unwrap()
+}
+
+pub(super) fn run_contention() -> TestResult {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 500;
+    with_mutex(|name, mutex| {
+        ensure!(!locked_elsewhere(name)?, "new mutex is locked");
+        let guard = mutex.lock().context("Lock failed")?;
+        ensure!(!guard.was_abandoned(), "new mutex reports abandonment");
+        ensure!(
+            locked_elsewhere(name)?,
+            "locked mutex could be locked by another thread"
+        );
+        drop(guard);
+        ensure!(
+            !locked_elsewhere(name)?,
+            "mutex stayed locked after the guard was dropped"
+        );
+
+        let inside = Arc::new(AtomicBool::new(false));
+        let counter = Arc::new(AtomicU32::new(0));
+        let threads = (0..THREADS)
+            .map(|_| {
+                let (name, inside, counter) = (name.to_owned(), Arc::clone(&inside), Arc::clone(&counter));
+                thread::spawn(move || -> TestResult {
+                    let mutex = NamedMutex::open(name)?;
+                    for _ in 0..ITERATIONS {
+                        let guard = mutex.lock()?;
+                        ensure!(!inside.swap(true, Acquire), "two threads held the mutex at once");
+                        // Not atomic as a whole, so concurrent increments would get lost.
+                        counter.store(counter.load(Relaxed) + 1, Relaxed);
+                        inside.store(false, Release);
+                        drop(guard);
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap()?;
+        }
+        let total = counter.load(Relaxed);
+        ensure!(
+            total == THREADS * ITERATIONS,
+            "{} increments were lost",
+            THREADS * ITERATIONS - total
+        );
+        Ok(())
+    })
+}
+
+/// The owner of a mutex that exits without unlocking it is a thread on the platforms where ownership is tracked per
+/// thread, so the exit of a process can be simulated with that of a thread.
+#[cfg(any(target_os = "linux", windows))]
+pub(super) fn run_abandonment() -> TestResult {
+    with_mutex(|name, mutex| {
+        let name = name.to_owned();
+        thread::spawn(move || -> TestResult {
+            // Leaked together with the guard, since a process which dies holding the mutex doesn't unmap it before
+            // the system notices that, either.
+            let mutex = Box::leak(Box::new(NamedMutex::open(name)?));
+            std::mem::forget(mutex.lock()?);
+            Ok(())
+        })
+        .join()
+        .unwrap()?;
+
+        let guard = mutex.lock().context("Lock of abandoned mutex failed")?;
+        ensure!(guard.was_abandoned(), "abandonment was not reported");
+        drop(guard);
+        let guard = mutex.lock().context("Lock after abandonment failed")?;
+        ensure!(!guard.was_abandoned(), "abandonment was reported twice");
+        Ok(())
+    })
+}
@@ -0,0 +1,111 @@
+//! Tests the counting, timeouts and cross-thread wakeups of named semaphores, as well as using one as a lock under
+//! contention.
+
+use {
+    super::{unique_name, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::sync::NamedSemaphore,
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, AtomicU32, Ordering::*},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+fn with_semaphore(initial_count: u32, f: impl FnOnce(&str, &NamedSemaphore) -> TestResult) -> TestResult {
+    let name = unique_name();
+    let sem = NamedSemaphore::create(&name, initial_count).context("Creation failed")?;
+    let result = f(&name, &sem);
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::sync::NamedSemaphoreExt;
+        sem.unlink().context("Unlink failed")?;
+    }
+    result
+}
+
+pub(super) fn run_counting() -> TestResult {
+    with_semaphore(2, |name, sem| {
+        match NamedSemaphore::create(name, 0) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => bail!("creating a taken name failed with an unexpected error: {}", e),
+            Ok(..) => bail!("second semaphore was created under the same name"),
+        }
+        ensure!(sem.try_wait()? && sem.try_wait()?, "initial count was not taken");
+        ensure!(!sem.try_wait()?, "count went below zero");
+
+        let start = Instant::now();
+        ensure!(
+            !sem.wait_timeout(Duration::from_millis(50))?,
+            "wait on a zero count succeeded"
+        );
+        ensure!(start.elapsed() >= Duration::from_millis(50), "wait timed out early");
+
+        // Posts made through another handle are seen by this one.
+        let other = NamedSemaphore::open(name).context("Open failed")?;
+        for _ in 0..3 {
+            other.post()?;
+        }
+        for _ in 0..3 {
+            ensure!(sem.wait_timeout(Duration::ZERO)?, "posted count was not taken");
+        }
+        ensure!(!sem.try_wait()?, "count went below zero");
+
+        // A blocked waiter is woken up by a post from another thread.
+        let woken = thread::spawn({
+            let name = name.to_owned();
+            move || -> TestResult {
+                let sem = NamedSemaphore::open(name)?;
+                ensure!(sem.wait_timeout(Duration::from_secs(10))?, "waiter was not woken up");
+                Ok(())
+            }
+        });
+        thread::sleep(Duration::from_millis(50));
+        other.post()?;
+        woken.join().unwrap()
+    })
+}
+
+pub(super) fn run_contention() -> TestResult {
+    const THREADS: u32 = 4;
+    const ITERATIONS: u32 = 500;
+    with_semaphore(1, |name, _| {
+        // With an initial count of one, the semaphore lets one thread at a time in.
+        let inside = Arc::new(AtomicBool::new(false));
+        let counter = Arc::new(AtomicU32::new(0));
+        let threads = (0..THREADS)
+            .map(|_| {
+                let (name, inside, counter) = (name.to_owned(), Arc::clone(&inside), Arc::clone(&counter));
+                thread::spawn(move || -> TestResult {
+                    let sem = NamedSemaphore::open(name)?;
+                    for _ in 0..ITERATIONS {
+                        sem.wait()?;
+                        ensure!(
+                            !inside.swap(true, Acquire),
+                            "two threads got past the semaphore at once"
+                        );
+                        // Not atomic as a whole, so concurrent increments would get lost.
+                        counter.store(counter.load(Relaxed) + 1, Relaxed);
+                        inside.store(false, Release);
+                        sem.post()?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap()?;
+        }
+        let total = counter.load(Relaxed);
+        ensure!(
+            total == THREADS * ITERATIONS,
+            "{} increments were lost",
+            THREADS * ITERATIONS - total
+        );
+        Ok(())
+    })
+}
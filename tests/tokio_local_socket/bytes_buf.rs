@@ -0,0 +1,60 @@
+//! Tests receiving messages into `BytesMut` and sending them through the `Bytes` sink of Tokio-based message-mode
+//! local sockets.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    bytes::{Bytes, BytesMut},
+    futures::SinkExt,
+    interprocess::local_socket::tokio::{LocalSocketMsgListener, LocalSocketMsgStream},
+    std::io,
+    tokio::try_join,
+};
+
+const SHORT: &[u8] = b"Short";
+// Bigger than the spare capacity reserved in an empty buffer.
+const LONG: &[u8] = &[b'L'; 4096];
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketMsgListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let conn = listener.accept().await.context("Accept failed")?;
+        let mut buf = BytesMut::new();
+        let size = conn.recv_buf(&mut buf).await.context("First receive failed")?;
+        ensure!(size == SHORT.len(), "first message has {} bytes", size);
+        let first = buf.split().freeze();
+        ensure!(first == SHORT, "first message doesn't match");
+        let size = conn.recv_buf(&mut buf).await.context("Second receive failed")?;
+        ensure!(size == LONG.len(), "second message has {} bytes", size);
+        ensure!(buf == LONG, "second message doesn't match");
+        ensure!(
+            conn.recv_buf(&mut buf).await.context("Final receive failed")? == 0,
+            "received data after the client hung up"
+        );
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketMsgStream::connect(&*name).await.context("Connect failed")?;
+        let mut sink = conn.bytes_sink();
+        sink.feed(Bytes::from_static(SHORT))
+            .await
+            .context("First send failed")?;
+        sink.send(Bytes::from_static(LONG))
+            .await
+            .context("Second send failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Tests that dropping a pending accept, as `select!` does with the branches that lose, doesn't lose any client, and
+//! that a `select!`-based server loop shuts down once the listener is closed.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::local_socket::tokio::{ListenerClosedError, LocalSocketListener, LocalSocketStream},
+    std::{io, sync::Arc, time::Duration},
+    tokio::{select, task, time::sleep},
+};
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let listener = Arc::new(listener);
+
+    select! {
+        conn = listener.accept() => bail!("accepted {:?} without any client", conn.map(drop)),
+        () = sleep(Duration::from_millis(50)) => {}
+    }
+    // The cancelled accept must leave the listener ready for the next client.
+    let client = task::spawn({
+        let name = name.clone();
+        async move { LocalSocketStream::connect(&*name).await.map(drop) }
+    });
+    select! {
+        conn = listener.accept() => { conn.context("Accept after cancellation failed")?; }
+        () = sleep(Duration::from_secs(10)) => bail!("client was lost by the cancelled accept"),
+    }
+    client
+        .await
+        .context("Client task panicked")?
+        .context("Connect failed")?;
+
+    let server = task::spawn({
+        let listener = Arc::clone(&listener);
+        async move {
+            loop {
+                select! {
+                    conn = listener.accept() => match conn {
+                        Ok(..) => {}
+                        Err(e) => return e,
+                    },
+                    () = sleep(Duration::from_millis(5)) => {}
+                }
+            }
+        }
+    });
+    sleep(Duration::from_millis(20)).await;
+    listener.close_and_unlink().context("Listener close failed")?;
+    let err = server.await.context("Server task panicked")?;
+    ensure!(
+        ListenerClosedError::is_in(&err),
+        "expected a 'listener closed' error, received '{}'",
+        err
+    );
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Tests closing a listener while a task is waiting for an incoming connection.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::local_socket::tokio::{ListenerClosedError, LocalSocketListener},
+    std::{future::poll_fn, io, path::Path, sync::Arc, time::Duration},
+    tokio::{task, time::timeout},
+};
+
+pub async fn run_and_verify_error(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let listener = Arc::new(listener);
+
+    let accepting = task::spawn({
+        let listener = Arc::clone(&listener);
+        async move { listener.accept().await.map(drop) }
+    });
+    task::yield_now().await;
+    listener.close_and_unlink().context("Listener close failed")?;
+
+    let err = match accepting.await.context("Accepting task panicked")? {
+        Err(e) => e,
+        Ok(()) => bail!("accept succeeded on a closed listener"),
+    };
+    ensure!(
+        ListenerClosedError::is_in(&err),
+        "expected a 'listener closed' error, received '{}'",
+        err
+    );
+    ensure!(listener.is_closed(), "listener does not report being closed");
+    let err = listener.accept().await.err().context("accept after close succeeded")?;
+    ensure!(
+        ListenerClosedError::is_in(&err),
+        "expected a 'listener closed' error after close, received '{}'",
+        err
+    );
+
+    if !name.starts_with('@') {
+        ensure!(!Path::new(&name).exists(), "socket file was not removed");
+    }
+    Ok(())
+}
+
+/// Checks that closing the listener wakes up a task which is waiting in `poll_accept()` rather than `accept()`.
+pub async fn run_poll_accept(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let listener = Arc::new(listener);
+
+    let accepting = task::spawn({
+        let listener = Arc::clone(&listener);
+        async move { poll_fn(|cx| listener.poll_accept(cx)).await.map(drop) }
+    });
+    // Give the task a chance to start waiting before closing the listener.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    listener.close_and_unlink().context("Listener close failed")?;
+
+    let err = match timeout(Duration::from_secs(5), accepting)
+        .await
+        .context("closing the listener didn't wake up poll_accept()")?
+        .context("Accepting task panicked")?
+    {
+        Err(e) => e,
+        Ok(()) => bail!("poll_accept succeeded on a closed listener"),
+    };
+    ensure!(
+        ListenerClosedError::is_in(&err),
+        "expected a 'listener closed' error, received '{}'",
+        err
+    );
+
+    if !name.starts_with('@') {
+        ensure!(!Path::new(&name).exists(), "socket file was not removed");
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Tests length-delimited framing over a local socket stream, including the enforcement of the maximum frame length.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    bytes::Bytes,
+    futures::{SinkExt, StreamExt},
+    interprocess::{
+        codec::LengthDelimitedExt,
+        local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    },
+    std::io,
+    tokio::try_join,
+};
+
+static MSGS: [&[u8]; 3] = [b"First message", b"", b"Third message, a bit longer than the others"];
+const MAX_FRAME_LENGTH: usize = 16;
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let mut conn = listener
+            .accept()
+            .await
+            .context("Accept failed")?
+            .length_delimited_with_max_frame_length(MAX_FRAME_LENGTH);
+        for msg in &MSGS[..2] {
+            let received = conn.next().await.context("Unexpected EOF")?.context("Receive failed")?;
+            ensure!(received == msg, "received {:?} instead of {:?}", received, msg);
+        }
+        match conn.next().await {
+            Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData => {}
+            els => ensure!(false, "oversized frame was not rejected: {:?}", els),
+        }
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketStream::connect(&*name)
+            .await
+            .context("Connect failed")?
+            .length_delimited();
+        for msg in MSGS {
+            conn.send(Bytes::from_static(msg)).await.context("Send failed")?;
+        }
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! Tests that connecting with a timeout succeeds when a server is there and fails promptly, with the same error as a
+//! plain connect, when there is none.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    std::{
+        io,
+        time::{Duration, Instant},
+    },
+    tokio::try_join,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    try_join!(async { listener.accept().await.context("Accept failed") }, async {
+        LocalSocketStream::connect_with_timeout(&*name, TIMEOUT)
+            .await
+            .context("Connect with timeout failed")
+    },)?;
+    drop(listener);
+
+    let missing = NameGen::new_auto(prefer_namespaced).next().unwrap();
+    let start = Instant::now();
+    let err = LocalSocketStream::connect_with_timeout(&*missing, TIMEOUT)
+        .await
+        .err()
+        .context("client successfully connected to nonexistent server")?;
+    ensure!(
+        matches!(err.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused),
+        "expected error to be 'not found' or 'connection refused', received '{}'",
+        err
+    );
+    ensure!(
+        start.elapsed() < TIMEOUT,
+        "connecting to a nonexistent server waited for the whole timeout"
+    );
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Tests shutting down and dropping the write half of a split stream.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    std::io,
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        try_join,
+    },
+};
+
+static REQUEST: &[u8] = b"Hello from client!";
+static REPLY: &[u8] = b"Hello from server!";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    // A large reply, so that it doesn't fit into the send buffer and is still in flight when the writer is dropped.
+    let big_reply = REPLY.repeat(64 * 1024);
+
+    let server = async {
+        let (mut reader, mut writer) = listener.accept().await.context("Accept failed")?.into_split();
+
+        #[cfg(unix)]
+        {
+            // Only Ud-sockets can signal end-of-file while the other half is still alive.
+            let mut request = Vec::new();
+            reader.read_to_end(&mut request).await.context("Receive failed")?;
+            ensure!(request == REQUEST, "received {:?} instead of {:?}", request, REQUEST);
+        }
+        #[cfg(not(unix))]
+        {
+            let mut request = [0; REQUEST.len()];
+            reader.read_exact(&mut request).await.context("Receive failed")?;
+            ensure!(request == REQUEST, "received {:?} instead of {:?}", request, REQUEST);
+        }
+
+        drop(reader);
+        writer.write_all(&big_reply).await.context("Send failed")?;
+        // Dropping the last half right away must neither lose data nor leave the client hanging.
+        drop(writer);
+        TestResult::Ok(())
+    };
+    let client = async {
+        let (mut reader, mut writer) = LocalSocketStream::connect(&*name)
+            .await
+            .context("Connect failed")?
+            .into_split();
+        writer.write_all(REQUEST).await.context("Send failed")?;
+        writer.shutdown().await.context("Shutdown failed")?;
+        let e = writer
+            .write_all(REQUEST)
+            .await
+            .err()
+            .context("Send succeeded after shutdown")?;
+        ensure!(
+            e.kind() == io::ErrorKind::BrokenPipe,
+            "expected a broken pipe error after shutdown, got {e}"
+        );
+
+        let mut reply = Vec::new();
+        reader.read_to_end(&mut reply).await.context("Receive failed")?;
+        ensure!(
+            reply == big_reply,
+            "received {} bytes of reply instead of {}",
+            reply.len(),
+            big_reply.len()
+        );
+        Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
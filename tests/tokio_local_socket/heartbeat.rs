@@ -0,0 +1,79 @@
+//! Tests the keepalive wrapper over message-mode local sockets, both with a responsive peer and with one which is
+//! connected but never answers.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::{
+        heartbeat::{Heartbeat, HeartbeatOptions},
+        local_socket::tokio::{LocalSocketMsgListener, LocalSocketMsgStream},
+    },
+    std::{
+        io,
+        time::{Duration, Instant},
+    },
+    tokio::{time::sleep, try_join},
+};
+
+const INTERVAL: Duration = Duration::from_millis(50);
+const TIMEOUT: Duration = Duration::from_millis(300);
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketMsgListener::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let options = HeartbeatOptions::new().interval(INTERVAL).timeout(TIMEOUT);
+    ensure!(
+        options.timeout(INTERVAL).build(connect(&name).await?).is_err(),
+        "accepted a timeout no longer than the interval"
+    );
+    listener.accept().await.context("Accept failed")?;
+
+    // A responsive peer is kept alive by pings alone, and messages go through in both directions.
+    let (accepted, connected) = try_join!(
+        async { listener.accept().await.context("Accept failed") },
+        connect(&name)
+    )?;
+    let server = options.build(accepted)?;
+    let client = Heartbeat::new(connected);
+    sleep(TIMEOUT * 2).await;
+    client.send(b"ping?").await.context("Client send failed")?;
+    ensure!(
+        server.recv().await.context("Server receive failed")?.as_deref() == Some(&b"ping?"[..]),
+        "wrong message received by the server"
+    );
+    server.send(b"").await.context("Server send failed")?;
+    ensure!(
+        client.recv().await.context("Client receive failed")?.as_deref() == Some(&[][..]),
+        "wrong message received by the client"
+    );
+    ensure!(server.round_trip_time().is_some(), "no ping was answered");
+    drop(client);
+    ensure!(
+        server.recv().await.context("Receive after close failed")?.is_none(),
+        "received a message after the peer closed the connection"
+    );
+
+    // A peer which stays connected without answering is detected within the timeout.
+    let (accepted, silent) = try_join!(
+        async { listener.accept().await.context("Accept failed") },
+        connect(&name)
+    )?;
+    let server = options.build(accepted)?;
+    let start = Instant::now();
+    let err = server.recv().await.err().context("Received from a silent peer")?;
+    ensure!(err.kind() == io::ErrorKind::TimedOut, "wrong error: {}", err);
+    ensure!(start.elapsed() < TIMEOUT * 5, "silent peer detected too late");
+    ensure!(server.is_closed(), "connection not closed after the timeout");
+    ensure!(server.send(b"late").await.is_err(), "sent to a dead peer");
+    drop(silent);
+    Ok(())
+}
+
+async fn connect(name: &str) -> anyhow::Result<LocalSocketMsgStream> {
+    LocalSocketMsgStream::connect(name).await.context("Connect failed")
+}
@@ -0,0 +1,67 @@
+//! Tests serving and consuming an HTTP API over a local socket with Hyper.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Client, Request, Response, Server,
+    },
+    interprocess::{hyper::LocalSocketConnector, local_socket::tokio::LocalSocketListener},
+    std::{convert::Infallible, io},
+    tokio::sync::oneshot,
+};
+
+static PATHS: [&str; 3] = ["/version", "/containers/json", "/"];
+
+async fn handle(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    Ok(Response::new(Body::from(format!("You asked for {}", req.uri().path()))))
+}
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(handle)) });
+    let server = tokio::spawn(
+        Server::builder(listener)
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            }),
+    );
+
+    let client =
+        Client::builder().build::<_, Body>(LocalSocketConnector::new(&*name).context("Connector creation failed")?);
+    for path in PATHS {
+        let uri = format!("http://localhost{path}")
+            .parse()
+            .context("URI parsing failed")?;
+        let response = client.get(uri).await.context("Request failed")?;
+        ensure!(
+            response.status().is_success(),
+            "request failed with {}",
+            response.status()
+        );
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context("Body receive failed")?;
+        let expected = format!("You asked for {path}");
+        ensure!(body == expected, "received {:?} instead of {:?}", body, expected);
+    }
+    drop(client);
+
+    shutdown_tx.send(()).ok();
+    server.await.context("Server panicked")?.context("Server failed")?;
+    Ok(())
+}
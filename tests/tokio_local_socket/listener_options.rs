@@ -0,0 +1,72 @@
+//! Tests creating a Tokio listener from the options builder, including the rejection of options it doesn't support.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, Context},
+    interprocess::local_socket::{tokio::LocalSocketStream, LocalSocketListenerOptions},
+    std::io,
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        try_join,
+    },
+};
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let options = LocalSocketListenerOptions::new().backlog(4);
+    #[cfg(unix)]
+    let options = {
+        use interprocess::os::unix::LocalSocketListenerOptionsExt;
+        options.mode(0o600).unlink_on_drop(true)
+    };
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match options.bind_tokio(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let mut conn = listener.accept().await.context("Accept failed")?;
+        let mut buf = [0; 1];
+        conn.read_exact(&mut buf).await.context("Receive failed")?;
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketStream::connect(&*name).await.context("Connect failed")?;
+        conn.write_all(b"\n").await.context("Send failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+
+    #[cfg(unix)]
+    if !name.starts_with('@') {
+        use {
+            anyhow::ensure,
+            std::{fs, os::unix::fs::PermissionsExt, path::Path},
+        };
+        let mode = fs::metadata(&name).context("Stat failed")?.permissions().mode();
+        ensure!(
+            mode & 0o777 == 0o600,
+            "socket file has mode {:o} instead of 600",
+            mode & 0o777
+        );
+        drop(listener);
+        ensure!(!Path::new(&name).exists(), "socket file wasn't deleted on drop");
+    }
+
+    // The name is never bound, since the check happens first.
+    match LocalSocketListenerOptions::new()
+        .same_user_only(true)
+        .bind_tokio(&*name)
+    {
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => {}
+        Err(e) => bail!("expected Unsupported, got a different error: {}", e),
+        Ok(..) => bail!("bound a listener with an unsupported option"),
+    }
+    Ok(())
+}
@@ -3,8 +3,42 @@
 mod util;
 use util::TestResult;
 
+// SOCK_SEQPACKET Ud-sockets are unsupported on Apple platforms
+#[cfg(all(feature = "bytes", not(any(target_os = "macos", target_os = "ios"))))]
+mod bytes_buf;
+mod cancel;
+mod close;
+#[cfg(feature = "codec")]
+mod codec;
+mod connect_timeout;
+mod half_shutdown;
+// SOCK_SEQPACKET Ud-sockets are unsupported on Apple platforms
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod heartbeat;
+#[cfg(feature = "hyper")]
+mod hyper;
+mod listener_options;
+// SOCK_SEQPACKET Ud-sockets are unsupported on Apple platforms
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod msg_adapters;
+// SOCK_SEQPACKET Ud-sockets are unsupported on Apple platforms
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+mod msg_stream;
+mod mux;
 mod no_server;
+mod peer_credentials;
+mod poll_accept;
+mod pubsub;
+mod readiness;
+mod reunite;
+#[cfg(feature = "rpc")]
+mod rpc;
 mod stream;
+#[cfg(unix)]
+mod sync_conversion;
+mod tokio_io;
+#[cfg(feature = "typed")]
+mod typed;
 
 use {interprocess::local_socket::NameTypeSupport, tokio::try_join};
 
@@ -35,3 +69,276 @@ async fn tokio_local_socket_no_server() -> TestResult {
     }
     Ok(())
 }
+#[tokio::test]
+async fn tokio_local_socket_close() -> TestResult {
+    // Same as above.
+    let f1 = close::run_and_verify_error(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = close::run_and_verify_error(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_close_poll_accept() -> TestResult {
+    // Same as above.
+    let f1 = close::run_poll_accept(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = close::run_poll_accept(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_cancel() -> TestResult {
+    // Same as above.
+    let f1 = cancel::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = cancel::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_reunite() -> TestResult {
+    // Same as above.
+    let f1 = reunite::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = reunite::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_connect_timeout() -> TestResult {
+    // Same as above.
+    let f1 = connect_timeout::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = connect_timeout::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_peer_credentials() -> TestResult {
+    // Same as above.
+    let f1 = peer_credentials::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = peer_credentials::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_readiness() -> TestResult {
+    // Same as above.
+    let f1 = readiness::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = readiness::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_tokio_io() -> TestResult {
+    // Same as above.
+    let f1 = tokio_io::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = tokio_io::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(feature = "codec")]
+#[tokio::test]
+async fn tokio_local_socket_codec() -> TestResult {
+    // Same as above.
+    let f1 = codec::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = codec::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[tokio::test]
+async fn tokio_local_socket_msg_stream() -> TestResult {
+    // Same as above.
+    let f1 = msg_stream::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = msg_stream::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(all(feature = "bytes", not(any(target_os = "macos", target_os = "ios"))))]
+#[tokio::test]
+async fn tokio_local_socket_bytes_buf() -> TestResult {
+    // Same as above.
+    let f1 = bytes_buf::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = bytes_buf::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[tokio::test]
+async fn tokio_local_socket_msg_adapters() -> TestResult {
+    // Same as above.
+    let f1 = msg_adapters::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = msg_adapters::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+#[tokio::test]
+async fn tokio_local_socket_heartbeat() -> TestResult {
+    // Same as above.
+    let f1 = heartbeat::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = heartbeat::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(feature = "typed")]
+#[tokio::test]
+async fn tokio_local_socket_typed() -> TestResult {
+    // Same as above.
+    let f1 = typed::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = typed::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_poll_accept() -> TestResult {
+    // Same as above.
+    let f1 = poll_accept::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = poll_accept::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(unix)]
+#[tokio::test]
+async fn tokio_local_socket_sync_conversion() -> TestResult {
+    // Same as above.
+    let f1 = sync_conversion::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = sync_conversion::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(feature = "hyper")]
+#[tokio::test]
+async fn tokio_local_socket_hyper() -> TestResult {
+    // Same as above.
+    let f1 = hyper::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = hyper::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_pubsub() -> TestResult {
+    // Same as above.
+    let f1 = pubsub::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = pubsub::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_mux() -> TestResult {
+    // Same as above.
+    let f1 = mux::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = mux::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[cfg(feature = "rpc")]
+#[tokio::test]
+async fn tokio_local_socket_rpc() -> TestResult {
+    // Same as above.
+    let f1 = rpc::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = rpc::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_listener_options() -> TestResult {
+    // Same as above.
+    let f1 = listener_options::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = listener_options::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_local_socket_half_shutdown() -> TestResult {
+    // Same as above.
+    let f1 = half_shutdown::run(false);
+    if NameTypeSupport::query() == NameTypeSupport::Both {
+        let f2 = half_shutdown::run(true);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
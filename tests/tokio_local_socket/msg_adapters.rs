@@ -0,0 +1,72 @@
+//! Tests the generic `Stream` and `Sink` adapters over Tokio-based message-mode local sockets, used through shared
+//! references so that both directions are driven concurrently.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::{SinkExt, StreamExt},
+    interprocess::{
+        local_socket::tokio::{LocalSocketMsgListener, LocalSocketMsgStream},
+        msg_adapters::{MsgSinkExt, MsgStreamExt},
+    },
+    std::io,
+    tokio::try_join,
+};
+
+const SHORT: &[u8] = b"Short";
+// Bigger than the buffer that messages are first received into.
+const LONG: &[u8] = &[b'L'; 4096];
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketMsgListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let conn = listener.accept().await.context("Accept failed")?;
+        let mut stream = (&conn).msg_stream();
+        let mut sink = (&conn).msg_sink();
+        let first = stream
+            .next()
+            .await
+            .context("stream ended early")?
+            .context("First receive failed")?;
+        ensure!(first == SHORT, "first message doesn't match");
+        sink.send(LONG.to_vec()).await.context("Reply failed")?;
+        let second = stream
+            .next()
+            .await
+            .context("stream ended early")?
+            .context("Second receive failed")?;
+        ensure!(second == LONG, "second message doesn't match");
+        ensure!(
+            stream.next().await.is_none(),
+            "stream didn't end after the client hung up"
+        );
+        TestResult::Ok(())
+    };
+    let client = async {
+        let conn = LocalSocketMsgStream::connect(&*name).await.context("Connect failed")?;
+        let mut sink = (&conn).msg_sink();
+        let mut stream = (&conn).msg_stream();
+        sink.send(SHORT.to_vec()).await.context("First send failed")?;
+        let reply = stream
+            .next()
+            .await
+            .context("stream ended early")?
+            .context("Receive failed")?;
+        ensure!(reply == LONG, "reply doesn't match");
+        sink.send(LONG.to_vec()).await.context("Second send failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
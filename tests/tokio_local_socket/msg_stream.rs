@@ -0,0 +1,72 @@
+//! Tests message boundary preservation by Tokio-based message-mode local sockets, both via the inherent methods and via
+//! the `Stream` and `Sink` implementations.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::{SinkExt, StreamExt},
+    interprocess::local_socket::tokio::{LocalSocketMsgListener, LocalSocketMsgStream},
+    std::io,
+    tokio::try_join,
+};
+
+const SHORT: &[u8] = b"Short";
+// Bigger than the buffer that messages are first received into.
+const LONG: &[u8] = &[b'L'; 4096];
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketMsgListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let mut conn = listener.accept().await.context("Accept failed")?;
+        let first = conn.recv().await.context("First receive failed")?;
+        ensure!(first == SHORT, "first message doesn't match");
+        let second = conn.recv().await.context("Second receive failed")?;
+        ensure!(second == LONG, "second message doesn't match");
+        conn.send(SHORT).await.context("Reply failed")?;
+
+        let third = conn
+            .next()
+            .await
+            .context("stream ended early")?
+            .context("Third receive failed")?;
+        ensure!(third == LONG, "third message doesn't match");
+        let fourth = conn
+            .next()
+            .await
+            .context("stream ended early")?
+            .context("Fourth receive failed")?;
+        ensure!(fourth == SHORT, "fourth message doesn't match");
+        ensure!(
+            conn.next().await.is_none(),
+            "stream didn't end after the client hung up"
+        );
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketMsgStream::connect(&*name).await.context("Connect failed")?;
+        // Sent back to back, so a byte stream would have merged them
+        conn.send(SHORT).await.context("First send failed")?;
+        conn.send(LONG).await.context("Second send failed")?;
+        let reply = conn.recv().await.context("Receive failed")?;
+        ensure!(reply == SHORT, "reply doesn't match");
+
+        conn.feed(LONG.to_vec()).await.context("Third send failed")?;
+        SinkExt::send(&mut conn, SHORT.to_vec())
+            .await
+            .context("Fourth send failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
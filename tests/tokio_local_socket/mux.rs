@@ -0,0 +1,102 @@
+//! Tests channels multiplexed over one local socket connection, including flow control, the limit on open channels,
+//! resets and the end of the connection.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::{
+        local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+        mux::{Mux, MuxOptions, Side},
+    },
+    std::{io, time::Duration},
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        time::timeout,
+        try_join,
+    },
+};
+
+/// More than the default receive window, so that the writer has to wait for the reader.
+const BULK_LEN: usize = 1024 * 1024;
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match LocalSocketListener::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let (accepted, connected) = try_join!(async { listener.accept().await.context("Accept failed") }, async {
+        LocalSocketStream::connect(&*name).await.context("Connect failed")
+    })?;
+    let server = MuxOptions::new()
+        .max_channels(2)
+        .build(accepted, Side::Server)
+        .context("Server creation failed")?;
+    let client = Mux::new(connected, Side::Client);
+
+    let mut bulk = client.open_channel().await.context("Open failed")?;
+    let mut control = client.open_channel().await.context("Open failed")?;
+    let err = client
+        .open_channel()
+        .await
+        .err()
+        .context("Opened a channel over the limit")?;
+    ensure!(err.kind() == io::ErrorKind::ConnectionRefused, "wrong error: {}", err);
+    let mut bulk_accepted = server.accept_channel().await?.context("No channel accepted")?;
+    let mut control_accepted = server.accept_channel().await?.context("No channel accepted")?;
+    ensure!(
+        (bulk_accepted.id(), control_accepted.id()) == (bulk.id(), control.id()),
+        "channels accepted out of order"
+    );
+
+    // The bulk transfer stalls while nobody reads it, without holding up the control channel.
+    let data = (0..BULK_LEN).map(|i| i as u8).collect::<Vec<_>>();
+    let mut write_bulk = Box::pin(async {
+        bulk.write_all(&data).await?;
+        bulk.shutdown().await
+    });
+    ensure!(
+        timeout(Duration::from_millis(100), &mut write_bulk).await.is_err(),
+        "wrote more than the flow control window allows"
+    );
+    control.write_all(b"ping").await.context("Control send failed")?;
+    let mut buf = [0; 4];
+    control_accepted
+        .read_exact(&mut buf)
+        .await
+        .context("Control receive failed")?;
+    ensure!(&buf == b"ping", "wrong control message");
+
+    let mut received = Vec::new();
+    try_join!(async { write_bulk.await.context("Bulk send failed") }, async {
+        bulk_accepted
+            .read_to_end(&mut received)
+            .await
+            .context("Bulk receive failed")
+    })?;
+    ensure!(received == data, "bulk data corrupted");
+
+    // Dropping a channel before the peer has finished sending on it resets the channel.
+    drop(control_accepted);
+    let err = control
+        .read(&mut buf)
+        .await
+        .err()
+        .context("Read from a reset channel")?;
+    ensure!(err.kind() == io::ErrorKind::ConnectionReset, "wrong error: {}", err);
+    ensure!(control.write_all(b"x").await.is_err(), "wrote into a reset channel");
+
+    // The connection is closed once the server drops everything.
+    drop((server, bulk_accepted));
+    ensure!(
+        client.accept_channel().await.context("Accept failed")?.is_none(),
+        "accepted a channel from a closed connection"
+    );
+    ensure!(
+        client.open_channel().await.is_err(),
+        "opened a channel on a closed connection"
+    );
+    Ok(())
+}
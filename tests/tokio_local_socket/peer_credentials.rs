@@ -0,0 +1,68 @@
+//! Tests that both ends of a Tokio-based local socket connection see the credentials, identity and process ID of the
+//! current process.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{bail, ensure, Context},
+    interprocess::local_socket::{
+        tokio::{LocalSocketListener, LocalSocketStream},
+        LocalSocketPeerCredentials, LocalSocketPeerIdentity,
+    },
+    std::{io, process},
+    tokio::try_join,
+};
+
+fn verify(conn: &LocalSocketStream) -> TestResult {
+    // Not reported by the OS there.
+    if !cfg!(any(target_os = "macos", target_os = "ios")) {
+        let pid = conn.peer_pid().context("Peer PID query failed")?;
+        ensure!(pid == process::id(), "peer PID {} is not the PID of this process", pid);
+    }
+
+    let creds = conn.peer_credentials().context("Peer credentials query failed")?;
+    if let Some(pid) = creds.pid() {
+        ensure!(pid == process::id(), "peer PID {} is not the PID of this process", pid);
+    }
+    match creds {
+        #[cfg(unix)]
+        LocalSocketPeerCredentials::Unix { uid, gid, .. } => {
+            ensure!(uid == unsafe { libc::geteuid() }, "peer UID doesn't match");
+            ensure!(gid == unsafe { libc::getegid() }, "peer GID doesn't match");
+        }
+        #[cfg(windows)]
+        LocalSocketPeerCredentials::Windows { sid, .. } => {
+            ensure!(sid.starts_with("S-1-"), "malformed peer SID {:?}", sid);
+        }
+        other => bail!("credentials of the wrong platform: {:?}", other),
+    }
+
+    let identity = conn.peer_identity().context("Peer identity query failed")?;
+    let own = LocalSocketPeerIdentity::of_current_process().context("Own identity query failed")?;
+    ensure!(
+        identity == own,
+        "peer identity {:?} is not the identity of this process {:?}",
+        identity,
+        own
+    );
+    Ok(())
+}
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let (server, client) = try_join!(async { listener.accept().await.context("Accept failed") }, async {
+        LocalSocketStream::connect(&*name).await.context("Connect failed")
+    },)?;
+    verify(&server).context("Server-side verification failed")?;
+    verify(&client).context("Client-side verification failed")
+}
@@ -0,0 +1,60 @@
+//! Tests accepting connections through the raw polling interface, as done by hand-written streams of clients.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::{stream, StreamExt},
+    interprocess::local_socket::{
+        tokio::{LocalSocketListener, LocalSocketStream},
+        ListenerClosedError,
+    },
+    std::io,
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        try_join,
+    },
+};
+
+const NUM_CLIENTS: u8 = 3;
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let mut incoming = stream::poll_fn(|cx| listener.poll_accept(cx).map(Some));
+        for _ in 0..NUM_CLIENTS {
+            let mut conn = incoming.next().await.unwrap().context("Accept failed")?;
+            let mut id = [0];
+            conn.read_exact(&mut id).await.context("Receive failed")?;
+            conn.write_all(&id).await.context("Send failed")?;
+        }
+        listener.close();
+        match incoming.next().await.unwrap() {
+            Err(e) if ListenerClosedError::is_in(&e) => {}
+            els => ensure!(false, "closed listener accepted a client: {:?}", els.map(|_| ())),
+        }
+        TestResult::Ok(())
+    };
+    let client = async {
+        for id in 0..NUM_CLIENTS {
+            let mut conn = LocalSocketStream::connect(&*name).await.context("Connect failed")?;
+            conn.write_all(&[id]).await.context("Send failed")?;
+            let mut echo = [0];
+            conn.read_exact(&mut echo).await.context("Receive failed")?;
+            ensure!(echo == [id], "received {:?} instead of {:?}", echo, [id]);
+        }
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Tests the Tokio publish/subscribe clients against a broker running on a thread of its own.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::pubsub::{
+        tokio::{Publisher, Subscriber},
+        Broker,
+    },
+    std::{io, sync::Arc, thread},
+};
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, broker) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| match Broker::bind(nm.as_str()) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|b| (nm, b))),
+        })
+        .unwrap()
+        .context("Broker bind failed")?;
+    let broker = Arc::new(broker);
+    let broker_thread = thread::spawn({
+        let broker = Arc::clone(&broker);
+        move || broker.run()
+    });
+
+    let mut subscriber = Subscriber::connect(name.as_str())
+        .await
+        .context("Subscriber connect failed")?;
+    subscriber.subscribe("events").await.context("Subscribe failed")?;
+
+    let mut publisher = Publisher::connect(name.as_str())
+        .await
+        .context("Publisher connect failed")?;
+    publisher.publish("other", b"ignored").await.context("Publish failed")?;
+    publisher.publish("events", b"hello").await.context("Publish failed")?;
+
+    let message = subscriber
+        .recv()
+        .await
+        .context("Receive failed")?
+        .context("Unexpected EOF")?;
+    ensure!(
+        message.topic == "events" && message.payload == b"hello",
+        "received {:?}",
+        message
+    );
+
+    broker.listener().close();
+    broker_thread.join().unwrap().context("Broker failed")?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Tests manual I/O loops built on readiness and `try_read()`/`try_write()`.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    std::io,
+    tokio::try_join,
+};
+
+static MSG: &[u8] = b"Ready when you are!\n";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let (server, client) = try_join!(listener.accept(), LocalSocketStream::connect(&*name))?;
+
+    let mut buf = [0; MSG.len()];
+    match server.try_read(&mut buf) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => ensure!(false, "try_read() on an empty stream returned {:?}", els),
+    }
+
+    let write = async {
+        let mut written = 0;
+        while written < MSG.len() {
+            client.writable().await?;
+            match client.try_write(&MSG[written..]) {
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        io::Result::Ok(())
+    };
+    let read = async {
+        let mut read = 0;
+        while read < buf.len() {
+            server.readable().await?;
+            match server.try_read(&mut buf[read..]) {
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        io::Result::Ok(())
+    };
+    try_join!(write, read).context("Manual I/O failed")?;
+    ensure!(buf == MSG, "received {:?} instead of {:?}", buf, MSG);
+    Ok(())
+}
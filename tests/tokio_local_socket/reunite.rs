@@ -0,0 +1,55 @@
+//! Tests that owned halves of a stream can be put back together, and that halves of different streams are handed back
+//! intact when an attempt is made to reunite them.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::io::{AsyncReadExt, AsyncWriteExt},
+    interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    std::io,
+    tokio::try_join,
+};
+
+static MSG: &[u8] = b"Reunited!\n";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let (mut server1, client1) = try_join!(listener.accept(), LocalSocketStream::connect(&*name))?;
+    let (mut server2, client2) = try_join!(listener.accept(), LocalSocketStream::connect(&*name))?;
+
+    let (r1, w1) = client1.into_split();
+    let (r2, w2) = client2.into_split();
+
+    let err = r1
+        .reunite(w2)
+        .err()
+        .context("halves of different streams were reunited")?;
+    let (r1, w2) = (err.read_half, err.write_half);
+    let err = w1
+        .reunite(r2)
+        .err()
+        .context("halves of different streams were reunited")?;
+    let (w1, r2) = (err.write_half, err.read_half);
+
+    let mut client1 = r1.reunite(w1).context("Reuniting the first stream failed")?;
+    let mut client2 = w2.reunite(r2).context("Reuniting the second stream failed")?;
+
+    for (client, server) in [(&mut client1, &mut server1), (&mut client2, &mut server2)] {
+        client.write_all(MSG).await.context("Send failed")?;
+        let mut buf = [0; MSG.len()];
+        server.read_exact(&mut buf).await.context("Receive failed")?;
+        ensure!(buf == MSG, "received {:?} instead of {:?}", buf, MSG);
+    }
+    Ok(())
+}
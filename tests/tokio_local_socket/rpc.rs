@@ -0,0 +1,91 @@
+//! Tests concurrent calls over one RPC connection, including the concurrency limit of the server, panicking handlers
+//! and stopping the server by closing its listener.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    futures::future::try_join_all,
+    interprocess::rpc::{RpcClient, RpcServer},
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    },
+    tokio::try_join,
+};
+
+const MAX_CONCURRENT_REQUESTS: usize = 2;
+const NUM_CALLS: u64 = 16;
+/// Requests with this value make the handler panic.
+const PANIC_REQUEST: u64 = 13;
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, server) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let s = match RpcServer::<u64, u64>::bind(&*nm) {
+                Ok(s) => s,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, s)))
+        })
+        .unwrap()
+        .context("Server bind failed")?;
+    let server = server.max_concurrent_requests(MAX_CONCURRENT_REQUESTS);
+
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let handler = {
+        let (running, max_running) = (Arc::clone(&running), Arc::clone(&max_running));
+        move |request: u64| {
+            let (running, max_running) = (Arc::clone(&running), Arc::clone(&max_running));
+            async move {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                // Later requests finish sooner, so that the responses arrive out of order.
+                tokio::time::sleep(Duration::from_millis(5 * (NUM_CALLS - request % NUM_CALLS))).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                assert_ne!(request, PANIC_REQUEST, "the handler was asked to panic");
+                request * 2
+            }
+        }
+    };
+
+    let serve = async { server.serve(handler).await.context("Serving failed") };
+    let client = async {
+        let client = RpcClient::<u64, u64>::connect(&*name).await.context("Connect failed")?;
+        let calls = (0..NUM_CALLS).filter(|&i| i != PANIC_REQUEST).map(|i| {
+            let client = client.clone();
+            async move {
+                let response = client.call(&i).await.context("Call failed")?;
+                ensure!(response == i * 2, "received {} instead of {}", response, i * 2);
+                TestResult::Ok(())
+            }
+        });
+        try_join_all(calls).await?;
+        let max_running = max_running.load(Ordering::SeqCst);
+        ensure!(
+            max_running <= MAX_CONCURRENT_REQUESTS,
+            "{} requests were handled at the same time, more than the limit of {}",
+            max_running,
+            MAX_CONCURRENT_REQUESTS
+        );
+
+        let e = client
+            .call(&PANIC_REQUEST)
+            .await
+            .err()
+            .context("Call succeeded even though the handler panicked")?;
+        ensure!(e.kind() == io::ErrorKind::Other, "unexpected error: {}", e);
+        let response = client.call(&1).await.context("Call after panic failed")?;
+        ensure!(response == 2, "received {} instead of 2", response);
+
+        server.listener().close_and_unlink().context("Listener close failed")?;
+        TestResult::Ok(())
+    };
+    try_join!(serve, client)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Tests handing an established connection over from a blocking component to an asynchronous one and back.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::local_socket::{
+        tokio::{LocalSocketListener, LocalSocketStream as TokioLocalSocketStream},
+        LocalSocketStream,
+    },
+    std::io::{self, prelude::*},
+    tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        task, try_join,
+    },
+};
+
+static MSGS: [&[u8; 5]; 4] = [b"sync1", b"tok1!", b"tok2!", b"sync2"];
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let mut conn = listener.accept().await.context("Accept failed")?;
+        let mut buf = [0; 5];
+        for pair in MSGS.chunks(2) {
+            conn.read_exact(&mut buf).await.context("Receive failed")?;
+            ensure!(&buf == pair[0], "received {:?} instead of {:?}", buf, pair[0]);
+            conn.write_all(pair[1]).await.context("Send failed")?;
+        }
+        TestResult::Ok(())
+    };
+    let client = async {
+        // Connecting to a Ud-socket doesn't wait for the server to accept, so this doesn't block the runtime.
+        let mut conn = LocalSocketStream::connect(&*name).context("Connect failed")?;
+        conn.write_all(MSGS[0]).context("Synchronous send failed")?;
+
+        let mut conn: TokioLocalSocketStream = conn.into_tokio().context("Conversion to Tokio failed")?;
+        let mut buf = [0; 5];
+        conn.read_exact(&mut buf).await.context("Asynchronous receive failed")?;
+        ensure!(&buf == MSGS[1], "received {:?} instead of {:?}", buf, MSGS[1]);
+        conn.write_all(MSGS[2]).await.context("Asynchronous send failed")?;
+
+        let mut conn = conn.into_sync().context("Conversion from Tokio failed")?;
+        task::spawn_blocking(move || {
+            // Would fail with `WouldBlock` if nonblocking mode was left enabled.
+            conn.read_exact(&mut buf).context("Synchronous receive failed")?;
+            ensure!(&buf == MSGS[3], "received {:?} instead of {:?}", buf, MSGS[3]);
+            Ok(())
+        })
+        .await
+        .context("Blocking task panicked")?
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! Tests that the streams and their halves work with Tokio's own I/O traits, without a compatibility adapter.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+    std::io,
+    tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        try_join,
+    },
+};
+
+static CLIENT_LINE: &[u8] = b"Hello from client!\n";
+static SERVER_LINE: &[u8] = b"Hello from server!\n";
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let conn = listener.accept().await.context("Accept failed")?;
+        let (reader, mut writer) = conn.into_split();
+        let mut buffer = Vec::with_capacity(128);
+        BufReader::new(reader)
+            .read_until(b'\n', &mut buffer)
+            .await
+            .context("Socket receive failed")?;
+        ensure!(buffer == CLIENT_LINE, "received line doesn't match");
+        writer.write_all(SERVER_LINE).await.context("Socket send failed")?;
+        writer.flush().await.context("Socket flush failed")?;
+        TestResult::Ok(())
+    };
+    let client = async {
+        let mut conn = LocalSocketStream::connect(&*name).await.context("Connect failed")?;
+        conn.write_all(CLIENT_LINE).await.context("Socket send failed")?;
+        let mut buffer = [0; SERVER_LINE.len()];
+        conn.read_exact(&mut buffer).await.context("Socket receive failed")?;
+        ensure!(buffer == SERVER_LINE, "received line doesn't match");
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//! Tests a conversation over a pair of typed channels, one in each direction, on the halves of a local socket stream.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::{
+        local_socket::tokio::{LocalSocketListener, LocalSocketStream},
+        typed::tokio::{TypedReceiver, TypedSender},
+    },
+    std::io,
+    tokio::try_join,
+};
+
+static REQUESTS: [&[u64]; 3] = [&[1, 2, 3], &[], &[u64::MAX / 2, 7]];
+
+pub async fn run(prefer_namespaced: bool) -> TestResult {
+    let (name, listener) = NameGen::new_auto(prefer_namespaced)
+        .find_map(|nm| {
+            let l = match LocalSocketListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let server = async {
+        let (reader, writer) = listener.accept().await.context("Accept failed")?.into_split();
+        let mut receiver = TypedReceiver::<Vec<u64>, _>::new(reader)
+            .await
+            .context("Header receive failed")?;
+        let mut sender = TypedSender::<u64, _>::new(writer).await.context("Header send failed")?;
+        while let Some(request) = receiver.recv().await.context("Receive failed")? {
+            sender.send(&request.iter().sum()).await.context("Send failed")?;
+        }
+        TestResult::Ok(())
+    };
+    let client = async {
+        let (reader, writer) = LocalSocketStream::connect(&*name)
+            .await
+            .context("Connect failed")?
+            .into_split();
+        let mut sender = TypedSender::<[u64], _>::new(writer)
+            .await
+            .context("Header send failed")?;
+        let mut receiver = TypedReceiver::<u64, _>::new(reader)
+            .await
+            .context("Header receive failed")?;
+        for request in REQUESTS {
+            sender.send(request).await.context("Send failed")?;
+            let sum = receiver
+                .recv()
+                .await
+                .context("Receive failed")?
+                .context("Unexpected EOF")?;
+            let expected = request.iter().sum::<u64>();
+            ensure!(sum == expected, "received {} instead of {}", sum, expected);
+        }
+        TestResult::Ok(())
+    };
+    try_join!(server, client)?;
+    Ok(())
+}
@@ -5,6 +5,7 @@ mod util;
 mod bytes;
 mod bytes_unidir_client_to_server;
 mod bytes_unidir_server_to_client;
+mod metadata;
 mod msg;
 mod msg_unidir_client_to_server;
 mod msg_unidir_server_to_client;
@@ -54,3 +55,8 @@ async fn tokio_named_pipe_msg_unidir_server_to_client() -> TestResult {
     )
     .await
 }
+
+#[tokio::test]
+async fn tokio_named_pipe_metadata() -> TestResult {
+    metadata::run().await
+}
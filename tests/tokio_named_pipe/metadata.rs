@@ -0,0 +1,72 @@
+//! Tests that the connection metadata of Tokio named pipe streams agrees between both sides and their split halves.
+
+use {
+    super::util::{NameGen, TestResult},
+    anyhow::{ensure, Context},
+    interprocess::os::windows::named_pipe::{
+        pipe_mode,
+        tokio::{DuplexPipeStream, PipeListenerOptionsExt},
+        PipeListenerOptions,
+    },
+    std::{ffi::OsStr, io, process},
+    tokio::try_join,
+};
+
+pub async fn run() -> TestResult {
+    let (name, listener) = NameGen::new(true)
+        .find_map(|nm| {
+            let rnm: &OsStr = nm.as_ref();
+            let l = match PipeListenerOptions::new()
+                .name(rnm)
+                .create_tokio_duplex::<pipe_mode::Bytes>()
+            {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    let accept = async { listener.accept().await.context("Accept failed") };
+    let connect = async {
+        DuplexPipeStream::<pipe_mode::Bytes>::connect(name.as_str())
+            .await
+            .context("Connect failed")
+    };
+    let (server, client) = try_join!(accept, connect)?;
+
+    let pid = process::id();
+    for conn in [&server, &client] {
+        ensure!(conn.client_process_id()? == pid, "wrong client PID");
+        ensure!(conn.server_process_id()? == pid, "wrong server PID");
+        ensure!(
+            conn.client_session_id()? == conn.server_session_id()?,
+            "session IDs differ"
+        );
+        ensure!(conn.client_user_sid()? == conn.server_user_sid()?, "user SIDs differ");
+    }
+    ensure!(server.client_user_sid()?.starts_with("S-1-"), "malformed SID");
+    ensure!(
+        server.client_computer_name()?.is_none(),
+        "local client reported as being on another computer"
+    );
+
+    let sid = server.client_user_sid()?;
+    let (recv_half, send_half) = server.split();
+    ensure!(
+        recv_half.client_user_sid()? == sid,
+        "receive half reports a different SID"
+    );
+    ensure!(send_half.server_user_sid()? == sid, "send half reports a different SID");
+    ensure!(
+        recv_half.client_computer_name()?.is_none(),
+        "receive half reports a remote client"
+    );
+    ensure!(
+        send_half.client_computer_name()?.is_none(),
+        "send half reports a remote client"
+    );
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Tests the `async-io`-based Ud-sockets: byte streams with file descriptor passing, closing listeners, and datagrams.
+
+use {
+    super::util::*,
+    anyhow::{bail, ensure, Context},
+    futures::{
+        executor::block_on,
+        future::join,
+        io::{AsyncReadExt, AsyncWriteExt},
+    },
+    interprocess::os::unix::udsocket::{
+        async_io::{ListenerClosedError, UdSocket, UdStream, UdStreamListener},
+        UdStream as SyncUdStream,
+    },
+    std::{
+        io::{self, prelude::*},
+        os::unix::{io::AsFd, net::UnixStream},
+        thread,
+    },
+};
+
+const CLIENT_MSG: &[u8] = b"Hello from client!";
+const SERVER_MSG: &[u8] = b"Hello from server!";
+const PAYLOAD: &[u8] = b"Received through the passed descriptor";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = namegen
+        .find_map(|nm| match UdStreamListener::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let client = thread::spawn(move || block_on(client(&name)));
+    block_on(server(&listener))?;
+    client.join().map_err(|_| anyhow::anyhow!("client panicked"))??;
+
+    block_on(close(&listener))?;
+    block_on(datagram(namegen))
+}
+
+async fn server(listener: &UdStreamListener) -> TestResult {
+    let conn = listener.accept().await.context("Accept failed")?;
+    let mut received = Vec::new();
+    (&conn).read_to_end(&mut received).await.context("Receive failed")?;
+    ensure!(
+        received == CLIENT_MSG,
+        "received {:?}",
+        String::from_utf8_lossy(&received)
+    );
+
+    let (mut near, far) = UnixStream::pair().context("Socketpair creation failed")?;
+    conn.send_fds(SERVER_MSG, &[far.as_fd()])
+        .await
+        .context("Send with file descriptor failed")?;
+    drop(far);
+    near.write_all(PAYLOAD).context("Write to socketpair failed")?;
+    Ok(())
+}
+
+async fn client(name: &str) -> TestResult {
+    let mut conn = UdStream::connect(name).await.context("Connect failed")?;
+    conn.write_all(CLIENT_MSG).await.context("Send failed")?;
+    conn.close().await.context("Shutdown failed")?;
+
+    let mut buf = [0; SERVER_MSG.len()];
+    let (read, fds) = conn
+        .recv_fds(&mut buf)
+        .await
+        .context("Receive with file descriptor failed")?;
+    ensure!(
+        &buf[..read] == SERVER_MSG,
+        "received {:?}",
+        String::from_utf8_lossy(&buf[..read])
+    );
+    let mut fds = fds.into_inner();
+    ensure!(fds.len() == 1, "received {} file descriptors", fds.len());
+
+    let mut far = UnixStream::from(fds.pop().unwrap());
+    let mut payload = [0; PAYLOAD.len()];
+    far.read_exact(&mut payload).context("Read from socketpair failed")?;
+    ensure!(payload == PAYLOAD, "read {:?} through the passed descriptor", payload);
+
+    // The stream stays in nonblocking mode when converted back.
+    let sync = SyncUdStream::try_from(conn).context("Conversion to blocking stream failed")?;
+    ensure!(sync.is_nonblocking()?, "converted stream is in blocking mode");
+    Ok(())
+}
+
+async fn close(listener: &UdStreamListener) -> TestResult {
+    // The accept is polled first, so it's already waiting when the listener gets closed.
+    let (rslt, ()) = join(listener.accept(), async { listener.close() }).await;
+    match rslt {
+        Err(e) if ListenerClosedError::is_in(&e) => {}
+        els => bail!("pending accept was not woken up by closing: {:?}", els),
+    }
+    ensure!(listener.is_closed(), "listener is not closed");
+    match listener.accept().await {
+        Err(e) if ListenerClosedError::is_in(&e) => Ok(()),
+        els => bail!("accept on a closed listener did not fail: {:?}", els),
+    }
+}
+
+async fn datagram(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = namegen
+        .find_map(|nm| match UdSocket::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|s| (nm, s))),
+        })
+        .unwrap()
+        .context("Receiver bind failed")?;
+    let sender = UdSocket::unbound().context("Sender creation failed")?;
+
+    let mut buf = [0; 64];
+    // The receive is polled first, so it has to wait for the datagram.
+    let (received, sent) = join(receiver.recv(&mut buf), sender.send_to(CLIENT_MSG, name.as_str())).await;
+    sent.context("Send failed")?;
+    let received = received.context("Receive failed")?;
+    ensure!(&buf[..received] == CLIENT_MSG, "received {:?}", &buf[..received]);
+
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+    let (near, far) = UnixStream::pair().context("Socketpair creation failed")?;
+    sender
+        .send_fds(SERVER_MSG, &[far.as_fd(), near.as_fd()])
+        .await
+        .context("Send with file descriptors failed")?;
+    let (received, fds) = receiver
+        .recv_fds(&mut buf)
+        .await
+        .context("Receive with file descriptors failed")?;
+    ensure!(&buf[..received] == SERVER_MSG, "received {:?}", &buf[..received]);
+    ensure!(fds.len() == 2, "received {} file descriptors", fds.len());
+
+    #[cfg(target_os = "linux")]
+    {
+        use interprocess::reliable_recv_msg::{AsyncReliableRecvMsgExt, RecvResult};
+        // Unlike with a plain receive, a datagram which doesn't fit into the buffer doesn't get truncated.
+        sender.send(PAYLOAD).await.context("Long send failed")?;
+        let mut buf = [0; 8];
+        let rslt = AsyncReliableRecvMsgExt::recv(&mut &receiver, &mut buf)
+            .await
+            .context("Reliable receive failed")?;
+        ensure!(
+            matches!(&rslt, RecvResult::Alloc(msg) if msg == PAYLOAD),
+            "reliable receive returned {:?}",
+            rslt
+        );
+    }
+    Ok(())
+}
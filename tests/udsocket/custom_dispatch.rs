@@ -0,0 +1,50 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{
+        ancillary::{
+            file_descriptors::{FileDescriptors, OwnedFileDescriptors},
+            Or,
+        },
+        Cmsg, CmsgBuffer,
+    },
+    std::os::{fd::OwnedFd, unix::net::UnixStream},
+};
+
+const MSG: &[u8] = b"Some control messages attached";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let (_near, far) = UnixStream::pair().context("Socketpair creation failed")?;
+    let fds = OwnedFileDescriptors::new(vec![OwnedFd::from(far)]);
+    let mut abuf = CmsgBuffer::new(64);
+    abuf.add_message(&fds);
+    sender.send_ancillary(MSG, abuf.as_ref()).context("Send failed")?;
+
+    let mut buf = [0; MSG.len()];
+    let mut abuf = CmsgBuffer::new(128);
+    let mut abuf = abuf.as_mut();
+    receiver.recv_ancillary(&mut buf, &mut abuf).context("Receive failed")?;
+
+    // Credentials are attached to every message, since Ud-sockets are bound with SO_PASSCRED. Here, they're treated
+    // as an unknown type of control message and end up in the catch-all.
+    let (mut known, mut unknown) = (0, 0);
+    for msg in abuf.as_ref().decode_as::<Or<FileDescriptors<'_>, Cmsg<'_>>>() {
+        match msg {
+            Ok(Or::Left(fds)) => {
+                assert_eq!(fds.len(), 1);
+                known += 1;
+            }
+            Ok(Or::Right(..)) => unknown += 1,
+            Err(e) => anyhow::bail!("control message parsing failed: {}", e),
+        }
+    }
+    assert_eq!(known, 1, "file descriptors not parsed");
+    assert_ne!(unknown, 0, "credentials not passed through to the catch-all");
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::ancillary::file_descriptors::SCM_MAX_FD,
+    std::{
+        fs::File,
+        io,
+        os::fd::{AsFd, BorrowedFd, OwnedFd},
+    },
+};
+
+const MSG: &[u8] = b"Lots of file descriptors attached";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let file = File::open("/dev/null").context("Opening /dev/null failed")?;
+    // One partial chunk at the end, then an exact multiple with an empty chunk at the end
+    for count in [SCM_MAX_FD + 47, SCM_MAX_FD] {
+        let owned = (0..count)
+            .map(|_| file.try_clone().map(OwnedFd::from))
+            .collect::<io::Result<Vec<_>>>()
+            .context("Descriptor duplication failed")?;
+        let fds = owned.iter().map(AsFd::as_fd).collect::<Vec<BorrowedFd<'_>>>();
+
+        let sent = sender.send_fds(MSG, &fds).context("Send failed")?;
+        assert_eq!(sent, MSG.len());
+        drop(fds);
+        drop(owned);
+
+        let mut buf = [0; MSG.len()];
+        let (read, received) = receiver.recv_fds(&mut buf).context("Receive failed")?;
+        assert_eq!(&buf[..read], MSG);
+        assert_eq!(received.len(), count);
+    }
+
+    let owned = (0..SCM_MAX_FD)
+        .map(|_| file.try_clone().map(OwnedFd::from))
+        .collect::<io::Result<Vec<_>>>()
+        .context("Descriptor duplication failed")?;
+    let fds = owned.iter().map(AsFd::as_fd).collect::<Vec<_>>();
+    let err = sender.send_fds(b"x", &fds).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{
+        ancillary::{file_descriptors::OwnedFileDescriptors, Ancillary},
+        CmsgBuffer,
+    },
+    std::{
+        io::{Read, Write},
+        os::{fd::OwnedFd, unix::net::UnixStream},
+    },
+};
+
+const MSG: &[u8] = b"Here's a file descriptor";
+const PAYLOAD: &[u8] = b"Received through the passed descriptor";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let (mut near1, far1) = UnixStream::pair().context("First socketpair creation failed")?;
+    let (mut near2, far2) = UnixStream::pair().context("Second socketpair creation failed")?;
+    // Two separate messages to make sure that the buffer can hold more than one
+    let msgs = [far1, far2].map(|far| OwnedFileDescriptors::new(vec![OwnedFd::from(far)]));
+    let mut abuf = CmsgBuffer::new(128);
+    for fds in &msgs {
+        anyhow::ensure!(abuf.add_message(fds) != 0, "control message didn't fit");
+    }
+    sender.send_ancillary(MSG, abuf.as_ref()).context("Send failed")?;
+    // The receiving end gets its own copies of the descriptors
+    drop(msgs);
+
+    let mut buf = [0; MSG.len()];
+    let mut abuf = CmsgBuffer::new(128);
+    let mut abuf = abuf.as_mut();
+    let read = receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .context("Receive failed")?
+        .size;
+    assert_eq!(&buf[..read], MSG);
+
+    let mut received = Vec::new();
+    for anc in abuf.as_ref().decode() {
+        match anc {
+            Ok(Ancillary::FileDescriptors(fds)) => {
+                received.extend(fds.into_owned().context("Taking ownership failed")?);
+            }
+            Ok(..) => {}
+            Err(e) => anyhow::bail!("control message parsing failed: {}", e),
+        }
+    }
+    assert_eq!(received.len(), 2);
+
+    for (near, far) in [&mut near1, &mut near2].into_iter().zip(received) {
+        let mut far = UnixStream::from(far);
+        near.write_all(PAYLOAD).context("Write to socketpair failed")?;
+        let mut buf = [0; PAYLOAD.len()];
+        far.read_exact(&mut buf)
+            .context("Read from received descriptor failed")?;
+        assert_eq!(buf, PAYLOAD);
+    }
+    Ok(())
+}
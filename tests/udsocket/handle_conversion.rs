@@ -0,0 +1,55 @@
+use {
+    super::util::*,
+    anyhow::Context,
+    interprocess::{
+        os::unix::udsocket::{UdSocket, UdStream, UdStreamListener},
+        ErrorKind,
+    },
+    std::{
+        io,
+        os::unix::io::{AsRawFd, OwnedFd},
+    },
+};
+
+static MSG: &[u8] = b"Hello through a converted socket!";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = namegen
+        .find_map(|nm| {
+            let l = match UdStreamListener::bind(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+
+    // A listener is not a connected stream, and the rejected descriptor must come back intact.
+    let listener_fd = OwnedFd::from(listener);
+    let raw = listener_fd.as_raw_fd();
+    let err = UdStream::try_from(listener_fd).expect_err("listener was accepted as a stream");
+    assert_eq!(err.cause.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(ErrorKind::of(&err.cause), ErrorKind::InvalidHandle);
+    assert_eq!(err.handle.as_raw_fd(), raw);
+    let listener = UdStreamListener::try_from(err.handle).context("listener round trip failed")?;
+
+    let client = UdStream::connect(name.as_str()).context("Connect failed")?;
+    let server = listener.accept().context("Accept failed")?;
+
+    // Neither a datagram socket nor a listener may be made out of a connected stream.
+    let err = UdSocket::try_from(OwnedFd::from(client)).expect_err("stream was accepted as a datagram socket");
+    assert_eq!(err.cause.kind(), io::ErrorKind::InvalidInput);
+    let err = UdStreamListener::try_from(err.handle).expect_err("stream was accepted as a listener");
+    assert_eq!(err.cause.kind(), io::ErrorKind::InvalidInput);
+    let client = UdStream::try_from(err.handle).context("stream round trip failed")?;
+
+    let written = client.send(MSG).context("Socket send failed")?;
+    assert_eq!(written, MSG.len());
+    let mut buf = [0; MSG.len()];
+    let read = server.recv(&mut buf).context("Socket receive failed")?;
+    assert_eq!(&buf[..read], MSG);
+
+    Ok(())
+}
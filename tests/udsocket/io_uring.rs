@@ -0,0 +1,115 @@
+//! Tests the `io_uring` ring: multishot accepting, batched stream receives and sends, and datagrams.
+
+use {
+    super::{bind_datagram, util::*},
+    anyhow::{ensure, Context},
+    interprocess::os::unix::udsocket::{
+        io_uring::{IoUring, Op},
+        UdStream, UdStreamListener,
+    },
+    std::{
+        io::{self, prelude::*},
+        thread,
+    },
+};
+
+const CLIENTS: usize = 6;
+const SERVER_MSG: &[u8] = b"Hello from server!";
+
+fn client_msg(i: usize) -> String {
+    format!("Hello from client #{}!", i)
+}
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let mut ring = match IoUring::new(4) {
+        Ok(r) => r,
+        // Kernels without io_uring, or with it turned off, are not what's being tested here.
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return Ok(()),
+        Err(e) => return Err(e).context("Ring creation failed"),
+    };
+    ensure!(ring.entries() == 4, "ring has {} entries", ring.entries());
+
+    let (name, listener) = namegen
+        .find_map(|nm| match UdStreamListener::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|l| (nm, l))),
+        })
+        .unwrap()
+        .context("Bind failed")?;
+
+    let clients = (0..CLIENTS)
+        .map(|i| {
+            let name = name.clone();
+            thread::spawn(move || client(&name, i))
+        })
+        .collect::<Vec<_>>();
+
+    let conns = ring
+        .accept_multishot(&listener, CLIENTS)
+        .context("Multishot accept failed")?;
+    ensure!(conns.len() == CLIENTS, "accepted {} connections", conns.len());
+
+    // More receives than the ring has entries, to have the batch split.
+    let mut bufs = vec![[0; 64]; CLIENTS];
+    let mut ops = conns
+        .iter()
+        .zip(bufs.iter_mut())
+        .map(|(conn, buf)| Op::recv(conn, buf))
+        .collect::<Vec<_>>();
+    ring.submit(&mut ops).context("Receive submission failed")?;
+    let mut received = Vec::with_capacity(CLIENTS);
+    for op in ops {
+        let len = op
+            .take_result()
+            .context("receive did not complete")?
+            .context("Receive failed")?;
+        received.push(len);
+    }
+    let mut msgs = bufs
+        .iter()
+        .zip(received)
+        .map(|(buf, len)| String::from_utf8_lossy(&buf[..len]).into_owned())
+        .collect::<Vec<_>>();
+    msgs.sort();
+    let mut expected = (0..CLIENTS).map(client_msg).collect::<Vec<_>>();
+    expected.sort();
+    ensure!(msgs == expected, "received {:?}", msgs);
+
+    let mut ops = conns.iter().map(|conn| Op::send(conn, SERVER_MSG)).collect::<Vec<_>>();
+    ring.submit(&mut ops).context("Send submission failed")?;
+    for op in &ops {
+        match op.result() {
+            Some(Ok(len)) => ensure!(*len == SERVER_MSG.len(), "sent {} bytes", len),
+            els => return Err(anyhow::anyhow!("send did not succeed: {:?}", els)),
+        }
+    }
+    for client in clients {
+        client.join().map_err(|_| anyhow::anyhow!("client panicked"))??;
+    }
+
+    datagram(&mut ring, namegen)
+}
+
+fn client(name: &str, i: usize) -> TestResult {
+    let mut conn = UdStream::connect(name).context("Connect failed")?;
+    conn.write_all(client_msg(i).as_bytes()).context("Send failed")?;
+    let mut buf = [0; SERVER_MSG.len()];
+    conn.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(buf == SERVER_MSG, "received {:?}", String::from_utf8_lossy(&buf));
+    Ok(())
+}
+
+fn datagram(ring: &mut IoUring, mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let sent = ring.send(&sender, SERVER_MSG).context("Send failed")?;
+    ensure!(sent == SERVER_MSG.len(), "sent {} bytes", sent);
+    let mut buf = [0; 64];
+    let received = ring.recv(&receiver, &mut buf).context("Receive failed")?;
+    ensure!(&buf[..received] == SERVER_MSG, "received {:?}", &buf[..received]);
+    Ok(())
+}
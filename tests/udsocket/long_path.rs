@@ -0,0 +1,67 @@
+//! Tests that socket paths longer than `sun_path` fail with `NameTooLong`, and work once the workaround is enabled.
+
+use {
+    super::util::*,
+    anyhow::{ensure, Context},
+    interprocess::{
+        os::unix::udsocket::{self, UdSocket, UdStream, UdStreamListener, MAX_UDSOCKET_PATH_LEN},
+        ErrorKind,
+    },
+    std::{
+        fs,
+        io::{Read, Write},
+        path::PathBuf,
+        process,
+    },
+};
+
+pub(super) fn run() -> TestResult {
+    let root = std::env::temp_dir().join(format!("interprocess-long-path-{}", process::id()));
+    let mut dir = root.clone();
+    while dir.as_os_str().len() <= MAX_UDSOCKET_PATH_LEN {
+        dir.push("deeply-nested-runtime-directory");
+    }
+    fs::create_dir_all(&dir).context("Directory creation failed")?;
+    let rslt = check_paths(dir);
+    udsocket::set_long_path_workaround(false);
+    let _ = fs::remove_dir_all(&root);
+    rslt
+}
+
+fn check_paths(dir: PathBuf) -> TestResult {
+    let stream_path = dir.join("stream.sock");
+    let dgram_path = dir.join("datagram.sock");
+
+    ensure!(
+        !udsocket::long_path_workaround(),
+        "long path workaround is enabled by default"
+    );
+    let err = UdStreamListener::bind(&*stream_path)
+        .err()
+        .context("bind to long path succeeded")?;
+    ensure!(ErrorKind::of(&err) == ErrorKind::NameTooLong, "unexpected error: {err}");
+    let err = UdStream::connect(&*stream_path)
+        .err()
+        .context("connect to long path succeeded")?;
+    ensure!(ErrorKind::of(&err) == ErrorKind::NameTooLong, "unexpected error: {err}");
+
+    udsocket::set_long_path_workaround(true);
+    let listener = UdStreamListener::bind_with_drop_guard(&*stream_path).context("Bind failed")?;
+    ensure!(stream_path.exists(), "socket file wasn't created at the long path");
+    let mut client = UdStream::connect(&*stream_path).context("Connect failed")?;
+    let mut server = listener.accept().context("Accept failed")?;
+    client.write_all(b"Hello!").context("Send failed")?;
+    let mut buf = [0; 6];
+    server.read_exact(&mut buf).context("Receive failed")?;
+    ensure!(&buf == b"Hello!", "received {buf:?}");
+    drop(listener);
+    ensure!(!stream_path.exists(), "drop guard didn't remove the socket file");
+
+    let receiver = UdSocket::bind_with_drop_guard(&*dgram_path).context("Datagram bind failed")?;
+    let sender = UdSocket::unbound().context("Datagram socket creation failed")?;
+    sender.send_to(b"Hi!", &*dgram_path).context("Datagram send failed")?;
+    let mut buf = [0; 3];
+    receiver.recv(&mut buf).context("Datagram receive failed")?;
+    ensure!(&buf == b"Hi!", "received {buf:?}");
+    Ok(())
+}
@@ -3,9 +3,53 @@
 #[path = "../util/mod.rs"]
 mod util;
 use util::*;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use {interprocess::os::unix::udsocket::UdSocket, std::io};
 
+#[cfg(all(feature = "async-io", any(target_os = "linux", target_os = "android")))]
+mod async_io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod custom_dispatch;
 mod datagram;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod fd_chunking;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod fd_passing;
+mod handle_conversion;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod io_uring;
+mod long_path;
+#[cfg(target_os = "linux")]
+mod memfd;
+#[cfg(target_os = "linux")]
+mod multi_message;
+mod peer_credentials;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod security;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sigpipe;
 mod stream;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod timestamp;
+#[cfg(all(feature = "tokio", target_os = "linux"))]
+mod tokio_recv_msg;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod truncation;
+
+/// Binds a datagram socket to the first free name produced by the given name generator.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn bind_datagram(namegen: &mut NameGen) -> io::Result<(String, UdSocket)> {
+    namegen
+        .find_map(|nm| {
+            let s = match UdSocket::bind_with_drop_guard(&*nm) {
+                Ok(s) => s,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, s)))
+        })
+        .unwrap()
+}
 
 #[test]
 fn udsocket_stream() {
@@ -24,3 +68,109 @@ fn udsocket_datagram() {
         run_with_namegen(NameGen::new(true));
     }
 }
+
+#[cfg(all(feature = "async-io", any(target_os = "linux", target_os = "android")))]
+#[test]
+fn udsocket_async_io() -> TestResult {
+    use async_io::*;
+    run_with_namegen(NameGen::new(false))?;
+    if cfg!(target_os = "linux") {
+        run_with_namegen(NameGen::new(true))?;
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+#[test]
+fn udsocket_io_uring() -> TestResult {
+    use io_uring::*;
+    run_with_namegen(NameGen::new(false))?;
+    run_with_namegen(NameGen::new(true))
+}
+
+#[test]
+fn udsocket_peer_credentials() -> TestResult {
+    use peer_credentials::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[test]
+fn udsocket_handle_conversion() -> TestResult {
+    use handle_conversion::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_fd_passing() -> TestResult {
+    use fd_passing::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_fd_chunking() -> TestResult {
+    use fd_chunking::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn udsocket_memfd() -> TestResult {
+    use memfd::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn udsocket_multi_message() -> TestResult {
+    use multi_message::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_custom_dispatch() -> TestResult {
+    use custom_dispatch::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_timestamp() -> TestResult {
+    use timestamp::*;
+    run_with_namegen(NameGen::new(false))?;
+    run_with_namegen(NameGen::new(true))
+}
+
+#[test]
+fn udsocket_long_path() -> TestResult {
+    long_path::run()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_security() -> TestResult {
+    use security::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_sigpipe() -> TestResult {
+    sigpipe::run()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_truncation() -> TestResult {
+    use truncation::*;
+    run_with_namegen(NameGen::new(false))
+}
+
+#[cfg(all(feature = "tokio", target_os = "linux"))]
+#[test]
+fn udsocket_tokio_recv_msg() -> TestResult {
+    use tokio_recv_msg::*;
+    run_with_namegen(NameGen::new(true))
+}
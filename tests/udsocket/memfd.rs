@@ -0,0 +1,76 @@
+use {
+    super::util::*,
+    anyhow::{ensure, Context},
+    interprocess::os::unix::{
+        memfd::{AnonymousSharedMemory, Seals},
+        udsocket::{UdStream, UdStreamListener},
+    },
+    std::{fs::File, io, os::fd::OwnedFd},
+};
+
+const MSG: &[u8] = b"Written before sealing";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = namegen
+        .find_map(|nm| {
+            let l = match UdStreamListener::bind_with_drop_guard(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    let sender = UdStream::connect(name.as_str()).context("Connect failed")?;
+    let receiver = listener.accept().context("Accept failed")?;
+
+    let shm = AnonymousSharedMemory::create(4096).context("Creation failed")?;
+    ensure!(shm.seals()?.is_empty(), "fresh object already sealed");
+    let mapping = shm.map().context("Mapping failed")?;
+    unsafe {
+        // SAFETY: nothing else has access to the memory yet
+        mapping.as_mut_slice()[..MSG.len()].copy_from_slice(MSG);
+    }
+    ensure!(
+        shm.add_seals(Seals::WRITE).is_err(),
+        "sealed against writes with a writable mapping in place"
+    );
+    drop(mapping);
+    shm.add_seals(Seals::SHRINK | Seals::GROW | Seals::WRITE | Seals::SEAL)
+        .context("Sealing failed")?;
+    shm.send_over(&sender).context("Send failed")?;
+    drop(shm);
+
+    // The memory outlives the sender's descriptor, since the receiver got its own.
+    let received = AnonymousSharedMemory::recv_from(&receiver).context("Receive failed")?;
+    ensure!(received.len() == 4096, "wrong size: {}", received.len());
+    ensure!(
+        received.seals()? == Seals::SHRINK | Seals::GROW | Seals::WRITE | Seals::SEAL,
+        "wrong seals"
+    );
+    let err = received.map().err().context("Writable mapping of sealed memory")?;
+    ensure!(err.kind() == io::ErrorKind::PermissionDenied, "wrong error: {}", err);
+    let mapping = received.map_read_only().context("Read-only mapping failed")?;
+    ensure!(
+        unsafe { &mapping.as_slice()[..MSG.len()] } == MSG,
+        "shared memory contents lost"
+    );
+    ensure!(
+        received.add_seals(Seals::FUTURE_WRITE).is_err(),
+        "added a seal to memory sealed against sealing"
+    );
+
+    // Anything other than shared memory is rejected.
+    let file = File::open("/dev/null").context("Opening /dev/null failed")?;
+    let err = AnonymousSharedMemory::try_from(OwnedFd::from(file))
+        .err()
+        .context("Accepted /dev/null as shared memory")?;
+    ensure!(err.kind() == io::ErrorKind::InvalidData, "wrong error: {}", err);
+    drop(sender);
+    let err = AnonymousSharedMemory::recv_from(&receiver)
+        .err()
+        .context("Received shared memory from a closed connection")?;
+    ensure!(err.kind() == io::ErrorKind::UnexpectedEof, "wrong error: {}", err);
+    Ok(())
+}
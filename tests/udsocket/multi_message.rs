@@ -0,0 +1,62 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{
+        ancillary::{credentials::Credentials, file_descriptors::FileDescriptors, Ancillary},
+        CmsgBuffer,
+    },
+    std::os::{fd::AsFd, unix::net::UnixStream},
+};
+
+const MSG: &[u8] = b"Several control messages attached";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let (a, b) = UnixStream::pair().context("Socketpair creation failed")?;
+    let fds = [a.as_fd(), b.as_fd()];
+    let creds = libc::ucred {
+        pid: std::process::id() as _,
+        uid: unsafe { libc::getuid() },
+        gid: unsafe { libc::getgid() },
+    };
+    let msgs = [
+        Ancillary::FileDescriptors(FileDescriptors::new(&fds[..1])),
+        Ancillary::Credentials(Credentials::new_sendable(&creds)),
+        Ancillary::FileDescriptors(FileDescriptors::new(&fds[1..])),
+    ];
+    let abuf = msgs.iter().collect::<CmsgBuffer>();
+    assert_eq!(
+        abuf.as_ref().cmsgs().count(),
+        msgs.len(),
+        "not all messages were serialized"
+    );
+    sender.send_ancillary(MSG, abuf.as_ref()).context("Send failed")?;
+
+    let mut buf = [0; MSG.len()];
+    let mut abuf = CmsgBuffer::new(256);
+    let mut abuf = abuf.as_mut();
+    let rslt = receiver.recv_ancillary(&mut buf, &mut abuf).context("Receive failed")?;
+    assert_eq!(rslt.size, MSG.len());
+    assert_eq!(&buf[..], MSG);
+
+    let (mut nfds, mut ncreds) = (0, 0);
+    for msg in abuf.as_ref().decode() {
+        match msg {
+            Ok(Ancillary::FileDescriptors(fds)) => nfds += fds.into_owned().context("Taking ownership failed")?.len(),
+            Ok(Ancillary::Credentials(c)) => {
+                assert_eq!(c.pid(), Some(creds.pid));
+                ncreds += 1;
+            }
+            Ok(other) => anyhow::bail!("unexpected control message: {:?}", other),
+            Err(e) => anyhow::bail!("control message parsing failed: {}", e),
+        }
+    }
+    assert_eq!(nfds, 2);
+    assert_eq!(ncreds, 1);
+    Ok(())
+}
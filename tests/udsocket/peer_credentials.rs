@@ -0,0 +1,38 @@
+use {
+    super::util::*,
+    anyhow::Context,
+    interprocess::os::unix::udsocket::{UdStream, UdStreamListener},
+    std::{fs, io, os::unix::fs::MetadataExt, process},
+};
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = namegen
+        .find_map(|nm| {
+            let l = match UdStreamListener::bind_with_drop_guard(&*nm) {
+                Ok(l) => l,
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse => return None,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok((nm, l)))
+        })
+        .unwrap()
+        .context("Listener bind failed")?;
+    // The socket file was created by this very process, so its owner is our effective user ID
+    let our_uid = fs::metadata(&name).context("Socket file metadata query failed")?.uid();
+
+    let client = UdStream::connect(name.as_str()).context("Connect failed")?;
+    let server = listener.accept().context("Accept failed")?;
+
+    for (side, conn) in [("server", &client), ("client", &server)] {
+        let creds = conn
+            .get_peer_credentials()
+            .with_context(|| format!("Querying {side} credentials failed"))?;
+        if let Some(pid) = creds.pid() {
+            assert_eq!(pid as u32, process::id(), "wrong {side} PID");
+        }
+        if let Some(uid) = creds.uid() {
+            assert_eq!(uid, our_uid, "wrong {side} UID");
+        }
+    }
+    Ok(())
+}
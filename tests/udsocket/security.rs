@@ -0,0 +1,38 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{ancillary::Ancillary, CmsgBuffer},
+};
+
+const MSG: &[u8] = b"Labeled message";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    receiver.set_passsec(true).context("Enabling SO_PASSSEC failed")?;
+
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+    sender.send(MSG).context("Send failed")?;
+
+    let mut buf = [0; MSG.len()];
+    let mut abuf = CmsgBuffer::new(256);
+    let mut abuf = abuf.as_mut();
+    let read = receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .context("Receive failed")?
+        .size;
+    assert_eq!(&buf[..read], MSG);
+
+    // Whether a security context is attached depends on the LSM configuration of the system running the test, so
+    // only its well-formedness can be checked.
+    for anc in abuf.as_ref().decode() {
+        match anc {
+            Ok(Ancillary::Security(sec)) => anyhow::ensure!(!sec.label().is_empty(), "empty security context"),
+            Ok(..) => {}
+            Err(e) => anyhow::bail!("control message parsing failed: {}", e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Tests that sending to a disconnected peer fails with `BrokenPipe` instead of raising `SIGPIPE`, unless opted out.
+
+use {
+    super::util::*,
+    anyhow::{ensure, Context},
+    interprocess::os::unix::udsocket::{self, UdStream},
+    std::{
+        io,
+        mem::zeroed,
+        os::{fd::OwnedFd, unix::net::UnixStream},
+        ptr,
+    },
+};
+
+pub(super) fn run() -> TestResult {
+    // SIGPIPE is blocked rather than caught, so that one raised by a send stays pending and can be checked for
+    // without affecting the other tests, which run on other threads
+    let set = sigpipe_set();
+    let mut old_mask = unsafe { zeroed() };
+    let rc = unsafe { libc::pthread_sigmask(libc::SIG_BLOCK, &set, &mut old_mask) };
+    ensure!(rc == 0, "blocking SIGPIPE failed");
+    let rslt = check_sends(&set);
+    unsafe { libc::pthread_sigmask(libc::SIG_SETMASK, &old_mask, ptr::null_mut()) };
+    udsocket::set_sigpipe_on_send(false);
+    rslt
+}
+
+fn check_sends(set: &libc::sigset_t) -> TestResult {
+    let (near, far) = UnixStream::pair().context("Socketpair creation failed")?;
+    let conn = UdStream::try_from(OwnedFd::from(near)).context("Conversion failed")?;
+    drop(far);
+
+    ensure!(!udsocket::sigpipe_on_send(), "SIGPIPE is raised on send by default");
+    let err = conn.send(b"Hello?").err().context("send to closed peer succeeded")?;
+    ensure!(err.kind() == io::ErrorKind::BrokenPipe, "unexpected error: {err}");
+    ensure!(!sigpipe_pending(), "SIGPIPE was raised despite being suppressed");
+
+    udsocket::set_sigpipe_on_send(true);
+    let err = conn.send(b"Hello?").err().context("send to closed peer succeeded")?;
+    ensure!(err.kind() == io::ErrorKind::BrokenPipe, "unexpected error: {err}");
+    ensure!(sigpipe_pending(), "SIGPIPE wasn't raised after opting into it");
+    let mut signal = 0;
+    unsafe { libc::sigwait(set, &mut signal) };
+    Ok(())
+}
+
+fn sigpipe_set() -> libc::sigset_t {
+    unsafe {
+        let mut set = zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGPIPE);
+        set
+    }
+}
+fn sigpipe_pending() -> bool {
+    unsafe {
+        let mut pending = zeroed();
+        libc::sigpending(&mut pending);
+        libc::sigismember(&pending, libc::SIGPIPE) == 1
+    }
+}
@@ -0,0 +1,46 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{ancillary::Ancillary, CmsgBuffer},
+    std::time::SystemTime,
+};
+
+const MSG: &[u8] = b"Timestamped message";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    receiver.set_timestamp(true).context("Enabling SO_TIMESTAMP failed")?;
+
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let before = SystemTime::now();
+    let written = sender.send(MSG).context("Send failed")?;
+    assert_eq!(written, MSG.len());
+
+    let mut buf = [0; MSG.len()];
+    let mut abuf = CmsgBuffer::new(64);
+    let mut abuf = abuf.as_mut();
+    let read = receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .context("Receive failed")?
+        .size;
+    assert_eq!(&buf[..read], MSG);
+
+    let timestamp = abuf
+        .as_ref()
+        .decode()
+        .find_map(|anc| match anc {
+            Ok(Ancillary::Timestamp(ts)) => Some(ts),
+            _ => None,
+        })
+        .context("No timestamp control message received")?;
+    let received = timestamp.to_system_time();
+    // Give the kernel's clock a bit of leeway with respect to the one used by SystemTime
+    let slack = std::time::Duration::from_millis(100);
+    anyhow::ensure!(received + slack >= before, "timestamp is too early");
+    anyhow::ensure!(received <= SystemTime::now() + slack, "timestamp is too late");
+    Ok(())
+}
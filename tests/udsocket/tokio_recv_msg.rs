@@ -0,0 +1,54 @@
+use {
+    super::util::*,
+    anyhow::{ensure, Context},
+    interprocess::{
+        os::unix::udsocket::tokio::UdSocket,
+        reliable_recv_msg::{AsyncReliableRecvMsgExt, RecvResult},
+    },
+    std::{io, path::Path},
+};
+
+const SHORT: &[u8] = b"Short";
+const LONG: &[u8] = b"This datagram is too long for the buffer";
+
+pub(super) fn run_with_namegen(namegen: NameGen) -> TestResult {
+    ::tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Runtime creation failed")?
+        .block_on(run(namegen))
+}
+
+async fn run(mut namegen: NameGen) -> TestResult {
+    let (name, mut receiver) = namegen
+        .find_map(|nm| match UdSocket::bind(&*nm) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => None,
+            els => Some(els.map(|s| (nm, s))),
+        })
+        .unwrap()
+        .context("Receiver bind failed")?;
+    if let Some(file) = name.strip_prefix('@') {
+        // The name is namespaced, so it must not be bound as a relative path.
+        ensure!(!Path::new(file).exists(), "binding to a namespaced name created a file");
+    }
+    let sender = UdSocket::unbound().context("Sender creation failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    sender.send(SHORT).await.context("First send failed")?;
+    sender.send(LONG).await.context("Second send failed")?;
+
+    // Unlike with a plain receive, the long datagram doesn't get truncated
+    let mut buf = [0; 8];
+    let rslt = AsyncReliableRecvMsgExt::recv(&mut receiver, &mut buf)
+        .await
+        .context("First receive failed")?;
+    assert!(matches!(rslt, RecvResult::Fit(size) if size == SHORT.len()));
+    assert_eq!(&buf[..SHORT.len()], SHORT);
+    let rslt = AsyncReliableRecvMsgExt::recv(&mut receiver, &mut buf)
+        .await
+        .context("Second receive failed")?;
+    assert!(matches!(rslt, RecvResult::Alloc(msg) if msg == LONG));
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use {
+    super::{bind_datagram, util::*},
+    anyhow::Context,
+    interprocess::os::unix::udsocket::cmsg::{ancillary::file_descriptors::OwnedFileDescriptors, CmsgBuffer},
+    std::os::{fd::OwnedFd, unix::net::UnixStream},
+};
+
+const MSG: &[u8] = b"This datagram is too long for the buffer";
+
+pub(super) fn run_with_namegen(mut namegen: NameGen) -> TestResult {
+    let (name, receiver) = bind_datagram(&mut namegen).context("Receiver bind failed")?;
+    let (_, sender) = bind_datagram(&mut namegen).context("Sender bind failed")?;
+    sender
+        .set_destination(name.as_str())
+        .context("Setting destination failed")?;
+
+    let (_near, far) = UnixStream::pair().context("Socketpair creation failed")?;
+    let fds = OwnedFileDescriptors::new(vec![OwnedFd::from(far)]);
+    let mut abuf = CmsgBuffer::new(64);
+    abuf.add_message(&fds);
+    sender.send_ancillary(MSG, abuf.as_ref()).context("Send failed")?;
+
+    // Neither the datagram nor the control message fit
+    let mut buf = [0; 8];
+    let mut abuf = CmsgBuffer::new(0);
+    let rslt = receiver
+        .recv_ancillary(&mut buf, &mut abuf.as_mut())
+        .context("Receive failed")?;
+    assert_eq!(rslt.size, buf.len());
+    assert_eq!(&buf[..], &MSG[..buf.len()]);
+    assert!(rslt.data_truncated, "data truncation not reported");
+    assert!(rslt.ancillary_truncated, "ancillary data truncation not reported");
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Tests querying and changing the capacity of a pipe, both after its creation and through the creation options.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::{
+        os::unix::unnamed_pipe::UnnamedPipeExt,
+        unnamed_pipe::{pipe, UnnamedPipeOptions},
+    },
+    std::io::prelude::*,
+};
+
+const SIZE: usize = 256 * 1024;
+
+pub(super) fn run() -> TestResult {
+    let (writer, reader) = pipe().context("Pipe creation failed")?;
+    let set = writer.set_pipe_size(SIZE).context("Setting the capacity failed")?;
+    ensure!(
+        set >= SIZE,
+        "capacity set to {} bytes instead of at least {}",
+        set,
+        SIZE
+    );
+    // The capacity belongs to the pipe, so both ends see the same value.
+    ensure!(writer.pipe_size()? == set, "writer reports a different capacity");
+    ensure!(reader.pipe_size()? == set, "reader reports a different capacity");
+
+    let (mut writer, reader) = UnnamedPipeOptions::new()
+        .buffer_size(SIZE)
+        .writer_nonblocking(true)
+        .build()
+        .context("Pipe creation with options failed")?;
+    let capacity = reader.pipe_size()?;
+    ensure!(
+        capacity >= SIZE,
+        "capacity of {} bytes is below the requested one",
+        capacity
+    );
+    // The whole capacity can be filled without the reader taking anything out.
+    let data = vec![0xa5; SIZE];
+    writer.write_all(&data).context("Filling the pipe failed")?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Tests that an end of a pipe made non-inheritable doesn't leak into a child process, which would keep the pipe from
+//! reporting end-of-file to the child.
+
+use {
+    super::TestResult,
+    anyhow::{bail, ensure, Context},
+    interprocess::{os::unix::unnamed_pipe::UnnamedPipeCreationOptions, unnamed_pipe::UnnamedPipeOptions},
+    std::{
+        io::prelude::*,
+        os::unix::io::AsRawFd,
+        process::{Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+fn has_cloexec(fd: &impl AsRawFd) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags, -1, "F_GETFD failed");
+    flags & libc::FD_CLOEXEC != 0
+}
+
+pub(super) fn run() -> TestResult {
+    let (writer, reader) = UnnamedPipeCreationOptions::new().reader_cloexec(true).build()?;
+    ensure!(has_cloexec(&reader), "reader is inheritable");
+    ensure!(!has_cloexec(&writer), "writer is not inheritable");
+    let (writer, reader) = UnnamedPipeOptions::new().inheritable(false).build()?;
+    ensure!(has_cloexec(&reader) && has_cloexec(&writer), "ends are inheritable");
+
+    let (mut writer, reader) = UnnamedPipeOptions::new()
+        .writer_inheritable(false)
+        .build()
+        .context("Pipe creation failed")?;
+    let mut child = Command::new("cat")
+        .stdin(reader)
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Spawning cat failed")?;
+    writer.write_all(b"Hello from the parent!\n")?;
+    drop(writer);
+
+    // Had the writing end leaked into the child, cat would wait for more input forever.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            ensure!(status.success(), "cat exited with {}", status);
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            bail!("cat did not see end-of-file on its standard input");
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod capacity;
+#[cfg(unix)]
+mod inheritance;
+mod options;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod splice;
+#[cfg(unix)]
+mod stdio;
+mod uninit;
+mod vectored;
+
+type TestResult = anyhow::Result<()>;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn unnamed_pipe_capacity() -> TestResult {
+    capacity::run()
+}
+#[cfg(unix)]
+#[test]
+fn unnamed_pipe_inheritance() -> TestResult {
+    inheritance::run()
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn unnamed_pipe_splice() -> TestResult {
+    splice::run()
+}
+#[test]
+fn unnamed_pipe_vectored() -> TestResult {
+    vectored::run()
+}
+#[test]
+fn unnamed_pipe_uninit() -> TestResult {
+    uninit::run()
+}
+#[cfg(unix)]
+#[test]
+fn unnamed_pipe_stdio() -> TestResult {
+    stdio::run()
+}
+#[test]
+fn unnamed_pipe_options() -> TestResult {
+    options::run()
+}
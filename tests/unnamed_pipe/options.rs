@@ -0,0 +1,45 @@
+//! Tests the nonblocking options of the pipe builder.
+
+use {
+    super::TestResult,
+    anyhow::{bail, ensure, Context},
+    interprocess::unnamed_pipe::UnnamedPipeOptions,
+    std::io::{self, prelude::*},
+};
+
+pub(super) fn run() -> TestResult {
+    let (mut writer, mut reader) = UnnamedPipeOptions::new()
+        .nonblocking(true)
+        .build()
+        .context("Pipe creation failed")?;
+
+    let mut buf = [0; 64];
+    match reader.read(&mut buf) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        Err(e) => bail!("read from an empty pipe failed with an unexpected error: {}", e),
+        Ok(n) => bail!("read of {} bytes from an empty pipe succeeded", n),
+    }
+
+    // Fill the pipe until the writer can't put anything else into it.
+    let chunk = [0x5a; 4096];
+    let mut total = 0;
+    loop {
+        match writer.write(&chunk) {
+            Ok(n) => total += n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+            Err(e) => return Err(e).context("Write failed"),
+        }
+        ensure!(
+            total <= 64 * 1024 * 1024,
+            "writes into a pipe nobody reads from never block"
+        );
+    }
+    ensure!(total > 0, "nothing could be written into an empty pipe");
+
+    let mut drained = 0;
+    while drained < total {
+        drained += reader.read(&mut buf).context("Read from a full pipe failed")?;
+    }
+    ensure!(drained == total, "read {} bytes instead of {}", drained, total);
+    Ok(())
+}
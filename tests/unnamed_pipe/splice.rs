@@ -0,0 +1,54 @@
+//! Tests moving data between pipes and files with `splice` and duplicating it with `tee`.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::{
+        os::unix::unnamed_pipe::{UnnamedPipeReaderExt, UnnamedPipeWriterExt},
+        unnamed_pipe::pipe,
+    },
+    std::{
+        env, fs,
+        io::{prelude::*, SeekFrom},
+        process,
+    },
+};
+
+const MSG: &[u8] = b"Moved around without passing through userspace";
+
+pub(super) fn run() -> TestResult {
+    let (mut writer, mut reader) = pipe().context("Pipe creation failed")?;
+    let (mut copy_writer, mut copy_reader) = pipe().context("Pipe creation failed")?;
+    writer.write_all(MSG)?;
+
+    let copied = reader.tee(&mut copy_writer, MSG.len()).context("Tee failed")?;
+    ensure!(copied == MSG.len(), "only {} bytes were teed", copied);
+    let mut buf = vec![0; MSG.len()];
+    copy_reader.read_exact(&mut buf)?;
+    ensure!(buf == MSG, "teed data doesn't match");
+
+    // The teed data is still in the first pipe, and gets spliced into a file and back into the second pipe.
+    let path = env::temp_dir().join(format!("interprocess-test-splice-{}", process::id()));
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .context("File creation failed")?;
+    fs::remove_file(&path)?;
+    let moved = reader.splice_to(&file, MSG.len()).context("Splice into file failed")?;
+    ensure!(moved == MSG.len(), "only {} bytes were spliced into the file", moved);
+    file.seek(SeekFrom::Start(0))?;
+    let moved = copy_writer
+        .splice_from(&file, MSG.len())
+        .context("Splice from file failed")?;
+    ensure!(moved == MSG.len(), "only {} bytes were spliced from the file", moved);
+    copy_reader.read_exact(&mut buf)?;
+    ensure!(buf == MSG, "spliced data doesn't match");
+
+    // The first pipe was emptied by the splice, so it reports end-of-file once the writer is gone.
+    drop(writer);
+    ensure!(reader.splice_to(&file, 1)? == 0, "splice after end-of-file moved data");
+    Ok(())
+}
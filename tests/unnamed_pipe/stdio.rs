@@ -0,0 +1,57 @@
+//! Tests wiring pipes into the standard input and output of a child process, and taking over the pipes created for it
+//! by the standard library.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::unnamed_pipe::{UnnamedPipeOptions, UnnamedPipeReader, UnnamedPipeWriter},
+    std::{
+        io::prelude::*,
+        process::{Command, Stdio},
+    },
+};
+
+const MSG: &str = "Round trip through cat\n";
+
+pub(super) fn run() -> TestResult {
+    // Our pipe feeds the child, and the standard library creates the one the child writes into.
+    let (mut writer, reader) = UnnamedPipeOptions::new()
+        .writer_inheritable(false)
+        .build()
+        .context("Pipe creation failed")?;
+    let mut child = Command::new("cat")
+        .stdin(reader)
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Spawning cat failed")?;
+    let mut output = UnnamedPipeReader::from(child.stdout.take().unwrap());
+    writer.write_all(MSG.as_bytes())?;
+    drop(writer);
+    let mut received = String::new();
+    output
+        .read_to_string(&mut received)
+        .context("Reading the output failed")?;
+    ensure!(received == MSG, "received {:?}", received);
+    ensure!(child.wait()?.success(), "cat failed");
+
+    // The other way around.
+    let (writer, mut reader) = UnnamedPipeOptions::new()
+        .reader_inheritable(false)
+        .build()
+        .context("Pipe creation failed")?;
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(writer)
+        .spawn()
+        .context("Spawning cat failed")?;
+    let mut input = UnnamedPipeWriter::from(child.stdin.take().unwrap());
+    input.write_all(MSG.as_bytes())?;
+    drop(input);
+    let mut received = String::new();
+    reader
+        .read_to_string(&mut received)
+        .context("Reading the output failed")?;
+    ensure!(received == MSG, "received {:?}", received);
+    ensure!(child.wait()?.success(), "cat failed");
+    Ok(())
+}
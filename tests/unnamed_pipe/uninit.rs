@@ -0,0 +1,29 @@
+//! Tests reading from a pipe into an uninitialized buffer.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::unnamed_pipe::pipe,
+    std::{io::prelude::*, mem::MaybeUninit, slice},
+};
+
+const MSG: &[u8] = b"Straight into uninitialized memory";
+
+pub(super) fn run() -> TestResult {
+    let (mut writer, mut reader) = pipe().context("Pipe creation failed")?;
+    writer.write_all(MSG)?;
+    drop(writer);
+
+    let mut buf = [MaybeUninit::<u8>::uninit(); 256];
+    let read = reader.read_to_uninit(&mut buf).context("Read failed")?;
+    ensure!(read == MSG.len(), "read {} bytes instead of {}", read, MSG.len());
+    let received = unsafe {
+        // SAFETY: the first `read` bytes have been initialized by the read
+        slice::from_raw_parts(buf.as_ptr().cast::<u8>(), read)
+    };
+    ensure!(received == MSG, "received {:?}", received);
+
+    let read = reader.read_to_uninit(&mut buf).context("Read at end-of-file failed")?;
+    ensure!(read == 0, "read {} bytes after the writer was dropped", read);
+    Ok(())
+}
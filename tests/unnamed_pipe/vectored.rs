@@ -0,0 +1,36 @@
+//! Tests that vectored reads and writes move the data of all the buffers in order.
+
+use {
+    super::TestResult,
+    anyhow::{ensure, Context},
+    interprocess::unnamed_pipe::pipe,
+    std::io::{prelude::*, IoSlice, IoSliceMut},
+};
+
+pub(super) fn run() -> TestResult {
+    let (mut writer, mut reader) = pipe().context("Pipe creation failed")?;
+
+    let parts: [&[u8]; 3] = [b"Header|", b"", b"Payload of the frame"];
+    let total = parts.iter().map(|p| p.len()).sum::<usize>();
+    let slices = parts.map(IoSlice::new);
+    // The pipe is empty and much larger than the data, so the write can't be partial.
+    let written = writer.write_vectored(&slices).context("Vectored write failed")?;
+    ensure!(written == total, "only {} out of {} bytes were written", written, total);
+
+    let (mut head, mut tail) = ([0; 7], [0; 64]);
+    let mut read = reader
+        .read_vectored(&mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut tail)])
+        .context("Vectored read failed")?;
+    if cfg!(windows) && read == head.len() {
+        // There's no scatter read for pipes on Windows, so only the first buffer gets filled.
+        read += reader.read(&mut tail).context("Read failed")?;
+    }
+    ensure!(read == total, "only {} out of {} bytes were read", read, total);
+    ensure!(&head == b"Header|", "first buffer contains {:?}", head);
+    ensure!(
+        &tail[..read - head.len()] == b"Payload of the frame",
+        "second buffer contains {:?}",
+        &tail[..read - head.len()]
+    );
+    Ok(())
+}